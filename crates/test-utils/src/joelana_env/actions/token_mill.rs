@@ -5,20 +5,42 @@ use crate::{
     },
     utils::token_mill::{constants::*, curve_generator::Curve},
 };
-use anchor_lang::{prelude::AccountMeta, InstructionData};
+use anchor_lang::{
+    prelude::AccountMeta, AccountDeserialize, AnchorSerialize, Discriminator, InstructionData,
+};
 use anchor_spl::{
     associated_token::get_associated_token_address_with_program_id, token::spl_token,
     token_2022::spl_token_2022,
 };
 use anyhow::Result;
+use bytemuck::Zeroable;
 use litesvm::types::{FailedTransactionMetadata, TransactionMetadata};
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey, transaction::TransactionError};
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, rent::Rent, transaction::TransactionError,
+};
 use token_mill::{
+    constant::PRICES_LENGTH,
     errors::TokenMillError,
-    manager::swap_manager::{SwapAmountType, SwapType},
+    manager::{
+        curve_manager::PricePreset,
+        metadata_manager::{metadata_address, TOKEN_METADATA_PROGRAM_ID},
+        swap_manager::{SwapAmountType, SwapDeadline, SwapFillType, SwapOptions, SwapType},
+    },
     state::{
-        QuoteTokenBadgeStatus, MARKET_PDA_SEED, MARKET_STAKING_PDA_SEED,
-        QUOTE_TOKEN_BADGE_PDA_SEED, REFERRAL_ACCOUNT_PDA_SEED, STAKING_POSITION_PDA_SEED,
+        CreatorFeeRecipient, GraduationAdapter, LaunchFeeRecipient, LegacyMarket, Market,
+        MarketStaking, QuoteTokenBadgeStatus, RebateTier, StakePosition, StakerDiscountTier,
+        TokenMillConfig, VestingMilestone, VestingPlan, BOOST_POOL_PDA_SEED,
+        BOOST_POSITION_PDA_SEED, CHANGE_LOG_PDA_SEED, COMPOUNDING_POSITION_PDA_SEED,
+        COMPOUNDING_VAULT_PDA_SEED, CREATOR_FEE_RECIPIENTS_LENGTH, CREATOR_FEE_SPLIT_PDA_SEED,
+        DCA_POSITION_PDA_SEED, FIRM_QUOTE_ESCROW_PDA_SEED, GRADUATION_AUTHORITY_PDA_SEED,
+        GRADUATION_PDA_SEED, LOCK_CERTIFICATE_PDA_SEED, MARKET_MAKER_BADGE_PDA_SEED,
+        MARKET_ORACLE_PDA_SEED, MARKET_PDA_SEED, MARKET_STAKING_PDA_SEED, MAX_VESTING_MILESTONES,
+        MIGRATION_CLAIM_PDA_SEED, MIGRATION_PDA_SEED, PROTOCOL_STATS_PDA_SEED,
+        QUOTE_TOKEN_BADGE_PDA_SEED, REBATE_TIERS_LENGTH, REFERRAL_ACCOUNT_PDA_SEED,
+        REFERRAL_CODE_PDA_SEED, RESTRICTED_WALLET_PDA_SEED, REWARD_VAULT_PDA_SEED,
+        STAKER_DISCOUNT_TIERS_LENGTH, STAKING_BADGE_PDA_SEED, STAKING_CHECKPOINTS_PDA_SEED,
+        STAKING_POSITION_PDA_SEED, SWAP_DELEGATE_PDA_SEED, TRADER_PROFILE_PDA_SEED,
+        TRADER_STATS_PDA_SEED,
     },
 };
 
@@ -40,6 +62,7 @@ pub struct TokenMillEnv {
     pub base_token_mint: Option<Pubkey>,
     pub quote_token_mint: Option<Pubkey>,
     pub quote_token_type: TokenType,
+    pub protocol_stats: Option<Pubkey>,
 }
 
 impl Default for TokenMillEnv {
@@ -71,6 +94,7 @@ impl TokenMillEnv {
             base_token_mint: Some(make_address("base_token_mint")),
             quote_token_mint: None,
             quote_token_type: TokenType::Token,
+            protocol_stats: None,
         }
     }
 
@@ -95,6 +119,26 @@ impl TokenMillEnv {
         self
     }
 
+    /// Quotes the market in wrapped SOL. Only the referral account and the protocol fee recipient
+    /// get a wSOL ATA, actors swap with native SOL
+    pub fn with_native_quote_token_mint(mut self) -> Self {
+        let quote_token = self.svm.create_native_mint().unwrap();
+
+        for wallet in [
+            CreateReferralAccountAction::new().referral_account,
+            make_address("dave"),
+        ] {
+            self.svm
+                .create_ata(&wallet, &quote_token, TokenType::Token)
+                .unwrap();
+        }
+
+        self.quote_token_mint = Some(quote_token);
+        self.quote_token_type = TokenType::Token;
+
+        self
+    }
+
     pub fn with_default_market(self) -> Self {
         self.with_market(make_address("base_token_mint"), DEFAULT_TOTAL_SUPPLY)
     }
@@ -102,38 +146,92 @@ impl TokenMillEnv {
     pub fn with_market(mut self, base_token_mint: Pubkey, total_supply: u64) -> Self {
         self.svm.change_payer("alice");
 
-        let quote_token_mint = self.quote_token_mint.unwrap();
+        self.base_token_mint = Some(base_token_mint);
 
         let mut create_market_action = CreateMarketAction::new(&self);
         create_market_action.total_supply = total_supply;
 
-        let set_prices_action = SetMarketPricesAction::new(Curve::default());
+        if let Some(protocol_stats) = self.protocol_stats {
+            create_market_action.with_protocol_stats(protocol_stats);
+        }
+
+        let set_prices_action = SetMarketPricesAction::new(Curve::default())
+            .with_custom_base_token_mint(base_token_mint);
 
         self.svm
             .execute_actions(&[create_market_action.no_badge(), &set_prices_action])
             .unwrap();
 
-        // Create ATAs
-        for actor in ACTORS {
+        // Create ATAs, the creator's one is created with the market
+        for actor in ACTORS.iter().filter(|actor| **actor != "alice") {
             self.svm
                 .create_ata(&make_address(actor), &base_token_mint, TokenType::Token2022)
                 .unwrap();
         }
 
+        self.market = create_market_action.market;
+
         self.svm
-            .create_ata(
-                &create_market_action.market,
-                &quote_token_mint,
-                self.quote_token_type,
-            )
+            .tokens
+            .insert(base_token_mint, TokenType::Token2022);
+
+        self
+    }
+
+    /// Wrap-mode market selling an existing Token2022 token held by the creator
+    pub fn with_wrapped_market(self, total_supply: u64, initial_deposit: u64) -> Self {
+        self.with_wrapped_market_from_mint_supply(total_supply, total_supply, initial_deposit)
+    }
+
+    /// Wrap-mode market selling `total_supply` out of the `mint_supply` held by the creator
+    pub fn with_wrapped_market_from_mint_supply(
+        mut self,
+        mint_supply: u64,
+        total_supply: u64,
+        initial_deposit: u64,
+    ) -> Self {
+        let base_token_mint = self
+            .svm
+            .create_fixed_supply_token(TokenType::Token2022, 6, &make_address("alice"), mint_supply)
+            .unwrap();
+
+        self.svm.change_payer("alice");
+
+        let mut create_market_action =
+            CreateMarketWithExistingMintAction::new(&self, base_token_mint);
+        create_market_action.total_supply = total_supply;
+        create_market_action.initial_deposit = initial_deposit;
+
+        let set_prices_action = SetMarketPricesAction::new(Curve::default())
+            .with_custom_base_token_mint(base_token_mint);
+
+        self.svm
+            .execute_actions(&[create_market_action.no_badge(), &set_prices_action])
             .unwrap();
 
+        // Create ATAs, the creator already holds the supply
+        for actor in ACTORS.iter().filter(|actor| **actor != "alice") {
+            self.svm
+                .create_ata(&make_address(actor), &base_token_mint, TokenType::Token2022)
+                .unwrap();
+        }
+
         self.market = create_market_action.market;
         self.base_token_mint = Some(base_token_mint);
 
+        self
+    }
+
+    pub fn with_protocol_stats(mut self) -> Self {
+        self.svm.change_payer("admin");
+
+        let create_protocol_stats_action = CreateProtocolStatsAction::new(&self);
+
         self.svm
-            .tokens
-            .insert(base_token_mint, TokenType::Token2022);
+            .execute_actions(&[&create_protocol_stats_action])
+            .unwrap();
+
+        self.protocol_stats = Some(create_protocol_stats_action.protocol_stats);
 
         self
     }
@@ -166,6 +264,165 @@ impl TokenMillEnv {
 
         self
     }
+
+    /// Boost pool created and funded by the creator
+    pub fn with_boost_pool(mut self, amount: u64, duration: i64) -> Self {
+        self.svm.change_payer("alice");
+
+        self.svm
+            .execute_actions(&[
+                &CreateBoostPoolAction::new(&self),
+                &FundBoostPoolAction::new(&self, amount, duration),
+            ])
+            .unwrap();
+
+        self
+    }
+
+    /// Rewrites the config with the layout of the first deployment, before `upgrade_config`
+    pub fn set_legacy_config(&mut self) {
+        let mut account = self.svm.get_account(&self.config);
+        let config = TokenMillConfig::try_deserialize(&mut account.data.as_slice()).unwrap();
+
+        let mut data = TokenMillConfig::DISCRIMINATOR.to_vec();
+
+        (
+            config.authority,
+            config.pending_authority,
+            config.protocol_fee_recipient,
+            config.default_protocol_fee_share,
+            config.referral_fee_share,
+        )
+            .serialize(&mut data)
+            .unwrap();
+
+        // Space of the legacy config, the pending authority taking 33 bytes when set
+        data.resize(8 + 32 + 33 + 32 + 2 + 2, 0);
+
+        account.lamports = Rent::default().minimum_balance(data.len());
+        account.data = data;
+
+        self.svm.set_account(self.config, account);
+    }
+
+    /// Rewrites the market with the layout of the first deployment, before `upgrade_market`
+    pub fn set_legacy_market(&mut self) {
+        let mut account = self.svm.get_account(&self.market);
+        let market = Market::try_deserialize(&mut account.data.as_slice()).unwrap();
+
+        let mut legacy_market = LegacyMarket::zeroed();
+
+        legacy_market.config = market.config;
+        legacy_market.creator = market.creator;
+        legacy_market.base_token_mint = market.base_token_mint;
+        legacy_market.quote_token_mint = market.quote_token_mint;
+        legacy_market.base_reserve = market.base_reserve;
+        legacy_market
+            .bid_prices
+            .copy_from_slice(&market.bid_prices[..PRICES_LENGTH]);
+        legacy_market
+            .ask_prices
+            .copy_from_slice(&market.ask_prices[..PRICES_LENGTH]);
+        legacy_market.width_scaled = market.width_scaled;
+        legacy_market.total_supply = market.total_supply;
+        legacy_market.fees.staking_fee_share = market.fees.staking_fee_share;
+        legacy_market.fees.creator_fee_share = market.fees.creator_fee_share;
+        legacy_market.fees.pending_staking_fees = market.fees.pending_staking_fees;
+        legacy_market.fees.pending_creator_fees = market.fees.pending_creator_fees;
+        legacy_market.quote_token_decimals = market.quote_token_decimals;
+        legacy_market.bump = market.bump;
+
+        account.data.truncate(8);
+        account
+            .data
+            .extend_from_slice(bytemuck::bytes_of(&legacy_market));
+        account.lamports = Rent::default().minimum_balance(account.data.len());
+
+        self.svm.set_account(self.market, account);
+    }
+
+    /// Rewrites the market staking with the layout of the first deployment, before `upgrade_market_staking`
+    pub fn set_legacy_market_staking(&mut self) {
+        let address = market_staking_address(&self.market);
+        let mut account = self.svm.get_account(&address);
+        let staking = MarketStaking::try_deserialize(&mut account.data.as_slice()).unwrap();
+
+        let mut data = MarketStaking::DISCRIMINATOR.to_vec();
+
+        (
+            staking.market,
+            staking.amount_staked,
+            staking.total_amount_vested,
+            staking.acc_reward_amount_per_share,
+        )
+            .serialize(&mut data)
+            .unwrap();
+
+        account.lamports = Rent::default().minimum_balance(data.len());
+        account.data = data;
+
+        self.svm.set_account(address, account);
+    }
+
+    /// Rewrites the stake position of `user` with the layout of the first deployment, before `upgrade_stake_position`
+    pub fn set_legacy_stake_position(&mut self, user: &Pubkey) {
+        let address = stake_position_address(&self.market, user);
+        let mut account = self.svm.get_account(&address);
+        let stake_position = StakePosition::try_deserialize(&mut account.data.as_slice()).unwrap();
+
+        let mut data = StakePosition::DISCRIMINATOR.to_vec();
+
+        (
+            stake_position.market,
+            stake_position.user,
+            stake_position.amount_staked,
+            stake_position.total_amount_vested,
+            stake_position.pending_rewards,
+            stake_position.acc_reward_amount_per_share,
+        )
+            .serialize(&mut data)
+            .unwrap();
+
+        account.lamports = Rent::default().minimum_balance(data.len());
+        account.data = data;
+
+        self.svm.set_account(address, account);
+
+        // The first deployment didn't escrow the positions in their market
+        let mut market_account = self.svm.get_account(&self.market);
+        let market_data = &mut market_account.data[8..8 + std::mem::size_of::<Market>()];
+        let mut market: Market = bytemuck::pod_read_unaligned(market_data);
+
+        market.escrowed_base -= stake_position.amount_staked + stake_position.total_amount_vested;
+        market.escrowed_quote -= stake_position.pending_rewards;
+        market_data.copy_from_slice(bytemuck::bytes_of(&market));
+
+        self.svm.set_account(self.market, market_account);
+    }
+
+    /// Rewrites a vesting plan with the layout of the first deployment, without funder, revocability nor milestones
+    pub fn set_legacy_vesting_plan(&mut self, address: &Pubkey) {
+        let mut account = self.svm.get_account(address);
+        let vesting_plan = VestingPlan::try_deserialize(&mut account.data.as_slice()).unwrap();
+
+        let mut data = VestingPlan::DISCRIMINATOR.to_vec();
+
+        (
+            vesting_plan.stake_position,
+            vesting_plan.amount_vested,
+            vesting_plan.amount_released,
+            vesting_plan.start,
+            vesting_plan.cliff_duration,
+            vesting_plan.vesting_duration,
+        )
+            .serialize(&mut data)
+            .unwrap();
+
+        account.lamports = Rent::default().minimum_balance(data.len());
+        account.data = data;
+
+        self.svm.set_account(*address, account);
+    }
 }
 
 fn tm_event_authority() -> Pubkey {
@@ -264,7 +521,10 @@ impl TransferConfigOwnershipAction {
 
 impl InstructionGenerator for TransferConfigOwnershipAction {
     fn accounts(&self) -> Vec<AccountMeta> {
-        let mut accounts = vec![AccountMeta::new(self.config, false)];
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
 
         accounts
             .append_payer(self.signer)
@@ -286,24 +546,30 @@ impl InstructionGenerator for TransferConfigOwnershipAction {
     }
 }
 
-pub struct AcceptConfigOwnershipAction {
+pub struct SetEmergencyAuthorityAction {
     // Accounts
     pub config: Pubkey,
     pub signer: Pubkey,
+    // Args
+    pub emergency_authority: Pubkey,
 }
 
-impl AcceptConfigOwnershipAction {
-    pub fn new(signer: Pubkey) -> Self {
+impl SetEmergencyAuthorityAction {
+    pub fn new(emergency_authority: Pubkey) -> Self {
         Self {
             config: make_address("config"),
-            signer,
+            signer: make_address("admin"),
+            emergency_authority,
         }
     }
 }
 
-impl InstructionGenerator for AcceptConfigOwnershipAction {
+impl InstructionGenerator for SetEmergencyAuthorityAction {
     fn accounts(&self) -> Vec<AccountMeta> {
-        let mut accounts = vec![AccountMeta::new(self.config, false)];
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
 
         accounts
             .append_payer(self.signer)
@@ -313,7 +579,9 @@ impl InstructionGenerator for AcceptConfigOwnershipAction {
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::AcceptConfigOwnership {};
+        let input = token_mill::instruction::SetEmergencyAuthority {
+            emergency_authority: self.emergency_authority,
+        };
 
         Instruction {
             program_id: token_mill::ID,
@@ -323,30 +591,34 @@ impl InstructionGenerator for AcceptConfigOwnershipAction {
     }
 }
 
-#[derive(Debug)]
-pub struct UpdateDefaultFeeSharesAction {
+pub struct PauseSwapsAction {
     // Accounts
     pub config: Pubkey,
     pub signer: Pubkey,
-    // Args
-    pub new_default_protocol_fee_share: u16,
-    pub new_referral_fee_share: u16,
 }
 
-impl UpdateDefaultFeeSharesAction {
-    pub fn new(new_default_protocol_fee_share: u16, new_referral_fee_share: u16) -> Self {
+impl Default for PauseSwapsAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PauseSwapsAction {
+    /// Pause signed by the authority, see `signer` for the emergency authority
+    pub fn new() -> Self {
         Self {
             config: make_address("config"),
             signer: make_address("admin"),
-            new_default_protocol_fee_share,
-            new_referral_fee_share,
         }
     }
 }
 
-impl InstructionGenerator for UpdateDefaultFeeSharesAction {
+impl InstructionGenerator for PauseSwapsAction {
     fn accounts(&self) -> Vec<AccountMeta> {
-        let mut accounts = vec![AccountMeta::new(self.config, false)];
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
 
         accounts
             .append_payer(self.signer)
@@ -356,10 +628,7 @@ impl InstructionGenerator for UpdateDefaultFeeSharesAction {
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::UpdateDefaultFeeShares {
-            new_default_protocol_fee_share: self.new_default_protocol_fee_share,
-            new_referral_fee_share: self.new_referral_fee_share,
-        };
+        let input = token_mill::instruction::PauseSwaps {};
 
         Instruction {
             program_id: token_mill::ID,
@@ -369,28 +638,33 @@ impl InstructionGenerator for UpdateDefaultFeeSharesAction {
     }
 }
 
-#[derive(Debug)]
-pub struct UpdateProtocolFeeRecipientAction {
+pub struct UnpauseSwapsAction {
     // Accounts
     pub config: Pubkey,
     pub signer: Pubkey,
-    // Args
-    pub new_protocol_fee_recipient: Pubkey,
 }
 
-impl UpdateProtocolFeeRecipientAction {
-    pub fn new(new_protocol_fee_recipient: Pubkey) -> Self {
+impl Default for UnpauseSwapsAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UnpauseSwapsAction {
+    pub fn new() -> Self {
         Self {
             config: make_address("config"),
             signer: make_address("admin"),
-            new_protocol_fee_recipient,
         }
     }
 }
 
-impl InstructionGenerator for UpdateProtocolFeeRecipientAction {
+impl InstructionGenerator for UnpauseSwapsAction {
     fn accounts(&self) -> Vec<AccountMeta> {
-        let mut accounts = vec![AccountMeta::new(self.config, false)];
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
 
         accounts
             .append_payer(self.signer)
@@ -400,9 +674,7 @@ impl InstructionGenerator for UpdateProtocolFeeRecipientAction {
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::UpdateProtocolFeeRecipient {
-            new_protocol_fee_recipient: self.new_protocol_fee_recipient,
-        };
+        let input = token_mill::instruction::UnpauseSwaps {};
 
         Instruction {
             program_id: token_mill::ID,
@@ -412,55 +684,34 @@ impl InstructionGenerator for UpdateProtocolFeeRecipientAction {
     }
 }
 
-pub struct CreateQuoteAssetBadgeAction {
+pub struct AcceptConfigOwnershipAction {
     // Accounts
     pub config: Pubkey,
-    pub quote_asset_badge: Pubkey,
-    pub token_mint: Pubkey,
     pub signer: Pubkey,
 }
 
-impl CreateQuoteAssetBadgeAction {
-    pub fn new(token_mint: Pubkey) -> Self {
-        let config = make_address("config");
-
-        let quote_asset_badge = Pubkey::find_program_address(
-            &[
-                QUOTE_TOKEN_BADGE_PDA_SEED.as_bytes(),
-                &config.to_bytes(),
-                &token_mint.to_bytes(),
-            ],
-            &token_mill::ID,
-        )
-        .0;
-
+impl AcceptConfigOwnershipAction {
+    pub fn new(signer: Pubkey) -> Self {
         Self {
-            config,
-            quote_asset_badge,
-            token_mint,
-            signer: make_address("admin"),
+            config: make_address("config"),
+            signer,
         }
     }
 }
 
-impl InstructionGenerator for CreateQuoteAssetBadgeAction {
+impl InstructionGenerator for AcceptConfigOwnershipAction {
     fn accounts(&self) -> Vec<AccountMeta> {
-        let mut accounts = vec![
-            AccountMeta::new_readonly(self.config, false),
-            AccountMeta::new(self.quote_asset_badge, false),
-            AccountMeta::new_readonly(self.token_mint, false),
-        ];
+        let mut accounts = vec![AccountMeta::new(self.config, false)];
 
         accounts
             .append_payer(self.signer)
-            .append_system_program()
             .append_cpi_event_accounts(tm_event_authority());
 
         accounts
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::CreateQuoteAssetBadge {};
+        let input = token_mill::instruction::AcceptConfigOwnership {};
 
         Instruction {
             program_id: token_mill::ID,
@@ -470,45 +721,32 @@ impl InstructionGenerator for CreateQuoteAssetBadgeAction {
     }
 }
 
-pub struct UpdateQuoteAssetBadgeAction {
+#[derive(Debug)]
+pub struct UpdateDefaultFeeSharesAction {
     // Accounts
     pub config: Pubkey,
-    pub quote_asset_badge: Pubkey,
-    pub token_mint: Pubkey,
     pub signer: Pubkey,
     // Args
-    pub status: QuoteTokenBadgeStatus,
+    pub new_default_protocol_fee_share: u16,
+    pub new_referral_fee_share: u16,
 }
 
-impl UpdateQuoteAssetBadgeAction {
-    pub fn new(token_mint: Pubkey, status: QuoteTokenBadgeStatus) -> Self {
-        let config = make_address("config");
-        let quote_asset_badge = Pubkey::find_program_address(
-            &[
-                QUOTE_TOKEN_BADGE_PDA_SEED.as_bytes(),
-                &config.to_bytes(),
-                &token_mint.to_bytes(),
-            ],
-            &token_mill::ID,
-        )
-        .0;
-
+impl UpdateDefaultFeeSharesAction {
+    pub fn new(new_default_protocol_fee_share: u16, new_referral_fee_share: u16) -> Self {
         Self {
-            config,
-            quote_asset_badge,
-            token_mint,
+            config: make_address("config"),
             signer: make_address("admin"),
-            status,
+            new_default_protocol_fee_share,
+            new_referral_fee_share,
         }
     }
 }
 
-impl InstructionGenerator for UpdateQuoteAssetBadgeAction {
+impl InstructionGenerator for UpdateDefaultFeeSharesAction {
     fn accounts(&self) -> Vec<AccountMeta> {
         let mut accounts = vec![
-            AccountMeta::new_readonly(self.config, false),
-            AccountMeta::new(self.quote_asset_badge, false),
-            AccountMeta::new_readonly(self.token_mint, false),
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
         ];
 
         accounts
@@ -519,8 +757,9 @@ impl InstructionGenerator for UpdateQuoteAssetBadgeAction {
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::UpdateQuoteAssetBadge {
-            status: self.status,
+        let input = token_mill::instruction::UpdateDefaultFeeShares {
+            new_default_protocol_fee_share: self.new_default_protocol_fee_share,
+            new_referral_fee_share: self.new_referral_fee_share,
         };
 
         Instruction {
@@ -531,97 +770,64 @@ impl InstructionGenerator for UpdateQuoteAssetBadgeAction {
     }
 }
 
-pub struct CreateMarketAction {
+pub struct SetQuoteFeeOverrideAction {
     // Accounts
     pub config: Pubkey,
-    pub market: Pubkey,
-    pub base_token_mint: Pubkey,
-    pub market_base_token_ata: Pubkey,
-    pub quote_token_badge: Pubkey,
-    pub quote_token_mint: Pubkey,
     pub signer: Pubkey,
     // Args
-    pub total_supply: u64,
+    pub quote_token_mint: Pubkey,
+    pub protocol_fee_share: u16,
+    pub referral_fee_share: u16,
+    pub creator_fee_share: u16,
+    pub staking_fee_share: u16,
 }
 
-impl CreateMarketAction {
-    pub fn new(testing_env: &TokenMillEnv) -> Self {
-        let config = make_address("config");
-
-        let base_token_mint = testing_env.base_token_mint.unwrap();
+impl SetQuoteFeeOverrideAction {
+    pub fn new(quote_token_mint: Pubkey, protocol_fee_share: u16, referral_fee_share: u16) -> Self {
+        Self {
+            config: make_address("config"),
+            signer: make_address("admin"),
+            quote_token_mint,
+            protocol_fee_share,
+            referral_fee_share,
+            creator_fee_share: 0,
+            staking_fee_share: 0,
+        }
+    }
 
-        let market = Pubkey::find_program_address(
-            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
-            &token_mill::ID,
-        )
-        .0;
+    pub fn with_market_fee_shares(
+        &mut self,
+        creator_fee_share: u16,
+        staking_fee_share: u16,
+    ) -> &mut Self {
+        self.creator_fee_share = creator_fee_share;
+        self.staking_fee_share = staking_fee_share;
 
-        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        self
+    }
+}
 
-        let quote_asset_badge = Pubkey::find_program_address(
-            &[
-                QUOTE_TOKEN_BADGE_PDA_SEED.as_bytes(),
-                &config.to_bytes(),
-                &quote_token_mint.to_bytes(),
-            ],
-            &token_mill::ID,
-        )
-        .0;
-
-        let market_base_token_ata = get_associated_token_address_with_program_id(
-            &market,
-            &base_token_mint,
-            &spl_token_2022::id(),
-        );
-
-        Self {
-            config,
-            market,
-            base_token_mint,
-            market_base_token_ata,
-            quote_token_mint,
-            quote_token_badge: quote_asset_badge,
-            signer: make_address("alice"),
-            total_supply: DEFAULT_TOTAL_SUPPLY,
-        }
-    }
-
-    pub fn no_badge(&mut self) -> &mut Self {
-        self.quote_token_badge = token_mill::ID;
-
-        self
-    }
-}
-
-impl InstructionGenerator for CreateMarketAction {
+impl InstructionGenerator for SetQuoteFeeOverrideAction {
     fn accounts(&self) -> Vec<AccountMeta> {
         let mut accounts = vec![
-            AccountMeta::new_readonly(self.config, false),
-            AccountMeta::new(self.market, false),
-            AccountMeta::new(self.base_token_mint, true),
-            AccountMeta::new(self.market_base_token_ata, false),
-            AccountMeta::new_readonly(self.quote_token_badge, false),
-            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
         ];
 
         accounts
             .append_payer(self.signer)
-            .append_system_program()
-            .append_token_2022_program()
-            .append_associated_token_program()
             .append_cpi_event_accounts(tm_event_authority());
 
         accounts
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::CreateMarket {
-            name: "name".to_string(),
-            symbol: "symbol".to_string(),
-            uri: "uri".to_string(),
-            total_supply: self.total_supply,
-            creator_fee_share: DEFAULT_CREATOR_FEE_SHARE,
-            staking_fee_share: DEFAULT_STAKING_FEE_SHARE,
+        let input = token_mill::instruction::SetQuoteFeeOverride {
+            quote_token_mint: self.quote_token_mint,
+            protocol_fee_share: self.protocol_fee_share,
+            referral_fee_share: self.referral_fee_share,
+            creator_fee_share: self.creator_fee_share,
+            staking_fee_share: self.staking_fee_share,
         };
 
         Instruction {
@@ -632,46 +838,32 @@ impl InstructionGenerator for CreateMarketAction {
     }
 }
 
-pub struct SetMarketPricesAction {
+pub struct SetRebateScheduleAction {
     // Accounts
-    pub market: Pubkey,
+    pub config: Pubkey,
     pub signer: Pubkey,
     // Args
-    pub price_curve: Curve,
+    pub quote_token_mint: Pubkey,
+    pub tiers: [RebateTier; REBATE_TIERS_LENGTH],
 }
 
-impl SetMarketPricesAction {
-    pub fn new(curve: Curve) -> Self {
-        let market = Pubkey::find_program_address(
-            &[
-                MARKET_PDA_SEED.as_bytes(),
-                &make_address("base_token_mint").to_bytes(),
-            ],
-            &token_mill::ID,
-        )
-        .0;
-
+impl SetRebateScheduleAction {
+    pub fn new(quote_token_mint: Pubkey, tiers: [RebateTier; REBATE_TIERS_LENGTH]) -> Self {
         Self {
-            market,
-            signer: make_address("alice"),
-            price_curve: curve,
+            config: make_address("config"),
+            signer: make_address("admin"),
+            quote_token_mint,
+            tiers,
         }
     }
-
-    pub fn with_custom_base_token_mint(mut self, base_token_mint: Pubkey) -> Self {
-        self.market = Pubkey::find_program_address(
-            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
-            &token_mill::ID,
-        )
-        .0;
-
-        self
-    }
 }
 
-impl InstructionGenerator for SetMarketPricesAction {
+impl InstructionGenerator for SetRebateScheduleAction {
     fn accounts(&self) -> Vec<AccountMeta> {
-        let mut accounts = vec![AccountMeta::new(self.market, false)];
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
 
         accounts
             .append_payer(self.signer)
@@ -681,14 +873,9 @@ impl InstructionGenerator for SetMarketPricesAction {
     }
 
     fn instruction(&self) -> Instruction {
-        let Curve {
-            bid_prices,
-            ask_prices,
-        } = self.price_curve;
-
-        let input = token_mill::instruction::SetMarketPrices {
-            bid_prices,
-            ask_prices,
+        let input = token_mill::instruction::SetRebateSchedule {
+            quote_token_mint: self.quote_token_mint,
+            tiers: self.tiers,
         };
 
         Instruction {
@@ -699,161 +886,47 @@ impl InstructionGenerator for SetMarketPricesAction {
     }
 }
 
-pub struct SwapAction {
+pub struct SetStakerDiscountsAction {
     // Accounts
     pub config: Pubkey,
-    pub market: Pubkey,
-    pub base_token_mint: Pubkey,
-    pub quote_token_mint: Pubkey,
-    pub market_base_token_ata: Pubkey,
-    pub market_quote_token_ata: Pubkey,
-    pub user_base_token_ata: Pubkey,
-    pub user_quote_token_ata: Pubkey,
-    pub protocol_quote_token_ata: Pubkey,
-    pub referral_quote_token_ata: Pubkey,
     pub signer: Pubkey,
-    pub quote_token_program: Pubkey,
     // Args
-    pub swap_type: SwapType,
-    pub swap_amount_type: SwapAmountType,
-    pub amount: u64,
-    pub other_amount_threshold: u64,
+    pub protocol_token_market: Pubkey,
+    pub tiers: [StakerDiscountTier; STAKER_DISCOUNT_TIERS_LENGTH],
 }
 
-impl SwapAction {
+impl SetStakerDiscountsAction {
     pub fn new(
-        token_mill_env: &TokenMillEnv,
-        swap_type: SwapType,
-        swap_amount_type: SwapAmountType,
-        amount: u64,
-        other_amount_threshold: u64,
-        referrer: Option<Pubkey>,
+        protocol_token_market: Pubkey,
+        tiers: [StakerDiscountTier; STAKER_DISCOUNT_TIERS_LENGTH],
     ) -> Self {
-        let config = make_address("config");
-        let base_token_mint = token_mill_env.base_token_mint.unwrap();
-
-        let signer = make_address("bob");
-
-        let market = Pubkey::find_program_address(
-            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
-            &token_mill::ID,
-        )
-        .0;
-
-        let quote_token_mint = token_mill_env.quote_token_mint.unwrap();
-        let quote_token_program = token_mill_env.quote_token_type.program_address();
-
-        let market_base_token_ata = get_associated_token_address_with_program_id(
-            &market,
-            &base_token_mint,
-            &spl_token_2022::id(),
-        );
-
-        let market_quote_token_ata = get_associated_token_address_with_program_id(
-            &market,
-            &quote_token_mint,
-            &quote_token_program,
-        );
-
-        let user_base_token_ata = get_associated_token_address_with_program_id(
-            &signer,
-            &base_token_mint,
-            &spl_token_2022::id(),
-        );
-
-        let user_quote_token_ata = get_associated_token_address_with_program_id(
-            &signer,
-            &quote_token_mint,
-            &quote_token_program,
-        );
-
-        let protocol_quote_token_ata = get_associated_token_address_with_program_id(
-            &make_address("dave"),
-            &quote_token_mint,
-            &quote_token_program,
-        );
-
-        let referral_account = if let Some(referrer) = referrer {
-            Pubkey::find_program_address(
-                &[
-                    REFERRAL_ACCOUNT_PDA_SEED.as_bytes(),
-                    &config.to_bytes(),
-                    &referrer.to_bytes(),
-                ],
-                &token_mill::ID,
-            )
-            .0
-        } else {
-            Pubkey::new_unique()
-        };
-
-        let referral_quote_token_ata = if referrer.is_some() {
-            get_associated_token_address_with_program_id(
-                &referral_account,
-                &quote_token_mint,
-                &quote_token_program,
-            )
-        } else {
-            token_mill::ID
-        };
-
         Self {
-            config,
-            market,
-            base_token_mint,
-            quote_token_mint,
-            market_base_token_ata,
-            market_quote_token_ata,
-            user_base_token_ata,
-            user_quote_token_ata,
-            protocol_quote_token_ata,
-            referral_quote_token_ata,
-            signer,
-            quote_token_program,
-            swap_type,
-            swap_amount_type,
-            amount,
-            other_amount_threshold,
+            config: make_address("config"),
+            signer: make_address("admin"),
+            protocol_token_market,
+            tiers,
         }
     }
 }
 
-impl InstructionGenerator for SwapAction {
+impl InstructionGenerator for SetStakerDiscountsAction {
     fn accounts(&self) -> Vec<AccountMeta> {
         let mut accounts = vec![
-            AccountMeta::new_readonly(self.config, false),
-            AccountMeta::new(self.market, false),
-            AccountMeta::new_readonly(self.base_token_mint, false),
-            AccountMeta::new_readonly(self.quote_token_mint, false),
-            AccountMeta::new(self.market_base_token_ata, false),
-            AccountMeta::new(self.market_quote_token_ata, false),
-            AccountMeta::new(self.user_base_token_ata, false),
-            AccountMeta::new(self.user_quote_token_ata, false),
-            AccountMeta::new(self.protocol_quote_token_ata, false),
-            AccountMeta::new(self.referral_quote_token_ata, false),
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
         ];
 
         accounts
             .append_payer(self.signer)
-            .append_token_2022_program();
-
-        match self.quote_token_program {
-            spl_token::ID => accounts.append_token_program(),
-            spl_token_2022::ID => accounts.append_token_2022_program(),
-            _ => unreachable!(),
-        };
-
-        accounts.append_cpi_event_accounts(tm_event_authority());
+            .append_cpi_event_accounts(tm_event_authority());
 
         accounts
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::Swap {
-            swap_type: self.swap_type,
-            swap_amount_type: self.swap_amount_type,
-            amount: self.amount,
-            other_amount_threshold: self.other_amount_threshold,
+        let input = token_mill::instruction::SetStakerDiscounts {
+            protocol_token_market: self.protocol_token_market,
+            tiers: self.tiers,
         };
 
         Instruction {
@@ -864,36 +937,32 @@ impl InstructionGenerator for SwapAction {
     }
 }
 
-pub struct UpdateCreatorAction {
+pub struct SetFeeExemptionAction {
     // Accounts
-    pub market: Pubkey,
+    pub config: Pubkey,
     pub signer: Pubkey,
     // Args
-    pub new_creator: Pubkey,
+    pub wallet: Pubkey,
+    pub fee_discount_bps: u16,
 }
 
-impl UpdateCreatorAction {
-    pub fn new(new_creator: Pubkey) -> Self {
-        let market = Pubkey::find_program_address(
-            &[
-                MARKET_PDA_SEED.as_bytes(),
-                &make_address("base_token_mint").to_bytes(),
-            ],
-            &token_mill::ID,
-        )
-        .0;
-
+impl SetFeeExemptionAction {
+    pub fn new(wallet: Pubkey, fee_discount_bps: u16) -> Self {
         Self {
-            market,
-            signer: make_address("alice"),
-            new_creator,
+            config: make_address("config"),
+            signer: make_address("admin"),
+            wallet,
+            fee_discount_bps,
         }
     }
 }
 
-impl InstructionGenerator for UpdateCreatorAction {
+impl InstructionGenerator for SetFeeExemptionAction {
     fn accounts(&self) -> Vec<AccountMeta> {
-        let mut accounts = vec![AccountMeta::new(self.market, false)];
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
 
         accounts
             .append_payer(self.signer)
@@ -903,8 +972,9 @@ impl InstructionGenerator for UpdateCreatorAction {
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::UpdateCreator {
-            new_creator: self.new_creator,
+        let input = token_mill::instruction::SetFeeExemption {
+            wallet: self.wallet,
+            fee_discount_bps: self.fee_discount_bps,
         };
 
         Instruction {
@@ -915,51 +985,8362 @@ impl InstructionGenerator for UpdateCreatorAction {
     }
 }
 
-pub struct UpdateMarketFeeSharesAction {
+pub struct RemoveQuoteFeeOverrideAction {
     // Accounts
-    pub market: Pubkey,
+    pub config: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub quote_token_mint: Pubkey,
+}
+
+impl RemoveQuoteFeeOverrideAction {
+    pub fn new(quote_token_mint: Pubkey) -> Self {
+        Self {
+            config: make_address("config"),
+            signer: make_address("admin"),
+            quote_token_mint,
+        }
+    }
+}
+
+impl InstructionGenerator for RemoveQuoteFeeOverrideAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::RemoveQuoteFeeOverride {
+            quote_token_mint: self.quote_token_mint,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UpdateMaxInterfaceFeeAction {
+    // Accounts
+    pub config: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub new_max_interface_fee_bps: u16,
+}
+
+impl UpdateMaxInterfaceFeeAction {
+    pub fn new(new_max_interface_fee_bps: u16) -> Self {
+        Self {
+            config: make_address("config"),
+            signer: make_address("admin"),
+            new_max_interface_fee_bps,
+        }
+    }
+}
+
+impl InstructionGenerator for UpdateMaxInterfaceFeeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpdateMaxInterfaceFee {
+            new_max_interface_fee_bps: self.new_max_interface_fee_bps,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UpdateMaxSpreadAction {
+    // Accounts
+    pub config: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub new_max_spread_bps: u16,
+}
+
+impl UpdateMaxSpreadAction {
+    pub fn new(new_max_spread_bps: u16) -> Self {
+        Self {
+            config: make_address("config"),
+            signer: make_address("admin"),
+            new_max_spread_bps,
+        }
+    }
+}
+
+impl InstructionGenerator for UpdateMaxSpreadAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpdateMaxSpread {
+            new_max_spread_bps: self.new_max_spread_bps,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SetGraduationConfigAction {
+    // Accounts
+    pub config: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub graduation_adapter: GraduationAdapter,
+    pub graduation_program: Pubkey,
+    pub graduation_pool_config: Pubkey,
+}
+
+impl SetGraduationConfigAction {
+    pub fn new(
+        graduation_adapter: GraduationAdapter,
+        graduation_program: Pubkey,
+        graduation_pool_config: Pubkey,
+    ) -> Self {
+        Self {
+            config: make_address("config"),
+            signer: make_address("admin"),
+            graduation_adapter,
+            graduation_program,
+            graduation_pool_config,
+        }
+    }
+}
+
+impl InstructionGenerator for SetGraduationConfigAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetGraduationConfig {
+            graduation_adapter: self.graduation_adapter,
+            graduation_program: self.graduation_program,
+            graduation_pool_config: self.graduation_pool_config,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UpdateEmergencyUnstakePenaltyAction {
+    // Accounts
+    pub config: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub new_emergency_unstake_penalty_bps: u16,
+}
+
+impl UpdateEmergencyUnstakePenaltyAction {
+    pub fn new(new_emergency_unstake_penalty_bps: u16) -> Self {
+        Self {
+            config: make_address("config"),
+            signer: make_address("admin"),
+            new_emergency_unstake_penalty_bps,
+        }
+    }
+}
+
+impl InstructionGenerator for UpdateEmergencyUnstakePenaltyAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpdateEmergencyUnstakePenalty {
+            new_emergency_unstake_penalty_bps: self.new_emergency_unstake_penalty_bps,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UpdateFeeDistributionIntervalAction {
+    // Accounts
+    pub config: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub new_fee_distribution_interval: u16,
+}
+
+impl UpdateFeeDistributionIntervalAction {
+    pub fn new(new_fee_distribution_interval: u16) -> Self {
+        Self {
+            config: make_address("config"),
+            signer: make_address("admin"),
+            new_fee_distribution_interval,
+        }
+    }
+}
+
+impl InstructionGenerator for UpdateFeeDistributionIntervalAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpdateFeeDistributionInterval {
+            new_fee_distribution_interval: self.new_fee_distribution_interval,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UpdateFeeSharesUpdateDelayAction {
+    // Accounts
+    pub config: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub new_fee_shares_update_delay: u32,
+}
+
+impl UpdateFeeSharesUpdateDelayAction {
+    pub fn new(new_fee_shares_update_delay: u32) -> Self {
+        Self {
+            config: make_address("config"),
+            signer: make_address("admin"),
+            new_fee_shares_update_delay,
+        }
+    }
+}
+
+impl InstructionGenerator for UpdateFeeSharesUpdateDelayAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpdateFeeSharesUpdateDelay {
+            new_fee_shares_update_delay: self.new_fee_shares_update_delay,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct UpdateMarketCloseDelayAction {
+    // Accounts
+    pub config: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub new_market_close_delay: u32,
+}
+
+impl UpdateMarketCloseDelayAction {
+    pub fn new(new_market_close_delay: u32) -> Self {
+        Self {
+            config: make_address("config"),
+            signer: make_address("admin"),
+            new_market_close_delay,
+        }
+    }
+}
+
+impl InstructionGenerator for UpdateMarketCloseDelayAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpdateMarketCloseDelay {
+            new_market_close_delay: self.new_market_close_delay,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct UpdateMinMarketAgeAction {
+    // Accounts
+    pub config: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub new_min_market_age: u32,
+}
+
+impl UpdateMinMarketAgeAction {
+    pub fn new(new_min_market_age: u32) -> Self {
+        Self {
+            config: make_address("config"),
+            signer: make_address("admin"),
+            new_min_market_age,
+        }
+    }
+}
+
+impl InstructionGenerator for UpdateMinMarketAgeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpdateMinMarketAge {
+            new_min_market_age: self.new_min_market_age,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct UpgradeConfigAction {
+    // Accounts
+    pub config: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl Default for UpgradeConfigAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UpgradeConfigAction {
+    pub fn new() -> Self {
+        Self {
+            config: make_address("config"),
+            signer: make_address("admin"),
+        }
+    }
+}
+
+impl InstructionGenerator for UpgradeConfigAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![AccountMeta::new(self.config, false)];
+
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpgradeConfig {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct UpgradeMarketAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl UpgradeMarketAction {
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        Self {
+            config: token_mill_env.config,
+            market: token_mill_env.market,
+            market_quote_token_ata: get_associated_token_address_with_program_id(
+                &token_mill_env.market,
+                &token_mill_env.quote_token_mint.unwrap(),
+                &token_mill_env.quote_token_type.program_address(),
+            ),
+            signer: make_address("admin"),
+        }
+    }
+}
+
+impl InstructionGenerator for UpgradeMarketAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new_readonly(self.market_quote_token_ata, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpgradeMarket {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UpdateFirmQuoteFeeAction {
+    // Accounts
+    pub config: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub new_firm_quote_fee_bps: u16,
+}
+
+impl UpdateFirmQuoteFeeAction {
+    pub fn new(new_firm_quote_fee_bps: u16) -> Self {
+        Self {
+            config: make_address("config"),
+            signer: make_address("admin"),
+            new_firm_quote_fee_bps,
+        }
+    }
+}
+
+impl InstructionGenerator for UpdateFirmQuoteFeeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpdateFirmQuoteFee {
+            new_firm_quote_fee_bps: self.new_firm_quote_fee_bps,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CreateMarketMakerBadgeAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market_maker_badge: Pubkey,
+    pub market_maker: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl CreateMarketMakerBadgeAction {
+    pub fn new(market_maker: Pubkey) -> Self {
+        let config = make_address("config");
+
+        Self {
+            config,
+            market_maker_badge: market_maker_badge_address(&config, &market_maker),
+            market_maker,
+            signer: make_address("admin"),
+        }
+    }
+}
+
+impl InstructionGenerator for CreateMarketMakerBadgeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market_maker_badge, false),
+            AccountMeta::new_readonly(self.market_maker, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateMarketMakerBadge {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct RevokeMarketMakerBadgeAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market_maker_badge: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl RevokeMarketMakerBadgeAction {
+    pub fn new(market_maker: Pubkey) -> Self {
+        let config = make_address("config");
+
+        Self {
+            config,
+            market_maker_badge: market_maker_badge_address(&config, &market_maker),
+            signer: make_address("admin"),
+        }
+    }
+}
+
+impl InstructionGenerator for RevokeMarketMakerBadgeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market_maker_badge, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::RevokeMarketMakerBadge {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+fn market_maker_badge_address(config: &Pubkey, market_maker: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            MARKET_MAKER_BADGE_PDA_SEED.as_bytes(),
+            &config.to_bytes(),
+            &market_maker.to_bytes(),
+        ],
+        &token_mill::ID,
+    )
+    .0
+}
+
+#[derive(Debug)]
+pub struct ProposeFeeRecipientAction {
+    // Accounts
+    pub config: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub pending_protocol_fee_recipient: Option<Pubkey>,
+}
+
+impl ProposeFeeRecipientAction {
+    pub fn new(pending_protocol_fee_recipient: Option<Pubkey>) -> Self {
+        Self {
+            config: make_address("config"),
+            signer: make_address("admin"),
+            pending_protocol_fee_recipient,
+        }
+    }
+}
+
+impl InstructionGenerator for ProposeFeeRecipientAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::ProposeFeeRecipient {
+            pending_protocol_fee_recipient: self.pending_protocol_fee_recipient,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AcceptFeeRecipientAction {
+    // Accounts
+    pub config: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl AcceptFeeRecipientAction {
+    pub fn new(signer: Pubkey) -> Self {
+        Self {
+            config: make_address("config"),
+            signer,
+        }
+    }
+}
+
+impl InstructionGenerator for AcceptFeeRecipientAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.config, false),
+            AccountMeta::new(change_log_address(&self.config), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::AcceptFeeRecipient {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CreateQuoteAssetBadgeAction {
+    // Accounts
+    pub config: Pubkey,
+    pub quote_asset_badge: Pubkey,
+    pub token_mint: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl CreateQuoteAssetBadgeAction {
+    pub fn new(token_mint: Pubkey) -> Self {
+        let config = make_address("config");
+
+        let quote_asset_badge = Pubkey::find_program_address(
+            &[
+                QUOTE_TOKEN_BADGE_PDA_SEED.as_bytes(),
+                &config.to_bytes(),
+                &token_mint.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            config,
+            quote_asset_badge,
+            token_mint,
+            signer: make_address("admin"),
+        }
+    }
+}
+
+impl InstructionGenerator for CreateQuoteAssetBadgeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.quote_asset_badge, false),
+            AccountMeta::new_readonly(self.token_mint, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateQuoteAssetBadge {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct UpdateQuoteAssetBadgeAction {
+    // Accounts
+    pub config: Pubkey,
+    pub quote_asset_badge: Pubkey,
+    pub token_mint: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub status: QuoteTokenBadgeStatus,
+}
+
+impl UpdateQuoteAssetBadgeAction {
+    pub fn new(token_mint: Pubkey, status: QuoteTokenBadgeStatus) -> Self {
+        let config = make_address("config");
+        let quote_asset_badge = Pubkey::find_program_address(
+            &[
+                QUOTE_TOKEN_BADGE_PDA_SEED.as_bytes(),
+                &config.to_bytes(),
+                &token_mint.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            config,
+            quote_asset_badge,
+            token_mint,
+            signer: make_address("admin"),
+            status,
+        }
+    }
+}
+
+impl InstructionGenerator for UpdateQuoteAssetBadgeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.quote_asset_badge, false),
+            AccountMeta::new_readonly(self.token_mint, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpdateQuoteAssetBadge {
+            status: self.status,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CreateMarketAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub quote_token_badge: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub protocol_stats: Pubkey,
+    pub signer: Pubkey,
+    pub launchpad: Pubkey,
+    pub metaplex_metadata: Pubkey,
+    pub token_metadata_program: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Args
+    pub total_supply: u64,
+    pub creator_fee_share: u16,
+    pub staking_fee_share: u16,
+}
+
+impl CreateMarketAction {
+    pub fn new(testing_env: &TokenMillEnv) -> Self {
+        let config = make_address("config");
+
+        let base_token_mint = testing_env.base_token_mint.unwrap();
+
+        let market = Pubkey::find_program_address(
+            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let quote_token_program = testing_env.quote_token_type.program_address();
+
+        let quote_asset_badge = Pubkey::find_program_address(
+            &[
+                QUOTE_TOKEN_BADGE_PDA_SEED.as_bytes(),
+                &config.to_bytes(),
+                &quote_token_mint.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        let market_base_token_ata = get_associated_token_address_with_program_id(
+            &market,
+            &base_token_mint,
+            &spl_token_2022::id(),
+        );
+
+        let market_quote_token_ata = get_associated_token_address_with_program_id(
+            &market,
+            &quote_token_mint,
+            &quote_token_program,
+        );
+
+        Self {
+            config,
+            market,
+            base_token_mint,
+            market_base_token_ata,
+            quote_token_mint,
+            market_quote_token_ata,
+            quote_token_badge: quote_asset_badge,
+            protocol_stats: token_mill::ID,
+            signer: make_address("alice"),
+            launchpad: token_mill::ID,
+            metaplex_metadata: token_mill::ID,
+            token_metadata_program: token_mill::ID,
+            quote_token_program,
+            total_supply: DEFAULT_TOTAL_SUPPLY,
+            creator_fee_share: DEFAULT_CREATOR_FEE_SHARE,
+            staking_fee_share: DEFAULT_STAKING_FEE_SHARE,
+        }
+    }
+
+    pub fn with_fee_shares(&mut self, creator_fee_share: u16, staking_fee_share: u16) -> &mut Self {
+        self.creator_fee_share = creator_fee_share;
+        self.staking_fee_share = staking_fee_share;
+
+        self
+    }
+
+    pub fn no_badge(&mut self) -> &mut Self {
+        self.quote_token_badge = token_mill::ID;
+
+        self
+    }
+
+    pub fn with_launchpad(&mut self, launchpad: Pubkey) -> &mut Self {
+        self.launchpad = launchpad;
+
+        self
+    }
+
+    pub fn with_metaplex_metadata(&mut self) -> &mut Self {
+        self.metaplex_metadata = metadata_address(&self.base_token_mint);
+        self.token_metadata_program = TOKEN_METADATA_PROGRAM_ID;
+
+        self
+    }
+
+    pub fn with_protocol_stats(&mut self, protocol_stats: Pubkey) -> &mut Self {
+        self.protocol_stats = protocol_stats;
+
+        self
+    }
+}
+
+impl InstructionGenerator for CreateMarketAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.base_token_mint, true),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new_readonly(self.quote_token_badge, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(
+                    &self.signer,
+                    &self.base_token_mint,
+                    &spl_token_2022::id(),
+                ),
+                false,
+            ),
+            AccountMeta::new(self.protocol_stats, false),
+        ];
+
+        accounts.append_payer(self.signer);
+        accounts.push(AccountMeta::new_readonly(
+            self.launchpad,
+            self.launchpad != token_mill::ID,
+        ));
+        accounts.push(AccountMeta::new(self.metaplex_metadata, false));
+        accounts.push(AccountMeta::new_readonly(
+            self.token_metadata_program,
+            false,
+        ));
+        accounts.append_system_program().append_token_2022_program();
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts
+            .append_associated_token_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateMarket {
+            name: "name".to_string(),
+            symbol: "symbol".to_string(),
+            uri: "uri".to_string(),
+            total_supply: self.total_supply,
+            creator_fee_share: self.creator_fee_share,
+            staking_fee_share: self.staking_fee_share,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CreateMarketWithExistingMintAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub creator_base_token_ata: Pubkey,
+    pub quote_token_badge: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub protocol_stats: Pubkey,
+    pub signer: Pubkey,
+    pub launchpad: Pubkey,
+    pub base_token_program: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Args
+    pub total_supply: u64,
+    pub initial_deposit: u64,
+}
+
+impl CreateMarketWithExistingMintAction {
+    pub fn new(testing_env: &TokenMillEnv, base_token_mint: Pubkey) -> Self {
+        let config = make_address("config");
+        let signer = make_address("alice");
+
+        let market = Pubkey::find_program_address(
+            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let quote_token_program = testing_env.quote_token_type.program_address();
+
+        let quote_token_badge = Pubkey::find_program_address(
+            &[
+                QUOTE_TOKEN_BADGE_PDA_SEED.as_bytes(),
+                &config.to_bytes(),
+                &quote_token_mint.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        let base_token_program = testing_env
+            .svm
+            .tokens
+            .get(&base_token_mint)
+            .unwrap()
+            .program_address();
+
+        let market_base_token_ata = get_associated_token_address_with_program_id(
+            &market,
+            &base_token_mint,
+            &base_token_program,
+        );
+
+        let creator_base_token_ata = get_associated_token_address_with_program_id(
+            &signer,
+            &base_token_mint,
+            &base_token_program,
+        );
+
+        let market_quote_token_ata = get_associated_token_address_with_program_id(
+            &market,
+            &quote_token_mint,
+            &quote_token_program,
+        );
+
+        Self {
+            config,
+            market,
+            base_token_mint,
+            market_base_token_ata,
+            creator_base_token_ata,
+            quote_token_badge,
+            quote_token_mint,
+            market_quote_token_ata,
+            protocol_stats: token_mill::ID,
+            signer,
+            launchpad: token_mill::ID,
+            base_token_program,
+            quote_token_program,
+            total_supply: DEFAULT_TOTAL_SUPPLY,
+            initial_deposit: DEFAULT_TOTAL_SUPPLY,
+        }
+    }
+
+    pub fn no_badge(&mut self) -> &mut Self {
+        self.quote_token_badge = token_mill::ID;
+
+        self
+    }
+
+    pub fn with_launchpad(&mut self, launchpad: Pubkey) -> &mut Self {
+        self.launchpad = launchpad;
+
+        self
+    }
+}
+
+impl InstructionGenerator for CreateMarketWithExistingMintAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.creator_base_token_ata, false),
+            AccountMeta::new_readonly(self.quote_token_badge, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.protocol_stats, false),
+        ];
+
+        accounts.append_payer(self.signer);
+        accounts.push(AccountMeta::new_readonly(
+            self.launchpad,
+            self.launchpad != token_mill::ID,
+        ));
+        accounts.append_system_program();
+
+        match self.base_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts
+            .append_associated_token_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateMarketWithExistingMint {
+            total_supply: self.total_supply,
+            initial_deposit: self.initial_deposit,
+            creator_fee_share: DEFAULT_CREATOR_FEE_SHARE,
+            staking_fee_share: DEFAULT_STAKING_FEE_SHARE,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CreateProtocolStatsAction {
+    // Accounts
+    pub config: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub protocol_stats: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl CreateProtocolStatsAction {
+    pub fn new(testing_env: &TokenMillEnv) -> Self {
+        let config = make_address("config");
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+
+        let protocol_stats = Pubkey::find_program_address(
+            &[
+                PROTOCOL_STATS_PDA_SEED.as_bytes(),
+                &config.to_bytes(),
+                &quote_token_mint.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            config,
+            quote_token_mint,
+            protocol_stats,
+            signer: make_address("admin"),
+        }
+    }
+}
+
+impl InstructionGenerator for CreateProtocolStatsAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.protocol_stats, false),
+        ];
+
+        accounts.append_payer(self.signer).append_system_program();
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateProtocolStats {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CreateMarketOracleAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_oracle: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl CreateMarketOracleAction {
+    pub fn new(testing_env: &TokenMillEnv) -> Self {
+        Self {
+            market: testing_env.market,
+            market_oracle: market_oracle_address(&testing_env.market),
+            signer: make_address("bob"),
+        }
+    }
+}
+
+impl InstructionGenerator for CreateMarketOracleAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.market_oracle, false),
+        ];
+
+        accounts.append_payer(self.signer).append_system_program();
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateMarketOracle {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetMarketPricesAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    pub launchpad: Pubkey,
+    // Args
+    pub price_curve: Curve,
+}
+
+impl SetMarketPricesAction {
+    pub fn new(curve: Curve) -> Self {
+        let market = Pubkey::find_program_address(
+            &[
+                MARKET_PDA_SEED.as_bytes(),
+                &make_address("base_token_mint").to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            config: make_address("config"),
+            market,
+            signer: make_address("alice"),
+            launchpad: token_mill::ID,
+            price_curve: curve,
+        }
+    }
+
+    pub fn with_custom_base_token_mint(mut self, base_token_mint: Pubkey) -> Self {
+        self.market = Pubkey::find_program_address(
+            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
+
+        self
+    }
+
+    pub fn with_launchpad(mut self, launchpad: Pubkey) -> Self {
+        self.launchpad = launchpad;
+
+        self
+    }
+}
+
+impl InstructionGenerator for SetMarketPricesAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts.append_payer(self.signer);
+        accounts.push(AccountMeta::new_readonly(
+            self.launchpad,
+            self.launchpad != token_mill::ID,
+        ));
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let Curve {
+            bid_prices,
+            ask_prices,
+        } = self.price_curve.clone();
+
+        let input = token_mill::instruction::SetMarketPrices {
+            bid_prices,
+            ask_prices,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetPricesFromPresetAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    pub launchpad: Pubkey,
+    // Args
+    pub preset: PricePreset,
+    pub prices_length: u8,
+    pub min_price: u64,
+    pub max_price: u64,
+    pub spread_bps: u16,
+}
+
+impl SetPricesFromPresetAction {
+    pub fn new(preset: PricePreset) -> Self {
+        let set_market_prices_action = SetMarketPricesAction::new(Curve::default());
+
+        Self {
+            config: set_market_prices_action.config,
+            market: set_market_prices_action.market,
+            signer: set_market_prices_action.signer,
+            launchpad: set_market_prices_action.launchpad,
+            preset,
+            prices_length: PRICES_LENGTH as u8,
+            min_price: 1_000,
+            max_price: 1_000_000,
+            spread_bps: 100,
+        }
+    }
+}
+
+impl InstructionGenerator for SetPricesFromPresetAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts.append_payer(self.signer);
+        accounts.push(AccountMeta::new_readonly(
+            self.launchpad,
+            self.launchpad != token_mill::ID,
+        ));
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetPricesFromPreset {
+            preset: self.preset,
+            prices_length: self.prices_length,
+            min_price: self.min_price,
+            max_price: self.max_price,
+            spread_bps: self.spread_bps,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct UpdatePricesAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    pub launchpad: Pubkey,
+    // Args
+    pub price_curve: Curve,
+}
+
+impl UpdatePricesAction {
+    pub fn new(testing_env: &TokenMillEnv, curve: Curve) -> Self {
+        Self {
+            config: testing_env.config,
+            market: testing_env.market,
+            signer: make_address("alice"),
+            launchpad: token_mill::ID,
+            price_curve: curve,
+        }
+    }
+}
+
+impl InstructionGenerator for UpdatePricesAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts.append_payer(self.signer);
+        accounts.push(AccountMeta::new_readonly(
+            self.launchpad,
+            self.launchpad != token_mill::ID,
+        ));
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let Curve {
+            bid_prices,
+            ask_prices,
+        } = self.price_curve.clone();
+
+        let input = token_mill::instruction::UpdatePrices {
+            bid_prices,
+            ask_prices,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct RaiseTailPricesAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    pub launchpad: Pubkey,
+    // Args
+    pub ask_prices: Vec<u64>,
+}
+
+impl RaiseTailPricesAction {
+    pub fn new(testing_env: &TokenMillEnv, ask_prices: Vec<u64>) -> Self {
+        Self {
+            config: testing_env.config,
+            market: testing_env.market,
+            signer: make_address("alice"),
+            launchpad: token_mill::ID,
+            ask_prices,
+        }
+    }
+}
+
+impl InstructionGenerator for RaiseTailPricesAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts.append_payer(self.signer);
+        accounts.push(AccountMeta::new_readonly(
+            self.launchpad,
+            self.launchpad != token_mill::ID,
+        ));
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::RaiseTailPrices {
+            ask_prices: self.ask_prices.clone(),
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct ValidateCreateMarketAction {
+    // Accounts
+    pub config: Pubkey,
+    pub quote_token_badge: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub total_supply: u64,
+    pub creator_fee_share: u16,
+    pub staking_fee_share: u16,
+    pub price_curve: Curve,
+}
+
+impl ValidateCreateMarketAction {
+    pub fn new(testing_env: &TokenMillEnv, curve: Curve) -> Self {
+        let create_market_action = CreateMarketAction::new(testing_env);
+
+        Self {
+            config: create_market_action.config,
+            quote_token_badge: create_market_action.quote_token_badge,
+            quote_token_mint: create_market_action.quote_token_mint,
+            signer: create_market_action.signer,
+            total_supply: create_market_action.total_supply,
+            creator_fee_share: DEFAULT_CREATOR_FEE_SHARE,
+            staking_fee_share: DEFAULT_STAKING_FEE_SHARE,
+            price_curve: curve,
+        }
+    }
+}
+
+impl InstructionGenerator for ValidateCreateMarketAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new_readonly(self.quote_token_badge, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let Curve {
+            bid_prices,
+            ask_prices,
+        } = self.price_curve.clone();
+
+        let input = token_mill::instruction::ValidateCreateMarket {
+            total_supply: self.total_supply,
+            creator_fee_share: self.creator_fee_share,
+            staking_fee_share: self.staking_fee_share,
+            bid_prices,
+            ask_prices,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct ValidateMarketPricesAction {
+    // Accounts
+    pub set_prices_action: SetMarketPricesAction,
+}
+
+impl ValidateMarketPricesAction {
+    pub fn new(set_prices_action: SetMarketPricesAction) -> Self {
+        Self { set_prices_action }
+    }
+}
+
+impl InstructionGenerator for ValidateMarketPricesAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        self.set_prices_action.accounts()
+    }
+
+    fn instruction(&self) -> Instruction {
+        let Curve {
+            bid_prices,
+            ask_prices,
+        } = self.set_prices_action.price_curve.clone();
+
+        let input = token_mill::instruction::ValidateMarketPrices {
+            bid_prices,
+            ask_prices,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SwapAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub user_base_token_ata: Pubkey,
+    pub user_quote_token_ata: Pubkey,
+    pub protocol_quote_token_ata: Pubkey,
+    pub referral_quote_token_ata: Pubkey,
+    pub referral_code: Pubkey,
+    pub interface_fee_token_account: Pubkey,
+    pub trader_profile: Pubkey,
+    pub creator_quote_token_ata: Pubkey,
+    pub staking: Pubkey,
+    pub protocol_stats: Pubkey,
+    pub trader_stats: Pubkey,
+    pub stake_position: Pubkey,
+    pub quote_price_feed: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Args
+    pub swap_type: SwapType,
+    pub swap_amount_type: SwapAmountType,
+    pub amount: u64,
+    pub other_amount_threshold: u64,
+    pub interface_fee_bps: u16,
+    pub deadline: Option<SwapDeadline>,
+    pub fill_type: SwapFillType,
+    pub max_price_impact_bps: Option<u16>,
+}
+
+impl SwapAction {
+    pub fn new(
+        token_mill_env: &TokenMillEnv,
+        swap_type: SwapType,
+        swap_amount_type: SwapAmountType,
+        amount: u64,
+        other_amount_threshold: u64,
+        referrer: Option<Pubkey>,
+    ) -> Self {
+        let config = make_address("config");
+        let base_token_mint = token_mill_env.base_token_mint.unwrap();
+
+        let signer = make_address("bob");
+
+        let market = Pubkey::find_program_address(
+            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
+
+        let quote_token_mint = token_mill_env.quote_token_mint.unwrap();
+        let quote_token_program = token_mill_env.quote_token_type.program_address();
+
+        let market_base_token_ata = get_associated_token_address_with_program_id(
+            &market,
+            &base_token_mint,
+            &spl_token_2022::id(),
+        );
+
+        let market_quote_token_ata = get_associated_token_address_with_program_id(
+            &market,
+            &quote_token_mint,
+            &quote_token_program,
+        );
+
+        let user_base_token_ata = get_associated_token_address_with_program_id(
+            &signer,
+            &base_token_mint,
+            &spl_token_2022::id(),
+        );
+
+        let user_quote_token_ata = get_associated_token_address_with_program_id(
+            &signer,
+            &quote_token_mint,
+            &quote_token_program,
+        );
+
+        let protocol_quote_token_ata = get_associated_token_address_with_program_id(
+            &make_address("dave"),
+            &quote_token_mint,
+            &quote_token_program,
+        );
+
+        let referral_account = if let Some(referrer) = referrer {
+            Pubkey::find_program_address(
+                &[
+                    REFERRAL_ACCOUNT_PDA_SEED.as_bytes(),
+                    &config.to_bytes(),
+                    &referrer.to_bytes(),
+                ],
+                &token_mill::ID,
+            )
+            .0
+        } else {
+            Pubkey::new_unique()
+        };
+
+        let referral_quote_token_ata = if referrer.is_some() {
+            get_associated_token_address_with_program_id(
+                &referral_account,
+                &quote_token_mint,
+                &quote_token_program,
+            )
+        } else {
+            token_mill::ID
+        };
+
+        Self {
+            config,
+            market,
+            base_token_mint,
+            quote_token_mint,
+            market_base_token_ata,
+            market_quote_token_ata,
+            user_base_token_ata,
+            user_quote_token_ata,
+            protocol_quote_token_ata,
+            referral_quote_token_ata,
+            referral_code: token_mill::ID,
+            interface_fee_token_account: token_mill::ID,
+            trader_profile: token_mill::ID,
+            creator_quote_token_ata: token_mill::ID,
+            staking: token_mill::ID,
+            protocol_stats: token_mill::ID,
+            trader_stats: token_mill::ID,
+            stake_position: token_mill::ID,
+            quote_price_feed: token_mill::ID,
+            signer,
+            quote_token_program,
+            swap_type,
+            swap_amount_type,
+            amount,
+            other_amount_threshold,
+            interface_fee_bps: 0,
+            deadline: None,
+            fill_type: SwapFillType::ImmediateOrCancel,
+            max_price_impact_bps: None,
+        }
+    }
+
+    pub fn with_user(&mut self, user: Pubkey) -> &mut Self {
+        self.signer = user;
+        self.user_base_token_ata = get_associated_token_address_with_program_id(
+            &user,
+            &self.base_token_mint,
+            &spl_token_2022::id(),
+        );
+        self.user_quote_token_ata = get_associated_token_address_with_program_id(
+            &user,
+            &self.quote_token_mint,
+            &self.quote_token_program,
+        );
+
+        self
+    }
+
+    pub fn with_referral_code(&mut self, code: &str) -> &mut Self {
+        self.referral_code = referral_code_address(&self.config, code);
+
+        self
+    }
+
+    pub fn with_deadline(&mut self, deadline: SwapDeadline) -> &mut Self {
+        self.deadline = Some(deadline);
+
+        self
+    }
+
+    pub fn with_interface_fee(&mut self, recipient: Pubkey, interface_fee_bps: u16) -> &mut Self {
+        self.interface_fee_token_account = get_associated_token_address_with_program_id(
+            &recipient,
+            &self.quote_token_mint,
+            &self.quote_token_program,
+        );
+        self.interface_fee_bps = interface_fee_bps;
+
+        self
+    }
+
+    pub fn with_fee_distribution_accounts(&mut self) -> &mut Self {
+        self.creator_quote_token_ata = get_associated_token_address_with_program_id(
+            &make_address("alice"),
+            &self.quote_token_mint,
+            &self.quote_token_program,
+        );
+        self.staking = Pubkey::find_program_address(
+            &[MARKET_STAKING_PDA_SEED.as_bytes(), &self.market.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
+
+        self
+    }
+
+    pub fn with_protocol_stats(&mut self) -> &mut Self {
+        self.protocol_stats = Pubkey::find_program_address(
+            &[
+                PROTOCOL_STATS_PDA_SEED.as_bytes(),
+                &self.config.to_bytes(),
+                &self.quote_token_mint.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        self
+    }
+
+    pub fn with_trader_stats(&mut self) -> &mut Self {
+        self.trader_stats =
+            trader_stats_address(&self.config, &self.quote_token_mint, &self.signer);
+
+        self
+    }
+
+    /// Passes the stake position of the user on `protocol_token_market`, for the staker discounts of the config
+    pub fn with_stake_position(&mut self, protocol_token_market: Pubkey) -> &mut Self {
+        self.stake_position = Pubkey::find_program_address(
+            &[
+                STAKING_POSITION_PDA_SEED.as_bytes(),
+                &protocol_token_market.to_bytes(),
+                &self.signer.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        self
+    }
+
+    pub fn with_trader_profile(&mut self) -> &mut Self {
+        self.trader_profile = Pubkey::find_program_address(
+            &[
+                TRADER_PROFILE_PDA_SEED.as_bytes(),
+                &self.market.to_bytes(),
+                &self.signer.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        self
+    }
+}
+
+impl InstructionGenerator for SwapAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.user_base_token_ata, false),
+            AccountMeta::new(self.user_quote_token_ata, false),
+            AccountMeta::new(self.protocol_quote_token_ata, false),
+            AccountMeta::new(self.referral_quote_token_ata, false),
+            AccountMeta::new_readonly(self.referral_code, false),
+            AccountMeta::new(self.interface_fee_token_account, false),
+            AccountMeta::new(self.trader_profile, false),
+            AccountMeta::new(self.creator_quote_token_ata, false),
+            AccountMeta::new(self.staking, false),
+            AccountMeta::new(self.protocol_stats, false),
+            AccountMeta::new(self.trader_stats, false),
+            AccountMeta::new_readonly(self.stake_position, false),
+            AccountMeta::new_readonly(self.quote_price_feed, false),
+            AccountMeta::new(market_oracle_address(&self.market), false),
+            AccountMeta::new_readonly(restricted_wallet_address(&self.market, &self.signer), false),
+            AccountMeta::new(self.signer, true),
+        ];
+
+        accounts.append_token_2022_program();
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts
+            .append_system_program()
+            .append_associated_token_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::Swap {
+            swap_type: self.swap_type,
+            swap_amount_type: self.swap_amount_type,
+            amount: self.amount,
+            other_amount_threshold: self.other_amount_threshold,
+            options: SwapOptions {
+                interface_fee_bps: self.interface_fee_bps,
+                deadline: self.deadline,
+                fill_type: self.fill_type,
+                max_price_impact_bps: self.max_price_impact_bps,
+            },
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SwapRouteAction {
+    // Accounts
+    pub config: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub user_quote_token_ata: Pubkey,
+    pub protocol_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Remaining accounts
+    pub sell_base_token_mint: Pubkey,
+    pub buy_base_token_mint: Pubkey,
+    // Args
+    pub amount: u64,
+    pub min_amount_out: u64,
+}
+
+impl SwapRouteAction {
+    pub fn new(
+        token_mill_env: &TokenMillEnv,
+        sell_base_token_mint: Pubkey,
+        buy_base_token_mint: Pubkey,
+        amount: u64,
+        min_amount_out: u64,
+    ) -> Self {
+        let swap_action = SwapAction::new(
+            token_mill_env,
+            SwapType::Sell,
+            SwapAmountType::ExactInput,
+            amount,
+            min_amount_out,
+            None,
+        );
+
+        Self {
+            config: swap_action.config,
+            quote_token_mint: swap_action.quote_token_mint,
+            user_quote_token_ata: swap_action.user_quote_token_ata,
+            protocol_quote_token_ata: swap_action.protocol_quote_token_ata,
+            signer: swap_action.signer,
+            quote_token_program: swap_action.quote_token_program,
+            sell_base_token_mint,
+            buy_base_token_mint,
+            amount,
+            min_amount_out,
+        }
+    }
+
+    fn leg_accounts(&self, base_token_mint: &Pubkey) -> Vec<AccountMeta> {
+        let market = Pubkey::find_program_address(
+            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
+
+        vec![
+            AccountMeta::new(market, false),
+            AccountMeta::new_readonly(*base_token_mint, false),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(
+                    &market,
+                    base_token_mint,
+                    &spl_token_2022::id(),
+                ),
+                false,
+            ),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(
+                    &market,
+                    &self.quote_token_mint,
+                    &self.quote_token_program,
+                ),
+                false,
+            ),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(
+                    &self.signer,
+                    base_token_mint,
+                    &spl_token_2022::id(),
+                ),
+                false,
+            ),
+            AccountMeta::new_readonly(token_mill::ID, false),
+            AccountMeta::new(market_oracle_address(&market), false),
+            AccountMeta::new_readonly(restricted_wallet_address(&market, &self.signer), false),
+        ]
+    }
+
+    /// Accounts shared by all legs, followed by the legs in the remaining accounts
+    fn shared_accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.user_quote_token_ata, false),
+            AccountMeta::new(self.protocol_quote_token_ata, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program();
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+}
+
+impl InstructionGenerator for SwapRouteAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = self.shared_accounts();
+
+        accounts.extend(self.leg_accounts(&self.sell_base_token_mint));
+        accounts.extend(self.leg_accounts(&self.buy_base_token_mint));
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SwapRoute {
+            amount: self.amount,
+            min_amount_out: self.min_amount_out,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct BuyBasketAction {
+    // Accounts
+    pub swap_route_action: SwapRouteAction,
+    // Remaining accounts
+    pub base_token_mints: Vec<Pubkey>,
+    // Args
+    pub amount: u64,
+    pub weights: Vec<u16>,
+    pub min_amount_out: u64,
+}
+
+impl BuyBasketAction {
+    pub fn new(
+        token_mill_env: &TokenMillEnv,
+        base_token_mints: Vec<Pubkey>,
+        weights: Vec<u16>,
+        amount: u64,
+        min_amount_out: u64,
+    ) -> Self {
+        let swap_route_action = SwapRouteAction::new(
+            token_mill_env,
+            Pubkey::default(),
+            Pubkey::default(),
+            amount,
+            min_amount_out,
+        );
+
+        Self {
+            swap_route_action,
+            base_token_mints,
+            amount,
+            weights,
+            min_amount_out,
+        }
+    }
+}
+
+impl InstructionGenerator for BuyBasketAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = self.swap_route_action.shared_accounts();
+
+        for base_token_mint in &self.base_token_mints {
+            accounts.extend(self.swap_route_action.leg_accounts(base_token_mint));
+        }
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::BuyBasket {
+            amount: self.amount,
+            weights: self.weights.clone(),
+            min_amount_out: self.min_amount_out,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CreateTraderProfileAction {
+    // Accounts
+    pub market: Pubkey,
+    pub trader_profile: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl CreateTraderProfileAction {
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        let base_token_mint = token_mill_env.base_token_mint.unwrap();
+        let signer = make_address("bob");
+
+        let market = Pubkey::find_program_address(
+            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
+
+        let trader_profile = Pubkey::find_program_address(
+            &[
+                TRADER_PROFILE_PDA_SEED.as_bytes(),
+                &market.to_bytes(),
+                &signer.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            market,
+            trader_profile,
+            signer,
+        }
+    }
+}
+
+impl InstructionGenerator for CreateTraderProfileAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.trader_profile, false),
+        ];
+
+        accounts.append_payer(self.signer).append_system_program();
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateTraderProfile {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CreateTraderStatsAction {
+    // Accounts
+    pub config: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub trader_stats: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl CreateTraderStatsAction {
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        let config = make_address("config");
+        let quote_token_mint = token_mill_env.quote_token_mint.unwrap();
+        let signer = make_address("bob");
+
+        Self {
+            config,
+            quote_token_mint,
+            trader_stats: trader_stats_address(&config, &quote_token_mint, &signer),
+            signer,
+        }
+    }
+}
+
+impl InstructionGenerator for CreateTraderStatsAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.trader_stats, false),
+        ];
+
+        accounts.append_payer(self.signer).append_system_program();
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateTraderStats {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct GetTraderPnlAction {
+    // Accounts
+    pub market: Pubkey,
+    pub trader_profile: Pubkey,
+}
+
+impl GetTraderPnlAction {
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        let create_trader_profile_action = CreateTraderProfileAction::new(token_mill_env);
+
+        Self {
+            market: create_trader_profile_action.market,
+            trader_profile: create_trader_profile_action.trader_profile,
+        }
+    }
+}
+
+impl InstructionGenerator for GetTraderPnlAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new_readonly(self.trader_profile, false),
+        ]
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::GetTraderPnl {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct GetInvariantsAction {
+    // Accounts
+    pub market: Pubkey,
+}
+
+impl GetInvariantsAction {
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        Self {
+            market: token_mill_env.market,
+        }
+    }
+}
+
+impl InstructionGenerator for GetInvariantsAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        vec![AccountMeta::new_readonly(self.market, false)]
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::GetInvariants {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SimulateMarketAction {
+    // Accounts
+    pub market: Pubkey,
+    // Args
+    pub sizes: Vec<u64>,
+}
+
+impl SimulateMarketAction {
+    pub fn new(token_mill_env: &TokenMillEnv, sizes: Vec<u64>) -> Self {
+        Self {
+            market: token_mill_env.market,
+            sizes,
+        }
+    }
+}
+
+impl InstructionGenerator for SimulateMarketAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        vec![AccountMeta::new_readonly(self.market, false)]
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SimulateMarket {
+            sizes: self.sizes.clone(),
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct GetMarketDepthAction {
+    // Accounts
+    pub market: Pubkey,
+    // Args
+    pub target_price: u64,
+    pub sizes: Vec<u64>,
+}
+
+impl GetMarketDepthAction {
+    pub fn new(token_mill_env: &TokenMillEnv, target_price: u64, sizes: Vec<u64>) -> Self {
+        Self {
+            market: token_mill_env.market,
+            target_price,
+            sizes,
+        }
+    }
+}
+
+impl InstructionGenerator for GetMarketDepthAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        vec![AccountMeta::new_readonly(self.market, false)]
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::GetMarketDepth {
+            target_price: self.target_price,
+            sizes: self.sizes.clone(),
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct QuoteSwapAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub trader_stats: Pubkey,
+    pub stake_position: Pubkey,
+    // Args
+    pub swap_type: SwapType,
+    pub swap_amount_type: SwapAmountType,
+    pub amount: u64,
+    pub interface_fee_bps: u16,
+    pub with_referral: bool,
+}
+
+impl QuoteSwapAction {
+    pub fn new(
+        token_mill_env: &TokenMillEnv,
+        swap_type: SwapType,
+        swap_amount_type: SwapAmountType,
+        amount: u64,
+    ) -> Self {
+        Self {
+            config: token_mill_env.config,
+            market: token_mill_env.market,
+            user: make_address("bob"),
+            trader_stats: token_mill::ID,
+            stake_position: token_mill::ID,
+            swap_type,
+            swap_amount_type,
+            amount,
+            interface_fee_bps: 0,
+            with_referral: false,
+        }
+    }
+
+    /// Quotes the swap `swap_action` would execute, with the same user, accounts and interface fee
+    pub fn from_swap_action(swap_action: &SwapAction) -> Self {
+        Self {
+            config: swap_action.config,
+            market: swap_action.market,
+            user: swap_action.signer,
+            trader_stats: swap_action.trader_stats,
+            stake_position: swap_action.stake_position,
+            swap_type: swap_action.swap_type,
+            swap_amount_type: swap_action.swap_amount_type,
+            amount: swap_action.amount,
+            interface_fee_bps: if swap_action.interface_fee_token_account == token_mill::ID {
+                0
+            } else {
+                swap_action.interface_fee_bps
+            },
+            with_referral: swap_action.referral_quote_token_ata != token_mill::ID,
+        }
+    }
+}
+
+impl InstructionGenerator for QuoteSwapAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new_readonly(self.user, false),
+            AccountMeta::new_readonly(self.trader_stats, false),
+            AccountMeta::new_readonly(self.stake_position, false),
+        ]
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::QuoteSwap {
+            swap_type: self.swap_type,
+            swap_amount_type: self.swap_amount_type,
+            amount: self.amount,
+            interface_fee_bps: self.interface_fee_bps,
+            with_referral: self.with_referral,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SellAndBurnAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub user_base_token_ata: Pubkey,
+    pub user_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Args
+    pub swap_amount_type: SwapAmountType,
+    pub amount: u64,
+    pub other_amount_threshold: u64,
+}
+
+impl SellAndBurnAction {
+    pub fn new(
+        token_mill_env: &TokenMillEnv,
+        swap_amount_type: SwapAmountType,
+        amount: u64,
+        other_amount_threshold: u64,
+    ) -> Self {
+        let swap_action = SwapAction::new(
+            token_mill_env,
+            SwapType::Sell,
+            swap_amount_type,
+            amount,
+            other_amount_threshold,
+            None,
+        );
+
+        Self {
+            config: swap_action.config,
+            market: swap_action.market,
+            base_token_mint: swap_action.base_token_mint,
+            quote_token_mint: swap_action.quote_token_mint,
+            market_quote_token_ata: swap_action.market_quote_token_ata,
+            user_base_token_ata: swap_action.user_base_token_ata,
+            user_quote_token_ata: swap_action.user_quote_token_ata,
+            signer: swap_action.signer,
+            quote_token_program: swap_action.quote_token_program,
+            swap_amount_type,
+            amount,
+            other_amount_threshold,
+        }
+    }
+}
+
+impl InstructionGenerator for SellAndBurnAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.base_token_mint, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.user_base_token_ata, false),
+            AccountMeta::new(self.user_quote_token_ata, false),
+            AccountMeta::new_readonly(token_mill::ID, false),
+            AccountMeta::new(market_oracle_address(&self.market), false),
+            AccountMeta::new_readonly(restricted_wallet_address(&self.market, &self.signer), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program();
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SellAndBurn {
+            swap_amount_type: self.swap_amount_type,
+            amount: self.amount,
+            other_amount_threshold: self.other_amount_threshold,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct BuybackAndBurnAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl BuybackAndBurnAction {
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        let swap_action = SwapAction::new(
+            token_mill_env,
+            SwapType::Buy,
+            SwapAmountType::ExactInput,
+            0,
+            0,
+            None,
+        );
+
+        Self {
+            config: swap_action.config,
+            market: swap_action.market,
+            base_token_mint: swap_action.base_token_mint,
+            market_base_token_ata: swap_action.market_base_token_ata,
+            signer: swap_action.signer,
+        }
+    }
+}
+
+impl InstructionGenerator for BuybackAndBurnAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new_readonly(token_mill::ID, false),
+            AccountMeta::new(market_oracle_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::BuybackAndBurn {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct GraduateMarketAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub graduation: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub graduation_authority: Pubkey,
+    pub graduation_authority_base_token_ata: Pubkey,
+    pub graduation_authority_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+}
+
+impl GraduateMarketAction {
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        let swap_action = SwapAction::new(
+            token_mill_env,
+            SwapType::Buy,
+            SwapAmountType::ExactInput,
+            0,
+            0,
+            None,
+        );
+
+        let graduation_authority = graduation_authority_address(&swap_action.market);
+
+        Self {
+            config: swap_action.config,
+            market: swap_action.market,
+            graduation: graduation_address(&swap_action.market),
+            base_token_mint: swap_action.base_token_mint,
+            quote_token_mint: swap_action.quote_token_mint,
+            market_base_token_ata: swap_action.market_base_token_ata,
+            market_quote_token_ata: swap_action.market_quote_token_ata,
+            graduation_authority,
+            graduation_authority_base_token_ata: get_associated_token_address_with_program_id(
+                &graduation_authority,
+                &swap_action.base_token_mint,
+                &spl_token_2022::ID,
+            ),
+            graduation_authority_quote_token_ata: get_associated_token_address_with_program_id(
+                &graduation_authority,
+                &swap_action.quote_token_mint,
+                &swap_action.quote_token_program,
+            ),
+            signer: swap_action.signer,
+            quote_token_program: swap_action.quote_token_program,
+        }
+    }
+}
+
+impl InstructionGenerator for GraduateMarketAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.graduation, false),
+            AccountMeta::new(self.base_token_mint, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new_readonly(self.graduation_authority, false),
+            AccountMeta::new(self.graduation_authority_base_token_ata, false),
+            AccountMeta::new(self.graduation_authority_quote_token_ata, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program();
+
+        accounts.push(AccountMeta::new_readonly(self.quote_token_program, false));
+
+        accounts
+            .append_associated_token_program()
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::GraduateMarket {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CompleteGraduationAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub graduation: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub graduation_authority: Pubkey,
+    pub graduation_authority_base_token_ata: Pubkey,
+    pub graduation_authority_quote_token_ata: Pubkey,
+    pub pool_program: Pubkey,
+    pub protocol_stats: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+    /// Accounts of the pool creation, passed as remaining accounts
+    pub pool_accounts: Vec<AccountMeta>,
+    // Args
+    pub pool_creation_lamports: u64,
+}
+
+impl CompleteGraduationAction {
+    /// Graduation to the Raydium CPMM pool program and config of the test config
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        let graduate_market_action = GraduateMarketAction::new(token_mill_env);
+
+        let pool_program = make_address("pool_program");
+        let pool_config = make_address("pool_config");
+
+        let base_token = (
+            graduate_market_action.base_token_mint,
+            graduate_market_action.graduation_authority_base_token_ata,
+            spl_token_2022::ID,
+        );
+        let quote_token = (
+            graduate_market_action.quote_token_mint,
+            graduate_market_action.graduation_authority_quote_token_ata,
+            graduate_market_action.quote_token_program,
+        );
+
+        let (token_0, token_1) = if base_token.0 < quote_token.0 {
+            (base_token, quote_token)
+        } else {
+            (quote_token, base_token)
+        };
+
+        let pool_state = Pubkey::find_program_address(
+            &[
+                b"pool",
+                &pool_config.to_bytes(),
+                &token_0.0.to_bytes(),
+                &token_1.0.to_bytes(),
+            ],
+            &pool_program,
+        )
+        .0;
+        let lp_mint =
+            Pubkey::find_program_address(&[b"pool_lp_mint", &pool_state.to_bytes()], &pool_program)
+                .0;
+        let pool_vault = |mint: &Pubkey| {
+            Pubkey::find_program_address(
+                &[b"pool_vault", &pool_state.to_bytes(), &mint.to_bytes()],
+                &pool_program,
+            )
+            .0
+        };
+
+        let pool_accounts = vec![
+            AccountMeta::new(graduate_market_action.graduation_authority, false),
+            AccountMeta::new_readonly(pool_config, false),
+            AccountMeta::new_readonly(
+                Pubkey::find_program_address(&[b"vault_and_lp_mint_auth_seed"], &pool_program).0,
+                false,
+            ),
+            AccountMeta::new(pool_state, false),
+            AccountMeta::new_readonly(token_0.0, false),
+            AccountMeta::new_readonly(token_1.0, false),
+            AccountMeta::new(lp_mint, false),
+            AccountMeta::new(token_0.1, false),
+            AccountMeta::new(token_1.1, false),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(
+                    &graduate_market_action.graduation_authority,
+                    &lp_mint,
+                    &spl_token::ID,
+                ),
+                false,
+            ),
+            AccountMeta::new(pool_vault(&token_0.0), false),
+            AccountMeta::new(pool_vault(&token_1.0), false),
+            AccountMeta::new(make_address("create_pool_fee"), false),
+            AccountMeta::new(
+                Pubkey::find_program_address(
+                    &[b"observation", &pool_state.to_bytes()],
+                    &pool_program,
+                )
+                .0,
+                false,
+            ),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(token_0.2, false),
+            AccountMeta::new_readonly(token_1.2, false),
+            AccountMeta::new_readonly(anchor_spl::associated_token::ID, false),
+            AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+            AccountMeta::new_readonly(solana_sdk::sysvar::rent::ID, false),
+        ];
+
+        Self {
+            config: graduate_market_action.config,
+            market: graduate_market_action.market,
+            graduation: graduate_market_action.graduation,
+            base_token_mint: graduate_market_action.base_token_mint,
+            quote_token_mint: graduate_market_action.quote_token_mint,
+            graduation_authority: graduate_market_action.graduation_authority,
+            graduation_authority_base_token_ata: graduate_market_action
+                .graduation_authority_base_token_ata,
+            graduation_authority_quote_token_ata: graduate_market_action
+                .graduation_authority_quote_token_ata,
+            pool_program,
+            protocol_stats: token_mill::ID,
+            signer: graduate_market_action.signer,
+            quote_token_program: graduate_market_action.quote_token_program,
+            pool_accounts,
+            pool_creation_lamports: 1_000_000_000,
+        }
+    }
+}
+
+impl InstructionGenerator for CompleteGraduationAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.graduation, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.graduation_authority, false),
+            AccountMeta::new(self.graduation_authority_base_token_ata, false),
+            AccountMeta::new(self.graduation_authority_quote_token_ata, false),
+            AccountMeta::new_readonly(self.pool_program, false),
+            AccountMeta::new(self.protocol_stats, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program();
+
+        accounts.push(AccountMeta::new_readonly(self.quote_token_program, false));
+
+        accounts
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts.extend(self.pool_accounts.iter().cloned());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CompleteGraduation {
+            pool_creation_lamports: self.pool_creation_lamports,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct BuySecondaryCurveAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub user_base_token_ata: Pubkey,
+    pub user_quote_token_ata: Pubkey,
+    pub quote_price_feed: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Args
+    pub base_amount: u64,
+    pub max_quote_amount: u64,
+}
+
+impl BuySecondaryCurveAction {
+    pub fn new(token_mill_env: &TokenMillEnv, base_amount: u64) -> Self {
+        let swap_action = SwapAction::new(
+            token_mill_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            base_amount,
+            u64::MAX,
+            None,
+        );
+
+        Self {
+            config: swap_action.config,
+            market: swap_action.market,
+            base_token_mint: swap_action.base_token_mint,
+            quote_token_mint: swap_action.quote_token_mint,
+            market_base_token_ata: swap_action.market_base_token_ata,
+            market_quote_token_ata: swap_action.market_quote_token_ata,
+            user_base_token_ata: swap_action.user_base_token_ata,
+            user_quote_token_ata: swap_action.user_quote_token_ata,
+            quote_price_feed: swap_action.quote_price_feed,
+            signer: swap_action.signer,
+            quote_token_program: swap_action.quote_token_program,
+            base_amount,
+            max_quote_amount: u64::MAX,
+        }
+    }
+}
+
+impl InstructionGenerator for BuySecondaryCurveAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.user_base_token_ata, false),
+            AccountMeta::new(self.user_quote_token_ata, false),
+            AccountMeta::new_readonly(self.quote_price_feed, false),
+            AccountMeta::new_readonly(restricted_wallet_address(&self.market, &self.signer), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program();
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::BuySecondaryCurve {
+            base_amount: self.base_amount,
+            max_quote_amount: self.max_quote_amount,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct PostFirmQuoteAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub market_maker_badge: Pubkey,
+    pub firm_quote: Pubkey,
+    pub token_mint: Pubkey,
+    pub escrow_authority: Pubkey,
+    pub escrow_token_ata: Pubkey,
+    pub market_maker_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub token_program: Pubkey,
+    // Args
+    pub swap_type: SwapType,
+    pub base_amount: u64,
+    pub price: u64,
+    pub expiry: i64,
+}
+
+impl PostFirmQuoteAction {
+    pub fn new(
+        token_mill_env: &TokenMillEnv,
+        swap_type: SwapType,
+        base_amount: u64,
+        price: u64,
+    ) -> Self {
+        let config = make_address("config");
+        let signer = make_address("carol");
+
+        // Asks escrow base tokens, bids escrow quote tokens
+        let (token_mint, token_program) = match swap_type {
+            SwapType::Buy => (
+                token_mill_env.base_token_mint.unwrap(),
+                spl_token_2022::id(),
+            ),
+            SwapType::Sell => (
+                token_mill_env.quote_token_mint.unwrap(),
+                token_mill_env.quote_token_type.program_address(),
+            ),
+        };
+
+        let firm_quote = make_address("firm_quote");
+        let escrow_authority = firm_quote_escrow_address(&firm_quote);
+        let escrow_token_ata = get_associated_token_address_with_program_id(
+            &escrow_authority,
+            &token_mint,
+            &token_program,
+        );
+
+        let market_maker_token_ata =
+            get_associated_token_address_with_program_id(&signer, &token_mint, &token_program);
+
+        Self {
+            config,
+            market: token_mill_env.market,
+            market_maker_badge: market_maker_badge_address(&config, &signer),
+            firm_quote,
+            token_mint,
+            escrow_authority,
+            escrow_token_ata,
+            market_maker_token_ata,
+            signer,
+            token_program,
+            swap_type,
+            base_amount,
+            price,
+            expiry: 3_600,
+        }
+    }
+}
+
+impl InstructionGenerator for PostFirmQuoteAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new_readonly(self.market_maker_badge, false),
+            AccountMeta::new(self.firm_quote, true),
+            AccountMeta::new_readonly(self.token_mint, false),
+            AccountMeta::new_readonly(self.escrow_authority, false),
+            AccountMeta::new(self.escrow_token_ata, false),
+            AccountMeta::new(self.market_maker_token_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts
+            .append_associated_token_program()
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::PostFirmQuote {
+            swap_type: self.swap_type,
+            base_amount: self.base_amount,
+            price: self.price,
+            expiry: self.expiry,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CancelFirmQuoteAction {
+    // Accounts
+    pub market: Pubkey,
+    pub firm_quote: Pubkey,
+    pub token_mint: Pubkey,
+    pub escrow_authority: Pubkey,
+    pub escrow_token_ata: Pubkey,
+    pub market_maker_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl CancelFirmQuoteAction {
+    pub fn new(post_firm_quote_action: &PostFirmQuoteAction) -> Self {
+        Self {
+            market: post_firm_quote_action.market,
+            firm_quote: post_firm_quote_action.firm_quote,
+            token_mint: post_firm_quote_action.token_mint,
+            escrow_authority: post_firm_quote_action.escrow_authority,
+            escrow_token_ata: post_firm_quote_action.escrow_token_ata,
+            market_maker_token_ata: post_firm_quote_action.market_maker_token_ata,
+            signer: post_firm_quote_action.signer,
+            token_program: post_firm_quote_action.token_program,
+        }
+    }
+}
+
+impl InstructionGenerator for CancelFirmQuoteAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.firm_quote, false),
+            AccountMeta::new_readonly(self.token_mint, false),
+            AccountMeta::new_readonly(self.escrow_authority, false),
+            AccountMeta::new(self.escrow_token_ata, false),
+            AccountMeta::new(self.market_maker_token_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CancelFirmQuote {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct GarbageCollectFirmQuoteAction {
+    // Accounts
+    pub market: Pubkey,
+    pub firm_quote: Pubkey,
+    pub token_mint: Pubkey,
+    pub escrow_authority: Pubkey,
+    pub escrow_token_ata: Pubkey,
+    pub market_maker_token_ata: Pubkey,
+    pub market_maker: Pubkey,
+    pub signer: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl GarbageCollectFirmQuoteAction {
+    pub fn new(post_firm_quote_action: &PostFirmQuoteAction) -> Self {
+        Self {
+            market: post_firm_quote_action.market,
+            firm_quote: post_firm_quote_action.firm_quote,
+            token_mint: post_firm_quote_action.token_mint,
+            escrow_authority: post_firm_quote_action.escrow_authority,
+            escrow_token_ata: post_firm_quote_action.escrow_token_ata,
+            market_maker_token_ata: post_firm_quote_action.market_maker_token_ata,
+            market_maker: post_firm_quote_action.signer,
+            signer: make_address("dave"),
+            token_program: post_firm_quote_action.token_program,
+        }
+    }
+}
+
+impl InstructionGenerator for GarbageCollectFirmQuoteAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.firm_quote, false),
+            AccountMeta::new_readonly(self.token_mint, false),
+            AccountMeta::new_readonly(self.escrow_authority, false),
+            AccountMeta::new(self.escrow_token_ata, false),
+            AccountMeta::new(self.market_maker_token_ata, false),
+            AccountMeta::new(self.market_maker, false),
+            AccountMeta::new(self.signer, true),
+        ];
+
+        match self.token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::GarbageCollectFirmQuote {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct FillFirmQuoteAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub firm_quote: Pubkey,
+    pub market_maker_badge: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub escrow_authority: Pubkey,
+    pub escrow_token_ata: Pubkey,
+    pub user_base_token_ata: Pubkey,
+    pub user_quote_token_ata: Pubkey,
+    pub market_maker_base_token_ata: Pubkey,
+    pub market_maker_quote_token_ata: Pubkey,
+    pub protocol_quote_token_ata: Pubkey,
+    pub quote_price_feed: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Args
+    pub base_amount: u64,
+    pub other_amount_threshold: u64,
+}
+
+impl FillFirmQuoteAction {
+    pub fn new(
+        token_mill_env: &TokenMillEnv,
+        post_firm_quote_action: &PostFirmQuoteAction,
+        base_amount: u64,
+    ) -> Self {
+        let swap_action = SwapAction::new(
+            token_mill_env,
+            post_firm_quote_action.swap_type,
+            SwapAmountType::ExactInput,
+            base_amount,
+            0,
+            None,
+        );
+        let market_maker = post_firm_quote_action.signer;
+
+        let other_amount_threshold = match post_firm_quote_action.swap_type {
+            SwapType::Buy => u64::MAX,
+            SwapType::Sell => 0,
+        };
+
+        Self {
+            config: swap_action.config,
+            market: swap_action.market,
+            firm_quote: post_firm_quote_action.firm_quote,
+            market_maker_badge: post_firm_quote_action.market_maker_badge,
+            base_token_mint: swap_action.base_token_mint,
+            quote_token_mint: swap_action.quote_token_mint,
+            escrow_authority: post_firm_quote_action.escrow_authority,
+            escrow_token_ata: post_firm_quote_action.escrow_token_ata,
+            user_base_token_ata: swap_action.user_base_token_ata,
+            user_quote_token_ata: swap_action.user_quote_token_ata,
+            market_maker_base_token_ata: get_associated_token_address_with_program_id(
+                &market_maker,
+                &swap_action.base_token_mint,
+                &spl_token_2022::id(),
+            ),
+            market_maker_quote_token_ata: get_associated_token_address_with_program_id(
+                &market_maker,
+                &swap_action.quote_token_mint,
+                &swap_action.quote_token_program,
+            ),
+            protocol_quote_token_ata: swap_action.protocol_quote_token_ata,
+            quote_price_feed: swap_action.quote_price_feed,
+            signer: swap_action.signer,
+            quote_token_program: swap_action.quote_token_program,
+            base_amount,
+            other_amount_threshold,
+        }
+    }
+}
+
+impl InstructionGenerator for FillFirmQuoteAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.firm_quote, false),
+            AccountMeta::new_readonly(self.market_maker_badge, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new_readonly(self.escrow_authority, false),
+            AccountMeta::new(self.escrow_token_ata, false),
+            AccountMeta::new(self.user_base_token_ata, false),
+            AccountMeta::new(self.user_quote_token_ata, false),
+            AccountMeta::new(self.market_maker_base_token_ata, false),
+            AccountMeta::new(self.market_maker_quote_token_ata, false),
+            AccountMeta::new(self.protocol_quote_token_ata, false),
+            AccountMeta::new_readonly(self.quote_price_feed, false),
+            AccountMeta::new_readonly(restricted_wallet_address(&self.market, &self.signer), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program();
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::FillFirmQuote {
+            base_amount: self.base_amount,
+            other_amount_threshold: self.other_amount_threshold,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct UpdateCreatorAction {
+    // Accounts
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub new_creator: Pubkey,
+}
+
+impl UpdateCreatorAction {
+    pub fn new(new_creator: Pubkey) -> Self {
+        let market = Pubkey::find_program_address(
+            &[
+                MARKET_PDA_SEED.as_bytes(),
+                &make_address("base_token_mint").to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            market,
+            signer: make_address("alice"),
+            new_creator,
+        }
+    }
+}
+
+impl InstructionGenerator for UpdateCreatorAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpdateCreator {
+            new_creator: self.new_creator,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct ProposeCreatorAction {
+    // Accounts
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub pending_creator: Option<Pubkey>,
+}
+
+impl ProposeCreatorAction {
+    pub fn new(pending_creator: Option<Pubkey>) -> Self {
+        let update_creator_action = UpdateCreatorAction::new(Pubkey::default());
+
+        Self {
+            market: update_creator_action.market,
+            signer: update_creator_action.signer,
+            pending_creator,
+        }
+    }
+}
+
+impl InstructionGenerator for ProposeCreatorAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::ProposeCreator {
+            pending_creator: self.pending_creator,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct AcceptCreatorAction {
+    // Accounts
+    pub market: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl AcceptCreatorAction {
+    pub fn new(pending_creator: Pubkey) -> Self {
+        let update_creator_action = UpdateCreatorAction::new(Pubkey::default());
+
+        Self {
+            market: update_creator_action.market,
+            signer: pending_creator,
+        }
+    }
+}
+
+impl InstructionGenerator for AcceptCreatorAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::AcceptCreator {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct UpdateMarketFeeSharesAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub new_creator_fee_share: u16,
+    pub new_staking_fee_share: u16,
+}
+
+impl UpdateMarketFeeSharesAction {
+    pub fn new(new_creator_fee_share: u16, new_staking_fee_share: u16) -> Self {
+        let market = Pubkey::find_program_address(
+            &[
+                MARKET_PDA_SEED.as_bytes(),
+                &make_address("base_token_mint").to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            config: make_address("config"),
+            market,
+            signer: make_address("alice"),
+            new_creator_fee_share,
+            new_staking_fee_share,
+        }
+    }
+}
+
+impl InstructionGenerator for UpdateMarketFeeSharesAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpdateMarketFeeShares {
+            new_creator_fee_share: self.new_creator_fee_share,
+            new_staking_fee_share: self.new_staking_fee_share,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct ActivateMarketFeeSharesAction {
+    // Accounts
+    pub market: Pubkey,
+}
+
+impl ActivateMarketFeeSharesAction {
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        Self {
+            market: token_mill_env.market,
+        }
+    }
+}
+
+impl InstructionGenerator for ActivateMarketFeeSharesAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::ActivateMarketFeeShares {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetMaxRaiseAction {
+    // Accounts
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub max_raise: u64,
+}
+
+impl SetMaxRaiseAction {
+    pub fn new(max_raise: u64) -> Self {
+        let market = Pubkey::find_program_address(
+            &[
+                MARKET_PDA_SEED.as_bytes(),
+                &make_address("base_token_mint").to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            market,
+            signer: make_address("alice"),
+            max_raise,
+        }
+    }
+}
+
+impl InstructionGenerator for SetMaxRaiseAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetMaxRaise {
+            max_raise: self.max_raise,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetVirtualSupplyAction {
+    // Accounts
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub virtual_supply: u64,
+}
+
+impl SetVirtualSupplyAction {
+    pub fn new(virtual_supply: u64) -> Self {
+        let market = Pubkey::find_program_address(
+            &[
+                MARKET_PDA_SEED.as_bytes(),
+                &make_address("base_token_mint").to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            market,
+            signer: make_address("alice"),
+            virtual_supply,
+        }
+    }
+}
+
+impl InstructionGenerator for SetVirtualSupplyAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetVirtualSupply {
+            virtual_supply: self.virtual_supply,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetGraduationSupplyAction {
+    // Accounts
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub graduation_supply: u64,
+}
+
+impl SetGraduationSupplyAction {
+    pub fn new(testing_env: &TokenMillEnv, graduation_supply: u64) -> Self {
+        Self {
+            market: testing_env.market,
+            signer: make_address("alice"),
+            graduation_supply,
+        }
+    }
+}
+
+impl InstructionGenerator for SetGraduationSupplyAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetGraduationSupply {
+            graduation_supply: self.graduation_supply,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetSecondaryCurveAction {
+    // Accounts
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub price: u64,
+}
+
+impl SetSecondaryCurveAction {
+    pub fn new(testing_env: &TokenMillEnv, price: u64) -> Self {
+        Self {
+            market: testing_env.market,
+            signer: make_address("alice"),
+            price,
+        }
+    }
+}
+
+impl InstructionGenerator for SetSecondaryCurveAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetSecondaryCurve { price: self.price };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetBurnFeeShareAction {
+    // Accounts
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub burn_fee_share: u16,
+}
+
+impl SetBurnFeeShareAction {
+    pub fn new(testing_env: &TokenMillEnv, burn_fee_share: u16) -> Self {
+        Self {
+            market: testing_env.market,
+            signer: make_address("alice"),
+            burn_fee_share,
+        }
+    }
+}
+
+impl InstructionGenerator for SetBurnFeeShareAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetBurnFeeShare {
+            burn_fee_share: self.burn_fee_share,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetImpactFeeAction {
+    // Accounts
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub max_impact_fee_bps: u16,
+    pub impact_fee_threshold_bps: u16,
+    pub impact_fee_rate_bps: u16,
+}
+
+impl SetImpactFeeAction {
+    pub fn new(
+        testing_env: &TokenMillEnv,
+        max_impact_fee_bps: u16,
+        impact_fee_threshold_bps: u16,
+        impact_fee_rate_bps: u16,
+    ) -> Self {
+        Self {
+            market: testing_env.market,
+            signer: make_address("alice"),
+            max_impact_fee_bps,
+            impact_fee_threshold_bps,
+            impact_fee_rate_bps,
+        }
+    }
+}
+
+impl InstructionGenerator for SetImpactFeeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetImpactFee {
+            max_impact_fee_bps: self.max_impact_fee_bps,
+            impact_fee_threshold_bps: self.impact_fee_threshold_bps,
+            impact_fee_rate_bps: self.impact_fee_rate_bps,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetLaunchFeeAction {
+    // Accounts
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub launch_fee_bps: u16,
+    pub duration: u32,
+    pub recipient: LaunchFeeRecipient,
+}
+
+impl SetLaunchFeeAction {
+    pub fn new(
+        testing_env: &TokenMillEnv,
+        launch_fee_bps: u16,
+        duration: u32,
+        recipient: LaunchFeeRecipient,
+    ) -> Self {
+        Self {
+            market: testing_env.market,
+            signer: make_address("alice"),
+            launch_fee_bps,
+            duration,
+            recipient,
+        }
+    }
+}
+
+impl InstructionGenerator for SetLaunchFeeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetLaunchFee {
+            launch_fee_bps: self.launch_fee_bps,
+            duration: self.duration,
+            recipient: self.recipient,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetIncentiveWeightsAction {
+    // Accounts
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub buy_weight: u16,
+    pub sell_weight: u16,
+    pub stake_weight: u16,
+}
+
+impl SetIncentiveWeightsAction {
+    pub fn new(buy_weight: u16, sell_weight: u16, stake_weight: u16) -> Self {
+        let market = Pubkey::find_program_address(
+            &[
+                MARKET_PDA_SEED.as_bytes(),
+                &make_address("base_token_mint").to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            market,
+            signer: make_address("alice"),
+            buy_weight,
+            sell_weight,
+            stake_weight,
+        }
+    }
+}
+
+impl InstructionGenerator for SetIncentiveWeightsAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetIncentiveWeights {
+            buy_weight: self.buy_weight,
+            sell_weight: self.sell_weight,
+            stake_weight: self.stake_weight,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetMaxSlotPriceMoveAction {
+    // Accounts
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub max_slot_price_move_bps: u16,
+}
+
+impl SetMaxSlotPriceMoveAction {
+    pub fn new(max_slot_price_move_bps: u16) -> Self {
+        let market = Pubkey::find_program_address(
+            &[
+                MARKET_PDA_SEED.as_bytes(),
+                &make_address("base_token_mint").to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            market,
+            signer: make_address("alice"),
+            max_slot_price_move_bps,
+        }
+    }
+}
+
+impl InstructionGenerator for SetMaxSlotPriceMoveAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetMaxSlotPriceMove {
+            max_slot_price_move_bps: self.max_slot_price_move_bps,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct PauseSellsAction {
+    // Accounts
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub duration: i64,
+}
+
+impl PauseSellsAction {
+    pub fn new(duration: i64) -> Self {
+        let market = Pubkey::find_program_address(
+            &[
+                MARKET_PDA_SEED.as_bytes(),
+                &make_address("base_token_mint").to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            market,
+            signer: make_address("alice"),
+            duration,
+        }
+    }
+}
+
+impl InstructionGenerator for PauseSellsAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::PauseSells {
+            duration: self.duration,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct RestrictWalletAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub restricted_wallet: Pubkey,
+    pub wallet: Pubkey,
+    pub wallet_base_token_ata: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl RestrictWalletAction {
+    pub fn new(testing_env: &TokenMillEnv, wallet: Pubkey) -> Self {
+        let base_token_mint = testing_env.base_token_mint.unwrap();
+        let market = testing_env.market;
+
+        Self {
+            config: testing_env.config,
+            market,
+            restricted_wallet: restricted_wallet_address(&market, &wallet),
+            wallet,
+            wallet_base_token_ata: get_associated_token_address_with_program_id(
+                &wallet,
+                &base_token_mint,
+                &spl_token_2022::id(),
+            ),
+            signer: make_address("admin"),
+        }
+    }
+}
+
+impl InstructionGenerator for RestrictWalletAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.restricted_wallet, false),
+            AccountMeta::new_readonly(self.wallet, false),
+            AccountMeta::new_readonly(self.wallet_base_token_ata, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+            AccountMeta::new(self.signer, true),
+        ];
+
+        accounts
+            .append_token_2022_program()
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::RestrictWallet {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct LiftWalletRestrictionAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub restricted_wallet: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl LiftWalletRestrictionAction {
+    pub fn new(testing_env: &TokenMillEnv, wallet: Pubkey) -> Self {
+        Self {
+            config: testing_env.config,
+            market: testing_env.market,
+            restricted_wallet: restricted_wallet_address(&testing_env.market, &wallet),
+            signer: make_address("admin"),
+        }
+    }
+}
+
+impl InstructionGenerator for LiftWalletRestrictionAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.restricted_wallet, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+            AccountMeta::new(self.signer, true),
+        ];
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::LiftWalletRestriction {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct OverrideSellPauseAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl Default for OverrideSellPauseAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OverrideSellPauseAction {
+    pub fn new() -> Self {
+        let market = Pubkey::find_program_address(
+            &[
+                MARKET_PDA_SEED.as_bytes(),
+                &make_address("base_token_mint").to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            config: make_address("config"),
+            market,
+            signer: make_address("admin"),
+        }
+    }
+}
+
+impl InstructionGenerator for OverrideSellPauseAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::OverrideSellPause {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetMarketPausedAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub paused: bool,
+}
+
+impl SetMarketPausedAction {
+    pub fn new(paused: bool) -> Self {
+        let override_sell_pause_action = OverrideSellPauseAction::new();
+
+        Self {
+            config: override_sell_pause_action.config,
+            market: override_sell_pause_action.market,
+            signer: override_sell_pause_action.signer,
+            paused,
+        }
+    }
+}
+
+impl InstructionGenerator for SetMarketPausedAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetMarketPaused {
+            paused: self.paused,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetQuotePriceFeedAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub quote_price_feed: Pubkey,
+    pub max_quote_depeg_bps: u16,
+}
+
+impl SetQuotePriceFeedAction {
+    pub fn new(
+        testing_env: &TokenMillEnv,
+        quote_price_feed: Pubkey,
+        max_quote_depeg_bps: u16,
+    ) -> Self {
+        Self {
+            config: testing_env.config,
+            market: testing_env.market,
+            signer: make_address("admin"),
+            quote_price_feed,
+            max_quote_depeg_bps,
+        }
+    }
+}
+
+impl InstructionGenerator for SetQuotePriceFeedAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetQuotePriceFeed {
+            quote_price_feed: self.quote_price_feed,
+            max_quote_depeg_bps: self.max_quote_depeg_bps,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetMarketReferralFeeShareAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub referral_fee_share: Option<u16>,
+}
+
+impl SetMarketReferralFeeShareAction {
+    pub fn new(testing_env: &TokenMillEnv, referral_fee_share: Option<u16>) -> Self {
+        Self {
+            config: testing_env.config,
+            market: testing_env.market,
+            signer: make_address("admin"),
+            referral_fee_share,
+        }
+    }
+}
+
+impl InstructionGenerator for SetMarketReferralFeeShareAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetMarketReferralFeeShare {
+            referral_fee_share: self.referral_fee_share,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CreateChangeLogAction {
+    // Accounts
+    pub target: Pubkey,
+    pub change_log: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl CreateChangeLogAction {
+    pub fn new(target: Pubkey) -> Self {
+        Self {
+            target,
+            change_log: change_log_address(&target),
+            signer: make_address("admin"),
+        }
+    }
+}
+
+impl InstructionGenerator for CreateChangeLogAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.target, false),
+            AccountMeta::new(self.change_log, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateChangeLog {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub fn graduation_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[GRADUATION_PDA_SEED.as_bytes(), &market.to_bytes()],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn graduation_authority_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[GRADUATION_AUTHORITY_PDA_SEED.as_bytes(), &market.to_bytes()],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn firm_quote_escrow_address(firm_quote: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            FIRM_QUOTE_ESCROW_PDA_SEED.as_bytes(),
+            &firm_quote.to_bytes(),
+        ],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn market_oracle_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[MARKET_ORACLE_PDA_SEED.as_bytes(), &market.to_bytes()],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn migration_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[MIGRATION_PDA_SEED.as_bytes(), &market.to_bytes()],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn migration_claim_address(migration: &Pubkey, holder: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            MIGRATION_CLAIM_PDA_SEED.as_bytes(),
+            &migration.to_bytes(),
+            &holder.to_bytes(),
+        ],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn trader_stats_address(config: &Pubkey, quote_token_mint: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            TRADER_STATS_PDA_SEED.as_bytes(),
+            &config.to_bytes(),
+            &quote_token_mint.to_bytes(),
+            &user.to_bytes(),
+        ],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn dca_position_address(market: &Pubkey, owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            DCA_POSITION_PDA_SEED.as_bytes(),
+            &market.to_bytes(),
+            &owner.to_bytes(),
+        ],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn swap_delegate_address(market: &Pubkey, owner: &Pubkey, delegate: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            SWAP_DELEGATE_PDA_SEED.as_bytes(),
+            &market.to_bytes(),
+            &owner.to_bytes(),
+            &delegate.to_bytes(),
+        ],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn restricted_wallet_address(market: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            RESTRICTED_WALLET_PDA_SEED.as_bytes(),
+            &market.to_bytes(),
+            &wallet.to_bytes(),
+        ],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn referral_code_address(config: &Pubkey, code: &str) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            REFERRAL_CODE_PDA_SEED.as_bytes(),
+            &config.to_bytes(),
+            code.as_bytes(),
+        ],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn change_log_address(target: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[CHANGE_LOG_PDA_SEED.as_bytes(), &target.to_bytes()],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn creator_fee_split_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[CREATOR_FEE_SPLIT_PDA_SEED.as_bytes(), &market.to_bytes()],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub struct DepositAdditionalSupplyAction {
+    // Accounts
+    pub market: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub creator_base_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub base_token_program: Pubkey,
+    // Args
+    pub amount: u64,
+}
+
+impl DepositAdditionalSupplyAction {
+    pub fn new(token_mill_env: &TokenMillEnv, amount: u64) -> Self {
+        let base_token_mint = token_mill_env.base_token_mint.unwrap();
+
+        let market = Pubkey::find_program_address(
+            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
+
+        let signer = make_address("alice");
+
+        let base_token_program = token_mill_env
+            .svm
+            .tokens
+            .get(&base_token_mint)
+            .unwrap()
+            .program_address();
+
+        let market_base_token_ata = get_associated_token_address_with_program_id(
+            &market,
+            &base_token_mint,
+            &base_token_program,
+        );
+
+        let creator_base_token_ata = get_associated_token_address_with_program_id(
+            &signer,
+            &base_token_mint,
+            &base_token_program,
+        );
+
+        Self {
+            market,
+            base_token_mint,
+            market_base_token_ata,
+            creator_base_token_ata,
+            signer,
+            base_token_program,
+            amount,
+        }
+    }
+}
+
+impl InstructionGenerator for DepositAdditionalSupplyAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.creator_base_token_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.base_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::DepositAdditionalSupply {
+            amount: self.amount,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct ImportMigrationAction {
+    // Accounts
+    pub market: Pubkey,
+    pub migration: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub creator_base_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub base_token_program: Pubkey,
+    // Args
+    pub source: Pubkey,
+    pub snapshot_slot: u64,
+    pub holders_root: [u8; 32],
+    pub claim_supply: u64,
+}
+
+impl ImportMigrationAction {
+    pub fn new(token_mill_env: &TokenMillEnv, holders_root: [u8; 32], claim_supply: u64) -> Self {
+        let base_token_mint = token_mill_env.base_token_mint.unwrap();
+        let market = token_mill_env.market;
+        let signer = make_address("alice");
+
+        let base_token_program = token_mill_env
+            .svm
+            .tokens
+            .get(&base_token_mint)
+            .unwrap()
+            .program_address();
+
+        Self {
+            market,
+            migration: migration_address(&market),
+            base_token_mint,
+            market_base_token_ata: get_associated_token_address_with_program_id(
+                &market,
+                &base_token_mint,
+                &base_token_program,
+            ),
+            creator_base_token_ata: get_associated_token_address_with_program_id(
+                &signer,
+                &base_token_mint,
+                &base_token_program,
+            ),
+            signer,
+            base_token_program,
+            source: make_address("source_launchpad"),
+            snapshot_slot: token_mill_env.svm.get_clock().slot,
+            holders_root,
+            claim_supply,
+        }
+    }
+}
+
+impl InstructionGenerator for ImportMigrationAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.migration, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.creator_base_token_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.base_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::ImportMigration {
+            source: self.source,
+            snapshot_slot: self.snapshot_slot,
+            holders_root: self.holders_root,
+            claim_supply: self.claim_supply,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct ClaimMigrationAction {
+    // Accounts
+    pub market: Pubkey,
+    pub migration: Pubkey,
+    pub migration_claim: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub holder_base_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub base_token_program: Pubkey,
+    // Args
+    pub amount: u64,
+    pub proof: Vec<[u8; 32]>,
+}
+
+impl ClaimMigrationAction {
+    pub fn new(token_mill_env: &TokenMillEnv, amount: u64, proof: Vec<[u8; 32]>) -> Self {
+        let base_token_mint = token_mill_env.base_token_mint.unwrap();
+        let market = token_mill_env.market;
+        let migration = migration_address(&market);
+        let signer = make_address("bob");
+
+        let base_token_program = token_mill_env
+            .svm
+            .tokens
+            .get(&base_token_mint)
+            .unwrap()
+            .program_address();
+
+        Self {
+            market,
+            migration,
+            migration_claim: migration_claim_address(&migration, &signer),
+            base_token_mint,
+            market_base_token_ata: get_associated_token_address_with_program_id(
+                &market,
+                &base_token_mint,
+                &base_token_program,
+            ),
+            holder_base_token_ata: get_associated_token_address_with_program_id(
+                &signer,
+                &base_token_mint,
+                &base_token_program,
+            ),
+            signer,
+            base_token_program,
+            amount,
+            proof,
+        }
+    }
+}
+
+impl InstructionGenerator for ClaimMigrationAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.migration, false),
+            AccountMeta::new(self.migration_claim, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.holder_base_token_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.base_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::ClaimMigration {
+            amount: self.amount,
+            proof: self.proof.clone(),
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct ClaimCreatorFeesAction {
+    // Accounts
+    pub market: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub creator_quote_token_ata: Pubkey,
+    pub creator_fee_split: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Remaining accounts
+    pub recipient_quote_token_atas: Vec<Pubkey>,
+}
+
+impl ClaimCreatorFeesAction {
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        let market = Pubkey::find_program_address(
+            &[
+                MARKET_PDA_SEED.as_bytes(),
+                &token_mill_env.base_token_mint.unwrap().to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        let signer = make_address("alice");
+
+        let quote_token_mint = token_mill_env.quote_token_mint.unwrap();
+        let quote_token_program = token_mill_env.quote_token_type.program_address();
+
+        let market_quote_token_ata = get_associated_token_address_with_program_id(
+            &market,
+            &quote_token_mint,
+            &quote_token_program,
+        );
+
+        let creator_quote_token_ata = get_associated_token_address_with_program_id(
+            &signer,
+            &quote_token_mint,
+            &quote_token_program,
+        );
+
+        Self {
+            market,
+            quote_token_mint,
+            market_quote_token_ata,
+            creator_quote_token_ata,
+            creator_fee_split: token_mill::ID,
+            signer,
+            quote_token_program,
+            recipient_quote_token_atas: Vec::new(),
+        }
+    }
+
+    /// Claims the fees to `token_account` instead of the quote token ATA of the creator
+    pub fn with_recipient(&mut self, token_account: Pubkey) -> &mut Self {
+        self.creator_quote_token_ata = token_account;
+
+        self
+    }
+
+    /// Claims the fees to the quote token ATAs of `recipients`, in the order of the split
+    pub fn with_creator_fee_split(&mut self, recipients: &[Pubkey]) -> &mut Self {
+        self.creator_fee_split = creator_fee_split_address(&self.market);
+        self.recipient_quote_token_atas = recipients
+            .iter()
+            .map(|recipient| {
+                get_associated_token_address_with_program_id(
+                    recipient,
+                    &self.quote_token_mint,
+                    &self.quote_token_program,
+                )
+            })
+            .collect();
+
+        self
+    }
+}
+
+impl InstructionGenerator for ClaimCreatorFeesAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.creator_quote_token_ata, false),
+            AccountMeta::new_readonly(self.creator_fee_split, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts.extend(
+            self.recipient_quote_token_atas
+                .iter()
+                .map(|ata| AccountMeta::new(*ata, false)),
+        );
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::ClaimCreatorFees {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct ProposeCloseMarketAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl ProposeCloseMarketAction {
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        Self {
+            config: token_mill_env.config,
+            market: token_mill_env.market,
+            signer: make_address("alice"),
+        }
+    }
+}
+
+impl InstructionGenerator for ProposeCloseMarketAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new_readonly(self.signer, true),
+        ];
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::ProposeCloseMarket {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CloseMarketAction {
+    // Accounts
+    pub market: Pubkey,
+    pub graduation: Pubkey,
+    pub market_oracle: Pubkey,
+    pub market_staking: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub creator_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+}
+
+impl CloseMarketAction {
+    /// Closes the market without its graduation, see `with_graduation`
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        let swap_action = SwapAction::new(
+            token_mill_env,
+            SwapType::Buy,
+            SwapAmountType::ExactInput,
+            0,
+            0,
+            None,
+        );
+
+        let signer = make_address("alice");
+
+        Self {
+            market: swap_action.market,
+            graduation: token_mill::ID,
+            market_oracle: market_oracle_address(&swap_action.market),
+            market_staking: market_staking_address(&swap_action.market),
+            base_token_mint: swap_action.base_token_mint,
+            quote_token_mint: swap_action.quote_token_mint,
+            market_base_token_ata: swap_action.market_base_token_ata,
+            market_quote_token_ata: swap_action.market_quote_token_ata,
+            creator_quote_token_ata: get_associated_token_address_with_program_id(
+                &signer,
+                &swap_action.quote_token_mint,
+                &swap_action.quote_token_program,
+            ),
+            signer,
+            quote_token_program: swap_action.quote_token_program,
+        }
+    }
+
+    pub fn with_graduation(&mut self) -> &mut Self {
+        self.graduation = graduation_address(&self.market);
+
+        self
+    }
+}
+
+impl InstructionGenerator for CloseMarketAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new_readonly(self.graduation, false),
+            AccountMeta::new(self.market_oracle, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.base_token_mint, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.creator_quote_token_ata, false),
+            AccountMeta::new(self.signer, true),
+        ];
+
+        accounts.append_token_2022_program();
+
+        accounts.push(AccountMeta::new_readonly(self.quote_token_program, false));
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CloseMarket {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct ClaimAllCreatorFeesAction {
+    // Accounts
+    pub quote_token_mint: Pubkey,
+    pub creator_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Remaining accounts
+    pub markets: Vec<Pubkey>,
+}
+
+impl ClaimAllCreatorFeesAction {
+    pub fn new(token_mill_env: &TokenMillEnv, markets: Vec<Pubkey>) -> Self {
+        let signer = make_address("alice");
+
+        let quote_token_mint = token_mill_env.quote_token_mint.unwrap();
+        let quote_token_program = token_mill_env.quote_token_type.program_address();
+
+        let creator_quote_token_ata = get_associated_token_address_with_program_id(
+            &signer,
+            &quote_token_mint,
+            &quote_token_program,
+        );
+
+        Self {
+            quote_token_mint,
+            creator_quote_token_ata,
+            signer,
+            quote_token_program,
+            markets,
+        }
+    }
+}
+
+impl InstructionGenerator for ClaimAllCreatorFeesAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.creator_quote_token_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        for market in &self.markets {
+            accounts.push(AccountMeta::new(*market, false));
+            accounts.push(AccountMeta::new(
+                get_associated_token_address_with_program_id(
+                    market,
+                    &self.quote_token_mint,
+                    &self.quote_token_program,
+                ),
+                false,
+            ));
+        }
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::ClaimAllCreatorFees {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetCreatorFeeSplitAction {
+    // Accounts
+    pub market: Pubkey,
+    pub creator_fee_split: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub recipients: [CreatorFeeRecipient; CREATOR_FEE_RECIPIENTS_LENGTH],
+}
+
+impl SetCreatorFeeSplitAction {
+    pub fn new(
+        testing_env: &TokenMillEnv,
+        recipients: [CreatorFeeRecipient; CREATOR_FEE_RECIPIENTS_LENGTH],
+    ) -> Self {
+        Self {
+            market: testing_env.market,
+            creator_fee_split: creator_fee_split_address(&testing_env.market),
+            signer: make_address("alice"),
+            recipients,
+        }
+    }
+}
+
+impl InstructionGenerator for SetCreatorFeeSplitAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.creator_fee_split, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetCreatorFeeSplit {
+            recipients: self.recipients,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct ClaimProtocolFeesAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub authority_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+}
+
+pub struct CreateStakingAction {
+    // Accounts
+    pub market: Pubkey,
+    pub staking: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl CreateStakingAction {
+    pub fn new(testing_env: &TokenMillEnv) -> Self {
+        let market = Pubkey::find_program_address(
+            &[
+                MARKET_PDA_SEED.as_bytes(),
+                &testing_env.base_token_mint.unwrap().to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        let staking = Pubkey::find_program_address(
+            &[MARKET_STAKING_PDA_SEED.as_bytes(), &market.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            market,
+            staking,
+            signer: make_address("admin"),
+        }
+    }
+}
+
+impl InstructionGenerator for CreateStakingAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.staking, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateStaking {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CreateStakePositionAction {
+    // Accounts
+    pub market: Pubkey,
+    pub stake_position: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl CreateStakePositionAction {
+    pub fn new(testing_env: &TokenMillEnv) -> Self {
+        let market = Pubkey::find_program_address(
+            &[
+                MARKET_PDA_SEED.as_bytes(),
+                &testing_env.base_token_mint.unwrap().to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        let signer: Pubkey = make_address("bob");
+
+        let stake_position = Pubkey::find_program_address(
+            &[
+                STAKING_POSITION_PDA_SEED.as_bytes(),
+                &market.to_bytes(),
+                &signer.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            market,
+            stake_position,
+            signer,
+        }
+    }
+
+    pub fn with_user(&mut self, user: Pubkey) -> &mut Self {
+        self.signer = user;
+        self.stake_position = stake_position_address(&self.market, &user);
+
+        self
+    }
+}
+
+impl InstructionGenerator for CreateStakePositionAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.stake_position, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateStakePosition {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct DepositAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub stake_position: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub user_base_token_ata: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub amount: u64,
+}
+
+impl DepositAction {
+    pub fn new(testing_env: &TokenMillEnv, amount: u64) -> Self {
+        let signer = make_address("bob");
+        let base_token_mint = testing_env.base_token_mint.unwrap();
+
+        let market = Pubkey::find_program_address(
+            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
+
+        let market_staking = Pubkey::find_program_address(
+            &[MARKET_STAKING_PDA_SEED.as_bytes(), &market.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
+
+        let stake_position = Pubkey::find_program_address(
+            &[
+                STAKING_POSITION_PDA_SEED.as_bytes(),
+                &market.to_bytes(),
+                &signer.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        let market_base_token_ata = get_associated_token_address_with_program_id(
+            &market,
+            &base_token_mint,
+            &spl_token_2022::id(),
+        );
+
+        let user_base_token_ata = get_associated_token_address_with_program_id(
+            &signer,
+            &base_token_mint,
+            &spl_token_2022::id(),
+        );
+
+        Self {
+            market,
+            market_staking,
+            stake_position,
+            base_token_mint,
+            market_base_token_ata,
+            user_base_token_ata,
+            signer,
+            amount,
+        }
+    }
+
+    pub fn with_user(&mut self, user: Pubkey) -> &mut Self {
+        self.signer = user;
+        self.stake_position = stake_position_address(&self.market, &user);
+        self.user_base_token_ata = get_associated_token_address_with_program_id(
+            &user,
+            &self.base_token_mint,
+            &spl_token_2022::id(),
+        );
+
+        self
+    }
+}
+
+impl InstructionGenerator for DepositAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.user_base_token_ata, false),
+            AccountMeta::new_readonly(restricted_wallet_address(&self.market, &self.signer), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::Deposit {
+            amount: self.amount,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct BuyAndStakeAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub staking: Pubkey,
+    pub stake_position: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub user_quote_token_ata: Pubkey,
+    pub protocol_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Args
+    pub swap_amount_type: SwapAmountType,
+    pub amount: u64,
+    pub other_amount_threshold: u64,
+}
+
+impl BuyAndStakeAction {
+    pub fn new(
+        testing_env: &TokenMillEnv,
+        swap_amount_type: SwapAmountType,
+        amount: u64,
+        other_amount_threshold: u64,
+    ) -> Self {
+        let swap_action = SwapAction::new(
+            testing_env,
+            SwapType::Buy,
+            swap_amount_type,
+            amount,
+            other_amount_threshold,
+            None,
+        );
+
+        let staking = Pubkey::find_program_address(
+            &[
+                MARKET_STAKING_PDA_SEED.as_bytes(),
+                &swap_action.market.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        Self {
+            config: swap_action.config,
+            market: swap_action.market,
+            staking,
+            stake_position: stake_position_address(&swap_action.market, &swap_action.signer),
+            base_token_mint: swap_action.base_token_mint,
+            quote_token_mint: swap_action.quote_token_mint,
+            market_quote_token_ata: swap_action.market_quote_token_ata,
+            user_quote_token_ata: swap_action.user_quote_token_ata,
+            protocol_quote_token_ata: swap_action.protocol_quote_token_ata,
+            signer: swap_action.signer,
+            quote_token_program: swap_action.quote_token_program,
+            swap_amount_type,
+            amount,
+            other_amount_threshold,
+        }
+    }
+}
+
+impl InstructionGenerator for BuyAndStakeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.staking, false),
+            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.user_quote_token_ata, false),
+            AccountMeta::new(self.protocol_quote_token_ata, false),
+            AccountMeta::new_readonly(token_mill::ID, false),
+            AccountMeta::new(market_oracle_address(&self.market), false),
+            AccountMeta::new_readonly(restricted_wallet_address(&self.market, &self.signer), false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::BuyAndStake {
+            swap_amount_type: self.swap_amount_type,
+            amount: self.amount,
+            other_amount_threshold: self.other_amount_threshold,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateStakingCheckpointsAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub staking_checkpoints: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl CreateStakingCheckpointsAction {
+    pub fn new(testing_env: &TokenMillEnv) -> Self {
+        let market = testing_env.market;
+
+        Self {
+            market,
+            market_staking: market_staking_address(&market),
+            staking_checkpoints: staking_checkpoints_address(&market),
+            signer: make_address("bob"),
+        }
+    }
+}
+
+impl InstructionGenerator for CreateStakingCheckpointsAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new_readonly(self.market_staking, false),
+            AccountMeta::new(self.staking_checkpoints, false),
+        ];
+
+        accounts.append_payer(self.signer).append_system_program();
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateStakingCheckpoints {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CheckpointStakingEpochAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub staking_checkpoints: Pubkey,
+}
+
+impl CheckpointStakingEpochAction {
+    pub fn new(testing_env: &TokenMillEnv) -> Self {
+        let market = testing_env.market;
+
+        Self {
+            market,
+            market_staking: market_staking_address(&market),
+            staking_checkpoints: staking_checkpoints_address(&market),
+        }
+    }
+}
+
+impl InstructionGenerator for CheckpointStakingEpochAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.staking_checkpoints, false),
+        ];
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CheckpointStakingEpoch {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateCompoundingVaultAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub compounding_vault: Pubkey,
+    pub vault_stake_position: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl CreateCompoundingVaultAction {
+    pub fn new(testing_env: &TokenMillEnv) -> Self {
+        let market = testing_env.market;
+        let compounding_vault = compounding_vault_address(&market);
+
+        Self {
+            market,
+            market_staking: market_staking_address(&market),
+            compounding_vault,
+            vault_stake_position: stake_position_address(&market, &compounding_vault),
+            signer: make_address("bob"),
+        }
+    }
+}
+
+impl InstructionGenerator for CreateCompoundingVaultAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new_readonly(self.market_staking, false),
+            AccountMeta::new(self.compounding_vault, false),
+            AccountMeta::new(self.vault_stake_position, false),
+        ];
+
+        accounts.append_payer(self.signer).append_system_program();
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateCompoundingVault {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DepositToCompoundingVaultAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub compounding_vault: Pubkey,
+    pub vault_stake_position: Pubkey,
+    pub compounding_position: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub user_base_token_ata: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub amount: u64,
+}
+
+impl DepositToCompoundingVaultAction {
+    pub fn new(testing_env: &TokenMillEnv, amount: u64) -> Self {
+        let deposit_action = DepositAction::new(testing_env, amount);
+        let compounding_vault = compounding_vault_address(&deposit_action.market);
+
+        Self {
+            market: deposit_action.market,
+            market_staking: deposit_action.market_staking,
+            compounding_vault,
+            vault_stake_position: stake_position_address(
+                &deposit_action.market,
+                &compounding_vault,
+            ),
+            compounding_position: compounding_position_address(
+                &compounding_vault,
+                &deposit_action.signer,
+            ),
+            base_token_mint: deposit_action.base_token_mint,
+            market_base_token_ata: deposit_action.market_base_token_ata,
+            user_base_token_ata: deposit_action.user_base_token_ata,
+            signer: deposit_action.signer,
+            amount,
+        }
+    }
+
+    pub fn with_user(&mut self, user: Pubkey) -> &mut Self {
+        self.signer = user;
+        self.compounding_position = compounding_position_address(&self.compounding_vault, &user);
+        self.user_base_token_ata = get_associated_token_address_with_program_id(
+            &user,
+            &self.base_token_mint,
+            &spl_token_2022::id(),
+        );
+
+        self
+    }
+}
+
+impl InstructionGenerator for DepositToCompoundingVaultAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.compounding_vault, false),
+            AccountMeta::new(self.vault_stake_position, false),
+            AccountMeta::new(self.compounding_position, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.user_base_token_ata, false),
+            AccountMeta::new_readonly(restricted_wallet_address(&self.market, &self.signer), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program()
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::DepositToCompoundingVault {
+            amount: self.amount,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct WithdrawFromCompoundingVaultAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub compounding_vault: Pubkey,
+    pub vault_stake_position: Pubkey,
+    pub compounding_position: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub user_base_token_ata: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub shares: u64,
+}
+
+impl WithdrawFromCompoundingVaultAction {
+    pub fn new(testing_env: &TokenMillEnv, shares: u64) -> Self {
+        let deposit_action = DepositToCompoundingVaultAction::new(testing_env, shares);
+
+        Self {
+            market: deposit_action.market,
+            market_staking: deposit_action.market_staking,
+            compounding_vault: deposit_action.compounding_vault,
+            vault_stake_position: deposit_action.vault_stake_position,
+            compounding_position: deposit_action.compounding_position,
+            base_token_mint: deposit_action.base_token_mint,
+            market_base_token_ata: deposit_action.market_base_token_ata,
+            user_base_token_ata: deposit_action.user_base_token_ata,
+            signer: deposit_action.signer,
+            shares,
+        }
+    }
+}
+
+impl InstructionGenerator for WithdrawFromCompoundingVaultAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.compounding_vault, false),
+            AccountMeta::new(self.vault_stake_position, false),
+            AccountMeta::new(self.compounding_position, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.user_base_token_ata, false),
+            AccountMeta::new_readonly(restricted_wallet_address(&self.market, &self.signer), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program()
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::WithdrawFromCompoundingVault {
+            shares: self.shares,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CompoundStakingRewardsAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub compounding_vault: Pubkey,
+    pub vault_stake_position: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub protocol_quote_token_ata: Pubkey,
+    pub cranker_quote_token_account: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+}
+
+impl CompoundStakingRewardsAction {
+    pub fn new(testing_env: &TokenMillEnv) -> Self {
+        let swap_action = SwapAction::new(
+            testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactInput,
+            0,
+            0,
+            None,
+        );
+        let compounding_vault = compounding_vault_address(&swap_action.market);
+
+        Self {
+            config: swap_action.config,
+            market: swap_action.market,
+            market_staking: market_staking_address(&swap_action.market),
+            compounding_vault,
+            vault_stake_position: stake_position_address(&swap_action.market, &compounding_vault),
+            quote_token_mint: swap_action.quote_token_mint,
+            market_quote_token_ata: swap_action.market_quote_token_ata,
+            protocol_quote_token_ata: swap_action.protocol_quote_token_ata,
+            cranker_quote_token_account: swap_action.user_quote_token_ata,
+            signer: swap_action.signer,
+            quote_token_program: swap_action.quote_token_program,
+        }
+    }
+
+    pub fn with_cranker(&mut self, cranker: Pubkey) -> &mut Self {
+        self.signer = cranker;
+        self.cranker_quote_token_account = get_associated_token_address_with_program_id(
+            &cranker,
+            &self.quote_token_mint,
+            &self.quote_token_program,
+        );
+
+        self
+    }
+}
+
+impl InstructionGenerator for CompoundStakingRewardsAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.compounding_vault, false),
+            AccountMeta::new(self.vault_stake_position, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.protocol_quote_token_ata, false),
+            AccountMeta::new(self.cranker_quote_token_account, false),
+            AccountMeta::new_readonly(token_mill::ID, false),
+            AccountMeta::new(market_oracle_address(&self.market), false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CompoundStakingRewards {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CreateSwapDelegateAction {
+    // Accounts
+    pub market: Pubkey,
+    pub swap_delegate: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub owner_base_token_ata: Pubkey,
+    pub owner_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub delegate: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Args
+    pub quote_allowance: u64,
+    pub base_allowance: u64,
+    pub expiry: i64,
+}
+
+impl CreateSwapDelegateAction {
+    pub fn new(
+        testing_env: &TokenMillEnv,
+        delegate: Pubkey,
+        quote_allowance: u64,
+        base_allowance: u64,
+        expiry: i64,
+    ) -> Self {
+        let swap_action = SwapAction::new(
+            testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactInput,
+            0,
+            0,
+            None,
+        );
+
+        Self {
+            market: swap_action.market,
+            swap_delegate: swap_delegate_address(
+                &swap_action.market,
+                &swap_action.signer,
+                &delegate,
+            ),
+            base_token_mint: swap_action.base_token_mint,
+            quote_token_mint: swap_action.quote_token_mint,
+            owner_base_token_ata: swap_action.user_base_token_ata,
+            owner_quote_token_ata: swap_action.user_quote_token_ata,
+            signer: swap_action.signer,
+            delegate,
+            quote_token_program: swap_action.quote_token_program,
+            quote_allowance,
+            base_allowance,
+            expiry,
+        }
+    }
+}
+
+impl InstructionGenerator for CreateSwapDelegateAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.swap_delegate, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.owner_base_token_ata, false),
+            AccountMeta::new(self.owner_quote_token_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
+        accounts.push(AccountMeta::new_readonly(self.delegate, false));
+        accounts.append_token_2022_program();
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateSwapDelegate {
+            quote_allowance: self.quote_allowance,
+            base_allowance: self.base_allowance,
+            expiry: self.expiry,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct RevokeSwapDelegateAction {
+    // Accounts
+    pub market: Pubkey,
+    pub swap_delegate: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub owner_base_token_ata: Pubkey,
+    pub owner_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+}
+
+impl RevokeSwapDelegateAction {
+    pub fn new(create_swap_delegate_action: &CreateSwapDelegateAction) -> Self {
+        Self {
+            market: create_swap_delegate_action.market,
+            swap_delegate: create_swap_delegate_action.swap_delegate,
+            base_token_mint: create_swap_delegate_action.base_token_mint,
+            quote_token_mint: create_swap_delegate_action.quote_token_mint,
+            owner_base_token_ata: create_swap_delegate_action.owner_base_token_ata,
+            owner_quote_token_ata: create_swap_delegate_action.owner_quote_token_ata,
+            signer: create_swap_delegate_action.signer,
+            quote_token_program: create_swap_delegate_action.quote_token_program,
+        }
+    }
+}
+
+impl InstructionGenerator for RevokeSwapDelegateAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.swap_delegate, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.owner_base_token_ata, false),
+            AccountMeta::new(self.owner_quote_token_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
+        accounts.append_token_2022_program();
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::RevokeSwapDelegate {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct DelegatedSwapAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub swap_delegate: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub owner_base_token_ata: Pubkey,
+    pub owner_quote_token_ata: Pubkey,
+    pub protocol_quote_token_ata: Pubkey,
+    pub owner: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Args
+    pub swap_type: SwapType,
+    pub swap_amount_type: SwapAmountType,
+    pub amount: u64,
+    pub other_amount_threshold: u64,
+}
+
+impl DelegatedSwapAction {
+    pub fn new(
+        testing_env: &TokenMillEnv,
+        delegate: Pubkey,
+        swap_type: SwapType,
+        swap_amount_type: SwapAmountType,
+        amount: u64,
+        other_amount_threshold: u64,
+    ) -> Self {
+        let swap_action = SwapAction::new(
+            testing_env,
+            swap_type,
+            swap_amount_type,
+            amount,
+            other_amount_threshold,
+            None,
+        );
+
+        Self {
+            config: swap_action.config,
+            market: swap_action.market,
+            swap_delegate: swap_delegate_address(
+                &swap_action.market,
+                &swap_action.signer,
+                &delegate,
+            ),
+            base_token_mint: swap_action.base_token_mint,
+            quote_token_mint: swap_action.quote_token_mint,
+            market_base_token_ata: swap_action.market_base_token_ata,
+            market_quote_token_ata: swap_action.market_quote_token_ata,
+            owner_base_token_ata: swap_action.user_base_token_ata,
+            owner_quote_token_ata: swap_action.user_quote_token_ata,
+            protocol_quote_token_ata: swap_action.protocol_quote_token_ata,
+            owner: swap_action.signer,
+            signer: delegate,
+            quote_token_program: swap_action.quote_token_program,
+            swap_type,
+            swap_amount_type,
+            amount,
+            other_amount_threshold,
+        }
+    }
+}
+
+impl InstructionGenerator for DelegatedSwapAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.swap_delegate, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.owner_base_token_ata, false),
+            AccountMeta::new(self.owner_quote_token_ata, false),
+            AccountMeta::new(self.protocol_quote_token_ata, false),
+            AccountMeta::new_readonly(token_mill::ID, false),
+            AccountMeta::new(market_oracle_address(&self.market), false),
+            AccountMeta::new_readonly(restricted_wallet_address(&self.market, &self.owner), false),
+            AccountMeta::new_readonly(self.owner, false),
+        ];
+
+        accounts.append_payer(self.signer);
+        accounts.append_token_2022_program();
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::DelegatedSwap {
+            swap_type: self.swap_type,
+            swap_amount_type: self.swap_amount_type,
+            amount: self.amount,
+            other_amount_threshold: self.other_amount_threshold,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CreateDcaPositionAction {
+    // Accounts
+    pub market: Pubkey,
+    pub dca_position: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub owner_base_token_ata: Pubkey,
+    pub owner_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Args
+    pub amount_per_buy: u64,
+    pub crank_bounty: u64,
+    pub interval: i64,
+    pub max_price: u64,
+    pub buys: u32,
+}
+
+impl CreateDcaPositionAction {
+    pub fn new(
+        testing_env: &TokenMillEnv,
+        amount_per_buy: u64,
+        crank_bounty: u64,
+        buys: u32,
+    ) -> Self {
+        let swap_action = SwapAction::new(
+            testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactInput,
+            amount_per_buy,
+            0,
+            None,
+        );
+
+        Self {
+            market: swap_action.market,
+            dca_position: dca_position_address(&swap_action.market, &swap_action.signer),
+            base_token_mint: swap_action.base_token_mint,
+            quote_token_mint: swap_action.quote_token_mint,
+            market_quote_token_ata: swap_action.market_quote_token_ata,
+            owner_base_token_ata: swap_action.user_base_token_ata,
+            owner_quote_token_ata: swap_action.user_quote_token_ata,
+            signer: swap_action.signer,
+            quote_token_program: swap_action.quote_token_program,
+            amount_per_buy,
+            crank_bounty,
+            interval: 3_600,
+            max_price: u64::MAX,
+            buys,
+        }
+    }
+}
+
+impl InstructionGenerator for CreateDcaPositionAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.dca_position, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.owner_base_token_ata, false),
+            AccountMeta::new(self.owner_quote_token_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
+        accounts.append_token_2022_program();
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts
+            .append_associated_token_program()
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateDcaPosition {
+            amount_per_buy: self.amount_per_buy,
+            crank_bounty: self.crank_bounty,
+            interval: self.interval,
+            max_price: self.max_price,
+            buys: self.buys,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct ExecuteDcaBuyAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub dca_position: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub owner_base_token_ata: Pubkey,
+    pub cranker_quote_token_ata: Pubkey,
+    pub protocol_quote_token_ata: Pubkey,
+    pub owner: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+}
+
+impl ExecuteDcaBuyAction {
+    pub fn new(
+        testing_env: &TokenMillEnv,
+        create_dca_position_action: &CreateDcaPositionAction,
+        cranker: Pubkey,
+    ) -> Self {
+        let swap_action = SwapAction::new(
+            testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactInput,
+            create_dca_position_action.amount_per_buy,
+            0,
+            None,
+        );
+
+        Self {
+            config: swap_action.config,
+            market: create_dca_position_action.market,
+            dca_position: create_dca_position_action.dca_position,
+            base_token_mint: create_dca_position_action.base_token_mint,
+            quote_token_mint: create_dca_position_action.quote_token_mint,
+            market_base_token_ata: swap_action.market_base_token_ata,
+            market_quote_token_ata: create_dca_position_action.market_quote_token_ata,
+            owner_base_token_ata: create_dca_position_action.owner_base_token_ata,
+            cranker_quote_token_ata: get_associated_token_address_with_program_id(
+                &cranker,
+                &create_dca_position_action.quote_token_mint,
+                &create_dca_position_action.quote_token_program,
+            ),
+            protocol_quote_token_ata: swap_action.protocol_quote_token_ata,
+            owner: create_dca_position_action.signer,
+            signer: cranker,
+            quote_token_program: create_dca_position_action.quote_token_program,
+        }
+    }
+}
+
+impl InstructionGenerator for ExecuteDcaBuyAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.dca_position, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.owner_base_token_ata, false),
+            AccountMeta::new(self.cranker_quote_token_ata, false),
+            AccountMeta::new(self.protocol_quote_token_ata, false),
+            AccountMeta::new_readonly(token_mill::ID, false),
+            AccountMeta::new(market_oracle_address(&self.market), false),
+            AccountMeta::new_readonly(restricted_wallet_address(&self.market, &self.owner), false),
+            AccountMeta::new(self.owner, false),
+        ];
+
+        accounts.append_payer(self.signer);
+        accounts.append_token_2022_program();
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::ExecuteDcaBuy {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CloseDcaPositionAction {
+    // Accounts
+    pub market: Pubkey,
+    pub dca_position: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub owner_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+}
+
+impl CloseDcaPositionAction {
+    pub fn new(create_dca_position_action: &CreateDcaPositionAction) -> Self {
+        Self {
+            market: create_dca_position_action.market,
+            dca_position: create_dca_position_action.dca_position,
+            quote_token_mint: create_dca_position_action.quote_token_mint,
+            market_quote_token_ata: create_dca_position_action.market_quote_token_ata,
+            owner_quote_token_ata: create_dca_position_action.owner_quote_token_ata,
+            signer: create_dca_position_action.signer,
+            quote_token_program: create_dca_position_action.quote_token_program,
+        }
+    }
+}
+
+impl InstructionGenerator for CloseDcaPositionAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.dca_position, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.owner_quote_token_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CloseDcaPosition {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct StakeForAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub stake_position: Pubkey,
+    pub beneficiary: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub payer_base_token_account: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub amount: u64,
+    pub lock_tier: u8,
+}
+
+impl StakeForAction {
+    pub fn new(
+        testing_env: &TokenMillEnv,
+        beneficiary: Pubkey,
+        amount: u64,
+        lock_tier: u8,
+    ) -> Self {
+        let deposit_action = DepositAction::new(testing_env, amount);
+
+        Self {
+            market: deposit_action.market,
+            market_staking: deposit_action.market_staking,
+            stake_position: stake_position_address(&deposit_action.market, &beneficiary),
+            beneficiary,
+            base_token_mint: deposit_action.base_token_mint,
+            market_base_token_ata: deposit_action.market_base_token_ata,
+            payer_base_token_account: deposit_action.user_base_token_ata,
+            signer: deposit_action.signer,
+            amount,
+            lock_tier,
+        }
+    }
+}
+
+impl InstructionGenerator for StakeForAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new_readonly(self.beneficiary, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.payer_base_token_account, false),
+            AccountMeta::new_readonly(
+                restricted_wallet_address(&self.market, &self.beneficiary),
+                false,
+            ),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program()
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::StakeFor {
+            amount: self.amount,
+            lock_tier: self.lock_tier,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetMinStakeDurationAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub min_stake_duration: i64,
+}
+
+impl SetMinStakeDurationAction {
+    pub fn new(testing_env: &TokenMillEnv, min_stake_duration: i64) -> Self {
+        Self {
+            market: testing_env.market,
+            market_staking: market_staking_address(&testing_env.market),
+            signer: make_address("alice"),
+            min_stake_duration,
+        }
+    }
+}
+
+impl InstructionGenerator for SetMinStakeDurationAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetMinStakeDuration {
+            min_stake_duration: self.min_stake_duration,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetStakingBadgeCollectionAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub badge_collection: Pubkey,
+    pub badge_multiplier_bps: u16,
+}
+
+impl SetStakingBadgeCollectionAction {
+    pub fn new(
+        testing_env: &TokenMillEnv,
+        badge_collection: Pubkey,
+        badge_multiplier_bps: u16,
+    ) -> Self {
+        Self {
+            market: testing_env.market,
+            market_staking: market_staking_address(&testing_env.market),
+            signer: make_address("alice"),
+            badge_collection,
+            badge_multiplier_bps,
+        }
+    }
+}
+
+impl InstructionGenerator for SetStakingBadgeCollectionAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(change_log_address(&self.market), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetStakingBadgeCollection {
+            badge_collection: self.badge_collection,
+            badge_multiplier_bps: self.badge_multiplier_bps,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct LinkStakingBadgeAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub stake_position: Pubkey,
+    pub staking_badge: Pubkey,
+    pub previous_stake_position: Pubkey,
+    pub badge_mint: Pubkey,
+    pub user_badge_token_account: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl LinkStakingBadgeAction {
+    pub fn new(testing_env: &TokenMillEnv, badge_mint: Pubkey) -> Self {
+        let market = testing_env.market;
+        let signer = make_address("bob");
+
+        Self {
+            market,
+            market_staking: market_staking_address(&market),
+            stake_position: stake_position_address(&market, &signer),
+            staking_badge: staking_badge_address(&market, &badge_mint),
+            previous_stake_position: token_mill::ID,
+            badge_mint,
+            user_badge_token_account: get_associated_token_address_with_program_id(
+                &signer,
+                &badge_mint,
+                &spl_token_2022::id(),
+            ),
+            signer,
+        }
+    }
+
+    pub fn with_user(&mut self, user: Pubkey) -> &mut Self {
+        self.signer = user;
+        self.stake_position = stake_position_address(&self.market, &user);
+        self.user_badge_token_account = get_associated_token_address_with_program_id(
+            &user,
+            &self.badge_mint,
+            &spl_token_2022::id(),
+        );
+
+        self
+    }
+}
+
+impl InstructionGenerator for LinkStakingBadgeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new(self.staking_badge, false),
+            AccountMeta::new(self.previous_stake_position, false),
+            AccountMeta::new_readonly(self.badge_mint, false),
+            AccountMeta::new_readonly(self.user_badge_token_account, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::LinkStakingBadge {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct WithdrawAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub stake_position: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub user_base_token_ata: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub amount: u64,
+}
+
+impl WithdrawAction {
+    pub fn new(testing_env: &TokenMillEnv, amount: u64) -> Self {
+        let deposit_action = DepositAction::new(testing_env, amount);
+
+        Self {
+            market: deposit_action.market,
+            market_staking: deposit_action.market_staking,
+            stake_position: deposit_action.stake_position,
+            base_token_mint: deposit_action.base_token_mint,
+            market_base_token_ata: deposit_action.market_base_token_ata,
+            user_base_token_ata: deposit_action.user_base_token_ata,
+            signer: deposit_action.signer,
+            amount,
+        }
+    }
+
+    pub fn with_user(&mut self, user: Pubkey) -> &mut Self {
+        self.signer = user;
+        self.stake_position = stake_position_address(&self.market, &user);
+        self.user_base_token_ata = get_associated_token_address_with_program_id(
+            &user,
+            &self.base_token_mint,
+            &spl_token_2022::id(),
+        );
+
+        self
+    }
+}
+
+impl InstructionGenerator for WithdrawAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.user_base_token_ata, false),
+            AccountMeta::new_readonly(restricted_wallet_address(&self.market, &self.signer), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::Withdraw {
+            amount: self.amount,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct EmergencyUnstakeAction {
+    // Accounts
+    pub config: Pubkey,
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub stake_position: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub user_base_token_ata: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub amount: u64,
+}
+
+impl EmergencyUnstakeAction {
+    pub fn new(testing_env: &TokenMillEnv, amount: u64) -> Self {
+        let withdraw_action = WithdrawAction::new(testing_env, amount);
+
+        Self {
+            config: make_address("config"),
+            market: withdraw_action.market,
+            market_staking: withdraw_action.market_staking,
+            stake_position: withdraw_action.stake_position,
+            base_token_mint: withdraw_action.base_token_mint,
+            market_base_token_ata: withdraw_action.market_base_token_ata,
+            user_base_token_ata: withdraw_action.user_base_token_ata,
+            signer: withdraw_action.signer,
+            amount,
+        }
+    }
+
+    pub fn with_user(&mut self, user: Pubkey) -> &mut Self {
+        self.signer = user;
+        self.stake_position = stake_position_address(&self.market, &user);
+        self.user_base_token_ata = get_associated_token_address_with_program_id(
+            &user,
+            &self.base_token_mint,
+            &spl_token_2022::id(),
+        );
+
+        self
+    }
+}
+
+impl InstructionGenerator for EmergencyUnstakeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.user_base_token_ata, false),
+            AccountMeta::new_readonly(restricted_wallet_address(&self.market, &self.signer), false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::EmergencyUnstake {
+            amount: self.amount,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LockStakePositionAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub stake_position: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub lock_tier: u8,
+}
+
+impl LockStakePositionAction {
+    pub fn new(testing_env: &TokenMillEnv, lock_tier: u8) -> Self {
+        let deposit_action = DepositAction::new(testing_env, 0);
+
+        Self {
+            market: deposit_action.market,
+            market_staking: deposit_action.market_staking,
+            stake_position: deposit_action.stake_position,
+            signer: deposit_action.signer,
+            lock_tier,
+        }
+    }
+}
+
+impl InstructionGenerator for LockStakePositionAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.stake_position, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::LockStakePosition {
+            lock_tier: self.lock_tier,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ExtendStakePositionLockAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub stake_position: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub lock_tier: u8,
+}
+
+impl ExtendStakePositionLockAction {
+    pub fn new(testing_env: &TokenMillEnv, lock_tier: u8) -> Self {
+        let lock_action = LockStakePositionAction::new(testing_env, lock_tier);
+
+        Self {
+            market: lock_action.market,
+            market_staking: lock_action.market_staking,
+            stake_position: lock_action.stake_position,
+            signer: lock_action.signer,
+            lock_tier,
+        }
+    }
+}
+
+impl InstructionGenerator for ExtendStakePositionLockAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.stake_position, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::ExtendStakePositionLock {
+            lock_tier: self.lock_tier,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct ClaimStakingRewardsAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub stake_position: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub user_quote_token_ata: Pubkey,
+    pub user_badge_token_account: Pubkey,
+    pub quote_token_program: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl ClaimStakingRewardsAction {
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        let base_token_mint = token_mill_env.base_token_mint.unwrap();
+        let signer = make_address("bob");
+
+        let market = Pubkey::find_program_address(
+            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
+
+        let market_staking = Pubkey::find_program_address(
+            &[MARKET_STAKING_PDA_SEED.as_bytes(), &market.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
+
+        let stake_position = Pubkey::find_program_address(
+            &[
+                STAKING_POSITION_PDA_SEED.as_bytes(),
+                &market.to_bytes(),
+                &signer.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        let quote_token_mint = token_mill_env.quote_token_mint.unwrap();
+        let quote_token_program = token_mill_env.quote_token_type.program_address();
+
+        let market_quote_token_ata = get_associated_token_address_with_program_id(
+            &market,
+            &quote_token_mint,
+            &quote_token_program,
+        );
+
+        let user_quote_token_ata = get_associated_token_address_with_program_id(
+            &signer,
+            &quote_token_mint,
+            &quote_token_program,
+        );
+
+        Self {
+            market,
+            market_staking,
+            stake_position,
+            quote_token_mint,
+            market_quote_token_ata,
+            user_quote_token_ata,
+            user_badge_token_account: token_mill::ID,
+            quote_token_program,
+            signer,
+        }
+    }
+
+    pub fn with_badge(&mut self, badge_mint: Pubkey) -> &mut Self {
+        self.user_badge_token_account = get_associated_token_address_with_program_id(
+            &self.signer,
+            &badge_mint,
+            &spl_token_2022::id(),
+        );
+
+        self
+    }
+}
+
+impl InstructionGenerator for ClaimStakingRewardsAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.user_quote_token_ata, false),
+            AccountMeta::new_readonly(self.user_badge_token_account, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::ClaimStakingRewards {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct SetClaimDelegateAction {
+    // Accounts
+    pub market: Pubkey,
+    pub stake_position: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub claim_delegate: Pubkey,
+}
+
+impl SetClaimDelegateAction {
+    pub fn new(testing_env: &TokenMillEnv, claim_delegate: Pubkey) -> Self {
+        let signer = make_address("bob");
+
+        Self {
+            market: testing_env.market,
+            stake_position: stake_position_address(&testing_env.market, &signer),
+            signer,
+            claim_delegate,
+        }
+    }
+}
+
+impl InstructionGenerator for SetClaimDelegateAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.stake_position, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::SetClaimDelegate {
+            claim_delegate: self.claim_delegate,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct DelegatedClaimStakingRewardsAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub stake_position: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub user_quote_token_ata: Pubkey,
+    pub user_badge_token_account: Pubkey,
+    pub user: Pubkey,
+    pub quote_token_program: Pubkey,
+    pub delegate: Pubkey,
+}
+
+impl DelegatedClaimStakingRewardsAction {
+    pub fn new(token_mill_env: &TokenMillEnv, delegate: Pubkey) -> Self {
+        let claim_action = ClaimStakingRewardsAction::new(token_mill_env);
+
+        Self {
+            market: claim_action.market,
+            market_staking: claim_action.market_staking,
+            stake_position: claim_action.stake_position,
+            quote_token_mint: claim_action.quote_token_mint,
+            market_quote_token_ata: claim_action.market_quote_token_ata,
+            user_quote_token_ata: claim_action.user_quote_token_ata,
+            user_badge_token_account: claim_action.user_badge_token_account,
+            user: claim_action.signer,
+            quote_token_program: claim_action.quote_token_program,
+            delegate,
+        }
+    }
+}
+
+impl InstructionGenerator for DelegatedClaimStakingRewardsAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.user_quote_token_ata, false),
+            AccountMeta::new_readonly(self.user_badge_token_account, false),
+            AccountMeta::new_readonly(self.user, false),
+        ];
+
+        accounts.append_payer(self.delegate);
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::DelegatedClaimStakingRewards {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct ExpireStakePositionLockAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub stake_position: Pubkey,
+}
+
+impl ExpireStakePositionLockAction {
+    pub fn new(testing_env: &TokenMillEnv) -> Self {
+        let deposit_action = DepositAction::new(testing_env, 0);
+
+        Self {
+            market: deposit_action.market,
+            market_staking: deposit_action.market_staking,
+            stake_position: deposit_action.stake_position,
+        }
+    }
+}
+
+impl InstructionGenerator for ExpireStakePositionLockAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.stake_position, false),
+        ];
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::ExpireStakePositionLock {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct UpgradeMarketStakingAction {
+    // Accounts
+    pub staking: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl UpgradeMarketStakingAction {
+    pub fn new(testing_env: &TokenMillEnv) -> Self {
+        Self {
+            staking: market_staking_address(&testing_env.market),
+            signer: testing_env.svm.payer,
+        }
+    }
+}
+
+impl InstructionGenerator for UpgradeMarketStakingAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![AccountMeta::new(self.staking, false)];
+
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpgradeMarketStaking {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct UpgradeStakePositionAction {
+    // Accounts
+    pub market: Pubkey,
+    pub stake_position: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl UpgradeStakePositionAction {
+    pub fn new(testing_env: &TokenMillEnv, user: &Pubkey) -> Self {
+        Self {
+            market: testing_env.market,
+            stake_position: stake_position_address(&testing_env.market, user),
+            signer: testing_env.svm.payer,
+        }
+    }
+}
+
+impl InstructionGenerator for UpgradeStakePositionAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.stake_position, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UpgradeStakePosition {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TransferStakePositionAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub stake_position: Pubkey,
+    pub new_stake_position: Pubkey,
+    pub new_owner: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl TransferStakePositionAction {
+    pub fn new(testing_env: &TokenMillEnv, new_owner: Pubkey) -> Self {
+        let market = testing_env.market;
+        let signer = make_address("bob");
+
+        Self {
+            market,
+            market_staking: market_staking_address(&market),
+            stake_position: stake_position_address(&market, &signer),
+            new_stake_position: stake_position_address(&market, &new_owner),
+            new_owner,
+            signer,
+        }
+    }
+}
+
+impl InstructionGenerator for TransferStakePositionAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new(self.new_stake_position, false),
+            AccountMeta::new_readonly(self.new_owner, false),
+            AccountMeta::new_readonly(
+                restricted_wallet_address(&self.market, &self.new_owner),
+                false,
+            ),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::TransferStakePosition {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateRewardVaultAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub reward_vault: Pubkey,
+    pub reward_token_mint: Pubkey,
+    pub reward_vault_ata: Pubkey,
+    pub signer: Pubkey,
+    pub reward_token_program: Pubkey,
+}
+
+impl CreateRewardVaultAction {
+    pub fn new(testing_env: &TokenMillEnv, reward_token_mint: Pubkey) -> Self {
+        let market = testing_env.market;
+        let reward_vault = reward_vault_address(&market);
+        let reward_token_program = testing_env
+            .svm
+            .tokens
+            .get(&reward_token_mint)
+            .unwrap()
+            .program_address();
+
+        Self {
+            market,
+            market_staking: market_staking_address(&market),
+            reward_vault,
+            reward_token_mint,
+            reward_vault_ata: get_associated_token_address_with_program_id(
+                &reward_vault,
+                &reward_token_mint,
+                &reward_token_program,
+            ),
+            signer: make_address("alice"),
+            reward_token_program,
+        }
+    }
+}
+
+impl InstructionGenerator for CreateRewardVaultAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new_readonly(self.market_staking, false),
+            AccountMeta::new(self.reward_vault, false),
+            AccountMeta::new_readonly(self.reward_token_mint, false),
+            AccountMeta::new(self.reward_vault_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.reward_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts
+            .append_associated_token_program()
+            .append_system_program();
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateRewardVault {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FundRewardVaultAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub reward_vault: Pubkey,
+    pub reward_token_mint: Pubkey,
+    pub reward_vault_ata: Pubkey,
+    pub funder_token_account: Pubkey,
+    pub signer: Pubkey,
+    pub reward_token_program: Pubkey,
+    // Args
+    pub amount: u64,
+    pub duration: i64,
+}
+
+impl FundRewardVaultAction {
+    pub fn new(
+        testing_env: &TokenMillEnv,
+        reward_token_mint: Pubkey,
+        amount: u64,
+        duration: i64,
+    ) -> Self {
+        let create_action = CreateRewardVaultAction::new(testing_env, reward_token_mint);
+        let signer = create_action.signer;
+
+        Self {
+            market: create_action.market,
+            market_staking: create_action.market_staking,
+            reward_vault: create_action.reward_vault,
+            reward_token_mint,
+            reward_vault_ata: create_action.reward_vault_ata,
+            funder_token_account: get_associated_token_address_with_program_id(
+                &signer,
+                &reward_token_mint,
+                &create_action.reward_token_program,
+            ),
+            signer,
+            reward_token_program: create_action.reward_token_program,
+            amount,
+            duration,
+        }
+    }
+
+    pub fn with_funder(&mut self, funder: Pubkey) -> &mut Self {
+        self.signer = funder;
+        self.funder_token_account = get_associated_token_address_with_program_id(
+            &funder,
+            &self.reward_token_mint,
+            &self.reward_token_program,
+        );
+
+        self
+    }
+}
+
+impl InstructionGenerator for FundRewardVaultAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.reward_vault, false),
+            AccountMeta::new_readonly(self.reward_token_mint, false),
+            AccountMeta::new(self.reward_vault_ata, false),
+            AccountMeta::new(self.funder_token_account, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.reward_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::FundRewardVault {
+            amount: self.amount,
+            duration: self.duration,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ClaimVaultRewardsAction {
+    // Accounts
+    pub market: Pubkey,
+    pub market_staking: Pubkey,
+    pub stake_position: Pubkey,
+    pub reward_vault: Pubkey,
+    pub reward_token_mint: Pubkey,
+    pub reward_vault_ata: Pubkey,
+    pub user_reward_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub reward_token_program: Pubkey,
+}
+
+impl ClaimVaultRewardsAction {
+    pub fn new(testing_env: &TokenMillEnv, reward_token_mint: Pubkey) -> Self {
+        let create_action = CreateRewardVaultAction::new(testing_env, reward_token_mint);
+        let signer = make_address("bob");
+
+        Self {
+            market: create_action.market,
+            market_staking: create_action.market_staking,
+            stake_position: stake_position_address(&create_action.market, &signer),
+            reward_vault: create_action.reward_vault,
+            reward_token_mint,
+            reward_vault_ata: create_action.reward_vault_ata,
+            user_reward_token_ata: get_associated_token_address_with_program_id(
+                &signer,
+                &reward_token_mint,
+                &create_action.reward_token_program,
+            ),
+            signer,
+            reward_token_program: create_action.reward_token_program,
+        }
+    }
+
+    pub fn with_user(&mut self, user: Pubkey) -> &mut Self {
+        self.signer = user;
+        self.stake_position = stake_position_address(&self.market, &user);
+        self.user_reward_token_ata = get_associated_token_address_with_program_id(
+            &user,
+            &self.reward_token_mint,
+            &self.reward_token_program,
+        );
+
+        self
+    }
+}
+
+impl InstructionGenerator for ClaimVaultRewardsAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new_readonly(self.reward_vault, false),
+            AccountMeta::new_readonly(self.reward_token_mint, false),
+            AccountMeta::new(self.reward_vault_ata, false),
+            AccountMeta::new(self.user_reward_token_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.reward_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts
+            .append_associated_token_program()
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::ClaimVaultRewards {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub fn stake_position_address(market: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            STAKING_POSITION_PDA_SEED.as_bytes(),
+            &market.to_bytes(),
+            &user.to_bytes(),
+        ],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn market_staking_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[MARKET_STAKING_PDA_SEED.as_bytes(), &market.to_bytes()],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn reward_vault_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[REWARD_VAULT_PDA_SEED.as_bytes(), &market.to_bytes()],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn staking_checkpoints_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[STAKING_CHECKPOINTS_PDA_SEED.as_bytes(), &market.to_bytes()],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn staking_badge_address(market: &Pubkey, badge_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            STAKING_BADGE_PDA_SEED.as_bytes(),
+            &market.to_bytes(),
+            &badge_mint.to_bytes(),
+        ],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn compounding_vault_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[COMPOUNDING_VAULT_PDA_SEED.as_bytes(), &market.to_bytes()],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn compounding_position_address(compounding_vault: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            COMPOUNDING_POSITION_PDA_SEED.as_bytes(),
+            &compounding_vault.to_bytes(),
+            &user.to_bytes(),
+        ],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn boost_pool_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[BOOST_POOL_PDA_SEED.as_bytes(), &market.to_bytes()],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub fn boost_position_address(market: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            BOOST_POSITION_PDA_SEED.as_bytes(),
+            &market.to_bytes(),
+            &user.to_bytes(),
+        ],
+        &token_mill::ID,
+    )
+    .0
+}
+
+pub struct CreateBoostPoolAction {
+    // Accounts
+    pub market: Pubkey,
+    pub boost_pool: Pubkey,
+    pub signer: Pubkey,
+}
+
+impl CreateBoostPoolAction {
+    pub fn new(testing_env: &TokenMillEnv) -> Self {
+        let market = testing_env.market;
+
+        Self {
+            market,
+            boost_pool: boost_pool_address(&market),
+            signer: make_address("alice"),
+        }
+    }
+}
+
+impl InstructionGenerator for CreateBoostPoolAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.boost_pool, false),
+        ];
+
+        accounts.append_payer(self.signer).append_system_program();
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateBoostPool {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct FundBoostPoolAction {
+    // Accounts
+    pub market: Pubkey,
+    pub boost_pool: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub creator_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Args
+    pub amount: u64,
+    pub duration: i64,
+}
+
+impl FundBoostPoolAction {
+    pub fn new(testing_env: &TokenMillEnv, amount: u64, duration: i64) -> Self {
+        let claim_creator_fees_action = ClaimCreatorFeesAction::new(testing_env);
+
+        Self {
+            market: claim_creator_fees_action.market,
+            boost_pool: boost_pool_address(&claim_creator_fees_action.market),
+            quote_token_mint: claim_creator_fees_action.quote_token_mint,
+            market_quote_token_ata: claim_creator_fees_action.market_quote_token_ata,
+            creator_quote_token_ata: claim_creator_fees_action.creator_quote_token_ata,
+            signer: claim_creator_fees_action.signer,
+            quote_token_program: claim_creator_fees_action.quote_token_program,
+            amount,
+            duration,
+        }
+    }
+}
+
+impl InstructionGenerator for FundBoostPoolAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.boost_pool, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.creator_quote_token_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::FundBoostPool {
+            amount: self.amount,
+            duration: self.duration,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct LockStakeAction {
+    // Accounts
+    pub market: Pubkey,
+    pub boost_pool: Pubkey,
+    pub stake_position: Pubkey,
+    pub boost_position: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub amount: u64,
+    pub duration: i64,
+}
+
+impl LockStakeAction {
+    pub fn new(testing_env: &TokenMillEnv, amount: u64, duration: i64) -> Self {
+        let market = testing_env.market;
+        let signer = make_address("bob");
+
+        Self {
+            market,
+            boost_pool: boost_pool_address(&market),
+            stake_position: stake_position_address(&market, &signer),
+            boost_position: boost_position_address(&market, &signer),
+            signer,
+            amount,
+            duration,
+        }
+    }
+
+    pub fn with_user(&mut self, user: Pubkey) -> &mut Self {
+        self.signer = user;
+        self.stake_position = stake_position_address(&self.market, &user);
+        self.boost_position = boost_position_address(&self.market, &user);
+
+        self
+    }
+}
+
+impl InstructionGenerator for LockStakeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.boost_pool, false),
+            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new(self.boost_position, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::LockStake {
+            amount: self.amount,
+            duration: self.duration,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct UnlockStakeAction {
+    // Accounts
+    pub market: Pubkey,
+    pub boost_pool: Pubkey,
+    pub stake_position: Pubkey,
+    pub boost_position: Pubkey,
+}
+
+impl UnlockStakeAction {
+    pub fn new(testing_env: &TokenMillEnv) -> Self {
+        let market = testing_env.market;
+        let user = make_address("bob");
+
+        Self {
+            market,
+            boost_pool: boost_pool_address(&market),
+            stake_position: stake_position_address(&market, &user),
+            boost_position: boost_position_address(&market, &user),
+        }
+    }
+}
+
+impl InstructionGenerator for UnlockStakeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.boost_pool, false),
+            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new(self.boost_position, false),
+        ];
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::UnlockStake {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct ClaimBoostRewardsAction {
+    // Accounts
+    pub market: Pubkey,
+    pub boost_pool: Pubkey,
+    pub boost_position: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub user_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+}
+
+impl ClaimBoostRewardsAction {
+    pub fn new(testing_env: &TokenMillEnv) -> Self {
+        let claim_staking_rewards_action = ClaimStakingRewardsAction::new(testing_env);
+        let market = claim_staking_rewards_action.market;
+        let signer = claim_staking_rewards_action.signer;
+
+        Self {
+            market,
+            boost_pool: boost_pool_address(&market),
+            boost_position: boost_position_address(&market, &signer),
+            quote_token_mint: claim_staking_rewards_action.quote_token_mint,
+            market_quote_token_ata: claim_staking_rewards_action.market_quote_token_ata,
+            user_quote_token_ata: claim_staking_rewards_action.user_quote_token_ata,
+            signer,
+            quote_token_program: claim_staking_rewards_action.quote_token_program,
+        }
+    }
+
+    pub fn with_user(&mut self, user: Pubkey) -> &mut Self {
+        self.signer = user;
+        self.boost_position = boost_position_address(&self.market, &user);
+        self.user_quote_token_ata = get_associated_token_address_with_program_id(
+            &user,
+            &self.quote_token_mint,
+            &self.quote_token_program,
+        );
+
+        self
+    }
+}
+
+impl InstructionGenerator for ClaimBoostRewardsAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.boost_pool, false),
+            AccountMeta::new(self.boost_position, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.user_quote_token_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::ClaimBoostRewards {};
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct CreateReferralAccountAction {
+    // Accounts
+    pub config: Pubkey,
+    pub referral_account: Pubkey,
     pub signer: Pubkey,
     // Args
-    pub new_creator_fee_share: u16,
-    pub new_staking_fee_share: u16,
+    pub referrer: Pubkey,
 }
 
-impl UpdateMarketFeeSharesAction {
-    pub fn new(new_creator_fee_share: u16, new_staking_fee_share: u16) -> Self {
-        let market = Pubkey::find_program_address(
+impl Default for CreateReferralAccountAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CreateReferralAccountAction {
+    pub fn new() -> Self {
+        let config = make_address("config");
+
+        let referrer = make_address("carol");
+
+        let referral_account = Pubkey::find_program_address(
             &[
-                MARKET_PDA_SEED.as_bytes(),
-                &make_address("base_token_mint").to_bytes(),
+                REFERRAL_ACCOUNT_PDA_SEED.as_bytes(),
+                (config.as_ref()),
+                (referrer.as_ref()),
             ],
             &token_mill::ID,
         )
         .0;
 
         Self {
-            market,
-            signer: make_address("alice"),
-            new_creator_fee_share,
-            new_staking_fee_share,
+            config,
+            referral_account,
+            signer: make_address("admin"),
+            referrer,
+        }
+    }
+}
+
+impl InstructionGenerator for CreateReferralAccountAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.referral_account, false),
+        ];
+
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::CreateReferralAccount {
+            referrer: self.referrer,
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
+        }
+    }
+}
+
+pub struct RegisterReferralCodeAction {
+    // Accounts
+    pub config: Pubkey,
+    pub referral_account: Pubkey,
+    pub referral_code: Pubkey,
+    pub signer: Pubkey,
+    // Args
+    pub code: String,
+}
+
+impl RegisterReferralCodeAction {
+    pub fn new(code: &str) -> Self {
+        let create_referral_account_action = CreateReferralAccountAction::new();
+        let config = create_referral_account_action.config;
+
+        Self {
+            config,
+            referral_account: create_referral_account_action.referral_account,
+            referral_code: referral_code_address(&config, code),
+            signer: create_referral_account_action.referrer,
+            code: code.to_string(),
+        }
+    }
+}
+
+impl InstructionGenerator for RegisterReferralCodeAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new_readonly(self.referral_account, false),
+            AccountMeta::new(self.referral_code, false),
+        ];
+
+        accounts.append_payer(self.signer).append_system_program();
+
+        accounts
+    }
+
+    fn instruction(&self) -> Instruction {
+        let input = token_mill::instruction::RegisterReferralCode {
+            code: self.code.clone(),
+        };
+
+        Instruction {
+            program_id: token_mill::ID,
+            accounts: self.accounts(),
+            data: input.data(),
         }
     }
 }
 
-impl InstructionGenerator for UpdateMarketFeeSharesAction {
-    fn accounts(&self) -> Vec<AccountMeta> {
-        let mut accounts = vec![AccountMeta::new(self.market, false)];
+pub struct ClaimReferralFeesAction {
+    pub config: Pubkey,
+    pub referral_account: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub referral_account_quote_token_ata: Pubkey,
+    pub referrer_quote_token_ata: Pubkey,
+    pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+}
+
+impl ClaimReferralFeesAction {
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        let config = make_address("config");
+        let signer = make_address("carol");
+
+        let referral_account = Pubkey::find_program_address(
+            &[
+                REFERRAL_ACCOUNT_PDA_SEED.as_bytes(),
+                (config.as_ref()),
+                (signer.as_ref()),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        let quote_token_mint = token_mill_env.quote_token_mint.unwrap();
+        let quote_token_program = token_mill_env.quote_token_type.program_address();
+
+        let referral_account_quote_token_ata = get_associated_token_address_with_program_id(
+            &referral_account,
+            &quote_token_mint,
+            &quote_token_program,
+        );
+
+        let referrer_quote_token_ata = get_associated_token_address_with_program_id(
+            &signer,
+            &quote_token_mint,
+            &quote_token_program,
+        );
+
+        Self {
+            config,
+            referral_account,
+            quote_token_mint,
+            referral_account_quote_token_ata,
+            referrer_quote_token_ata,
+            signer,
+            quote_token_program,
+        }
+    }
+
+    /// Claims the fees to `token_account` instead of the quote token ATA of the referrer
+    pub fn with_recipient(&mut self, token_account: Pubkey) -> &mut Self {
+        self.referrer_quote_token_ata = token_account;
+
+        self
+    }
+}
+
+impl InstructionGenerator for ClaimReferralFeesAction {
+    fn accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.referral_account, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.referral_account_quote_token_ata, false),
+            AccountMeta::new(self.referrer_quote_token_ata, false),
+        ];
+
+        accounts.append_payer(self.signer);
 
-        accounts
-            .append_payer(self.signer)
-            .append_cpi_event_accounts(tm_event_authority());
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
+        accounts.append_cpi_event_accounts(tm_event_authority());
 
         accounts
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::UpdateMarketFeeShares {
-            new_creator_fee_share: self.new_creator_fee_share,
-            new_staking_fee_share: self.new_staking_fee_share,
-        };
+        let input = token_mill::instruction::ClaimReferralFees {};
 
         Instruction {
             program_id: token_mill::ID,
@@ -969,79 +9350,113 @@ impl InstructionGenerator for UpdateMarketFeeSharesAction {
     }
 }
 
-pub struct ClaimCreatorFeesAction {
+pub struct CreateVestingPlanAction {
     // Accounts
     pub market: Pubkey,
-    pub quote_token_mint: Pubkey,
-    pub market_quote_token_ata: Pubkey,
-    pub creator_quote_token_ata: Pubkey,
+    pub staking: Pubkey,
+    pub staking_position: Pubkey,
+    pub vesting_plan: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub user_base_token_ata: Pubkey,
     pub signer: Pubkey,
-    pub quote_token_program: Pubkey,
+    // Args
+    pub vesting_amount: u64,
+    pub start: i64,
+    pub vesting_duration: i64,
+    pub cliff_duration: i64,
 }
 
-impl ClaimCreatorFeesAction {
-    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+impl CreateVestingPlanAction {
+    pub fn new(
+        vesting_amount: u64,
+        start: i64,
+        vesting_duration: i64,
+        cliff_duration: i64,
+    ) -> Self {
+        let base_token_mint = make_address("base_token_mint");
+        let vesting_plan = make_address("vesting_plan");
+        let signer = make_address("bob");
+
         let market = Pubkey::find_program_address(
-            &[
-                MARKET_PDA_SEED.as_bytes(),
-                &token_mill_env.base_token_mint.unwrap().to_bytes(),
-            ],
+            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
             &token_mill::ID,
         )
         .0;
 
-        let signer = make_address("alice");
+        let staking = Pubkey::find_program_address(
+            &[MARKET_STAKING_PDA_SEED.as_bytes(), &market.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
 
-        let quote_token_mint = token_mill_env.quote_token_mint.unwrap();
-        let quote_token_program = token_mill_env.quote_token_type.program_address();
+        let staking_position = Pubkey::find_program_address(
+            &[
+                STAKING_POSITION_PDA_SEED.as_bytes(),
+                &market.to_bytes(),
+                &signer.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
 
-        let market_quote_token_ata = get_associated_token_address_with_program_id(
+        let market_base_token_ata = get_associated_token_address_with_program_id(
             &market,
-            &quote_token_mint,
-            &quote_token_program,
+            &base_token_mint,
+            &spl_token_2022::id(),
         );
 
-        let creator_quote_token_ata = get_associated_token_address_with_program_id(
+        let user_base_token_ata = get_associated_token_address_with_program_id(
             &signer,
-            &quote_token_mint,
-            &quote_token_program,
+            &base_token_mint,
+            &spl_token_2022::id(),
         );
 
         Self {
             market,
-            quote_token_mint,
-            market_quote_token_ata,
-            creator_quote_token_ata,
+            staking,
+            staking_position,
+            vesting_plan,
+            base_token_mint,
+            market_base_token_ata,
+            user_base_token_ata,
             signer,
-            quote_token_program,
+            vesting_amount,
+            start,
+            vesting_duration,
+            cliff_duration,
         }
     }
 }
 
-impl InstructionGenerator for ClaimCreatorFeesAction {
+impl InstructionGenerator for CreateVestingPlanAction {
     fn accounts(&self) -> Vec<AccountMeta> {
         let mut accounts = vec![
             AccountMeta::new(self.market, false),
-            AccountMeta::new_readonly(self.quote_token_mint, false),
-            AccountMeta::new(self.market_quote_token_ata, false),
-            AccountMeta::new(self.creator_quote_token_ata, false),
+            AccountMeta::new(self.staking, false),
+            AccountMeta::new(self.staking_position, false),
+            AccountMeta::new(self.vesting_plan, true),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.user_base_token_ata, false),
         ];
 
-        accounts.append_payer(self.signer);
-
-        match self.quote_token_program {
-            spl_token::ID => accounts.append_token_program(),
-            spl_token_2022::ID => accounts.append_token_2022_program(),
-            _ => unreachable!(),
-        };
-
-        accounts.append_cpi_event_accounts(tm_event_authority());
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program()
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
 
         accounts
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::ClaimCreatorFees {};
+        let input = token_mill::instruction::CreateVestingPlan {
+            vesting_amount: self.vesting_amount,
+            start: self.start,
+            vesting_duration: self.vesting_duration,
+            cliff_duration: self.cliff_duration,
+        };
 
         Instruction {
             program_id: token_mill::ID,
@@ -1051,31 +9466,42 @@ impl InstructionGenerator for ClaimCreatorFeesAction {
     }
 }
 
-pub struct ClaimProtocolFeesAction {
-    // Accounts
-    pub config: Pubkey,
-    pub market: Pubkey,
-    pub quote_token_mint: Pubkey,
-    pub market_quote_token_ata: Pubkey,
-    pub authority_quote_token_ata: Pubkey,
-    pub signer: Pubkey,
-    pub quote_token_program: Pubkey,
-}
-
-pub struct CreateStakingAction {
+pub struct CreateCreatorVestingPlanAction {
     // Accounts
     pub market: Pubkey,
     pub staking: Pubkey,
+    pub stake_position: Pubkey,
+    pub vesting_plan: Pubkey,
+    pub recipient: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub creator_base_token_ata: Pubkey,
     pub signer: Pubkey,
+    // Args
+    pub start: i64,
+    pub vesting_amount: u64,
+    pub vesting_duration: i64,
+    pub cliff_duration: i64,
+    pub revocable: bool,
+    pub milestones: [VestingMilestone; MAX_VESTING_MILESTONES],
 }
 
-impl CreateStakingAction {
-    pub fn new(testing_env: &TokenMillEnv) -> Self {
+impl CreateCreatorVestingPlanAction {
+    pub fn new(
+        testing_env: &TokenMillEnv,
+        recipient: Pubkey,
+        vesting_amount: u64,
+        start: i64,
+        vesting_duration: i64,
+        cliff_duration: i64,
+        revocable: bool,
+    ) -> Self {
+        let base_token_mint = testing_env.base_token_mint.unwrap();
+        let vesting_plan = make_address("creator_vesting_plan");
+        let signer = make_address("alice");
+
         let market = Pubkey::find_program_address(
-            &[
-                MARKET_PDA_SEED.as_bytes(),
-                &testing_env.base_token_mint.unwrap().to_bytes(),
-            ],
+            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
             &token_mill::ID,
         )
         .0;
@@ -1086,23 +9512,64 @@ impl CreateStakingAction {
         )
         .0;
 
+        let stake_position = Pubkey::find_program_address(
+            &[
+                STAKING_POSITION_PDA_SEED.as_bytes(),
+                &market.to_bytes(),
+                &recipient.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
+
+        let market_base_token_ata = get_associated_token_address_with_program_id(
+            &market,
+            &base_token_mint,
+            &spl_token_2022::id(),
+        );
+
+        let creator_base_token_ata = get_associated_token_address_with_program_id(
+            &signer,
+            &base_token_mint,
+            &spl_token_2022::id(),
+        );
+
         Self {
             market,
             staking,
-            signer: make_address("admin"),
+            stake_position,
+            vesting_plan,
+            recipient,
+            base_token_mint,
+            market_base_token_ata,
+            creator_base_token_ata,
+            signer,
+            start,
+            vesting_amount,
+            vesting_duration,
+            cliff_duration,
+            revocable,
+            milestones: [VestingMilestone::default(); MAX_VESTING_MILESTONES],
         }
     }
 }
 
-impl InstructionGenerator for CreateStakingAction {
+impl InstructionGenerator for CreateCreatorVestingPlanAction {
     fn accounts(&self) -> Vec<AccountMeta> {
         let mut accounts = vec![
-            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new(self.market, false),
             AccountMeta::new(self.staking, false),
+            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new(self.vesting_plan, true),
+            AccountMeta::new_readonly(self.recipient, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.creator_base_token_ata, false),
         ];
 
         accounts
             .append_payer(self.signer)
+            .append_token_2022_program()
             .append_system_program()
             .append_cpi_event_accounts(tm_event_authority());
 
@@ -1110,7 +9577,14 @@ impl InstructionGenerator for CreateStakingAction {
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::CreateStaking {};
+        let input = token_mill::instruction::CreateCreatorVestingPlan {
+            start: self.start,
+            vesting_amount: self.vesting_amount,
+            vesting_duration: self.vesting_duration,
+            cliff_duration: self.cliff_duration,
+            revocable: self.revocable,
+            milestones: self.milestones,
+        };
 
         Instruction {
             program_id: token_mill::ID,
@@ -1120,53 +9594,109 @@ impl InstructionGenerator for CreateStakingAction {
     }
 }
 
-pub struct CreateStakePositionAction {
+pub struct DevBuyAndVestAction {
     // Accounts
+    pub config: Pubkey,
     pub market: Pubkey,
+    pub staking: Pubkey,
     pub stake_position: Pubkey,
+    pub vesting_plan: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub market_quote_token_ata: Pubkey,
+    pub creator_quote_token_ata: Pubkey,
+    pub protocol_quote_token_ata: Pubkey,
     pub signer: Pubkey,
+    pub quote_token_program: Pubkey,
+    // Args
+    pub swap_amount_type: SwapAmountType,
+    pub amount: u64,
+    pub other_amount_threshold: u64,
+    pub vesting_duration: i64,
+    pub cliff_duration: i64,
 }
 
-impl CreateStakePositionAction {
-    pub fn new(testing_env: &TokenMillEnv) -> Self {
-        let market = Pubkey::find_program_address(
-            &[
-                MARKET_PDA_SEED.as_bytes(),
-                &testing_env.base_token_mint.unwrap().to_bytes(),
-            ],
-            &token_mill::ID,
-        )
-        .0;
+impl DevBuyAndVestAction {
+    pub fn new(
+        testing_env: &TokenMillEnv,
+        swap_amount_type: SwapAmountType,
+        amount: u64,
+        other_amount_threshold: u64,
+        vesting_duration: i64,
+        cliff_duration: i64,
+    ) -> Self {
+        let buy_and_stake_action = BuyAndStakeAction::new(
+            testing_env,
+            swap_amount_type,
+            amount,
+            other_amount_threshold,
+        );
 
-        let signer: Pubkey = make_address("bob");
+        let mut action = Self {
+            config: buy_and_stake_action.config,
+            market: buy_and_stake_action.market,
+            staking: buy_and_stake_action.staking,
+            stake_position: buy_and_stake_action.stake_position,
+            vesting_plan: make_address("dev_vesting_plan"),
+            base_token_mint: buy_and_stake_action.base_token_mint,
+            quote_token_mint: buy_and_stake_action.quote_token_mint,
+            market_quote_token_ata: buy_and_stake_action.market_quote_token_ata,
+            creator_quote_token_ata: buy_and_stake_action.user_quote_token_ata,
+            protocol_quote_token_ata: buy_and_stake_action.protocol_quote_token_ata,
+            signer: buy_and_stake_action.signer,
+            quote_token_program: buy_and_stake_action.quote_token_program,
+            swap_amount_type,
+            amount,
+            other_amount_threshold,
+            vesting_duration,
+            cliff_duration,
+        };
+
+        action.with_user(make_address("alice"));
+
+        action
+    }
 
-        let stake_position = Pubkey::find_program_address(
-            &[
-                STAKING_POSITION_PDA_SEED.as_bytes(),
-                &market.to_bytes(),
-                &signer.to_bytes(),
-            ],
-            &token_mill::ID,
-        )
-        .0;
+    pub fn with_user(&mut self, user: Pubkey) -> &mut Self {
+        self.signer = user;
+        self.stake_position = stake_position_address(&self.market, &user);
+        self.creator_quote_token_ata = get_associated_token_address_with_program_id(
+            &user,
+            &self.quote_token_mint,
+            &self.quote_token_program,
+        );
 
-        Self {
-            market,
-            stake_position,
-            signer,
-        }
+        self
     }
 }
 
-impl InstructionGenerator for CreateStakePositionAction {
+impl InstructionGenerator for DevBuyAndVestAction {
     fn accounts(&self) -> Vec<AccountMeta> {
         let mut accounts = vec![
-            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new_readonly(self.config, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.staking, false),
             AccountMeta::new(self.stake_position, false),
+            AccountMeta::new(self.vesting_plan, true),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new_readonly(self.quote_token_mint, false),
+            AccountMeta::new(self.market_quote_token_ata, false),
+            AccountMeta::new(self.creator_quote_token_ata, false),
+            AccountMeta::new(self.protocol_quote_token_ata, false),
+            AccountMeta::new_readonly(token_mill::ID, false),
+            AccountMeta::new(market_oracle_address(&self.market), false),
+            AccountMeta::new_readonly(restricted_wallet_address(&self.market, &self.signer), false),
         ];
 
+        accounts.append_payer(self.signer);
+
+        match self.quote_token_program {
+            spl_token::ID => accounts.append_token_program(),
+            spl_token_2022::ID => accounts.append_token_2022_program(),
+            _ => unreachable!(),
+        };
+
         accounts
-            .append_payer(self.signer)
             .append_system_program()
             .append_cpi_event_accounts(tm_event_authority());
 
@@ -1174,7 +9704,13 @@ impl InstructionGenerator for CreateStakePositionAction {
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::CreateStakePosition {};
+        let input = token_mill::instruction::DevBuyAndVest {
+            swap_amount_type: self.swap_amount_type,
+            amount: self.amount,
+            other_amount_threshold: self.other_amount_threshold,
+            vesting_duration: self.vesting_duration,
+            cliff_duration: self.cliff_duration,
+        };
 
         Instruction {
             program_id: token_mill::ID,
@@ -1184,77 +9720,68 @@ impl InstructionGenerator for CreateStakePositionAction {
     }
 }
 
-pub struct DepositAction {
+pub struct ReleaseAction {
     // Accounts
     pub market: Pubkey,
-    pub market_staking: Pubkey,
-    pub stake_position: Pubkey,
+    pub staking: Pubkey,
+    pub staking_position: Pubkey,
+    pub vesting_plan: Pubkey,
     pub base_token_mint: Pubkey,
     pub market_base_token_ata: Pubkey,
     pub user_base_token_ata: Pubkey,
     pub signer: Pubkey,
-    // Args
-    pub amount: u64,
 }
 
-impl DepositAction {
-    pub fn new(testing_env: &TokenMillEnv, amount: u64) -> Self {
-        let signer = make_address("bob");
-        let base_token_mint = testing_env.base_token_mint.unwrap();
+impl Default for ReleaseAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        let market = Pubkey::find_program_address(
-            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
-            &token_mill::ID,
-        )
-        .0;
+impl ReleaseAction {
+    pub fn new() -> Self {
+        let create_vesting_plan_action = CreateVestingPlanAction::new(0, 0, 0, 0);
 
-        let market_staking = Pubkey::find_program_address(
-            &[MARKET_STAKING_PDA_SEED.as_bytes(), &market.to_bytes()],
-            &token_mill::ID,
-        )
-        .0;
+        Self {
+            market: create_vesting_plan_action.market,
+            staking: create_vesting_plan_action.staking,
+            staking_position: create_vesting_plan_action.staking_position,
+            vesting_plan: create_vesting_plan_action.vesting_plan,
+            base_token_mint: create_vesting_plan_action.base_token_mint,
+            market_base_token_ata: create_vesting_plan_action.market_base_token_ata,
+            user_base_token_ata: create_vesting_plan_action.user_base_token_ata,
+            signer: create_vesting_plan_action.signer,
+        }
+    }
 
-        let stake_position = Pubkey::find_program_address(
+    pub fn with_user(&mut self, user: Pubkey) -> &mut Self {
+        self.signer = user;
+        self.staking_position = Pubkey::find_program_address(
             &[
                 STAKING_POSITION_PDA_SEED.as_bytes(),
-                &market.to_bytes(),
-                &signer.to_bytes(),
+                &self.market.to_bytes(),
+                &user.to_bytes(),
             ],
             &token_mill::ID,
         )
         .0;
-
-        let market_base_token_ata = get_associated_token_address_with_program_id(
-            &market,
-            &base_token_mint,
-            &spl_token_2022::id(),
-        );
-
-        let user_base_token_ata = get_associated_token_address_with_program_id(
-            &signer,
-            &base_token_mint,
+        self.user_base_token_ata = get_associated_token_address_with_program_id(
+            &user,
+            &self.base_token_mint,
             &spl_token_2022::id(),
         );
 
-        Self {
-            market,
-            market_staking,
-            stake_position,
-            base_token_mint,
-            market_base_token_ata,
-            user_base_token_ata,
-            signer,
-            amount,
-        }
+        self
     }
 }
 
-impl InstructionGenerator for DepositAction {
+impl InstructionGenerator for ReleaseAction {
     fn accounts(&self) -> Vec<AccountMeta> {
         let mut accounts = vec![
             AccountMeta::new(self.market, false),
-            AccountMeta::new(self.market_staking, false),
-            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new(self.staking, false),
+            AccountMeta::new(self.staking_position, false),
+            AccountMeta::new(self.vesting_plan, false),
             AccountMeta::new_readonly(self.base_token_mint, false),
             AccountMeta::new(self.market_base_token_ata, false),
             AccountMeta::new(self.user_base_token_ata, false),
@@ -1269,9 +9796,7 @@ impl InstructionGenerator for DepositAction {
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::Deposit {
-            amount: self.amount,
-        };
+        let input = token_mill::instruction::Release {};
 
         Instruction {
             program_id: token_mill::ID,
@@ -1281,42 +9806,53 @@ impl InstructionGenerator for DepositAction {
     }
 }
 
-pub struct WithdrawAction {
+pub struct ReleaseAllAction {
     // Accounts
     pub market: Pubkey,
-    pub market_staking: Pubkey,
-    pub stake_position: Pubkey,
+    pub staking: Pubkey,
+    pub staking_position: Pubkey,
     pub base_token_mint: Pubkey,
     pub market_base_token_ata: Pubkey,
     pub user_base_token_ata: Pubkey,
     pub signer: Pubkey,
-    // Args
-    pub amount: u64,
+    // Remaining accounts
+    pub vesting_plans: Vec<Pubkey>,
 }
 
-impl WithdrawAction {
-    pub fn new(testing_env: &TokenMillEnv, amount: u64) -> Self {
-        let deposit_action = DepositAction::new(testing_env, amount);
+impl ReleaseAllAction {
+    pub fn new(vesting_plans: Vec<Pubkey>) -> Self {
+        let release_action = ReleaseAction::new();
 
         Self {
-            market: deposit_action.market,
-            market_staking: deposit_action.market_staking,
-            stake_position: deposit_action.stake_position,
-            base_token_mint: deposit_action.base_token_mint,
-            market_base_token_ata: deposit_action.market_base_token_ata,
-            user_base_token_ata: deposit_action.user_base_token_ata,
-            signer: deposit_action.signer,
-            amount,
+            market: release_action.market,
+            staking: release_action.staking,
+            staking_position: release_action.staking_position,
+            base_token_mint: release_action.base_token_mint,
+            market_base_token_ata: release_action.market_base_token_ata,
+            user_base_token_ata: release_action.user_base_token_ata,
+            signer: release_action.signer,
+            vesting_plans,
         }
     }
+
+    pub fn with_user(&mut self, user: Pubkey) -> &mut Self {
+        let mut release_action = ReleaseAction::new();
+        release_action.with_user(user);
+
+        self.signer = release_action.signer;
+        self.staking_position = release_action.staking_position;
+        self.user_base_token_ata = release_action.user_base_token_ata;
+
+        self
+    }
 }
 
-impl InstructionGenerator for WithdrawAction {
+impl InstructionGenerator for ReleaseAllAction {
     fn accounts(&self) -> Vec<AccountMeta> {
         let mut accounts = vec![
             AccountMeta::new(self.market, false),
-            AccountMeta::new(self.market_staking, false),
-            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new(self.staking, false),
+            AccountMeta::new(self.staking_position, false),
             AccountMeta::new_readonly(self.base_token_mint, false),
             AccountMeta::new(self.market_base_token_ata, false),
             AccountMeta::new(self.user_base_token_ata, false),
@@ -1327,13 +9863,15 @@ impl InstructionGenerator for WithdrawAction {
             .append_token_2022_program()
             .append_cpi_event_accounts(tm_event_authority());
 
+        for vesting_plan in &self.vesting_plans {
+            accounts.push(AccountMeta::new(*vesting_plan, false));
+        }
+
         accounts
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::Withdraw {
-            amount: self.amount,
-        };
+        let input = token_mill::instruction::ReleaseAll {};
 
         Instruction {
             program_id: token_mill::ID,
@@ -1343,99 +9881,55 @@ impl InstructionGenerator for WithdrawAction {
     }
 }
 
-pub struct ClaimStakingRewardsAction {
+pub struct RevokeVestingPlanAction {
     // Accounts
     pub market: Pubkey,
-    pub market_staking: Pubkey,
+    pub staking: Pubkey,
     pub stake_position: Pubkey,
-    pub quote_token_mint: Pubkey,
-    pub market_quote_token_ata: Pubkey,
-    pub user_quote_token_ata: Pubkey,
-    pub quote_token_program: Pubkey,
+    pub vesting_plan: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub funder_base_token_ata: Pubkey,
     pub signer: Pubkey,
 }
 
-impl ClaimStakingRewardsAction {
-    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
-        let base_token_mint = token_mill_env.base_token_mint.unwrap();
-        let signer = make_address("bob");
-
-        let market = Pubkey::find_program_address(
-            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
-            &token_mill::ID,
-        )
-        .0;
-
-        let market_staking = Pubkey::find_program_address(
-            &[MARKET_STAKING_PDA_SEED.as_bytes(), &market.to_bytes()],
-            &token_mill::ID,
-        )
-        .0;
-
-        let stake_position = Pubkey::find_program_address(
-            &[
-                STAKING_POSITION_PDA_SEED.as_bytes(),
-                &market.to_bytes(),
-                &signer.to_bytes(),
-            ],
-            &token_mill::ID,
-        )
-        .0;
-
-        let quote_token_mint = token_mill_env.quote_token_mint.unwrap();
-        let quote_token_program = token_mill_env.quote_token_type.program_address();
-
-        let market_quote_token_ata = get_associated_token_address_with_program_id(
-            &market,
-            &quote_token_mint,
-            &quote_token_program,
-        );
-
-        let user_quote_token_ata = get_associated_token_address_with_program_id(
-            &signer,
-            &quote_token_mint,
-            &quote_token_program,
-        );
-
+impl RevokeVestingPlanAction {
+    pub fn new(create_action: &CreateCreatorVestingPlanAction) -> Self {
         Self {
-            market,
-            market_staking,
-            stake_position,
-            quote_token_mint,
-            market_quote_token_ata,
-            user_quote_token_ata,
-            quote_token_program,
-            signer,
+            market: create_action.market,
+            staking: create_action.staking,
+            stake_position: create_action.stake_position,
+            vesting_plan: create_action.vesting_plan,
+            base_token_mint: create_action.base_token_mint,
+            market_base_token_ata: create_action.market_base_token_ata,
+            funder_base_token_ata: create_action.creator_base_token_ata,
+            signer: create_action.signer,
         }
     }
 }
 
-impl InstructionGenerator for ClaimStakingRewardsAction {
+impl InstructionGenerator for RevokeVestingPlanAction {
     fn accounts(&self) -> Vec<AccountMeta> {
         let mut accounts = vec![
             AccountMeta::new(self.market, false),
-            AccountMeta::new(self.market_staking, false),
+            AccountMeta::new(self.staking, false),
             AccountMeta::new(self.stake_position, false),
-            AccountMeta::new_readonly(self.quote_token_mint, false),
-            AccountMeta::new(self.market_quote_token_ata, false),
-            AccountMeta::new(self.user_quote_token_ata, false),
+            AccountMeta::new(self.vesting_plan, false),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.funder_base_token_ata, false),
         ];
 
-        accounts.append_payer(self.signer);
-
-        match self.quote_token_program {
-            spl_token::ID => accounts.append_token_program(),
-            spl_token_2022::ID => accounts.append_token_2022_program(),
-            _ => unreachable!(),
-        };
-
-        accounts.append_cpi_event_accounts(tm_event_authority());
+        accounts
+            .append_payer(self.signer)
+            .append_token_2022_program()
+            .append_cpi_event_accounts(tm_event_authority());
 
         accounts
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::ClaimStakingRewards {};
+        let input = token_mill::instruction::RevokeVestingPlan {};
 
         Instruction {
             program_id: token_mill::ID,
@@ -1445,55 +9939,83 @@ impl InstructionGenerator for ClaimStakingRewardsAction {
     }
 }
 
-pub struct CreateReferralAccountAction {
-    // Accounts
-    pub config: Pubkey,
-    pub referral_account: Pubkey,
-    pub signer: Pubkey,
-    // Args
-    pub referrer: Pubkey,
-}
-
-impl Default for CreateReferralAccountAction {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl CreateReferralAccountAction {
-    pub fn new() -> Self {
-        let config = make_address("config");
-
-        let referrer = make_address("carol");
+pub struct TransferVestingPlanAction {
+    // Accounts
+    pub market: Pubkey,
+    pub staking: Pubkey,
+    pub stake_position: Pubkey,
+    pub new_stake_position: Pubkey,
+    pub vesting_plan: Pubkey,
+    pub lock_certificate: Pubkey,
+    pub new_beneficiary: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub market_base_token_ata: Pubkey,
+    pub user_base_token_ata: Pubkey,
+    pub signer: Pubkey,
+}
 
-        let referral_account = Pubkey::find_program_address(
+impl TransferVestingPlanAction {
+    pub fn new(create_action: &CreateCreatorVestingPlanAction, new_beneficiary: Pubkey) -> Self {
+        let lock_certificate = Pubkey::find_program_address(
             &[
-                REFERRAL_ACCOUNT_PDA_SEED.as_bytes(),
-                (config.as_ref()),
-                (referrer.as_ref()),
+                LOCK_CERTIFICATE_PDA_SEED.as_bytes(),
+                &create_action.vesting_plan.to_bytes(),
             ],
             &token_mill::ID,
         )
         .0;
 
+        let user_base_token_ata = get_associated_token_address_with_program_id(
+            &create_action.recipient,
+            &create_action.base_token_mint,
+            &spl_token_2022::id(),
+        );
+
         Self {
-            config,
-            referral_account,
-            signer: make_address("admin"),
-            referrer,
+            market: create_action.market,
+            staking: create_action.staking,
+            stake_position: create_action.stake_position,
+            new_stake_position: stake_position_address(&create_action.market, &new_beneficiary),
+            vesting_plan: create_action.vesting_plan,
+            lock_certificate,
+            new_beneficiary,
+            base_token_mint: create_action.base_token_mint,
+            market_base_token_ata: create_action.market_base_token_ata,
+            user_base_token_ata,
+            signer: create_action.recipient,
         }
     }
+
+    pub fn with_new_beneficiary(&mut self, new_beneficiary: Pubkey) -> &mut Self {
+        self.new_beneficiary = new_beneficiary;
+        self.new_stake_position = stake_position_address(&self.market, &new_beneficiary);
+
+        self
+    }
 }
 
-impl InstructionGenerator for CreateReferralAccountAction {
+impl InstructionGenerator for TransferVestingPlanAction {
     fn accounts(&self) -> Vec<AccountMeta> {
         let mut accounts = vec![
-            AccountMeta::new_readonly(self.config, false),
-            AccountMeta::new(self.referral_account, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.staking, false),
+            AccountMeta::new(self.stake_position, false),
+            AccountMeta::new(self.new_stake_position, false),
+            AccountMeta::new(self.vesting_plan, false),
+            AccountMeta::new_readonly(self.lock_certificate, false),
+            AccountMeta::new_readonly(self.new_beneficiary, false),
+            AccountMeta::new_readonly(
+                restricted_wallet_address(&self.market, &self.new_beneficiary),
+                false,
+            ),
+            AccountMeta::new_readonly(self.base_token_mint, false),
+            AccountMeta::new(self.market_base_token_ata, false),
+            AccountMeta::new(self.user_base_token_ata, false),
         ];
 
         accounts
             .append_payer(self.signer)
+            .append_token_2022_program()
             .append_system_program()
             .append_cpi_event_accounts(tm_event_authority());
 
@@ -1501,9 +10023,7 @@ impl InstructionGenerator for CreateReferralAccountAction {
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::CreateReferralAccount {
-            referrer: self.referrer,
-        };
+        let input = token_mill::instruction::TransferVestingPlan {};
 
         Instruction {
             program_id: token_mill::ID,
@@ -1513,82 +10033,36 @@ impl InstructionGenerator for CreateReferralAccountAction {
     }
 }
 
-pub struct ClaimReferralFeesAction {
-    pub config: Pubkey,
-    pub referral_account: Pubkey,
-    pub quote_token_mint: Pubkey,
-    pub referral_account_quote_token_ata: Pubkey,
-    pub referrer_quote_token_ata: Pubkey,
+#[derive(Debug)]
+pub struct UpgradeVestingPlanAction {
+    // Accounts
+    pub vesting_plan: Pubkey,
     pub signer: Pubkey,
-    pub quote_token_program: Pubkey,
 }
 
-impl ClaimReferralFeesAction {
-    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
-        let config = make_address("config");
-        let signer = make_address("carol");
-
-        let referral_account = Pubkey::find_program_address(
-            &[
-                REFERRAL_ACCOUNT_PDA_SEED.as_bytes(),
-                (config.as_ref()),
-                (signer.as_ref()),
-            ],
-            &token_mill::ID,
-        )
-        .0;
-
-        let quote_token_mint = token_mill_env.quote_token_mint.unwrap();
-        let quote_token_program = token_mill_env.quote_token_type.program_address();
-
-        let referral_account_quote_token_ata = get_associated_token_address_with_program_id(
-            &referral_account,
-            &quote_token_mint,
-            &quote_token_program,
-        );
-
-        let referrer_quote_token_ata = get_associated_token_address_with_program_id(
-            &signer,
-            &quote_token_mint,
-            &quote_token_program,
-        );
-
+impl UpgradeVestingPlanAction {
+    pub fn new(vesting_plan: Pubkey) -> Self {
         Self {
-            config,
-            referral_account,
-            quote_token_mint,
-            referral_account_quote_token_ata,
-            referrer_quote_token_ata,
-            signer,
-            quote_token_program,
+            vesting_plan,
+            signer: make_address("carol"),
         }
     }
 }
 
-impl InstructionGenerator for ClaimReferralFeesAction {
+impl InstructionGenerator for UpgradeVestingPlanAction {
     fn accounts(&self) -> Vec<AccountMeta> {
-        let mut accounts = vec![
-            AccountMeta::new_readonly(self.referral_account, false),
-            AccountMeta::new_readonly(self.quote_token_mint, false),
-            AccountMeta::new(self.referral_account_quote_token_ata, false),
-            AccountMeta::new(self.referrer_quote_token_ata, false),
-        ];
-
-        accounts.append_payer(self.signer);
-
-        match self.quote_token_program {
-            spl_token::ID => accounts.append_token_program(),
-            spl_token_2022::ID => accounts.append_token_2022_program(),
-            _ => unreachable!(),
-        };
+        let mut accounts = vec![AccountMeta::new(self.vesting_plan, false)];
 
-        accounts.append_cpi_event_accounts(tm_event_authority());
+        accounts
+            .append_payer(self.signer)
+            .append_system_program()
+            .append_cpi_event_accounts(tm_event_authority());
 
         accounts
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::ClaimReferralFees {};
+        let input = token_mill::instruction::UpgradeVestingPlan {};
 
         Instruction {
             program_id: token_mill::ID,
@@ -1598,100 +10072,55 @@ impl InstructionGenerator for ClaimReferralFeesAction {
     }
 }
 
-pub struct CreateVestingPlanAction {
+pub struct IssueVestingLockCertificateAction {
     // Accounts
     pub market: Pubkey,
-    pub staking: Pubkey,
-    pub staking_position: Pubkey,
+    pub stake_position: Pubkey,
     pub vesting_plan: Pubkey,
-    pub base_token_mint: Pubkey,
-    pub market_base_token_ata: Pubkey,
-    pub user_base_token_ata: Pubkey,
+    pub lock_certificate: Pubkey,
     pub signer: Pubkey,
-    // Args
-    pub vesting_amount: u64,
-    pub start: i64,
-    pub vesting_duration: i64,
-    pub cliff_duration: i64,
 }
 
-impl CreateVestingPlanAction {
-    pub fn new(
-        vesting_amount: u64,
-        start: i64,
-        vesting_duration: i64,
-        cliff_duration: i64,
-    ) -> Self {
-        let base_token_mint = make_address("base_token_mint");
-        let vesting_plan = make_address("vesting_plan");
-        let signer = make_address("bob");
-
-        let market = Pubkey::find_program_address(
-            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
-            &token_mill::ID,
-        )
-        .0;
+impl Default for IssueVestingLockCertificateAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        let staking = Pubkey::find_program_address(
-            &[MARKET_STAKING_PDA_SEED.as_bytes(), &market.to_bytes()],
-            &token_mill::ID,
-        )
-        .0;
+impl IssueVestingLockCertificateAction {
+    pub fn new() -> Self {
+        let create_vesting_plan_action = CreateVestingPlanAction::new(0, 0, 0, 0);
 
-        let staking_position = Pubkey::find_program_address(
+        let lock_certificate = Pubkey::find_program_address(
             &[
-                STAKING_POSITION_PDA_SEED.as_bytes(),
-                &market.to_bytes(),
-                &signer.to_bytes(),
+                LOCK_CERTIFICATE_PDA_SEED.as_bytes(),
+                &create_vesting_plan_action.vesting_plan.to_bytes(),
             ],
             &token_mill::ID,
         )
         .0;
 
-        let market_base_token_ata = get_associated_token_address_with_program_id(
-            &market,
-            &base_token_mint,
-            &spl_token_2022::id(),
-        );
-
-        let user_base_token_ata = get_associated_token_address_with_program_id(
-            &signer,
-            &base_token_mint,
-            &spl_token_2022::id(),
-        );
-
         Self {
-            market,
-            staking,
-            staking_position,
-            vesting_plan,
-            base_token_mint,
-            market_base_token_ata,
-            user_base_token_ata,
-            signer,
-            vesting_amount,
-            start,
-            vesting_duration,
-            cliff_duration,
+            market: create_vesting_plan_action.market,
+            stake_position: create_vesting_plan_action.staking_position,
+            vesting_plan: create_vesting_plan_action.vesting_plan,
+            lock_certificate,
+            signer: make_address("carol"),
         }
     }
 }
 
-impl InstructionGenerator for CreateVestingPlanAction {
+impl InstructionGenerator for IssueVestingLockCertificateAction {
     fn accounts(&self) -> Vec<AccountMeta> {
         let mut accounts = vec![
-            AccountMeta::new(self.market, false),
-            AccountMeta::new(self.staking, false),
-            AccountMeta::new(self.staking_position, false),
-            AccountMeta::new(self.vesting_plan, true),
-            AccountMeta::new_readonly(self.base_token_mint, false),
-            AccountMeta::new(self.market_base_token_ata, false),
-            AccountMeta::new(self.user_base_token_ata, false),
+            AccountMeta::new_readonly(self.market, false),
+            AccountMeta::new_readonly(self.stake_position, false),
+            AccountMeta::new_readonly(self.vesting_plan, false),
+            AccountMeta::new(self.lock_certificate, false),
+            AccountMeta::new(self.signer, true),
         ];
 
         accounts
-            .append_payer(self.signer)
-            .append_token_2022_program()
             .append_system_program()
             .append_cpi_event_accounts(tm_event_authority());
 
@@ -1699,12 +10128,7 @@ impl InstructionGenerator for CreateVestingPlanAction {
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::CreateVestingPlan {
-            vesting_amount: self.vesting_amount,
-            start: self.start,
-            vesting_duration: self.vesting_duration,
-            cliff_duration: self.cliff_duration,
-        };
+        let input = token_mill::instruction::IssueVestingLockCertificate {};
 
         Instruction {
             program_id: token_mill::ID,
@@ -1714,63 +10138,70 @@ impl InstructionGenerator for CreateVestingPlanAction {
     }
 }
 
-pub struct ReleaseAction {
+pub struct GarbageCollectAction {
     // Accounts
-    pub market: Pubkey,
-    pub staking: Pubkey,
-    pub staking_position: Pubkey,
+    pub stake_position: Pubkey,
     pub vesting_plan: Pubkey,
-    pub base_token_mint: Pubkey,
-    pub market_base_token_ata: Pubkey,
-    pub user_base_token_ata: Pubkey,
+    pub user: Pubkey,
+    pub funder: Pubkey,
     pub signer: Pubkey,
 }
 
-impl Default for ReleaseAction {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+impl GarbageCollectAction {
+    pub fn new(token_mill_env: &TokenMillEnv) -> Self {
+        let base_token_mint = token_mill_env.base_token_mint.unwrap();
+        let user = make_address("bob");
 
-impl ReleaseAction {
-    pub fn new() -> Self {
-        let create_vesting_plan_action = CreateVestingPlanAction::new(0, 0, 0, 0);
+        let market = Pubkey::find_program_address(
+            &[MARKET_PDA_SEED.as_bytes(), &base_token_mint.to_bytes()],
+            &token_mill::ID,
+        )
+        .0;
+
+        let stake_position = Pubkey::find_program_address(
+            &[
+                STAKING_POSITION_PDA_SEED.as_bytes(),
+                &market.to_bytes(),
+                &user.to_bytes(),
+            ],
+            &token_mill::ID,
+        )
+        .0;
 
         Self {
-            market: create_vesting_plan_action.market,
-            staking: create_vesting_plan_action.staking,
-            staking_position: create_vesting_plan_action.staking_position,
-            vesting_plan: create_vesting_plan_action.vesting_plan,
-            base_token_mint: create_vesting_plan_action.base_token_mint,
-            market_base_token_ata: create_vesting_plan_action.market_base_token_ata,
-            user_base_token_ata: create_vesting_plan_action.user_base_token_ata,
-            signer: create_vesting_plan_action.signer,
+            stake_position,
+            vesting_plan: token_mill::ID,
+            user,
+            funder: token_mill::ID,
+            signer: make_address("dave"),
         }
     }
+
+    pub fn with_vesting_plan(&mut self, vesting_plan: Pubkey, funder: Pubkey) -> &mut Self {
+        self.vesting_plan = vesting_plan;
+        self.funder = funder;
+
+        self
+    }
 }
 
-impl InstructionGenerator for ReleaseAction {
+impl InstructionGenerator for GarbageCollectAction {
     fn accounts(&self) -> Vec<AccountMeta> {
         let mut accounts = vec![
-            AccountMeta::new(self.market, false),
-            AccountMeta::new(self.staking, false),
-            AccountMeta::new(self.staking_position, false),
+            AccountMeta::new(self.stake_position, false),
             AccountMeta::new(self.vesting_plan, false),
-            AccountMeta::new_readonly(self.base_token_mint, false),
-            AccountMeta::new(self.market_base_token_ata, false),
-            AccountMeta::new(self.user_base_token_ata, false),
+            AccountMeta::new(self.user, false),
+            AccountMeta::new(self.funder, false),
+            AccountMeta::new(self.signer, true),
         ];
 
-        accounts
-            .append_payer(self.signer)
-            .append_token_2022_program()
-            .append_cpi_event_accounts(tm_event_authority());
+        accounts.append_cpi_event_accounts(tm_event_authority());
 
         accounts
     }
 
     fn instruction(&self) -> Instruction {
-        let input = token_mill::instruction::Release {};
+        let input = token_mill::instruction::GarbageCollect {};
 
         Instruction {
             program_id: token_mill::ID,
@@ -1,14 +1,18 @@
 use anchor_lang::{
     prelude::{AccountMeta, Clock},
-    AccountDeserialize,
+    AccountDeserialize, AnchorSerialize,
 };
 use anchor_spl::token_interface::spl_token_2022::{
-    extension::StateWithExtensions, solana_program::program_pack::Pack,
+    extension::{
+        BaseStateWithExtensionsMut, ExtensionType, StateWithExtensions, StateWithExtensionsMut,
+    },
+    solana_program::{program_option::COption, program_pack::Pack},
     state::Account as SplAccount,
 };
 use anchor_spl::{
     associated_token::spl_associated_token_account,
-    token_2022::spl_token_2022::{self},
+    token::spl_token,
+    token_2022::spl_token_2022::{self, instruction::AuthorityType},
 };
 use anyhow::Result;
 use litesvm::{
@@ -26,11 +30,16 @@ use solana_sdk::{
     signature::Keypair,
     transaction::{Transaction, TransactionError},
 };
+use spl_token_group_interface::state::TokenGroupMember;
 use std::collections::HashMap;
+use token_mill::manager::price_feed_manager::{
+    PriceFeedMessage, PriceUpdateV2, VerificationLevel, PRICE_UPDATE_V2_DISCRIMINATOR,
+    PYTH_RECEIVER_PROGRAM_ID,
+};
 
 pub use token_mill::{
     errors::TokenMillError,
-    manager::swap_manager::{SwapAmountType, SwapType},
+    manager::swap_manager::{SwapAmountType, SwapDeadline, SwapFillType, SwapType},
     state::QuoteTokenBadgeStatus,
 };
 
@@ -139,6 +148,20 @@ impl JoelanaEnv {
         self.svm_engine.set_sysvar(&clock);
     }
 
+    pub fn warp_to_slot(&mut self, slot: u64) {
+        self.svm_engine.warp_to_slot(slot);
+    }
+
+    pub fn warp_epochs(&mut self, epochs: u64) {
+        let mut clock = self.svm_engine.get_sysvar::<Clock>();
+        clock.epoch += epochs;
+        self.svm_engine.set_sysvar(&clock);
+    }
+
+    pub fn get_clock(&self) -> Clock {
+        self.svm_engine.get_sysvar::<Clock>()
+    }
+
     pub fn create_token(&mut self, token_type: TokenType, decimals: u8) -> Result<Pubkey> {
         let payer = Keypair::new();
 
@@ -170,6 +193,256 @@ impl JoelanaEnv {
         Ok(token_address)
     }
 
+    /// Sets up the wrapped SOL mint, which isn't preloaded by LiteSVM
+    pub fn create_native_mint(&mut self) -> Result<Pubkey> {
+        let mint = spl_token::state::Mint {
+            decimals: 9,
+            is_initialized: true,
+            ..Default::default()
+        };
+
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint::pack(mint, &mut data)?;
+
+        self.svm_engine.set_account(
+            spl_token::native_mint::ID,
+            Account {
+                lamports: sol_to_lamports(1.0),
+                data,
+                owner: spl_token::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )?;
+
+        self.tokens
+            .insert(spl_token::native_mint::ID, TokenType::Token);
+
+        Ok(spl_token::native_mint::ID)
+    }
+
+    /// Sets up a fully verified Pyth price update, as posted by the Pyth receiver program
+    pub fn set_pyth_price_update(
+        &mut self,
+        address: Pubkey,
+        price: i64,
+        exponent: i32,
+        publish_time: i64,
+    ) -> Result<()> {
+        let price_update = PriceUpdateV2 {
+            write_authority: Pubkey::default(),
+            verification_level: VerificationLevel::Full,
+            price_message: PriceFeedMessage {
+                feed_id: [0; 32],
+                price,
+                conf: 0,
+                exponent,
+                publish_time,
+                prev_publish_time: publish_time,
+                ema_price: price,
+                ema_conf: 0,
+            },
+            posted_slot: 0,
+        };
+
+        let mut data = PRICE_UPDATE_V2_DISCRIMINATOR.to_vec();
+        price_update.serialize(&mut data)?;
+
+        self.svm_engine.set_account(
+            address,
+            Account {
+                lamports: sol_to_lamports(1.0),
+                data,
+                owner: PYTH_RECEIVER_PROGRAM_ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Creates a token whose whole supply is held by `holder`, with the mint authority removed
+    pub fn create_fixed_supply_token(
+        &mut self,
+        token_type: TokenType,
+        decimals: u8,
+        holder: &Pubkey,
+        supply: u64,
+    ) -> Result<Pubkey> {
+        let payer = Keypair::new();
+
+        self.airdrop(&payer.pubkey());
+
+        let token_address = CreateMint::new(&mut self.svm_engine, &payer)
+            .decimals(decimals)
+            .token_program_id(&token_type.program_address())
+            .send()
+            .unwrap();
+
+        let holder_ata = self.create_ata(holder, &token_address, token_type)?;
+
+        MintTo::new(
+            &mut self.svm_engine,
+            &payer,
+            &token_address,
+            &holder_ata,
+            supply,
+        )
+        .token_program_id(&token_type.program_address())
+        .send()
+        .unwrap();
+
+        let remove_authority_instruction = spl_token_2022::instruction::set_authority(
+            &token_type.program_address(),
+            &token_address,
+            None,
+            AuthorityType::MintTokens,
+            &payer.pubkey(),
+            &[],
+        )?;
+
+        let tx = Transaction::new_signed_with_payer(
+            &[remove_authority_instruction],
+            Some(&payer.pubkey()),
+            &[&payer],
+            self.svm_engine.latest_blockhash(),
+        );
+
+        self.svm_engine.send_transaction(tx).unwrap();
+
+        self.tokens.insert(token_address, token_type);
+
+        Ok(token_address)
+    }
+
+    /// Creates an NFT registered as a member of the Token-2022 `group`, held by `holder`
+    pub fn create_group_member_nft(&mut self, group: &Pubkey, holder: &Pubkey) -> Result<Pubkey> {
+        let mint_authority = Keypair::new();
+        let token_address = Pubkey::new_unique();
+
+        self.airdrop(&mint_authority.pubkey());
+
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::TokenGroupMember,
+        ])?;
+        let mut data = vec![0; space];
+
+        let mut mint =
+            StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data)?;
+
+        let member = mint.init_extension::<TokenGroupMember>(true)?;
+        member.mint = token_address;
+        member.group = *group;
+
+        mint.base = spl_token_2022::state::Mint {
+            mint_authority: COption::Some(mint_authority.pubkey()),
+            decimals: 0,
+            is_initialized: true,
+            ..Default::default()
+        };
+        mint.pack_base();
+        mint.init_account_type()?;
+
+        self.svm_engine.set_account(
+            token_address,
+            Account {
+                lamports: sol_to_lamports(1.0),
+                data,
+                owner: spl_token_2022::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )?;
+
+        let holder_ata = self.create_ata(holder, &token_address, TokenType::Token2022)?;
+
+        MintTo::new(
+            &mut self.svm_engine,
+            &mint_authority,
+            &token_address,
+            &holder_ata,
+            1,
+        )
+        .token_program_id(&spl_token_2022::ID)
+        .send()
+        .unwrap();
+
+        self.tokens.insert(token_address, TokenType::Token2022);
+
+        Ok(token_address)
+    }
+
+    /// Moves an NFT from the associated token account of `from` to the one of `to`, created if needed
+    pub fn transfer_nft(&mut self, token_mint: &Pubkey, from: &Pubkey, to: &Pubkey) -> Result<()> {
+        let token_type = self.tokens[token_mint];
+        let to_ata = self.get_ata_address(token_mint, to);
+
+        if self.try_get_account(&to_ata).is_none() {
+            self.create_ata(to, token_mint, token_type)?;
+        }
+
+        let transfer_instruction = spl_token_2022::instruction::transfer_checked(
+            &token_type.program_address(),
+            &self.get_ata_address(token_mint, from),
+            token_mint,
+            &to_ata,
+            from,
+            &[],
+            1,
+            0,
+        )?;
+
+        let payer = self.payer;
+        self.payer = *from;
+
+        let result = self.execute(&[transfer_instruction]);
+
+        self.payer = payer;
+
+        result.map_err(|err| anyhow::anyhow!("{:?}", err.err))?;
+
+        Ok(())
+    }
+
+    /// Transfers `amount` tokens from the associated token account of `from` to the existing one of `to`
+    pub fn transfer_tokens(
+        &mut self,
+        token_mint: &Pubkey,
+        from: &Pubkey,
+        to: &Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let token_type = self.tokens[token_mint];
+        let mint_account = self.get_account(token_mint);
+        let decimals =
+            StateWithExtensions::<spl_token_2022::state::Mint>::unpack(mint_account.data())?
+                .base
+                .decimals;
+
+        let transfer_instruction = spl_token_2022::instruction::transfer_checked(
+            &token_type.program_address(),
+            &self.get_ata_address(token_mint, from),
+            token_mint,
+            &self.get_ata_address(token_mint, to),
+            from,
+            &[],
+            amount,
+            decimals,
+        )?;
+
+        let payer = self.payer;
+        self.payer = *from;
+
+        let result = self.execute(&[transfer_instruction]);
+
+        self.payer = payer;
+
+        result.map_err(|err| anyhow::anyhow!("{:?}", err.err))?;
+
+        Ok(())
+    }
+
     pub fn create_ata(
         &mut self,
         wallet: &Pubkey,
@@ -196,6 +469,14 @@ impl JoelanaEnv {
             .expect("Account not found")
     }
 
+    pub fn try_get_account(&self, pubkey: &Pubkey) -> Option<Account> {
+        self.svm_engine.get_account(pubkey)
+    }
+
+    pub fn set_account(&mut self, pubkey: Pubkey, account: Account) {
+        self.svm_engine.set_account(pubkey, account).unwrap();
+    }
+
     pub fn get_parsed_account<T>(&self, pubkey: &Pubkey) -> T
     where
         T: AccountDeserialize,
@@ -215,6 +496,12 @@ impl JoelanaEnv {
         )
     }
 
+    pub fn get_lamports(&self, pubkey: &Pubkey) -> u64 {
+        self.try_get_account(pubkey)
+            .map(|account| account.lamports)
+            .unwrap_or_default()
+    }
+
     pub fn get_balance(&self, token_mint: &Pubkey, wallet: &Pubkey) -> u64 {
         let token_ata = self.get_ata_address(token_mint, wallet);
 
@@ -2,30 +2,36 @@ use token_mill::constant::{PRICES_LENGTH, SCALE};
 
 const SCALE_EVM: u128 = 1_000_000_000_000_000_000; //1e18
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct Curve {
-    pub bid_prices: [u64; PRICES_LENGTH],
-    pub ask_prices: [u64; PRICES_LENGTH],
+    pub bid_prices: Vec<u64>,
+    pub ask_prices: Vec<u64>,
 }
 
 impl Default for Curve {
     fn default() -> Self {
-        let mut bid_prices = [0; PRICES_LENGTH];
-        let mut ask_prices = [0; PRICES_LENGTH];
+        Self::linear(PRICES_LENGTH)
+    }
+}
 
-        for i in 0..PRICES_LENGTH {
-            bid_prices[i] = i as u64 * SCALE as u64 * 9 / 10_000;
-            ask_prices[i] = i as u64 * SCALE as u64 / 1_000;
-        }
+impl Curve {
+    /// Default curve sampled with `prices_length` prices, the prices at the full supply being the same
+    pub fn linear(prices_length: usize) -> Self {
+        let interval_number = prices_length as u64 - 1;
+
+        let bid_prices = (0..interval_number + 1)
+            .map(|i| i * SCALE as u64 * 9 / (1_000 * interval_number))
+            .collect();
+        let ask_prices = (0..interval_number + 1)
+            .map(|i| i * SCALE as u64 / (100 * interval_number))
+            .collect();
 
         Self {
             bid_prices,
             ask_prices,
         }
     }
-}
 
-impl Curve {
     pub fn to_evm(&self) -> (Vec<u128>, Vec<u128>) {
         (
             self.bid_prices
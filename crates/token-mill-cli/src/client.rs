@@ -0,0 +1,163 @@
+use std::{mem::size_of, str::FromStr};
+
+use anchor_lang::{event::EVENT_IX_TAG_LE, AccountDeserialize, Discriminator};
+use anyhow::{anyhow, ensure, Context, Result};
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::RpcTransactionConfig,
+};
+use solana_sdk::{
+    account::from_account,
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signature},
+    signer::Signer,
+    sysvar::{self, clock::Clock},
+    transaction::Transaction,
+};
+use solana_transaction_status::{UiInnerInstructions, UiInstruction, UiTransactionEncoding};
+use token_mill::state::Market;
+
+pub struct Client {
+    rpc: RpcClient,
+    keypair_path: String,
+}
+
+impl Client {
+    pub fn new(url: &str, keypair_path: &str) -> Self {
+        Self {
+            rpc: RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed()),
+            keypair_path: expand_home(keypair_path),
+        }
+    }
+
+    pub fn payer(&self) -> Result<Keypair> {
+        read_keypair_file(&self.keypair_path)
+            .map_err(|error| anyhow!("Failed to read keypair {}: {error}", self.keypair_path))
+    }
+
+    pub fn get_account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        let data = self
+            .rpc
+            .get_account_data(address)
+            .with_context(|| format!("Account {address} not found"))?;
+
+        Ok(T::try_deserialize(&mut data.as_slice())?)
+    }
+
+    /// Markets are zero-copy accounts, read directly from their raw data
+    pub fn get_market(&self, address: &Pubkey) -> Result<Market> {
+        let data = self
+            .rpc
+            .get_account_data(address)
+            .with_context(|| format!("Market {address} not found"))?;
+
+        ensure!(
+            data.len() >= 8 + size_of::<Market>() && data[..8] == Market::DISCRIMINATOR,
+            "Account {address} is not a market"
+        );
+
+        Ok(bytemuck::pod_read_unaligned(
+            &data[8..8 + size_of::<Market>()],
+        ))
+    }
+
+    /// Clock sysvar of the cluster, to quote swaps at the current slot and timestamp
+    pub fn get_clock(&self) -> Result<Clock> {
+        let account = self.rpc.get_account(&sysvar::clock::ID)?;
+
+        from_account(&account).context("Invalid clock sysvar")
+    }
+
+    pub fn get_token_program(&self, mint: &Pubkey) -> Result<Pubkey> {
+        Ok(self.rpc.get_account(mint)?.owner)
+    }
+
+    /// Signatures of the successful transactions involving `address`, oldest first
+    pub fn get_signatures(&self, address: &Pubkey) -> Result<Vec<Signature>> {
+        let mut signatures = Vec::new();
+        let mut before = None;
+
+        loop {
+            let page = self.rpc.get_signatures_for_address_with_config(
+                address,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until: None,
+                    limit: None,
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )?;
+
+            let Some(last) = page.last() else {
+                break;
+            };
+
+            before = Some(Signature::from_str(&last.signature)?);
+
+            for status in page.iter().filter(|status| status.err.is_none()) {
+                signatures.push(Signature::from_str(&status.signature)?);
+            }
+        }
+
+        signatures.reverse();
+
+        Ok(signatures)
+    }
+
+    /// Events emitted through self-CPI by a transaction, stripped of the event instruction tag
+    pub fn get_events(&self, signature: &Signature) -> Result<Vec<Vec<u8>>> {
+        let transaction = self.rpc.get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            },
+        )?;
+
+        let inner_instructions: Option<Vec<UiInnerInstructions>> = transaction
+            .transaction
+            .meta
+            .and_then(|meta| meta.inner_instructions.into());
+
+        let mut events = Vec::new();
+
+        for instruction in inner_instructions
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|inner_instructions| inner_instructions.instructions)
+        {
+            if let UiInstruction::Compiled(instruction) = instruction {
+                let data = bs58::decode(&instruction.data).into_vec()?;
+
+                if let Some(event) = data.strip_prefix(EVENT_IX_TAG_LE) {
+                    events.push(event.to_vec());
+                }
+            }
+        }
+
+        Ok(events)
+    }
+
+    pub fn send(&self, instructions: &[Instruction]) -> Result<Signature> {
+        let payer = self.payer()?;
+
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &[&payer],
+            self.rpc.get_latest_blockhash()?,
+        );
+
+        Ok(self.rpc.send_and_confirm_transaction(&transaction)?)
+    }
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(relative_path), Ok(home)) => format!("{home}/{relative_path}"),
+        _ => path.to_string(),
+    }
+}
@@ -0,0 +1,188 @@
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anyhow::{ensure, Result};
+use clap::Subcommand;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signer::Signer,
+};
+use token_mill::state::{CreatorFeeSplit, CREATOR_FEE_SPLIT_PDA_SEED};
+
+use super::event_authority;
+use crate::client::Client;
+
+#[derive(Subcommand)]
+pub enum FeesCommand {
+    /// Claim the pending creator fees of a market, signed by the creator keypair.
+    /// Split fees are sent to the recipients of the split
+    Claim {
+        market: Pubkey,
+        /// Quote token account receiving the fees, defaults to the ATA of the keypair
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+    },
+    /// Claim the pending creator fees of several markets sharing a quote token in one transaction.
+    /// Markets with a fee split are not supported
+    ClaimAll {
+        #[arg(required = true)]
+        markets: Vec<Pubkey>,
+        /// Quote token account receiving the fees, defaults to the ATA of the keypair
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+    },
+}
+
+impl FeesCommand {
+    pub fn run(self, client: &Client) -> Result<()> {
+        match self {
+            FeesCommand::Claim { market, recipient } => claim(client, &market, recipient),
+            FeesCommand::ClaimAll { markets, recipient } => claim_all(client, &markets, recipient),
+        }
+    }
+}
+
+fn claim(client: &Client, address: &Pubkey, recipient: Option<Pubkey>) -> Result<()> {
+    let market = client.get_market(address)?;
+    let creator = client.payer()?.pubkey();
+    let quote_token_program = client.get_token_program(&market.quote_token_mint)?;
+
+    let creator_quote_token_ata = match recipient {
+        Some(recipient) => recipient,
+        None => get_associated_token_address_with_program_id(
+            &creator,
+            &market.quote_token_mint,
+            &quote_token_program,
+        ),
+    };
+
+    let creator_fee_split = (market.creator_fee_split == 1).then(|| {
+        Pubkey::find_program_address(
+            &[CREATOR_FEE_SPLIT_PDA_SEED.as_bytes(), address.as_ref()],
+            &token_mill::ID,
+        )
+        .0
+    });
+
+    let accounts = token_mill::accounts::ClaimCreatorFees {
+        market: *address,
+        quote_token_mint: market.quote_token_mint,
+        market_quote_token_ata: get_associated_token_address_with_program_id(
+            address,
+            &market.quote_token_mint,
+            &quote_token_program,
+        ),
+        creator_quote_token_ata,
+        creator_fee_split,
+        creator,
+        quote_token_program,
+        event_authority: event_authority(),
+        program: token_mill::ID,
+    };
+
+    let mut accounts = accounts.to_account_metas(None);
+
+    if let Some(creator_fee_split) = creator_fee_split {
+        let creator_fee_split = client.get_account::<CreatorFeeSplit>(&creator_fee_split)?;
+
+        accounts.extend(creator_fee_split.recipients().map(|entry| {
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(
+                    &entry.recipient,
+                    &market.quote_token_mint,
+                    &quote_token_program,
+                ),
+                false,
+            )
+        }));
+    }
+
+    let instruction = Instruction {
+        program_id: token_mill::ID,
+        accounts,
+        data: token_mill::instruction::ClaimCreatorFees {}.data(),
+    };
+
+    let signature = client.send(&[instruction])?;
+
+    println!(
+        "Claimed {} quote tokens: {signature}",
+        market.fees.pending_creator_fees
+    );
+
+    Ok(())
+}
+
+fn claim_all(client: &Client, addresses: &[Pubkey], recipient: Option<Pubkey>) -> Result<()> {
+    let creator = client.payer()?.pubkey();
+    let markets = addresses
+        .iter()
+        .map(|address| client.get_market(address))
+        .collect::<Result<Vec<_>>>()?;
+
+    let quote_token_mint = markets[0].quote_token_mint;
+    ensure!(
+        markets
+            .iter()
+            .all(|market| market.quote_token_mint == quote_token_mint),
+        "All markets must share the same quote token"
+    );
+    ensure!(
+        markets.iter().all(|market| market.creator_fee_split == 0),
+        "Markets with a creator fee split must be claimed individually"
+    );
+
+    let quote_token_program = client.get_token_program(&quote_token_mint)?;
+
+    let creator_quote_token_ata = match recipient {
+        Some(recipient) => recipient,
+        None => get_associated_token_address_with_program_id(
+            &creator,
+            &quote_token_mint,
+            &quote_token_program,
+        ),
+    };
+
+    let accounts = token_mill::accounts::ClaimAllCreatorFees {
+        quote_token_mint,
+        creator_quote_token_ata,
+        creator,
+        quote_token_program,
+        event_authority: event_authority(),
+        program: token_mill::ID,
+    };
+
+    let mut accounts = accounts.to_account_metas(None);
+
+    for address in addresses {
+        accounts.push(AccountMeta::new(*address, false));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                address,
+                &quote_token_mint,
+                &quote_token_program,
+            ),
+            false,
+        ));
+    }
+
+    let instruction = Instruction {
+        program_id: token_mill::ID,
+        accounts,
+        data: token_mill::instruction::ClaimAllCreatorFees {}.data(),
+    };
+
+    let signature = client.send(&[instruction])?;
+
+    let total: u64 = markets
+        .iter()
+        .map(|market| market.fees.pending_creator_fees)
+        .sum();
+
+    println!(
+        "Claimed {total} quote tokens from {} markets: {signature}",
+        markets.len()
+    );
+
+    Ok(())
+}
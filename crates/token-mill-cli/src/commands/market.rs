@@ -0,0 +1,129 @@
+use anyhow::Result;
+use clap::{Subcommand, ValueEnum};
+use solana_sdk::pubkey::Pubkey;
+use token_mill::manager::swap_manager::{self, SwapAmountType, SwapType};
+
+use crate::client::Client;
+
+#[derive(Subcommand)]
+pub enum MarketCommand {
+    /// Print the state of a market
+    Inspect { market: Pubkey },
+    /// Simulate a swap against the current state of a market, interface fees excluded
+    Quote {
+        market: Pubkey,
+        #[arg(value_enum)]
+        side: Side,
+        amount: u64,
+        /// Interpret `amount` as the amount received instead of the amount paid
+        #[arg(long)]
+        exact_output: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl MarketCommand {
+    pub fn run(self, client: &Client) -> Result<()> {
+        match self {
+            MarketCommand::Inspect { market } => inspect(client, &market),
+            MarketCommand::Quote {
+                market,
+                side,
+                amount,
+                exact_output,
+            } => quote(client, &market, side, amount, exact_output),
+        }
+    }
+}
+
+fn inspect(client: &Client, address: &Pubkey) -> Result<()> {
+    let market = client.get_market(address)?;
+
+    println!("Market:               {address}");
+    println!("Config:               {}", market.config);
+    println!("Creator:              {}", market.creator);
+    println!("Launchpad:            {}", market.launchpad);
+    println!("Base token mint:      {}", market.base_token_mint);
+    println!("Quote token mint:     {}", market.quote_token_mint);
+    println!("Total supply:         {}", market.total_supply);
+    println!("Base reserve:         {}", market.base_reserve);
+    println!("Circulating supply:   {}", market.circulating_supply());
+    println!("Pending supply:       {}", market.pending_supply);
+    println!("Burned supply:        {}", market.burned_supply);
+    println!("Escrowed base:        {}", market.escrowed_base);
+    println!("Escrowed quote:       {}", market.escrowed_quote);
+    println!("Quote raised:         {}", market.quote_raised);
+    println!("Max raise:            {}", market.max_raise);
+    println!("Sells paused until:   {}", market.sells_paused_until);
+    println!("Swap count:           {}", market.swap_count);
+    println!("Creator fee share:    {}", market.fees.creator_fee_share);
+    println!("Staking fee share:    {}", market.fees.staking_fee_share);
+    println!("Burn fee share:       {}", market.fees.burn_fee_share);
+    println!("Pending creator fees: {}", market.fees.pending_creator_fees);
+    println!("Pending staking fees: {}", market.fees.pending_staking_fees);
+    println!("Pending burn fees:    {}", market.fees.pending_burn_fees);
+
+    if market.are_prices_set() {
+        let prices_hash = market
+            .prices_hash
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+
+        println!("Prices hash:          {prices_hash}");
+        println!("Prices (bid / ask):");
+
+        for i in 0..market.prices_length() {
+            println!(
+                "  {i:>2}: {} / {}",
+                market.bid_prices[i], market.ask_prices[i]
+            );
+        }
+    } else {
+        println!("Prices:               not set");
+    }
+
+    Ok(())
+}
+
+fn quote(
+    client: &Client,
+    address: &Pubkey,
+    side: Side,
+    amount: u64,
+    exact_output: bool,
+) -> Result<()> {
+    // The swap is applied to a local copy of the market
+    let mut market = client.get_market(address)?;
+
+    let swap_type = match side {
+        Side::Buy => SwapType::Buy,
+        Side::Sell => SwapType::Sell,
+    };
+    let swap_amount_type = if exact_output {
+        SwapAmountType::ExactOutput
+    } else {
+        SwapAmountType::ExactInput
+    };
+
+    let (base_amount, quote_amount, swap_fee) = swap_manager::swap(
+        &mut market,
+        swap_type,
+        swap_amount_type,
+        amount,
+        &client.get_clock()?,
+    )?;
+
+    let (creator_fee, staking_fee, protocol_fee, _) = market.fees.distribute_fee(swap_fee, None)?;
+
+    println!("Base amount:  {base_amount}");
+    println!("Quote amount: {quote_amount}");
+    println!("Swap fee:     {swap_fee} (creator {creator_fee}, staking {staking_fee}, protocol {protocol_fee})");
+
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use std::{fs, path::Path, str::FromStr};
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anyhow::{anyhow, ensure, Context, Result};
+use clap::Subcommand;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signer::Signer};
+use token_mill::state::{
+    hash_holder, hash_nodes, Migration, MIGRATION_CLAIM_PDA_SEED, MIGRATION_PDA_SEED,
+};
+
+use super::event_authority;
+use crate::client::Client;
+
+#[derive(Subcommand)]
+pub enum MigrationCommand {
+    /// Print the merkle root of a holder snapshot, a CSV file of `wallet,amount` lines
+    Root { snapshot: String },
+    /// Record the import of a project into a wrap-mode market, signed by the creator keypair
+    Import {
+        market: Pubkey,
+        /// Program or market of the launchpad the project comes from
+        source: Pubkey,
+        snapshot: String,
+        snapshot_slot: u64,
+        /// Escrow the snapshot total so that holders can claim their allocation
+        #[arg(long)]
+        claimable: bool,
+    },
+    /// Claim the allocation of the keypair from the snapshot the migration was imported with
+    Claim { market: Pubkey, snapshot: String },
+}
+
+impl MigrationCommand {
+    pub fn run(self, client: &Client) -> Result<()> {
+        match self {
+            MigrationCommand::Root { snapshot } => {
+                let holders = read_snapshot(Path::new(&snapshot))?;
+
+                println!("Holders: {}", holders.len());
+                println!(
+                    "Total:   {}",
+                    holders.iter().map(|(_, amount)| amount).sum::<u64>()
+                );
+                println!("Root:    {}", to_hex(&get_root(&holders)));
+
+                Ok(())
+            }
+            MigrationCommand::Import {
+                market,
+                source,
+                snapshot,
+                snapshot_slot,
+                claimable,
+            } => import(
+                client,
+                &market,
+                source,
+                Path::new(&snapshot),
+                snapshot_slot,
+                claimable,
+            ),
+            MigrationCommand::Claim { market, snapshot } => {
+                claim(client, &market, Path::new(&snapshot))
+            }
+        }
+    }
+}
+
+fn import(
+    client: &Client,
+    address: &Pubkey,
+    source: Pubkey,
+    snapshot: &Path,
+    snapshot_slot: u64,
+    claimable: bool,
+) -> Result<()> {
+    let market = client.get_market(address)?;
+    let creator = client.payer()?.pubkey();
+    let base_token_program = client.get_token_program(&market.base_token_mint)?;
+
+    let holders = read_snapshot(snapshot)?;
+    let holders_root = get_root(&holders);
+    let claim_supply = if claimable {
+        holders.iter().map(|(_, amount)| amount).sum()
+    } else {
+        0
+    };
+
+    let accounts = token_mill::accounts::ImportMigration {
+        market: *address,
+        migration: migration_address(address),
+        base_token_mint: market.base_token_mint,
+        market_base_token_ata: get_associated_token_address_with_program_id(
+            address,
+            &market.base_token_mint,
+            &base_token_program,
+        ),
+        creator_base_token_ata: get_associated_token_address_with_program_id(
+            &creator,
+            &market.base_token_mint,
+            &base_token_program,
+        ),
+        creator,
+        base_token_program,
+        system_program: solana_sdk::system_program::ID,
+        event_authority: event_authority(),
+        program: token_mill::ID,
+    };
+
+    let instruction = Instruction {
+        program_id: token_mill::ID,
+        accounts: accounts.to_account_metas(None),
+        data: token_mill::instruction::ImportMigration {
+            source,
+            snapshot_slot,
+            holders_root,
+            claim_supply,
+        }
+        .data(),
+    };
+
+    let signature = client.send(&[instruction])?;
+
+    println!(
+        "Imported {} holders with root {}, {claim_supply} base tokens claimable: {signature}",
+        holders.len(),
+        to_hex(&holders_root)
+    );
+
+    Ok(())
+}
+
+fn claim(client: &Client, address: &Pubkey, snapshot: &Path) -> Result<()> {
+    let market = client.get_market(address)?;
+    let holder = client.payer()?.pubkey();
+    let base_token_program = client.get_token_program(&market.base_token_mint)?;
+
+    let migration_address = migration_address(address);
+    let migration = client.get_account::<Migration>(&migration_address)?;
+
+    let holders = read_snapshot(snapshot)?;
+
+    ensure!(
+        get_root(&holders) == migration.holders_root,
+        "The snapshot doesn't match the root of the migration"
+    );
+
+    let index = holders
+        .iter()
+        .position(|(wallet, _)| *wallet == holder)
+        .ok_or_else(|| anyhow!("{holder} is not part of the snapshot"))?;
+    let amount = holders[index].1;
+
+    let accounts = token_mill::accounts::ClaimMigration {
+        market: *address,
+        migration: migration_address,
+        migration_claim: Pubkey::find_program_address(
+            &[
+                MIGRATION_CLAIM_PDA_SEED.as_bytes(),
+                migration_address.as_ref(),
+                holder.as_ref(),
+            ],
+            &token_mill::ID,
+        )
+        .0,
+        base_token_mint: market.base_token_mint,
+        market_base_token_ata: get_associated_token_address_with_program_id(
+            address,
+            &market.base_token_mint,
+            &base_token_program,
+        ),
+        holder_base_token_ata: get_associated_token_address_with_program_id(
+            &holder,
+            &market.base_token_mint,
+            &base_token_program,
+        ),
+        holder,
+        base_token_program,
+        system_program: solana_sdk::system_program::ID,
+        event_authority: event_authority(),
+        program: token_mill::ID,
+    };
+
+    let instruction = Instruction {
+        program_id: token_mill::ID,
+        accounts: accounts.to_account_metas(None),
+        data: token_mill::instruction::ClaimMigration {
+            amount,
+            proof: get_proof(&holders, index),
+        }
+        .data(),
+    };
+
+    let signature = client.send(&[instruction])?;
+
+    println!("Claimed {amount} base tokens: {signature}");
+
+    Ok(())
+}
+
+fn migration_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[MIGRATION_PDA_SEED.as_bytes(), market.as_ref()],
+        &token_mill::ID,
+    )
+    .0
+}
+
+fn read_snapshot(path: &Path) -> Result<Vec<(Pubkey, u64)>> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let holders = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (wallet, amount) = line
+                .split_once(',')
+                .ok_or_else(|| anyhow!("Invalid snapshot line: {line}"))?;
+
+            Ok((
+                Pubkey::from_str(wallet.trim())?,
+                amount.trim().parse::<u64>()?,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    ensure!(!holders.is_empty(), "The snapshot is empty");
+
+    Ok(holders)
+}
+
+/// Levels of the merkle tree from the leaves to the root, an odd node being promoted as is
+fn get_levels(holders: &[(Pubkey, u64)]) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![holders
+        .iter()
+        .map(|(wallet, amount)| hash_holder(wallet, *amount))
+        .collect::<Vec<_>>()];
+
+    while levels.last().unwrap().len() > 1 {
+        let level = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|nodes| match nodes {
+                [left, right] => hash_nodes(left, right),
+                [node] => *node,
+                _ => unreachable!(),
+            })
+            .collect();
+
+        levels.push(level);
+    }
+
+    levels
+}
+
+fn get_root(holders: &[(Pubkey, u64)]) -> [u8; 32] {
+    get_levels(holders).last().unwrap()[0]
+}
+
+fn get_proof(holders: &[(Pubkey, u64)], mut index: usize) -> Vec<[u8; 32]> {
+    let mut proof = Vec::new();
+
+    for level in get_levels(holders).iter().filter(|level| level.len() > 1) {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+
+        index /= 2;
+    }
+
+    proof
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -0,0 +1,17 @@
+mod fees;
+mod market;
+mod migration;
+mod reconcile;
+mod stake;
+
+pub use fees::*;
+pub use market::*;
+pub use migration::*;
+pub use reconcile::*;
+pub use stake::*;
+
+use solana_sdk::pubkey::Pubkey;
+
+fn event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &token_mill::ID).0
+}
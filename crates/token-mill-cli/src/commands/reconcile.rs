@@ -0,0 +1,114 @@
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+use token_mill::events::{
+    TokenMillCreatorFeeClaimEvent, TokenMillFeeDistributionEvent, TokenMillSwapEvent,
+};
+
+use crate::client::Client;
+
+/// Fee movements of a market rebuilt from its events
+#[derive(Default)]
+struct FeeLedger {
+    swaps: u64,
+    creator_fees: u64,
+    staking_fees: u64,
+    creator_fees_claimed: u64,
+    creator_fees_distributed: u64,
+    staking_fees_distributed: u64,
+}
+
+impl FeeLedger {
+    fn record(&mut self, market: &Pubkey, event: &[u8]) -> Result<()> {
+        let (discriminator, mut data) = event.split_at(event.len().min(8));
+
+        if discriminator == TokenMillSwapEvent::DISCRIMINATOR {
+            let event = TokenMillSwapEvent::deserialize(&mut data)?;
+
+            if event.market == *market {
+                self.swaps += 1;
+                self.creator_fees += event.creator_fee;
+                self.staking_fees += event.staking_fee;
+            }
+        } else if discriminator == TokenMillCreatorFeeClaimEvent::DISCRIMINATOR {
+            let event = TokenMillCreatorFeeClaimEvent::deserialize(&mut data)?;
+
+            if event.market == *market {
+                self.creator_fees_claimed += event.fees_distributed;
+            }
+        } else if discriminator == TokenMillFeeDistributionEvent::DISCRIMINATOR {
+            let event = TokenMillFeeDistributionEvent::deserialize(&mut data)?;
+
+            if event.market == *market {
+                self.creator_fees_distributed += event.creator_fees_distributed;
+                self.staking_fees_distributed += event.staking_fees_distributed;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Replays the fee events of a market and compares the expected pending fees with the on-chain ones
+pub fn reconcile(client: &Client, address: &Pubkey) -> Result<()> {
+    let mut ledger = FeeLedger::default();
+
+    let signatures = client.get_signatures(address)?;
+
+    for signature in &signatures {
+        for event in client.get_events(signature)? {
+            ledger.record(address, &event)?;
+        }
+    }
+
+    let market = client.get_market(address)?;
+
+    let expected_creator_fees = i128::from(ledger.creator_fees)
+        - i128::from(ledger.creator_fees_claimed)
+        - i128::from(ledger.creator_fees_distributed);
+    let creator_fees_diff = i128::from(market.fees.pending_creator_fees) - expected_creator_fees;
+
+    // Staking fees are also pushed to the staking account on deposits, withdrawals and claims,
+    // which don't emit the amount, so only an upper bound of the pending staking fees is known
+    let max_staking_fees =
+        i128::from(ledger.staking_fees) - i128::from(ledger.staking_fees_distributed);
+    let staking_fees_accrued_by_positions =
+        max_staking_fees - i128::from(market.fees.pending_staking_fees);
+
+    println!("Transactions replayed:        {}", signatures.len());
+    println!("Swaps:                        {}", ledger.swaps);
+    println!();
+    println!("Creator fees accrued:         {}", ledger.creator_fees);
+    println!(
+        "Creator fees claimed:         {}",
+        ledger.creator_fees_claimed
+    );
+    println!(
+        "Creator fees distributed:     {}",
+        ledger.creator_fees_distributed
+    );
+    println!("Expected pending creator fees: {expected_creator_fees}");
+    println!(
+        "On-chain pending creator fees: {}",
+        market.fees.pending_creator_fees
+    );
+    println!("Difference:                   {creator_fees_diff}");
+    println!();
+    println!("Staking fees accrued:         {}", ledger.staking_fees);
+    println!(
+        "Staking fees distributed:     {}",
+        ledger.staking_fees_distributed
+    );
+    println!(
+        "On-chain pending staking fees: {}",
+        market.fees.pending_staking_fees
+    );
+    println!("Accrued by stake positions:   {staking_fees_accrued_by_positions}");
+
+    if creator_fees_diff != 0 || staking_fees_accrued_by_positions < 0 {
+        println!();
+        println!("MISMATCH: the on-chain pending fees don't match the replayed events");
+    }
+
+    Ok(())
+}
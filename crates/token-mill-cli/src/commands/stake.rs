@@ -0,0 +1,71 @@
+use anyhow::Result;
+use clap::Subcommand;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use token_mill::state::{
+    MarketStaking, StakePosition, MARKET_STAKING_PDA_SEED, STAKING_POSITION_PDA_SEED,
+};
+
+use crate::client::Client;
+
+#[derive(Subcommand)]
+pub enum StakeCommand {
+    /// Print the stake position of a user on a market
+    Show {
+        market: Pubkey,
+        /// Owner of the position, defaults to the keypair
+        #[arg(long)]
+        user: Option<Pubkey>,
+    },
+}
+
+impl StakeCommand {
+    pub fn run(self, client: &Client) -> Result<()> {
+        match self {
+            StakeCommand::Show { market, user } => show(client, &market, user),
+        }
+    }
+}
+
+fn show(client: &Client, address: &Pubkey, user: Option<Pubkey>) -> Result<()> {
+    let user = match user {
+        Some(user) => user,
+        None => client.payer()?.pubkey(),
+    };
+
+    let market = client.get_market(address)?;
+
+    let staking_address = Pubkey::find_program_address(
+        &[MARKET_STAKING_PDA_SEED.as_bytes(), address.as_ref()],
+        &token_mill::ID,
+    )
+    .0;
+    let position_address = Pubkey::find_program_address(
+        &[
+            STAKING_POSITION_PDA_SEED.as_bytes(),
+            address.as_ref(),
+            user.as_ref(),
+        ],
+        &token_mill::ID,
+    )
+    .0;
+
+    let mut staking = client.get_account::<MarketStaking>(&staking_address)?;
+    let mut position = client.get_account::<StakePosition>(&position_address)?;
+
+    // Includes the staking fees not distributed yet
+    let acc_reward_amount_per_share = staking.accrue_rewards(market.fees.pending_staking_fees)?;
+    position.accrue_rewards(acc_reward_amount_per_share)?;
+
+    println!("Stake position:  {position_address}");
+    println!("User:            {user}");
+    println!("Amount staked:   {}", position.amount_staked);
+    println!("Amount vested:   {}", position.total_amount_vested);
+    println!("Lock tier:       {}", position.lock_tier);
+    println!("Lock end:        {}", position.lock_end);
+    println!("Lock bonus:      {}", position.lock_bonus);
+    println!("Pending rewards: {}", position.pending_rewards);
+    println!("Market staked:   {}", staking.amount_staked);
+    println!("Market vested:   {}", staking.total_amount_vested);
+
+    Ok(())
+}
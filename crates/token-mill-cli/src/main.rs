@@ -0,0 +1,77 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use solana_sdk::pubkey::Pubkey;
+
+mod client;
+mod commands;
+
+use client::Client;
+use commands::{reconcile, FeesCommand, MarketCommand, MigrationCommand, StakeCommand};
+
+#[derive(Parser)]
+#[command(
+    name = "token-mill-cli",
+    about = "Inspect and interact with Token Mill markets"
+)]
+struct Cli {
+    /// RPC endpoint of the cluster
+    #[arg(
+        long,
+        short = 'u',
+        global = true,
+        default_value = "http://localhost:8899"
+    )]
+    url: String,
+
+    /// Keypair signing the transactions, also used as the default user
+    #[arg(
+        long,
+        short = 'k',
+        global = true,
+        default_value = "~/.config/solana/id.json"
+    )]
+    keypair: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Market state and quotes
+    Market {
+        #[command(subcommand)]
+        command: MarketCommand,
+    },
+    /// Creator fees
+    Fees {
+        #[command(subcommand)]
+        command: FeesCommand,
+    },
+    /// Staking positions
+    Stake {
+        #[command(subcommand)]
+        command: StakeCommand,
+    },
+    /// Imports from other launchpads and holder claims
+    Migration {
+        #[command(subcommand)]
+        command: MigrationCommand,
+    },
+    /// Replay the fee events of a market and diff them against its pending fees
+    Reconcile { market: Pubkey },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let client = Client::new(&cli.url, &cli.keypair);
+
+    match cli.command {
+        Command::Market { command } => command.run(&client),
+        Command::Fees { command } => command.run(&client),
+        Command::Stake { command } => command.run(&client),
+        Command::Migration { command } => command.run(&client),
+        Command::Reconcile { market } => reconcile(&client, &market),
+    }
+}
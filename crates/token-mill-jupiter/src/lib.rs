@@ -0,0 +1,435 @@
+//! Jupiter `Amm` implementation over Token Mill markets.
+//!
+//! Quotes are computed with the program's own swap math on a local copy of the market,
+//! so they match the on-chain execution of `swap` without interface fee nor referral.
+
+use std::{mem::size_of, sync::atomic::Ordering};
+
+use anchor_lang::{AccountDeserialize, Discriminator, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anyhow::{anyhow, bail, ensure, Result};
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, Quote, QuoteParams, Side, Swap,
+    SwapAndAccountMetas, SwapMode, SwapParams,
+};
+use solana_sdk::{clock::Clock, instruction::Instruction, pubkey::Pubkey};
+use token_mill::{
+    manager::swap_manager::{self, SwapAmountType, SwapOptions, SwapType},
+    state::{Market, TokenMillConfig, MARKET_ORACLE_PDA_SEED, RESTRICTED_WALLET_PDA_SEED},
+};
+
+#[derive(Clone)]
+pub struct TokenMillAmm {
+    key: Pubkey,
+    market: Market,
+    config: Option<TokenMillConfig>,
+    base_token_program: Option<Pubkey>,
+    quote_token_program: Option<Pubkey>,
+    clock_ref: ClockRef,
+}
+
+impl TokenMillAmm {
+    fn swap_type(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> Result<SwapType> {
+        let (base_token_mint, quote_token_mint) =
+            (self.market.base_token_mint, self.market.quote_token_mint);
+
+        if *input_mint == quote_token_mint && *output_mint == base_token_mint {
+            Ok(SwapType::Buy)
+        } else if *input_mint == base_token_mint && *output_mint == quote_token_mint {
+            Ok(SwapType::Sell)
+        } else {
+            bail!("Mints are not traded by market {}", self.key)
+        }
+    }
+
+    fn token_programs(&self) -> Result<(Pubkey, Pubkey)> {
+        self.base_token_program
+            .zip(self.quote_token_program)
+            .ok_or_else(|| anyhow!("Market {} mints are not loaded", self.key))
+    }
+
+    /// Clock the quotes are computed at, kept up to date by the router
+    fn clock(&self) -> Clock {
+        Clock {
+            slot: self.clock_ref.slot.load(Ordering::Relaxed),
+            unix_timestamp: self.clock_ref.unix_timestamp.load(Ordering::Relaxed),
+            ..Clock::default()
+        }
+    }
+}
+
+/// Markets are zero-copy accounts, read directly from their raw data
+pub fn parse_market(data: &[u8]) -> Result<Market> {
+    ensure!(
+        data.len() >= 8 + size_of::<Market>() && data[..8] == Market::DISCRIMINATOR,
+        "Account is not a market"
+    );
+
+    Ok(bytemuck::pod_read_unaligned(
+        &data[8..8 + size_of::<Market>()],
+    ))
+}
+
+impl Amm for TokenMillAmm {
+    fn from_keyed_account(keyed_account: &KeyedAccount, amm_context: &AmmContext) -> Result<Self> {
+        Ok(Self {
+            key: keyed_account.key,
+            market: parse_market(&keyed_account.account.data)?,
+            config: None,
+            base_token_program: None,
+            quote_token_program: None,
+            clock_ref: amm_context.clock_ref.clone(),
+        })
+    }
+
+    fn label(&self) -> String {
+        "Token Mill".to_string()
+    }
+
+    fn program_id(&self) -> Pubkey {
+        token_mill::ID
+    }
+
+    fn key(&self) -> Pubkey {
+        self.key
+    }
+
+    fn get_reserve_mints(&self) -> Vec<Pubkey> {
+        vec![self.market.base_token_mint, self.market.quote_token_mint]
+    }
+
+    fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        vec![
+            self.key,
+            self.market.config,
+            self.market.base_token_mint,
+            self.market.quote_token_mint,
+        ]
+    }
+
+    fn update(&mut self, account_map: &AccountMap) -> Result<()> {
+        let get_account = |address: &Pubkey| {
+            account_map
+                .get(address)
+                .ok_or_else(|| anyhow!("Missing account {address}"))
+        };
+
+        self.market = parse_market(&get_account(&self.key)?.data)?;
+        self.config = Some(TokenMillConfig::try_deserialize(
+            &mut get_account(&self.market.config)?.data.as_slice(),
+        )?);
+        self.base_token_program = Some(get_account(&self.market.base_token_mint)?.owner);
+        self.quote_token_program = Some(get_account(&self.market.quote_token_mint)?.owner);
+
+        Ok(())
+    }
+
+    fn quote(&self, quote_params: &QuoteParams) -> Result<Quote> {
+        let swap_type = self.swap_type(&quote_params.input_mint, &quote_params.output_mint)?;
+        let swap_amount_type = match quote_params.swap_mode {
+            SwapMode::ExactIn => SwapAmountType::ExactInput,
+            SwapMode::ExactOut => SwapAmountType::ExactOutput,
+        };
+
+        let clock = self.clock();
+
+        if swap_type == SwapType::Sell && self.market.are_sells_paused(clock.unix_timestamp) {
+            bail!("Sells are paused on market {}", self.key);
+        }
+
+        let mut market = self.market;
+
+        let (base_amount, quote_amount, swap_fee) = swap_manager::swap(
+            &mut market,
+            swap_type,
+            swap_amount_type,
+            quote_params.amount,
+            &clock,
+        )?;
+
+        let (in_amount, out_amount) = match swap_type {
+            SwapType::Buy => (quote_amount, base_amount),
+            SwapType::Sell => (base_amount, quote_amount),
+        };
+
+        // Routes can't rely on the partial fills at the end of the curve
+        let filled_amount = match quote_params.swap_mode {
+            SwapMode::ExactIn => in_amount,
+            SwapMode::ExactOut => out_amount,
+        };
+
+        ensure!(
+            filled_amount == quote_params.amount,
+            "Not enough liquidity on market {}",
+            self.key
+        );
+
+        Ok(Quote {
+            in_amount,
+            out_amount,
+            fee_amount: swap_fee,
+            fee_mint: self.market.quote_token_mint,
+            ..Quote::default()
+        })
+    }
+
+    fn get_swap_and_account_metas(&self, swap_params: &SwapParams) -> Result<SwapAndAccountMetas> {
+        let swap_type = self.swap_type(&swap_params.source_mint, &swap_params.destination_mint)?;
+        let (base_token_program, quote_token_program) = self.token_programs()?;
+        let config = self
+            .config
+            .as_ref()
+            .ok_or_else(|| anyhow!("Market {} config is not loaded", self.key))?;
+
+        let (user_base_token_ata, user_quote_token_ata) = match swap_type {
+            SwapType::Buy => (
+                swap_params.destination_token_account,
+                swap_params.source_token_account,
+            ),
+            SwapType::Sell => (
+                swap_params.source_token_account,
+                swap_params.destination_token_account,
+            ),
+        };
+
+        let accounts = token_mill::accounts::Swap {
+            config: self.market.config,
+            market: self.key,
+            base_token_mint: self.market.base_token_mint,
+            quote_token_mint: self.market.quote_token_mint,
+            market_base_token_ata: get_associated_token_address_with_program_id(
+                &self.key,
+                &self.market.base_token_mint,
+                &base_token_program,
+            ),
+            market_quote_token_ata: get_associated_token_address_with_program_id(
+                &self.key,
+                &self.market.quote_token_mint,
+                &quote_token_program,
+            ),
+            user_base_token_ata,
+            user_quote_token_ata,
+            protocol_quote_token_ata: get_associated_token_address_with_program_id(
+                &config.protocol_fee_recipient,
+                &self.market.quote_token_mint,
+                &quote_token_program,
+            ),
+            referral_token_account: None,
+            referral_code: None,
+            interface_fee_token_account: None,
+            trader_profile: None,
+            creator_quote_token_ata: None,
+            staking: None,
+            protocol_stats: None,
+            trader_stats: None,
+            stake_position: None,
+            quote_price_feed: (self.market.quote_price_feed != Pubkey::default())
+                .then_some(self.market.quote_price_feed),
+            market_oracle: Pubkey::find_program_address(
+                &[MARKET_ORACLE_PDA_SEED.as_bytes(), self.key.as_ref()],
+                &token_mill::ID,
+            )
+            .0,
+            restricted_wallet: Pubkey::find_program_address(
+                &[
+                    RESTRICTED_WALLET_PDA_SEED.as_bytes(),
+                    self.key.as_ref(),
+                    swap_params.token_transfer_authority.as_ref(),
+                ],
+                &token_mill::ID,
+            )
+            .0,
+            user: swap_params.token_transfer_authority,
+            base_token_program,
+            quote_token_program,
+            system_program: solana_sdk::system_program::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            event_authority: event_authority(),
+            program: token_mill::ID,
+        };
+
+        Ok(SwapAndAccountMetas {
+            swap: Swap::TokenMill {
+                side: match swap_type {
+                    SwapType::Buy => Side::Bid,
+                    SwapType::Sell => Side::Ask,
+                },
+            },
+            account_metas: accounts.to_account_metas(None),
+        })
+    }
+
+    fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn supports_exact_out(&self) -> bool {
+        true
+    }
+
+    fn get_accounts_len(&self) -> usize {
+        28
+    }
+
+    fn is_active(&self) -> bool {
+        self.market.are_prices_set()
+    }
+}
+
+fn event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &token_mill::ID).0
+}
+
+/// Direct swap instruction for the accounts of a route, used by integrators calling the program without Jupiter
+pub fn swap_instruction(
+    amm: &TokenMillAmm,
+    swap_params: &SwapParams,
+    swap_mode: SwapMode,
+    other_amount_threshold: u64,
+) -> Result<Instruction> {
+    let SwapAndAccountMetas { account_metas, .. } = amm.get_swap_and_account_metas(swap_params)?;
+    let swap_type = amm.swap_type(&swap_params.source_mint, &swap_params.destination_mint)?;
+
+    let (swap_amount_type, amount) = match swap_mode {
+        SwapMode::ExactIn => (SwapAmountType::ExactInput, swap_params.in_amount),
+        SwapMode::ExactOut => (SwapAmountType::ExactOutput, swap_params.out_amount),
+    };
+
+    Ok(Instruction {
+        program_id: token_mill::ID,
+        accounts: account_metas,
+        data: token_mill::instruction::Swap {
+            swap_type,
+            swap_amount_type,
+            amount,
+            other_amount_threshold,
+            options: SwapOptions::default(),
+        }
+        .data(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{AnchorDeserialize, Space};
+    use bytemuck::Zeroable;
+    use joelana_test_utils::utils::token_mill::curve_generator::Curve;
+    use solana_sdk::account::Account;
+
+    use super::*;
+
+    const TOTAL_SUPPLY: u64 = 1_000_000_000_000;
+
+    fn amm() -> TokenMillAmm {
+        let mut market = Market::zeroed();
+        let curve = Curve::default();
+
+        market
+            .initialize(
+                0,
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                9,
+                TOTAL_SUPPLY,
+                0,
+                0,
+            )
+            .unwrap();
+        market
+            .check_and_set_prices(&curve.bid_prices, &curve.ask_prices, 0)
+            .unwrap();
+
+        let mut data = Market::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&market));
+
+        let keyed_account = KeyedAccount {
+            key: Pubkey::new_unique(),
+            account: Account {
+                data,
+                owner: token_mill::ID,
+                ..Account::default()
+            },
+            params: None,
+        };
+
+        TokenMillAmm::from_keyed_account(
+            &keyed_account,
+            &AmmContext {
+                clock_ref: ClockRef::default(),
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn quote_matches_swap_manager() {
+        let amm = amm();
+        let mut market = amm.market;
+
+        let (base_amount, quote_amount, swap_fee) = swap_manager::swap(
+            &mut market,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            TOTAL_SUPPLY / 10,
+            &amm.clock(),
+        )
+        .unwrap();
+
+        let quote = amm
+            .quote(&QuoteParams {
+                amount: TOTAL_SUPPLY / 10,
+                input_mint: amm.market.quote_token_mint,
+                output_mint: amm.market.base_token_mint,
+                swap_mode: SwapMode::ExactOut,
+            })
+            .unwrap();
+
+        assert_eq!(quote.in_amount, quote_amount);
+        assert_eq!(quote.out_amount, base_amount);
+        assert_eq!(quote.fee_amount, swap_fee);
+    }
+
+    #[test]
+    fn quote_rejects_partial_fills() {
+        let amm = amm();
+
+        let result = amm.quote(&QuoteParams {
+            amount: TOTAL_SUPPLY + 1,
+            input_mint: amm.market.quote_token_mint,
+            output_mint: amm.market.base_token_mint,
+            swap_mode: SwapMode::ExactOut,
+        });
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn accounts_len_matches_swap_accounts() {
+        let mut amm = amm();
+        amm.config = Some(
+            TokenMillConfig::deserialize(&mut [0; TokenMillConfig::INIT_SPACE].as_slice()).unwrap(),
+        );
+        amm.base_token_program = Some(Pubkey::new_unique());
+        amm.quote_token_program = Some(Pubkey::new_unique());
+
+        let SwapAndAccountMetas { account_metas, .. } = amm
+            .get_swap_and_account_metas(&SwapParams {
+                swap_mode: SwapMode::ExactIn,
+                in_amount: 1,
+                out_amount: 0,
+                source_mint: amm.market.quote_token_mint,
+                destination_mint: amm.market.base_token_mint,
+                source_token_account: Pubkey::new_unique(),
+                destination_token_account: Pubkey::new_unique(),
+                token_transfer_authority: Pubkey::new_unique(),
+                quote_mint_to_referrer: None,
+                jupiter_program_id: &Pubkey::new_unique(),
+                missing_dynamic_accounts_as_default: false,
+            })
+            .unwrap();
+
+        assert_eq!(amm.get_accounts_len(), account_metas.len());
+    }
+}
@@ -1,5 +1,7 @@
-pub const PRICES_LENGTH: usize = 11;
+pub const PRICES_LENGTH: usize = 11; // Default curve resolution, used until the prices are set
 pub const INTERVAL_NUMBER: u64 = PRICES_LENGTH as u64 - 1;
+pub const MAX_PRICES_LENGTH: usize = 51; // Two longer price arrays wouldn't fit in a transaction
+pub const CURVE_RESOLUTIONS: [usize; 3] = [PRICES_LENGTH, 21, MAX_PRICES_LENGTH]; // Supported numbers of prices
 pub const MAX_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000; // 1e9 * 1e6
 pub const MAX_PRICE: u64 = 1_000_000_000_000_000_000; // 1e18
 pub const MILL_TOKEN_DECIMALS: u8 = 6;
@@ -7,3 +9,28 @@ pub const BASE_PRECISION: u64 = 1_000_000; // 1e6
 pub const SCALE: u128 = 10_000_000_000; // 1e10
 pub const STAKING_SCALE: u128 = 1_000_000_000_000_000_000; // 1e18
 pub const MAX_BPS: u64 = 10_000;
+pub const MAX_INTERFACE_FEE_BPS: u16 = 100; // 1%, hard cap of the interface fees allowed by the config
+pub const GARBAGE_COLLECTION_REWARD_SHARE: u64 = 1_000; // 10% of the reclaimed rent
+pub const COMPOUNDING_CRANKER_FEE_BPS: u64 = 50; // 0.5% of the compounded staking fees
+pub const MAX_SELL_PAUSE_DURATION: i64 = 259_200; // 3 days
+pub const MIN_SELL_PAUSE_COOLDOWN: i64 = 604_800; // 7 days between the end of a sell pause and the next one
+pub const BOOST_MIN_LOCK_DURATION: i64 = 15_552_000; // 180 days, top lock tier
+pub const MAX_LOCK_DURATION: i64 = 31_536_000; // 365 days
+/// Stake lockup durations and the multipliers they apply to the staking rewards, in bps. Tier 0 is unlocked
+pub const STAKING_LOCKUP_TIERS: [(i64, u64); 4] = [
+    (0, 10_000),
+    (2_592_000, 12_500),  // 30 days
+    (7_776_000, 15_000),  // 90 days
+    (15_552_000, 20_000), // 180 days
+];
+pub const MAX_QUOTE_PRICE_AGE: i64 = 60; // 1 minute, older quote prices are ignored
+pub const MAX_SIMULATION_SIZES: usize = 16; // Keeps `simulate_market` results within the return data limit
+pub const MAX_LAUNCH_FEE_BPS: u16 = 5_000; // 50% surcharge on the first swaps
+pub const MAX_LAUNCH_FEE_DURATION: u32 = 86_400; // 1 day
+pub const MAX_IMPACT_FEE_BPS: u16 = 1_000; // 10%
+pub const MAX_FEE_SHARES_UPDATE_DELAY: u32 = 2_592_000; // 30 days
+pub const MAX_MARKET_CLOSE_DELAY: u32 = 2_592_000; // 30 days
+pub const MAX_MIN_MARKET_AGE: u32 = 31_536_000; // 365 days
+pub const MAX_MIN_STAKE_DURATION: i64 = 2_592_000; // 30 days
+pub const MAX_STAKING_BADGE_MULTIPLIER_BPS: u16 = 15_000; // 1.5x, below the longest lockup tier
+pub const MAX_QUOTE_TOKEN_DECIMALS: u8 = 12; // Above, normalizing quote amounts to `SCALE` drops more than 100 units
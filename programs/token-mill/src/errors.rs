@@ -25,4 +25,96 @@ pub enum TokenMillError {
     InvalidStakePosition,
     InvalidVestingDuration,
     InvalidVestingStartTime,
+    InterfaceFeeTooHigh,
+    NothingToCollect,
+    InvalidMaxRaise,
+    MaxRaiseReached,
+    SellsPaused,
+    SellPauseLocked,
+    InvalidSellPauseDuration,
+    InvalidFirmQuote,
+    FirmQuoteExpired,
+    FirmQuoteAmountExceeded,
+    InvalidChangeLogTarget,
+    NothingLocked,
+    QuoteFeeOverridesFull,
+    QuoteFeeOverrideNotFound,
+    InvalidLaunchpad,
+    SwapDeadlineExceeded,
+    SwapNotFullyFilled,
+    InvalidLockDuration,
+    StakeLocked,
+    LockNotExpired,
+    InvalidEmissionDuration,
+    InvalidRouteAccounts,
+    WalletRestricted,
+    PriceImpactTooHigh,
+    PricesNotSet,
+    InvalidBasketWeights,
+    InvalidQuotePriceFeed,
+    QuoteDepegged,
+    InvalidMigration,
+    InvalidMerkleProof,
+    InvalidSwapDelegate,
+    SwapDelegateExpired,
+    SwapDelegateAllowanceExceeded,
+    SlotPriceMoveExceeded,
+    InvalidSimulationSizes,
+    InvalidRebateTiers,
+    RebateSchedulesFull,
+    InvalidDcaPosition,
+    DcaBuyNotDue,
+    DcaPriceLimitExceeded,
+    InvalidCurveResolution,
+    InvalidPricePreset,
+    MarketAlreadyTraded,
+    InvalidTailPrices,
+    SpreadTooWide,
+    InvalidVirtualSupply,
+    UnsupportedQuoteTokenDecimals,
+    InvalidReferralCode,
+    InvalidReferralTokenAccount,
+    InvalidLaunchFee,
+    InvalidImpactFee,
+    FeeExemptionsFull,
+    InvalidCreatorFeeSplit,
+    InvalidFeeSharesUpdateDelay,
+    FeeSharesUpdateNotDue,
+    InvalidStakerDiscountTiers,
+    InvalidClaimAccounts,
+    StakeNotLocked,
+    StakePositionNotTransferable,
+    EmergencyUnstakeDisabled,
+    StakingEpochAlreadyCheckpointed,
+    InvalidMinStakeDuration,
+    MinStakeDurationNotReached,
+    InvalidStakingBadgeMultiplier,
+    StakingBadgeCollectionAlreadySet,
+    InvalidStakingBadge,
+    VestingPlanNotRevocable,
+    VestingPlanRevocable,
+    InvalidVestingMilestones,
+    VestingPlanMilestoneGated,
+    VestingPlanCertified,
+    InvalidGraduationSupply,
+    GraduationDisabled,
+    GraduationNotReached,
+    MarketGraduated,
+    InvalidGraduationAccounts,
+    GraduationCompleted,
+    InvalidGraduationPrice,
+    MarketNotClosable,
+    MarketVaultsNotEmpty,
+    MarketPaused,
+    SwapsPaused,
+    InvalidEmergencyAuthority,
+    InvalidMetadataAccount,
+    InvalidMarketCloseDelay,
+    MarketCloseNotDue,
+    InvalidSecondaryCurvePrice,
+    SecondaryCurveInactive,
+    SellPauseCooldown,
+    InvalidMinMarketAge,
+    InvalidMarketStaking,
+    InvalidVestingPlan,
 }
@@ -1,8 +1,12 @@
 use anchor_lang::event;
 use anchor_lang::prelude::*;
 
-use crate::constant::PRICES_LENGTH;
-use crate::manager::swap_manager::SwapType;
+use crate::manager::swap_manager::{SwapAmountType, SwapType};
+use crate::state::{
+    CreatorFeeRecipient, GraduationAdapter, IncentiveAction, LaunchFeeRecipient, RebateTier,
+    StakerDiscountTier, CREATOR_FEE_RECIPIENTS_LENGTH, REBATE_TIERS_LENGTH,
+    STAKER_DISCOUNT_TIERS_LENGTH,
+};
 use crate::QuoteTokenBadgeStatus;
 
 #[event]
@@ -31,6 +35,7 @@ pub struct TokenMillMarketCreationEvent {
     pub config: Pubkey,
     pub market: Pubkey,
     pub creator: Pubkey,
+    pub launchpad: Option<Pubkey>,
     pub base_token_mint: Pubkey,
     pub quote_token_mint: Pubkey,
     pub total_supply: u64,
@@ -40,11 +45,37 @@ pub struct TokenMillMarketCreationEvent {
     pub staking_fee_share: u16,
 }
 
+#[event]
+pub struct TokenMillSupplyDepositEvent {
+    pub market: Pubkey,
+    pub amount: u64,
+    pub pending_supply: u64,
+}
+
+#[event]
+pub struct TokenMillMigrationImportEvent {
+    pub migration: Pubkey,
+    pub market: Pubkey,
+    pub source: Pubkey,
+    pub snapshot_slot: u64,
+    pub holders_root: [u8; 32],
+    pub unsold_supply: u64,
+    pub claim_supply: u64,
+}
+
+#[event]
+pub struct TokenMillMigrationClaimEvent {
+    pub migration: Pubkey,
+    pub holder: Pubkey,
+    pub amount: u64,
+}
+
 #[event]
 pub struct TokenMillMarketPriceSetEvent {
     pub market: Pubkey,
-    pub bid_prices: [u64; PRICES_LENGTH],
-    pub ask_prices: [u64; PRICES_LENGTH],
+    pub bid_prices: Vec<u64>,
+    pub ask_prices: Vec<u64>,
+    pub prices_hash: [u8; 32],
 }
 
 #[event]
@@ -59,6 +90,40 @@ pub struct TokenMillSwapEvent {
     pub staking_fee: u64,
     pub protocol_fee: u64,
     pub referral_fee: u64,
+    pub interface_fee_recipient: Option<Pubkey>,
+    pub interface_fee: u64,
+    pub circulating_supply_before: u64,
+    pub circulating_supply_after: u64,
+    /// Ask price of the curve at `circulating_supply_after`
+    pub ask_price: u64,
+    /// Bid price of the curve at `circulating_supply_after`
+    pub bid_price: u64,
+}
+
+/// Emitted when the curve runs out of liquidity before the specified amount of a swap is filled
+#[event]
+pub struct TokenMillSwapPartialFillEvent {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub swap_type: SwapType,
+    pub swap_amount_type: SwapAmountType,
+    pub filled_amount: u64,
+    pub unfilled_amount: u64,
+}
+
+#[event]
+pub struct TokenMillBuybackAndBurnEvent {
+    pub market: Pubkey,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+}
+
+#[event]
+pub struct TokenMillSellAndBurnEvent {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub base_amount: u64,
+    pub quote_amount: u64,
 }
 
 #[event]
@@ -68,6 +133,26 @@ pub struct TokenMillCreatorFeeClaimEvent {
     pub fees_distributed: u64,
 }
 
+#[event]
+pub struct TokenMillBurnFeeShareUpdateEvent {
+    pub market: Pubkey,
+    pub new_creator_fee_share: u16,
+    pub new_burn_fee_share: u16,
+}
+
+#[event]
+pub struct TokenMillCreatorFeeSplitClaimEvent {
+    pub market: Pubkey,
+    pub recipients: Vec<Pubkey>,
+    pub amounts: Vec<u64>,
+}
+
+#[event]
+pub struct TokenMillCreatorFeeSplitUpdateEvent {
+    pub market: Pubkey,
+    pub recipients: [CreatorFeeRecipient; CREATOR_FEE_RECIPIENTS_LENGTH],
+}
+
 #[event]
 pub struct TokenMillDefaultFeeSharesUpdateEvent {
     pub config: Pubkey,
@@ -75,6 +160,30 @@ pub struct TokenMillDefaultFeeSharesUpdateEvent {
     pub new_referral_fee_share: u16,
 }
 
+#[event]
+pub struct TokenMillMaxInterfaceFeeUpdateEvent {
+    pub config: Pubkey,
+    pub new_max_interface_fee_bps: u16,
+}
+
+#[event]
+pub struct TokenMillMaxSpreadUpdateEvent {
+    pub config: Pubkey,
+    pub new_max_spread_bps: u16,
+}
+
+#[event]
+pub struct TokenMillEmergencyUnstakePenaltyUpdateEvent {
+    pub config: Pubkey,
+    pub new_emergency_unstake_penalty_bps: u16,
+}
+
+#[event]
+pub struct TokenMillProtocolFeeRecipientProposalEvent {
+    pub config: Pubkey,
+    pub pending_protocol_fee_recipient: Option<Pubkey>,
+}
+
 #[event]
 pub struct TokenMillProtocolFeeRecipientUpdateEvent {
     pub config: Pubkey,
@@ -87,6 +196,12 @@ pub struct TokenMillCreatorUpdateEvent {
     pub new_creator: Pubkey,
 }
 
+#[event]
+pub struct TokenMillCreatorProposalEvent {
+    pub market: Pubkey,
+    pub pending_creator: Option<Pubkey>,
+}
+
 #[event]
 pub struct TokenMillMarketFeeSharesUpdateEvent {
     pub market: Pubkey,
@@ -94,6 +209,14 @@ pub struct TokenMillMarketFeeSharesUpdateEvent {
     pub new_staking_fee_share: u16,
 }
 
+#[event]
+pub struct TokenMillMarketFeeSharesProposalEvent {
+    pub market: Pubkey,
+    pub new_creator_fee_share: u16,
+    pub new_staking_fee_share: u16,
+    pub activation_time: i64,
+}
+
 #[event]
 pub struct TokenMillStakingDepositEvent {
     pub market: Pubkey,
@@ -108,6 +231,14 @@ pub struct TokenMillStakingWithdrawalEvent {
     pub amount: u64,
 }
 
+#[event]
+pub struct TokenMillEmergencyUnstakeEvent {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub penalty_amount: u64,
+}
+
 #[event]
 pub struct TokenMillReferralFeeClaimEvent {
     pub referrer: Pubkey,
@@ -115,6 +246,111 @@ pub struct TokenMillReferralFeeClaimEvent {
     pub fees_distributed: u64,
 }
 
+#[event]
+pub struct TokenMillStakingLockupEvent {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub lock_tier: u8,
+    pub lock_end: i64,
+    pub lock_bonus: u64,
+}
+
+#[event]
+pub struct TokenMillRewardVaultFundingEvent {
+    pub market: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub reward_remaining: u64,
+    pub emission_end: i64,
+}
+
+#[event]
+pub struct TokenMillVaultRewardsClaimEvent {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub reward_token_mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TokenMillStakePositionTransferEvent {
+    pub market: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub lock_tier: u8,
+    pub lock_end: i64,
+}
+
+#[event]
+pub struct TokenMillCompoundingVaultDepositEvent {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct TokenMillCompoundingVaultWithdrawalEvent {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct TokenMillStakingRewardsCompoundEvent {
+    pub market: Pubkey,
+    pub cranker: Pubkey,
+    pub quote_amount: u64,
+    pub base_amount: u64,
+    pub cranker_fee: u64,
+}
+
+#[event]
+pub struct TokenMillMinStakeDurationUpdateEvent {
+    pub market: Pubkey,
+    pub min_stake_duration: i64,
+}
+
+#[event]
+pub struct TokenMillStakingBadgeCollectionUpdateEvent {
+    pub market: Pubkey,
+    pub badge_collection: Pubkey,
+    pub badge_multiplier_bps: u16,
+}
+
+#[event]
+pub struct TokenMillStakingBadgeLinkEvent {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub badge_mint: Pubkey,
+    pub badge_bonus: u64,
+}
+
+#[event]
+pub struct TokenMillStakingBadgeUnlinkEvent {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub badge_mint: Pubkey,
+}
+
+#[event]
+pub struct TokenMillClaimDelegateUpdateEvent {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub claim_delegate: Pubkey,
+}
+
+#[event]
+pub struct TokenMillStakingCheckpointEvent {
+    pub market: Pubkey,
+    pub epoch: u64,
+    pub amount_staked: u64,
+    pub total_shares: u64,
+    pub acc_reward_amount_per_share: u128,
+}
+
 #[event]
 pub struct TokenMillStakingRewardsClaimEvent {
     pub market: Pubkey,
@@ -122,6 +358,37 @@ pub struct TokenMillStakingRewardsClaimEvent {
     pub amount_distributed: u64,
 }
 
+#[event]
+pub struct TokenMillBoostPoolFundingEvent {
+    pub market: Pubkey,
+    pub amount: u64,
+    pub reward_remaining: u64,
+    pub emission_end: i64,
+}
+
+#[event]
+pub struct TokenMillStakeLockEvent {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub lock_end: i64,
+    pub weight: u64,
+}
+
+#[event]
+pub struct TokenMillStakeUnlockEvent {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TokenMillBoostRewardsClaimEvent {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount_distributed: u64,
+}
+
 #[event]
 pub struct TokenMillVestingPlanCreationEvent {
     pub market: Pubkey,
@@ -133,8 +400,417 @@ pub struct TokenMillVestingPlanCreationEvent {
     pub cliff_duration: i64,
 }
 
+#[event]
+pub struct TokenMillLockCertificateEvent {
+    pub lock_certificate: Pubkey,
+    pub target: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub unlock_start: i64,
+    pub unlock_end: i64,
+}
+
 #[event]
 pub struct TokenMillVestingPlanReleaseEvent {
     pub vesting_plan: Pubkey,
     pub amount_released: u64,
 }
+
+#[event]
+pub struct TokenMillVestingPlanRevocationEvent {
+    pub vesting_plan: Pubkey,
+    pub funder: Pubkey,
+    pub amount_revoked: u64,
+}
+
+#[event]
+pub struct TokenMillVestingPlanTransferEvent {
+    pub vesting_plan: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount_released: u64,
+    pub amount_transferred: u64,
+}
+
+#[event]
+pub struct TokenMillGarbageCollectionEvent {
+    pub account: Pubkey,
+    pub caller: Pubkey,
+    pub rent_recipient: Pubkey,
+    pub rent_reclaimed: u64,
+    pub caller_reward: u64,
+}
+
+#[event]
+pub struct TokenMillGraduationSupplyUpdateEvent {
+    pub market: Pubkey,
+    pub new_graduation_supply: u64,
+}
+
+#[event]
+pub struct TokenMillSecondaryCurveUpdateEvent {
+    pub market: Pubkey,
+    pub new_price: u64,
+}
+
+/// Proceeds of the buy are added to the pending staking fees of the market
+#[event]
+pub struct TokenMillSecondaryCurveBuyEvent {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+}
+
+#[event]
+pub struct TokenMillGraduationConfigUpdateEvent {
+    pub config: Pubkey,
+    pub new_graduation_adapter: GraduationAdapter,
+    pub new_graduation_program: Pubkey,
+    pub new_graduation_pool_config: Pubkey,
+}
+
+#[event]
+pub struct TokenMillMarketGraduationEvent {
+    pub market: Pubkey,
+    pub graduation: Pubkey,
+    pub adapter: GraduationAdapter,
+    /// Base and quote amounts seeding the pool
+    pub base_amount: u64,
+    pub quote_amount: u64,
+    /// Unsold base amount burned
+    pub burned_amount: u64,
+    /// Unsold base amount moved to the secondary curve instead
+    pub secondary_curve_supply: u64,
+}
+
+#[event]
+pub struct TokenMillGraduationCompletionEvent {
+    pub market: Pubkey,
+    pub graduation: Pubkey,
+    pub pool_program: Pubkey,
+    pub pool: Pubkey,
+}
+
+/// Warns the holders of a market that it can be closed from `close_time`
+#[event]
+pub struct TokenMillMarketCloseProposalEvent {
+    pub market: Pubkey,
+    pub close_time: i64,
+}
+
+#[event]
+pub struct TokenMillMarketCloseEvent {
+    pub market: Pubkey,
+    pub creator: Pubkey,
+    pub quote_amount: u64,
+    pub burned_amount: u64,
+}
+
+#[event]
+pub struct TokenMillMaxRaiseUpdateEvent {
+    pub market: Pubkey,
+    pub new_max_raise: u64,
+}
+
+#[event]
+pub struct TokenMillVirtualSupplyUpdateEvent {
+    pub market: Pubkey,
+    pub new_virtual_supply: u64,
+}
+
+#[event]
+pub struct TokenMillSellPauseUpdateEvent {
+    pub market: Pubkey,
+    pub sells_paused_until: i64,
+    pub locked: bool,
+}
+
+#[event]
+pub struct TokenMillMarketPauseUpdateEvent {
+    pub market: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct TokenMillEmergencyAuthorityUpdateEvent {
+    pub config: Pubkey,
+    pub new_emergency_authority: Pubkey,
+}
+
+#[event]
+pub struct TokenMillSwapsPauseUpdateEvent {
+    pub config: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct TokenMillQuotePriceFeedUpdateEvent {
+    pub market: Pubkey,
+    pub quote_price_feed: Pubkey,
+    pub max_quote_depeg_bps: u16,
+}
+
+#[event]
+pub struct TokenMillImpactFeeUpdateEvent {
+    pub market: Pubkey,
+    pub max_impact_fee_bps: u16,
+    pub impact_fee_threshold_bps: u16,
+    pub impact_fee_rate_bps: u16,
+}
+
+#[event]
+pub struct TokenMillLaunchFeeUpdateEvent {
+    pub market: Pubkey,
+    pub launch_fee_bps: u16,
+    pub duration: u32,
+    pub recipient: LaunchFeeRecipient,
+}
+
+#[event]
+pub struct TokenMillMarketReferralFeeShareUpdateEvent {
+    pub market: Pubkey,
+    pub referral_fee_share: Option<u16>,
+}
+
+#[event]
+pub struct TokenMillIncentiveWeightsUpdateEvent {
+    pub market: Pubkey,
+    pub buy_weight: u16,
+    pub sell_weight: u16,
+    pub stake_weight: u16,
+}
+
+#[event]
+pub struct TokenMillSlotPriceMoveLimitUpdateEvent {
+    pub market: Pubkey,
+    pub max_slot_price_move_bps: u16,
+}
+
+#[event]
+pub struct TokenMillFeeDistributionIntervalUpdateEvent {
+    pub config: Pubkey,
+    pub new_fee_distribution_interval: u16,
+}
+
+#[event]
+pub struct TokenMillFeeSharesUpdateDelayUpdateEvent {
+    pub config: Pubkey,
+    pub new_fee_shares_update_delay: u32,
+}
+
+#[event]
+pub struct TokenMillMarketCloseDelayUpdateEvent {
+    pub config: Pubkey,
+    pub new_market_close_delay: u32,
+}
+
+#[event]
+pub struct TokenMillMinMarketAgeUpdateEvent {
+    pub config: Pubkey,
+    pub new_min_market_age: u32,
+}
+
+#[event]
+pub struct TokenMillFeeDistributionEvent {
+    pub market: Pubkey,
+    pub creator_fees_distributed: u64,
+    pub staking_fees_distributed: u64,
+}
+
+#[event]
+pub struct TokenMillWalletRestrictionEvent {
+    pub market: Pubkey,
+    pub wallet: Pubkey,
+    pub restricted: bool,
+    pub base_balance_snapshot: u64,
+}
+
+#[event]
+pub struct TokenMillMarketMakerBadgeEvent {
+    pub config: Pubkey,
+    pub market_maker: Pubkey,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct TokenMillFirmQuoteFeeUpdateEvent {
+    pub config: Pubkey,
+    pub new_firm_quote_fee_bps: u16,
+}
+
+#[event]
+pub struct TokenMillQuoteFeeOverrideUpdateEvent {
+    pub config: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub protocol_fee_share: u16,
+    pub referral_fee_share: u16,
+    pub creator_fee_share: u16,
+    pub staking_fee_share: u16,
+}
+
+#[event]
+pub struct TokenMillFeeExemptionUpdateEvent {
+    pub config: Pubkey,
+    pub wallet: Pubkey,
+    pub fee_discount_bps: u16,
+}
+
+#[event]
+pub struct TokenMillRebateScheduleUpdateEvent {
+    pub config: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub tiers: [RebateTier; REBATE_TIERS_LENGTH],
+}
+
+#[event]
+pub struct TokenMillStakerDiscountsUpdateEvent {
+    pub config: Pubkey,
+    pub protocol_token_market: Pubkey,
+    pub tiers: [StakerDiscountTier; STAKER_DISCOUNT_TIERS_LENGTH],
+}
+
+#[event]
+pub struct TokenMillQuoteFeeOverrideRemovalEvent {
+    pub config: Pubkey,
+    pub quote_token_mint: Pubkey,
+}
+
+#[event]
+pub struct TokenMillFirmQuotePostEvent {
+    pub firm_quote: Pubkey,
+    pub market: Pubkey,
+    pub market_maker: Pubkey,
+    pub swap_type: SwapType,
+    pub base_amount: u64,
+    pub price: u64,
+    pub expiry: i64,
+}
+
+#[event]
+pub struct TokenMillFirmQuoteCancelEvent {
+    pub firm_quote: Pubkey,
+    pub amount_returned: u64,
+}
+
+#[event]
+pub struct TokenMillFirmQuoteFillEvent {
+    pub firm_quote: Pubkey,
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub swap_type: SwapType,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+    pub protocol_fee: u64,
+}
+
+#[event]
+pub struct TokenMillChangeLogCreationEvent {
+    pub change_log: Pubkey,
+    pub target: Pubkey,
+}
+
+/// Standardized activity record for creator-run points campaigns, only emitted for actions with a weight set
+#[event]
+pub struct TokenMillIncentiveEvent {
+    pub wallet: Pubkey,
+    pub market: Pubkey,
+    pub action: IncentiveAction,
+    /// Quote amount of swaps, base amount of stakes
+    pub amount: u64,
+    pub weight: u64,
+}
+
+#[event]
+pub struct TokenMillSwapDelegateCreationEvent {
+    pub swap_delegate: Pubkey,
+    pub market: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub quote_allowance: u64,
+    pub base_allowance: u64,
+    pub expiry: i64,
+}
+
+#[event]
+pub struct TokenMillSwapDelegateRevocationEvent {
+    pub swap_delegate: Pubkey,
+}
+
+/// Emitted when part of the protocol fee of a swap is rebated to the trader, based on their 30-day volume
+#[event]
+pub struct TokenMillProtocolFeeRebateEvent {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    /// 30-day volume of the trader before the swap
+    pub volume: u64,
+    pub rebate_bps: u16,
+    pub rebate: u64,
+}
+
+#[event]
+pub struct TokenMillStakerDiscountEvent {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    /// Protocol tokens staked by the user
+    pub amount_staked: u64,
+    pub discount_bps: u16,
+    pub discount: u64,
+}
+
+#[event]
+pub struct TokenMillDcaPositionCreationEvent {
+    pub dca_position: Pubkey,
+    pub market: Pubkey,
+    pub owner: Pubkey,
+    pub amount_per_buy: u64,
+    pub crank_bounty: u64,
+    pub interval: i64,
+    pub max_price: u64,
+    pub buys: u32,
+}
+
+/// Emitted alongside the `TokenMillSwapEvent` of each buy executed by a DCA crank
+#[event]
+pub struct TokenMillDcaBuyEvent {
+    pub dca_position: Pubkey,
+    pub cranker: Pubkey,
+    pub crank_bounty: u64,
+    pub buys_remaining: u32,
+}
+
+#[event]
+pub struct TokenMillDcaPositionCloseEvent {
+    pub dca_position: Pubkey,
+    pub amount_returned: u64,
+}
+
+#[event]
+pub struct TokenMillConfigUpgradeEvent {
+    pub config: Pubkey,
+}
+
+#[event]
+pub struct TokenMillMarketUpgradeEvent {
+    pub market: Pubkey,
+    pub quote_raised: u64,
+}
+
+#[event]
+pub struct TokenMillMarketStakingUpgradeEvent {
+    pub market: Pubkey,
+    pub staking: Pubkey,
+}
+
+#[event]
+pub struct TokenMillStakePositionUpgradeEvent {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub stake_position: Pubkey,
+}
+
+#[event]
+pub struct TokenMillVestingPlanUpgradeEvent {
+    pub vesting_plan: Pubkey,
+    pub stake_position: Pubkey,
+}
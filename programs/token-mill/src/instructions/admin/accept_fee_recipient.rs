@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillProtocolFeeRecipientUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, ChangeLogParameter, TokenMillConfig},
+    CHANGE_LOG_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptFeeRecipient<'info> {
+    #[account(mut, constraint = config.pending_protocol_fee_recipient == Some(pending_protocol_fee_recipient.key()) @ TokenMillError::InvalidAuthority)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    /// CHECK: Change log of the config, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [CHANGE_LOG_PDA_SEED.as_bytes(), config.key().as_ref()],
+        bump
+    )]
+    pub change_log: UncheckedAccount<'info>,
+
+    pub pending_protocol_fee_recipient: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<AcceptFeeRecipient>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let new_protocol_fee_recipient = ctx.accounts.pending_protocol_fee_recipient.key();
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::ProtocolFeeRecipient,
+        encode_pubkey(Some(config.protocol_fee_recipient)),
+        encode_pubkey(Some(new_protocol_fee_recipient)),
+    )?;
+
+    config.protocol_fee_recipient = new_protocol_fee_recipient;
+    config.pending_protocol_fee_recipient = None;
+
+    emit_cpi!(TokenMillProtocolFeeRecipientUpdateEvent {
+        config: config.key(),
+        new_protocol_fee_recipient,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TokenMillConfig;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, AcceptFeeRecipientAction, ProposeFeeRecipientAction, TokenMillEnv,
+        },
+        make_address, TokenMillError,
+    };
+
+    fn setup_env() -> (TokenMillEnv, AcceptFeeRecipientAction) {
+        let mut testing_env = TokenMillEnv::new();
+
+        let new_protocol_fee_recipient = make_address("carol");
+
+        testing_env
+            .svm
+            .execute_actions(&[&ProposeFeeRecipientAction::new(Some(
+                new_protocol_fee_recipient,
+            ))])
+            .unwrap();
+
+        let action = AcceptFeeRecipientAction::new(new_protocol_fee_recipient);
+
+        testing_env.svm.change_payer("carol");
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn accept_fee_recipient() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config_account = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(config_account.protocol_fee_recipient, make_address("carol"));
+        assert_eq!(config_account.pending_protocol_fee_recipient, None);
+    }
+
+    #[test]
+    fn accept_fee_recipient_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+
+    #[test]
+    fn accept_cancelled_fee_recipient() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&ProposeFeeRecipientAction::new(None)])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
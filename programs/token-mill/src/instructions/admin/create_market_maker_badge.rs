@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillMarketMakerBadgeEvent,
+    state::{MarketMakerBadge, TokenMillConfig},
+    MARKET_MAKER_BADGE_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateMarketMakerBadge<'info> {
+    #[account(has_one = authority @ TokenMillError::InvalidAuthority)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        init,
+        seeds = [
+            MARKET_MAKER_BADGE_PDA_SEED.as_bytes(),
+            config.key().as_ref(),
+            market_maker.key().as_ref(),
+        ],
+        bump,
+        payer = authority,
+        space = 8 + MarketMakerBadge::INIT_SPACE
+    )]
+    pub market_maker_badge: Account<'info, MarketMakerBadge>,
+
+    /// CHECK: Market maker being approved
+    pub market_maker: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateMarketMakerBadge>) -> Result<()> {
+    let market_maker_badge = &mut ctx.accounts.market_maker_badge;
+
+    market_maker_badge.initialize(
+        ctx.bumps.market_maker_badge,
+        ctx.accounts.config.key(),
+        ctx.accounts.market_maker.key(),
+    )?;
+
+    emit_cpi!(TokenMillMarketMakerBadgeEvent {
+        config: ctx.accounts.config.key(),
+        market_maker: ctx.accounts.market_maker.key(),
+        enabled: true,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MarketMakerBadge;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, CreateMarketMakerBadgeAction, TokenMillEnv},
+        make_address, TokenMillError,
+    };
+
+    #[test]
+    fn create_market_maker_badge() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = CreateMarketMakerBadgeAction::new(make_address("carol"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market_maker_badge = testing_env
+            .svm
+            .get_parsed_account::<MarketMakerBadge>(&action.market_maker_badge);
+
+        assert_eq!(market_maker_badge.config, action.config);
+        assert_eq!(market_maker_badge.market_maker, make_address("carol"));
+    }
+
+    #[test]
+    fn create_market_maker_badge_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let mut action = CreateMarketMakerBadgeAction::new(make_address("carol"));
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
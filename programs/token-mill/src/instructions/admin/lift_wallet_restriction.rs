@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillWalletRestrictionEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, ChangeLogParameter, Market, RestrictedWallet, TokenMillConfig},
+    CHANGE_LOG_PDA_SEED, RESTRICTED_WALLET_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct LiftWalletRestriction<'info> {
+    #[account(has_one = authority @ TokenMillError::InvalidAuthority)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(has_one = config @ TokenMillError::InvalidConfigAccount)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        mut,
+        seeds = [
+            RESTRICTED_WALLET_PDA_SEED.as_bytes(),
+            market.key().as_ref(),
+            restricted_wallet.wallet.as_ref(),
+        ],
+        bump = restricted_wallet.bump,
+        close = authority
+    )]
+    pub restricted_wallet: Account<'info, RestrictedWallet>,
+
+    /// CHECK: Change log of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [CHANGE_LOG_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub change_log: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<LiftWalletRestriction>) -> Result<()> {
+    let wallet = ctx.accounts.restricted_wallet.wallet;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::WalletRestriction,
+        encode_pubkey(Some(wallet)),
+        encode_pubkey(None),
+    )?;
+
+    emit_cpi!(TokenMillWalletRestrictionEvent {
+        market: ctx.accounts.market.key(),
+        wallet,
+        restricted: false,
+        base_balance_snapshot: ctx.accounts.restricted_wallet.base_balance_snapshot,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, LiftWalletRestrictionAction, RestrictWalletAction, SwapAction,
+            TokenMillEnv,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    fn setup_env() -> (TokenMillEnv, LiftWalletRestrictionAction) {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&RestrictWalletAction::new(
+                &testing_env,
+                make_address("bob"),
+            )])
+            .unwrap();
+
+        let action = LiftWalletRestrictionAction::new(&testing_env, make_address("bob"));
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn lift_wallet_restriction() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.restricted_wallet)
+            .is_none());
+
+        testing_env.svm.change_payer("bob");
+
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+    }
+
+    #[test]
+    fn lift_wallet_restriction_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
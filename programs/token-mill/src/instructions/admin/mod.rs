@@ -1,13 +1,53 @@
 pub mod accept_config_ownership;
+pub mod accept_fee_recipient;
 pub mod create_config;
+pub mod create_market_maker_badge;
 pub mod create_quote_asset_badge;
+pub mod lift_wallet_restriction;
+pub mod override_sell_pause;
+pub mod pause_swaps;
+pub mod propose_fee_recipient;
+pub mod remove_quote_fee_override;
+pub mod restrict_wallet;
+pub mod revoke_market_maker_badge;
+pub mod set_emergency_authority;
+pub mod set_fee_exemption;
+pub mod set_graduation_config;
+pub mod set_market_paused;
+#[cfg(feature = "referrals")]
+pub mod set_market_referral_fee_share;
+pub mod set_quote_fee_override;
+pub mod set_quote_price_feed;
+pub mod set_rebate_schedule;
+#[cfg(feature = "staking")]
+pub mod set_staker_discounts;
 pub mod transfer_config_ownership;
+pub mod unpause_swaps;
 pub mod update_default_fee_shares;
-pub mod update_protocol_fee_recipient;
+#[cfg(feature = "staking")]
+pub mod update_emergency_unstake_penalty;
+pub mod update_fee_distribution_interval;
+pub mod update_fee_shares_update_delay;
+pub mod update_firm_quote_fee;
+pub mod update_market_close_delay;
+pub mod update_max_interface_fee;
+pub mod update_max_spread;
+pub mod update_min_market_age;
 pub mod update_quote_asset_badge;
+pub mod upgrade_config;
+pub mod upgrade_market;
 
 pub use accept_config_ownership::*;
+pub use accept_fee_recipient::*;
 pub use create_config::*;
+pub use create_market_maker_badge::*;
 pub use create_quote_asset_badge::*;
+pub use lift_wallet_restriction::*;
+pub use override_sell_pause::*;
+pub use pause_swaps::*;
+pub use restrict_wallet::*;
+pub use revoke_market_maker_badge::*;
 pub use transfer_config_ownership::*;
 pub use update_quote_asset_badge::*;
+pub use upgrade_config::*;
+pub use upgrade_market::*;
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillSellPauseUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_i64, ChangeLogParameter, Market, TokenMillConfig},
+    CHANGE_LOG_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MarketAdminUpdate<'info> {
+    #[account(has_one = authority @ TokenMillError::InvalidAuthority)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(mut, has_one = config @ TokenMillError::InvalidConfigAccount)]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: Change log of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [CHANGE_LOG_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub change_log: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+/// The config authority acts as guardian: it lifts an abusive sell pause and prevents the creator from pausing again
+pub fn handler(ctx: Context<MarketAdminUpdate>) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::SellPauseOverride,
+        encode_i64(market.sells_paused_until),
+        encode_i64(0),
+    )?;
+
+    market.sells_paused_until = 0;
+    market.sell_pause_locked = 1;
+
+    emit_cpi!(TokenMillSellPauseUpdateEvent {
+        market: ctx.accounts.market.key(),
+        sells_paused_until: 0,
+        locked: true,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, OverrideSellPauseAction, PauseSellsAction, TokenMillEnv,
+        },
+        TokenMillError,
+    };
+
+    fn setup_env() -> (TokenMillEnv, PauseSellsAction, OverrideSellPauseAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        let pause_action = PauseSellsAction::new(3_600);
+
+        testing_env.svm.execute_actions(&[&pause_action]).unwrap();
+
+        testing_env.svm.change_payer("admin");
+
+        let action = OverrideSellPauseAction::new();
+
+        (testing_env, pause_action, action)
+    }
+
+    #[test]
+    fn override_sell_pause() {
+        let (mut testing_env, pause_action, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.sells_paused_until, 0);
+        assert_eq!(market.sell_pause_locked, 1);
+
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&pause_action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::SellPauseLocked);
+    }
+
+    #[test]
+    fn override_sell_pause_with_invalid_signer() {
+        let (mut testing_env, _, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillSwapsPauseUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter, TokenMillConfig},
+    CHANGE_LOG_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PauseSwaps<'info> {
+    #[account(
+        mut,
+        constraint = emergency_authority.key() == config.emergency_authority
+            || emergency_authority.key() == config.authority @ TokenMillError::InvalidAuthority
+    )]
+    pub config: Account<'info, TokenMillConfig>,
+
+    /// CHECK: Change log of the config, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [CHANGE_LOG_PDA_SEED.as_bytes(), config.key().as_ref()],
+        bump
+    )]
+    pub change_log: UncheckedAccount<'info>,
+
+    pub emergency_authority: Signer<'info>,
+}
+
+/// Circuit breaker halting the swaps of every market, e.g. on a math or mint exploit.
+/// Signed by the emergency authority or the authority, while only the authority can lift it with `unpause_swaps`
+pub fn handler(ctx: Context<PauseSwaps>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::SwapsPause,
+        encode_u64(config.swaps_paused.into()),
+        encode_u64(1),
+    )?;
+
+    config.swaps_paused = true;
+
+    emit_cpi!(TokenMillSwapsPauseUpdateEvent {
+        config: ctx.accounts.config.key(),
+        paused: true,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TokenMillConfig;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, PauseSwapsAction, SetEmergencyAuthorityAction, SwapAction,
+            TokenMillEnv, UnpauseSwapsAction,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    fn setup_env() -> (TokenMillEnv, PauseSwapsAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetEmergencyAuthorityAction::new(make_address("carol"))])
+            .unwrap();
+
+        let mut action = PauseSwapsAction::new();
+
+        action.signer = testing_env.svm.change_payer("carol");
+
+        (testing_env, action)
+    }
+
+    fn buy_action(testing_env: &TokenMillEnv) -> SwapAction {
+        SwapAction::new(
+            testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000,
+            u64::MAX,
+            None,
+        )
+    }
+
+    #[test]
+    fn pause_swaps() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert!(config.swaps_paused);
+
+        testing_env.svm.change_payer("bob");
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&buy_action(&testing_env)]);
+
+        assert_eq!(tm_parse_error(result).unwrap(), TokenMillError::SwapsPaused);
+
+        // The emergency authority can't lift the pause
+        let mut unpause_action = UnpauseSwapsAction::new();
+
+        unpause_action.signer = testing_env.svm.change_payer("carol");
+
+        let result = testing_env.svm.execute_actions(&[&unpause_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+
+    #[test]
+    fn pause_swaps_by_authority() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("admin");
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert!(config.swaps_paused);
+    }
+
+    #[test]
+    fn pause_swaps_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
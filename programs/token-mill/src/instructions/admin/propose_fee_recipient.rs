@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+
+use super::ConfigUpdate;
+use crate::{
+    events::TokenMillProtocolFeeRecipientProposalEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, ChangeLogParameter},
+};
+
+/// Proposes a new protocol fee recipient, which only takes over once it accepts with `accept_fee_recipient`.
+/// Proposing `None` cancels the pending proposal
+pub fn handler(
+    ctx: Context<ConfigUpdate>,
+    pending_protocol_fee_recipient: Option<Pubkey>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::PendingProtocolFeeRecipient,
+        encode_pubkey(config.pending_protocol_fee_recipient),
+        encode_pubkey(pending_protocol_fee_recipient),
+    )?;
+
+    config.pending_protocol_fee_recipient = pending_protocol_fee_recipient;
+
+    emit_cpi!(TokenMillProtocolFeeRecipientProposalEvent {
+        config: ctx.accounts.config.key(),
+        pending_protocol_fee_recipient,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TokenMillConfig;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, ProposeFeeRecipientAction, TokenMillEnv},
+        make_address, TokenMillError,
+    };
+
+    fn setup_env() -> (TokenMillEnv, ProposeFeeRecipientAction) {
+        let testing_env = TokenMillEnv::new();
+
+        let action = ProposeFeeRecipientAction::new(Some(make_address("carol")));
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn propose_fee_recipient() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config_account = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(
+            config_account.pending_protocol_fee_recipient,
+            Some(make_address("carol"))
+        );
+        assert_eq!(config_account.protocol_fee_recipient, make_address("dave"));
+    }
+
+    #[test]
+    fn cancel_fee_recipient_proposal() {
+        let (mut testing_env, mut action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        action.pending_protocol_fee_recipient = None;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config_account = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(config_account.pending_protocol_fee_recipient, None);
+        assert_eq!(config_account.protocol_fee_recipient, make_address("dave"));
+    }
+
+    #[test]
+    fn propose_fee_recipient_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillQuoteFeeOverrideRemovalEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, encode_quote_fee_override, ChangeLogParameter},
+};
+
+use super::ConfigUpdate;
+
+/// Markets quoted in `quote_token_mint` fall back to the default fee shares
+pub fn handler(ctx: Context<ConfigUpdate>, quote_token_mint: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    let removed_override = config.remove_quote_fee_override(&quote_token_mint)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::QuoteFeeOverrideRemoval,
+        encode_pubkey(Some(quote_token_mint)),
+        encode_quote_fee_override(&removed_override),
+    )?;
+
+    emit_cpi!(TokenMillQuoteFeeOverrideRemovalEvent {
+        config: ctx.accounts.config.key(),
+        quote_token_mint,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TokenMillConfig;
+    use joelana_test_utils::{
+        joelana_env::{
+            actions::token_mill::{
+                tm_parse_error, RemoveQuoteFeeOverrideAction, SetQuoteFeeOverrideAction,
+                TokenMillEnv,
+            },
+            TokenMillError,
+        },
+        utils::token_mill::constants::DEFAULT_PROTOCOL_FEE_SHARE,
+    };
+
+    fn setup_env() -> (TokenMillEnv, RemoveQuoteFeeOverrideAction) {
+        let mut testing_env = TokenMillEnv::new().with_default_quote_token_mint();
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetQuoteFeeOverrideAction::new(quote_token_mint, 0, 0)])
+            .unwrap();
+
+        let action = RemoveQuoteFeeOverrideAction::new(quote_token_mint);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn remove_quote_fee_override() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert!(config
+            .get_quote_fee_override(&action.quote_token_mint)
+            .is_none());
+        assert_eq!(
+            config.get_protocol_fee_share(&action.quote_token_mint),
+            DEFAULT_PROTOCOL_FEE_SHARE
+        );
+    }
+
+    #[test]
+    fn remove_missing_quote_fee_override() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::QuoteFeeOverrideNotFound);
+    }
+
+    #[test]
+    fn remove_quote_fee_override_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
@@ -0,0 +1,193 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillWalletRestrictionEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, ChangeLogParameter, Market, RestrictedWallet, TokenMillConfig},
+    CHANGE_LOG_PDA_SEED, RESTRICTED_WALLET_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RestrictWallet<'info> {
+    #[account(has_one = authority @ TokenMillError::InvalidAuthority)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(has_one = config @ TokenMillError::InvalidConfigAccount)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        init,
+        seeds = [
+            RESTRICTED_WALLET_PDA_SEED.as_bytes(),
+            market.key().as_ref(),
+            wallet.key().as_ref(),
+        ],
+        bump,
+        payer = authority,
+        space = 8 + RestrictedWallet::INIT_SPACE
+    )]
+    pub restricted_wallet: Account<'info, RestrictedWallet>,
+
+    /// CHECK: Wallet being restricted
+    pub wallet: UncheckedAccount<'info>,
+
+    /// Snapshotted if the wallet holds one
+    #[account(
+        associated_token::mint = market.load()?.base_token_mint,
+        associated_token::authority = wallet,
+        associated_token::token_program = base_token_program
+    )]
+    pub wallet_base_token_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Change log of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [CHANGE_LOG_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub change_log: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// The config authority acts as guardian: it freezes the swaps and stake updates of a wallet on a market,
+/// recording the base token balance of the wallet at that time
+pub fn handler(ctx: Context<RestrictWallet>) -> Result<()> {
+    let wallet = ctx.accounts.wallet.key();
+    let base_balance_snapshot = ctx
+        .accounts
+        .wallet_base_token_ata
+        .as_ref()
+        .map_or(0, |ata| ata.amount);
+
+    ctx.accounts.restricted_wallet.initialize(
+        ctx.bumps.restricted_wallet,
+        ctx.accounts.market.key(),
+        wallet,
+        Clock::get()?.unix_timestamp,
+        base_balance_snapshot,
+    )?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::WalletRestriction,
+        encode_pubkey(None),
+        encode_pubkey(Some(wallet)),
+    )?;
+
+    emit_cpi!(TokenMillWalletRestrictionEvent {
+        market: ctx.accounts.market.key(),
+        wallet,
+        restricted: true,
+        base_balance_snapshot,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RestrictedWallet;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, DepositAction, RestrictWalletAction, SwapAction, TokenMillEnv,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const BUY_AMOUNT: u64 = 1_000_000_000;
+
+    fn setup_env() -> (TokenMillEnv, RestrictWalletAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(BUY_AMOUNT);
+        testing_env.svm.change_payer("admin");
+
+        let action = RestrictWalletAction::new(&testing_env, make_address("bob"));
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn restrict_wallet() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let restricted_wallet = testing_env
+            .svm
+            .get_parsed_account::<RestrictedWallet>(&action.restricted_wallet);
+
+        assert_eq!(restricted_wallet.market, testing_env.market);
+        assert_eq!(restricted_wallet.wallet, make_address("bob"));
+        assert_eq!(restricted_wallet.base_balance_snapshot, BUY_AMOUNT);
+    }
+
+    #[test]
+    fn swap_and_stake_with_restricted_wallet() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Sell,
+            SwapAmountType::ExactInput,
+            BUY_AMOUNT,
+            0,
+            None,
+        );
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::WalletRestricted
+        );
+
+        let deposit_action = DepositAction::new(&testing_env, BUY_AMOUNT);
+
+        let result = testing_env.svm.execute_actions(&[&deposit_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::WalletRestricted
+        );
+
+        // Other wallets are unaffected
+        testing_env.svm.change_payer("carol");
+
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            BUY_AMOUNT,
+            u64::MAX,
+            None,
+        );
+        swap_action.with_user(make_address("carol"));
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+    }
+
+    #[test]
+    fn restrict_wallet_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
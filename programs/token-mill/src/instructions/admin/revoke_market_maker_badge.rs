@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillMarketMakerBadgeEvent,
+    state::{MarketMakerBadge, TokenMillConfig},
+    MARKET_MAKER_BADGE_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RevokeMarketMakerBadge<'info> {
+    #[account(has_one = authority @ TokenMillError::InvalidAuthority)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            MARKET_MAKER_BADGE_PDA_SEED.as_bytes(),
+            config.key().as_ref(),
+            market_maker_badge.market_maker.as_ref(),
+        ],
+        bump = market_maker_badge.bump,
+        close = authority
+    )]
+    pub market_maker_badge: Account<'info, MarketMakerBadge>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Revoking a badge prevents the market maker from posting new quotes and makes its open quotes unfillable.
+/// The market maker can still cancel them to recover the escrowed tokens.
+pub fn handler(ctx: Context<RevokeMarketMakerBadge>) -> Result<()> {
+    emit_cpi!(TokenMillMarketMakerBadgeEvent {
+        config: ctx.accounts.config.key(),
+        market_maker: ctx.accounts.market_maker_badge.market_maker,
+        enabled: false,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateMarketMakerBadgeAction, RevokeMarketMakerBadgeAction,
+            TokenMillEnv,
+        },
+        make_address, TokenMillError,
+    };
+
+    fn setup_env() -> (TokenMillEnv, RevokeMarketMakerBadgeAction) {
+        let mut testing_env = TokenMillEnv::new();
+
+        testing_env
+            .svm
+            .execute_actions(&[&CreateMarketMakerBadgeAction::new(make_address("carol"))])
+            .unwrap();
+
+        let action = RevokeMarketMakerBadgeAction::new(make_address("carol"));
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn revoke_market_maker_badge() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.market_maker_badge)
+            .is_none());
+    }
+
+    #[test]
+    fn revoke_market_maker_badge_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
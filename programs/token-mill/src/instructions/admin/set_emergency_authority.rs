@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillEmergencyAuthorityUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, ChangeLogParameter},
+};
+
+use super::ConfigUpdate;
+
+/// Sets the signer allowed to halt every swap with `pause_swaps`, e.g. a hot wallet of the incident responders.
+/// It must differ from the authority, the default key removing it
+pub fn handler(ctx: Context<ConfigUpdate>, emergency_authority: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    require!(
+        emergency_authority != config.authority,
+        TokenMillError::InvalidEmergencyAuthority
+    );
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::EmergencyAuthority,
+        encode_pubkey(Some(config.emergency_authority)),
+        encode_pubkey(Some(emergency_authority)),
+    )?;
+
+    config.emergency_authority = emergency_authority;
+
+    emit_cpi!(TokenMillEmergencyAuthorityUpdateEvent {
+        config: ctx.accounts.config.key(),
+        new_emergency_authority: emergency_authority,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TokenMillConfig;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, SetEmergencyAuthorityAction, TokenMillEnv},
+        make_address, TokenMillError,
+    };
+
+    #[test]
+    fn set_emergency_authority() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = SetEmergencyAuthorityAction::new(make_address("carol"));
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(config.emergency_authority, make_address("carol"));
+    }
+
+    #[test]
+    fn set_emergency_authority_to_authority() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = SetEmergencyAuthorityAction::new(make_address("admin"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidEmergencyAuthority
+        );
+    }
+
+    #[test]
+    fn set_emergency_authority_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let mut action = SetEmergencyAuthorityAction::new(make_address("mallory"));
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
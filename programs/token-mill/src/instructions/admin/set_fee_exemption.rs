@@ -0,0 +1,199 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillFeeExemptionUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, encode_u64, ChangeLogParameter},
+};
+
+use super::ConfigUpdate;
+
+/// Waives `fee_discount_bps` of the swap fee on the swaps made for `wallet`, so that internal flows swapping through
+/// the program, such as migrators or aggregators, don't charge their users twice. A discount of 0 removes the exemption.
+pub fn handler(ctx: Context<ConfigUpdate>, wallet: Pubkey, fee_discount_bps: u16) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    config.set_fee_exemption(wallet, fee_discount_bps)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::FeeExemption,
+        encode_pubkey(Some(wallet)),
+        encode_u64(fee_discount_bps.into()),
+    )?;
+
+    emit_cpi!(TokenMillFeeExemptionUpdateEvent {
+        config: ctx.accounts.config.key(),
+        wallet,
+        fee_discount_bps,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constant::MAX_BPS, state::FEE_EXEMPTIONS_LENGTH, Market, TokenMillConfig};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, SetFeeExemptionAction, SwapAction, TokenMillEnv},
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+    use solana_sdk::pubkey::Pubkey;
+
+    const FEE_DISCOUNT_BPS: u16 = 5_000;
+
+    /// Quote amount paid by bob for a buy, and the market after it
+    fn buy(fee_discount_bps: u16) -> (u64, Market) {
+        let mut testing_env = TokenMillEnv::default();
+
+        if fee_discount_bps != 0 {
+            testing_env.svm.change_payer("admin");
+
+            testing_env
+                .svm
+                .execute_actions(&[&SetFeeExemptionAction::new(
+                    make_address("bob"),
+                    fee_discount_bps,
+                )])
+                .unwrap();
+        }
+
+        testing_env.svm.change_payer("bob");
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &testing_env.svm.payer);
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                1_000_000_000,
+                u64::MAX,
+                None,
+            )])
+            .unwrap();
+
+        let cost = balance_before
+            - testing_env
+                .svm
+                .get_balance(&quote_token_mint, &testing_env.svm.payer);
+
+        (
+            cost,
+            testing_env
+                .svm
+                .get_parsed_account::<Market>(&testing_env.market),
+        )
+    }
+
+    #[test]
+    fn set_fee_exemption() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let mut action = SetFeeExemptionAction::new(make_address("carol"), FEE_DISCOUNT_BPS);
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(
+            config.get_fee_discount_bps(&make_address("carol")),
+            FEE_DISCOUNT_BPS
+        );
+        assert_eq!(config.get_fee_discount_bps(&make_address("bob")), 0);
+
+        // A discount of 0 removes the exemption
+        action.fee_discount_bps = 0;
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert!(config
+            .fee_exemptions
+            .iter()
+            .all(|exemption| exemption.wallet != make_address("carol")));
+    }
+
+    #[test]
+    fn swap_with_fee_exemption() {
+        let (cost, market) = buy(0);
+        let (discounted_cost, discounted_market) = buy(FEE_DISCOUNT_BPS);
+        let (exempted_cost, exempted_market) = buy(MAX_BPS as u16);
+
+        // The curve is traded the same, only the fee is waived
+        assert_eq!(discounted_market.quote_raised, market.quote_raised);
+        assert_eq!(exempted_market.quote_raised, market.quote_raised);
+
+        assert!(exempted_cost < discounted_cost && discounted_cost < cost);
+        assert!(discounted_market.fees.pending_creator_fees < market.fees.pending_creator_fees);
+        assert_eq!(exempted_market.fees.pending_creator_fees, 0);
+    }
+
+    #[test]
+    fn set_fee_exemption_with_full_table() {
+        let mut testing_env = TokenMillEnv::new();
+
+        for _ in 0..FEE_EXEMPTIONS_LENGTH {
+            testing_env
+                .svm
+                .execute_actions(&[&SetFeeExemptionAction::new(
+                    Pubkey::new_unique(),
+                    FEE_DISCOUNT_BPS,
+                )])
+                .unwrap();
+        }
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&SetFeeExemptionAction::new(
+                make_address("carol"),
+                FEE_DISCOUNT_BPS,
+            )]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::FeeExemptionsFull
+        );
+    }
+
+    #[test]
+    fn set_fee_exemption_with_invalid_value() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&SetFeeExemptionAction::new(
+                make_address("carol"),
+                MAX_BPS as u16 + 1,
+            )]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidFeeShare
+        );
+    }
+
+    #[test]
+    fn set_fee_exemption_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let mut action = SetFeeExemptionAction::new(make_address("carol"), FEE_DISCOUNT_BPS);
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
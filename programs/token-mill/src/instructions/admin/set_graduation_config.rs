@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillGraduationConfigUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, ChangeLogParameter, GraduationAdapter},
+};
+
+use super::ConfigUpdate;
+
+/// Sets the AMM markets graduate to, the default program disabling graduations.
+/// Pending graduations keep the AMM they started with
+pub fn handler(
+    ctx: Context<ConfigUpdate>,
+    graduation_adapter: GraduationAdapter,
+    graduation_program: Pubkey,
+    graduation_pool_config: Pubkey,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::GraduationConfig,
+        encode_pubkey(Some(config.graduation_program)),
+        encode_pubkey(Some(graduation_program)),
+    )?;
+
+    config.graduation_adapter = graduation_adapter;
+    config.graduation_program = graduation_program;
+    config.graduation_pool_config = graduation_pool_config;
+
+    emit_cpi!(TokenMillGraduationConfigUpdateEvent {
+        config: ctx.accounts.config.key(),
+        new_graduation_adapter: graduation_adapter,
+        new_graduation_program: graduation_program,
+        new_graduation_pool_config: graduation_pool_config,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{state::GraduationAdapter, TokenMillConfig};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, SetGraduationConfigAction, TokenMillEnv},
+        make_address, TokenMillError,
+    };
+
+    fn setup_env() -> (TokenMillEnv, SetGraduationConfigAction) {
+        let testing_env = TokenMillEnv::new();
+
+        let action = SetGraduationConfigAction::new(
+            GraduationAdapter::MeteoraDammV2,
+            make_address("pool_program"),
+            make_address("pool_config"),
+        );
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn set_graduation_config() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config_account = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(
+            config_account.graduation_adapter,
+            GraduationAdapter::MeteoraDammV2
+        );
+        assert_eq!(config_account.graduation_program, action.graduation_program);
+        assert_eq!(
+            config_account.graduation_pool_config,
+            action.graduation_pool_config
+        );
+    }
+
+    #[test]
+    fn set_graduation_config_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
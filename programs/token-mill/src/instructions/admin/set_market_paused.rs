@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillMarketPauseUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::MarketAdminUpdate;
+
+/// The config authority acts as guardian: it halts the swaps, firm quote fills and stake deposits of a compromised
+/// market. Fee claims, unstakes and vesting releases remain available while the market is paused
+pub fn handler(ctx: Context<MarketAdminUpdate>, paused: bool) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::MarketPause,
+        encode_u64(market.paused.into()),
+        encode_u64(paused.into()),
+    )?;
+
+    market.paused = paused.into();
+
+    emit_cpi!(TokenMillMarketPauseUpdateEvent {
+        market: ctx.accounts.market.key(),
+        paused,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, ClaimCreatorFeesAction, DepositAction, SetMarketPausedAction,
+            SwapAction, TokenMillEnv, WithdrawAction,
+        },
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const BASE_AMOUNT: u64 = 1_000_000;
+
+    fn buy_action(testing_env: &TokenMillEnv) -> SwapAction {
+        SwapAction::new(
+            testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            BASE_AMOUNT,
+            u64::MAX,
+            None,
+        )
+    }
+
+    fn setup_env() -> TokenMillEnv {
+        let mut testing_env = TokenMillEnv::default().with_staking(BASE_AMOUNT);
+
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, BASE_AMOUNT / 2)])
+            .unwrap();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetMarketPausedAction::new(true)])
+            .unwrap();
+
+        testing_env
+    }
+
+    #[test]
+    fn set_market_paused() {
+        let mut testing_env = setup_env();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.paused, 1);
+
+        testing_env.svm.change_payer("bob");
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&buy_action(&testing_env)]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketPaused
+        );
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, BASE_AMOUNT / 2)]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketPaused
+        );
+
+        // Exits remain open
+        testing_env
+            .svm
+            .execute_actions(&[&WithdrawAction::new(&testing_env, BASE_AMOUNT / 2)])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&ClaimCreatorFeesAction::new(&testing_env)])
+            .unwrap();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetMarketPausedAction::new(false)])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&buy_action(&testing_env)])
+            .unwrap();
+    }
+
+    #[test]
+    fn set_market_paused_with_invalid_signer() {
+        let mut testing_env = setup_env();
+
+        let mut action = SetMarketPausedAction::new(false);
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
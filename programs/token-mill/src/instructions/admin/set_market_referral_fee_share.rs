@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillMarketReferralFeeShareUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_fee_shares, ChangeLogParameter},
+};
+
+use super::MarketAdminUpdate;
+
+/// Overrides the referral fee share of the config for a single market, e.g. to reward the partner that launched it.
+/// `None` removes the override, the market then follows the config again.
+pub fn handler(ctx: Context<MarketAdminUpdate>, referral_fee_share: Option<u16>) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    let previous_value = encode_fee_shares(
+        u16::from(market.referral_fee_share_overridden),
+        market.referral_fee_share_override,
+    );
+
+    market.set_referral_fee_share_override(referral_fee_share)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::MarketReferralFeeShare,
+        previous_value,
+        encode_fee_shares(
+            u16::from(market.referral_fee_share_overridden),
+            market.referral_fee_share_override,
+        ),
+    )?;
+
+    emit_cpi!(TokenMillMarketReferralFeeShareUpdateEvent {
+        market: ctx.accounts.market.key(),
+        referral_fee_share,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constant::MAX_BPS, Market};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateReferralAccountAction, SetMarketReferralFeeShareAction,
+            SwapAction, TokenMillEnv,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    fn setup_env(referral_fee_share: Option<u16>) -> TokenMillEnv {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetMarketReferralFeeShareAction::new(
+                &testing_env,
+                referral_fee_share,
+            )])
+            .unwrap();
+
+        testing_env
+    }
+
+    /// Referral fee earned by carol on a buy of bob
+    fn swap_with_referral(testing_env: &mut TokenMillEnv) -> u64 {
+        testing_env.svm.change_payer("bob");
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let referral_account = CreateReferralAccountAction::new().referral_account;
+
+        let balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &referral_account);
+
+        let swap_action = SwapAction::new(
+            testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000,
+            u64::MAX,
+            Some(make_address("carol")),
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        testing_env
+            .svm
+            .get_balance(&quote_token_mint, &referral_account)
+            - balance_before
+    }
+
+    #[test]
+    fn set_market_referral_fee_share() {
+        let mut testing_env = setup_env(Some(MAX_BPS as u16));
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.referral_fee_share_overridden, 1);
+        assert_eq!(market.referral_fee_share_override, MAX_BPS as u16);
+
+        let overridden_referral_fee = swap_with_referral(&mut testing_env);
+
+        let mut testing_env = TokenMillEnv::default();
+
+        let default_referral_fee = swap_with_referral(&mut testing_env);
+
+        assert!(overridden_referral_fee > default_referral_fee);
+    }
+
+    #[test]
+    fn set_market_referral_fee_share_to_zero() {
+        let mut testing_env = setup_env(Some(0));
+
+        assert_eq!(swap_with_referral(&mut testing_env), 0);
+    }
+
+    #[test]
+    fn remove_market_referral_fee_share() {
+        let mut testing_env = setup_env(Some(0));
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetMarketReferralFeeShareAction::new(&testing_env, None)])
+            .unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.referral_fee_share_overridden, 0);
+        assert_eq!(market.referral_fee_share_override, 0);
+
+        assert!(swap_with_referral(&mut testing_env) > 0);
+    }
+
+    #[test]
+    fn set_market_referral_fee_share_too_high() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("admin");
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&SetMarketReferralFeeShareAction::new(
+                &testing_env,
+                Some(MAX_BPS as u16 + 1),
+            )]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidFeeShare
+        );
+    }
+
+    #[test]
+    fn set_market_referral_fee_share_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::default();
+
+        let mut action = SetMarketReferralFeeShareAction::new(&testing_env, Some(0));
+        action.signer = testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
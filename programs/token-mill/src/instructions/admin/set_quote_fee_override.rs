@@ -0,0 +1,300 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::MAX_BPS,
+    errors::TokenMillError,
+    events::TokenMillQuoteFeeOverrideUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, encode_quote_fee_override, ChangeLogParameter, QuoteFeeOverride},
+};
+
+use super::ConfigUpdate;
+
+/// Overrides the default fee shares for markets quoted in `quote_token_mint`.
+/// The protocol fee share only applies to markets created afterwards, the referral fee share applies to all swaps.
+/// The creator and staking fee shares are the defaults of the markets created without any, 0 to leave none.
+pub fn handler(
+    ctx: Context<ConfigUpdate>,
+    quote_token_mint: Pubkey,
+    protocol_fee_share: u16,
+    referral_fee_share: u16,
+    creator_fee_share: u16,
+    staking_fee_share: u16,
+) -> Result<()> {
+    require!(
+        protocol_fee_share <= MAX_BPS as u16 && referral_fee_share <= MAX_BPS as u16,
+        TokenMillError::InvalidFeeShare
+    );
+
+    // Default market fee shares have to add up with the protocol fee share
+    require!(
+        (creator_fee_share == 0 && staking_fee_share == 0)
+            || u64::from(protocol_fee_share)
+                + u64::from(creator_fee_share)
+                + u64::from(staking_fee_share)
+                == MAX_BPS,
+        TokenMillError::InvalidFeeShare
+    );
+
+    #[cfg(not(feature = "staking"))]
+    require!(staking_fee_share == 0, TokenMillError::InvalidFeeShare);
+
+    require!(
+        quote_token_mint != Pubkey::default(),
+        TokenMillError::InvalidQuoteTokenMint
+    );
+
+    let config = &mut ctx.accounts.config;
+
+    config.set_quote_fee_override(
+        quote_token_mint,
+        protocol_fee_share,
+        referral_fee_share,
+        creator_fee_share,
+        staking_fee_share,
+    )?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::QuoteFeeOverride,
+        encode_pubkey(Some(quote_token_mint)),
+        encode_quote_fee_override(&QuoteFeeOverride {
+            quote_token_mint,
+            protocol_fee_share,
+            referral_fee_share,
+            creator_fee_share,
+            staking_fee_share,
+        }),
+    )?;
+
+    emit_cpi!(TokenMillQuoteFeeOverrideUpdateEvent {
+        config: ctx.accounts.config.key(),
+        quote_token_mint,
+        protocol_fee_share,
+        referral_fee_share,
+        creator_fee_share,
+        staking_fee_share,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        constant::MAX_BPS,
+        state::{QuoteFeeOverride, QUOTE_FEE_OVERRIDES_LENGTH},
+        Market, TokenMillConfig,
+    };
+    use joelana_test_utils::{
+        joelana_env::{
+            actions::token_mill::{
+                tm_parse_error, CreateMarketAction, SetQuoteFeeOverrideAction, TokenMillEnv,
+            },
+            TokenMillError,
+        },
+        utils::token_mill::constants::{DEFAULT_PROTOCOL_FEE_SHARE, DEFAULT_REFERRAL_FEE_SHARE},
+    };
+    use solana_sdk::pubkey::Pubkey;
+
+    const PROTOCOL_FEE_SHARE: u16 = 2_000;
+    const REFERRAL_FEE_SHARE: u16 = 1_000;
+    const CREATOR_FEE_SHARE: u16 = 4_000;
+    const STAKING_FEE_SHARE: u16 = 4_000;
+
+    fn setup_env() -> (TokenMillEnv, SetQuoteFeeOverrideAction) {
+        let testing_env = TokenMillEnv::new().with_default_quote_token_mint();
+
+        let action = SetQuoteFeeOverrideAction::new(
+            testing_env.quote_token_mint.unwrap(),
+            PROTOCOL_FEE_SHARE,
+            REFERRAL_FEE_SHARE,
+        );
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn set_quote_fee_override() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(
+            config.get_quote_fee_override(&action.quote_token_mint),
+            Some(&QuoteFeeOverride {
+                quote_token_mint: action.quote_token_mint,
+                protocol_fee_share: PROTOCOL_FEE_SHARE,
+                referral_fee_share: REFERRAL_FEE_SHARE,
+                creator_fee_share: 0,
+                staking_fee_share: 0,
+            })
+        );
+        assert_eq!(
+            config.get_protocol_fee_share(&action.quote_token_mint),
+            PROTOCOL_FEE_SHARE
+        );
+        assert_eq!(
+            config.get_referral_fee_share(&action.quote_token_mint),
+            REFERRAL_FEE_SHARE
+        );
+
+        // Other quote tokens fall back to the defaults
+        assert_eq!(
+            config.get_protocol_fee_share(&Pubkey::new_unique()),
+            DEFAULT_PROTOCOL_FEE_SHARE
+        );
+        assert_eq!(
+            config.get_referral_fee_share(&Pubkey::new_unique()),
+            DEFAULT_REFERRAL_FEE_SHARE
+        );
+    }
+
+    #[test]
+    fn replace_quote_fee_override() {
+        let (mut testing_env, mut action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        action.protocol_fee_share = DEFAULT_PROTOCOL_FEE_SHARE;
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(
+            config.get_protocol_fee_share(&action.quote_token_mint),
+            DEFAULT_PROTOCOL_FEE_SHARE
+        );
+        assert_eq!(
+            config
+                .quote_fee_overrides
+                .iter()
+                .filter(|fee_override| fee_override.quote_token_mint == action.quote_token_mint)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn create_market_with_quote_fee_override() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        // Default creator and staking fee shares no longer add up with the overridden protocol fee share
+        let mut create_market_action = CreateMarketAction::new(&testing_env);
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[create_market_action.no_badge()]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidFeeShare);
+    }
+
+    #[test]
+    fn create_market_with_quote_default_fee_shares() {
+        let (mut testing_env, mut action) = setup_env();
+
+        testing_env
+            .svm
+            .execute_actions(&[action.with_market_fee_shares(CREATOR_FEE_SHARE, STAKING_FEE_SHARE)])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let mut create_market_action = CreateMarketAction::new(&testing_env);
+
+        testing_env
+            .svm
+            .execute_actions(&[create_market_action.no_badge().with_fee_shares(0, 0)])
+            .unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&create_market_action.market);
+
+        assert_eq!(market.fees.creator_fee_share, CREATOR_FEE_SHARE);
+        assert_eq!(market.fees.staking_fee_share, STAKING_FEE_SHARE);
+    }
+
+    #[test]
+    fn set_quote_fee_override_with_invalid_market_fee_shares() {
+        let (mut testing_env, mut action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[
+            action.with_market_fee_shares(CREATOR_FEE_SHARE, STAKING_FEE_SHARE + 1)
+        ]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidFeeShare);
+    }
+
+    #[test]
+    fn set_quote_fee_override_with_full_table() {
+        let (mut testing_env, action) = setup_env();
+
+        for _ in 0..QUOTE_FEE_OVERRIDES_LENGTH {
+            let action = SetQuoteFeeOverrideAction::new(
+                Pubkey::new_unique(),
+                PROTOCOL_FEE_SHARE,
+                REFERRAL_FEE_SHARE,
+            );
+
+            testing_env.svm.execute_actions(&[&action]).unwrap();
+        }
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::QuoteFeeOverridesFull);
+    }
+
+    #[test]
+    fn set_quote_fee_override_with_invalid_value() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.protocol_fee_share = MAX_BPS as u16 + 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidFeeShare);
+    }
+
+    #[test]
+    fn set_quote_fee_override_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
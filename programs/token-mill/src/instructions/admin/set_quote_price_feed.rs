@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillQuotePriceFeedUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, ChangeLogParameter},
+};
+
+use super::MarketAdminUpdate;
+
+/// Registers the Pyth price update of the quote token that swaps check against its peg.
+/// Only the guardian can set it, as a mismatched feed would block every swap. Default disables the check.
+pub fn handler(
+    ctx: Context<MarketAdminUpdate>,
+    quote_price_feed: Pubkey,
+    max_quote_depeg_bps: u16,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::QuotePriceFeed,
+        encode_pubkey(Some(market.quote_price_feed)),
+        encode_pubkey(Some(quote_price_feed)),
+    )?;
+
+    market.quote_price_feed = quote_price_feed;
+    market.max_quote_depeg_bps = max_quote_depeg_bps;
+
+    emit_cpi!(TokenMillQuotePriceFeedUpdateEvent {
+        market: ctx.accounts.market.key(),
+        quote_price_feed,
+        max_quote_depeg_bps,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, SetQuotePriceFeedAction, SwapAction, TokenMillEnv},
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const MAX_QUOTE_DEPEG_BPS: u16 = 200;
+
+    fn setup_env(price: i64) -> (TokenMillEnv, SwapAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        let quote_price_feed = make_address("quote_price_feed");
+        let publish_time = testing_env.svm.get_clock().unix_timestamp;
+
+        testing_env
+            .svm
+            .set_pyth_price_update(quote_price_feed, price, -8, publish_time)
+            .unwrap();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetQuotePriceFeedAction::new(
+                &testing_env,
+                quote_price_feed,
+                MAX_QUOTE_DEPEG_BPS,
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000,
+            u64::MAX,
+            None,
+        );
+        swap_action.quote_price_feed = quote_price_feed;
+
+        (testing_env, swap_action)
+    }
+
+    #[test]
+    fn set_quote_price_feed() {
+        let (testing_env, swap_action) = setup_env(100_000_000);
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.quote_price_feed, swap_action.quote_price_feed);
+        assert_eq!(market.max_quote_depeg_bps, MAX_QUOTE_DEPEG_BPS);
+    }
+
+    #[test]
+    fn swap_with_pegged_quote() {
+        let (mut testing_env, swap_action) = setup_env(99_000_000);
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn swap_with_depegged_quote() {
+        let (mut testing_env, swap_action) = setup_env(95_000_000);
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::QuoteDepegged
+        );
+    }
+
+    #[test]
+    fn swap_with_stale_depegged_quote() {
+        let (mut testing_env, swap_action) = setup_env(95_000_000);
+
+        testing_env.svm.warp(61);
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn swap_without_quote_price_feed() {
+        let (mut testing_env, mut swap_action) = setup_env(100_000_000);
+
+        swap_action.quote_price_feed = crate::ID;
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidQuotePriceFeed
+        );
+    }
+
+    #[test]
+    fn set_quote_price_feed_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::default();
+
+        let mut action =
+            SetQuotePriceFeedAction::new(&testing_env, make_address("quote_price_feed"), 0);
+        action.signer = testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
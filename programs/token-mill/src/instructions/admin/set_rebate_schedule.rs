@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillRebateScheduleUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{
+        encode_pubkey, encode_rebate_tiers, ChangeLogParameter, RebateTier, REBATE_TIERS_LENGTH,
+    },
+};
+
+use super::ConfigUpdate;
+
+/// Sets the protocol fee rebates granted on swaps quoted in `quote_token_mint` to traders whose 30-day volume
+/// reaches the tier thresholds. Tiers with a rebate of 0 are unused, the schedule is removed if all of them are.
+pub fn handler(
+    ctx: Context<ConfigUpdate>,
+    quote_token_mint: Pubkey,
+    tiers: [RebateTier; REBATE_TIERS_LENGTH],
+) -> Result<()> {
+    require!(
+        quote_token_mint != Pubkey::default(),
+        TokenMillError::InvalidQuoteTokenMint
+    );
+
+    let config = &mut ctx.accounts.config;
+
+    config.set_rebate_schedule(quote_token_mint, tiers)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::RebateSchedule,
+        encode_pubkey(Some(quote_token_mint)),
+        encode_rebate_tiers(&tiers),
+    )?;
+
+    emit_cpi!(TokenMillRebateScheduleUpdateEvent {
+        config: ctx.accounts.config.key(),
+        quote_token_mint,
+        tiers,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{state::RebateTier, TokenMillConfig};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, SetRebateScheduleAction, TokenMillEnv},
+        make_address, TokenMillError,
+    };
+
+    const TIERS: [RebateTier; 3] = [
+        RebateTier {
+            min_volume: 0,
+            rebate_bps: 0,
+        },
+        RebateTier {
+            min_volume: 1_000_000_000,
+            rebate_bps: 10,
+        },
+        RebateTier {
+            min_volume: 10_000_000_000,
+            rebate_bps: 25,
+        },
+    ];
+
+    fn setup_env() -> (TokenMillEnv, SetRebateScheduleAction) {
+        let testing_env = TokenMillEnv::new().with_default_quote_token_mint();
+
+        let action = SetRebateScheduleAction::new(testing_env.quote_token_mint.unwrap(), TIERS);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn set_rebate_schedule() {
+        let (mut testing_env, mut action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(config.get_rebate_bps(&action.quote_token_mint, 0), 0);
+        assert_eq!(
+            config.get_rebate_bps(&action.quote_token_mint, 1_000_000_000),
+            10
+        );
+        assert_eq!(
+            config.get_rebate_bps(&action.quote_token_mint, 50_000_000_000),
+            25
+        );
+        assert_eq!(
+            config.get_rebate_bps(&make_address("other_mint"), 50_000_000_000),
+            0
+        );
+
+        // Unused tiers remove the schedule
+        action.tiers = Default::default();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert!(config
+            .rebate_schedules
+            .iter()
+            .all(|schedule| schedule.quote_token_mint != action.quote_token_mint));
+    }
+
+    #[test]
+    fn set_rebate_schedule_with_decreasing_tiers() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.tiers[2].min_volume = action.tiers[1].min_volume;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidRebateTiers
+        );
+    }
+
+    #[test]
+    fn set_rebate_schedule_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillStakerDiscountsUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{
+        encode_pubkey, encode_staker_discount_tiers, ChangeLogParameter, StakerDiscountTier,
+        STAKER_DISCOUNT_TIERS_LENGTH,
+    },
+};
+
+use super::ConfigUpdate;
+
+/// Sets the market of the protocol token and the protocol fee discounts granted to its stakers on `swap`.
+/// Tiers with a discount of 0 are unused, a default market disables the discounts.
+pub fn handler(
+    ctx: Context<ConfigUpdate>,
+    protocol_token_market: Pubkey,
+    tiers: [StakerDiscountTier; STAKER_DISCOUNT_TIERS_LENGTH],
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    let old_protocol_token_market = config.protocol_token_market;
+
+    config.set_staker_discounts(protocol_token_market, tiers)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::StakerDiscounts,
+        encode_pubkey(Some(old_protocol_token_market)),
+        encode_staker_discount_tiers(&tiers),
+    )?;
+
+    emit_cpi!(TokenMillStakerDiscountsUpdateEvent {
+        config: ctx.accounts.config.key(),
+        protocol_token_market,
+        tiers,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{state::StakerDiscountTier, TokenMillConfig};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, DepositAction, SetStakerDiscountsAction, SwapAction, TokenMillEnv,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    const TIERS: [StakerDiscountTier; 3] = [
+        StakerDiscountTier {
+            min_stake: 1_000_000,
+            discount_bps: 1_000,
+        },
+        StakerDiscountTier {
+            min_stake: STAKE_AMOUNT,
+            discount_bps: 5_000,
+        },
+        StakerDiscountTier {
+            min_stake: 0,
+            discount_bps: 0,
+        },
+    ];
+
+    fn setup_env() -> (TokenMillEnv, SetStakerDiscountsAction) {
+        let testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        let action = SetStakerDiscountsAction::new(testing_env.market, TIERS);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn set_staker_discounts() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(config.protocol_token_market, testing_env.market);
+        assert_eq!(config.get_staker_discount_bps(&testing_env.market, 0), 0);
+        assert_eq!(
+            config.get_staker_discount_bps(&testing_env.market, 1_000_000),
+            1_000
+        );
+        assert_eq!(
+            config.get_staker_discount_bps(&testing_env.market, STAKE_AMOUNT),
+            5_000
+        );
+        assert_eq!(
+            config.get_staker_discount_bps(&make_address("other_market"), STAKE_AMOUNT),
+            0
+        );
+    }
+
+    /// Protocol fee paid on a buy by a staker of the market, with or without the staker discounts
+    fn staker_protocol_fee(with_discounts: bool) -> u64 {
+        let (mut testing_env, action) = setup_env();
+
+        if with_discounts {
+            testing_env.svm.change_payer("admin");
+            testing_env.svm.execute_actions(&[&action]).unwrap();
+        }
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000,
+            u64::MAX,
+            None,
+        );
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let protocol_fee_recipient = make_address("dave");
+        let protocol_balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &protocol_fee_recipient);
+
+        testing_env
+            .svm
+            .execute_actions(&[swap_action.with_stake_position(testing_env.market)])
+            .unwrap();
+
+        testing_env
+            .svm
+            .get_balance(&quote_token_mint, &protocol_fee_recipient)
+            - protocol_balance_before
+    }
+
+    #[test]
+    fn swap_with_staker_discount() {
+        let protocol_fee = staker_protocol_fee(false);
+        let discounted_protocol_fee = staker_protocol_fee(true);
+
+        assert!(protocol_fee > 0);
+        assert_eq!(
+            discounted_protocol_fee,
+            protocol_fee - protocol_fee * u64::from(TIERS[1].discount_bps) / 10_000
+        );
+    }
+
+    #[test]
+    fn set_staker_discounts_with_decreasing_tiers() {
+        let (mut testing_env, mut action) = setup_env();
+
+        testing_env.svm.change_payer("admin");
+
+        action.tiers[1].min_stake = action.tiers[0].min_stake;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidStakerDiscountTiers
+        );
+    }
+
+    #[test]
+    fn set_staker_discounts_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
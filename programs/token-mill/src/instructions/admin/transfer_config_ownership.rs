@@ -1,6 +1,11 @@
 use anchor_lang::prelude::*;
 
-use crate::{errors::TokenMillError, state::TokenMillConfig};
+use crate::{
+    errors::TokenMillError,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, ChangeLogParameter, TokenMillConfig},
+    CHANGE_LOG_PDA_SEED,
+};
 
 #[event_cpi]
 #[derive(Accounts)]
@@ -8,12 +13,27 @@ pub struct ConfigUpdate<'info> {
     #[account(mut, has_one = authority @ TokenMillError::InvalidAuthority)]
     pub config: Account<'info, TokenMillConfig>,
 
+    /// CHECK: Change log of the config, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [CHANGE_LOG_PDA_SEED.as_bytes(), config.key().as_ref()],
+        bump
+    )]
+    pub change_log: UncheckedAccount<'info>,
+
     pub authority: Signer<'info>,
 }
 
 pub fn handler(ctx: Context<ConfigUpdate>, new_authority: Option<Pubkey>) -> Result<()> {
     let config = &mut ctx.accounts.config;
 
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::PendingAuthority,
+        encode_pubkey(config.pending_authority),
+        encode_pubkey(new_authority),
+    )?;
+
     config.pending_authority = new_authority;
 
     Ok(())
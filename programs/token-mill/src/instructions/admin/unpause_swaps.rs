@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillSwapsPauseUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::ConfigUpdate;
+
+/// Lifts the circuit breaker set by `pause_swaps`, reserved to the authority
+pub fn handler(ctx: Context<ConfigUpdate>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::SwapsPause,
+        encode_u64(config.swaps_paused.into()),
+        encode_u64(0),
+    )?;
+
+    config.swaps_paused = false;
+
+    emit_cpi!(TokenMillSwapsPauseUpdateEvent {
+        config: ctx.accounts.config.key(),
+        paused: false,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{PauseSwapsAction, SwapAction, TokenMillEnv, UnpauseSwapsAction},
+        SwapAmountType, SwapType,
+    };
+
+    #[test]
+    fn unpause_swaps() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&PauseSwapsAction::new(), &UnpauseSwapsAction::new()])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                1_000_000,
+                u64::MAX,
+                None,
+            )])
+            .unwrap();
+    }
+}
@@ -1,7 +1,11 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    constant::MAX_BPS, errors::TokenMillError, events::TokenMillDefaultFeeSharesUpdateEvent,
+    constant::MAX_BPS,
+    errors::TokenMillError,
+    events::TokenMillDefaultFeeSharesUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_fee_shares, ChangeLogParameter},
 };
 
 use super::ConfigUpdate;
@@ -19,6 +23,13 @@ pub fn handler(
 
     let config = &mut ctx.accounts.config;
 
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::DefaultFeeShares,
+        encode_fee_shares(config.default_protocol_fee_share, config.referral_fee_share),
+        encode_fee_shares(new_default_protocol_fee_share, new_referral_fee_share),
+    )?;
+
     config.default_protocol_fee_share = new_default_protocol_fee_share;
     config.referral_fee_share = new_referral_fee_share;
 
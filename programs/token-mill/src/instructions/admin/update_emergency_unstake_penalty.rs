@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::MAX_BPS,
+    errors::TokenMillError,
+    events::TokenMillEmergencyUnstakePenaltyUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::ConfigUpdate;
+
+/// Sets the penalty of the emergency unstakes, 0 disabling them. It applies to the current lockups as well
+pub fn handler(ctx: Context<ConfigUpdate>, new_emergency_unstake_penalty_bps: u16) -> Result<()> {
+    require!(
+        u64::from(new_emergency_unstake_penalty_bps) <= MAX_BPS,
+        TokenMillError::InvalidFeeShare
+    );
+
+    let config = &mut ctx.accounts.config;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::EmergencyUnstakePenalty,
+        encode_u64(config.emergency_unstake_penalty_bps.into()),
+        encode_u64(new_emergency_unstake_penalty_bps.into()),
+    )?;
+
+    config.emergency_unstake_penalty_bps = new_emergency_unstake_penalty_bps;
+
+    emit_cpi!(TokenMillEmergencyUnstakePenaltyUpdateEvent {
+        config: ctx.accounts.config.key(),
+        new_emergency_unstake_penalty_bps,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constant::MAX_BPS, TokenMillConfig};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, TokenMillEnv, UpdateEmergencyUnstakePenaltyAction},
+        TokenMillError,
+    };
+
+    const NEW_PENALTY_BPS: u16 = 2_000;
+
+    fn setup_env() -> (TokenMillEnv, UpdateEmergencyUnstakePenaltyAction) {
+        let testing_env = TokenMillEnv::new();
+
+        let action = UpdateEmergencyUnstakePenaltyAction::new(NEW_PENALTY_BPS);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn update_emergency_unstake_penalty() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config_account = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(
+            config_account.emergency_unstake_penalty_bps,
+            NEW_PENALTY_BPS
+        );
+    }
+
+    #[test]
+    fn update_emergency_unstake_penalty_with_invalid_value() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.new_emergency_unstake_penalty_bps = MAX_BPS as u16 + 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidFeeShare
+        );
+    }
+
+    #[test]
+    fn update_emergency_unstake_penalty_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillFeeDistributionIntervalUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::ConfigUpdate;
+
+pub fn handler(ctx: Context<ConfigUpdate>, new_fee_distribution_interval: u16) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::FeeDistributionInterval,
+        encode_u64(config.fee_distribution_interval.into()),
+        encode_u64(new_fee_distribution_interval.into()),
+    )?;
+
+    config.fee_distribution_interval = new_fee_distribution_interval;
+
+    emit_cpi!(TokenMillFeeDistributionIntervalUpdateEvent {
+        config: ctx.accounts.config.key(),
+        new_fee_distribution_interval,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Market, MarketStaking, TokenMillConfig};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, DepositAction, SwapAction, TokenMillEnv,
+            UpdateFeeDistributionIntervalAction,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    #[test]
+    fn update_fee_distribution_interval() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = UpdateFeeDistributionIntervalAction::new(2);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(config.fee_distribution_interval, 2);
+    }
+
+    #[test]
+    fn distribute_fees_every_nth_swap() {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&UpdateFeeDistributionIntervalAction::new(2)])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000,
+            u64::MAX,
+            None,
+        );
+        swap_action.with_fee_distribution_accounts();
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let creator_balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("alice"));
+
+        // The market already went through a swap when setting up staking
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.swap_count, 2);
+        assert_eq!(market.fees.pending_creator_fees, 0);
+        assert_eq!(market.fees.pending_staking_fees, 0);
+
+        let creator_balance_after = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("alice"));
+
+        assert!(creator_balance_after > creator_balance_before);
+
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&swap_action.staking);
+
+        assert!(staking.acc_reward_amount_per_share > 0);
+    }
+
+    #[test]
+    fn update_fee_distribution_interval_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let mut action = UpdateFeeDistributionIntervalAction::new(2);
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
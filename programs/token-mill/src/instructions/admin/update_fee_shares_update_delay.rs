@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::MAX_FEE_SHARES_UPDATE_DELAY,
+    errors::TokenMillError,
+    events::TokenMillFeeSharesUpdateDelayUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::ConfigUpdate;
+
+/// Sets the delay applied to the fee shares proposed from now on, pending proposals keep their activation time
+pub fn handler(ctx: Context<ConfigUpdate>, new_fee_shares_update_delay: u32) -> Result<()> {
+    require!(
+        new_fee_shares_update_delay <= MAX_FEE_SHARES_UPDATE_DELAY,
+        TokenMillError::InvalidFeeSharesUpdateDelay
+    );
+
+    let config = &mut ctx.accounts.config;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::FeeSharesUpdateDelay,
+        encode_u64(config.fee_shares_update_delay.into()),
+        encode_u64(new_fee_shares_update_delay.into()),
+    )?;
+
+    config.fee_shares_update_delay = new_fee_shares_update_delay;
+
+    emit_cpi!(TokenMillFeeSharesUpdateDelayUpdateEvent {
+        config: ctx.accounts.config.key(),
+        new_fee_shares_update_delay,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constant::MAX_FEE_SHARES_UPDATE_DELAY, TokenMillConfig};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, TokenMillEnv, UpdateFeeSharesUpdateDelayAction},
+        TokenMillError,
+    };
+
+    const NEW_FEE_SHARES_UPDATE_DELAY: u32 = 86_400;
+
+    #[test]
+    fn update_fee_shares_update_delay() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = UpdateFeeSharesUpdateDelayAction::new(NEW_FEE_SHARES_UPDATE_DELAY);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(config.fee_shares_update_delay, NEW_FEE_SHARES_UPDATE_DELAY);
+    }
+
+    #[test]
+    fn update_fee_shares_update_delay_above_max() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = UpdateFeeSharesUpdateDelayAction::new(MAX_FEE_SHARES_UPDATE_DELAY + 1);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidFeeSharesUpdateDelay);
+    }
+
+    #[test]
+    fn update_fee_shares_update_delay_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let mut action = UpdateFeeSharesUpdateDelayAction::new(NEW_FEE_SHARES_UPDATE_DELAY);
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::MAX_BPS,
+    errors::TokenMillError,
+    events::TokenMillFirmQuoteFeeUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::ConfigUpdate;
+
+pub fn handler(ctx: Context<ConfigUpdate>, new_firm_quote_fee_bps: u16) -> Result<()> {
+    require!(
+        new_firm_quote_fee_bps <= MAX_BPS as u16,
+        TokenMillError::InvalidFeeShare
+    );
+
+    let config = &mut ctx.accounts.config;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::FirmQuoteFee,
+        encode_u64(config.firm_quote_fee_bps.into()),
+        encode_u64(new_firm_quote_fee_bps.into()),
+    )?;
+
+    config.firm_quote_fee_bps = new_firm_quote_fee_bps;
+
+    emit_cpi!(TokenMillFirmQuoteFeeUpdateEvent {
+        config: ctx.accounts.config.key(),
+        new_firm_quote_fee_bps,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constant::MAX_BPS, TokenMillConfig};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, TokenMillEnv, UpdateFirmQuoteFeeAction},
+        TokenMillError,
+    };
+
+    const NEW_FIRM_QUOTE_FEE_BPS: u16 = 50;
+
+    #[test]
+    fn update_firm_quote_fee() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = UpdateFirmQuoteFeeAction::new(NEW_FIRM_QUOTE_FEE_BPS);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config_account = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(config_account.firm_quote_fee_bps, NEW_FIRM_QUOTE_FEE_BPS);
+    }
+
+    #[test]
+    fn update_firm_quote_fee_with_invalid_value() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = UpdateFirmQuoteFeeAction::new(MAX_BPS as u16 + 1);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidFeeShare);
+    }
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::MAX_MARKET_CLOSE_DELAY,
+    errors::TokenMillError,
+    events::TokenMillMarketCloseDelayUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::ConfigUpdate;
+
+/// Sets the delay applied to the market closings proposed from now on, pending proposals keep their close time
+pub fn handler(ctx: Context<ConfigUpdate>, new_market_close_delay: u32) -> Result<()> {
+    require!(
+        new_market_close_delay <= MAX_MARKET_CLOSE_DELAY,
+        TokenMillError::InvalidMarketCloseDelay
+    );
+
+    let config = &mut ctx.accounts.config;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::MarketCloseDelay,
+        encode_u64(config.market_close_delay.into()),
+        encode_u64(new_market_close_delay.into()),
+    )?;
+
+    config.market_close_delay = new_market_close_delay;
+
+    emit_cpi!(TokenMillMarketCloseDelayUpdateEvent {
+        config: ctx.accounts.config.key(),
+        new_market_close_delay,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constant::MAX_MARKET_CLOSE_DELAY, TokenMillConfig};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, TokenMillEnv, UpdateMarketCloseDelayAction},
+        TokenMillError,
+    };
+
+    const NEW_MARKET_CLOSE_DELAY: u32 = 86_400;
+
+    #[test]
+    fn update_market_close_delay() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = UpdateMarketCloseDelayAction::new(NEW_MARKET_CLOSE_DELAY);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(config.market_close_delay, NEW_MARKET_CLOSE_DELAY);
+    }
+
+    #[test]
+    fn update_market_close_delay_above_max() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = UpdateMarketCloseDelayAction::new(MAX_MARKET_CLOSE_DELAY + 1);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidMarketCloseDelay);
+    }
+
+    #[test]
+    fn update_market_close_delay_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let mut action = UpdateMarketCloseDelayAction::new(NEW_MARKET_CLOSE_DELAY);
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
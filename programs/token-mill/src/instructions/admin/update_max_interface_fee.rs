@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::MAX_INTERFACE_FEE_BPS,
+    errors::TokenMillError,
+    events::TokenMillMaxInterfaceFeeUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::ConfigUpdate;
+
+pub fn handler(ctx: Context<ConfigUpdate>, new_max_interface_fee_bps: u16) -> Result<()> {
+    require!(
+        new_max_interface_fee_bps <= MAX_INTERFACE_FEE_BPS,
+        TokenMillError::InvalidFeeShare
+    );
+
+    let config = &mut ctx.accounts.config;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::MaxInterfaceFee,
+        encode_u64(config.max_interface_fee_bps.into()),
+        encode_u64(new_max_interface_fee_bps.into()),
+    )?;
+
+    config.max_interface_fee_bps = new_max_interface_fee_bps;
+
+    emit_cpi!(TokenMillMaxInterfaceFeeUpdateEvent {
+        config: ctx.accounts.config.key(),
+        new_max_interface_fee_bps,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constant::MAX_INTERFACE_FEE_BPS, TokenMillConfig};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, TokenMillEnv, UpdateMaxInterfaceFeeAction},
+        TokenMillError,
+    };
+
+    const NEW_MAX_INTERFACE_FEE_BPS: u16 = MAX_INTERFACE_FEE_BPS;
+
+    fn setup_env() -> (TokenMillEnv, UpdateMaxInterfaceFeeAction) {
+        let testing_env = TokenMillEnv::new();
+
+        let action = UpdateMaxInterfaceFeeAction::new(NEW_MAX_INTERFACE_FEE_BPS);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn update_max_interface_fee() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config_account = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(
+            config_account.max_interface_fee_bps,
+            NEW_MAX_INTERFACE_FEE_BPS
+        );
+    }
+
+    #[test]
+    fn update_max_interface_fee_with_invalid_value() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.new_max_interface_fee_bps = MAX_INTERFACE_FEE_BPS + 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidFeeShare);
+    }
+
+    #[test]
+    fn update_max_interface_fee_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::MAX_BPS,
+    errors::TokenMillError,
+    events::TokenMillMaxSpreadUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::ConfigUpdate;
+
+/// Sets the widest bid/ask spread allowed on the curves set from now on, existing curves are unaffected
+pub fn handler(ctx: Context<ConfigUpdate>, new_max_spread_bps: u16) -> Result<()> {
+    require!(
+        u64::from(new_max_spread_bps) <= MAX_BPS,
+        TokenMillError::InvalidFeeShare
+    );
+
+    let config = &mut ctx.accounts.config;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::MaxSpread,
+        encode_u64(config.max_spread_bps.into()),
+        encode_u64(new_max_spread_bps.into()),
+    )?;
+
+    config.max_spread_bps = new_max_spread_bps;
+
+    emit_cpi!(TokenMillMaxSpreadUpdateEvent {
+        config: ctx.accounts.config.key(),
+        new_max_spread_bps,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constant::MAX_BPS, TokenMillConfig};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, TokenMillEnv, UpdateMaxSpreadAction},
+        TokenMillError,
+    };
+
+    const NEW_MAX_SPREAD_BPS: u16 = 500;
+
+    fn setup_env() -> (TokenMillEnv, UpdateMaxSpreadAction) {
+        let testing_env = TokenMillEnv::new();
+
+        let action = UpdateMaxSpreadAction::new(NEW_MAX_SPREAD_BPS);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn update_max_spread() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config_account = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(config_account.max_spread_bps, NEW_MAX_SPREAD_BPS);
+    }
+
+    #[test]
+    fn update_max_spread_with_invalid_value() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.new_max_spread_bps = MAX_BPS as u16 + 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidFeeShare
+        );
+    }
+
+    #[test]
+    fn update_max_spread_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::MAX_MIN_MARKET_AGE,
+    errors::TokenMillError,
+    events::TokenMillMinMarketAgeUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::ConfigUpdate;
+
+/// Sets the age markets have to reach before their creator can propose to close them, pending proposals are kept
+pub fn handler(ctx: Context<ConfigUpdate>, new_min_market_age: u32) -> Result<()> {
+    require!(
+        new_min_market_age <= MAX_MIN_MARKET_AGE,
+        TokenMillError::InvalidMinMarketAge
+    );
+
+    let config = &mut ctx.accounts.config;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::MinMarketAge,
+        encode_u64(config.min_market_age.into()),
+        encode_u64(new_min_market_age.into()),
+    )?;
+
+    config.min_market_age = new_min_market_age;
+
+    emit_cpi!(TokenMillMinMarketAgeUpdateEvent {
+        config: ctx.accounts.config.key(),
+        new_min_market_age,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constant::MAX_MIN_MARKET_AGE, TokenMillConfig};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, TokenMillEnv, UpdateMinMarketAgeAction},
+        TokenMillError,
+    };
+
+    const NEW_MIN_MARKET_AGE: u32 = 604_800;
+
+    #[test]
+    fn update_min_market_age() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = UpdateMinMarketAgeAction::new(NEW_MIN_MARKET_AGE);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(config.min_market_age, NEW_MIN_MARKET_AGE);
+    }
+
+    #[test]
+    fn update_min_market_age_above_max() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = UpdateMinMarketAgeAction::new(MAX_MIN_MARKET_AGE + 1);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidMinMarketAge);
+    }
+
+    #[test]
+    fn update_min_market_age_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let mut action = UpdateMinMarketAgeAction::new(NEW_MIN_MARKET_AGE);
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
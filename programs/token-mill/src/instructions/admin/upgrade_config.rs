@@ -0,0 +1,153 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+    Discriminator,
+};
+
+use crate::{errors::TokenMillError, events::TokenMillConfigUpgradeEvent, state::TokenMillConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpgradeConfig<'info> {
+    /// CHECK: Config of the first deployment, checked by the handler as it doesn't deserialize yet
+    #[account(mut, owner = crate::ID @ TokenMillError::InvalidConfigAccount)]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a config created by the first deployment to the current layout. The fields added since then are appended
+/// to the config, so the zeroed bytes deserialize to their default values
+pub fn handler(ctx: Context<UpgradeConfig>) -> Result<()> {
+    let config_info = ctx.accounts.config.to_account_info();
+    let new_len = 8 + TokenMillConfig::INIT_SPACE;
+
+    {
+        let data = config_info.try_borrow_data()?;
+
+        require!(
+            data.len() >= 40 && data.len() < new_len && data[..8] == TokenMillConfig::DISCRIMINATOR,
+            TokenMillError::InvalidConfigAccount
+        );
+        require!(
+            data[8..40] == ctx.accounts.authority.key().to_bytes(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+
+    grow_account(
+        &config_info,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        new_len,
+    )?;
+
+    TokenMillConfig::try_deserialize(&mut &config_info.try_borrow_data()?[..])?;
+
+    emit_cpi!(TokenMillConfigUpgradeEvent {
+        config: ctx.accounts.config.key(),
+    });
+
+    Ok(())
+}
+
+/// Reallocates an account of the program to `new_len` zero-initialized bytes, the payer topping up its rent
+pub(crate) fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    new_len: usize,
+) -> Result<()> {
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+
+    if rent_due > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    account.realloc(new_len, true)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, TokenMillEnv, UpgradeConfigAction},
+        make_address, TokenMillError,
+    };
+
+    use crate::state::TokenMillConfig;
+
+    fn setup_env() -> (TokenMillEnv, UpgradeConfigAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.set_legacy_config();
+
+        let action = UpgradeConfigAction::new();
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn upgrade_config() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let config = testing_env
+            .svm
+            .get_parsed_account::<TokenMillConfig>(&action.config);
+
+        assert_eq!(config.authority, action.signer);
+        assert_eq!(config.protocol_fee_recipient, make_address("dave"));
+        assert_eq!(config.max_spread_bps, 0);
+        assert_eq!(config.market_close_delay, 0);
+        assert_eq!(config.min_market_age, 0);
+    }
+
+    #[test]
+    fn upgrade_config_twice() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidConfigAccount);
+    }
+
+    #[test]
+    fn upgrade_config_with_invalid_authority() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
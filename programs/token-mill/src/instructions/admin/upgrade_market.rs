@@ -0,0 +1,221 @@
+use anchor_lang::{prelude::*, Discriminator};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id, token_interface::TokenAccount,
+};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillMarketUpgradeEvent,
+    state::{LegacyMarket, Market, TokenMillConfig},
+};
+
+use super::upgrade_config::grow_account;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpgradeMarket<'info> {
+    #[account(has_one = authority @ TokenMillError::InvalidAuthority)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    /// CHECK: Market of the first deployment, checked by the handler as it doesn't deserialize yet
+    #[account(mut, owner = crate::ID @ TokenMillError::InvalidMarket)]
+    pub market: UncheckedAccount<'info>,
+
+    pub market_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Converts a market created by the first deployment to the current layout, the prices having grown to
+/// `MAX_PRICES_LENGTH` and the precomputed curve values being inserted after them.
+/// Gated by the config authority since the quote amount raised is recovered from the balance of the market
+pub fn handler(ctx: Context<UpgradeMarket>) -> Result<()> {
+    let market_info = ctx.accounts.market.to_account_info();
+
+    let legacy_market = {
+        let data = market_info.try_borrow_data()?;
+
+        require!(
+            data.len() == 8 + std::mem::size_of::<LegacyMarket>()
+                && data[..8] == Market::DISCRIMINATOR,
+            TokenMillError::InvalidMarket
+        );
+
+        *bytemuck::from_bytes::<LegacyMarket>(&data[8..])
+    };
+
+    require_keys_eq!(
+        legacy_market.config,
+        ctx.accounts.config.key(),
+        TokenMillError::InvalidConfigAccount
+    );
+
+    let market_quote_token_ata = &ctx.accounts.market_quote_token_ata;
+
+    require_keys_eq!(
+        market_quote_token_ata.key(),
+        get_associated_token_address_with_program_id(
+            &market_info.key(),
+            &legacy_market.quote_token_mint,
+            market_quote_token_ata.to_account_info().owner,
+        ),
+        TokenMillError::InvalidMintAccount
+    );
+
+    // The pending fees are held by the market next to the quote reserve of the curve
+    let quote_reserve = market_quote_token_ata
+        .amount
+        .saturating_sub(legacy_market.fees.pending_staking_fees)
+        .saturating_sub(legacy_market.fees.pending_creator_fees);
+
+    let market = Market::from_legacy(&legacy_market, quote_reserve)?;
+
+    grow_account(
+        &market_info,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        8 + Market::INIT_SPACE,
+    )?;
+
+    {
+        let mut data = market_info.try_borrow_mut_data()?;
+
+        *bytemuck::from_bytes_mut::<Market>(&mut data[8..8 + std::mem::size_of::<Market>()]) =
+            market;
+    }
+
+    emit_cpi!(TokenMillMarketUpgradeEvent {
+        market: market_info.key(),
+        quote_raised: quote_reserve,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, SwapAction, TokenMillEnv, UpgradeMarketAction},
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    use crate::state::Market;
+
+    const BASE_AMOUNT: u64 = 10_000_000_000;
+
+    fn setup_env() -> (TokenMillEnv, Market, UpgradeMarketAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                BASE_AMOUNT,
+                u64::MAX,
+                None,
+            )])
+            .unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        testing_env.set_legacy_market();
+        testing_env.svm.change_payer("admin");
+
+        let action = UpgradeMarketAction::new(&testing_env);
+
+        (testing_env, market, action)
+    }
+
+    #[test]
+    fn upgrade_market() {
+        let (mut testing_env, market_before, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env.svm.get_parsed_account::<Market>(&action.market);
+
+        assert_eq!(market.creator, market_before.creator);
+        assert_eq!(market.base_reserve, market_before.base_reserve);
+        assert_eq!(market.total_supply, market_before.total_supply);
+        assert_eq!(market.interval_number, market_before.interval_number);
+        assert_eq!(market.width_scaled, market_before.width_scaled);
+        assert_eq!(market.bid_prices, market_before.bid_prices);
+        assert_eq!(market.ask_prices, market_before.ask_prices);
+        assert_eq!(market.prices_hash, market_before.prices_hash);
+        assert_eq!(
+            market.bid_cumulative_quotes,
+            market_before.bid_cumulative_quotes
+        );
+        assert_eq!(
+            market.ask_cumulative_quotes,
+            market_before.ask_cumulative_quotes
+        );
+        assert_eq!(
+            market.fees.pending_creator_fees,
+            market_before.fees.pending_creator_fees
+        );
+        assert_eq!(
+            market.quote_raised,
+            testing_env
+                .svm
+                .get_balance(&testing_env.quote_token_mint.unwrap(), &action.market)
+                - market.fees.pending_creator_fees
+                - market.fees.pending_staking_fees
+        );
+
+        // The curve trades again
+        testing_env.svm.change_payer("bob");
+
+        let result = testing_env.svm.execute_actions(&[&SwapAction::new(
+            &testing_env,
+            SwapType::Sell,
+            SwapAmountType::ExactInput,
+            BASE_AMOUNT,
+            0,
+            None,
+        )]);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn upgrade_market_twice() {
+        let (mut testing_env, _, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidMarket);
+    }
+
+    #[test]
+    fn upgrade_market_with_invalid_authority() {
+        let (mut testing_env, _, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
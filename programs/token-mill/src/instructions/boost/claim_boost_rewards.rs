@@ -0,0 +1,213 @@
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillBoostRewardsClaimEvent,
+    manager::token_manager::transfer_from_pda,
+    state::{BoostPool, BoostPosition, Market},
+    MARKET_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimBoostRewards<'info> {
+    #[account(mut, has_one = quote_token_mint @ TokenMillError::InvalidMintAccount)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub boost_pool: Account<'info, BoostPool>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = user @ TokenMillError::InvalidAuthority
+    )]
+    pub boost_position: Account<'info, BoostPosition>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = quote_token_program
+    )]
+    pub user_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<ClaimBoostRewards>) -> Result<()> {
+    let boost_pool = &mut ctx.accounts.boost_pool;
+    let boost_position = &mut ctx.accounts.boost_position;
+
+    let acc_reward_amount_per_weight = boost_pool.update(Clock::get()?.unix_timestamp)?;
+    let rewards = boost_position.accrue_rewards(acc_reward_amount_per_weight)?;
+
+    let pending_rewards = boost_position.pending_rewards;
+    boost_position.pending_rewards = 0;
+
+    let (base_token_mint, market_bump) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        market.escrow_quote(rewards)?;
+        market.release_quote(pending_rewards)?;
+
+        (market.base_token_mint, market.bump)
+    };
+
+    let seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint.as_ref(),
+        &[market_bump],
+    ];
+
+    transfer_from_pda(
+        &ctx.accounts.quote_token_mint,
+        ctx.accounts.market.to_account_info(),
+        &ctx.accounts.market_quote_token_ata,
+        &ctx.accounts.user_quote_token_ata,
+        &ctx.accounts.quote_token_program,
+        pending_rewards,
+        &seeds,
+    )?;
+
+    emit_cpi!(TokenMillBoostRewardsClaimEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        amount_distributed: pending_rewards,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            ClaimBoostRewardsAction, CreateStakePositionAction, DepositAction, LockStakeAction,
+            SwapAction, TokenMillEnv,
+        },
+        make_address, SwapAmountType, SwapType,
+    };
+
+    use crate::constant::BOOST_MIN_LOCK_DURATION;
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+    const FUNDING_AMOUNT: u64 = 1_000_000_000;
+    const EMISSION_DURATION: i64 = 86_400;
+
+    #[test]
+    fn claim_boost_rewards() {
+        let mut testing_env = TokenMillEnv::default()
+            .with_staking(STAKE_AMOUNT)
+            .with_boost_pool(FUNDING_AMOUNT, EMISSION_DURATION);
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &DepositAction::new(&testing_env, STAKE_AMOUNT),
+                &LockStakeAction::new(&testing_env, STAKE_AMOUNT, BOOST_MIN_LOCK_DURATION),
+            ])
+            .unwrap();
+
+        testing_env.svm.warp(EMISSION_DURATION);
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("bob"));
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&ClaimBoostRewardsAction::new(&testing_env)]);
+
+        assert!(result.is_ok());
+
+        let balance_after = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("bob"));
+
+        // Bob is the only locker and receives the whole emission, up to rounding
+        assert!(balance_after - balance_before <= FUNDING_AMOUNT);
+        assert!(balance_after - balance_before >= FUNDING_AMOUNT - 1);
+    }
+
+    #[test]
+    fn claim_boost_rewards_lock_weighted() {
+        let mut testing_env = TokenMillEnv::default()
+            .with_staking(STAKE_AMOUNT)
+            .with_boost_pool(FUNDING_AMOUNT, EMISSION_DURATION);
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &DepositAction::new(&testing_env, STAKE_AMOUNT),
+                &LockStakeAction::new(&testing_env, STAKE_AMOUNT, BOOST_MIN_LOCK_DURATION),
+            ])
+            .unwrap();
+
+        // Carol locks the same amount for twice as long
+        let carol = testing_env.svm.change_payer("carol");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                SwapAction::new(
+                    &testing_env,
+                    SwapType::Buy,
+                    SwapAmountType::ExactOutput,
+                    STAKE_AMOUNT,
+                    u64::MAX,
+                    None,
+                )
+                .with_user(carol),
+                CreateStakePositionAction::new(&testing_env).with_user(carol),
+                DepositAction::new(&testing_env, STAKE_AMOUNT).with_user(carol),
+                LockStakeAction::new(&testing_env, STAKE_AMOUNT, 2 * BOOST_MIN_LOCK_DURATION)
+                    .with_user(carol),
+            ])
+            .unwrap();
+
+        testing_env.svm.warp(EMISSION_DURATION);
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let bob_balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("bob"));
+        let carol_balance_before = testing_env.svm.get_balance(&quote_token_mint, &carol);
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &ClaimBoostRewardsAction::new(&testing_env),
+                ClaimBoostRewardsAction::new(&testing_env).with_user(carol),
+            ])
+            .unwrap();
+
+        let bob_rewards = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("bob"))
+            - bob_balance_before;
+        let carol_rewards =
+            testing_env.svm.get_balance(&quote_token_mint, &carol) - carol_balance_before;
+
+        assert!(bob_rewards + carol_rewards <= FUNDING_AMOUNT);
+        assert!(bob_rewards + carol_rewards >= FUNDING_AMOUNT - 2);
+        assert!(carol_rewards.abs_diff(2 * bob_rewards) <= 2);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    errors::TokenMillError,
+    state::{BoostPool, Market},
+    BOOST_POOL_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateBoostPool<'info> {
+    #[account(has_one = creator @ TokenMillError::InvalidAuthority)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + BoostPool::INIT_SPACE,
+        seeds = [BOOST_POOL_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub boost_pool: Account<'info, BoostPool>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateBoostPool>) -> Result<()> {
+    let boost_pool = &mut ctx.accounts.boost_pool;
+    let market = &ctx.accounts.market;
+
+    boost_pool.initialize(market.key(), Clock::get()?.unix_timestamp)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::actions::token_mill::{
+        tm_parse_error, CreateBoostPoolAction, TokenMillEnv,
+    };
+
+    use crate::{errors::TokenMillError, BoostPool};
+
+    #[test]
+    fn create_boost_pool() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        let create_boost_pool_action = CreateBoostPoolAction::new(&testing_env);
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&create_boost_pool_action]);
+
+        assert!(result.is_ok());
+
+        let boost_pool = testing_env
+            .svm
+            .get_parsed_account::<BoostPool>(&create_boost_pool_action.boost_pool);
+
+        assert_eq!(boost_pool.market, testing_env.market);
+        assert_eq!(boost_pool.reward_remaining, 0);
+    }
+
+    #[test]
+    fn create_boost_pool_invalid_authority() {
+        let mut testing_env = TokenMillEnv::default();
+
+        let mut create_boost_pool_action = CreateBoostPoolAction::new(&testing_env);
+        create_boost_pool_action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&create_boost_pool_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
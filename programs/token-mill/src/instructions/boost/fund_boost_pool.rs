@@ -0,0 +1,150 @@
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillBoostPoolFundingEvent,
+    manager::token_manager::transfer_from_eoa,
+    state::{BoostPool, Market},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FundBoostPool<'info> {
+    #[account(
+        has_one = creator @ TokenMillError::InvalidAuthority,
+        has_one = quote_token_mint @ TokenMillError::InvalidQuoteTokenMint
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub boost_pool: Account<'info, BoostPool>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = quote_token_program
+    )]
+    pub creator_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub creator: Signer<'info>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Adds `amount` to the pool and restarts the emission of all the remaining rewards over `duration`
+pub fn handler(ctx: Context<FundBoostPool>, amount: u64, duration: i64) -> Result<()> {
+    require!(duration > 0, TokenMillError::InvalidEmissionDuration);
+
+    let boost_pool = &mut ctx.accounts.boost_pool;
+
+    require!(
+        amount > 0 || boost_pool.reward_remaining > 0,
+        TokenMillError::InvalidAmount
+    );
+
+    let current_time = Clock::get()?.unix_timestamp;
+
+    boost_pool.update(current_time)?;
+    boost_pool.fund(amount, current_time, duration)?;
+
+    transfer_from_eoa(
+        &ctx.accounts.quote_token_mint,
+        &ctx.accounts.creator,
+        &ctx.accounts.creator_quote_token_ata,
+        &ctx.accounts.market_quote_token_ata,
+        &ctx.accounts.quote_token_program,
+        amount,
+    )?;
+
+    emit_cpi!(TokenMillBoostPoolFundingEvent {
+        market: ctx.accounts.market.key(),
+        amount,
+        reward_remaining: boost_pool.reward_remaining,
+        emission_end: boost_pool.emission_end,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateBoostPoolAction, FundBoostPoolAction, TokenMillEnv,
+        },
+        make_address,
+    };
+
+    use crate::{errors::TokenMillError, BoostPool};
+
+    const FUNDING_AMOUNT: u64 = 1_000_000_000;
+    const EMISSION_DURATION: i64 = 86_400;
+
+    #[test]
+    fn fund_boost_pool() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        let create_boost_pool_action = CreateBoostPoolAction::new(&testing_env);
+        let fund_boost_pool_action =
+            FundBoostPoolAction::new(&testing_env, FUNDING_AMOUNT, EMISSION_DURATION);
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("alice"));
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&create_boost_pool_action, &fund_boost_pool_action]);
+
+        assert!(result.is_ok());
+
+        let balance_after = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("alice"));
+
+        assert_eq!(balance_before - balance_after, FUNDING_AMOUNT);
+
+        let boost_pool = testing_env
+            .svm
+            .get_parsed_account::<BoostPool>(&create_boost_pool_action.boost_pool);
+
+        assert_eq!(boost_pool.reward_remaining, FUNDING_AMOUNT);
+        assert_eq!(
+            boost_pool.emission_end,
+            testing_env.svm.get_clock().unix_timestamp + EMISSION_DURATION
+        );
+    }
+
+    #[test]
+    fn fund_boost_pool_invalid_duration() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        let create_boost_pool_action = CreateBoostPoolAction::new(&testing_env);
+        let fund_boost_pool_action = FundBoostPoolAction::new(&testing_env, FUNDING_AMOUNT, 0);
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&create_boost_pool_action, &fund_boost_pool_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidEmissionDuration
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    constant::{BOOST_MIN_LOCK_DURATION, MAX_LOCK_DURATION},
+    errors::TokenMillError,
+    events::TokenMillStakeLockEvent,
+    state::{BoostPool, BoostPosition, Market, StakePosition},
+    BOOST_POSITION_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct LockStake<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub boost_pool: Account<'info, BoostPool>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = user @ TokenMillError::InvalidAuthority
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + BoostPosition::INIT_SPACE,
+        seeds = [BOOST_POSITION_PDA_SEED.as_bytes(), market.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub boost_position: Account<'info, BoostPosition>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Locks `amount` of the user's stake for `duration`, weighted by the lock duration relative to the top lock tier.
+/// A position holds a single lock at a time, which has to be unlocked before locking again.
+pub fn handler(ctx: Context<LockStake>, amount: u64, duration: i64) -> Result<()> {
+    require!(
+        (BOOST_MIN_LOCK_DURATION..=MAX_LOCK_DURATION).contains(&duration),
+        TokenMillError::InvalidLockDuration
+    );
+
+    let boost_pool = &mut ctx.accounts.boost_pool;
+    let boost_position = &mut ctx.accounts.boost_position;
+    let stake_position = &mut ctx.accounts.stake_position;
+
+    require!(!boost_position.is_locked(), TokenMillError::StakeLocked);
+    require!(amount > 0, TokenMillError::InvalidAmount);
+    require_gte!(
+        stake_position.unlocked_amount(),
+        amount,
+        TokenMillError::InsufficientStakeAmount
+    );
+
+    let current_time = Clock::get()?.unix_timestamp;
+
+    if boost_position.market == Pubkey::default() {
+        boost_position.initialize(ctx.accounts.market.key(), ctx.accounts.user.key())?;
+    }
+
+    let acc_reward_amount_per_weight = boost_pool.update(current_time)?;
+    let rewards = boost_position.accrue_rewards(acc_reward_amount_per_weight)?;
+
+    ctx.accounts.market.load_mut()?.escrow_quote(rewards)?;
+
+    let weight = u64::try_from(
+        u128::from(amount) * u128::try_from(duration)? / u128::try_from(BOOST_MIN_LOCK_DURATION)?,
+    )?;
+    let lock_end = current_time + duration;
+
+    boost_position.amount_locked = amount;
+    boost_position.lock_end = lock_end;
+    boost_position.weight = weight;
+    boost_pool.total_weight += weight;
+    stake_position.amount_locked += amount;
+
+    emit_cpi!(TokenMillStakeLockEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        amount,
+        lock_end,
+        weight,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::actions::token_mill::{
+        tm_parse_error, DepositAction, LockStakeAction, TokenMillEnv, WithdrawAction,
+    };
+
+    use crate::{
+        constant::{BOOST_MIN_LOCK_DURATION, MAX_LOCK_DURATION},
+        errors::TokenMillError,
+        BoostPool, BoostPosition, StakePosition,
+    };
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    fn setup() -> TokenMillEnv {
+        let mut testing_env = TokenMillEnv::default()
+            .with_staking(STAKE_AMOUNT)
+            .with_boost_pool(1_000_000_000, 86_400);
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+
+        testing_env
+    }
+
+    #[test]
+    fn lock_stake() {
+        let mut testing_env = setup();
+
+        let lock_stake_action =
+            LockStakeAction::new(&testing_env, STAKE_AMOUNT / 2, MAX_LOCK_DURATION);
+
+        let result = testing_env.svm.execute_actions(&[&lock_stake_action]);
+
+        assert!(result.is_ok());
+
+        let boost_position = testing_env
+            .svm
+            .get_parsed_account::<BoostPosition>(&lock_stake_action.boost_position);
+        let expected_weight = u64::try_from(
+            u128::from(STAKE_AMOUNT / 2) * MAX_LOCK_DURATION as u128
+                / BOOST_MIN_LOCK_DURATION as u128,
+        )
+        .unwrap();
+
+        assert_eq!(boost_position.amount_locked, STAKE_AMOUNT / 2);
+        assert_eq!(boost_position.weight, expected_weight);
+        assert_eq!(
+            boost_position.lock_end,
+            testing_env.svm.get_clock().unix_timestamp + MAX_LOCK_DURATION
+        );
+
+        let boost_pool = testing_env
+            .svm
+            .get_parsed_account::<BoostPool>(&lock_stake_action.boost_pool);
+        assert_eq!(boost_pool.total_weight, expected_weight);
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&lock_stake_action.stake_position);
+        assert_eq!(stake_position.amount_locked, STAKE_AMOUNT / 2);
+
+        // Only the unlocked part of the stake can be withdrawn
+        let result = testing_env
+            .svm
+            .execute_actions(&[&WithdrawAction::new(&testing_env, STAKE_AMOUNT)]);
+
+        assert_eq!(tm_parse_error(result).unwrap(), TokenMillError::StakeLocked);
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&WithdrawAction::new(&testing_env, STAKE_AMOUNT / 2)]);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn lock_stake_below_top_tier() {
+        let mut testing_env = setup();
+
+        let lock_stake_action =
+            LockStakeAction::new(&testing_env, STAKE_AMOUNT, BOOST_MIN_LOCK_DURATION - 1);
+
+        let result = testing_env.svm.execute_actions(&[&lock_stake_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidLockDuration
+        );
+    }
+
+    #[test]
+    fn lock_stake_twice() {
+        let mut testing_env = setup();
+
+        let lock_stake_action =
+            LockStakeAction::new(&testing_env, STAKE_AMOUNT / 2, BOOST_MIN_LOCK_DURATION);
+
+        testing_env
+            .svm
+            .execute_actions(&[&lock_stake_action])
+            .unwrap();
+
+        testing_env.svm.warp(1);
+
+        let result = testing_env.svm.execute_actions(&[&lock_stake_action]);
+
+        assert_eq!(tm_parse_error(result).unwrap(), TokenMillError::StakeLocked);
+    }
+}
@@ -0,0 +1,11 @@
+pub mod claim_boost_rewards;
+pub mod create_boost_pool;
+pub mod fund_boost_pool;
+pub mod lock_stake;
+pub mod unlock_stake;
+
+pub use claim_boost_rewards::*;
+pub use create_boost_pool::*;
+pub use fund_boost_pool::*;
+pub use lock_stake::*;
+pub use unlock_stake::*;
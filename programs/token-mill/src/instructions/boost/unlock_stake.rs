@@ -0,0 +1,133 @@
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillStakeUnlockEvent,
+    state::{BoostPool, BoostPosition, Market, StakePosition},
+};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UnlockStake<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub boost_pool: Account<'info, BoostPool>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        constraint = stake_position.user == boost_position.user @ TokenMillError::InvalidStakePosition
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub boost_position: Account<'info, BoostPosition>,
+}
+
+/// Releases an expired lock. Permissionless, so that expired locks stop earning boost rewards
+/// as soon as anyone unlocks them. The rewards accrued until then are kept by the position.
+pub fn handler(ctx: Context<UnlockStake>) -> Result<()> {
+    let boost_pool = &mut ctx.accounts.boost_pool;
+    let boost_position = &mut ctx.accounts.boost_position;
+    let stake_position = &mut ctx.accounts.stake_position;
+
+    require!(boost_position.is_locked(), TokenMillError::NothingLocked);
+
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(
+        current_time >= boost_position.lock_end,
+        TokenMillError::LockNotExpired
+    );
+
+    let acc_reward_amount_per_weight = boost_pool.update(current_time)?;
+    let rewards = boost_position.accrue_rewards(acc_reward_amount_per_weight)?;
+
+    ctx.accounts.market.load_mut()?.escrow_quote(rewards)?;
+
+    let amount = boost_position.amount_locked;
+
+    boost_pool.total_weight -= boost_position.weight;
+    stake_position.amount_locked -= amount;
+    boost_position.amount_locked = 0;
+    boost_position.weight = 0;
+
+    emit_cpi!(TokenMillStakeUnlockEvent {
+        market: ctx.accounts.market.key(),
+        user: boost_position.user,
+        amount,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::actions::token_mill::{
+        tm_parse_error, DepositAction, LockStakeAction, TokenMillEnv, UnlockStakeAction,
+        WithdrawAction,
+    };
+
+    use crate::{
+        constant::BOOST_MIN_LOCK_DURATION, errors::TokenMillError, BoostPool, StakePosition,
+    };
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    #[test]
+    fn unlock_stake() {
+        let mut testing_env = TokenMillEnv::default()
+            .with_staking(STAKE_AMOUNT)
+            .with_boost_pool(1_000_000_000, 86_400);
+
+        testing_env.svm.change_payer("bob");
+
+        let lock_stake_action =
+            LockStakeAction::new(&testing_env, STAKE_AMOUNT, BOOST_MIN_LOCK_DURATION);
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &DepositAction::new(&testing_env, STAKE_AMOUNT),
+                &lock_stake_action,
+            ])
+            .unwrap();
+
+        let unlock_stake_action = UnlockStakeAction::new(&testing_env);
+
+        let result = testing_env.svm.execute_actions(&[&unlock_stake_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::LockNotExpired
+        );
+
+        testing_env.svm.warp(BOOST_MIN_LOCK_DURATION);
+
+        // Anyone can unlock an expired lock
+        testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&unlock_stake_action]);
+
+        assert!(result.is_ok());
+
+        let boost_pool = testing_env
+            .svm
+            .get_parsed_account::<BoostPool>(&lock_stake_action.boost_pool);
+        assert_eq!(boost_pool.total_weight, 0);
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&lock_stake_action.stake_position);
+        assert_eq!(stake_position.amount_locked, 0);
+
+        testing_env.svm.change_payer("bob");
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&WithdrawAction::new(&testing_env, STAKE_AMOUNT)]);
+
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+
+use crate::{constant::MAX_BPS, errors::TokenMillError, manager::swap_manager::SwapType};
+
+use super::swap_route::{execute_leg, RouteLeg, SwapRoute};
+
+/// Splits `amount` quote tokens across several markets sharing the same quote token, in bps of `amount` given by `weights`.
+/// Each market is passed in the remaining accounts as a `RouteLeg`, in the order of `weights`.
+/// Every buy is fully filled and only the total base amount bought is checked against `min_amount_out`.
+/// Returns the quote amount spent and the total base amount bought.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+    amount: u64,
+    weights: Vec<u16>,
+    min_amount_out: u64,
+) -> Result<(u64, u64)> {
+    if amount == 0 {
+        return Err(TokenMillError::InvalidAmount.into());
+    }
+
+    require!(
+        !weights.is_empty()
+            && weights.iter().all(|weight| *weight > 0)
+            && weights.iter().map(|weight| u64::from(*weight)).sum::<u64>() <= MAX_BPS,
+        TokenMillError::InvalidBasketWeights
+    );
+    require!(
+        ctx.remaining_accounts.len() == weights.len() * RouteLeg::ACCOUNTS_LEN,
+        TokenMillError::InvalidRouteAccounts
+    );
+
+    let legs = ctx
+        .remaining_accounts
+        .chunks(RouteLeg::ACCOUNTS_LEN)
+        .map(|accounts| RouteLeg::try_from_accounts(accounts, ctx.accounts))
+        .collect::<Result<Vec<_>>>()?;
+
+    for (i, leg) in legs.iter().enumerate() {
+        require!(
+            legs[..i]
+                .iter()
+                .all(|other_leg| other_leg.market.key() != leg.market.key()),
+            TokenMillError::InvalidRouteAccounts
+        );
+    }
+
+    let mut quote_amount_in = 0;
+    let mut base_amount_out = 0;
+
+    for (leg, weight) in legs.iter().zip(weights) {
+        let leg_amount =
+            u64::try_from(u128::from(amount) * u128::from(weight) / u128::from(MAX_BPS))?;
+
+        require!(leg_amount > 0, TokenMillError::InvalidAmount);
+
+        let (base_amount, quote_amount) = execute_leg(&ctx, leg, SwapType::Buy, leg_amount)?;
+
+        quote_amount_in += quote_amount;
+        base_amount_out += base_amount;
+    }
+
+    if base_amount_out < min_amount_out {
+        return Err(TokenMillError::AmountThresholdNotMet.into());
+    }
+
+    Ok((quote_amount_in, base_amount_out))
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AnchorDeserialize;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, BuyBasketAction, TokenMillEnv, DEFAULT_TOTAL_SUPPLY,
+        },
+        make_address, TokenMillError,
+    };
+
+    const AMOUNT: u64 = 1_000_000_000;
+
+    fn setup_env() -> (TokenMillEnv, BuyBasketAction) {
+        let testing_env = TokenMillEnv::default();
+        let base_token_mint_a = testing_env.base_token_mint.unwrap();
+
+        let base_token_mint_b = make_address("base_token_mint_b");
+        let mut testing_env = testing_env.with_market(base_token_mint_b, DEFAULT_TOTAL_SUPPLY);
+
+        testing_env.svm.change_payer("bob");
+
+        let action = BuyBasketAction::new(
+            &testing_env,
+            vec![base_token_mint_a, base_token_mint_b],
+            vec![6_000, 4_000],
+            AMOUNT,
+            0,
+        );
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn buy_basket() {
+        let (mut testing_env, action) = setup_env();
+
+        let bob = make_address("bob");
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let quote_balance_before = testing_env.svm.get_balance(&quote_token_mint, &bob);
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let (quote_amount_in, base_amount_out) =
+            <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        assert_eq!(quote_amount_in, AMOUNT);
+        assert_eq!(
+            testing_env.svm.get_balance(&quote_token_mint, &bob),
+            quote_balance_before - AMOUNT
+        );
+
+        let base_balance_a = testing_env
+            .svm
+            .get_balance(&action.base_token_mints[0], &bob);
+        let base_balance_b = testing_env
+            .svm
+            .get_balance(&action.base_token_mints[1], &bob);
+
+        // Both markets share the same curve, the heavier weight buys more
+        assert!(base_balance_a > base_balance_b);
+        assert_eq!(base_balance_a + base_balance_b, base_amount_out);
+    }
+
+    #[test]
+    fn buy_basket_with_invalid_weights() {
+        let (mut testing_env, mut action) = setup_env();
+
+        for weights in [vec![6_000, 4_001], vec![10_000, 0], vec![]] {
+            action.weights = weights;
+
+            let result = testing_env.svm.execute_actions(&[&action]);
+
+            assert_eq!(
+                tm_parse_error(result).unwrap(),
+                TokenMillError::InvalidBasketWeights
+            );
+        }
+    }
+
+    #[test]
+    fn buy_basket_with_duplicate_market() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.base_token_mints[1] = action.base_token_mints[0];
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidRouteAccounts
+        );
+    }
+
+    #[test]
+    fn buy_basket_below_min_amount_out() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.min_amount_out = u64::MAX;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::AmountThresholdNotMet
+        );
+    }
+}
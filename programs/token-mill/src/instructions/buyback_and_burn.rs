@@ -0,0 +1,265 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillBuybackAndBurnEvent,
+    manager::{oracle_manager, price_feed_manager, swap_manager, token_manager::burn_from_pda},
+    state::Market,
+    TokenMillConfig, MARKET_ORACLE_PDA_SEED, MARKET_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BuybackAndBurn<'info> {
+    #[account(constraint = !config.swaps_paused @ TokenMillError::SwapsPaused)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        mut,
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut)]
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pyth price update of the quote token, required if the market registered one. Checked in the handler
+    pub quote_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: TWAP oracle of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [MARKET_ORACLE_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub market_oracle: UncheckedAccount<'info>,
+
+    pub user: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Buys base tokens from the curve with the burn fees accrued by the market and burns them. Permissionless.
+/// The burned supply stays at the bottom of the curve, so the buyback lifts the price like any buy, and the quote amount
+/// paid stays in the curve reserve until `close_market` sweeps it to the creator.
+pub fn handler(ctx: Context<BuybackAndBurn>) -> Result<(u64, u64)> {
+    let (base_amount, quote_amount, market_bump) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        price_feed_manager::check_quote_peg(market, ctx.accounts.quote_price_feed.as_deref())?;
+
+        let (base_amount, quote_amount) = swap_manager::buyback_and_burn(market, &Clock::get()?)?;
+
+        oracle_manager::record_prices(&ctx.accounts.market_oracle, market)?;
+
+        (base_amount, quote_amount, market.bump)
+    };
+
+    let base_token_mint_key = ctx.accounts.base_token_mint.key();
+    let seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint_key.as_ref(),
+        &[market_bump],
+    ];
+
+    burn_from_pda(
+        &ctx.accounts.base_token_mint,
+        ctx.accounts.market.to_account_info(),
+        &ctx.accounts.market_base_token_ata,
+        &ctx.accounts.base_token_program,
+        base_amount,
+        &seeds,
+    )?;
+
+    emit_cpi!(TokenMillBuybackAndBurnEvent {
+        market: ctx.accounts.market.key(),
+        base_amount,
+        quote_amount,
+    });
+
+    Ok((base_amount, quote_amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, BuybackAndBurnAction, CloseMarketAction, ProposeCloseMarketAction,
+            SetBurnFeeShareAction, SwapAction, TokenMillEnv,
+        },
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const BURN_FEE_SHARE: u16 = 2_000;
+    const BASE_AMOUNT: u64 = 100_000_000_000;
+
+    fn setup_env() -> (TokenMillEnv, BuybackAndBurnAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetBurnFeeShareAction::new(&testing_env, BURN_FEE_SHARE)])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                BASE_AMOUNT,
+                u64::MAX,
+                None,
+            )])
+            .unwrap();
+
+        let action = BuybackAndBurnAction::new(&testing_env);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn buyback_and_burn() {
+        let (mut testing_env, action) = setup_env();
+
+        let market_before = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+        let market_base_balance_before = testing_env
+            .svm
+            .get_balance(&action.base_token_mint, &testing_env.market);
+
+        assert!(market_before.fees.pending_burn_fees > 0);
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let market_after = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+        let burned_amount = market_base_balance_before
+            - testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &testing_env.market);
+
+        assert!(burned_amount > 0);
+        assert_eq!(
+            market_after.total_supply,
+            market_before.total_supply - burned_amount
+        );
+        assert_eq!(
+            market_after.base_reserve,
+            market_before.base_reserve - burned_amount
+        );
+        assert_eq!(
+            market_after.circulating_supply(),
+            market_before.circulating_supply()
+        );
+        // The burned supply stays on the curve, the buyback lifting the price
+        assert_eq!(market_after.burned_supply, burned_amount);
+        assert!(
+            market_after.get_spot_prices().unwrap().0 > market_before.get_spot_prices().unwrap().0
+        );
+        assert!(market_after.quote_raised > market_before.quote_raised);
+        // Only the fee of the buyback and its rounding are left for the next one
+        assert!(market_after.fees.pending_burn_fees < market_before.fees.pending_burn_fees);
+    }
+
+    #[test]
+    fn close_market_after_buyback_and_burn() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        // Selling back the circulating supply leaves the quote amount paid for the burned supply in the market
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Sell,
+                SwapAmountType::ExactInput,
+                BASE_AMOUNT,
+                0,
+                None,
+            )])
+            .unwrap();
+
+        let close_action = CloseMarketAction::new(&testing_env);
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+        let quote_reserve = testing_env
+            .svm
+            .get_balance(&close_action.quote_token_mint, &testing_env.market);
+        let creator_quote_balance = testing_env
+            .svm
+            .get_balance(&close_action.quote_token_mint, &close_action.signer);
+
+        assert_eq!(market.circulating_supply(), 0);
+        assert!(
+            quote_reserve
+                > market.fees.pending_creator_fees
+                    + market.fees.pending_staking_fees
+                    + market.fees.pending_burn_fees
+                    + market.swap_count
+                    + 1
+        );
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&ProposeCloseMarketAction::new(&testing_env), &close_action])
+            .unwrap();
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&close_action.quote_token_mint, &close_action.signer),
+            creator_quote_balance + quote_reserve
+        );
+    }
+
+    #[test]
+    fn buyback_and_burn_without_burn_fees() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                BASE_AMOUNT,
+                u64::MAX,
+                None,
+            )])
+            .unwrap();
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&BuybackAndBurnAction::new(&testing_env)]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAmount
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillChangeLogCreationEvent,
+    state::{ChangeLog, Market, TokenMillConfig},
+    CHANGE_LOG_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateChangeLog<'info> {
+    /// CHECK: Config or market whose parameter changes will be recorded, checked in the handler
+    #[account(owner = crate::ID @ TokenMillError::InvalidChangeLogTarget)]
+    pub target: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [CHANGE_LOG_PDA_SEED.as_bytes(), target.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + ChangeLog::INIT_SPACE
+    )]
+    pub change_log: AccountLoader<'info, ChangeLog>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Permissionless, so that holders don't depend on the authority or the creator to enable the audit trail
+pub fn handler(ctx: Context<CreateChangeLog>) -> Result<()> {
+    {
+        let data = ctx.accounts.target.try_borrow_data()?;
+
+        require!(
+            data.len() >= 8
+                && (data[..8] == TokenMillConfig::DISCRIMINATOR
+                    || data[..8] == Market::DISCRIMINATOR),
+            TokenMillError::InvalidChangeLogTarget
+        );
+    }
+
+    let change_log = &mut ctx.accounts.change_log.load_init()?;
+
+    change_log.initialize(ctx.accounts.target.key())?;
+
+    emit_cpi!(TokenMillChangeLogCreationEvent {
+        change_log: ctx.accounts.change_log.key(),
+        target: ctx.accounts.target.key(),
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{state::CHANGE_LOG_LENGTH, ChangeLog, ChangeLogParameter};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, AcceptFeeRecipientAction, CreateChangeLogAction,
+            OverrideSellPauseAction, PauseSellsAction, ProposeFeeRecipientAction, TokenMillEnv,
+            UpdateFeeDistributionIntervalAction,
+        },
+        make_address, TokenMillError,
+    };
+
+    #[test]
+    fn record_config_changes() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = CreateChangeLogAction::new(testing_env.config);
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env
+            .svm
+            .execute_actions(&[&ProposeFeeRecipientAction::new(Some(make_address("carol")))])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        testing_env
+            .svm
+            .execute_actions(&[&AcceptFeeRecipientAction::new(make_address("carol"))])
+            .unwrap();
+
+        let change_log = testing_env
+            .svm
+            .get_parsed_account::<ChangeLog>(&action.change_log);
+
+        assert_eq!(change_log.target, testing_env.config);
+        assert_eq!(change_log.count, 2);
+
+        let entry = change_log.get_entry(1).unwrap();
+
+        assert_eq!(
+            entry.parameter,
+            ChangeLogParameter::PendingProtocolFeeRecipient as u8
+        );
+
+        let entry = change_log.get_entry(0).unwrap();
+
+        assert_eq!(
+            entry.parameter,
+            ChangeLogParameter::ProtocolFeeRecipient as u8
+        );
+        assert_eq!(entry.old_value, make_address("dave").to_bytes());
+        assert_eq!(entry.new_value, make_address("carol").to_bytes());
+    }
+
+    #[test]
+    fn record_market_changes() {
+        let mut testing_env = TokenMillEnv::default();
+
+        let action = CreateChangeLogAction::new(testing_env.market);
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&PauseSellsAction::new(3_600)])
+            .unwrap();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&OverrideSellPauseAction::new()])
+            .unwrap();
+
+        let change_log = testing_env
+            .svm
+            .get_parsed_account::<ChangeLog>(&action.change_log);
+
+        assert_eq!(change_log.count, 2);
+        assert_eq!(
+            change_log.get_entry(0).unwrap().parameter,
+            ChangeLogParameter::SellPauseOverride as u8
+        );
+        assert_eq!(
+            change_log.get_entry(1).unwrap().parameter,
+            ChangeLogParameter::SellPause as u8
+        );
+    }
+
+    #[test]
+    fn change_log_wraps_around() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = CreateChangeLogAction::new(testing_env.config);
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let changes = CHANGE_LOG_LENGTH as u16 + 2;
+
+        for interval in 1..=changes {
+            testing_env
+                .svm
+                .execute_actions(&[&UpdateFeeDistributionIntervalAction::new(interval)])
+                .unwrap();
+        }
+
+        let change_log = testing_env
+            .svm
+            .get_parsed_account::<ChangeLog>(&action.change_log);
+
+        assert_eq!(change_log.count, u64::from(changes));
+
+        let latest_entry = change_log.get_entry(0).unwrap();
+        let oldest_entry = change_log.get_entry(CHANGE_LOG_LENGTH as u64 - 1).unwrap();
+
+        assert_eq!(latest_entry.new_value[..2], changes.to_le_bytes());
+        assert_eq!(oldest_entry.new_value[..2], 3u16.to_le_bytes());
+        assert!(change_log.get_entry(CHANGE_LOG_LENGTH as u64).is_none());
+    }
+
+    #[test]
+    fn create_change_log_with_invalid_target() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let action = CreateChangeLogAction::new(make_address("mallory"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidChangeLogTarget);
+    }
+}
@@ -6,7 +6,8 @@ use anchor_spl::{
     associated_token::AssociatedToken,
     token_2022::{spl_token_2022::instruction::AuthorityType, Token2022},
     token_interface::{
-        self, token_metadata_initialize, Mint, TokenAccount, TokenMetadataInitialize,
+        self, token_metadata_initialize, Mint, TokenAccount, TokenInterface,
+        TokenMetadataInitialize,
     },
 };
 
@@ -14,9 +15,13 @@ use crate::{
     constant::{MAX_BPS, MILL_TOKEN_DECIMALS},
     errors::TokenMillError,
     events::TokenMillMarketCreationEvent,
-    manager::token_manager::check_mint_extensions,
-    state::{Market, TokenMillConfig},
-    QuoteTokenBadge, QuoteTokenBadgeStatus, MARKET_PDA_SEED, QUOTE_TOKEN_BADGE_PDA_SEED,
+    manager::{
+        metadata_manager::{self, TOKEN_METADATA_PROGRAM_ID},
+        token_manager::check_mint_extensions,
+    },
+    state::{Market, ProtocolStats, TokenMillConfig},
+    QuoteTokenBadge, QuoteTokenBadgeStatus, MARKET_PDA_SEED, PROTOCOL_STATS_PDA_SEED,
+    QUOTE_TOKEN_BADGE_PDA_SEED,
 };
 
 #[event_cpi]
@@ -66,11 +71,54 @@ pub struct CreateMarket<'info> {
 
     pub quote_token_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    // The market and creator ATAs are created here so that the prices and the creator's first buy
+    // can be bundled in the same transaction. ATA creation being permissionless, they may already exist
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_program
+    )]
+    pub creator_base_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            PROTOCOL_STATS_PDA_SEED.as_bytes(),
+            config.key().as_ref(),
+            quote_token_mint.key().as_ref(),
+        ],
+        bump = protocol_stats.bump,
+    )]
+    pub protocol_stats: Option<Box<Account<'info, ProtocolStats>>>,
+
     #[account(mut)]
     pub creator: Signer<'info>,
 
+    /// Launchpad co-signing the market parameters, recorded on the market
+    pub launchpad: Option<Signer<'info>>,
+
+    /// CHECK: Metaplex metadata account of the base mint, created when passed. Checked against the mint
+    #[account(mut)]
+    pub metaplex_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Required along with the Metaplex metadata account
+    #[account(address = TOKEN_METADATA_PROGRAM_ID @ TokenMillError::InvalidMetadataAccount)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token2022>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
@@ -85,12 +133,21 @@ pub fn handler(
 ) -> Result<()> {
     let config = &ctx.accounts.config;
 
+    let quote_token_mint = ctx.accounts.quote_token_mint.key();
+    let protocol_fee_share = config.get_protocol_fee_share(&quote_token_mint);
+    let (creator_fee_share, staking_fee_share) =
+        config.get_market_fee_shares(&quote_token_mint, creator_fee_share, staking_fee_share);
+
     require_eq!(
-        creator_fee_share + staking_fee_share + config.default_protocol_fee_share,
+        creator_fee_share + staking_fee_share + protocol_fee_share,
         MAX_BPS as u16,
         TokenMillError::InvalidFeeShare
     );
 
+    // Staking fees could never be claimed without the staking instructions
+    #[cfg(not(feature = "staking"))]
+    require!(staking_fee_share == 0, TokenMillError::InvalidFeeShare);
+
     require!(
         check_mint_extensions(&ctx.accounts.quote_token_mint)?,
         TokenMillError::UnsupportedTokenMint
@@ -104,12 +161,22 @@ pub fn handler(
             config.key(),
             ctx.accounts.creator.key(),
             ctx.accounts.base_token_mint.key(),
-            ctx.accounts.quote_token_mint.key(),
+            quote_token_mint,
             ctx.accounts.quote_token_mint.decimals,
             total_supply,
             creator_fee_share,
             staking_fee_share,
         )?;
+
+        market.creation_time = Clock::get()?.unix_timestamp;
+
+        if let Some(launchpad) = &ctx.accounts.launchpad {
+            market.launchpad = launchpad.key();
+        }
+    }
+
+    if let Some(protocol_stats) = &mut ctx.accounts.protocol_stats {
+        protocol_stats.markets_created += 1;
     }
 
     let base_token_mint_key = ctx.accounts.base_token_mint.key();
@@ -119,6 +186,28 @@ pub fn handler(
         &[ctx.bumps.market],
     ];
 
+    // The Metaplex metadata mirrors the Token-2022 one, for the wallets and indexers not reading the extension
+    if let Some(metaplex_metadata) = &ctx.accounts.metaplex_metadata {
+        let token_metadata_program = ctx
+            .accounts
+            .token_metadata_program
+            .as_ref()
+            .ok_or(TokenMillError::InvalidMetadataAccount)?;
+
+        metadata_manager::create_metadata_account(
+            token_metadata_program,
+            metaplex_metadata,
+            &ctx.accounts.base_token_mint.to_account_info(),
+            &ctx.accounts.market.to_account_info(),
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            name.clone(),
+            symbol.clone(),
+            uri.clone(),
+            &market_seeds,
+        )?;
+    }
+
     ctx.accounts
         .initialize_token_metadata(name, symbol, uri, &market_seeds)?;
 
@@ -129,11 +218,16 @@ pub fn handler(
         config: ctx.accounts.config.key(),
         market: ctx.accounts.market.key(),
         creator: ctx.accounts.creator.key(),
+        launchpad: ctx
+            .accounts
+            .launchpad
+            .as_ref()
+            .map(|launchpad| launchpad.key()),
         base_token_mint: ctx.accounts.base_token_mint.key(),
-        quote_token_mint: ctx.accounts.quote_token_mint.key(),
+        quote_token_mint,
         total_supply,
-        protocol_fee_share: config.default_protocol_fee_share,
-        referral_fee_share: config.referral_fee_share,
+        protocol_fee_share,
+        referral_fee_share: config.get_referral_fee_share(&quote_token_mint),
         creator_fee_share,
         staking_fee_share,
     });
@@ -229,15 +323,19 @@ impl<'info> CreateMarket<'info> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        constant::{INTERVAL_NUMBER, MAX_TOTAL_SUPPLY},
+        constant::{INTERVAL_NUMBER, MAX_QUOTE_TOKEN_DECIMALS, MAX_TOTAL_SUPPLY},
         Market,
     };
-    use joelana_test_utils::joelana_env::{
-        actions::token_mill::{
-            tm_parse_error, CreateMarketAction, CreateQuoteAssetBadgeAction, TokenMillEnv,
-            UpdateQuoteAssetBadgeAction, DEFAULT_TOTAL_SUPPLY,
+    use joelana_test_utils::{
+        joelana_env::{
+            actions::token_mill::{
+                tm_parse_error, CreateMarketAction, CreateQuoteAssetBadgeAction,
+                SetMarketPricesAction, SwapAction, TokenMillEnv, UpdateQuoteAssetBadgeAction,
+                DEFAULT_TOTAL_SUPPLY,
+            },
+            make_address, SwapAmountType, SwapType, TokenMillError, TokenType,
         },
-        TokenMillError, TokenType,
+        utils::token_mill::curve_generator::Curve,
     };
     use rstest::rstest;
 
@@ -266,7 +364,7 @@ mod tests {
     #[rstest]
     fn create_market(
         #[values(TokenType::Token, TokenType::Token2022)] token_type: TokenType,
-        #[values(6, 9)] quote_token_decimals: u8,
+        #[values(0, 6, 9, MAX_QUOTE_TOKEN_DECIMALS)] quote_token_decimals: u8,
     ) {
         let (mut testing_env, action) = setup_env(token_type, quote_token_decimals);
 
@@ -285,6 +383,102 @@ mod tests {
         assert_eq!(market.base_reserve, DEFAULT_TOTAL_SUPPLY);
     }
 
+    #[rstest]
+    fn create_market_with_unsupported_quote_token_decimals(
+        #[values(TokenType::Token, TokenType::Token2022)] token_type: TokenType,
+        #[values(MAX_QUOTE_TOKEN_DECIMALS + 1, 18)] quote_token_decimals: u8,
+    ) {
+        let (mut testing_env, action) = setup_env(token_type, quote_token_decimals);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::UnsupportedQuoteTokenDecimals
+        );
+    }
+
+    #[rstest]
+    fn create_market_set_prices_and_buy_in_one_transaction(
+        #[values(TokenType::Token, TokenType::Token2022)] token_type: TokenType,
+    ) {
+        let (mut testing_env, action) = setup_env(token_type, 9);
+
+        let set_prices_action = SetMarketPricesAction::new(Curve::default());
+
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000,
+            u64::MAX,
+            None,
+        );
+        swap_action.with_user(make_address("alice"));
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&action, &set_prices_action, &swap_action]);
+
+        assert!(result.is_ok());
+
+        testing_env
+            .svm
+            .tokens
+            .insert(action.base_token_mint, TokenType::Token2022);
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &make_address("alice")),
+            1_000_000_000
+        );
+    }
+
+    #[test]
+    fn create_market_with_launchpad() {
+        let (mut testing_env, mut action) = setup_env(TokenType::Token, 6);
+
+        action.with_launchpad(make_address("carol"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env.svm.get_parsed_account::<Market>(&action.market);
+
+        assert_eq!(market.launchpad, make_address("carol"));
+    }
+
+    // The Token Metadata program doesn't run in the test environment, only the checks preceding the CPI are covered
+    #[test]
+    fn create_market_with_invalid_metaplex_metadata() {
+        let (mut testing_env, mut action) = setup_env(TokenType::Token, 6);
+
+        action.with_metaplex_metadata().metaplex_metadata = make_address("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidMetadataAccount
+        );
+    }
+
+    #[test]
+    fn create_market_with_missing_token_metadata_program() {
+        let (mut testing_env, mut action) = setup_env(TokenType::Token, 6);
+
+        action.with_metaplex_metadata().token_metadata_program = crate::ID;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidMetadataAccount
+        );
+    }
+
     #[test]
     fn create_market_with_disabled_quote_asset_badge() {
         let (mut testing_env, mut action) = setup_env(TokenType::Token, 6);
@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    state::{Market, MarketOracle},
+    MARKET_ORACLE_PDA_SEED,
+};
+
+#[derive(Accounts)]
+pub struct CreateMarketOracle<'info> {
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        init,
+        seeds = [MARKET_ORACLE_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + MarketOracle::INIT_SPACE
+    )]
+    pub market_oracle: Account<'info, MarketOracle>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Permissionless, so that integrators can start observing the prices of any market.
+/// The oracle starts from the current spot prices, the prices have to be set.
+pub fn handler(ctx: Context<CreateMarketOracle>) -> Result<()> {
+    let market = ctx.accounts.market.load()?;
+
+    require!(market.are_prices_set(), TokenMillError::PricesNotSet);
+
+    let (ask_price, bid_price) = market.get_spot_prices()?;
+
+    ctx.accounts.market_oracle.initialize(
+        ctx.bumps.market_oracle,
+        ctx.accounts.market.key(),
+        Clock::get()?.unix_timestamp,
+        ask_price,
+        bid_price,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MarketOracle;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            market_oracle_address, tm_parse_error, CreateMarketAction, CreateMarketOracleAction,
+            SwapAction, TokenMillEnv,
+        },
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    #[test]
+    fn create_market_oracle() {
+        let mut testing_env = TokenMillEnv::default();
+
+        let action = CreateMarketOracleAction::new(&testing_env);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market_oracle = testing_env
+            .svm
+            .get_parsed_account::<MarketOracle>(&action.market_oracle);
+
+        assert_eq!(market_oracle.market, testing_env.market);
+        assert_eq!(market_oracle.cumulative_ask_price, 0);
+    }
+
+    #[test]
+    fn create_market_oracle_without_prices() {
+        let mut testing_env = TokenMillEnv::new().with_default_quote_token_mint();
+        testing_env.svm.change_payer("alice");
+
+        let mut create_market_action = CreateMarketAction::new(&testing_env);
+
+        testing_env
+            .svm
+            .execute_actions(&[create_market_action.no_badge()])
+            .unwrap();
+
+        let mut action = CreateMarketOracleAction::new(&testing_env);
+        action.market = create_market_action.market;
+        action.market_oracle = market_oracle_address(&action.market);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::PricesNotSet);
+    }
+
+    #[test]
+    fn track_market_oracle() {
+        let mut testing_env = TokenMillEnv::default();
+
+        let action = CreateMarketOracleAction::new(&testing_env);
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        testing_env.svm.warp(100);
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let market_oracle = testing_env
+            .svm
+            .get_parsed_account::<MarketOracle>(&action.market_oracle);
+
+        assert!(market_oracle.last_ask_price > 0);
+        assert!(market_oracle.last_bid_price > 0);
+        assert!(market_oracle.cumulative_ask_price > 0);
+        assert!(market_oracle.cumulative_bid_price > 0);
+    }
+}
@@ -0,0 +1,375 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constant::{MAX_BPS, MILL_TOKEN_DECIMALS},
+    errors::TokenMillError,
+    events::TokenMillMarketCreationEvent,
+    manager::token_manager::{check_mint_extensions, transfer_from_eoa},
+    state::{Market, ProtocolStats, TokenMillConfig},
+    QuoteTokenBadge, QuoteTokenBadgeStatus, MARKET_PDA_SEED, PROTOCOL_STATS_PDA_SEED,
+    QUOTE_TOKEN_BADGE_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateMarketWithExistingMint<'info> {
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        init,
+        seeds = [MARKET_PDA_SEED.as_bytes(), base_token_mint.key().as_ref()],
+        bump,
+        payer = creator,
+        space = 8 + Market::INIT_SPACE
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    /// The supply must be fixed and the market tokens must not be freezable
+    #[account(
+        mint::token_program = base_token_program,
+        constraint = base_token_mint.decimals == MILL_TOKEN_DECIMALS @ TokenMillError::UnsupportedTokenMint,
+        constraint = base_token_mint.mint_authority.is_none() @ TokenMillError::UnsupportedTokenMint,
+        constraint = base_token_mint.freeze_authority.is_none() @ TokenMillError::UnsupportedTokenMint,
+    )]
+    pub base_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = base_token_program
+    )]
+    pub creator_base_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            QUOTE_TOKEN_BADGE_PDA_SEED.as_bytes(),
+            config.key().as_ref(),
+            quote_token_mint.key().as_ref(),
+        ],
+        bump = quote_token_badge.bump,
+        constraint = quote_token_badge.status == QuoteTokenBadgeStatus::Enabled || creator.key() == config.authority @ TokenMillError::InvalidQuoteAssetBadge,
+    )]
+    pub quote_token_badge: Option<Account<'info, QuoteTokenBadge>>,
+
+    pub quote_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // Created here so that the prices and the creator's first buy can be bundled in the same transaction
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            PROTOCOL_STATS_PDA_SEED.as_bytes(),
+            config.key().as_ref(),
+            quote_token_mint.key().as_ref(),
+        ],
+        bump = protocol_stats.bump,
+    )]
+    pub protocol_stats: Option<Box<Account<'info, ProtocolStats>>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Launchpad co-signing the market parameters, recorded on the market
+    pub launchpad: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Wrap mode: sells an already existing token through the curve instead of minting a new one.
+/// The creator escrows `initial_deposit` into the market, the rest of `total_supply` can be deposited later
+/// with `deposit_additional_supply` and is only sellable once deposited.
+pub fn handler(
+    ctx: Context<CreateMarketWithExistingMint>,
+    total_supply: u64,
+    initial_deposit: u64,
+    creator_fee_share: u16,
+    staking_fee_share: u16,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+
+    let quote_token_mint = ctx.accounts.quote_token_mint.key();
+    let protocol_fee_share = config.get_protocol_fee_share(&quote_token_mint);
+    let (creator_fee_share, staking_fee_share) =
+        config.get_market_fee_shares(&quote_token_mint, creator_fee_share, staking_fee_share);
+
+    require_eq!(
+        creator_fee_share + staking_fee_share + protocol_fee_share,
+        MAX_BPS as u16,
+        TokenMillError::InvalidFeeShare
+    );
+
+    #[cfg(not(feature = "staking"))]
+    require!(staking_fee_share == 0, TokenMillError::InvalidFeeShare);
+
+    require!(
+        check_mint_extensions(&ctx.accounts.base_token_mint)?
+            && check_mint_extensions(&ctx.accounts.quote_token_mint)?,
+        TokenMillError::UnsupportedTokenMint
+    );
+
+    require!(
+        initial_deposit > 0 && initial_deposit <= total_supply,
+        TokenMillError::InvalidAmount
+    );
+
+    {
+        let mut market = ctx.accounts.market.load_init()?;
+
+        market.initialize(
+            ctx.bumps.market,
+            config.key(),
+            ctx.accounts.creator.key(),
+            ctx.accounts.base_token_mint.key(),
+            quote_token_mint,
+            ctx.accounts.quote_token_mint.decimals,
+            total_supply,
+            creator_fee_share,
+            staking_fee_share,
+        )?;
+
+        market.creation_time = Clock::get()?.unix_timestamp;
+
+        market.pending_supply = total_supply - initial_deposit;
+
+        if let Some(launchpad) = &ctx.accounts.launchpad {
+            market.launchpad = launchpad.key();
+        }
+    }
+
+    if let Some(protocol_stats) = &mut ctx.accounts.protocol_stats {
+        protocol_stats.markets_created += 1;
+    }
+
+    transfer_from_eoa(
+        &ctx.accounts.base_token_mint,
+        &ctx.accounts.creator,
+        &ctx.accounts.creator_base_token_ata,
+        &ctx.accounts.market_base_token_ata,
+        &ctx.accounts.base_token_program,
+        initial_deposit,
+    )?;
+
+    // The sellable reserve must be fully backed by the tokens held by the market
+    ctx.accounts.market_base_token_ata.reload()?;
+
+    require_eq!(
+        ctx.accounts.market_base_token_ata.amount,
+        initial_deposit,
+        TokenMillError::InvalidTotalSupply
+    );
+
+    emit_cpi!(TokenMillMarketCreationEvent {
+        config: ctx.accounts.config.key(),
+        market: ctx.accounts.market.key(),
+        creator: ctx.accounts.creator.key(),
+        launchpad: ctx
+            .accounts
+            .launchpad
+            .as_ref()
+            .map(|launchpad| launchpad.key()),
+        base_token_mint: ctx.accounts.base_token_mint.key(),
+        quote_token_mint,
+        total_supply,
+        protocol_fee_share,
+        referral_fee_share: config.get_referral_fee_share(&quote_token_mint),
+        creator_fee_share,
+        staking_fee_share,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateMarketWithExistingMintAction, TokenMillEnv, DEFAULT_TOTAL_SUPPLY,
+        },
+        make_address, TokenMillError, TokenType,
+    };
+    use rstest::rstest;
+
+    fn setup_env(
+        base_token_type: TokenType,
+        base_token_decimals: u8,
+        minted_supply: u64,
+    ) -> (TokenMillEnv, CreateMarketWithExistingMintAction) {
+        let mut testing_env = TokenMillEnv::new().with_default_quote_token_mint();
+
+        let base_token_mint = testing_env
+            .svm
+            .create_fixed_supply_token(
+                base_token_type,
+                base_token_decimals,
+                &make_address("alice"),
+                minted_supply,
+            )
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let action = CreateMarketWithExistingMintAction::new(&testing_env, base_token_mint);
+
+        (testing_env, action)
+    }
+
+    #[rstest]
+    fn create_market_with_existing_mint(
+        #[values(TokenType::Token, TokenType::Token2022)] base_token_type: TokenType,
+    ) {
+        let (mut testing_env, mut action) = setup_env(base_token_type, 6, DEFAULT_TOTAL_SUPPLY * 2);
+
+        let result = testing_env.svm.execute_actions(&[action.no_badge()]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env.svm.get_parsed_account::<Market>(&action.market);
+
+        assert_eq!(market.base_token_mint, action.base_token_mint);
+        assert_eq!(market.total_supply, DEFAULT_TOTAL_SUPPLY);
+        assert_eq!(market.base_reserve, DEFAULT_TOTAL_SUPPLY);
+        assert_eq!(market.pending_supply, 0);
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &action.market),
+            DEFAULT_TOTAL_SUPPLY
+        );
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &action.signer),
+            DEFAULT_TOTAL_SUPPLY
+        );
+    }
+
+    #[test]
+    fn create_market_with_existing_mint_and_partial_deposit() {
+        let (mut testing_env, mut action) =
+            setup_env(TokenType::Token2022, 6, DEFAULT_TOTAL_SUPPLY);
+
+        action.initial_deposit = DEFAULT_TOTAL_SUPPLY / 10;
+
+        let result = testing_env.svm.execute_actions(&[action.no_badge()]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env.svm.get_parsed_account::<Market>(&action.market);
+
+        assert_eq!(market.base_reserve, DEFAULT_TOTAL_SUPPLY);
+        assert_eq!(market.pending_supply, DEFAULT_TOTAL_SUPPLY * 9 / 10);
+        assert_eq!(market.available_supply(), DEFAULT_TOTAL_SUPPLY / 10);
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &action.market),
+            DEFAULT_TOTAL_SUPPLY / 10
+        );
+    }
+
+    #[test]
+    fn create_market_with_existing_mint_and_invalid_initial_deposit() {
+        let (mut testing_env, mut action) =
+            setup_env(TokenType::Token2022, 6, DEFAULT_TOTAL_SUPPLY);
+
+        action.initial_deposit = DEFAULT_TOTAL_SUPPLY + 1;
+
+        let result = testing_env.svm.execute_actions(&[action.no_badge()]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAmount);
+    }
+
+    #[test]
+    fn create_market_with_existing_mint_and_insufficient_balance() {
+        let (mut testing_env, mut action) =
+            setup_env(TokenType::Token2022, 6, DEFAULT_TOTAL_SUPPLY / 2);
+
+        let result = testing_env.svm.execute_actions(&[action.no_badge()]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn create_market_with_existing_mint_and_invalid_decimals() {
+        let (mut testing_env, mut action) =
+            setup_env(TokenType::Token2022, 9, DEFAULT_TOTAL_SUPPLY);
+
+        let result = testing_env.svm.execute_actions(&[action.no_badge()]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::UnsupportedTokenMint);
+    }
+
+    #[test]
+    fn create_market_with_existing_mint_and_mint_authority() {
+        let mut testing_env = TokenMillEnv::new().with_default_quote_token_mint();
+
+        let base_token_mint = testing_env
+            .svm
+            .create_token(TokenType::Token2022, 6)
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let mut action = CreateMarketWithExistingMintAction::new(&testing_env, base_token_mint);
+
+        let result = testing_env.svm.execute_actions(&[action.no_badge()]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::UnsupportedTokenMint);
+    }
+
+    #[test]
+    fn create_market_with_existing_mint_and_invalid_supply() {
+        let (mut testing_env, mut action) =
+            setup_env(TokenType::Token2022, 6, DEFAULT_TOTAL_SUPPLY);
+
+        action.total_supply = DEFAULT_TOTAL_SUPPLY + 1;
+
+        let result = testing_env.svm.execute_actions(&[action.no_badge()]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidTotalSupply);
+    }
+}
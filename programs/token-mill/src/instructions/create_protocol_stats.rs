@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    state::{ProtocolStats, TokenMillConfig},
+    PROTOCOL_STATS_PDA_SEED,
+};
+
+#[derive(Accounts)]
+pub struct CreateProtocolStats<'info> {
+    pub config: Account<'info, TokenMillConfig>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        seeds = [
+            PROTOCOL_STATS_PDA_SEED.as_bytes(),
+            config.key().as_ref(),
+            quote_token_mint.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = 8 + ProtocolStats::INIT_SPACE
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateProtocolStats>) -> Result<()> {
+    let protocol_stats = &mut ctx.accounts.protocol_stats;
+
+    protocol_stats.initialize(
+        ctx.bumps.protocol_stats,
+        ctx.accounts.config.key(),
+        ctx.accounts.quote_token_mint.key(),
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ProtocolStats;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{CreateProtocolStatsAction, SwapAction, TokenMillEnv},
+        SwapAmountType, SwapType,
+    };
+
+    #[test]
+    fn create_protocol_stats() {
+        let mut testing_env = TokenMillEnv::new().with_default_quote_token_mint();
+
+        let action = CreateProtocolStatsAction::new(&testing_env);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let protocol_stats = testing_env
+            .svm
+            .get_parsed_account::<ProtocolStats>(&action.protocol_stats);
+
+        assert_eq!(protocol_stats.config, testing_env.config);
+        assert_eq!(
+            protocol_stats.quote_token_mint,
+            testing_env.quote_token_mint.unwrap()
+        );
+    }
+
+    #[test]
+    fn track_protocol_stats() {
+        let mut testing_env = TokenMillEnv::new()
+            .with_default_quote_token_mint()
+            .with_protocol_stats()
+            .with_default_market();
+
+        testing_env.svm.change_payer("bob");
+
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000,
+            u64::MAX,
+            None,
+        );
+        swap_action.with_protocol_stats();
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let protocol_stats = testing_env
+            .svm
+            .get_parsed_account::<ProtocolStats>(&testing_env.protocol_stats.unwrap());
+
+        assert_eq!(protocol_stats.markets_created, 1);
+        assert!(protocol_stats.cumulative_volume > 0);
+        assert!(protocol_stats.cumulative_fees > 0);
+    }
+}
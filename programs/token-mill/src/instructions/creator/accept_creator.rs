@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillCreatorUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, ChangeLogParameter, Market},
+    CHANGE_LOG_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptCreator<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: Change log of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [CHANGE_LOG_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub change_log: UncheckedAccount<'info>,
+
+    pub pending_creator: Signer<'info>,
+}
+
+/// Completes the transfer started with `propose_creator`, the pending creator taking over the market and the
+/// creator fees accrued from then on
+pub fn handler(ctx: Context<AcceptCreator>) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+    let new_creator = ctx.accounts.pending_creator.key();
+
+    require_keys_eq!(
+        market.pending_creator,
+        new_creator,
+        TokenMillError::InvalidAuthority
+    );
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::Creator,
+        encode_pubkey(Some(market.creator)),
+        encode_pubkey(Some(new_creator)),
+    )?;
+
+    market.creator = new_creator;
+    market.pending_creator = Pubkey::default();
+
+    emit_cpi!(TokenMillCreatorUpdateEvent {
+        market: ctx.accounts.market.key(),
+        new_creator,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, AcceptCreatorAction, ProposeCreatorAction, TokenMillEnv,
+            UpdateCreatorAction,
+        },
+        make_address, TokenMillError,
+    };
+
+    fn setup_env() -> (TokenMillEnv, AcceptCreatorAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&ProposeCreatorAction::new(Some(make_address("carol")))])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let action = AcceptCreatorAction::new(make_address("carol"));
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn accept_creator() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.creator, make_address("carol"));
+        assert_eq!(market.pending_creator, Default::default());
+
+        // The new creator can update the market
+        let mut update_creator_action = UpdateCreatorAction::new(make_address("dave"));
+
+        update_creator_action.signer = make_address("carol");
+
+        testing_env
+            .svm
+            .execute_actions(&[&update_creator_action])
+            .unwrap();
+    }
+
+    #[test]
+    fn accept_creator_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+
+    #[test]
+    fn accept_cancelled_creator_proposal() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&ProposeCreatorAction::new(None)])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillMarketFeeSharesUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_fee_shares, ChangeLogParameter, Market},
+    CHANGE_LOG_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ActivateMarketFeeShares<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: Change log of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [CHANGE_LOG_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub change_log: UncheckedAccount<'info>,
+}
+
+/// Applies the fee shares proposed by the creator once their activation time is reached. Permissionless.
+pub fn handler(ctx: Context<ActivateMarketFeeShares>) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    let old_value = encode_fee_shares(market.fees.creator_fee_share, market.fees.staking_fee_share);
+
+    let (new_creator_fee_share, new_staking_fee_share) =
+        market.activate_fee_shares(Clock::get()?.unix_timestamp)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::MarketFeeShares,
+        old_value,
+        encode_fee_shares(new_creator_fee_share, new_staking_fee_share),
+    )?;
+
+    emit_cpi!(TokenMillMarketFeeSharesUpdateEvent {
+        market: ctx.accounts.market.key(),
+        new_creator_fee_share,
+        new_staking_fee_share,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, ActivateMarketFeeSharesAction, TokenMillEnv,
+            UpdateFeeSharesUpdateDelayAction, UpdateMarketFeeSharesAction,
+        },
+        TokenMillError,
+    };
+
+    const NEW_CREATOR_FEE_SHARE: u16 = 3_000;
+    const NEW_STAKING_FEE_SHARE: u16 = 6_000;
+    const FEE_SHARES_UPDATE_DELAY: u32 = 86_400;
+
+    fn setup_env() -> TokenMillEnv {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&UpdateFeeSharesUpdateDelayAction::new(
+                FEE_SHARES_UPDATE_DELAY,
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&UpdateMarketFeeSharesAction::new(
+                NEW_CREATOR_FEE_SHARE,
+                NEW_STAKING_FEE_SHARE,
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+    }
+
+    #[test]
+    fn activate_market_fee_shares() {
+        let mut testing_env = setup_env();
+
+        testing_env.svm.warp(FEE_SHARES_UPDATE_DELAY.into());
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&ActivateMarketFeeSharesAction::new(&testing_env)]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.fees.creator_fee_share, NEW_CREATOR_FEE_SHARE);
+        assert_eq!(market.fees.staking_fee_share, NEW_STAKING_FEE_SHARE);
+        assert_eq!(market.fee_shares_activation_time, 0);
+    }
+
+    #[test]
+    fn activate_market_fee_shares_before_delay() {
+        let mut testing_env = setup_env();
+
+        testing_env.svm.warp(i64::from(FEE_SHARES_UPDATE_DELAY) - 1);
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&ActivateMarketFeeSharesAction::new(&testing_env)]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::FeeSharesUpdateNotDue);
+    }
+
+    #[test]
+    fn activate_market_fee_shares_twice() {
+        let mut testing_env = setup_env();
+
+        testing_env.svm.warp(FEE_SHARES_UPDATE_DELAY.into());
+
+        testing_env
+            .svm
+            .execute_actions(&[&ActivateMarketFeeSharesAction::new(&testing_env)])
+            .unwrap();
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&ActivateMarketFeeSharesAction::new(&testing_env)]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::FeeSharesUpdateNotDue);
+    }
+}
@@ -0,0 +1,226 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    errors::TokenMillError, events::TokenMillCreatorFeeClaimEvent,
+    manager::token_manager::transfer_from_pda, state::Market, MARKET_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimAllCreatorFees<'info> {
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    // Can be any quote token account, letting creators route their fees to a treasury or cold storage
+    #[account(
+        mut,
+        token::mint = quote_token_mint,
+        token::token_program = quote_token_program
+    )]
+    pub creator_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub creator: Signer<'info>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Number of remaining accounts of each market: the market and its quote token ATA
+pub const CLAIM_ALL_ACCOUNTS_LEN: usize = 2;
+
+/// Claims the pending creator fees of every market passed in the remaining accounts, all quoted in `quote_token_mint`.
+/// Markets splitting their creator fees have to be claimed with `claim_creator_fees`. Returns the total amount claimed.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimAllCreatorFees<'info>>,
+) -> Result<u64> {
+    require!(
+        !ctx.remaining_accounts.is_empty()
+            && ctx
+                .remaining_accounts
+                .len()
+                .is_multiple_of(CLAIM_ALL_ACCOUNTS_LEN),
+        TokenMillError::InvalidClaimAccounts
+    );
+
+    let quote_token_mint = ctx.accounts.quote_token_mint.key();
+    let mut total_fees = 0;
+
+    for accounts in ctx.remaining_accounts.chunks(CLAIM_ALL_ACCOUNTS_LEN) {
+        let [market, market_quote_token_ata] = accounts else {
+            return Err(TokenMillError::InvalidClaimAccounts.into());
+        };
+
+        let market_loader = AccountLoader::<Market>::try_from(market)?;
+
+        let (pending_fees, base_token_mint, bump) = {
+            let market = &mut market_loader.load_mut()?;
+
+            require_keys_eq!(
+                market.creator,
+                ctx.accounts.creator.key(),
+                TokenMillError::InvalidAuthority
+            );
+            require_keys_eq!(
+                market.quote_token_mint,
+                quote_token_mint,
+                TokenMillError::InvalidQuoteTokenMint
+            );
+            require!(
+                market.creator_fee_split == 0,
+                TokenMillError::InvalidCreatorFeeSplit
+            );
+
+            let pending_fees = market.fees.pending_creator_fees;
+            market.fees.pending_creator_fees = 0;
+
+            (pending_fees, market.base_token_mint, market.bump)
+        };
+
+        require_keys_eq!(
+            market_quote_token_ata.key(),
+            get_associated_token_address_with_program_id(
+                &market.key(),
+                &quote_token_mint,
+                &ctx.accounts.quote_token_program.key()
+            ),
+            TokenMillError::InvalidClaimAccounts
+        );
+
+        if pending_fees == 0 {
+            continue;
+        }
+
+        let market_seeds = [
+            MARKET_PDA_SEED.as_bytes(),
+            base_token_mint.as_ref(),
+            &[bump],
+        ];
+
+        transfer_from_pda(
+            &ctx.accounts.quote_token_mint,
+            market.to_account_info(),
+            &InterfaceAccount::try_from(market_quote_token_ata)?,
+            &ctx.accounts.creator_quote_token_ata,
+            &ctx.accounts.quote_token_program,
+            pending_fees,
+            &market_seeds,
+        )?;
+
+        total_fees += pending_fees;
+
+        emit_cpi!(TokenMillCreatorFeeClaimEvent {
+            market: market.key(),
+            creator: ctx.accounts.creator.key(),
+            fees_distributed: pending_fees,
+        });
+    }
+
+    Ok(total_fees)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use anchor_lang::AnchorDeserialize;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, BuyBasketAction, ClaimAllCreatorFeesAction, TokenMillEnv,
+            DEFAULT_TOTAL_SUPPLY,
+        },
+        make_address, TokenMillError,
+    };
+    use solana_sdk::pubkey::Pubkey;
+
+    fn setup_env() -> (TokenMillEnv, ClaimAllCreatorFeesAction) {
+        let testing_env = TokenMillEnv::default();
+        let market_a = testing_env.market;
+        let base_token_mint_a = testing_env.base_token_mint.unwrap();
+
+        let base_token_mint_b = make_address("base_token_mint_b");
+        let mut testing_env = testing_env.with_market(base_token_mint_b, DEFAULT_TOTAL_SUPPLY);
+        let market_b = testing_env.market;
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&BuyBasketAction::new(
+                &testing_env,
+                vec![base_token_mint_a, base_token_mint_b],
+                vec![5_000, 5_000],
+                1_000_000_000,
+                0,
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let action = ClaimAllCreatorFeesAction::new(&testing_env, vec![market_a, market_b]);
+
+        (testing_env, action)
+    }
+
+    fn pending_creator_fees(testing_env: &TokenMillEnv, market: &Pubkey) -> u64 {
+        testing_env
+            .svm
+            .get_parsed_account::<Market>(market)
+            .fees
+            .pending_creator_fees
+    }
+
+    #[test]
+    fn claim_all_creator_fees() {
+        let (mut testing_env, action) = setup_env();
+
+        let pending_fees_a = pending_creator_fees(&testing_env, &action.markets[0]);
+        let pending_fees_b = pending_creator_fees(&testing_env, &action.markets[1]);
+
+        assert!(pending_fees_a > 0 && pending_fees_b > 0);
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let alice = make_address("alice");
+        let balance_before = testing_env.svm.get_balance(&quote_token_mint, &alice);
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let total_fees = u64::try_from_slice(&result.return_data.data).unwrap();
+
+        assert_eq!(total_fees, pending_fees_a + pending_fees_b);
+        assert_eq!(
+            testing_env.svm.get_balance(&quote_token_mint, &alice),
+            balance_before + total_fees
+        );
+        assert_eq!(pending_creator_fees(&testing_env, &action.markets[0]), 0);
+        assert_eq!(pending_creator_fees(&testing_env, &action.markets[1]), 0);
+    }
+
+    #[test]
+    fn claim_all_creator_fees_with_invalid_creator() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+
+    #[test]
+    fn claim_all_creator_fees_without_markets() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.markets.clear();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidClaimAccounts
+        );
+    }
+}
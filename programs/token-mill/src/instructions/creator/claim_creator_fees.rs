@@ -1,9 +1,15 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
 
 use crate::{
-    errors::TokenMillError, events::TokenMillCreatorFeeClaimEvent,
-    manager::token_manager::transfer_from_pda, state::Market, MARKET_PDA_SEED,
+    errors::TokenMillError,
+    events::{TokenMillCreatorFeeClaimEvent, TokenMillCreatorFeeSplitClaimEvent},
+    manager::token_manager::transfer_from_pda,
+    state::{CreatorFeeSplit, Market},
+    MARKET_PDA_SEED,
 };
 
 #[event_cpi]
@@ -26,23 +32,33 @@ pub struct ClaimCreatorFees<'info> {
     )]
     pub market_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
 
+    // Can be any quote token account, letting creators route their fees to a treasury or cold storage
     #[account(
         mut,
-        associated_token::mint = quote_token_mint,
-        associated_token::authority = creator,
-        associated_token::token_program = quote_token_program
+        token::mint = quote_token_mint,
+        token::token_program = quote_token_program
     )]
     pub creator_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
 
+    // Required while the creator fees are split, the quote token ATAs of the recipients are then passed in the
+    // remaining accounts, in the order of the split
+    #[account(has_one = market @ TokenMillError::InvalidMarket)]
+    pub creator_fee_split: Option<Account<'info, CreatorFeeSplit>>,
+
     pub creator: Signer<'info>,
 
     pub quote_token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn handler(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimCreatorFees<'info>>) -> Result<()> {
     let (pending_fees, base_token_mint, bump) = {
         let market = &mut ctx.accounts.market.load_mut()?;
 
+        require!(
+            ctx.accounts.creator_fee_split.is_some() == (market.creator_fee_split == 1),
+            TokenMillError::InvalidCreatorFeeSplit
+        );
+
         let pending_fees = market.fees.pending_creator_fees;
         market.fees.pending_creator_fees = 0;
 
@@ -55,15 +71,62 @@ pub fn handler(ctx: Context<ClaimCreatorFees>) -> Result<()> {
         &[bump],
     ];
 
-    transfer_from_pda(
-        &ctx.accounts.quote_token_mint,
-        ctx.accounts.market.to_account_info(),
-        &ctx.accounts.market_quote_token_ata,
-        &ctx.accounts.creator_quote_token_ata,
-        &ctx.accounts.quote_token_program,
-        pending_fees,
-        &market_seeds,
-    )?;
+    match &ctx.accounts.creator_fee_split {
+        Some(creator_fee_split) => {
+            let amounts = creator_fee_split.split(pending_fees)?;
+
+            require!(
+                ctx.remaining_accounts.len() == amounts.len(),
+                TokenMillError::InvalidCreatorFeeSplit
+            );
+
+            for ((entry, amount), recipient_token_account) in creator_fee_split
+                .recipients()
+                .zip(&amounts)
+                .zip(ctx.remaining_accounts)
+            {
+                require_keys_eq!(
+                    recipient_token_account.key(),
+                    get_associated_token_address_with_program_id(
+                        &entry.recipient,
+                        &ctx.accounts.quote_token_mint.key(),
+                        &ctx.accounts.quote_token_program.key()
+                    ),
+                    TokenMillError::InvalidCreatorFeeSplit
+                );
+
+                if *amount > 0 {
+                    transfer_from_pda(
+                        &ctx.accounts.quote_token_mint,
+                        ctx.accounts.market.to_account_info(),
+                        &ctx.accounts.market_quote_token_ata,
+                        &InterfaceAccount::try_from(recipient_token_account)?,
+                        &ctx.accounts.quote_token_program,
+                        *amount,
+                        &market_seeds,
+                    )?;
+                }
+            }
+
+            emit_cpi!(TokenMillCreatorFeeSplitClaimEvent {
+                market: ctx.accounts.market.key(),
+                recipients: creator_fee_split
+                    .recipients()
+                    .map(|entry| entry.recipient)
+                    .collect(),
+                amounts,
+            });
+        }
+        None => transfer_from_pda(
+            &ctx.accounts.quote_token_mint,
+            ctx.accounts.market.to_account_info(),
+            &ctx.accounts.market_quote_token_ata,
+            &ctx.accounts.creator_quote_token_ata,
+            &ctx.accounts.quote_token_program,
+            pending_fees,
+            &market_seeds,
+        )?,
+    }
 
     emit_cpi!(TokenMillCreatorFeeClaimEvent {
         market: ctx.accounts.market.key(),
@@ -76,14 +139,16 @@ pub fn handler(ctx: Context<ClaimCreatorFees>) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use crate::Market;
+    use crate::{state::CreatorFeeRecipient, Market};
     use joelana_test_utils::joelana_env::{
         actions::token_mill::{
-            tm_parse_error, ClaimCreatorFeesAction, SwapAction, TokenMillEnv, DEFAULT_TOTAL_SUPPLY,
+            tm_parse_error, ClaimCreatorFeesAction, SetCreatorFeeSplitAction, SwapAction,
+            TokenMillEnv, DEFAULT_TOTAL_SUPPLY,
         },
-        SwapAmountType, SwapType, TokenMillError, TokenType,
+        make_address, SwapAmountType, SwapType, TokenMillError, TokenType,
     };
     use rstest::rstest;
+    use solana_sdk::pubkey::Pubkey;
 
     fn setup_env(token_type: TokenType) -> (TokenMillEnv, ClaimCreatorFeesAction) {
         let mut testing_env = TokenMillEnv::new()
@@ -137,4 +202,139 @@ mod tests {
 
         assert_eq!(error, TokenMillError::InvalidAuthority);
     }
+
+    #[test]
+    fn claim_creator_fees_to_recipient() {
+        let (mut testing_env, mut action) = setup_env(TokenType::Token);
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let treasury = make_address("treasury");
+        let treasury_quote_token_ata = testing_env
+            .svm
+            .create_ata(&treasury, &quote_token_mint, testing_env.quote_token_type)
+            .unwrap();
+
+        let pending_fees = testing_env
+            .svm
+            .get_parsed_account::<Market>(&action.market)
+            .fees
+            .pending_creator_fees;
+
+        testing_env
+            .svm
+            .execute_actions(&[action.with_recipient(treasury_quote_token_ata)])
+            .unwrap();
+
+        assert_eq!(
+            testing_env.svm.get_balance(&quote_token_mint, &treasury),
+            pending_fees
+        );
+    }
+
+    #[test]
+    fn claim_creator_fees_to_account_of_other_mint() {
+        let (mut testing_env, mut action) = setup_env(TokenType::Token);
+
+        let bob_base_token_ata = testing_env
+            .svm
+            .get_ata_address(&testing_env.base_token_mint.unwrap(), &make_address("bob"));
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[action.with_recipient(bob_base_token_ata)]);
+
+        assert!(result.is_err());
+    }
+
+    fn setup_split(testing_env: &mut TokenMillEnv) -> [Pubkey; 2] {
+        let recipients = [make_address("treasury"), make_address("dev")];
+
+        let mut entries = [CreatorFeeRecipient::default(); 4];
+        entries[0] = CreatorFeeRecipient {
+            recipient: recipients[0],
+            share_bps: 7_000,
+        };
+        entries[1] = CreatorFeeRecipient {
+            recipient: recipients[1],
+            share_bps: 3_000,
+        };
+
+        let split_action = SetCreatorFeeSplitAction::new(testing_env, entries);
+
+        testing_env.svm.execute_actions(&[&split_action]).unwrap();
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+
+        for recipient in &recipients {
+            testing_env
+                .svm
+                .create_ata(recipient, &quote_token_mint, testing_env.quote_token_type)
+                .unwrap();
+        }
+
+        recipients
+    }
+
+    #[test]
+    fn claim_split_creator_fees() {
+        let (mut testing_env, mut action) = setup_env(TokenType::Token);
+
+        let recipients = setup_split(&mut testing_env);
+
+        let pending_fees = testing_env
+            .svm
+            .get_parsed_account::<Market>(&action.market)
+            .fees
+            .pending_creator_fees;
+
+        testing_env
+            .svm
+            .execute_actions(&[action.with_creator_fee_split(&recipients)])
+            .unwrap();
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let treasury_fees = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &recipients[0]);
+        let dev_fees = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &recipients[1]);
+
+        assert_eq!(dev_fees, pending_fees * 3_000 / 10_000);
+        assert_eq!(treasury_fees, pending_fees - dev_fees);
+
+        let market = testing_env.svm.get_parsed_account::<Market>(&action.market);
+
+        assert_eq!(market.fees.pending_creator_fees, 0);
+    }
+
+    #[test]
+    fn claim_split_creator_fees_to_creator() {
+        let (mut testing_env, action) = setup_env(TokenType::Token);
+
+        setup_split(&mut testing_env);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidCreatorFeeSplit
+        );
+    }
+
+    #[test]
+    fn claim_split_creator_fees_to_invalid_recipients() {
+        let (mut testing_env, mut action) = setup_env(TokenType::Token);
+
+        let [treasury, dev] = setup_split(&mut testing_env);
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[action.with_creator_fee_split(&[dev, treasury])]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidCreatorFeeSplit
+        );
+    }
 }
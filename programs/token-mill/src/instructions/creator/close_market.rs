@@ -0,0 +1,476 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillMarketCloseEvent,
+    manager::token_manager::{burn_from_pda, close_pda_token_account, transfer_from_pda},
+    state::{Graduation, GraduationStatus, Market, MarketOracle, MarketStaking},
+    GRADUATION_PDA_SEED, MARKET_ORACLE_PDA_SEED, MARKET_PDA_SEED, MARKET_STAKING_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseMarket<'info> {
+    #[account(
+        mut,
+        close = creator,
+        has_one = creator @ TokenMillError::InvalidAuthority,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    // Required once the market graduated, the graduation account stays open as a record of the pool
+    #[account(
+        seeds = [GRADUATION_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump = graduation.bump
+    )]
+    pub graduation: Option<Box<Account<'info, Graduation>>>,
+
+    /// CHECK: TWAP oracle of the market, closed in the handler if it has been created
+    #[account(
+        mut,
+        seeds = [MARKET_ORACLE_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub market_oracle: UncheckedAccount<'info>,
+
+    /// CHECK: Staking of the market, closed in the handler if it has been created
+    #[account(
+        mut,
+        seeds = [MARKET_STAKING_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub market_staking: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub base_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub quote_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = quote_token_mint,
+        token::token_program = quote_token_program
+    )]
+    pub creator_quote_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Closes a market whose circulating supply was sold back, or whose graduation is completed, returning the rent of
+/// the market, its vaults, and its oracle and staking accounts to the creator.
+/// The base tokens left in the vault are burned and the quote tokens swept to the creator, whatever their balances.
+/// Nothing may be escrowed for others, open DCA positions, stakes, vesting plans, migration allocations or unclaimed
+/// rewards preventing it.
+/// The closing has to be proposed with `propose_close_market` first, the `market_close_delay` of the config elapsing.
+pub fn handler(ctx: Context<CloseMarket>) -> Result<()> {
+    let (base_amount, quote_amount, market_bump) = {
+        let market = ctx.accounts.market.load()?;
+
+        require!(
+            market.is_close_due(Clock::get()?.unix_timestamp),
+            TokenMillError::MarketCloseNotDue
+        );
+
+        // The pool of a pending graduation is created with the market account
+        if market.graduated == 1 {
+            require!(
+                ctx.accounts
+                    .graduation
+                    .as_ref()
+                    .is_some_and(|graduation| graduation.status == GraduationStatus::Completed),
+                TokenMillError::MarketNotClosable
+            );
+        }
+
+        let (base_amount, quote_amount) = market.close(
+            ctx.accounts.market_base_token_ata.amount,
+            ctx.accounts.market_quote_token_ata.amount,
+        )?;
+
+        (base_amount, quote_amount, market.bump)
+    };
+
+    // The oracle and staking accounts are derived from the market, so they can't be left out while they exist
+    let creator = ctx.accounts.creator.to_account_info();
+    let market_oracle = ctx.accounts.market_oracle.to_account_info();
+    let market_staking = ctx.accounts.market_staking.to_account_info();
+
+    if !market_oracle.data_is_empty() {
+        MarketOracle::try_deserialize(&mut &market_oracle.try_borrow_data()?[..])?;
+
+        close_account(&market_oracle, &creator)?;
+    }
+
+    if !market_staking.data_is_empty() {
+        let staking = MarketStaking::try_deserialize(&mut &market_staking.try_borrow_data()?[..])?;
+
+        require!(
+            staking.total_shares() == 0 && staking.pending_penalty_amount == 0,
+            TokenMillError::MarketNotClosable
+        );
+
+        close_account(&market_staking, &creator)?;
+    }
+
+    let base_token_mint_key = ctx.accounts.base_token_mint.key();
+    let market_seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint_key.as_ref(),
+        &[market_bump],
+    ];
+
+    if base_amount > 0 {
+        burn_from_pda(
+            &ctx.accounts.base_token_mint,
+            ctx.accounts.market.to_account_info(),
+            &ctx.accounts.market_base_token_ata,
+            &ctx.accounts.base_token_program,
+            base_amount,
+            &market_seeds,
+        )?;
+    }
+
+    if quote_amount > 0 {
+        transfer_from_pda(
+            &ctx.accounts.quote_token_mint,
+            ctx.accounts.market.to_account_info(),
+            &ctx.accounts.market_quote_token_ata,
+            &ctx.accounts.creator_quote_token_ata,
+            &ctx.accounts.quote_token_program,
+            quote_amount,
+            &market_seeds,
+        )?;
+    }
+
+    close_pda_token_account(
+        ctx.accounts.market.to_account_info(),
+        &ctx.accounts.market_base_token_ata,
+        ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.base_token_program,
+        &market_seeds,
+    )?;
+
+    close_pda_token_account(
+        ctx.accounts.market.to_account_info(),
+        &ctx.accounts.market_quote_token_ata,
+        ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.quote_token_program,
+        &market_seeds,
+    )?;
+
+    emit_cpi!(TokenMillMarketCloseEvent {
+        market: ctx.accounts.market.key(),
+        creator: ctx.accounts.creator.key(),
+        quote_amount,
+        burned_amount: base_amount,
+    });
+
+    Ok(())
+}
+
+/// Closes an account of the program, like the `close` constraint, its rent going to `recipient`
+fn close_account(account: &AccountInfo, recipient: &AccountInfo) -> Result<()> {
+    recipient.add_lamports(account.lamports())?;
+    account.sub_lamports(account.lamports())?;
+
+    account.assign(&System::id());
+    account.realloc(0, false)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CloseDcaPositionAction, CloseMarketAction, CreateDcaPositionAction,
+            CreateMarketOracleAction, CreateStakingAction, ProposeCloseMarketAction, SwapAction,
+            TokenMillEnv, DEFAULT_TOTAL_SUPPLY,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    use crate::Market;
+
+    const BASE_AMOUNT: u64 = DEFAULT_TOTAL_SUPPLY / 10;
+
+    #[test]
+    fn close_market() {
+        let mut testing_env = TokenMillEnv::default();
+
+        let action = CloseMarketAction::new(&testing_env);
+
+        testing_env.svm.change_payer("alice");
+
+        let creator_lamports = testing_env.svm.get_lamports(&action.signer);
+
+        testing_env
+            .svm
+            .execute_actions(&[&ProposeCloseMarketAction::new(&testing_env), &action])
+            .unwrap();
+
+        assert!(testing_env.svm.try_get_account(&action.market).is_none());
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.market_base_token_ata)
+            .is_none());
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.market_quote_token_ata)
+            .is_none());
+        // The rent of the market and its vaults outweighs the transaction fee
+        assert!(testing_env.svm.get_lamports(&action.signer) > creator_lamports);
+    }
+
+    #[test]
+    fn close_market_with_oracle_and_staking() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &CreateMarketOracleAction::new(&testing_env),
+                &CreateStakingAction::new(&testing_env),
+            ])
+            .unwrap();
+
+        let action = CloseMarketAction::new(&testing_env);
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&ProposeCloseMarketAction::new(&testing_env), &action])
+            .unwrap();
+
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.market_oracle)
+            .is_none());
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.market_staking)
+            .is_none());
+    }
+
+    #[test]
+    fn close_market_after_sell_back() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &SwapAction::new(
+                    &testing_env,
+                    SwapType::Buy,
+                    SwapAmountType::ExactOutput,
+                    BASE_AMOUNT,
+                    u64::MAX,
+                    None,
+                ),
+                &SwapAction::new(
+                    &testing_env,
+                    SwapType::Sell,
+                    SwapAmountType::ExactInput,
+                    BASE_AMOUNT,
+                    0,
+                    None,
+                ),
+            ])
+            .unwrap();
+
+        let action = CloseMarketAction::new(&testing_env);
+
+        let market = testing_env.svm.get_parsed_account::<Market>(&action.market);
+        let pending_fees = market.fees.pending_creator_fees
+            + market.fees.pending_staking_fees
+            + market.fees.pending_burn_fees;
+        let quote_reserve = testing_env
+            .svm
+            .get_balance(&action.quote_token_mint, &action.market);
+        let creator_quote_balance = testing_env
+            .svm
+            .get_balance(&action.quote_token_mint, &action.signer);
+
+        assert!(pending_fees > 0 && quote_reserve >= pending_fees);
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&ProposeCloseMarketAction::new(&testing_env), &action])
+            .unwrap();
+
+        // The pending fees and the rounding dust of the curve are swept to the creator
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.quote_token_mint, &action.signer),
+            creator_quote_balance + quote_reserve
+        );
+        assert!(testing_env.svm.try_get_account(&action.market).is_none());
+    }
+
+    #[test]
+    fn close_market_with_donation() {
+        let mut testing_env = TokenMillEnv::default();
+
+        let action = CloseMarketAction::new(&testing_env);
+
+        // Tokens sent to the vaults don't block the close, they are swept with the rest
+        testing_env
+            .svm
+            .transfer_tokens(
+                &action.quote_token_mint,
+                &make_address("bob"),
+                &action.market,
+                1_000,
+            )
+            .unwrap();
+
+        let creator_quote_balance = testing_env
+            .svm
+            .get_balance(&action.quote_token_mint, &action.signer);
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&ProposeCloseMarketAction::new(&testing_env), &action])
+            .unwrap();
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.quote_token_mint, &action.signer),
+            creator_quote_balance + 1_000
+        );
+        assert!(testing_env.svm.try_get_account(&action.market).is_none());
+    }
+
+    #[test]
+    fn close_market_with_dca_position() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        let create_dca_position_action =
+            CreateDcaPositionAction::new(&testing_env, 1_000_000, 1_000, 3);
+
+        testing_env
+            .svm
+            .execute_actions(&[&create_dca_position_action])
+            .unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.escrowed_quote, 3 * (1_000_000 + 1_000));
+
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[
+            &ProposeCloseMarketAction::new(&testing_env),
+            &CloseMarketAction::new(&testing_env),
+        ]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketVaultsNotEmpty
+        );
+
+        // Once the escrow is returned, the market can be closed
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&CloseDcaPositionAction::new(&create_dca_position_action)])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &ProposeCloseMarketAction::new(&testing_env),
+                &CloseMarketAction::new(&testing_env),
+            ])
+            .unwrap();
+    }
+
+    #[test]
+    fn close_market_with_circulating_supply() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                BASE_AMOUNT,
+                u64::MAX,
+                None,
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[
+            &ProposeCloseMarketAction::new(&testing_env),
+            &CloseMarketAction::new(&testing_env),
+        ]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketNotClosable
+        );
+    }
+
+    #[test]
+    fn close_market_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::default();
+
+        let mut action = CloseMarketAction::new(&testing_env);
+
+        action.signer = testing_env.svm.change_payer("mallory");
+        action.creator_quote_token_ata = testing_env
+            .svm
+            .get_ata_address(&action.quote_token_mint, &make_address("mallory"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
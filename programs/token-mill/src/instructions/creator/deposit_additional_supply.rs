@@ -0,0 +1,211 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError, events::TokenMillSupplyDepositEvent,
+    manager::token_manager::transfer_from_eoa, state::Market,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DepositAdditionalSupply<'info> {
+    #[account(
+        mut,
+        has_one = creator @ TokenMillError::InvalidAuthority,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = base_token_program
+    )]
+    pub creator_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub creator: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Deposits part of the supply of a wrap-mode market that wasn't escrowed at creation,
+/// making it available for buys at the tail of the curve
+pub fn handler(ctx: Context<DepositAdditionalSupply>, amount: u64) -> Result<()> {
+    let pending_supply = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        market.deposit_supply(amount)?;
+
+        market.pending_supply
+    };
+
+    let balance_before = ctx.accounts.market_base_token_ata.amount;
+
+    transfer_from_eoa(
+        &ctx.accounts.base_token_mint,
+        &ctx.accounts.creator,
+        &ctx.accounts.creator_base_token_ata,
+        &ctx.accounts.market_base_token_ata,
+        &ctx.accounts.base_token_program,
+        amount,
+    )?;
+
+    ctx.accounts.market_base_token_ata.reload()?;
+
+    require_eq!(
+        ctx.accounts.market_base_token_ata.amount - balance_before,
+        amount,
+        TokenMillError::InvalidAmount
+    );
+
+    emit_cpi!(TokenMillSupplyDepositEvent {
+        market: ctx.accounts.market.key(),
+        amount,
+        pending_supply,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, DepositAdditionalSupplyAction, SwapAction, TokenMillEnv,
+            DEFAULT_TOTAL_SUPPLY,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const INITIAL_DEPOSIT: u64 = DEFAULT_TOTAL_SUPPLY / 4;
+
+    fn setup_env() -> (TokenMillEnv, DepositAdditionalSupplyAction) {
+        let testing_env = TokenMillEnv::new()
+            .with_default_quote_token_mint()
+            .with_wrapped_market(DEFAULT_TOTAL_SUPPLY, INITIAL_DEPOSIT);
+
+        let action = DepositAdditionalSupplyAction::new(
+            &testing_env,
+            DEFAULT_TOTAL_SUPPLY - INITIAL_DEPOSIT,
+        );
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn deposit_additional_supply() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.amount = DEFAULT_TOTAL_SUPPLY / 4;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env.svm.get_parsed_account::<Market>(&action.market);
+
+        assert_eq!(market.pending_supply, DEFAULT_TOTAL_SUPPLY / 2);
+        assert_eq!(market.available_supply(), DEFAULT_TOTAL_SUPPLY / 2);
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &action.market),
+            DEFAULT_TOTAL_SUPPLY / 2
+        );
+    }
+
+    #[test]
+    fn buy_capped_to_deposited_supply() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.change_payer("bob");
+
+        let buy_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            DEFAULT_TOTAL_SUPPLY / 2,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&buy_action]).unwrap();
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &make_address("bob")),
+            INITIAL_DEPOSIT
+        );
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let buy_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactInput,
+            u64::MAX / 8,
+            0,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&buy_action]).unwrap();
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &make_address("bob")),
+            DEFAULT_TOTAL_SUPPLY
+        );
+    }
+
+    #[test]
+    fn deposit_additional_supply_above_pending_supply() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.amount += 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAmount);
+    }
+
+    #[test]
+    fn deposit_additional_supply_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+        action.creator_base_token_ata = testing_env
+            .svm
+            .get_ata_address(&action.base_token_mint, &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
@@ -1,7 +1,37 @@
+pub mod accept_creator;
+pub mod activate_market_fee_shares;
+pub mod claim_all_creator_fees;
 pub mod claim_creator_fees;
+pub mod close_market;
+pub mod deposit_additional_supply;
+pub mod pause_sells;
+pub mod propose_close_market;
+pub mod propose_creator;
+pub mod raise_tail_prices;
+pub mod set_burn_fee_share;
+pub mod set_creator_fee_split;
+pub mod set_graduation_supply;
+pub mod set_impact_fee;
+pub mod set_incentive_weights;
+pub mod set_launch_fee;
 pub mod set_market_prices;
+pub mod set_max_raise;
+pub mod set_max_slot_price_move;
+pub mod set_prices_from_preset;
+pub mod set_secondary_curve;
+pub mod set_virtual_supply;
 pub mod update_creator;
 pub mod update_market_fee_shares;
+pub mod update_prices;
+pub mod validate_market_prices;
 
+pub use accept_creator::*;
+pub use activate_market_fee_shares::*;
+pub use claim_all_creator_fees::*;
 pub use claim_creator_fees::*;
+pub use close_market::*;
+pub use deposit_additional_supply::*;
+pub use propose_close_market::*;
+pub use set_creator_fee_split::*;
 pub use set_market_prices::*;
+pub use update_market_fee_shares::*;
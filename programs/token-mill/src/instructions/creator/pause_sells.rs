@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillSellPauseUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_i64, ChangeLogParameter},
+};
+
+use super::MarketSettingsUpdate;
+
+/// Disables sells for `duration` seconds, buys remain open. A duration of 0 lifts the pause.
+/// Pauses can't be extended and are spaced by a cooldown.
+pub fn handler(ctx: Context<MarketSettingsUpdate>, duration: i64) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    let old_sells_paused_until = market.sells_paused_until;
+
+    market.pause_sells(Clock::get()?.unix_timestamp, duration)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::SellPause,
+        encode_i64(old_sells_paused_until),
+        encode_i64(market.sells_paused_until),
+    )?;
+
+    emit_cpi!(TokenMillSellPauseUpdateEvent {
+        market: ctx.accounts.market.key(),
+        sells_paused_until: market.sells_paused_until,
+        locked: false,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        constant::{MAX_SELL_PAUSE_DURATION, MIN_SELL_PAUSE_COOLDOWN},
+        Market,
+    };
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, PauseSellsAction, SwapAction, TokenMillEnv},
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const PAUSE_DURATION: i64 = 3_600;
+
+    fn setup_env() -> (TokenMillEnv, PauseSellsAction, SwapAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        swap_action.swap_type = SwapType::Sell;
+        swap_action.swap_amount_type = SwapAmountType::ExactInput;
+        swap_action.amount = 1_000_000;
+        swap_action.other_amount_threshold = 0;
+
+        testing_env.svm.change_payer("alice");
+
+        let action = PauseSellsAction::new(PAUSE_DURATION);
+
+        (testing_env, action, swap_action)
+    }
+
+    #[test]
+    fn pause_sells() {
+        let (mut testing_env, action, mut swap_action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert!(market.sells_paused_until > 0);
+
+        testing_env.svm.change_payer("bob");
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::SellsPaused);
+
+        // Buys remain open
+        swap_action.swap_type = SwapType::Buy;
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        // Sells resume once the pause expires
+        testing_env.svm.warp(PAUSE_DURATION);
+        swap_action.swap_type = SwapType::Sell;
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+    }
+
+    #[test]
+    fn pause_sells_again() {
+        let (mut testing_env, mut action, _) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        // The pause can't be extended
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::SellPauseCooldown);
+
+        // Nor lifted and paused again right away
+        action.duration = 0;
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        action.duration = PAUSE_DURATION;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::SellPauseCooldown);
+
+        testing_env.svm.warp(MIN_SELL_PAUSE_COOLDOWN - 1);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::SellPauseCooldown);
+
+        testing_env.svm.warp(1);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn pause_sells_for_too_long() {
+        let (mut testing_env, mut action, _) = setup_env();
+
+        action.duration = MAX_SELL_PAUSE_DURATION + 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidSellPauseDuration);
+    }
+
+    #[test]
+    fn pause_sells_with_invalid_signer() {
+        let (mut testing_env, mut action, _) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
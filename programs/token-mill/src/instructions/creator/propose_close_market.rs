@@ -0,0 +1,240 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError, events::TokenMillMarketCloseProposalEvent, state::Market,
+    TokenMillConfig,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeCloseMarket<'info> {
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        mut,
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = creator @ TokenMillError::InvalidAuthority
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    pub creator: Signer<'info>,
+}
+
+/// First step of `close_market`, which can close the market once the `market_close_delay` of the config has elapsed.
+/// The market has to be closable already and older than the `min_market_age` of the config. The proposal event gives
+/// the holders time to react, the market staying fully tradable in the meantime, though any trade cancels it.
+pub fn handler(ctx: Context<ProposeCloseMarket>) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    let close_time = market.propose_close(
+        Clock::get()?.unix_timestamp,
+        ctx.accounts.config.market_close_delay,
+        ctx.accounts.config.min_market_age,
+    )?;
+
+    emit_cpi!(TokenMillMarketCloseProposalEvent {
+        market: ctx.accounts.market.key(),
+        close_time,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CloseMarketAction, ProposeCloseMarketAction, SwapAction, TokenMillEnv,
+            UpdateMarketCloseDelayAction, UpdateMinMarketAgeAction, DEFAULT_TOTAL_SUPPLY,
+        },
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const MARKET_CLOSE_DELAY: u32 = 86_400;
+    const MIN_MARKET_AGE: u32 = 604_800;
+    const BASE_AMOUNT: u64 = DEFAULT_TOTAL_SUPPLY / 10;
+
+    fn setup_env() -> (TokenMillEnv, ProposeCloseMarketAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&UpdateMarketCloseDelayAction::new(MARKET_CLOSE_DELAY)])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let action = ProposeCloseMarketAction::new(&testing_env);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn propose_close_market() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(
+            market.close_time,
+            testing_env.svm.get_clock().unix_timestamp + i64::from(MARKET_CLOSE_DELAY)
+        );
+    }
+
+    #[test]
+    fn close_market_before_close_time() {
+        let (mut testing_env, action) = setup_env();
+
+        let close_action = CloseMarketAction::new(&testing_env);
+
+        // Closing requires a proposal
+        let result = testing_env.svm.execute_actions(&[&close_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketCloseNotDue
+        );
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&close_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketCloseNotDue
+        );
+
+        testing_env.svm.warp(i64::from(MARKET_CLOSE_DELAY));
+
+        testing_env.svm.execute_actions(&[&close_action]).unwrap();
+
+        assert!(testing_env
+            .svm
+            .try_get_account(&close_action.market)
+            .is_none());
+    }
+
+    #[test]
+    fn propose_close_market_with_circulating_supply() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                BASE_AMOUNT,
+                u64::MAX,
+                None,
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketNotClosable
+        );
+    }
+
+    #[test]
+    fn propose_close_market_before_min_market_age() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&UpdateMinMarketAgeAction::new(MIN_MARKET_AGE)])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketNotClosable
+        );
+
+        testing_env.svm.warp(i64::from(MIN_MARKET_AGE));
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+    }
+
+    #[test]
+    fn close_market_after_trade() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        // The circulating supply is back to 0, but the trades cancel the proposal
+        testing_env
+            .svm
+            .execute_actions(&[
+                &SwapAction::new(
+                    &testing_env,
+                    SwapType::Buy,
+                    SwapAmountType::ExactOutput,
+                    BASE_AMOUNT,
+                    u64::MAX,
+                    None,
+                ),
+                &SwapAction::new(
+                    &testing_env,
+                    SwapType::Sell,
+                    SwapAmountType::ExactInput,
+                    BASE_AMOUNT,
+                    0,
+                    None,
+                ),
+            ])
+            .unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.close_time, 0);
+
+        testing_env.svm.warp(i64::from(MARKET_CLOSE_DELAY));
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&CloseMarketAction::new(&testing_env)]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketCloseNotDue
+        );
+    }
+
+    #[test]
+    fn propose_close_market_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
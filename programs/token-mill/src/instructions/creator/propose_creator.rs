@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillCreatorProposalEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, ChangeLogParameter},
+};
+
+use super::MarketSettingsUpdate;
+
+/// Proposes a new creator, e.g. a DAO or a new team, which only takes over once it accepts with `accept_creator`.
+/// The current creator keeps the market and its fees until then. Proposing `None` cancels the pending proposal
+pub fn handler(ctx: Context<MarketSettingsUpdate>, pending_creator: Option<Pubkey>) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    let previous_pending_creator = Some(market.pending_creator)
+        .filter(|pending_creator| *pending_creator != Pubkey::default());
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::PendingCreator,
+        encode_pubkey(previous_pending_creator),
+        encode_pubkey(pending_creator),
+    )?;
+
+    market.pending_creator = pending_creator.unwrap_or_default();
+
+    emit_cpi!(TokenMillCreatorProposalEvent {
+        market: ctx.accounts.market.key(),
+        pending_creator,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, ProposeCreatorAction, TokenMillEnv},
+        make_address, TokenMillError,
+    };
+
+    fn setup_env() -> (TokenMillEnv, ProposeCreatorAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        let action = ProposeCreatorAction::new(Some(make_address("carol")));
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn propose_creator() {
+        let (mut testing_env, mut action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        // The creator only changes once the proposal is accepted
+        assert_eq!(market.creator, make_address("alice"));
+        assert_eq!(market.pending_creator, make_address("carol"));
+
+        action.pending_creator = None;
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.pending_creator, Default::default());
+    }
+
+    #[test]
+    fn propose_creator_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
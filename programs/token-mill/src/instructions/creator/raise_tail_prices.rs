@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillMarketPriceSetEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::SetMarketPrices;
+
+/// Raises the ask prices of the unsold tail of the curve, see `Market::raise_tail_prices`.
+/// `ask_prices` is the whole new ask curve, prices that can't be raised have to be left unchanged.
+pub fn handler(ctx: Context<SetMarketPrices>, ask_prices: Vec<u64>) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    if market.launchpad != Pubkey::default() {
+        require!(
+            ctx.accounts
+                .launchpad
+                .as_ref()
+                .is_some_and(|launchpad| launchpad.key() == market.launchpad),
+            TokenMillError::InvalidLaunchpad
+        );
+    }
+
+    let previous_max_ask_price = market.max_ask_price();
+
+    market.raise_tail_prices(&ask_prices, ctx.accounts.config.max_spread_bps)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::MarketPrices,
+        encode_u64(previous_max_ask_price),
+        encode_u64(market.max_ask_price()),
+    )?;
+
+    let prices_length = market.prices_length();
+
+    emit_cpi!(TokenMillMarketPriceSetEvent {
+        market: ctx.accounts.market.key(),
+        bid_prices: market.bid_prices[..prices_length].to_vec(),
+        ask_prices,
+        prices_hash: market.prices_hash,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, RaiseTailPricesAction, SwapAction, TokenMillEnv, DEFAULT_TOTAL_SUPPLY,
+        },
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    fn setup_env() -> (TokenMillEnv, RaiseTailPricesAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        // Circulating supply in the middle of the third interval
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            DEFAULT_TOTAL_SUPPLY / 4,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+        let mut ask_prices = market.ask_prices[..market.prices_length()].to_vec();
+
+        for ask_price in ask_prices.iter_mut().skip(4) {
+            *ask_price *= 2;
+        }
+
+        let action = RaiseTailPricesAction::new(&testing_env, ask_prices);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn raise_tail_prices() {
+        let (mut testing_env, action) = setup_env();
+
+        let market_before = testing_env.svm.get_parsed_account::<Market>(&action.market);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env.svm.get_parsed_account::<Market>(&action.market);
+
+        assert_eq!(
+            market.ask_prices[..market.prices_length()],
+            action.ask_prices
+        );
+        assert_eq!(market.bid_prices[..4], market_before.bid_prices[..4]);
+        assert_eq!(market.bid_prices[4], market_before.bid_prices[4] * 2);
+        assert_eq!(
+            market.get_spot_prices().unwrap(),
+            market_before.get_spot_prices().unwrap()
+        );
+    }
+
+    #[test]
+    fn raise_tail_prices_below_circulating_supply() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.ask_prices[3] += 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidTailPrices
+        );
+    }
+
+    #[test]
+    fn raise_tail_prices_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillBurnFeeShareUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_fee_shares, ChangeLogParameter},
+};
+
+use super::MarketSettingsUpdate;
+
+/// Sets the share of the swap fee accrued for `buyback_and_burn`, taken from or given back to the creator fee share
+pub fn handler(ctx: Context<MarketSettingsUpdate>, burn_fee_share: u16) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    let old_value = encode_fee_shares(market.fees.creator_fee_share, market.fees.burn_fee_share);
+
+    market.fees.set_burn_fee_share(burn_fee_share)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::BurnFeeShare,
+        old_value,
+        encode_fee_shares(market.fees.creator_fee_share, market.fees.burn_fee_share),
+    )?;
+
+    emit_cpi!(TokenMillBurnFeeShareUpdateEvent {
+        market: ctx.accounts.market.key(),
+        new_creator_fee_share: market.fees.creator_fee_share,
+        new_burn_fee_share: burn_fee_share,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::{
+        joelana_env::{
+            actions::token_mill::{
+                tm_parse_error, SetBurnFeeShareAction, SwapAction, TokenMillEnv,
+            },
+            SwapAmountType, SwapType, TokenMillError,
+        },
+        utils::token_mill::constants::DEFAULT_CREATOR_FEE_SHARE,
+    };
+
+    const BURN_FEE_SHARE: u16 = 1_500;
+
+    #[test]
+    fn set_burn_fee_share() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetBurnFeeShareAction::new(&testing_env, BURN_FEE_SHARE)])
+            .unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.fees.burn_fee_share, BURN_FEE_SHARE);
+        assert_eq!(
+            market.fees.creator_fee_share,
+            DEFAULT_CREATOR_FEE_SHARE - BURN_FEE_SHARE
+        );
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                1_000_000_000,
+                u64::MAX,
+                None,
+            )])
+            .unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        // Both shares are rounded down from the same swap fee
+        assert!(market.fees.pending_burn_fees > 0);
+        assert!(
+            market.fees.pending_burn_fees * u64::from(DEFAULT_CREATOR_FEE_SHARE - BURN_FEE_SHARE)
+                <= (market.fees.pending_creator_fees + 1) * u64::from(BURN_FEE_SHARE)
+        );
+
+        // The share is given back to the creator
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetBurnFeeShareAction::new(&testing_env, 0)])
+            .unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.fees.burn_fee_share, 0);
+        assert_eq!(market.fees.creator_fee_share, DEFAULT_CREATOR_FEE_SHARE);
+    }
+
+    #[test]
+    fn set_burn_fee_share_above_creator_fee_share() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&SetBurnFeeShareAction::new(
+                &testing_env,
+                DEFAULT_CREATOR_FEE_SHARE + 1,
+            )]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidFeeShare
+        );
+    }
+
+    #[test]
+    fn set_burn_fee_share_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::default();
+
+        let mut action = SetBurnFeeShareAction::new(&testing_env, BURN_FEE_SHARE);
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillCreatorFeeSplitUpdateEvent,
+    state::{
+        CreatorFeeRecipient, CreatorFeeSplit, Market, CREATOR_FEE_RECIPIENTS_LENGTH,
+        CREATOR_FEE_SPLIT_PDA_SEED,
+    },
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCreatorFeeSplit<'info> {
+    #[account(mut, has_one = creator @ TokenMillError::InvalidAuthority)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        init_if_needed,
+        seeds = [CREATOR_FEE_SPLIT_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        payer = creator,
+        space = 8 + CreatorFeeSplit::INIT_SPACE
+    )]
+    pub creator_fee_split: Account<'info, CreatorFeeSplit>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Splits the creator fees of the market among up to `CREATOR_FEE_RECIPIENTS_LENGTH` recipients, in bps of the fees.
+/// The pending creator fees are then claimed to the recipients, including the fees accrued before the split.
+/// Passing only unused entries removes the split, the creator being the sole recipient again.
+pub fn handler(
+    ctx: Context<SetCreatorFeeSplit>,
+    recipients: [CreatorFeeRecipient; CREATOR_FEE_RECIPIENTS_LENGTH],
+) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    if recipients
+        .iter()
+        .all(|entry| *entry == CreatorFeeRecipient::default())
+    {
+        market.creator_fee_split = 0;
+    } else {
+        ctx.accounts.creator_fee_split.initialize(
+            ctx.bumps.creator_fee_split,
+            ctx.accounts.market.key(),
+            recipients,
+        )?;
+
+        market.creator_fee_split = 1;
+    }
+
+    emit_cpi!(TokenMillCreatorFeeSplitUpdateEvent {
+        market: ctx.accounts.market.key(),
+        recipients,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        state::{CreatorFeeRecipient, CreatorFeeSplit},
+        Market,
+    };
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, SetCreatorFeeSplitAction, TokenMillEnv},
+        make_address, TokenMillError,
+    };
+
+    fn recipients(shares: &[(&str, u16)]) -> [CreatorFeeRecipient; 4] {
+        let mut recipients = [CreatorFeeRecipient::default(); 4];
+
+        for (entry, (name, share_bps)) in recipients.iter_mut().zip(shares) {
+            *entry = CreatorFeeRecipient {
+                recipient: make_address(name),
+                share_bps: *share_bps,
+            };
+        }
+
+        recipients
+    }
+
+    #[test]
+    fn set_creator_fee_split() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        let mut action = SetCreatorFeeSplitAction::new(
+            &testing_env,
+            recipients(&[("treasury", 6_000), ("dev", 3_000), ("marketing", 1_000)]),
+        );
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let creator_fee_split = testing_env
+            .svm
+            .get_parsed_account::<CreatorFeeSplit>(&action.creator_fee_split);
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(creator_fee_split.market, testing_env.market);
+        assert_eq!(creator_fee_split.recipients, action.recipients);
+        assert_eq!(market.creator_fee_split, 1);
+
+        // Unused entries only remove the split
+        action.recipients = Default::default();
+
+        testing_env.svm.warp(1);
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.creator_fee_split, 0);
+    }
+
+    #[test]
+    fn set_invalid_creator_fee_split() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        let mut gap = recipients(&[("treasury", 6_000), ("dev", 4_000)]);
+        gap.swap(1, 2);
+
+        for recipients in [
+            recipients(&[("treasury", 6_000), ("dev", 3_000)]),
+            recipients(&[("treasury", 10_000), ("dev", 0)]),
+            recipients(&[("treasury", 6_000), ("treasury", 4_000)]),
+            gap,
+        ] {
+            let result = testing_env
+                .svm
+                .execute_actions(&[&SetCreatorFeeSplitAction::new(&testing_env, recipients)]);
+
+            assert_eq!(
+                tm_parse_error(result).unwrap(),
+                TokenMillError::InvalidCreatorFeeSplit
+            );
+        }
+    }
+
+    #[test]
+    fn set_creator_fee_split_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::default();
+
+        let mut action =
+            SetCreatorFeeSplitAction::new(&testing_env, recipients(&[("treasury", 10_000)]));
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
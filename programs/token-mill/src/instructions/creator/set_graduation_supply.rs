@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillGraduationSupplyUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::MarketSettingsUpdate;
+
+/// Reserves the tail of the curve to seed the pool the market graduates to, only before the first trade
+pub fn handler(ctx: Context<MarketSettingsUpdate>, graduation_supply: u64) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    let old_graduation_supply = market.graduation_supply;
+
+    market.set_graduation_supply(graduation_supply)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::GraduationSupply,
+        encode_u64(old_graduation_supply),
+        encode_u64(graduation_supply),
+    )?;
+
+    emit_cpi!(TokenMillGraduationSupplyUpdateEvent {
+        market: ctx.accounts.market.key(),
+        new_graduation_supply: graduation_supply,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, SetGraduationSupplyAction, SwapAction, TokenMillEnv,
+            DEFAULT_TOTAL_SUPPLY,
+        },
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const GRADUATION_SUPPLY: u64 = DEFAULT_TOTAL_SUPPLY / 5;
+
+    fn setup_env() -> (TokenMillEnv, SetGraduationSupplyAction) {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("alice");
+
+        let action = SetGraduationSupplyAction::new(&testing_env, GRADUATION_SUPPLY);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn set_graduation_supply() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.graduation_supply, GRADUATION_SUPPLY);
+        assert_eq!(
+            market.available_supply(),
+            DEFAULT_TOTAL_SUPPLY - GRADUATION_SUPPLY
+        );
+    }
+
+    #[test]
+    fn set_graduation_supply_to_total_supply() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.graduation_supply = DEFAULT_TOTAL_SUPPLY;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidGraduationSupply);
+    }
+
+    #[test]
+    fn set_graduation_supply_after_trade() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactInput,
+                1_000_000,
+                0,
+                None,
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::MarketAlreadyTraded);
+    }
+
+    #[test]
+    fn set_graduation_supply_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
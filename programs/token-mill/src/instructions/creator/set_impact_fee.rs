@@ -0,0 +1,213 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillImpactFeeUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_impact_fee, ChangeLogParameter},
+};
+
+use super::MarketSettingsUpdate;
+
+/// Charges swaps moving the price by more than `impact_fee_threshold_bps` an extra `impact_fee_rate_bps` of fee
+/// per bps of excess price impact, capped at `max_impact_fee_bps`. The fee is shared like the swap fee
+pub fn handler(
+    ctx: Context<MarketSettingsUpdate>,
+    max_impact_fee_bps: u16,
+    impact_fee_threshold_bps: u16,
+    impact_fee_rate_bps: u16,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    let old_value = encode_impact_fee(
+        market.max_impact_fee_bps,
+        market.impact_fee_threshold_bps,
+        market.impact_fee_rate_bps,
+    );
+
+    market.set_impact_fee(
+        max_impact_fee_bps,
+        impact_fee_threshold_bps,
+        impact_fee_rate_bps,
+    )?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::ImpactFee,
+        old_value,
+        encode_impact_fee(
+            max_impact_fee_bps,
+            impact_fee_threshold_bps,
+            impact_fee_rate_bps,
+        ),
+    )?;
+
+    emit_cpi!(TokenMillImpactFeeUpdateEvent {
+        market: ctx.accounts.market.key(),
+        max_impact_fee_bps,
+        impact_fee_threshold_bps,
+        impact_fee_rate_bps,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constant::MAX_IMPACT_FEE_BPS, Market};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, SetImpactFeeAction, SwapAction, TokenMillEnv},
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const MAX_IMPACT_FEE: u16 = 1_000;
+    const IMPACT_FEE_THRESHOLD_BPS: u16 = 100;
+    const IMPACT_FEE_RATE_BPS: u16 = 1_000;
+
+    /// Quote amount paid by bob for `base_amount`, once a thousandth of the supply has been bought
+    fn buy_cost(with_impact_fee: bool, base_amount: u64) -> u64 {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                1_000_000_000,
+                u64::MAX,
+                None,
+            )])
+            .unwrap();
+
+        if with_impact_fee {
+            testing_env.svm.change_payer("alice");
+
+            testing_env
+                .svm
+                .execute_actions(&[&SetImpactFeeAction::new(
+                    &testing_env,
+                    MAX_IMPACT_FEE,
+                    IMPACT_FEE_THRESHOLD_BPS,
+                    IMPACT_FEE_RATE_BPS,
+                )])
+                .unwrap();
+
+            testing_env.svm.change_payer("bob");
+        }
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &testing_env.svm.payer);
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                base_amount,
+                u64::MAX,
+                None,
+            )])
+            .unwrap();
+
+        balance_before
+            - testing_env
+                .svm
+                .get_balance(&quote_token_mint, &testing_env.svm.payer)
+    }
+
+    #[test]
+    fn set_impact_fee() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetImpactFeeAction::new(
+                &testing_env,
+                MAX_IMPACT_FEE,
+                IMPACT_FEE_THRESHOLD_BPS,
+                IMPACT_FEE_RATE_BPS,
+            )])
+            .unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.max_impact_fee_bps, MAX_IMPACT_FEE);
+        assert_eq!(market.impact_fee_threshold_bps, IMPACT_FEE_THRESHOLD_BPS);
+        assert_eq!(market.impact_fee_rate_bps, IMPACT_FEE_RATE_BPS);
+    }
+
+    #[test]
+    fn small_buy_without_impact_fee() {
+        // Moves the price by about 5 bps
+        let base_amount = 1_000_000;
+
+        assert_eq!(buy_cost(true, base_amount), buy_cost(false, base_amount));
+    }
+
+    #[test]
+    fn large_buy_with_impact_fee() {
+        // Moves the price well above the threshold, the fee is capped
+        let base_amount = 10_000_000_000;
+
+        let cost = buy_cost(false, base_amount);
+        let cost_with_impact_fee = buy_cost(true, base_amount);
+
+        assert_eq!(
+            cost_with_impact_fee,
+            (cost * 10_000).div_ceil(10_000 - u64::from(MAX_IMPACT_FEE))
+        );
+    }
+
+    #[test]
+    fn set_invalid_impact_fee() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        for (max_impact_fee_bps, impact_fee_rate_bps) in [
+            (MAX_IMPACT_FEE_BPS + 1, IMPACT_FEE_RATE_BPS),
+            (MAX_IMPACT_FEE, 0),
+        ] {
+            let result = testing_env.svm.execute_actions(&[&SetImpactFeeAction::new(
+                &testing_env,
+                max_impact_fee_bps,
+                IMPACT_FEE_THRESHOLD_BPS,
+                impact_fee_rate_bps,
+            )]);
+
+            assert_eq!(
+                tm_parse_error(result).unwrap(),
+                TokenMillError::InvalidImpactFee
+            );
+        }
+    }
+
+    #[test]
+    fn set_impact_fee_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::default();
+
+        let mut action = SetImpactFeeAction::new(
+            &testing_env,
+            MAX_IMPACT_FEE,
+            IMPACT_FEE_THRESHOLD_BPS,
+            IMPACT_FEE_RATE_BPS,
+        );
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
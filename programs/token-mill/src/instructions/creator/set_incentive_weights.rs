@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillIncentiveWeightsUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_incentive_weights, ChangeLogParameter},
+};
+
+use super::MarketSettingsUpdate;
+
+/// Sets the weights of the incentive events emitted on buys, sells and stakes, in bps of the activity amount
+pub fn handler(
+    ctx: Context<MarketSettingsUpdate>,
+    buy_weight: u16,
+    sell_weight: u16,
+    stake_weight: u16,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    let old_incentive_weights = encode_incentive_weights(&market.incentive_weights);
+
+    market.set_incentive_weights(buy_weight, sell_weight, stake_weight);
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::IncentiveWeights,
+        old_incentive_weights,
+        encode_incentive_weights(&market.incentive_weights),
+    )?;
+
+    emit_cpi!(TokenMillIncentiveWeightsUpdateEvent {
+        market: ctx.accounts.market.key(),
+        buy_weight,
+        sell_weight,
+        stake_weight,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, SetIncentiveWeightsAction, SwapAction, TokenMillEnv,
+        },
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const BUY_WEIGHT: u16 = 10_000;
+    const SELL_WEIGHT: u16 = 5_000;
+    const STAKE_WEIGHT: u16 = 20_000;
+
+    fn setup_env() -> (TokenMillEnv, SetIncentiveWeightsAction) {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("alice");
+
+        let action = SetIncentiveWeightsAction::new(BUY_WEIGHT, SELL_WEIGHT, STAKE_WEIGHT);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn set_incentive_weights() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.incentive_weights.buy_weight, BUY_WEIGHT);
+        assert_eq!(market.incentive_weights.sell_weight, SELL_WEIGHT);
+        assert_eq!(market.incentive_weights.stake_weight, STAKE_WEIGHT);
+    }
+
+    #[test]
+    fn swap_with_incentive_weights() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        swap_action.swap_type = SwapType::Sell;
+        swap_action.swap_amount_type = SwapAmountType::ExactInput;
+        swap_action.other_amount_threshold = 0;
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+    }
+
+    #[test]
+    fn set_incentive_weights_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let err = tm_parse_error(result).unwrap();
+
+        assert_eq!(err, TokenMillError::InvalidAuthority);
+    }
+}
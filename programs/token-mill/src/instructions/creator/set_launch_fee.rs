@@ -0,0 +1,254 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillLaunchFeeUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_launch_fee, ChangeLogParameter, LaunchFeeRecipient},
+};
+
+use super::MarketSettingsUpdate;
+
+/// Charges a surcharge of `launch_fee_bps` on the quote amount of swaps, decaying linearly to 0 over `duration`
+/// seconds from the first swap, and added to the pending fees of `recipient`. Has to be called before the first swap
+pub fn handler(
+    ctx: Context<MarketSettingsUpdate>,
+    launch_fee_bps: u16,
+    duration: u32,
+    recipient: LaunchFeeRecipient,
+) -> Result<()> {
+    #[cfg(not(feature = "staking"))]
+    require!(
+        recipient != LaunchFeeRecipient::Staking,
+        TokenMillError::InvalidLaunchFee
+    );
+
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    let old_value = encode_launch_fee(
+        market.launch_fee_bps,
+        market.launch_fee_recipient,
+        market.launch_fee_duration,
+    );
+
+    market.set_launch_fee(launch_fee_bps, duration, recipient)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::LaunchFee,
+        old_value,
+        encode_launch_fee(launch_fee_bps, recipient as u8, duration),
+    )?;
+
+    emit_cpi!(TokenMillLaunchFeeUpdateEvent {
+        market: ctx.accounts.market.key(),
+        launch_fee_bps,
+        duration,
+        recipient,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        constant::{MAX_LAUNCH_FEE_BPS, MAX_LAUNCH_FEE_DURATION},
+        state::LaunchFeeRecipient,
+        Market,
+    };
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, SetLaunchFeeAction, SwapAction, TokenMillEnv},
+        SwapAmountType, SwapType, TokenMillError,
+    };
+    use rstest::rstest;
+
+    const LAUNCH_FEE_BPS: u16 = 3_000;
+    const DURATION: u32 = 600;
+    const QUOTE_AMOUNT: u64 = 1_000_000_000;
+
+    fn setup_env(recipient: LaunchFeeRecipient) -> (TokenMillEnv, SwapAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetLaunchFeeAction::new(
+                &testing_env,
+                LAUNCH_FEE_BPS,
+                DURATION,
+                recipient,
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactInput,
+            QUOTE_AMOUNT,
+            0,
+            None,
+        );
+
+        (testing_env, swap_action)
+    }
+
+    fn pending_fees(testing_env: &TokenMillEnv) -> (u64, u64) {
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        (
+            market.fees.pending_creator_fees,
+            market.fees.pending_staking_fees,
+        )
+    }
+
+    #[rstest]
+    fn set_launch_fee(
+        #[values(LaunchFeeRecipient::Creator, LaunchFeeRecipient::Staking)]
+        recipient: LaunchFeeRecipient,
+    ) {
+        let (mut testing_env, swap_action) = setup_env(recipient);
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.launch_fee_bps, LAUNCH_FEE_BPS);
+        assert_eq!(market.launch_fee_duration, DURATION);
+        assert_eq!(market.launch_fee_recipient, recipient as u8);
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        // The whole launch fee of the first swap is charged
+        let launch_fee = QUOTE_AMOUNT * u64::from(LAUNCH_FEE_BPS) / 10_000;
+        let (pending_creator_fees, pending_staking_fees) = pending_fees(&testing_env);
+
+        assert_eq!(
+            market.launch_fee_start,
+            testing_env.svm.get_clock().unix_timestamp
+        );
+        assert!(market.quote_raised <= QUOTE_AMOUNT - launch_fee);
+
+        match recipient {
+            LaunchFeeRecipient::Creator => assert!(pending_creator_fees >= launch_fee),
+            LaunchFeeRecipient::Staking => assert!(pending_staking_fees >= launch_fee),
+        }
+    }
+
+    #[test]
+    fn launch_fee_decays() {
+        let (mut testing_env, swap_action) = setup_env(LaunchFeeRecipient::Creator);
+
+        let mut quote_raised = 0;
+        let mut previous_launch_fee = u64::MAX;
+
+        // The launch fee of each swap is the pending creator fees left over by the curve
+        for _ in 0..4 {
+            let (pending_creator_fees_before, _) = pending_fees(&testing_env);
+
+            testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+            let market = testing_env
+                .svm
+                .get_parsed_account::<Market>(&testing_env.market);
+
+            let launch_fee = QUOTE_AMOUNT - (market.quote_raised - quote_raised);
+
+            assert!(launch_fee < previous_launch_fee);
+            assert!(market.fees.pending_creator_fees - pending_creator_fees_before >= launch_fee);
+
+            quote_raised = market.quote_raised;
+            previous_launch_fee = launch_fee;
+
+            testing_env.svm.warp(i64::from(DURATION) / 4);
+        }
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        // Once the decay is over, the schedule is cleared
+        assert_eq!(market.launch_fee_bps, 0);
+        assert!(QUOTE_AMOUNT - (market.quote_raised - quote_raised) < previous_launch_fee);
+    }
+
+    #[rstest]
+    fn set_invalid_launch_fee(
+        #[values(
+            (MAX_LAUNCH_FEE_BPS + 1, DURATION),
+            (LAUNCH_FEE_BPS, 0),
+            (0, DURATION),
+            (LAUNCH_FEE_BPS, MAX_LAUNCH_FEE_DURATION + 1)
+        )]
+        launch_fee: (u16, u32),
+    ) {
+        let (launch_fee_bps, duration) = launch_fee;
+
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&SetLaunchFeeAction::new(
+            &testing_env,
+            launch_fee_bps,
+            duration,
+            LaunchFeeRecipient::Creator,
+        )]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidLaunchFee
+        );
+    }
+
+    #[test]
+    fn set_launch_fee_after_first_swap() {
+        let (mut testing_env, swap_action) = setup_env(LaunchFeeRecipient::Creator);
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&SetLaunchFeeAction::new(
+            &testing_env,
+            0,
+            0,
+            LaunchFeeRecipient::Creator,
+        )]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketAlreadyTraded
+        );
+    }
+
+    #[test]
+    fn set_launch_fee_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::default();
+
+        let mut action = SetLaunchFeeAction::new(
+            &testing_env,
+            LAUNCH_FEE_BPS,
+            DURATION,
+            LaunchFeeRecipient::Creator,
+        );
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -1,8 +1,11 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    constant::PRICES_LENGTH, errors::TokenMillError, events::TokenMillMarketPriceSetEvent,
-    state::Market,
+    errors::TokenMillError,
+    events::TokenMillMarketPriceSetEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter, Market},
+    TokenMillConfig, CHANGE_LOG_PDA_SEED,
 };
 
 #[event_cpi]
@@ -11,22 +14,75 @@ pub struct MarketSettingsUpdate<'info> {
     #[account(mut, has_one = creator @ TokenMillError::InvalidAuthority)]
     pub market: AccountLoader<'info, Market>,
 
+    /// CHECK: Change log of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [CHANGE_LOG_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub change_log: UncheckedAccount<'info>,
+
+    pub creator: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetMarketPrices<'info> {
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        mut,
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = creator @ TokenMillError::InvalidAuthority
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: Change log of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [CHANGE_LOG_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub change_log: UncheckedAccount<'info>,
+
     pub creator: Signer<'info>,
+
+    /// Launchpad recorded at market creation, must co-sign the price curve if any
+    pub launchpad: Option<Signer<'info>>,
 }
 
 pub fn handler(
-    ctx: Context<MarketSettingsUpdate>,
-    bid_prices: [u64; PRICES_LENGTH],
-    ask_prices: [u64; PRICES_LENGTH],
+    ctx: Context<SetMarketPrices>,
+    bid_prices: Vec<u64>,
+    ask_prices: Vec<u64>,
 ) -> Result<()> {
     let market = &mut ctx.accounts.market.load_mut()?;
 
-    market.check_and_set_prices(bid_prices, ask_prices)?;
+    if market.launchpad != Pubkey::default() {
+        require!(
+            ctx.accounts
+                .launchpad
+                .as_ref()
+                .is_some_and(|launchpad| launchpad.key() == market.launchpad),
+            TokenMillError::InvalidLaunchpad
+        );
+    }
+
+    market.check_and_set_prices(&bid_prices, &ask_prices, ctx.accounts.config.max_spread_bps)?;
+
+    // The full curve is in the event, the change log only keeps the highest ask price
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::MarketPrices,
+        encode_u64(0),
+        encode_u64(market.max_ask_price()),
+    )?;
 
     emit_cpi!(TokenMillMarketPriceSetEvent {
         market: ctx.accounts.market.key(),
         bid_prices,
         ask_prices,
+        prices_hash: market.prices_hash,
     });
 
     Ok(())
@@ -42,14 +98,19 @@ mod tests {
         joelana_env::{
             actions::token_mill::{
                 tm_parse_error, CreateMarketAction, CreateQuoteAssetBadgeAction,
-                SetMarketPricesAction, TokenMillEnv,
+                SetMarketPricesAction, TokenMillEnv, UpdateMaxSpreadAction,
             },
-            TokenMillError,
+            make_address, TokenMillError,
         },
         utils::token_mill::curve_generator::Curve,
     };
+    use rstest::rstest;
 
     fn setup_env() -> (TokenMillEnv, SetMarketPricesAction) {
+        setup_env_with_launchpad(None)
+    }
+
+    fn setup_env_with_launchpad(launchpad: Option<&str>) -> (TokenMillEnv, SetMarketPricesAction) {
         let mut testing_env = TokenMillEnv::new().with_default_quote_token_mint();
 
         let action = CreateQuoteAssetBadgeAction::new(testing_env.quote_token_mint.unwrap());
@@ -58,7 +119,11 @@ mod tests {
 
         testing_env.svm.change_payer("alice");
 
-        let create_market_action = CreateMarketAction::new(&testing_env);
+        let mut create_market_action = CreateMarketAction::new(&testing_env);
+
+        if let Some(launchpad) = launchpad {
+            create_market_action.with_launchpad(make_address(launchpad));
+        }
 
         testing_env
             .svm
@@ -86,6 +151,102 @@ mod tests {
         assert_eq!(market.ask_prices[2], action.price_curve.ask_prices[2]);
     }
 
+    #[rstest]
+    fn set_market_prices_with_finer_curve(#[values(21, 51)] prices_length: usize) {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.price_curve = Curve::linear(prices_length);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env.svm.get_parsed_account::<Market>(&action.market);
+
+        assert!(market.are_prices_set());
+        assert_eq!(market.interval_number, prices_length as u64 - 1);
+        assert_eq!(
+            market.bid_prices[..prices_length],
+            action.price_curve.bid_prices
+        );
+        assert_eq!(
+            market.ask_prices[..prices_length],
+            action.price_curve.ask_prices
+        );
+        assert_eq!(
+            market.max_ask_price(),
+            Curve::default().ask_prices[INTERVAL_NUMBER as usize]
+        );
+    }
+
+    #[test]
+    fn set_market_prices_with_flat_segment() {
+        let (mut testing_env, mut action) = setup_env();
+
+        // Fixed-price presale tranche over the first three intervals, followed by the increasing tail
+        for i in 0..4 {
+            action.price_curve.bid_prices[i] = action.price_curve.bid_prices[3];
+            action.price_curve.ask_prices[i] = action.price_curve.ask_prices[3];
+        }
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env.svm.get_parsed_account::<Market>(&action.market);
+
+        assert_eq!(market.ask_prices[0], market.ask_prices[3]);
+        assert!(market.ask_prices[4] > market.ask_prices[3]);
+        assert_eq!(
+            market.get_spot_prices().unwrap(),
+            (market.ask_prices[0], market.bid_prices[0])
+        );
+    }
+
+    #[rstest]
+    fn set_market_prices_with_invalid_resolution(#[values(2, 12, 50)] prices_length: usize) {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.price_curve = Curve::linear(prices_length);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidCurveResolution
+        );
+    }
+
+    #[test]
+    fn set_market_prices_with_launchpad() {
+        let (mut testing_env, action) = setup_env_with_launchpad(Some("carol"));
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&action.with_launchpad(make_address("carol"))]);
+
+        assert!(result.is_ok());
+    }
+
+    #[rstest]
+    fn set_market_prices_with_invalid_launchpad(
+        #[values(None, Some("mallory"))] signer: Option<&str>,
+    ) {
+        let (mut testing_env, mut action) = setup_env_with_launchpad(Some("carol"));
+
+        if let Some(signer) = signer {
+            action = action.with_launchpad(make_address(signer));
+        }
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidLaunchpad);
+    }
+
     #[test]
     fn set_market_prices_twice() {
         let (mut testing_env, action) = setup_env();
@@ -164,6 +325,32 @@ mod tests {
         assert_eq!(error, TokenMillError::PriceTooHigh);
     }
 
+    #[rstest]
+    fn set_market_prices_with_max_spread(#[values(500, 1_000)] max_spread_bps: u16) {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&UpdateMaxSpreadAction::new(max_spread_bps)])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        // The default curve bids 10% below the asks
+        if max_spread_bps < 1_000 {
+            assert_eq!(
+                tm_parse_error(result).unwrap(),
+                TokenMillError::SpreadTooWide
+            );
+        } else {
+            assert!(result.is_ok());
+        }
+    }
+
     #[test]
     fn set_market_prices_with_invalid_signer() {
         let (mut testing_env, mut action) = setup_env();
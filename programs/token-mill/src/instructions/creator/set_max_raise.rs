@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillMaxRaiseUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::MarketSettingsUpdate;
+
+pub fn handler(ctx: Context<MarketSettingsUpdate>, max_raise: u64) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    let old_max_raise = market.max_raise;
+
+    market.set_max_raise(max_raise)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::MaxRaise,
+        encode_u64(old_max_raise),
+        encode_u64(max_raise),
+    )?;
+
+    emit_cpi!(TokenMillMaxRaiseUpdateEvent {
+        market: ctx.accounts.market.key(),
+        new_max_raise: max_raise,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, SetMaxRaiseAction, SwapAction, TokenMillEnv},
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const MAX_RAISE: u64 = 1_000_000_000;
+
+    fn setup_env() -> (TokenMillEnv, SetMaxRaiseAction) {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("alice");
+
+        let action = SetMaxRaiseAction::new(MAX_RAISE);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn set_max_raise() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.max_raise, MAX_RAISE);
+    }
+
+    #[test]
+    fn buy_until_max_raise() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactInput,
+            MAX_RAISE * 2,
+            0,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert!(market.is_max_raise_reached());
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::MaxRaiseReached);
+    }
+
+    #[test]
+    fn set_max_raise_below_quote_raised() {
+        let (mut testing_env, mut action) = setup_env();
+
+        testing_env.svm.change_payer("bob");
+
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactInput,
+            MAX_RAISE,
+            0,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        action.max_raise = MAX_RAISE / 2;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidMaxRaise);
+    }
+
+    #[test]
+    fn set_max_raise_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
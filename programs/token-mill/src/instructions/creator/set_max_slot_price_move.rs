@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillSlotPriceMoveLimitUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::MarketSettingsUpdate;
+
+/// Sets how far the ask price can move within a slot before further swaps of the slot are rejected, in bps.
+/// 0 disables the guard
+pub fn handler(ctx: Context<MarketSettingsUpdate>, max_slot_price_move_bps: u16) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    let old_max_slot_price_move_bps = market.max_slot_price_move_bps;
+
+    market.set_max_slot_price_move(max_slot_price_move_bps, Clock::get()?.slot)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::SlotPriceMoveLimit,
+        encode_u64(old_max_slot_price_move_bps.into()),
+        encode_u64(max_slot_price_move_bps.into()),
+    )?;
+
+    emit_cpi!(TokenMillSlotPriceMoveLimitUpdateEvent {
+        market: ctx.accounts.market.key(),
+        max_slot_price_move_bps,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, SetMaxSlotPriceMoveAction, SwapAction, TokenMillEnv,
+        },
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const MAX_SLOT_PRICE_MOVE_BPS: u16 = 100;
+
+    fn setup_env() -> (TokenMillEnv, SwapAction) {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("bob");
+
+        // Moves the curve away from its zero starting price
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            100_000_000_000,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetMaxSlotPriceMoveAction::new(MAX_SLOT_PRICE_MOVE_BPS)])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        swap_action.amount = 10_000_000_000;
+
+        (testing_env, swap_action)
+    }
+
+    #[test]
+    fn set_max_slot_price_move() {
+        let (testing_env, _) = setup_env();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.max_slot_price_move_bps, MAX_SLOT_PRICE_MOVE_BPS);
+        assert_eq!(market.guarded_slot, testing_env.svm.get_clock().slot);
+    }
+
+    #[test]
+    fn swap_after_slot_price_move() {
+        let (mut testing_env, mut swap_action) = setup_env();
+
+        // The swap moving the price above the limit goes through
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        swap_action.swap_type = SwapType::Sell;
+        swap_action.swap_amount_type = SwapAmountType::ExactInput;
+        swap_action.amount = 1_000_000_000;
+        swap_action.other_amount_threshold = 0;
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::SlotPriceMoveExceeded
+        );
+
+        let slot = testing_env.svm.get_clock().slot;
+        testing_env.svm.warp_to_slot(slot + 1);
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+    }
+
+    #[test]
+    fn swap_within_slot_price_move() {
+        let (mut testing_env, mut swap_action) = setup_env();
+
+        swap_action.amount = 1_000_000;
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+    }
+
+    #[test]
+    fn set_max_slot_price_move_with_invalid_signer() {
+        let (mut testing_env, _) = setup_env();
+
+        let mut action = SetMaxSlotPriceMoveAction::new(0);
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
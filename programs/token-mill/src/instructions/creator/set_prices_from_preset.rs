@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+use crate::manager::curve_manager::{self, PricePreset};
+
+use super::{set_market_prices, SetMarketPrices};
+
+/// Sets the market prices to a `preset` curve of `prices_length` prices computed on-chain,
+/// going from `min_price` to `max_price` with bid prices `spread_bps` below the ask prices.
+/// The generated curve goes through the same checks as the ones set with `set_market_prices`.
+pub fn handler(
+    ctx: Context<SetMarketPrices>,
+    preset: PricePreset,
+    prices_length: u8,
+    min_price: u64,
+    max_price: u64,
+    spread_bps: u16,
+) -> Result<()> {
+    let (bid_prices, ask_prices) = curve_manager::generate_prices(
+        preset,
+        prices_length.into(),
+        min_price,
+        max_price,
+        spread_bps,
+    )?;
+
+    set_market_prices::handler(ctx, bid_prices, ask_prices)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constant::MAX_PRICES_LENGTH, manager::curve_manager::PricePreset, Market};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateMarketAction, CreateQuoteAssetBadgeAction,
+            SetPricesFromPresetAction, TokenMillEnv,
+        },
+        TokenMillError,
+    };
+    use rstest::rstest;
+
+    fn setup_env() -> (TokenMillEnv, SetPricesFromPresetAction) {
+        let mut testing_env = TokenMillEnv::new().with_default_quote_token_mint();
+
+        let action = CreateQuoteAssetBadgeAction::new(testing_env.quote_token_mint.unwrap());
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let create_market_action = CreateMarketAction::new(&testing_env);
+
+        testing_env
+            .svm
+            .execute_actions(&[&create_market_action])
+            .unwrap();
+
+        let action = SetPricesFromPresetAction::new(PricePreset::Linear);
+
+        (testing_env, action)
+    }
+
+    #[rstest]
+    fn set_prices_from_preset(
+        #[values(
+            PricePreset::Linear,
+            PricePreset::Exponential,
+            PricePreset::Logistic,
+            PricePreset::ConstantProduct
+        )]
+        preset: PricePreset,
+    ) {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.preset = preset;
+        action.prices_length = MAX_PRICES_LENGTH as u8;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env.svm.get_parsed_account::<Market>(&action.market);
+
+        assert!(market.are_prices_set());
+        assert_eq!(market.prices_length(), MAX_PRICES_LENGTH);
+        assert_eq!(market.ask_prices[0], action.min_price);
+        assert_eq!(market.max_ask_price(), action.max_price);
+        assert!(market.bid_prices[1] < market.ask_prices[1]);
+    }
+
+    #[test]
+    fn set_prices_from_preset_with_invalid_parameters() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.min_price = action.max_price + 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidPricePreset
+        );
+    }
+
+    #[test]
+    fn set_prices_from_preset_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
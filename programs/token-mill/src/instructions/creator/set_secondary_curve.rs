@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillSecondaryCurveUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::MarketSettingsUpdate;
+
+/// Keeps selling the supply left unsold by the graduation at a flat price rather than burning it, the proceeds going
+/// to the stakers. Only before the graduation, a price of 0 burning the unsold supply again
+pub fn handler(ctx: Context<MarketSettingsUpdate>, price: u64) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    let old_price = market.secondary_curve_price;
+
+    market.set_secondary_curve_price(price)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::SecondaryCurvePrice,
+        encode_u64(old_price),
+        encode_u64(price),
+    )?;
+
+    emit_cpi!(TokenMillSecondaryCurveUpdateEvent {
+        market: ctx.accounts.market.key(),
+        new_price: price,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, SetSecondaryCurveAction, TokenMillEnv},
+        TokenMillError,
+    };
+
+    fn setup_env() -> (TokenMillEnv, SetSecondaryCurveAction, u64) {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("alice");
+
+        let max_ask_price = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market)
+            .max_ask_price();
+
+        let action = SetSecondaryCurveAction::new(&testing_env, max_ask_price);
+
+        (testing_env, action, max_ask_price)
+    }
+
+    #[test]
+    fn set_secondary_curve() {
+        let (mut testing_env, action, max_ask_price) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.secondary_curve_price, max_ask_price);
+    }
+
+    #[test]
+    fn set_secondary_curve_below_curve() {
+        let (mut testing_env, mut action, max_ask_price) = setup_env();
+
+        action.price = max_ask_price - 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidSecondaryCurvePrice);
+    }
+
+    #[test]
+    fn set_secondary_curve_with_invalid_signer() {
+        let (mut testing_env, mut action, _) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
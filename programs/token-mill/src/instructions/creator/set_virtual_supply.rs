@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::TokenMillVirtualSupplyUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::MarketSettingsUpdate;
+
+/// Offsets the start of the curve by `virtual_supply`, has to be called before `set_market_prices`
+pub fn handler(ctx: Context<MarketSettingsUpdate>, virtual_supply: u64) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    let old_virtual_supply = market.virtual_supply;
+
+    market.set_virtual_supply(virtual_supply)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::VirtualSupply,
+        encode_u64(old_virtual_supply),
+        encode_u64(virtual_supply),
+    )?;
+
+    emit_cpi!(TokenMillVirtualSupplyUpdateEvent {
+        market: ctx.accounts.market.key(),
+        new_virtual_supply: virtual_supply,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::{
+        joelana_env::{
+            actions::token_mill::{
+                tm_parse_error, CreateMarketAction, CreateQuoteAssetBadgeAction,
+                SetMarketPricesAction, SetVirtualSupplyAction, TokenMillEnv, DEFAULT_TOTAL_SUPPLY,
+            },
+            TokenMillError,
+        },
+        utils::token_mill::curve_generator::Curve,
+    };
+
+    const VIRTUAL_SUPPLY: u64 = DEFAULT_TOTAL_SUPPLY / 4;
+
+    fn setup_env() -> (TokenMillEnv, SetVirtualSupplyAction) {
+        let mut testing_env = TokenMillEnv::new().with_default_quote_token_mint();
+
+        let action = CreateQuoteAssetBadgeAction::new(testing_env.quote_token_mint.unwrap());
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let create_market_action = CreateMarketAction::new(&testing_env);
+
+        testing_env
+            .svm
+            .execute_actions(&[&create_market_action])
+            .unwrap();
+
+        let action = SetVirtualSupplyAction::new(VIRTUAL_SUPPLY);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn set_virtual_supply() {
+        let (mut testing_env, action) = setup_env();
+
+        let curve = Curve::default();
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&action, &SetMarketPricesAction::new(curve.clone())]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env.svm.get_parsed_account::<Market>(&action.market);
+
+        assert_eq!(market.virtual_supply, VIRTUAL_SUPPLY);
+        assert_eq!(market.curve_supply(), VIRTUAL_SUPPLY);
+        // A fifth of the curve is virtual, the market starts at its third price
+        assert_eq!(
+            market.get_spot_prices().unwrap(),
+            (curve.ask_prices[2], curve.bid_prices[2])
+        );
+    }
+
+    #[test]
+    fn set_virtual_supply_after_prices() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetMarketPricesAction::new(Curve::default())])
+            .unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::PricesAlreadySet
+        );
+    }
+
+    #[test]
+    fn set_virtual_supply_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -1,13 +1,26 @@
 use anchor_lang::prelude::*;
 
-use crate::events::TokenMillCreatorUpdateEvent;
+use crate::{
+    events::TokenMillCreatorUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, ChangeLogParameter},
+};
 
 use super::MarketSettingsUpdate;
 
+/// Hands the market over at once, cancelling any pending proposal. See `propose_creator` for the two-step transfer
 pub fn handler(ctx: Context<MarketSettingsUpdate>, new_creator: Pubkey) -> Result<()> {
     let market = &mut ctx.accounts.market.load_mut()?;
 
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::Creator,
+        encode_pubkey(Some(market.creator)),
+        encode_pubkey(Some(new_creator)),
+    )?;
+
     market.creator = new_creator;
+    market.pending_creator = Pubkey::default();
 
     emit_cpi!(TokenMillCreatorUpdateEvent {
         market: ctx.accounts.market.key(),
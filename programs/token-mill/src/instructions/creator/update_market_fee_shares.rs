@@ -1,33 +1,90 @@
 use anchor_lang::prelude::*;
 
-use crate::{errors::TokenMillError, events::TokenMillMarketFeeSharesUpdateEvent};
-
-use super::MarketSettingsUpdate;
+use crate::{
+    errors::TokenMillError,
+    events::{TokenMillMarketFeeSharesProposalEvent, TokenMillMarketFeeSharesUpdateEvent},
+    manager::change_log_manager::record_change,
+    state::{encode_fee_shares, ChangeLogParameter, Market},
+    TokenMillConfig, CHANGE_LOG_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MarketFeeSharesUpdate<'info> {
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        mut,
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = creator @ TokenMillError::InvalidAuthority
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: Change log of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [CHANGE_LOG_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub change_log: UncheckedAccount<'info>,
+
+    pub creator: Signer<'info>,
+}
 
+/// Applies the new fee shares at once if the config has no `fee_shares_update_delay`.
+/// Otherwise proposes them, `activate_market_fee_shares` applying them once the delay has elapsed.
 pub fn handler(
-    ctx: Context<MarketSettingsUpdate>,
+    ctx: Context<MarketFeeSharesUpdate>,
     new_creator_fee_share: u16,
     new_staking_fee_share: u16,
 ) -> Result<()> {
     let market = &mut ctx.accounts.market.load_mut()?;
 
-    // Total fee share (creator + staking + protocol fee shares) must always be 100%,
-    // and since the protocol fee share cannot be changed after market creation,
-    // creator_fee_share + staking_fee_share must be constant.
-    require_eq!(
-        new_creator_fee_share + new_staking_fee_share,
-        market.fees.creator_fee_share + market.fees.staking_fee_share,
-        TokenMillError::InvalidFeeShare
-    );
+    #[cfg(not(feature = "staking"))]
+    require!(new_staking_fee_share == 0, TokenMillError::InvalidFeeShare);
 
-    market.fees.creator_fee_share = new_creator_fee_share;
-    market.fees.staking_fee_share = new_staking_fee_share;
+    let fee_shares_update_delay = ctx.accounts.config.fee_shares_update_delay;
 
-    emit_cpi!(TokenMillMarketFeeSharesUpdateEvent {
-        market: ctx.accounts.market.key(),
-        new_creator_fee_share,
-        new_staking_fee_share,
-    });
+    // Total fee share (creator + staking + burn + protocol fee shares) must always be 100%,
+    // and since the protocol fee share cannot be changed after market creation and the burn fee share is set apart,
+    // creator_fee_share + staking_fee_share must be constant.
+    if fee_shares_update_delay == 0 {
+        let old_value =
+            encode_fee_shares(market.fees.creator_fee_share, market.fees.staking_fee_share);
+
+        market
+            .fees
+            .update_fee_shares(new_creator_fee_share, new_staking_fee_share)?;
+        market.cancel_fee_shares_proposal();
+
+        record_change(
+            &ctx.accounts.change_log,
+            ChangeLogParameter::MarketFeeShares,
+            old_value,
+            encode_fee_shares(new_creator_fee_share, new_staking_fee_share),
+        )?;
+
+        emit_cpi!(TokenMillMarketFeeSharesUpdateEvent {
+            market: ctx.accounts.market.key(),
+            new_creator_fee_share,
+            new_staking_fee_share,
+        });
+    } else {
+        let activation_time = Clock::get()?.unix_timestamp + i64::from(fee_shares_update_delay);
+
+        market.propose_fee_shares(
+            new_creator_fee_share,
+            new_staking_fee_share,
+            activation_time,
+        )?;
+
+        emit_cpi!(TokenMillMarketFeeSharesProposalEvent {
+            market: ctx.accounts.market.key(),
+            new_creator_fee_share,
+            new_staking_fee_share,
+            activation_time,
+        });
+    }
 
     Ok(())
 }
@@ -36,12 +93,16 @@ pub fn handler(
 mod tests {
     use crate::Market;
     use joelana_test_utils::joelana_env::{
-        actions::token_mill::{tm_parse_error, TokenMillEnv, UpdateMarketFeeSharesAction},
+        actions::token_mill::{
+            tm_parse_error, TokenMillEnv, UpdateFeeSharesUpdateDelayAction,
+            UpdateMarketFeeSharesAction,
+        },
         TokenMillError,
     };
 
     const NEW_CREATOR_FEE_SHARE: u16 = 3_000;
     const NEW_STAKING_FEE_SHARE: u16 = 6_000;
+    const FEE_SHARES_UPDATE_DELAY: u32 = 86_400;
 
     fn setup_env() -> (TokenMillEnv, UpdateMarketFeeSharesAction) {
         let mut testing_env = TokenMillEnv::default();
@@ -68,6 +129,39 @@ mod tests {
         assert_eq!(market.fees.staking_fee_share, NEW_STAKING_FEE_SHARE);
     }
 
+    #[test]
+    fn propose_market_fee_shares() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&UpdateFeeSharesUpdateDelayAction::new(
+                FEE_SHARES_UPDATE_DELAY,
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.fees.creator_fee_share, 4_500);
+        assert_eq!(market.fees.staking_fee_share, 4_500);
+        assert_eq!(market.pending_creator_fee_share, NEW_CREATOR_FEE_SHARE);
+        assert_eq!(market.pending_staking_fee_share, NEW_STAKING_FEE_SHARE);
+        assert_eq!(
+            market.fee_shares_activation_time,
+            testing_env.svm.get_clock().unix_timestamp + i64::from(FEE_SHARES_UPDATE_DELAY)
+        );
+    }
+
     #[test]
     fn update_market_fee_shares_with_invalid_distribution() {
         let (mut testing_env, mut action) = setup_env();
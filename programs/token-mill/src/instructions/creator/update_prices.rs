@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillMarketPriceSetEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_u64, ChangeLogParameter},
+};
+
+use super::SetMarketPrices;
+
+/// Replaces the price curve of a market as long as none of its supply has been bought.
+/// Lets the creator fix a mistaken curve without redeploying the market.
+pub fn handler(
+    ctx: Context<SetMarketPrices>,
+    bid_prices: Vec<u64>,
+    ask_prices: Vec<u64>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market.load_mut()?;
+
+    if market.launchpad != Pubkey::default() {
+        require!(
+            ctx.accounts
+                .launchpad
+                .as_ref()
+                .is_some_and(|launchpad| launchpad.key() == market.launchpad),
+            TokenMillError::InvalidLaunchpad
+        );
+    }
+
+    let previous_max_ask_price = market.max_ask_price();
+
+    market.update_prices(&bid_prices, &ask_prices, ctx.accounts.config.max_spread_bps)?;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::MarketPrices,
+        encode_u64(previous_max_ask_price),
+        encode_u64(market.max_ask_price()),
+    )?;
+
+    emit_cpi!(TokenMillMarketPriceSetEvent {
+        market: ctx.accounts.market.key(),
+        bid_prices,
+        ask_prices,
+        prices_hash: market.prices_hash,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::{
+        joelana_env::{
+            actions::token_mill::{tm_parse_error, SwapAction, TokenMillEnv, UpdatePricesAction},
+            SwapAmountType, SwapType, TokenMillError,
+        },
+        utils::token_mill::curve_generator::Curve,
+    };
+
+    fn setup_env() -> (TokenMillEnv, UpdatePricesAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        let action = UpdatePricesAction::new(&testing_env, Curve::linear(21));
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn update_prices() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env.svm.get_parsed_account::<Market>(&action.market);
+
+        assert_eq!(market.prices_length(), 21);
+        assert_eq!(market.bid_prices[..21], action.price_curve.bid_prices);
+        assert_eq!(market.ask_prices[..21], action.price_curve.ask_prices);
+    }
+
+    #[test]
+    fn update_prices_after_buy() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.change_payer("bob");
+
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactInput,
+            1_000_000,
+            0,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketAlreadyTraded
+        );
+    }
+
+    #[test]
+    fn update_prices_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::TokenMillError;
+
+use super::SetMarketPrices;
+
+/// Dry run of `set_market_prices`, meant to be simulated by launch tooling.
+/// Runs the same account and price checks on a copy of the market and returns without writing anything.
+pub fn handler(
+    ctx: Context<SetMarketPrices>,
+    bid_prices: Vec<u64>,
+    ask_prices: Vec<u64>,
+) -> Result<()> {
+    let mut market = *ctx.accounts.market.load()?;
+
+    if market.launchpad != Pubkey::default() {
+        require!(
+            ctx.accounts
+                .launchpad
+                .as_ref()
+                .is_some_and(|launchpad| launchpad.key() == market.launchpad),
+            TokenMillError::InvalidLaunchpad
+        );
+    }
+
+    market.check_and_set_prices(&bid_prices, &ask_prices, ctx.accounts.config.max_spread_bps)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::{
+        joelana_env::{
+            actions::token_mill::{
+                tm_parse_error, CreateMarketAction, SetMarketPricesAction, TokenMillEnv,
+                ValidateMarketPricesAction,
+            },
+            TokenMillError,
+        },
+        utils::token_mill::curve_generator::Curve,
+    };
+
+    fn setup_env() -> (TokenMillEnv, ValidateMarketPricesAction) {
+        let mut testing_env = TokenMillEnv::new().with_default_quote_token_mint();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[CreateMarketAction::new(&testing_env).no_badge()])
+            .unwrap();
+
+        let action = ValidateMarketPricesAction::new(SetMarketPricesAction::new(Curve::default()));
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn validate_market_prices() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&action.set_prices_action.market);
+
+        assert!(!market.are_prices_set());
+    }
+
+    #[test]
+    fn validate_market_prices_with_invalid_prices() {
+        let (mut testing_env, mut action) = setup_env();
+
+        let curve = &mut action.set_prices_action.price_curve;
+        curve.bid_prices[1] = curve.ask_prices[1] + 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::BidAskMismatch
+        );
+    }
+
+    #[test]
+    fn validate_market_prices_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.set_prices_action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
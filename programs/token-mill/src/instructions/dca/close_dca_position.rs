@@ -0,0 +1,155 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillDcaPositionCloseEvent,
+    manager::token_manager::transfer_from_pda,
+    state::{DcaPosition, Market},
+    MARKET_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseDcaPosition<'info> {
+    #[account(mut, has_one = quote_token_mint @ TokenMillError::InvalidMintAccount)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = owner @ TokenMillError::InvalidAuthority,
+        close = owner
+    )]
+    pub dca_position: Account<'info, DcaPosition>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = quote_token_program
+    )]
+    pub owner_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Cancels the remaining buys of a DCA position, and returns their escrow to the owner
+pub fn handler(ctx: Context<CloseDcaPosition>) -> Result<()> {
+    let dca_position = &ctx.accounts.dca_position;
+
+    let (base_token_mint, bump) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        market.release_quote(dca_position.escrow_amount)?;
+
+        (market.base_token_mint, market.bump)
+    };
+
+    let market_seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint.as_ref(),
+        &[bump],
+    ];
+
+    if dca_position.escrow_amount > 0 {
+        transfer_from_pda(
+            &ctx.accounts.quote_token_mint,
+            ctx.accounts.market.to_account_info(),
+            &ctx.accounts.market_quote_token_ata,
+            &ctx.accounts.owner_quote_token_ata,
+            &ctx.accounts.quote_token_program,
+            dca_position.escrow_amount,
+            &market_seeds,
+        )?;
+    }
+
+    emit_cpi!(TokenMillDcaPositionCloseEvent {
+        dca_position: dca_position.key(),
+        amount_returned: dca_position.escrow_amount,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CloseDcaPositionAction, CreateDcaPositionAction, TokenMillEnv,
+        },
+        TokenMillError,
+    };
+
+    const AMOUNT_PER_BUY: u64 = 100_000_000;
+    const CRANK_BOUNTY: u64 = 10_000;
+
+    fn setup_env() -> (TokenMillEnv, CloseDcaPositionAction) {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("bob");
+
+        let create_action =
+            CreateDcaPositionAction::new(&testing_env, AMOUNT_PER_BUY, CRANK_BOUNTY, 3);
+
+        testing_env.svm.execute_actions(&[&create_action]).unwrap();
+
+        let action = CloseDcaPositionAction::new(&create_action);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn close_dca_position() {
+        let (mut testing_env, action) = setup_env();
+
+        let quote_balance_before = testing_env
+            .svm
+            .get_balance(&action.quote_token_mint, &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.quote_token_mint, &action.signer)
+                - quote_balance_before,
+            3 * (AMOUNT_PER_BUY + CRANK_BOUNTY)
+        );
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.dca_position)
+            .is_none());
+    }
+
+    #[test]
+    fn close_dca_position_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+        action.owner_quote_token_ata = testing_env
+            .svm
+            .get_ata_address(&action.quote_token_mint, &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillDcaPositionCreationEvent,
+    manager::token_manager::transfer_from_eoa,
+    state::{DcaPosition, Market, DCA_POSITION_PDA_SEED},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateDcaPosition<'info> {
+    #[account(
+        mut,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        init,
+        seeds = [
+            DCA_POSITION_PDA_SEED.as_bytes(),
+            market.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = 8 + DcaPosition::INIT_SPACE
+    )]
+    pub dca_position: Account<'info, DcaPosition>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // Created here so that cranks can always deliver the purchased tokens
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = base_token_program
+    )]
+    pub owner_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = quote_token_program
+    )]
+    pub owner_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Schedules `buys` buys of `amount_per_buy` quote tokens on the market, at least `interval` seconds apart.
+/// The quote tokens of all the buys and their crank bounties are escrowed in the market upfront.
+/// Buys averaging above `max_price` can't be executed.
+pub fn handler(
+    ctx: Context<CreateDcaPosition>,
+    amount_per_buy: u64,
+    crank_bounty: u64,
+    interval: i64,
+    max_price: u64,
+    buys: u32,
+) -> Result<()> {
+    let dca_position = &mut ctx.accounts.dca_position;
+
+    dca_position.initialize(
+        ctx.bumps.dca_position,
+        ctx.accounts.market.key(),
+        ctx.accounts.owner.key(),
+        amount_per_buy,
+        crank_bounty,
+        interval,
+        max_price,
+        buys,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    ctx.accounts
+        .market
+        .load_mut()?
+        .escrow_quote(dca_position.escrow_amount)?;
+
+    transfer_from_eoa(
+        &ctx.accounts.quote_token_mint,
+        &ctx.accounts.owner,
+        &ctx.accounts.owner_quote_token_ata,
+        &ctx.accounts.market_quote_token_ata,
+        &ctx.accounts.quote_token_program,
+        dca_position.escrow_amount,
+    )?;
+
+    emit_cpi!(TokenMillDcaPositionCreationEvent {
+        dca_position: dca_position.key(),
+        market: ctx.accounts.market.key(),
+        owner: ctx.accounts.owner.key(),
+        amount_per_buy,
+        crank_bounty,
+        interval,
+        max_price,
+        buys,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DcaPosition;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, CreateDcaPositionAction, TokenMillEnv},
+        TokenMillError,
+    };
+
+    const AMOUNT_PER_BUY: u64 = 100_000_000;
+    const CRANK_BOUNTY: u64 = 10_000;
+
+    #[test]
+    fn create_dca_position() {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("bob");
+
+        let action = CreateDcaPositionAction::new(&testing_env, AMOUNT_PER_BUY, CRANK_BOUNTY, 3);
+
+        let quote_balance_before = testing_env
+            .svm
+            .get_balance(&action.quote_token_mint, &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let dca_position = testing_env
+            .svm
+            .get_parsed_account::<DcaPosition>(&action.dca_position);
+
+        assert_eq!(dca_position.owner, action.signer);
+        assert_eq!(dca_position.buys_remaining, 3);
+        assert_eq!(
+            dca_position.escrow_amount,
+            3 * (AMOUNT_PER_BUY + CRANK_BOUNTY)
+        );
+        assert_eq!(
+            quote_balance_before
+                - testing_env
+                    .svm
+                    .get_balance(&action.quote_token_mint, &action.signer),
+            dca_position.escrow_amount
+        );
+    }
+
+    #[test]
+    fn create_dca_position_without_buys() {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("bob");
+
+        let action = CreateDcaPositionAction::new(&testing_env, AMOUNT_PER_BUY, CRANK_BOUNTY, 0);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidDcaPosition
+        );
+    }
+}
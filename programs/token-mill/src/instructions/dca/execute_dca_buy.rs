@@ -0,0 +1,431 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::{TokenMillDcaBuyEvent, TokenMillIncentiveEvent, TokenMillSwapEvent},
+    manager::{
+        oracle_manager, price_feed_manager,
+        swap_manager::{self, SwapAmountType, SwapType},
+        token_manager::transfer_from_pda,
+    },
+    state::{DcaPosition, Market},
+    TokenMillConfig, MARKET_ORACLE_PDA_SEED, MARKET_PDA_SEED, RESTRICTED_WALLET_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteDcaBuy<'info> {
+    #[account(constraint = !config.swaps_paused @ TokenMillError::SwapsPaused)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        mut,
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = owner @ TokenMillError::InvalidAuthority
+    )]
+    pub dca_position: Account<'info, DcaPosition>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = base_token_program
+    )]
+    pub owner_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = cranker,
+        associated_token::token_program = quote_token_program
+    )]
+    pub cranker_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = config.protocol_fee_recipient,
+        associated_token::token_program = quote_token_program
+    )]
+    pub protocol_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pyth price update of the quote token, required if the market registered one. Checked in the handler
+    pub quote_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: TWAP oracle of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [MARKET_ORACLE_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub market_oracle: UncheckedAccount<'info>,
+
+    /// CHECK: Compliance hold of the owner on this market, must not exist
+    #[account(
+        seeds = [RESTRICTED_WALLET_PDA_SEED.as_bytes(), market.key().as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = restricted_wallet.data_is_empty() @ TokenMillError::WalletRestricted
+    )]
+    pub restricted_wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Owner of the DCA position, refunded the rent of the position after its last buy
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    pub cranker: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Permissionless crank executing the next due buy of a DCA position with its escrowed quote tokens.
+/// The buy has to be fully filled and average at most the max price of the position.
+/// The purchased tokens are sent to the owner and the crank bounty to the cranker.
+/// The position is closed after its last buy, unless a fee discount is left in escrow for the owner to close it.
+pub fn handler(ctx: Context<ExecuteDcaBuy>) -> Result<(u64, u64)> {
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let dca_position = &mut ctx.accounts.dca_position;
+    let amount = dca_position.amount_per_buy;
+    let crank_bounty = dca_position.crank_bounty;
+
+    dca_position.execute_buy(current_time)?;
+
+    let (
+        base_amount,
+        quote_amount,
+        creator_fee,
+        staking_fee,
+        protocol_fee,
+        incentive_weight,
+        circulating_supply_before,
+        circulating_supply_after,
+        (ask_price, bid_price),
+        market_bump,
+    ) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        price_feed_manager::check_quote_peg(market, ctx.accounts.quote_price_feed.as_deref())?;
+
+        let circulating_supply_before = market.circulating_supply();
+
+        let (base_amount, quote_amount, swap_fee) = swap_manager::swap(
+            market,
+            SwapType::Buy,
+            SwapAmountType::ExactInput,
+            amount,
+            &Clock::get()?,
+        )?;
+
+        oracle_manager::record_prices(&ctx.accounts.market_oracle, market)?;
+
+        require!(quote_amount == amount, TokenMillError::SwapNotFullyFilled);
+
+        let (quote_amount, swap_fee) = swap_manager::apply_fee_exemption(
+            &ctx.accounts.config,
+            &ctx.accounts.owner.key(),
+            SwapType::Buy,
+            quote_amount,
+            swap_fee,
+        )?;
+
+        // The discount of exempted owners stays in the escrow, returned when the position is closed
+        dca_position.escrow_amount += amount - quote_amount;
+        market.release_quote(quote_amount + crank_bounty)?;
+
+        dca_position.check_price(base_amount, quote_amount)?;
+
+        let (creator_fee, staking_fee, protocol_fee, _) =
+            market.fees.distribute_fee(swap_fee, None)?;
+
+        (
+            base_amount,
+            quote_amount,
+            creator_fee,
+            staking_fee,
+            protocol_fee,
+            market.get_incentive_weight(SwapType::Buy.into(), quote_amount)?,
+            circulating_supply_before,
+            market.circulating_supply(),
+            market.get_spot_prices()?,
+            market.bump,
+        )
+    };
+
+    let base_token_mint_key = ctx.accounts.base_token_mint.key();
+    let market_seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint_key.as_ref(),
+        &[market_bump],
+    ];
+
+    let accounts = &ctx.accounts;
+
+    // The quote tokens of the buy are already held by the market
+    transfer_from_pda(
+        &accounts.base_token_mint,
+        accounts.market.to_account_info(),
+        &accounts.market_base_token_ata,
+        &accounts.owner_base_token_ata,
+        &accounts.base_token_program,
+        base_amount,
+        &market_seeds,
+    )?;
+
+    if protocol_fee > 0 {
+        transfer_from_pda(
+            &accounts.quote_token_mint,
+            accounts.market.to_account_info(),
+            &accounts.market_quote_token_ata,
+            &accounts.protocol_quote_token_ata,
+            &accounts.quote_token_program,
+            protocol_fee,
+            &market_seeds,
+        )?;
+    }
+
+    if crank_bounty > 0 {
+        transfer_from_pda(
+            &accounts.quote_token_mint,
+            accounts.market.to_account_info(),
+            &accounts.market_quote_token_ata,
+            &accounts.cranker_quote_token_ata,
+            &accounts.quote_token_program,
+            crank_bounty,
+            &market_seeds,
+        )?;
+    }
+
+    let market_key = accounts.market.key();
+    let owner_key = accounts.owner.key();
+
+    emit_cpi!(TokenMillSwapEvent {
+        user: owner_key,
+        market: market_key,
+        swap_type: SwapType::Buy,
+        base_amount,
+        quote_amount,
+        referral_token_account: None,
+        creator_fee,
+        staking_fee,
+        protocol_fee,
+        referral_fee: 0,
+        interface_fee_recipient: None,
+        interface_fee: 0,
+        circulating_supply_before,
+        circulating_supply_after,
+        ask_price,
+        bid_price,
+    });
+
+    emit_cpi!(TokenMillDcaBuyEvent {
+        dca_position: accounts.dca_position.key(),
+        cranker: accounts.cranker.key(),
+        crank_bounty,
+        buys_remaining: accounts.dca_position.buys_remaining,
+    });
+
+    if let Some(weight) = incentive_weight {
+        emit_cpi!(TokenMillIncentiveEvent {
+            wallet: owner_key,
+            market: market_key,
+            action: SwapType::Buy.into(),
+            amount: quote_amount,
+            weight,
+        });
+    }
+
+    if ctx.accounts.dca_position.buys_remaining == 0 && ctx.accounts.dca_position.escrow_amount == 0
+    {
+        ctx.accounts
+            .dca_position
+            .close(ctx.accounts.owner.to_account_info())?;
+    }
+
+    Ok((base_amount, quote_amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AnchorDeserialize;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateDcaPositionAction, ExecuteDcaBuyAction, SetFeeExemptionAction,
+            TokenMillEnv,
+        },
+        make_address, TokenMillError,
+    };
+
+    use crate::DcaPosition;
+
+    const AMOUNT_PER_BUY: u64 = 100_000_000;
+    const CRANK_BOUNTY: u64 = 10_000;
+
+    fn setup_env() -> (TokenMillEnv, CreateDcaPositionAction, ExecuteDcaBuyAction) {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("bob");
+
+        let create_action =
+            CreateDcaPositionAction::new(&testing_env, AMOUNT_PER_BUY, CRANK_BOUNTY, 2);
+
+        testing_env.svm.execute_actions(&[&create_action]).unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let action = ExecuteDcaBuyAction::new(&testing_env, &create_action, make_address("carol"));
+
+        (testing_env, create_action, action)
+    }
+
+    #[test]
+    fn execute_dca_buy() {
+        let (mut testing_env, create_action, action) = setup_env();
+
+        let cranker_balance_before = testing_env
+            .svm
+            .get_balance(&create_action.quote_token_mint, &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+        let (base_amount, quote_amount) =
+            <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        assert_eq!(quote_amount, AMOUNT_PER_BUY);
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&create_action.base_token_mint, &create_action.signer),
+            base_amount
+        );
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&create_action.quote_token_mint, &action.signer),
+            cranker_balance_before + CRANK_BOUNTY
+        );
+
+        let dca_position = testing_env
+            .svm
+            .get_parsed_account::<DcaPosition>(&action.dca_position);
+
+        assert_eq!(dca_position.buys_remaining, 1);
+        assert_eq!(dca_position.escrow_amount, AMOUNT_PER_BUY + CRANK_BOUNTY);
+
+        // The next buy isn't due before the interval elapsed
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::DcaBuyNotDue
+        );
+
+        testing_env.svm.warp(create_action.interval);
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        // The position is closed after its last buy
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.dca_position)
+            .is_none());
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&create_action.quote_token_mint, &action.signer),
+            cranker_balance_before + 2 * CRANK_BOUNTY
+        );
+    }
+
+    #[test]
+    fn execute_dca_buy_above_max_price() {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("bob");
+
+        let mut create_action =
+            CreateDcaPositionAction::new(&testing_env, AMOUNT_PER_BUY, CRANK_BOUNTY, 2);
+        create_action.max_price = 1;
+
+        testing_env.svm.execute_actions(&[&create_action]).unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let action = ExecuteDcaBuyAction::new(&testing_env, &create_action, make_address("carol"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::DcaPriceLimitExceeded
+        );
+    }
+
+    #[test]
+    fn execute_dca_buy_with_fee_exemption() {
+        let (mut testing_env, create_action, action) = setup_env();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetFeeExemptionAction::new(create_action.signer, 10_000)])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+        let (_, quote_amount) = <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        assert!(quote_amount < AMOUNT_PER_BUY);
+
+        let dca_position = testing_env
+            .svm
+            .get_parsed_account::<DcaPosition>(&action.dca_position);
+
+        // The discount stays in escrow
+        assert_eq!(
+            dca_position.escrow_amount,
+            2 * AMOUNT_PER_BUY + CRANK_BOUNTY - quote_amount
+        );
+
+        testing_env.svm.warp(create_action.interval);
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        // The position is left open after its last buy, for the owner to get the discounts back
+        let dca_position = testing_env
+            .svm
+            .get_parsed_account::<DcaPosition>(&action.dca_position);
+
+        assert_eq!(dca_position.buys_remaining, 0);
+        assert!(dca_position.escrow_amount > 0);
+    }
+}
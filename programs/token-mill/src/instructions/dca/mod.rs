@@ -0,0 +1,7 @@
+pub mod close_dca_position;
+pub mod create_dca_position;
+pub mod execute_dca_buy;
+
+pub use close_dca_position::*;
+pub use create_dca_position::*;
+pub use execute_dca_buy::*;
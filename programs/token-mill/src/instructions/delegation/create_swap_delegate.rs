@@ -0,0 +1,193 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillSwapDelegateCreationEvent,
+    manager::token_manager::approve_from_eoa,
+    state::{Market, SwapDelegate, SWAP_DELEGATE_PDA_SEED},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateSwapDelegate<'info> {
+    #[account(
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        init,
+        seeds = [
+            SWAP_DELEGATE_PDA_SEED.as_bytes(),
+            market.key().as_ref(),
+            owner.key().as_ref(),
+            delegate.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = 8 + SwapDelegate::INIT_SPACE
+    )]
+    pub swap_delegate: Account<'info, SwapDelegate>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = base_token_program
+    )]
+    pub owner_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = quote_token_program
+    )]
+    pub owner_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: Session key allowed to swap on behalf of the owner
+    pub delegate: UncheckedAccount<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Authorizes `delegate` to swap on the market on behalf of the owner until `expiry`, spending at most
+/// `quote_allowance` quote tokens on buys and `base_allowance` base tokens on sells.
+/// The swap delegate PDA is approved as delegate of both owner token accounts, replacing any previous approval.
+pub fn handler(
+    ctx: Context<CreateSwapDelegate>,
+    quote_allowance: u64,
+    base_allowance: u64,
+    expiry: i64,
+) -> Result<()> {
+    let swap_delegate = &mut ctx.accounts.swap_delegate;
+
+    swap_delegate.initialize(
+        ctx.bumps.swap_delegate,
+        ctx.accounts.market.key(),
+        ctx.accounts.owner.key(),
+        ctx.accounts.delegate.key(),
+        quote_allowance,
+        base_allowance,
+        expiry,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    approve_from_eoa(
+        &ctx.accounts.owner,
+        &ctx.accounts.owner_quote_token_ata,
+        swap_delegate.to_account_info(),
+        &ctx.accounts.quote_token_program,
+        quote_allowance,
+    )?;
+
+    approve_from_eoa(
+        &ctx.accounts.owner,
+        &ctx.accounts.owner_base_token_ata,
+        swap_delegate.to_account_info(),
+        &ctx.accounts.base_token_program,
+        base_allowance,
+    )?;
+
+    emit_cpi!(TokenMillSwapDelegateCreationEvent {
+        swap_delegate: swap_delegate.key(),
+        market: ctx.accounts.market.key(),
+        owner: ctx.accounts.owner.key(),
+        delegate: ctx.accounts.delegate.key(),
+        quote_allowance,
+        base_allowance,
+        expiry,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SwapDelegate;
+    use anchor_lang::solana_program::program_option::COption;
+    use anchor_spl::token_2022::spl_token_2022::{
+        extension::StateWithExtensions, state::Account as SplAccount,
+    };
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, CreateSwapDelegateAction, TokenMillEnv},
+        make_address, TokenMillError,
+    };
+
+    const QUOTE_ALLOWANCE: u64 = 1_000_000_000;
+    const BASE_ALLOWANCE: u64 = 2_000_000_000;
+
+    fn setup_env() -> (TokenMillEnv, CreateSwapDelegateAction) {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("bob");
+
+        let expiry = testing_env.svm.get_clock().unix_timestamp + 3_600;
+
+        let action = CreateSwapDelegateAction::new(
+            &testing_env,
+            make_address("carol"),
+            QUOTE_ALLOWANCE,
+            BASE_ALLOWANCE,
+            expiry,
+        );
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn create_swap_delegate() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let swap_delegate = testing_env
+            .svm
+            .get_parsed_account::<SwapDelegate>(&action.swap_delegate);
+
+        assert_eq!(swap_delegate.owner, make_address("bob"));
+        assert_eq!(swap_delegate.delegate, make_address("carol"));
+        assert_eq!(swap_delegate.quote_allowance, QUOTE_ALLOWANCE);
+        assert_eq!(swap_delegate.base_allowance, BASE_ALLOWANCE);
+        assert_eq!(swap_delegate.expiry, action.expiry);
+
+        for (ata, allowance) in [
+            (action.owner_quote_token_ata, QUOTE_ALLOWANCE),
+            (action.owner_base_token_ata, BASE_ALLOWANCE),
+        ] {
+            let account = testing_env.svm.get_account(&ata);
+            let token_account = StateWithExtensions::<SplAccount>::unpack(&account.data)
+                .unwrap()
+                .base;
+
+            assert_eq!(token_account.delegate, COption::Some(action.swap_delegate));
+            assert_eq!(token_account.delegated_amount, allowance);
+        }
+    }
+
+    #[test]
+    fn create_expired_swap_delegate() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.expiry = testing_env.svm.get_clock().unix_timestamp;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidSwapDelegate
+        );
+    }
+}
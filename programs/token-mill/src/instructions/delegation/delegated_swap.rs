@@ -0,0 +1,493 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::{TokenMillIncentiveEvent, TokenMillSwapEvent},
+    manager::{
+        oracle_manager, price_feed_manager,
+        swap_manager::{self, SwapAmountType, SwapType},
+        token_manager::transfer_from_pda,
+    },
+    state::{Market, SwapDelegate, SWAP_DELEGATE_PDA_SEED},
+    TokenMillConfig, MARKET_ORACLE_PDA_SEED, MARKET_PDA_SEED, RESTRICTED_WALLET_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DelegatedSwap<'info> {
+    #[account(constraint = !config.swaps_paused @ TokenMillError::SwapsPaused)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        mut,
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = owner @ TokenMillError::InvalidAuthority,
+        has_one = delegate @ TokenMillError::InvalidAuthority
+    )]
+    pub swap_delegate: Account<'info, SwapDelegate>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = base_token_program
+    )]
+    pub owner_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = quote_token_program
+    )]
+    pub owner_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = config.protocol_fee_recipient,
+        associated_token::token_program = quote_token_program
+    )]
+    pub protocol_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pyth price update of the quote token, required if the market registered one. Checked in the handler
+    pub quote_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: TWAP oracle of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [MARKET_ORACLE_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub market_oracle: UncheckedAccount<'info>,
+
+    /// CHECK: Compliance hold of the owner on this market, must not exist
+    #[account(
+        seeds = [RESTRICTED_WALLET_PDA_SEED.as_bytes(), market.key().as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = restricted_wallet.data_is_empty() @ TokenMillError::WalletRestricted
+    )]
+    pub restricted_wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Wallet the swap is executed for, checked against the swap delegate
+    pub owner: UncheckedAccount<'info>,
+
+    pub delegate: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Swaps on behalf of the owner of a swap delegate, signed by its delegate.
+/// The input tokens are pulled from the owner token accounts through the approval of the swap delegate PDA,
+/// and the output tokens are sent to the owner token accounts.
+/// The swap has to be fully filled, and referral and interface fees are not supported.
+pub fn handler(
+    ctx: Context<DelegatedSwap>,
+    swap_type: SwapType,
+    swap_amount_type: SwapAmountType,
+    amount: u64,
+    other_amount_threshold: u64,
+) -> Result<(u64, u64)> {
+    if amount == 0 {
+        return Err(TokenMillError::InvalidAmount.into());
+    }
+
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let (
+        base_amount,
+        quote_amount,
+        creator_fee,
+        staking_fee,
+        protocol_fee,
+        incentive_weight,
+        circulating_supply_before,
+        circulating_supply_after,
+        (ask_price, bid_price),
+        market_bump,
+    ) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        if swap_type == SwapType::Sell && market.are_sells_paused(current_time) {
+            return Err(TokenMillError::SellsPaused.into());
+        }
+
+        price_feed_manager::check_quote_peg(market, ctx.accounts.quote_price_feed.as_deref())?;
+
+        let circulating_supply_before = market.circulating_supply();
+
+        let (base_amount, quote_amount, swap_fee) =
+            swap_manager::swap(market, swap_type, swap_amount_type, amount, &Clock::get()?)?;
+
+        oracle_manager::record_prices(&ctx.accounts.market_oracle, market)?;
+
+        let filled_amount = match (swap_type, swap_amount_type) {
+            (SwapType::Buy, SwapAmountType::ExactInput)
+            | (SwapType::Sell, SwapAmountType::ExactOutput) => quote_amount,
+            (SwapType::Buy, SwapAmountType::ExactOutput)
+            | (SwapType::Sell, SwapAmountType::ExactInput) => base_amount,
+        };
+
+        require!(filled_amount == amount, TokenMillError::SwapNotFullyFilled);
+
+        // The exemption follows the owner, on whose behalf the delegate swaps
+        let (quote_amount, swap_fee) = swap_manager::apply_fee_exemption(
+            &ctx.accounts.config,
+            &ctx.accounts.owner.key(),
+            swap_type,
+            quote_amount,
+            swap_fee,
+        )?;
+
+        let (creator_fee, staking_fee, protocol_fee, _) =
+            market.fees.distribute_fee(swap_fee, None)?;
+
+        (
+            base_amount,
+            quote_amount,
+            creator_fee,
+            staking_fee,
+            protocol_fee,
+            market.get_incentive_weight(swap_type.into(), quote_amount)?,
+            circulating_supply_before,
+            market.circulating_supply(),
+            market.get_spot_prices()?,
+            market.bump,
+        )
+    };
+
+    let (amount_in, amount_out) = match swap_type {
+        SwapType::Buy => (quote_amount, base_amount),
+        SwapType::Sell => (base_amount, quote_amount),
+    };
+
+    match swap_amount_type {
+        SwapAmountType::ExactInput => {
+            if amount_out < other_amount_threshold {
+                return Err(TokenMillError::AmountThresholdNotMet.into());
+            }
+        }
+        SwapAmountType::ExactOutput => {
+            if amount_in > other_amount_threshold {
+                return Err(TokenMillError::AmountThresholdNotMet.into());
+            }
+        }
+    }
+
+    ctx.accounts
+        .swap_delegate
+        .spend(swap_type, base_amount, quote_amount, current_time)?;
+
+    let market_key = ctx.accounts.market.key();
+    let owner_key = ctx.accounts.owner.key();
+    let delegate_key = ctx.accounts.delegate.key();
+    let swap_delegate_seeds = [
+        SWAP_DELEGATE_PDA_SEED.as_bytes(),
+        market_key.as_ref(),
+        owner_key.as_ref(),
+        delegate_key.as_ref(),
+        &[ctx.accounts.swap_delegate.bump],
+    ];
+
+    let base_token_mint_key = ctx.accounts.base_token_mint.key();
+    let market_seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint_key.as_ref(),
+        &[market_bump],
+    ];
+
+    let accounts = &ctx.accounts;
+
+    let (
+        mint_in,
+        mint_out,
+        owner_account_in,
+        owner_account_out,
+        market_account_in,
+        market_account_out,
+        token_program_in,
+        token_program_out,
+    ) = match swap_type {
+        SwapType::Buy => (
+            &accounts.quote_token_mint,
+            &accounts.base_token_mint,
+            &accounts.owner_quote_token_ata,
+            &accounts.owner_base_token_ata,
+            &accounts.market_quote_token_ata,
+            &accounts.market_base_token_ata,
+            &accounts.quote_token_program,
+            &accounts.base_token_program,
+        ),
+        SwapType::Sell => (
+            &accounts.base_token_mint,
+            &accounts.quote_token_mint,
+            &accounts.owner_base_token_ata,
+            &accounts.owner_quote_token_ata,
+            &accounts.market_base_token_ata,
+            &accounts.market_quote_token_ata,
+            &accounts.base_token_program,
+            &accounts.quote_token_program,
+        ),
+    };
+
+    transfer_from_pda(
+        mint_in,
+        accounts.swap_delegate.to_account_info(),
+        owner_account_in,
+        market_account_in,
+        token_program_in,
+        amount_in,
+        &swap_delegate_seeds,
+    )?;
+
+    transfer_from_pda(
+        mint_out,
+        accounts.market.to_account_info(),
+        market_account_out,
+        owner_account_out,
+        token_program_out,
+        amount_out,
+        &market_seeds,
+    )?;
+
+    if protocol_fee > 0 {
+        transfer_from_pda(
+            &accounts.quote_token_mint,
+            accounts.market.to_account_info(),
+            &accounts.market_quote_token_ata,
+            &accounts.protocol_quote_token_ata,
+            &accounts.quote_token_program,
+            protocol_fee,
+            &market_seeds,
+        )?;
+    }
+
+    emit_cpi!(TokenMillSwapEvent {
+        user: owner_key,
+        market: market_key,
+        swap_type,
+        base_amount,
+        quote_amount,
+        referral_token_account: None,
+        creator_fee,
+        staking_fee,
+        protocol_fee,
+        referral_fee: 0,
+        interface_fee_recipient: None,
+        interface_fee: 0,
+        circulating_supply_before,
+        circulating_supply_after,
+        ask_price,
+        bid_price,
+    });
+
+    if let Some(weight) = incentive_weight {
+        emit_cpi!(TokenMillIncentiveEvent {
+            wallet: owner_key,
+            market: market_key,
+            action: swap_type.into(),
+            amount: quote_amount,
+            weight,
+        });
+    }
+
+    Ok((base_amount, quote_amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SwapDelegate;
+    use anchor_lang::AnchorDeserialize;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateSwapDelegateAction, DelegatedSwapAction,
+            RevokeSwapDelegateAction, TokenMillEnv,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const QUOTE_ALLOWANCE: u64 = 1_000_000_000;
+    const BASE_AMOUNT: u64 = 1_000_000_000;
+
+    fn setup_env() -> (TokenMillEnv, CreateSwapDelegateAction, DelegatedSwapAction) {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("bob");
+
+        let expiry = testing_env.svm.get_clock().unix_timestamp + 3_600;
+
+        let create_action = CreateSwapDelegateAction::new(
+            &testing_env,
+            make_address("carol"),
+            QUOTE_ALLOWANCE,
+            BASE_AMOUNT / 2,
+            expiry,
+        );
+
+        testing_env.svm.execute_actions(&[&create_action]).unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let action = DelegatedSwapAction::new(
+            &testing_env,
+            make_address("carol"),
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            BASE_AMOUNT,
+            u64::MAX,
+        );
+
+        (testing_env, create_action, action)
+    }
+
+    #[test]
+    fn delegated_swap() {
+        let (mut testing_env, create_action, mut action) = setup_env();
+
+        let bob = make_address("bob");
+        let quote_balance_before = testing_env
+            .svm
+            .get_balance(&create_action.quote_token_mint, &bob);
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+        let (base_amount, quote_amount) =
+            <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        assert_eq!(base_amount, BASE_AMOUNT);
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&create_action.base_token_mint, &bob),
+            BASE_AMOUNT
+        );
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&create_action.quote_token_mint, &bob),
+            quote_balance_before - quote_amount
+        );
+
+        // The delegate never holds any token
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&create_action.base_token_mint, &action.signer),
+            0
+        );
+
+        action.swap_type = SwapType::Sell;
+        action.swap_amount_type = SwapAmountType::ExactInput;
+        action.amount = BASE_AMOUNT / 2;
+        action.other_amount_threshold = 0;
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let swap_delegate = testing_env
+            .svm
+            .get_parsed_account::<SwapDelegate>(&action.swap_delegate);
+
+        assert_eq!(
+            swap_delegate.quote_allowance,
+            QUOTE_ALLOWANCE - quote_amount
+        );
+        assert_eq!(swap_delegate.base_allowance, 0);
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&create_action.base_token_mint, &bob),
+            BASE_AMOUNT / 2
+        );
+    }
+
+    #[test]
+    fn delegated_swap_above_allowance() {
+        let (mut testing_env, _, mut action) = setup_env();
+
+        action.swap_amount_type = SwapAmountType::ExactInput;
+        action.amount = QUOTE_ALLOWANCE + 1;
+        action.other_amount_threshold = 0;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::SwapDelegateAllowanceExceeded
+        );
+    }
+
+    #[test]
+    fn delegated_swap_after_expiry() {
+        let (mut testing_env, _, action) = setup_env();
+
+        testing_env.svm.warp(3_600);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::SwapDelegateExpired
+        );
+    }
+
+    #[test]
+    fn delegated_swap_after_revocation() {
+        let (mut testing_env, create_action, action) = setup_env();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&RevokeSwapDelegateAction::new(&create_action)])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn delegated_swap_with_invalid_signer() {
+        let (mut testing_env, _, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -0,0 +1,7 @@
+pub mod create_swap_delegate;
+pub mod delegated_swap;
+pub mod revoke_swap_delegate;
+
+pub use create_swap_delegate::*;
+pub use delegated_swap::*;
+pub use revoke_swap_delegate::*;
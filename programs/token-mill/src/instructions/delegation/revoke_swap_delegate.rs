@@ -0,0 +1,156 @@
+use anchor_lang::{prelude::*, solana_program::program_option::COption};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillSwapDelegateRevocationEvent,
+    manager::token_manager::revoke_from_eoa,
+    state::{Market, SwapDelegate},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RevokeSwapDelegate<'info> {
+    #[account(
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = owner @ TokenMillError::InvalidAuthority,
+        close = owner
+    )]
+    pub swap_delegate: Account<'info, SwapDelegate>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = base_token_program
+    )]
+    pub owner_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = quote_token_program
+    )]
+    pub owner_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Closes a swap delegate, and revokes the approvals of the owner token accounts that are still held by it
+pub fn handler(ctx: Context<RevokeSwapDelegate>) -> Result<()> {
+    let swap_delegate = ctx.accounts.swap_delegate.key();
+
+    if ctx.accounts.owner_quote_token_ata.delegate == COption::Some(swap_delegate) {
+        revoke_from_eoa(
+            &ctx.accounts.owner,
+            &ctx.accounts.owner_quote_token_ata,
+            &ctx.accounts.quote_token_program,
+        )?;
+    }
+
+    if ctx.accounts.owner_base_token_ata.delegate == COption::Some(swap_delegate) {
+        revoke_from_eoa(
+            &ctx.accounts.owner,
+            &ctx.accounts.owner_base_token_ata,
+            &ctx.accounts.base_token_program,
+        )?;
+    }
+
+    emit_cpi!(TokenMillSwapDelegateRevocationEvent { swap_delegate });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::program_option::COption;
+    use anchor_spl::token_2022::spl_token_2022::{
+        extension::StateWithExtensions, state::Account as SplAccount,
+    };
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateSwapDelegateAction, RevokeSwapDelegateAction, TokenMillEnv,
+        },
+        make_address, TokenMillError,
+    };
+
+    fn setup_env() -> (TokenMillEnv, RevokeSwapDelegateAction) {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("bob");
+
+        let expiry = testing_env.svm.get_clock().unix_timestamp + 3_600;
+
+        let create_action = CreateSwapDelegateAction::new(
+            &testing_env,
+            make_address("carol"),
+            1_000_000_000,
+            1_000_000_000,
+            expiry,
+        );
+
+        testing_env.svm.execute_actions(&[&create_action]).unwrap();
+
+        let action = RevokeSwapDelegateAction::new(&create_action);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn revoke_swap_delegate() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.swap_delegate)
+            .is_none());
+
+        for ata in [action.owner_quote_token_ata, action.owner_base_token_ata] {
+            let account = testing_env.svm.get_account(&ata);
+            let token_account = StateWithExtensions::<SplAccount>::unpack(&account.data)
+                .unwrap()
+                .base;
+
+            assert_eq!(token_account.delegate, COption::None);
+        }
+    }
+
+    #[test]
+    fn revoke_swap_delegate_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+        action.owner_quote_token_ata = testing_env
+            .svm
+            .get_ata_address(&action.quote_token_mint, &action.signer);
+        action.owner_base_token_ata = testing_env
+            .svm
+            .get_ata_address(&action.base_token_mint, &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
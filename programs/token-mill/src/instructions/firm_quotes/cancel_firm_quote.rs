@@ -0,0 +1,197 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillFirmQuoteCancelEvent,
+    manager::{
+        swap_manager::SwapType,
+        token_manager::{close_pda_token_account, transfer_from_pda},
+    },
+    state::{FirmQuote, Market},
+    FIRM_QUOTE_ESCROW_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelFirmQuote<'info> {
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = market_maker @ TokenMillError::InvalidAuthority,
+        close = market_maker
+    )]
+    pub firm_quote: Account<'info, FirmQuote>,
+
+    /// Base token mint for asks, quote token mint for bids
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Data-less PDA holding the escrow of the firm quote
+    #[account(
+        seeds = [FIRM_QUOTE_ESCROW_PDA_SEED.as_bytes(), firm_quote.key().as_ref()],
+        bump
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_authority,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = market_maker,
+        associated_token::token_program = token_program
+    )]
+    pub market_maker_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub market_maker: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Closes a firm quote, expired or not, and returns the remaining escrow and its rent to the market maker
+pub fn handler(ctx: Context<CancelFirmQuote>) -> Result<()> {
+    let firm_quote = &ctx.accounts.firm_quote;
+
+    let escrow_mint = {
+        let market = ctx.accounts.market.load()?;
+
+        match firm_quote.swap_type {
+            SwapType::Buy => market.base_token_mint,
+            SwapType::Sell => market.quote_token_mint,
+        }
+    };
+
+    require!(
+        ctx.accounts.token_mint.key() == escrow_mint,
+        TokenMillError::InvalidMintAccount
+    );
+
+    let firm_quote_key = firm_quote.key();
+    let escrow_authority_seeds = [
+        FIRM_QUOTE_ESCROW_PDA_SEED.as_bytes(),
+        firm_quote_key.as_ref(),
+        &[ctx.bumps.escrow_authority],
+    ];
+
+    // The whole balance is returned so that tokens sent to the escrow can't prevent closing it
+    let amount_returned = ctx.accounts.escrow_token_ata.amount;
+
+    if amount_returned > 0 {
+        transfer_from_pda(
+            &ctx.accounts.token_mint,
+            ctx.accounts.escrow_authority.to_account_info(),
+            &ctx.accounts.escrow_token_ata,
+            &ctx.accounts.market_maker_token_ata,
+            &ctx.accounts.token_program,
+            amount_returned,
+            &escrow_authority_seeds,
+        )?;
+    }
+
+    close_pda_token_account(
+        ctx.accounts.escrow_authority.to_account_info(),
+        &ctx.accounts.escrow_token_ata,
+        ctx.accounts.market_maker.to_account_info(),
+        &ctx.accounts.token_program,
+        &escrow_authority_seeds,
+    )?;
+
+    emit_cpi!(TokenMillFirmQuoteCancelEvent {
+        firm_quote: firm_quote_key,
+        amount_returned,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CancelFirmQuoteAction, CreateMarketMakerBadgeAction,
+            PostFirmQuoteAction, TokenMillEnv,
+        },
+        make_address, SwapType, TokenMillError,
+    };
+
+    const BASE_AMOUNT: u64 = 1_000_000_000;
+    const PRICE: u64 = 1_000;
+
+    fn setup_env() -> (TokenMillEnv, CancelFirmQuoteAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env
+            .svm
+            .execute_actions(&[&CreateMarketMakerBadgeAction::new(make_address("carol"))])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let post_action =
+            PostFirmQuoteAction::new(&testing_env, SwapType::Sell, BASE_AMOUNT, PRICE);
+
+        testing_env.svm.execute_actions(&[&post_action]).unwrap();
+
+        let action = CancelFirmQuoteAction::new(&post_action);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn cancel_firm_quote() {
+        let (mut testing_env, action) = setup_env();
+
+        let market_maker_balance_before = testing_env
+            .svm
+            .get_balance(&action.token_mint, &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market_maker_balance_after = testing_env
+            .svm
+            .get_balance(&action.token_mint, &action.signer);
+
+        assert_eq!(
+            market_maker_balance_after - market_maker_balance_before,
+            BASE_AMOUNT * PRICE / 1_000_000
+        );
+
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.firm_quote)
+            .is_none());
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.escrow_token_ata)
+            .is_none());
+    }
+
+    #[test]
+    fn cancel_firm_quote_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+        action.market_maker_token_ata = testing_env
+            .svm
+            .get_ata_address(&action.token_mint, &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
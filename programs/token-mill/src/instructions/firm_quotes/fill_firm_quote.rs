@@ -0,0 +1,514 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constant::MAX_BPS,
+    errors::TokenMillError,
+    events::TokenMillFirmQuoteFillEvent,
+    manager::{
+        price_feed_manager,
+        swap_manager::SwapType,
+        token_manager::{transfer_from_eoa, transfer_from_pda},
+    },
+    state::{FirmQuote, Market, MarketMakerBadge, TokenMillConfig},
+    FIRM_QUOTE_ESCROW_PDA_SEED, MARKET_MAKER_BADGE_PDA_SEED, RESTRICTED_WALLET_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FillFirmQuote<'info> {
+    #[account(constraint = !config.swaps_paused @ TokenMillError::SwapsPaused)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub firm_quote: Box<Account<'info, FirmQuote>>,
+
+    // Quotes of revoked market makers can't be filled anymore
+    #[account(
+        seeds = [
+            MARKET_MAKER_BADGE_PDA_SEED.as_bytes(),
+            config.key().as_ref(),
+            firm_quote.market_maker.as_ref(),
+        ],
+        bump = market_maker_badge.bump,
+    )]
+    pub market_maker_badge: Box<Account<'info, MarketMakerBadge>>,
+
+    pub base_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub quote_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Data-less PDA holding the escrow of the firm quote
+    #[account(
+        seeds = [FIRM_QUOTE_ESCROW_PDA_SEED.as_bytes(), firm_quote.key().as_ref()],
+        bump
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    /// Base token account of the escrow for asks, quote token account for bids
+    #[account(mut, token::authority = escrow_authority)]
+    pub escrow_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = base_token_program
+    )]
+    pub user_base_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = quote_token_program
+    )]
+    pub user_quote_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = firm_quote.market_maker,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_maker_base_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = firm_quote.market_maker,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_maker_quote_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = config.protocol_fee_recipient,
+        associated_token::token_program = quote_token_program
+    )]
+    pub protocol_quote_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Pyth price update of the quote token, required if the market registered one. Checked in the handler
+    pub quote_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Compliance hold of the user on this market, must not exist
+    #[account(
+        seeds = [RESTRICTED_WALLET_PDA_SEED.as_bytes(), market.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = restricted_wallet.data_is_empty() @ TokenMillError::WalletRestricted
+    )]
+    pub restricted_wallet: UncheckedAccount<'info>,
+
+    pub user: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Fills a firm quote as an alternative to the curve.
+/// The protocol fee is charged in quote token on top of the quoted price, and paid by the taker.
+pub fn handler(
+    ctx: Context<FillFirmQuote>,
+    base_amount: u64,
+    other_amount_threshold: u64,
+) -> Result<(u64, u64)> {
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let firm_quote = &mut ctx.accounts.firm_quote;
+    let swap_type = firm_quote.swap_type;
+
+    // Fills go through the same market checks as the swaps on the curve
+    {
+        let market = ctx.accounts.market.load()?;
+
+        require!(market.paused == 0, TokenMillError::MarketPaused);
+        require!(market.graduated == 0, TokenMillError::MarketGraduated);
+
+        if swap_type == SwapType::Sell && market.are_sells_paused(current_time) {
+            return Err(TokenMillError::SellsPaused.into());
+        }
+
+        price_feed_manager::check_quote_peg(&market, ctx.accounts.quote_price_feed.as_deref())?;
+    }
+
+    let quote_amount = firm_quote.fill(base_amount, current_time)?;
+
+    let protocol_fee = u64::try_from(
+        u128::from(quote_amount) * u128::from(ctx.accounts.config.firm_quote_fee_bps)
+            / u128::from(MAX_BPS),
+    )?;
+
+    let firm_quote_key = firm_quote.key();
+    let escrow_authority_seeds = [
+        FIRM_QUOTE_ESCROW_PDA_SEED.as_bytes(),
+        firm_quote_key.as_ref(),
+        &[ctx.bumps.escrow_authority],
+    ];
+
+    let user = &ctx.accounts.user;
+
+    match swap_type {
+        // The market maker sells base tokens from the escrow
+        SwapType::Buy => {
+            require!(
+                quote_amount + protocol_fee <= other_amount_threshold,
+                TokenMillError::AmountThresholdNotMet
+            );
+
+            transfer_from_eoa(
+                &ctx.accounts.quote_token_mint,
+                user,
+                &ctx.accounts.user_quote_token_ata,
+                &ctx.accounts.market_maker_quote_token_ata,
+                &ctx.accounts.quote_token_program,
+                quote_amount,
+            )?;
+
+            if protocol_fee > 0 {
+                transfer_from_eoa(
+                    &ctx.accounts.quote_token_mint,
+                    user,
+                    &ctx.accounts.user_quote_token_ata,
+                    &ctx.accounts.protocol_quote_token_ata,
+                    &ctx.accounts.quote_token_program,
+                    protocol_fee,
+                )?;
+            }
+
+            transfer_from_pda(
+                &ctx.accounts.base_token_mint,
+                ctx.accounts.escrow_authority.to_account_info(),
+                &ctx.accounts.escrow_token_ata,
+                &ctx.accounts.user_base_token_ata,
+                &ctx.accounts.base_token_program,
+                base_amount,
+                &escrow_authority_seeds,
+            )?;
+        }
+        // The market maker buys base tokens with the escrowed quote tokens
+        SwapType::Sell => {
+            require!(
+                quote_amount - protocol_fee >= other_amount_threshold,
+                TokenMillError::AmountThresholdNotMet
+            );
+
+            transfer_from_eoa(
+                &ctx.accounts.base_token_mint,
+                user,
+                &ctx.accounts.user_base_token_ata,
+                &ctx.accounts.market_maker_base_token_ata,
+                &ctx.accounts.base_token_program,
+                base_amount,
+            )?;
+
+            transfer_from_pda(
+                &ctx.accounts.quote_token_mint,
+                ctx.accounts.escrow_authority.to_account_info(),
+                &ctx.accounts.escrow_token_ata,
+                &ctx.accounts.user_quote_token_ata,
+                &ctx.accounts.quote_token_program,
+                quote_amount - protocol_fee,
+                &escrow_authority_seeds,
+            )?;
+
+            if protocol_fee > 0 {
+                transfer_from_pda(
+                    &ctx.accounts.quote_token_mint,
+                    ctx.accounts.escrow_authority.to_account_info(),
+                    &ctx.accounts.escrow_token_ata,
+                    &ctx.accounts.protocol_quote_token_ata,
+                    &ctx.accounts.quote_token_program,
+                    protocol_fee,
+                    &escrow_authority_seeds,
+                )?;
+            }
+        }
+    }
+
+    emit_cpi!(TokenMillFirmQuoteFillEvent {
+        firm_quote: firm_quote_key,
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        swap_type,
+        base_amount,
+        quote_amount,
+        protocol_fee,
+    });
+
+    Ok((base_amount, quote_amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FirmQuote;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateMarketMakerBadgeAction, FillFirmQuoteAction, PauseSellsAction,
+            PostFirmQuoteAction, RestrictWalletAction, RevokeMarketMakerBadgeAction,
+            SetMarketPausedAction, SwapAction, TokenMillEnv, UpdateFirmQuoteFeeAction,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const BASE_AMOUNT: u64 = 1_000_000_000;
+    const PRICE: u64 = 1_000;
+    const FIRM_QUOTE_FEE_BPS: u16 = 100;
+
+    fn setup_env(swap_type: SwapType) -> (TokenMillEnv, FillFirmQuoteAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &CreateMarketMakerBadgeAction::new(make_address("carol")),
+                &UpdateFirmQuoteFeeAction::new(FIRM_QUOTE_FEE_BPS),
+            ])
+            .unwrap();
+
+        // Both the market maker and the taker need base tokens
+        for user in ["bob", "carol"] {
+            testing_env.svm.change_payer(user);
+
+            let mut buy_action = SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                BASE_AMOUNT,
+                u64::MAX,
+                None,
+            );
+            buy_action.with_user(make_address(user));
+
+            testing_env.svm.execute_actions(&[&buy_action]).unwrap();
+        }
+
+        testing_env.svm.change_payer("carol");
+
+        let post_action = PostFirmQuoteAction::new(&testing_env, swap_type, BASE_AMOUNT, PRICE);
+
+        testing_env.svm.execute_actions(&[&post_action]).unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let action = FillFirmQuoteAction::new(&testing_env, &post_action, BASE_AMOUNT / 2);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn fill_firm_quote_buy() {
+        let (mut testing_env, action) = setup_env(SwapType::Buy);
+
+        let base_token_mint = testing_env.base_token_mint.unwrap();
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+
+        let base_balance_before = testing_env
+            .svm
+            .get_balance(&base_token_mint, &action.signer);
+        let quote_balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &action.signer);
+        let protocol_balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("dave"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let quote_amount = BASE_AMOUNT / 2 * PRICE / 1_000_000;
+        let protocol_fee = quote_amount * u64::from(FIRM_QUOTE_FEE_BPS) / 10_000;
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&base_token_mint, &action.signer)
+                - base_balance_before,
+            BASE_AMOUNT / 2
+        );
+        assert_eq!(
+            quote_balance_before
+                - testing_env
+                    .svm
+                    .get_balance(&quote_token_mint, &action.signer),
+            quote_amount + protocol_fee
+        );
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&quote_token_mint, &make_address("dave"))
+                - protocol_balance_before,
+            protocol_fee
+        );
+
+        let firm_quote = testing_env
+            .svm
+            .get_parsed_account::<FirmQuote>(&action.firm_quote);
+
+        assert_eq!(firm_quote.base_amount, BASE_AMOUNT / 2);
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&base_token_mint, &action.escrow_authority),
+            firm_quote.escrow_amount
+        );
+    }
+
+    #[test]
+    fn fill_firm_quote_sell() {
+        let (mut testing_env, action) = setup_env(SwapType::Sell);
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+
+        let quote_balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let quote_amount = BASE_AMOUNT / 2 * PRICE / 1_000_000;
+        let protocol_fee = quote_amount * u64::from(FIRM_QUOTE_FEE_BPS) / 10_000;
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&quote_token_mint, &action.signer)
+                - quote_balance_before,
+            quote_amount - protocol_fee
+        );
+    }
+
+    #[test]
+    fn fill_firm_quote_exceeding_size() {
+        let (mut testing_env, mut action) = setup_env(SwapType::Buy);
+
+        action.base_amount = BASE_AMOUNT + 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::FirmQuoteAmountExceeded);
+    }
+
+    #[test]
+    fn fill_firm_quote_with_threshold_not_met() {
+        let (mut testing_env, mut action) = setup_env(SwapType::Buy);
+
+        action.other_amount_threshold = BASE_AMOUNT / 2 * PRICE / 1_000_000;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::AmountThresholdNotMet);
+    }
+
+    #[test]
+    fn fill_expired_firm_quote() {
+        let (mut testing_env, action) = setup_env(SwapType::Buy);
+
+        testing_env.svm.warp(3_600);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::FirmQuoteExpired);
+    }
+
+    #[test]
+    fn fill_firm_quote_after_revocation() {
+        let (mut testing_env, action) = setup_env(SwapType::Buy);
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&RevokeMarketMakerBadgeAction::new(make_address("carol"))])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn fill_firm_quote_with_restricted_wallet() {
+        let (mut testing_env, action) = setup_env(SwapType::Buy);
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&RestrictWalletAction::new(&testing_env, action.signer)])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::WalletRestricted
+        );
+    }
+
+    #[test]
+    fn fill_firm_quote_sell_with_sells_paused() {
+        let (mut testing_env, action) = setup_env(SwapType::Sell);
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&PauseSellsAction::new(60)])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(tm_parse_error(result).unwrap(), TokenMillError::SellsPaused);
+    }
+
+    #[test]
+    fn fill_firm_quote_on_paused_market() {
+        let (mut testing_env, action) = setup_env(SwapType::Buy);
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetMarketPausedAction::new(true)])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketPaused
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::{TokenMillFirmQuoteCancelEvent, TokenMillGarbageCollectionEvent},
+    manager::{
+        swap_manager::SwapType,
+        token_manager::{close_pda_token_account, collect_rent, transfer_from_pda},
+    },
+    state::{FirmQuote, Market},
+    FIRM_QUOTE_ESCROW_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct GarbageCollectFirmQuote<'info> {
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = market_maker @ TokenMillError::InvalidAuthority
+    )]
+    pub firm_quote: Account<'info, FirmQuote>,
+
+    /// Base token mint for asks, quote token mint for bids
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Data-less PDA holding the escrow of the firm quote
+    #[account(
+        seeds = [FIRM_QUOTE_ESCROW_PDA_SEED.as_bytes(), firm_quote.key().as_ref()],
+        bump
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_authority,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = market_maker,
+        associated_token::token_program = token_program
+    )]
+    pub market_maker_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Market maker of the firm quote, receives the remaining escrow and the reclaimed rent minus the caller
+    /// reward
+    #[account(mut)]
+    pub market_maker: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Permissionless crank closing an expired firm quote.
+/// The remaining escrow is returned to the market maker, the caller is rewarded with a share of the reclaimed rent.
+pub fn handler(ctx: Context<GarbageCollectFirmQuote>) -> Result<()> {
+    let firm_quote = &ctx.accounts.firm_quote;
+
+    require!(
+        firm_quote.is_expired(Clock::get()?.unix_timestamp),
+        TokenMillError::NothingToCollect
+    );
+
+    let escrow_mint = {
+        let market = ctx.accounts.market.load()?;
+
+        match firm_quote.swap_type {
+            SwapType::Buy => market.base_token_mint,
+            SwapType::Sell => market.quote_token_mint,
+        }
+    };
+
+    require!(
+        ctx.accounts.token_mint.key() == escrow_mint,
+        TokenMillError::InvalidMintAccount
+    );
+
+    let firm_quote_key = firm_quote.key();
+    let escrow_authority_seeds = [
+        FIRM_QUOTE_ESCROW_PDA_SEED.as_bytes(),
+        firm_quote_key.as_ref(),
+        &[ctx.bumps.escrow_authority],
+    ];
+
+    // The whole balance is returned so that tokens sent to the escrow can't prevent closing it
+    let amount_returned = ctx.accounts.escrow_token_ata.amount;
+
+    if amount_returned > 0 {
+        transfer_from_pda(
+            &ctx.accounts.token_mint,
+            ctx.accounts.escrow_authority.to_account_info(),
+            &ctx.accounts.escrow_token_ata,
+            &ctx.accounts.market_maker_token_ata,
+            &ctx.accounts.token_program,
+            amount_returned,
+            &escrow_authority_seeds,
+        )?;
+    }
+
+    close_pda_token_account(
+        ctx.accounts.escrow_authority.to_account_info(),
+        &ctx.accounts.escrow_token_ata,
+        ctx.accounts.market_maker.to_account_info(),
+        &ctx.accounts.token_program,
+        &escrow_authority_seeds,
+    )?;
+
+    emit_cpi!(TokenMillFirmQuoteCancelEvent {
+        firm_quote: firm_quote_key,
+        amount_returned,
+    });
+
+    let (rent_reclaimed, caller_reward) = collect_rent(
+        firm_quote,
+        &ctx.accounts.caller.to_account_info(),
+        &ctx.accounts.market_maker.to_account_info(),
+    )?;
+
+    emit_cpi!(TokenMillGarbageCollectionEvent {
+        account: firm_quote_key,
+        caller: ctx.accounts.caller.key(),
+        rent_recipient: ctx.accounts.market_maker.key(),
+        rent_reclaimed,
+        caller_reward,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateMarketMakerBadgeAction, GarbageCollectFirmQuoteAction,
+            PostFirmQuoteAction, TokenMillEnv,
+        },
+        make_address, SwapType, TokenMillError,
+    };
+
+    const BASE_AMOUNT: u64 = 1_000_000_000;
+    const PRICE: u64 = 1_000;
+
+    fn setup_env() -> (
+        TokenMillEnv,
+        PostFirmQuoteAction,
+        GarbageCollectFirmQuoteAction,
+    ) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env
+            .svm
+            .execute_actions(&[&CreateMarketMakerBadgeAction::new(make_address("carol"))])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let post_action =
+            PostFirmQuoteAction::new(&testing_env, SwapType::Sell, BASE_AMOUNT, PRICE);
+
+        testing_env.svm.execute_actions(&[&post_action]).unwrap();
+
+        testing_env.svm.change_payer("dave");
+
+        let action = GarbageCollectFirmQuoteAction::new(&post_action);
+
+        (testing_env, post_action, action)
+    }
+
+    #[test]
+    fn garbage_collect_expired_firm_quote() {
+        let (mut testing_env, post_action, action) = setup_env();
+
+        testing_env.svm.warp(post_action.expiry);
+
+        let market_maker_balance_before = testing_env
+            .svm
+            .get_balance(&action.token_mint, &action.market_maker);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market_maker_balance_after = testing_env
+            .svm
+            .get_balance(&action.token_mint, &action.market_maker);
+
+        assert_eq!(
+            market_maker_balance_after - market_maker_balance_before,
+            BASE_AMOUNT * PRICE / 1_000_000
+        );
+
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.firm_quote)
+            .is_none());
+    }
+
+    #[test]
+    fn garbage_collect_live_firm_quote() {
+        let (mut testing_env, _, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::NothingToCollect);
+    }
+}
@@ -0,0 +1,9 @@
+pub mod cancel_firm_quote;
+pub mod fill_firm_quote;
+pub mod garbage_collect_firm_quote;
+pub mod post_firm_quote;
+
+pub use cancel_firm_quote::*;
+pub use fill_firm_quote::*;
+pub use garbage_collect_firm_quote::*;
+pub use post_firm_quote::*;
@@ -0,0 +1,283 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillFirmQuotePostEvent,
+    manager::{swap_manager::SwapType, token_manager::transfer_from_eoa},
+    state::{FirmQuote, Market, MarketMakerBadge, TokenMillConfig},
+    FIRM_QUOTE_ESCROW_PDA_SEED, MARKET_MAKER_BADGE_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PostFirmQuote<'info> {
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(has_one = config @ TokenMillError::InvalidConfigAccount)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        seeds = [
+            MARKET_MAKER_BADGE_PDA_SEED.as_bytes(),
+            config.key().as_ref(),
+            market_maker.key().as_ref(),
+        ],
+        bump = market_maker_badge.bump,
+    )]
+    pub market_maker_badge: Account<'info, MarketMakerBadge>,
+
+    #[account(init, payer = market_maker, space = 8 + FirmQuote::INIT_SPACE)]
+    pub firm_quote: Account<'info, FirmQuote>,
+
+    /// Base token mint for asks, quote token mint for bids
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Data-less PDA holding the escrow of the firm quote
+    #[account(
+        seeds = [FIRM_QUOTE_ESCROW_PDA_SEED.as_bytes(), firm_quote.key().as_ref()],
+        bump
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = market_maker,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow_authority,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = market_maker,
+        associated_token::token_program = token_program
+    )]
+    pub market_maker_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub market_maker: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<PostFirmQuote>,
+    swap_type: SwapType,
+    base_amount: u64,
+    price: u64,
+    expiry: i64,
+) -> Result<()> {
+    let escrow_mint = {
+        let market = ctx.accounts.market.load()?;
+
+        // Quotes compete with the curve and are held to the same market checks
+        require!(market.paused == 0, TokenMillError::MarketPaused);
+        require!(market.graduated == 0, TokenMillError::MarketGraduated);
+
+        match swap_type {
+            SwapType::Buy => market.base_token_mint,
+            SwapType::Sell => market.quote_token_mint,
+        }
+    };
+
+    require!(
+        ctx.accounts.token_mint.key() == escrow_mint,
+        TokenMillError::InvalidMintAccount
+    );
+
+    let firm_quote = &mut ctx.accounts.firm_quote;
+
+    firm_quote.initialize(
+        ctx.accounts.market.key(),
+        ctx.accounts.market_maker.key(),
+        swap_type,
+        base_amount,
+        price,
+        expiry,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    transfer_from_eoa(
+        &ctx.accounts.token_mint,
+        &ctx.accounts.market_maker,
+        &ctx.accounts.market_maker_token_ata,
+        &ctx.accounts.escrow_token_ata,
+        &ctx.accounts.token_program,
+        firm_quote.escrow_amount,
+    )?;
+
+    emit_cpi!(TokenMillFirmQuotePostEvent {
+        firm_quote: firm_quote.key(),
+        market: ctx.accounts.market.key(),
+        market_maker: ctx.accounts.market_maker.key(),
+        swap_type,
+        base_amount,
+        price,
+        expiry,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FirmQuote;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateMarketMakerBadgeAction, PostFirmQuoteAction,
+            SetMarketPausedAction, SwapAction, TokenMillEnv,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const BASE_AMOUNT: u64 = 1_000_000_000;
+    const PRICE: u64 = 1_000;
+
+    fn setup_env() -> TokenMillEnv {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env
+            .svm
+            .execute_actions(&[&CreateMarketMakerBadgeAction::new(make_address("carol"))])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let mut buy_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            BASE_AMOUNT,
+            u64::MAX,
+            None,
+        );
+        buy_action.with_user(make_address("carol"));
+
+        testing_env.svm.execute_actions(&[&buy_action]).unwrap();
+
+        testing_env
+    }
+
+    #[test]
+    fn post_firm_quote() {
+        let mut testing_env = setup_env();
+
+        for swap_type in [SwapType::Buy, SwapType::Sell] {
+            let mut action = PostFirmQuoteAction::new(&testing_env, swap_type, BASE_AMOUNT, PRICE);
+            action.firm_quote = make_address(&format!("firm_quote_{:?}", swap_type));
+
+            let market_maker_balance_before = testing_env
+                .svm
+                .get_balance(&action.token_mint, &action.signer);
+
+            let result = testing_env.svm.execute_actions(&[&action]);
+
+            assert!(result.is_ok());
+
+            let firm_quote = testing_env
+                .svm
+                .get_parsed_account::<FirmQuote>(&action.firm_quote);
+
+            assert_eq!(firm_quote.market_maker, action.signer);
+            assert_eq!(firm_quote.base_amount, BASE_AMOUNT);
+
+            let market_maker_balance_after = testing_env
+                .svm
+                .get_balance(&action.token_mint, &action.signer);
+
+            assert_eq!(
+                market_maker_balance_before - market_maker_balance_after,
+                firm_quote.escrow_amount
+            );
+            assert_eq!(
+                testing_env
+                    .svm
+                    .get_balance(&action.token_mint, &action.escrow_authority),
+                firm_quote.escrow_amount
+            );
+        }
+    }
+
+    #[test]
+    fn post_firm_quote_on_paused_market() {
+        let mut testing_env = setup_env();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetMarketPausedAction::new(true)])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let action = PostFirmQuoteAction::new(&testing_env, SwapType::Buy, BASE_AMOUNT, PRICE);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketPaused
+        );
+    }
+
+    #[test]
+    fn post_firm_quote_with_expired_quote() {
+        let mut testing_env = setup_env();
+
+        let mut action = PostFirmQuoteAction::new(&testing_env, SwapType::Buy, BASE_AMOUNT, PRICE);
+        action.expiry = 0;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidFirmQuote);
+    }
+
+    #[test]
+    fn post_firm_quote_with_invalid_mint() {
+        let mut testing_env = setup_env();
+
+        let mut action = PostFirmQuoteAction::new(&testing_env, SwapType::Buy, BASE_AMOUNT, PRICE);
+        let sell_action =
+            PostFirmQuoteAction::new(&testing_env, SwapType::Sell, BASE_AMOUNT, PRICE);
+        action.token_mint = sell_action.token_mint;
+        action.escrow_token_ata = sell_action.escrow_token_ata;
+        action.market_maker_token_ata = sell_action.market_maker_token_ata;
+        action.token_program = sell_action.token_program;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidMintAccount);
+    }
+
+    #[test]
+    fn post_firm_quote_without_badge() {
+        let mut testing_env = setup_env();
+
+        let mut action = PostFirmQuoteAction::new(&testing_env, SwapType::Sell, BASE_AMOUNT, PRICE);
+        action.signer = testing_env.svm.change_payer("mallory");
+        action.market_maker_token_ata = testing_env
+            .svm
+            .get_ata_address(&action.token_mint, &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+    }
+}
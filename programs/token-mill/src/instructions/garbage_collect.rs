@@ -0,0 +1,313 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillGarbageCollectionEvent,
+    manager::token_manager::collect_rent,
+    state::{StakePosition, VestingPlan},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct GarbageCollect<'info> {
+    #[account(mut, has_one = user @ TokenMillError::InvalidAuthority)]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(mut, has_one = stake_position @ TokenMillError::InvalidStakePosition)]
+    pub vesting_plan: Option<Account<'info, VestingPlan>>,
+
+    /// CHECK: Owner of the stake position, receives its reclaimed rent minus the caller reward
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+
+    /// CHECK: Funder of the vesting plan, receives its reclaimed rent minus the caller reward
+    #[account(mut)]
+    pub funder: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+}
+
+/// Permissionless crank closing auxiliary accounts that no longer hold any value: fully released vesting plans and
+/// empty stake positions without a linked badge.
+/// The caller is rewarded with a share of the reclaimed rent, the rest is returned to the wallet that paid it.
+pub fn handler(ctx: Context<GarbageCollect>) -> Result<()> {
+    let mut collected = false;
+
+    if let Some(vesting_plan) = &ctx.accounts.vesting_plan {
+        if vesting_plan.is_fully_released() {
+            // Plans of the first deployment have no funder, their rent having been paid by the owner of the position
+            let rent_recipient = if vesting_plan.funder == Pubkey::default() {
+                ctx.accounts.user.to_account_info()
+            } else {
+                ctx.accounts
+                    .funder
+                    .as_ref()
+                    .filter(|funder| funder.key() == vesting_plan.funder)
+                    .ok_or(TokenMillError::InvalidAuthority)?
+                    .to_account_info()
+            };
+
+            let (rent_reclaimed, caller_reward) = collect_rent(
+                vesting_plan,
+                &ctx.accounts.caller.to_account_info(),
+                &rent_recipient,
+            )?;
+
+            emit_cpi!(TokenMillGarbageCollectionEvent {
+                account: vesting_plan.key(),
+                caller: ctx.accounts.caller.key(),
+                rent_recipient: rent_recipient.key(),
+                rent_reclaimed,
+                caller_reward,
+            });
+
+            collected = true;
+        }
+    }
+
+    let stake_position = &ctx.accounts.stake_position;
+
+    if stake_position.is_empty() {
+        let (rent_reclaimed, caller_reward) = collect_rent(
+            stake_position,
+            &ctx.accounts.caller.to_account_info(),
+            &ctx.accounts.user.to_account_info(),
+        )?;
+
+        emit_cpi!(TokenMillGarbageCollectionEvent {
+            account: stake_position.key(),
+            caller: ctx.accounts.caller.key(),
+            rent_recipient: ctx.accounts.user.key(),
+            rent_reclaimed,
+            caller_reward,
+        });
+
+        collected = true;
+    }
+
+    require!(collected, TokenMillError::NothingToCollect);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateCreatorVestingPlanAction, DepositAction, GarbageCollectAction,
+            LinkStakingBadgeAction, ReleaseAction, SetStakingBadgeCollectionAction, SwapAction,
+            TokenMillEnv, WithdrawAction,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    fn setup_env() -> (TokenMillEnv, GarbageCollectAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        let mut action = GarbageCollectAction::new(&testing_env);
+
+        action.signer = testing_env.svm.change_payer("bob");
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn garbage_collect_empty_stake_position() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.stake_position)
+            .is_none());
+    }
+
+    #[test]
+    fn garbage_collect_empty_stake_position_of_other_user() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("dave");
+
+        let stake_position_rent = testing_env.svm.get_lamports(&action.stake_position);
+        let user_lamports_before = testing_env.svm.get_lamports(&action.user);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        // The rent goes back to the owner, minus the caller reward
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.stake_position)
+            .is_none());
+        assert!(testing_env.svm.get_lamports(&action.user) > user_lamports_before);
+        assert!(
+            testing_env.svm.get_lamports(&action.user) < user_lamports_before + stake_position_rent
+        );
+    }
+
+    #[test]
+    fn garbage_collect_stake_position_with_linked_badge() {
+        let (mut testing_env, action) = setup_env();
+
+        let badge_collection = make_address("badge_collection");
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetStakingBadgeCollectionAction::new(
+                &testing_env,
+                badge_collection,
+                12_000,
+            )])
+            .unwrap();
+
+        let badge_mint = testing_env
+            .svm
+            .create_group_member_nft(&badge_collection, &make_address("bob"))
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+        testing_env
+            .svm
+            .execute_actions(&[&LinkStakingBadgeAction::new(&testing_env, badge_mint)])
+            .unwrap();
+        testing_env
+            .svm
+            .execute_actions(&[&WithdrawAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+
+        // The badge stays linked to the position, which can't be closed until it's unlinked
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::NothingToCollect);
+    }
+
+    #[test]
+    fn garbage_collect_after_withdrawal() {
+        let (mut testing_env, action) = setup_env();
+
+        let deposit_action = DepositAction::new(&testing_env, STAKE_AMOUNT);
+        let withdraw_action = WithdrawAction::new(&testing_env, STAKE_AMOUNT);
+
+        testing_env
+            .svm
+            .execute_actions(&[&deposit_action, &withdraw_action])
+            .unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn garbage_collect_non_empty_stake_position() {
+        let (mut testing_env, action) = setup_env();
+
+        let deposit_action = DepositAction::new(&testing_env, STAKE_AMOUNT);
+
+        testing_env.svm.execute_actions(&[&deposit_action]).unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::NothingToCollect);
+    }
+
+    #[test]
+    fn garbage_collect_released_vesting_plan() {
+        const VESTING_AMOUNT: u64 = 1_000_000_000;
+        const VESTING_DURATION: i64 = 300;
+        const CLIFF_DURATION: i64 = 60;
+
+        let mut testing_env = TokenMillEnv::default().with_staking(0);
+
+        testing_env.svm.warp(1);
+
+        let alice = testing_env.svm.change_payer("alice");
+        let carol = make_address("carol");
+
+        let create_action = CreateCreatorVestingPlanAction::new(
+            &testing_env,
+            carol,
+            VESTING_AMOUNT,
+            1,
+            VESTING_DURATION,
+            CLIFF_DURATION,
+            false,
+        );
+
+        testing_env
+            .svm
+            .execute_actions(&[SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                VESTING_AMOUNT,
+                u64::MAX,
+                None,
+            )
+            .with_user(alice)])
+            .unwrap();
+
+        testing_env.svm.execute_actions(&[&create_action]).unwrap();
+
+        testing_env.svm.warp(VESTING_DURATION);
+        testing_env.svm.change_payer("carol");
+
+        let mut release_action = ReleaseAction::new();
+        release_action.vesting_plan = create_action.vesting_plan;
+        release_action.with_user(carol);
+
+        testing_env.svm.execute_actions(&[&release_action]).unwrap();
+
+        let mut action = GarbageCollectAction::new(&testing_env);
+        action.stake_position = create_action.stake_position;
+        action.user = carol;
+        action.signer = testing_env.svm.change_payer("dave");
+
+        // The rent of the plan goes back to its funder, not to the owner of the stake position
+        action.with_vesting_plan(create_action.vesting_plan, carol);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+
+        action.with_vesting_plan(create_action.vesting_plan, alice);
+
+        let vesting_plan_rent = testing_env.svm.get_lamports(&create_action.vesting_plan);
+        let funder_lamports_before = testing_env.svm.get_lamports(&alice);
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        assert!(testing_env
+            .svm
+            .try_get_account(&create_action.vesting_plan)
+            .is_none());
+        assert!(testing_env.svm.get_lamports(&alice) > funder_lamports_before);
+        assert!(testing_env.svm.get_lamports(&alice) < funder_lamports_before + vesting_plan_rent);
+    }
+}
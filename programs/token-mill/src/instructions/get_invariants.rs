@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Market, MarketInvariants};
+
+#[derive(Accounts)]
+pub struct GetInvariants<'info> {
+    pub market: AccountLoader<'info, Market>,
+}
+
+/// View instruction returning the derived quantities of a market
+pub fn handler(ctx: Context<GetInvariants>) -> Result<MarketInvariants> {
+    let market = ctx.accounts.market.load()?;
+
+    market.invariants()
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AnchorDeserialize;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{GetInvariantsAction, SwapAction, TokenMillEnv},
+        SwapAmountType, SwapType,
+    };
+
+    use crate::{
+        constant::{INTERVAL_NUMBER, MAX_BPS},
+        Market, MarketInvariants,
+    };
+
+    #[test]
+    fn get_invariants() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let action = GetInvariantsAction::new(&testing_env);
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let invariants = MarketInvariants::try_from_slice(&result.return_data.data).unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(
+            invariants.total_supply,
+            invariants.base_reserve + invariants.circulating_supply
+        );
+        assert_eq!(invariants.width_scaled, market.width_scaled);
+        assert_eq!(invariants.total_supply % INTERVAL_NUMBER, 0);
+        assert!(u64::from(invariants.fee_share_sum) <= MAX_BPS);
+        assert!(invariants.worst_case_quote > 0);
+
+        let (_, sell_quote) = market
+            .get_quote_amount(market.circulating_supply(), SwapAmountType::ExactInput)
+            .unwrap();
+
+        assert!(invariants.worst_case_quote >= sell_quote);
+    }
+}
@@ -0,0 +1,220 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::MAX_SIMULATION_SIZES,
+    errors::TokenMillError,
+    manager::swap_manager::SwapType,
+    state::{DepthLevel, LadderQuote, Market},
+};
+
+#[derive(Accounts)]
+pub struct GetMarketDepth<'info> {
+    pub market: AccountLoader<'info, Market>,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub struct MarketDepth {
+    pub ask_price: u64,
+    pub bid_price: u64,
+    /// Swap moving the spot price to the target price, see `Market::depth_to_price`
+    pub depth_to_price: DepthLevel,
+    /// Quotes of the sizes passed by the caller, in the same order
+    pub quotes: Vec<LadderQuote>,
+}
+
+/// View instruction returning the spot prices, the swap moving the price to `target_price` and the quotes of a
+/// ladder of base amounts, computed with the on-chain curve math.
+pub fn handler(
+    ctx: Context<GetMarketDepth>,
+    target_price: u64,
+    sizes: Vec<u64>,
+) -> Result<MarketDepth> {
+    require!(
+        sizes.len() <= MAX_SIMULATION_SIZES,
+        TokenMillError::InvalidSimulationSizes
+    );
+
+    let market = ctx.accounts.market.load()?;
+
+    Ok(MarketDepth {
+        ask_price: market.spot_price(SwapType::Buy)?,
+        bid_price: market.spot_price(SwapType::Sell)?,
+        depth_to_price: market.depth_to_price(target_price)?,
+        quotes: market.quote_ladder(&sizes)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AnchorDeserialize;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, GetMarketDepthAction, SimulateMarketAction, SwapAction, TokenMillEnv,
+            DEFAULT_TOTAL_SUPPLY,
+        },
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    use crate::{
+        constant::{INTERVAL_NUMBER, MAX_SIMULATION_SIZES},
+        instructions::{MarketDepth, MarketSimulation},
+        Market,
+    };
+
+    const BASE_AMOUNT: u64 = 150_000_000_000;
+
+    fn setup_env() -> TokenMillEnv {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            BASE_AMOUNT,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        testing_env
+    }
+
+    fn get_market_depth(
+        testing_env: &mut TokenMillEnv,
+        target_price: u64,
+        sizes: Vec<u64>,
+    ) -> MarketDepth {
+        let action = GetMarketDepthAction::new(testing_env, target_price, sizes);
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        MarketDepth::try_from_slice(&result.return_data.data).unwrap()
+    }
+
+    #[test]
+    fn get_market_depth_to_higher_price() {
+        let mut testing_env = setup_env();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+        let interval_supply = DEFAULT_TOTAL_SUPPLY / INTERVAL_NUMBER;
+
+        // Ask price at the end of the third interval
+        let target_price = market.get_ask_price(3 * interval_supply).unwrap();
+
+        let depth = get_market_depth(&mut testing_env, target_price, vec![]);
+
+        assert_eq!(depth.ask_price, market.get_ask_price(BASE_AMOUNT).unwrap());
+        assert_eq!(depth.bid_price, market.get_bid_price(BASE_AMOUNT).unwrap());
+        assert_eq!(depth.depth_to_price.supply, 3 * interval_supply);
+        assert_eq!(
+            depth.depth_to_price.base_amount,
+            3 * interval_supply - BASE_AMOUNT
+        );
+
+        // Buying the depth moves the ask price to the target
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            depth.depth_to_price.base_amount,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let depth = get_market_depth(&mut testing_env, target_price, vec![]);
+
+        assert_eq!(depth.ask_price, target_price);
+        assert_eq!(depth.depth_to_price.base_amount, 0);
+    }
+
+    #[test]
+    fn get_market_depth_to_lower_price() {
+        let mut testing_env = setup_env();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+        let interval_supply = DEFAULT_TOTAL_SUPPLY / INTERVAL_NUMBER;
+
+        let target_price = market.get_bid_price(interval_supply).unwrap();
+
+        let depth = get_market_depth(&mut testing_env, target_price, vec![]);
+
+        assert_eq!(depth.depth_to_price.supply, interval_supply);
+        assert_eq!(
+            depth.depth_to_price.base_amount,
+            BASE_AMOUNT - interval_supply
+        );
+        assert_eq!(
+            depth.depth_to_price.quote_amount,
+            market
+                .get_quote_amount(BASE_AMOUNT - interval_supply, SwapAmountType::ExactInput)
+                .unwrap()
+                .1
+        );
+
+        // Below the curve, every token in circulation is sold
+        let depth = get_market_depth(&mut testing_env, 0, vec![]);
+
+        assert_eq!(depth.depth_to_price.supply, 0);
+        assert_eq!(depth.depth_to_price.base_amount, BASE_AMOUNT);
+    }
+
+    #[test]
+    fn get_market_depth_within_spread() {
+        let mut testing_env = setup_env();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        let (ask_price, bid_price) = market.get_spot_prices().unwrap();
+
+        for target_price in [bid_price, (ask_price + bid_price) / 2, ask_price] {
+            let depth = get_market_depth(&mut testing_env, target_price, vec![]);
+
+            assert_eq!(depth.depth_to_price.supply, BASE_AMOUNT);
+            assert_eq!(depth.depth_to_price.base_amount, 0);
+            assert_eq!(depth.depth_to_price.quote_amount, 0);
+        }
+    }
+
+    #[test]
+    fn get_market_depth_quotes_match_simulation() {
+        let mut testing_env = setup_env();
+
+        let sizes = vec![1_000_000_000, 10_000_000_000, DEFAULT_TOTAL_SUPPLY];
+
+        let depth = get_market_depth(&mut testing_env, 0, sizes.clone());
+
+        let action = SimulateMarketAction::new(&testing_env, sizes);
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+        let simulation = MarketSimulation::try_from_slice(&result.return_data.data).unwrap();
+
+        assert_eq!(depth.quotes, simulation.quotes);
+        assert_eq!(depth.ask_price, simulation.ask_price);
+        assert_eq!(depth.bid_price, simulation.bid_price);
+    }
+
+    #[test]
+    fn get_market_depth_with_too_many_sizes() {
+        let mut testing_env = setup_env();
+
+        let action =
+            GetMarketDepthAction::new(&testing_env, 0, vec![1_000_000; MAX_SIMULATION_SIZES + 1]);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidSimulationSizes
+        );
+    }
+}
@@ -0,0 +1,298 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillSecondaryCurveBuyEvent,
+    manager::{
+        price_feed_manager,
+        token_manager::{transfer_from_eoa, transfer_from_pda},
+    },
+    state::{Market, TokenMillConfig},
+    MARKET_PDA_SEED, RESTRICTED_WALLET_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BuySecondaryCurve<'info> {
+    #[account(constraint = !config.swaps_paused @ TokenMillError::SwapsPaused)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        mut,
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    pub base_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub quote_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = base_token_program
+    )]
+    pub user_base_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = quote_token_program
+    )]
+    pub user_quote_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Pyth price update of the quote token, required if the market registered one. Checked in the handler
+    pub quote_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Compliance hold of the user on this market, must not exist
+    #[account(
+        seeds = [RESTRICTED_WALLET_PDA_SEED.as_bytes(), market.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = restricted_wallet.data_is_empty() @ TokenMillError::WalletRestricted
+    )]
+    pub restricted_wallet: UncheckedAccount<'info>,
+
+    pub user: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Buys from the supply left unsold by the graduation, at the flat price set by the creator.
+/// The quote amount paid is added to the pending staking fees, distributed to the stakers like the swap fees.
+pub fn handler(
+    ctx: Context<BuySecondaryCurve>,
+    base_amount: u64,
+    max_quote_amount: u64,
+) -> Result<u64> {
+    let (quote_amount, base_token_mint, bump) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        require!(market.paused == 0, TokenMillError::MarketPaused);
+
+        price_feed_manager::check_quote_peg(market, ctx.accounts.quote_price_feed.as_deref())?;
+
+        let quote_amount = market.buy_secondary_curve(base_amount)?;
+
+        (quote_amount, market.base_token_mint, market.bump)
+    };
+
+    require!(
+        quote_amount <= max_quote_amount,
+        TokenMillError::AmountThresholdNotMet
+    );
+
+    transfer_from_eoa(
+        &ctx.accounts.quote_token_mint,
+        &ctx.accounts.user,
+        &ctx.accounts.user_quote_token_ata,
+        &ctx.accounts.market_quote_token_ata,
+        &ctx.accounts.quote_token_program,
+        quote_amount,
+    )?;
+
+    let market_seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint.as_ref(),
+        &[bump],
+    ];
+
+    transfer_from_pda(
+        &ctx.accounts.base_token_mint,
+        ctx.accounts.market.to_account_info(),
+        &ctx.accounts.market_base_token_ata,
+        &ctx.accounts.user_base_token_ata,
+        &ctx.accounts.base_token_program,
+        base_amount,
+        &market_seeds,
+    )?;
+
+    emit_cpi!(TokenMillSecondaryCurveBuyEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        base_amount,
+        quote_amount,
+    });
+
+    Ok(quote_amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{state::GraduationAdapter, Market};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, BuySecondaryCurveAction, GraduateMarketAction,
+            SetGraduationConfigAction, SetGraduationSupplyAction, SetMaxRaiseAction,
+            SetSecondaryCurveAction, SwapAction, TokenMillEnv, DEFAULT_TOTAL_SUPPLY,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const GRADUATION_SUPPLY: u64 = DEFAULT_TOTAL_SUPPLY / 5;
+    const BASE_AMOUNT: u64 = 1_000_000_000;
+
+    fn setup_env() -> (TokenMillEnv, GraduateMarketAction, BuySecondaryCurveAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        let max_ask_price = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market)
+            .max_ask_price();
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetGraduationSupplyAction::new(
+                &testing_env,
+                GRADUATION_SUPPLY,
+            )])
+            .unwrap();
+        testing_env
+            .svm
+            .execute_actions(&[&SetSecondaryCurveAction::new(&testing_env, max_ask_price)])
+            .unwrap();
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetGraduationConfigAction::new(
+                GraduationAdapter::RaydiumCpmm,
+                make_address("pool_program"),
+                make_address("pool_config"),
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                BASE_AMOUNT,
+                u64::MAX,
+                None,
+            )])
+            .unwrap();
+
+        // Caps the raise at the amount raised so far, most of the supply remaining unsold
+        let quote_raised = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market)
+            .quote_raised;
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetMaxRaiseAction::new(quote_raised)])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let graduate_action = GraduateMarketAction::new(&testing_env);
+        let action = BuySecondaryCurveAction::new(&testing_env, BASE_AMOUNT);
+
+        (testing_env, graduate_action, action)
+    }
+
+    #[test]
+    fn buy_secondary_curve() {
+        let (mut testing_env, graduate_action, action) = setup_env();
+
+        testing_env
+            .svm
+            .execute_actions(&[&graduate_action])
+            .unwrap();
+
+        let market_before = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        // The unsold supply isn't burned
+        assert_eq!(
+            market_before.secondary_curve_supply,
+            DEFAULT_TOTAL_SUPPLY - GRADUATION_SUPPLY - BASE_AMOUNT
+        );
+        assert_eq!(market_before.total_supply, DEFAULT_TOTAL_SUPPLY);
+
+        let user_base_balance_before = testing_env
+            .svm
+            .get_balance(&action.base_token_mint, &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(
+            market.secondary_curve_supply,
+            market_before.secondary_curve_supply - BASE_AMOUNT
+        );
+        assert!(market.fees.pending_staking_fees > market_before.fees.pending_staking_fees);
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &action.signer)
+                - user_base_balance_before,
+            BASE_AMOUNT
+        );
+    }
+
+    #[test]
+    fn buy_secondary_curve_with_slippage() {
+        let (mut testing_env, graduate_action, mut action) = setup_env();
+
+        testing_env
+            .svm
+            .execute_actions(&[&graduate_action])
+            .unwrap();
+
+        action.max_quote_amount = 0;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::AmountThresholdNotMet);
+    }
+
+    #[test]
+    fn buy_secondary_curve_before_graduation() {
+        let (mut testing_env, _, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::SecondaryCurveInactive);
+    }
+}
@@ -0,0 +1,267 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillGraduationCompletionEvent,
+    manager::graduation_manager::{self, GraduationLiquidity},
+    state::{Graduation, GraduationStatus, Market, ProtocolStats, TokenMillConfig},
+    GRADUATION_AUTHORITY_PDA_SEED, GRADUATION_PDA_SEED, PROTOCOL_STATS_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CompleteGraduation<'info> {
+    pub config: Box<Account<'info, TokenMillConfig>>,
+
+    #[account(
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        mut,
+        seeds = [GRADUATION_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump = graduation.bump,
+        constraint = graduation.status == GraduationStatus::Pending @ TokenMillError::GraduationCompleted
+    )]
+    pub graduation: Box<Account<'info, Graduation>>,
+
+    pub base_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub quote_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Data-less PDA creating the pool, funded with the lamports of the pool creation
+    #[account(
+        mut,
+        seeds = [GRADUATION_AUTHORITY_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub graduation_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = graduation_authority,
+        associated_token::token_program = base_token_program
+    )]
+    pub graduation_authority_base_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = graduation_authority,
+        associated_token::token_program = quote_token_program
+    )]
+    pub graduation_authority_quote_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Pool program set when the graduation started
+    #[account(address = graduation.pool_program @ TokenMillError::InvalidGraduationAccounts)]
+    pub pool_program: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            PROTOCOL_STATS_PDA_SEED.as_bytes(),
+            config.key().as_ref(),
+            quote_token_mint.key().as_ref(),
+        ],
+        bump = protocol_stats.bump,
+    )]
+    pub protocol_stats: Option<Box<Account<'info, ProtocolStats>>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates the pool of a pending graduation through the adapter of its AMM. Permissionless.
+/// Remaining accounts are the accounts of the pool creation instruction of the AMM, in its order.
+/// The payer funds the graduation authority with `pool_creation_lamports` for the pool accounts and creation fee,
+/// getting back what the pool program didn't use. The liquidity is held by the graduation authority for good.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CompleteGraduation<'info>>,
+    pool_creation_lamports: u64,
+) -> Result<Pubkey> {
+    let market_key = ctx.accounts.market.key();
+    let graduation_authority_seeds = [
+        GRADUATION_AUTHORITY_PDA_SEED.as_bytes(),
+        market_key.as_ref(),
+        &[ctx.bumps.graduation_authority],
+    ];
+
+    transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: ctx.accounts.graduation_authority.to_account_info(),
+            },
+        ),
+        pool_creation_lamports,
+    )?;
+
+    let graduation = &ctx.accounts.graduation;
+
+    let pool = graduation_manager::create_pool(
+        graduation.adapter,
+        &ctx.accounts.pool_program.to_account_info(),
+        ctx.remaining_accounts,
+        &GraduationLiquidity {
+            market: market_key,
+            graduation_authority: ctx.accounts.graduation_authority.key(),
+            graduation_authority_seeds: &graduation_authority_seeds,
+            pool_config: graduation.pool_config,
+            base_token_mint: ctx.accounts.base_token_mint.key(),
+            quote_token_mint: ctx.accounts.quote_token_mint.key(),
+            base_token_account: ctx.accounts.graduation_authority_base_token_ata.key(),
+            quote_token_account: ctx.accounts.graduation_authority_quote_token_ata.key(),
+            base_amount: graduation.base_amount,
+            quote_amount: graduation.quote_amount,
+        },
+    )?;
+
+    let lamports_left = ctx.accounts.graduation_authority.lamports();
+
+    if lamports_left > 0 {
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.graduation_authority.to_account_info(),
+                    to: ctx.accounts.payer.to_account_info(),
+                },
+                &[&graduation_authority_seeds],
+            ),
+            lamports_left,
+        )?;
+    }
+
+    ctx.accounts.graduation.complete(pool)?;
+
+    if let Some(protocol_stats) = &mut ctx.accounts.protocol_stats {
+        protocol_stats.markets_graduated += 1;
+    }
+
+    emit_cpi!(TokenMillGraduationCompletionEvent {
+        market: market_key,
+        graduation: ctx.accounts.graduation.key(),
+        pool_program: ctx.accounts.pool_program.key(),
+        pool,
+    });
+
+    Ok(pool)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{manager::graduation_manager::raydium_cpmm, state::GraduationAdapter};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CompleteGraduationAction, GraduateMarketAction,
+            SetGraduationConfigAction, SetGraduationSupplyAction, SwapAction, TokenMillEnv,
+            DEFAULT_TOTAL_SUPPLY,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    // No pool program runs in the test environment, only the checks preceding the pool creation are covered
+    fn setup_env() -> (TokenMillEnv, CompleteGraduationAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetGraduationSupplyAction::new(
+                &testing_env,
+                DEFAULT_TOTAL_SUPPLY / 5,
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetGraduationConfigAction::new(
+                GraduationAdapter::RaydiumCpmm,
+                make_address("pool_program"),
+                make_address("pool_config"),
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &SwapAction::new(
+                    &testing_env,
+                    SwapType::Buy,
+                    SwapAmountType::ExactOutput,
+                    DEFAULT_TOTAL_SUPPLY,
+                    u64::MAX,
+                    None,
+                ),
+                &GraduateMarketAction::new(&testing_env),
+            ])
+            .unwrap();
+
+        let action = CompleteGraduationAction::new(&testing_env);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn complete_graduation_with_invalid_pool_program() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.pool_program = make_address("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidGraduationAccounts
+        );
+    }
+
+    #[test]
+    fn complete_graduation_with_missing_pool_accounts() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action
+            .pool_accounts
+            .truncate(raydium_cpmm::ACCOUNTS_LEN - 1);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidGraduationAccounts
+        );
+    }
+
+    #[test]
+    fn complete_graduation_with_invalid_pool_accounts() {
+        let (mut testing_env, mut action) = setup_env();
+
+        // Pool created by mallory instead of the graduation authority
+        action.pool_accounts[0].pubkey = make_address("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidGraduationAccounts
+        );
+    }
+}
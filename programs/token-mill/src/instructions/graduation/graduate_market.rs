@@ -0,0 +1,329 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillMarketGraduationEvent,
+    manager::token_manager::{burn_from_pda, transfer_from_pda},
+    state::{Graduation, Market, TokenMillConfig},
+    GRADUATION_AUTHORITY_PDA_SEED, GRADUATION_PDA_SEED, MARKET_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct GraduateMarket<'info> {
+    #[account(
+        constraint = config.graduation_program != Pubkey::default() @ TokenMillError::GraduationDisabled
+    )]
+    pub config: Box<Account<'info, TokenMillConfig>>,
+
+    #[account(
+        mut,
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        init,
+        seeds = [GRADUATION_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + Graduation::INIT_SPACE
+    )]
+    pub graduation: Box<Account<'info, Graduation>>,
+
+    #[account(mut)]
+    pub base_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub quote_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Data-less PDA holding the liquidity until the pool is created
+    #[account(
+        seeds = [GRADUATION_AUTHORITY_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub graduation_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = graduation_authority,
+        associated_token::token_program = base_token_program
+    )]
+    pub graduation_authority_base_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = graduation_authority,
+        associated_token::token_program = quote_token_program
+    )]
+    pub graduation_authority_quote_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Starts the graduation of a market whose curve sold out or whose max raise is reached. Permissionless.
+/// Curve swaps are disabled for good, the unsold supply is burned or moved to the secondary curve, and the graduation supply and the quote amount
+/// backing the circulating supply on the bid curve move to the graduation authority until `complete_graduation`
+/// seeds the pool of the AMM set in the config with them.
+pub fn handler(ctx: Context<GraduateMarket>) -> Result<(u64, u64)> {
+    let (base_amount, quote_amount, burned_amount, secondary_curve_supply, market_bump) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        let (base_amount, quote_amount, burned_amount) = market.graduate()?;
+
+        (
+            base_amount,
+            quote_amount,
+            burned_amount,
+            market.secondary_curve_supply,
+            market.bump,
+        )
+    };
+
+    let base_token_mint_key = ctx.accounts.base_token_mint.key();
+    let market_seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint_key.as_ref(),
+        &[market_bump],
+    ];
+
+    transfer_from_pda(
+        &ctx.accounts.base_token_mint,
+        ctx.accounts.market.to_account_info(),
+        &ctx.accounts.market_base_token_ata,
+        &ctx.accounts.graduation_authority_base_token_ata,
+        &ctx.accounts.base_token_program,
+        base_amount,
+        &market_seeds,
+    )?;
+
+    transfer_from_pda(
+        &ctx.accounts.quote_token_mint,
+        ctx.accounts.market.to_account_info(),
+        &ctx.accounts.market_quote_token_ata,
+        &ctx.accounts.graduation_authority_quote_token_ata,
+        &ctx.accounts.quote_token_program,
+        quote_amount,
+        &market_seeds,
+    )?;
+
+    if burned_amount > 0 {
+        burn_from_pda(
+            &ctx.accounts.base_token_mint,
+            ctx.accounts.market.to_account_info(),
+            &ctx.accounts.market_base_token_ata,
+            &ctx.accounts.base_token_program,
+            burned_amount,
+            &market_seeds,
+        )?;
+    }
+
+    let config = &ctx.accounts.config;
+
+    ctx.accounts.graduation.initialize(
+        ctx.bumps.graduation,
+        ctx.accounts.market.key(),
+        config.graduation_adapter,
+        config.graduation_program,
+        config.graduation_pool_config,
+        base_amount,
+        quote_amount,
+        burned_amount,
+    )?;
+
+    emit_cpi!(TokenMillMarketGraduationEvent {
+        market: ctx.accounts.market.key(),
+        graduation: ctx.accounts.graduation.key(),
+        adapter: config.graduation_adapter,
+        base_amount,
+        quote_amount,
+        burned_amount,
+        secondary_curve_supply,
+    });
+
+    Ok((base_amount, quote_amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        state::{Graduation, GraduationAdapter, GraduationStatus},
+        Market,
+    };
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, GraduateMarketAction, SetGraduationConfigAction,
+            SetGraduationSupplyAction, SwapAction, TokenMillEnv, DEFAULT_TOTAL_SUPPLY,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const GRADUATION_SUPPLY: u64 = DEFAULT_TOTAL_SUPPLY / 5;
+
+    fn setup_env() -> (TokenMillEnv, GraduateMarketAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetGraduationSupplyAction::new(
+                &testing_env,
+                GRADUATION_SUPPLY,
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetGraduationConfigAction::new(
+                GraduationAdapter::RaydiumCpmm,
+                make_address("pool_program"),
+                make_address("pool_config"),
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let action = GraduateMarketAction::new(&testing_env);
+
+        (testing_env, action)
+    }
+
+    fn buy_out(testing_env: &mut TokenMillEnv) -> SwapAction {
+        let swap_action = SwapAction::new(
+            testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            DEFAULT_TOTAL_SUPPLY,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        swap_action
+    }
+
+    #[test]
+    fn graduate_market() {
+        let (mut testing_env, action) = setup_env();
+
+        let swap_action = buy_out(&mut testing_env);
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        // Buys stop at the graduation supply
+        assert_eq!(market.base_reserve, GRADUATION_SUPPLY);
+
+        let quote_reserve = testing_env
+            .svm
+            .get_balance(&action.quote_token_mint, &testing_env.market);
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let graduation = testing_env
+            .svm
+            .get_parsed_account::<Graduation>(&action.graduation);
+
+        assert_eq!(graduation.status, GraduationStatus::Pending);
+        assert_eq!(graduation.adapter, GraduationAdapter::RaydiumCpmm);
+        assert_eq!(graduation.pool_program, make_address("pool_program"));
+        assert_eq!(graduation.base_amount, GRADUATION_SUPPLY);
+        assert_eq!(graduation.burned_amount, 0);
+        assert!(graduation.quote_amount > 0 && graduation.quote_amount <= quote_reserve);
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &action.graduation_authority),
+            GRADUATION_SUPPLY
+        );
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.quote_token_mint, &action.graduation_authority),
+            graduation.quote_amount
+        );
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketGraduated
+        );
+    }
+
+    #[test]
+    fn graduate_market_before_sell_out() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::GraduationNotReached
+        );
+    }
+
+    #[test]
+    fn graduate_market_disabled() {
+        let (mut testing_env, action) = setup_env();
+
+        buy_out(&mut testing_env);
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetGraduationConfigAction::new(
+                GraduationAdapter::RaydiumCpmm,
+                Default::default(),
+                Default::default(),
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::GraduationDisabled
+        );
+    }
+}
@@ -0,0 +1,7 @@
+pub mod buy_secondary_curve;
+pub mod complete_graduation;
+pub mod graduate_market;
+
+pub use buy_secondary_curve::*;
+pub use complete_graduation::*;
+pub use graduate_market::*;
@@ -0,0 +1,229 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillMigrationClaimEvent,
+    manager::token_manager::transfer_from_pda,
+    state::{Market, Migration, MigrationClaim, MIGRATION_CLAIM_PDA_SEED},
+    MARKET_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimMigration<'info> {
+    #[account(mut, has_one = base_token_mint @ TokenMillError::InvalidMintAccount)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub migration: Account<'info, Migration>,
+
+    #[account(
+        init,
+        seeds = [MIGRATION_CLAIM_PDA_SEED.as_bytes(), migration.key().as_ref(), holder.key().as_ref()],
+        bump,
+        payer = holder,
+        space = 8 + MigrationClaim::INIT_SPACE
+    )]
+    pub migration_claim: Account<'info, MigrationClaim>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = holder,
+        associated_token::token_program = base_token_program
+    )]
+    pub holder_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Claims the allocation of a holder of the migration snapshot from the claim supply escrowed by the market.
+/// `proof` holds the sibling nodes from the `hash_holder(holder, amount)` leaf up to the root.
+pub fn handler(ctx: Context<ClaimMigration>, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+    let holder = ctx.accounts.holder.key();
+
+    ctx.accounts.migration.claim(&holder, amount, &proof)?;
+
+    ctx.accounts.migration_claim.initialize(
+        ctx.bumps.migration_claim,
+        ctx.accounts.migration.key(),
+        holder,
+        amount,
+    )?;
+
+    let bump = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        market.release_base(amount)?;
+
+        market.bump
+    };
+    let base_token_mint = ctx.accounts.base_token_mint.key();
+
+    let market_seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint.as_ref(),
+        &[bump],
+    ];
+
+    transfer_from_pda(
+        &ctx.accounts.base_token_mint,
+        ctx.accounts.market.to_account_info(),
+        &ctx.accounts.market_base_token_ata,
+        &ctx.accounts.holder_base_token_ata,
+        &ctx.accounts.base_token_program,
+        amount,
+        &market_seeds,
+    )?;
+
+    emit_cpi!(TokenMillMigrationClaimEvent {
+        migration: ctx.accounts.migration.key(),
+        holder,
+        amount,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash_holder, hash_nodes, Market, Migration};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            migration_claim_address, tm_parse_error, ClaimMigrationAction, ImportMigrationAction,
+            TokenMillEnv, DEFAULT_TOTAL_SUPPLY,
+        },
+        make_address, TokenMillError,
+    };
+
+    const BOB_AMOUNT: u64 = 1_000_000_000;
+    const CAROL_AMOUNT: u64 = 2_000_000_000;
+
+    fn setup_env() -> (TokenMillEnv, ClaimMigrationAction) {
+        let mut testing_env = TokenMillEnv::new()
+            .with_default_quote_token_mint()
+            .with_wrapped_market_from_mint_supply(
+                2 * DEFAULT_TOTAL_SUPPLY,
+                DEFAULT_TOTAL_SUPPLY,
+                DEFAULT_TOTAL_SUPPLY,
+            );
+
+        let bob_leaf = hash_holder(&make_address("bob"), BOB_AMOUNT);
+        let carol_leaf = hash_holder(&make_address("carol"), CAROL_AMOUNT);
+
+        testing_env.svm.change_payer("alice");
+
+        let import_action = ImportMigrationAction::new(
+            &testing_env,
+            hash_nodes(&bob_leaf, &carol_leaf),
+            BOB_AMOUNT + CAROL_AMOUNT,
+        );
+
+        testing_env.svm.execute_actions(&[&import_action]).unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let action = ClaimMigrationAction::new(&testing_env, BOB_AMOUNT, vec![carol_leaf]);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn claim_migration() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &make_address("bob")),
+            BOB_AMOUNT
+        );
+
+        let migration = testing_env
+            .svm
+            .get_parsed_account::<Migration>(&action.migration);
+
+        assert_eq!(migration.claimed_amount, BOB_AMOUNT);
+
+        // The reserve is untouched
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.base_reserve, DEFAULT_TOTAL_SUPPLY);
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &testing_env.market),
+            DEFAULT_TOTAL_SUPPLY + CAROL_AMOUNT
+        );
+    }
+
+    #[test]
+    fn claim_migration_twice() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &make_address("bob")),
+            BOB_AMOUNT
+        );
+    }
+
+    #[test]
+    fn claim_migration_with_invalid_proof() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.amount = CAROL_AMOUNT;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidMerkleProof
+        );
+    }
+
+    #[test]
+    fn claim_migration_of_another_holder() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+        action.holder_base_token_ata = testing_env
+            .svm
+            .get_ata_address(&action.base_token_mint, &action.signer);
+        action.migration_claim = migration_claim_address(&action.migration, &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidMerkleProof
+        );
+    }
+}
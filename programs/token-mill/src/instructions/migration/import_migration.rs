@@ -0,0 +1,266 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillMigrationImportEvent,
+    manager::token_manager::transfer_from_eoa,
+    state::{Market, Migration, MIGRATION_PDA_SEED},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ImportMigration<'info> {
+    #[account(
+        mut,
+        has_one = creator @ TokenMillError::InvalidAuthority,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        init,
+        seeds = [MIGRATION_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        payer = creator,
+        space = 8 + Migration::INIT_SPACE
+    )]
+    pub migration: Account<'info, Migration>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = base_token_program
+    )]
+    pub creator_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Records the import of a project from another launchpad into a wrap-mode market, before its first trade.
+/// The total supply of the market is the unsold supply of the project, the rest of the mint supply being held by the
+/// holders of the snapshot committed in `holders_root`. The creator can escrow `claim_supply` base tokens in the market
+/// for the holders to claim against the snapshot with `claim_migration`, or 0 to only record it.
+pub fn handler(
+    ctx: Context<ImportMigration>,
+    source: Pubkey,
+    snapshot_slot: u64,
+    holders_root: [u8; 32],
+    claim_supply: u64,
+) -> Result<()> {
+    let unsold_supply = {
+        let market = ctx.accounts.market.load()?;
+
+        require!(
+            market.circulating_supply() == 0
+                && ctx.accounts.base_token_mint.supply > market.total_supply,
+            TokenMillError::InvalidMigration
+        );
+
+        market.total_supply
+    };
+
+    require!(
+        holders_root != [0; 32] && snapshot_slot <= Clock::get()?.slot,
+        TokenMillError::InvalidMigration
+    );
+
+    ctx.accounts.migration.initialize(
+        ctx.bumps.migration,
+        ctx.accounts.market.key(),
+        source,
+        snapshot_slot,
+        holders_root,
+        claim_supply,
+    )?;
+
+    if claim_supply > 0 {
+        let balance_before = ctx.accounts.market_base_token_ata.amount;
+
+        transfer_from_eoa(
+            &ctx.accounts.base_token_mint,
+            &ctx.accounts.creator,
+            &ctx.accounts.creator_base_token_ata,
+            &ctx.accounts.market_base_token_ata,
+            &ctx.accounts.base_token_program,
+            claim_supply,
+        )?;
+
+        ctx.accounts.market_base_token_ata.reload()?;
+
+        require_eq!(
+            ctx.accounts.market_base_token_ata.amount - balance_before,
+            claim_supply,
+            TokenMillError::InvalidAmount
+        );
+
+        ctx.accounts.market.load_mut()?.escrow_base(claim_supply)?;
+    }
+
+    emit_cpi!(TokenMillMigrationImportEvent {
+        migration: ctx.accounts.migration.key(),
+        market: ctx.accounts.market.key(),
+        source,
+        snapshot_slot,
+        holders_root,
+        unsold_supply,
+        claim_supply,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Market, Migration};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, ImportMigrationAction, SwapAction, TokenMillEnv, DEFAULT_TOTAL_SUPPLY,
+        },
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const HOLDERS_SUPPLY: u64 = DEFAULT_TOTAL_SUPPLY / 2;
+    const CLAIM_SUPPLY: u64 = 1_000_000_000;
+
+    fn setup_env() -> (TokenMillEnv, ImportMigrationAction) {
+        let mut testing_env = TokenMillEnv::new()
+            .with_default_quote_token_mint()
+            .with_wrapped_market_from_mint_supply(
+                DEFAULT_TOTAL_SUPPLY + HOLDERS_SUPPLY,
+                DEFAULT_TOTAL_SUPPLY,
+                DEFAULT_TOTAL_SUPPLY,
+            );
+
+        testing_env.svm.change_payer("alice");
+
+        let action = ImportMigrationAction::new(&testing_env, [1; 32], CLAIM_SUPPLY);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn import_migration() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let migration = testing_env
+            .svm
+            .get_parsed_account::<Migration>(&action.migration);
+
+        assert_eq!(migration.market, testing_env.market);
+        assert_eq!(migration.source, action.source);
+        assert_eq!(migration.holders_root, [1; 32]);
+        assert_eq!(migration.claim_supply, CLAIM_SUPPLY);
+
+        // The claim supply is held on top of the reserve
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.base_reserve, DEFAULT_TOTAL_SUPPLY);
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &testing_env.market),
+            DEFAULT_TOTAL_SUPPLY + CLAIM_SUPPLY
+        );
+    }
+
+    #[test]
+    fn import_migration_without_claim() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.claim_supply = 0;
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &testing_env.market),
+            DEFAULT_TOTAL_SUPPLY
+        );
+    }
+
+    #[test]
+    fn import_migration_after_trade() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                1_000_000_000,
+                u64::MAX,
+                None,
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidMigration
+        );
+    }
+
+    #[test]
+    fn import_migration_without_holders() {
+        let mut testing_env = TokenMillEnv::new()
+            .with_default_quote_token_mint()
+            .with_wrapped_market(DEFAULT_TOTAL_SUPPLY, DEFAULT_TOTAL_SUPPLY);
+
+        testing_env.svm.change_payer("alice");
+
+        let action = ImportMigrationAction::new(&testing_env, [1; 32], 0);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidMigration
+        );
+    }
+
+    #[test]
+    fn import_migration_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+        action.creator_base_token_ata = testing_env
+            .svm
+            .get_ata_address(&action.base_token_mint, &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
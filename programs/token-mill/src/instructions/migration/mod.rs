@@ -0,0 +1,5 @@
+pub mod claim_migration;
+pub mod import_migration;
+
+pub use claim_migration::*;
+pub use import_migration::*;
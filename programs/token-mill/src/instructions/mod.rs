@@ -1,15 +1,66 @@
 pub mod admin;
+#[cfg(feature = "boost")]
+pub mod boost;
+pub mod buy_basket;
+pub mod buyback_and_burn;
+pub mod create_change_log;
 pub mod create_market;
+pub mod create_market_oracle;
+pub mod create_market_with_existing_mint;
+pub mod create_protocol_stats;
 pub mod creator;
+pub mod dca;
+pub mod delegation;
+pub mod firm_quotes;
+#[cfg(feature = "staking")]
+pub mod garbage_collect;
+pub mod get_invariants;
+pub mod get_market_depth;
+pub mod graduation;
+pub mod migration;
+pub mod quote_swap;
+#[cfg(feature = "referrals")]
 pub mod referrals;
+pub mod sell_and_burn;
+pub mod simulate_market;
+#[cfg(feature = "staking")]
 pub mod staking;
 pub mod swap;
+pub mod swap_route;
+pub mod trader_profile;
+pub mod validate_create_market;
+#[cfg(feature = "vesting")]
 pub mod vesting;
 
 pub use admin::*;
+#[cfg(feature = "boost")]
+pub use boost::*;
+pub use buyback_and_burn::*;
+pub use create_change_log::*;
 pub use create_market::*;
+pub use create_market_oracle::*;
+pub use create_market_with_existing_mint::*;
+pub use create_protocol_stats::*;
 pub use creator::*;
+pub use dca::*;
+pub use delegation::*;
+pub use firm_quotes::*;
+#[cfg(feature = "staking")]
+pub use garbage_collect::*;
+pub use get_invariants::*;
+pub use get_market_depth::*;
+pub use graduation::*;
+pub use migration::*;
+pub use quote_swap::*;
+#[cfg(feature = "referrals")]
 pub use referrals::*;
+pub use sell_and_burn::*;
+pub use simulate_market::*;
+#[cfg(feature = "staking")]
 pub use staking::*;
 pub use swap::*;
+pub use swap_route::*;
+pub use trader_profile::*;
+pub use validate_create_market::*;
+#[cfg(feature = "vesting")]
 pub use vesting::*;
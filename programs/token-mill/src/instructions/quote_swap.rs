@@ -0,0 +1,356 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    manager::swap_manager::{self, SwapAmountType, SwapQuote, SwapType},
+    state::{Market, StakePosition, TokenMillConfig, TraderStats},
+};
+
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(has_one = config @ TokenMillError::InvalidConfigAccount)]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: Wallet the swap is quoted for, giving its fee exemption
+    pub user: UncheckedAccount<'info>,
+
+    // 30-day volume of the user, giving its protocol fee rebate
+    #[account(
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = user @ TokenMillError::InvalidAuthority,
+        constraint = trader_stats.quote_token_mint == market.load()?.quote_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub trader_stats: Option<Box<Account<'info, TraderStats>>>,
+
+    // Stake position of the user on the protocol token market, giving its staker discount
+    #[account(has_one = user @ TokenMillError::InvalidAuthority)]
+    pub stake_position: Option<Box<Account<'info, StakePosition>>>,
+}
+
+/// View instruction returning the amounts and fees of a swap executed against the current market state.
+/// Runs the checks and fee computations of `swap` for the same user and accounts, `with_referral` standing for a
+/// referral token account and a non-zero `interface_fee_bps` for an interface fee token account.
+pub fn handler(
+    ctx: Context<QuoteSwap>,
+    swap_type: SwapType,
+    swap_amount_type: SwapAmountType,
+    amount: u64,
+    interface_fee_bps: u16,
+    with_referral: bool,
+) -> Result<SwapQuote> {
+    if amount == 0 {
+        return Err(TokenMillError::InvalidAmount.into());
+    }
+
+    let config = &ctx.accounts.config;
+
+    require!(
+        interface_fee_bps <= config.max_interface_fee_bps,
+        TokenMillError::InterfaceFeeTooHigh
+    );
+
+    let charged_interface_fee_bps = (interface_fee_bps != 0).then_some(interface_fee_bps);
+
+    let curve_amount = swap_manager::get_amount_without_interface_fee(
+        swap_type,
+        swap_amount_type,
+        amount,
+        charged_interface_fee_bps,
+    )?;
+
+    // The swap is applied to a copy of the market
+    let mut market = *ctx.accounts.market.load()?;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    if swap_type == SwapType::Sell && market.are_sells_paused(current_time) {
+        return Err(TokenMillError::SellsPaused.into());
+    }
+
+    let (base_amount, quote_amount, swap_fee) = swap_manager::swap(
+        &mut market,
+        swap_type,
+        swap_amount_type,
+        curve_amount,
+        &Clock::get()?,
+    )?;
+
+    let (quote_amount, swap_fee) = swap_manager::apply_fee_exemption(
+        config,
+        &ctx.accounts.user.key(),
+        swap_type,
+        quote_amount,
+        swap_fee,
+    )?;
+
+    #[cfg(feature = "referrals")]
+    let referral_fee_share = with_referral.then(|| market.get_referral_fee_share(config));
+    #[cfg(not(feature = "referrals"))]
+    let referral_fee_share = {
+        let _ = with_referral;
+        None
+    };
+
+    let (creator_fee, staking_fee, protocol_fee, referral_fee) =
+        market.fees.distribute_fee(swap_fee, referral_fee_share)?;
+
+    let interface_fee = swap_manager::get_swap_interface_fee(
+        swap_type,
+        swap_amount_type,
+        amount,
+        quote_amount,
+        charged_interface_fee_bps,
+    )?;
+
+    let refunds = swap_manager::get_protocol_fee_refunds(
+        config,
+        &market.quote_token_mint,
+        quote_amount,
+        protocol_fee,
+        ctx.accounts
+            .trader_stats
+            .as_ref()
+            .map(|trader_stats| trader_stats.get_volume(current_time)),
+        ctx.accounts
+            .stake_position
+            .as_ref()
+            .map(|stake_position| (stake_position.market, stake_position.amount_staked)),
+    )?;
+
+    let (amount_in, amount_out) = match swap_type {
+        SwapType::Buy => (quote_amount + interface_fee - refunds.total(), base_amount),
+        SwapType::Sell => (base_amount, quote_amount - interface_fee + refunds.total()),
+    };
+
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        base_amount,
+        quote_amount,
+        creator_fee,
+        staking_fee,
+        protocol_fee: protocol_fee - refunds.total(),
+        referral_fee,
+        interface_fee,
+        protocol_fee_rebate: refunds.rebate_amount(),
+        staker_discount: refunds.discount_amount(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AnchorDeserialize;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateTraderStatsAction, DepositAction, QuoteSwapAction,
+            SetFeeExemptionAction, SetRebateScheduleAction, SetStakerDiscountsAction, SwapAction,
+            TokenMillEnv, UpdateMaxInterfaceFeeAction,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+    use rstest::rstest;
+
+    use crate::{
+        manager::swap_manager::SwapQuote,
+        state::{RebateTier, StakerDiscountTier},
+    };
+
+    const BASE_AMOUNT: u64 = 1_000_000_000;
+
+    fn setup_env() -> TokenMillEnv {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            10 * BASE_AMOUNT,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        testing_env
+    }
+
+    #[rstest]
+    fn quote_swap(
+        #[values(SwapType::Buy, SwapType::Sell)] swap_type: SwapType,
+        #[values(SwapAmountType::ExactInput, SwapAmountType::ExactOutput)]
+        swap_amount_type: SwapAmountType,
+    ) {
+        let mut testing_env = setup_env();
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let bob = make_address("bob");
+
+        let action = QuoteSwapAction::new(&testing_env, swap_type, swap_amount_type, BASE_AMOUNT);
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let quote = SwapQuote::try_from_slice(&result.return_data.data).unwrap();
+
+        let swap_action = SwapAction::new(
+            &testing_env,
+            swap_type,
+            swap_amount_type,
+            BASE_AMOUNT,
+            match swap_amount_type {
+                SwapAmountType::ExactInput => 0,
+                SwapAmountType::ExactOutput => u64::MAX,
+            },
+            None,
+        );
+
+        let quote_balance_before = testing_env.svm.get_balance(&quote_token_mint, &bob);
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let quote_balance_after = testing_env.svm.get_balance(&quote_token_mint, &bob);
+
+        let (base_amount, quote_amount) =
+            <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        assert_eq!(quote.base_amount, base_amount);
+        assert_eq!(quote.quote_amount, quote_amount);
+
+        match swap_type {
+            SwapType::Buy => {
+                assert_eq!(quote_balance_before - quote_balance_after, quote.amount_in)
+            }
+            SwapType::Sell => {
+                assert_eq!(quote_balance_after - quote_balance_before, quote.amount_out)
+            }
+        }
+    }
+
+    #[rstest]
+    fn quote_swap_with_user_fees(
+        #[values(SwapType::Buy, SwapType::Sell)] swap_type: SwapType,
+        #[values(SwapAmountType::ExactInput, SwapAmountType::ExactOutput)]
+        swap_amount_type: SwapAmountType,
+    ) {
+        let mut testing_env = TokenMillEnv::default().with_staking(20 * BASE_AMOUNT);
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let bob = make_address("bob");
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &UpdateMaxInterfaceFeeAction::new(100),
+                &SetFeeExemptionAction::new(bob, 5_000),
+                &SetRebateScheduleAction::new(
+                    quote_token_mint,
+                    [
+                        RebateTier {
+                            min_volume: 0,
+                            rebate_bps: 10,
+                        },
+                        RebateTier::default(),
+                        RebateTier::default(),
+                    ],
+                ),
+                &SetStakerDiscountsAction::new(
+                    testing_env.market,
+                    [
+                        StakerDiscountTier {
+                            min_stake: 1,
+                            discount_bps: 5_000,
+                        },
+                        StakerDiscountTier::default(),
+                        StakerDiscountTier::default(),
+                    ],
+                ),
+            ])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &CreateTraderStatsAction::new(&testing_env),
+                &DepositAction::new(&testing_env, BASE_AMOUNT),
+            ])
+            .unwrap();
+
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            swap_type,
+            swap_amount_type,
+            BASE_AMOUNT,
+            match swap_amount_type {
+                SwapAmountType::ExactInput => 0,
+                SwapAmountType::ExactOutput => u64::MAX,
+            },
+            None,
+        );
+        swap_action
+            .with_interface_fee(make_address("carol"), 100)
+            .with_trader_stats()
+            .with_stake_position(testing_env.market);
+
+        let action = QuoteSwapAction::from_swap_action(&swap_action);
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let quote = SwapQuote::try_from_slice(&result.return_data.data).unwrap();
+
+        assert!(quote.interface_fee > 0);
+        assert!(quote.protocol_fee_rebate > 0);
+        assert!(quote.staker_discount > 0);
+
+        let quote_balance_before = testing_env.svm.get_balance(&quote_token_mint, &bob);
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let quote_balance_after = testing_env.svm.get_balance(&quote_token_mint, &bob);
+
+        let (base_amount, quote_amount) =
+            <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        // The quote matches the execution, fee exemption, interface fee, rebate and staker discount included
+        assert_eq!(quote.base_amount, base_amount);
+        assert_eq!(quote.quote_amount, quote_amount);
+
+        match swap_type {
+            SwapType::Buy => {
+                assert_eq!(quote_balance_before - quote_balance_after, quote.amount_in)
+            }
+            SwapType::Sell => {
+                assert_eq!(quote_balance_after - quote_balance_before, quote.amount_out)
+            }
+        }
+    }
+
+    #[test]
+    fn quote_swap_with_interface_fee_too_high() {
+        let mut testing_env = setup_env();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&UpdateMaxInterfaceFeeAction::new(100)])
+            .unwrap();
+
+        let mut action =
+            QuoteSwapAction::new(&testing_env, SwapType::Buy, SwapAmountType::ExactInput, 1);
+        action.interface_fee_bps = 101;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InterfaceFeeTooHigh);
+    }
+}
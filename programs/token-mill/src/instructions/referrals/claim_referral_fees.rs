@@ -22,11 +22,11 @@ pub struct ClaimReferralFees<'info> {
     )]
     pub referral_account_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
 
+    // Can be any quote token account, letting referrers route their fees to a treasury or cold storage
     #[account(
         mut,
-        associated_token::mint = quote_token_mint,
-        associated_token::authority = referrer,
-        associated_token::token_program = quote_token_program
+        token::mint = quote_token_mint,
+        token::token_program = quote_token_program
     )]
     pub referrer_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
 
@@ -97,4 +97,47 @@ mod tests {
             .execute_actions(&[&claim_referral_fees_action])
             .unwrap();
     }
+
+    #[test]
+    fn claim_referral_fees_to_recipient() {
+        let mut testing_env = TokenMillEnv::default();
+
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000,
+            u64::MAX,
+            Some(make_address("carol")),
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let treasury = make_address("treasury");
+        let treasury_quote_token_ata = testing_env
+            .svm
+            .create_ata(&treasury, &quote_token_mint, testing_env.quote_token_type)
+            .unwrap();
+
+        let mut claim_referral_fees_action = ClaimReferralFeesAction::new(&testing_env);
+
+        let pending_fees = testing_env.svm.get_balance(
+            &quote_token_mint,
+            &claim_referral_fees_action.referral_account,
+        );
+
+        testing_env
+            .svm
+            .execute_actions(&[claim_referral_fees_action.with_recipient(treasury_quote_token_ata)])
+            .unwrap();
+
+        assert!(pending_fees > 0);
+        assert_eq!(
+            testing_env.svm.get_balance(&quote_token_mint, &treasury),
+            pending_fees
+        );
+    }
 }
@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError, ReferralAccount, ReferralCode, TokenMillConfig, REFERRAL_CODE_PDA_SEED,
+};
+
+#[derive(Accounts)]
+#[instruction(code: String)]
+pub struct RegisterReferralCode<'info> {
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = referrer @ TokenMillError::InvalidAuthority
+    )]
+    pub referral_account: Account<'info, ReferralAccount>,
+
+    #[account(
+        init,
+        seeds = [REFERRAL_CODE_PDA_SEED.as_bytes(), config.key().as_ref(), code.as_bytes()],
+        bump,
+        payer = referrer,
+        space = 8 + ReferralCode::INIT_SPACE
+    )]
+    pub referral_code: Account<'info, ReferralCode>,
+
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Registers a code resolving to the referral account of the referrer, first come first served.
+/// Swaps passing the code account only accept the ATA of that referral account as referral token account.
+pub fn handler(ctx: Context<RegisterReferralCode>, code: String) -> Result<()> {
+    let referral_code = &mut ctx.accounts.referral_code;
+
+    referral_code.initialize(
+        ctx.bumps.referral_code,
+        ctx.accounts.config.key(),
+        ctx.accounts.referrer.key(),
+        ctx.accounts.referral_account.key(),
+        &code,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateReferralAccountAction, RegisterReferralCodeAction, TokenMillEnv,
+        },
+        make_address, TokenMillError,
+    };
+    use rstest::rstest;
+
+    use crate::ReferralCode;
+
+    #[test]
+    fn register_referral_code() {
+        let mut testing_env = TokenMillEnv::new();
+
+        testing_env.svm.change_payer("carol");
+
+        let action = RegisterReferralCodeAction::new("carol-42");
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let referral_code = testing_env
+            .svm
+            .get_parsed_account::<ReferralCode>(&action.referral_code);
+
+        assert_eq!(referral_code.config, testing_env.config);
+        assert_eq!(referral_code.referrer, make_address("carol"));
+        assert_eq!(
+            referral_code.referral_account,
+            CreateReferralAccountAction::new().referral_account
+        );
+        assert_eq!(&referral_code.code[..8], b"carol-42");
+        assert_eq!(referral_code.code[8..], [0; 8]);
+    }
+
+    #[test]
+    fn register_referral_code_twice() {
+        let mut testing_env = TokenMillEnv::new();
+
+        testing_env.svm.change_payer("carol");
+
+        let action = RegisterReferralCodeAction::new("carol");
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env.svm.warp(1);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    fn register_invalid_referral_code(
+        #[values("", "Carol", "carol 42", "carol.sol", "carol_the_referrer")] code: &str,
+    ) {
+        let mut testing_env = TokenMillEnv::new();
+
+        testing_env.svm.change_payer("carol");
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&RegisterReferralCodeAction::new(code)]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidReferralCode
+        );
+    }
+
+    #[test]
+    fn register_referral_code_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::new();
+
+        let mut action = RegisterReferralCodeAction::new("carol");
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
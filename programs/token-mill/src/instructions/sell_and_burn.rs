@@ -0,0 +1,290 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillSellAndBurnEvent,
+    manager::{
+        oracle_manager, price_feed_manager,
+        swap_manager::{self, SwapAmountType},
+        token_manager::{burn_from_eoa, transfer_from_pda},
+    },
+    state::Market,
+    TokenMillConfig, MARKET_ORACLE_PDA_SEED, MARKET_PDA_SEED, RESTRICTED_WALLET_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SellAndBurn<'info> {
+    #[account(constraint = !config.swaps_paused @ TokenMillError::SwapsPaused)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        mut,
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut)]
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.load()?.quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.load()?.base_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = base_token_program
+    )]
+    pub user_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.load()?.quote_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = quote_token_program
+    )]
+    pub user_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pyth price update of the quote token, required if the market registered one. Checked in the handler
+    pub quote_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: TWAP oracle of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [MARKET_ORACLE_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub market_oracle: UncheckedAccount<'info>,
+
+    /// CHECK: Compliance hold of the user on this market, must not exist
+    #[account(
+        seeds = [RESTRICTED_WALLET_PDA_SEED.as_bytes(), market.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = restricted_wallet.data_is_empty() @ TokenMillError::WalletRestricted
+    )]
+    pub restricted_wallet: UncheckedAccount<'info>,
+
+    pub user: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Sells base tokens at the bid price and burns them instead of returning them to the reserve.
+/// Sells don't carry a swap fee, so the user receives the full quote amount.
+pub fn handler(
+    ctx: Context<SellAndBurn>,
+    swap_amount_type: SwapAmountType,
+    amount: u64,
+    other_amount_threshold: u64,
+) -> Result<(u64, u64)> {
+    if amount == 0 {
+        return Err(TokenMillError::InvalidAmount.into());
+    }
+
+    let (base_amount, quote_amount, market_bump) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        if market.are_sells_paused(Clock::get()?.unix_timestamp) {
+            return Err(TokenMillError::SellsPaused.into());
+        }
+
+        price_feed_manager::check_quote_peg(market, ctx.accounts.quote_price_feed.as_deref())?;
+
+        let (base_amount, quote_amount) =
+            swap_manager::sell_and_burn(market, swap_amount_type, amount, &Clock::get()?)?;
+
+        oracle_manager::record_prices(&ctx.accounts.market_oracle, market)?;
+
+        (base_amount, quote_amount, market.bump)
+    };
+
+    match swap_amount_type {
+        SwapAmountType::ExactInput => {
+            if quote_amount < other_amount_threshold {
+                return Err(TokenMillError::AmountThresholdNotMet.into());
+            }
+        }
+        SwapAmountType::ExactOutput => {
+            if base_amount > other_amount_threshold {
+                return Err(TokenMillError::AmountThresholdNotMet.into());
+            }
+        }
+    }
+
+    burn_from_eoa(
+        &ctx.accounts.base_token_mint,
+        &ctx.accounts.user,
+        &ctx.accounts.user_base_token_ata,
+        &ctx.accounts.base_token_program,
+        base_amount,
+    )?;
+
+    let base_token_mint_key = ctx.accounts.base_token_mint.key();
+    let seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint_key.as_ref(),
+        &[market_bump],
+    ];
+
+    transfer_from_pda(
+        &ctx.accounts.quote_token_mint,
+        ctx.accounts.market.to_account_info(),
+        &ctx.accounts.market_quote_token_ata,
+        &ctx.accounts.user_quote_token_ata,
+        &ctx.accounts.quote_token_program,
+        quote_amount,
+        &seeds,
+    )?;
+
+    emit_cpi!(TokenMillSellAndBurnEvent {
+        user: ctx.accounts.user.key(),
+        market: ctx.accounts.market.key(),
+        base_amount,
+        quote_amount,
+    });
+
+    Ok((base_amount, quote_amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Market;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, SellAndBurnAction, SwapAction, TokenMillEnv},
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    const BUY_AMOUNT: u64 = 1_000_000_000;
+
+    fn setup_env() -> (TokenMillEnv, SellAndBurnAction) {
+        let mut testing_env = TokenMillEnv::default();
+        testing_env.svm.change_payer("bob");
+
+        let buy_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            BUY_AMOUNT,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&buy_action]).unwrap();
+
+        let action =
+            SellAndBurnAction::new(&testing_env, SwapAmountType::ExactInput, BUY_AMOUNT / 2, 0);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn sell_and_burn() {
+        let (mut testing_env, action) = setup_env();
+
+        let market_before = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+        let quote_balance_before = testing_env
+            .svm
+            .get_balance(&action.quote_token_mint, &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let market_after = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market_after.base_reserve, market_before.base_reserve);
+        assert_eq!(
+            market_after.total_supply,
+            market_before.total_supply - BUY_AMOUNT / 2
+        );
+        assert_eq!(
+            market_after.circulating_supply(),
+            market_before.circulating_supply() - BUY_AMOUNT / 2
+        );
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &action.signer),
+            BUY_AMOUNT / 2
+        );
+        assert!(
+            testing_env
+                .svm
+                .get_balance(&action.quote_token_mint, &action.signer)
+                > quote_balance_before
+        );
+    }
+
+    #[test]
+    fn sell_and_burn_pays_the_same_as_a_sell() {
+        let (mut testing_env, action) = setup_env();
+
+        let quote_balance_before = testing_env
+            .svm
+            .get_balance(&action.quote_token_mint, &action.signer);
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let burn_proceeds = testing_env
+            .svm
+            .get_balance(&action.quote_token_mint, &action.signer)
+            - quote_balance_before;
+
+        let sell_action = SwapAction::new(
+            &testing_env,
+            SwapType::Sell,
+            SwapAmountType::ExactInput,
+            BUY_AMOUNT / 2,
+            0,
+            None,
+        );
+
+        let (mut other_env, _) = setup_env();
+
+        let quote_balance_before = other_env
+            .svm
+            .get_balance(&action.quote_token_mint, &action.signer);
+
+        other_env.svm.execute_actions(&[&sell_action]).unwrap();
+
+        let sell_proceeds = other_env
+            .svm
+            .get_balance(&action.quote_token_mint, &action.signer)
+            - quote_balance_before;
+
+        assert_eq!(burn_proceeds, sell_proceeds);
+    }
+
+    #[test]
+    fn sell_and_burn_with_invalid_amount_out() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.other_amount_threshold = u64::MAX;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::AmountThresholdNotMet);
+    }
+}
@@ -0,0 +1,209 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::{INTERVAL_NUMBER, MAX_SIMULATION_SIZES},
+    errors::TokenMillError,
+    state::{DepthLevel, LadderQuote, Market},
+};
+
+#[derive(Accounts)]
+pub struct SimulateMarket<'info> {
+    pub market: AccountLoader<'info, Market>,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub struct MarketSimulation {
+    pub circulating_supply: u64,
+    pub ask_price: u64,
+    pub bid_price: u64,
+    /// Buys up to each tenth of the total supply above the circulating supply, nearest first.
+    /// The last level stops at the available supply, or at the remaining raise of a capped market
+    pub ask_depth: Vec<DepthLevel>,
+    /// Sells down to each tenth of the total supply below the circulating supply, nearest first
+    pub bid_depth: Vec<DepthLevel>,
+    /// Quotes of the sizes passed by the caller, in the same order
+    pub quotes: Vec<LadderQuote>,
+}
+
+/// View instruction returning the spot prices, the depth of the curve on both sides and the quotes of a ladder of
+/// base amounts, so that a depth chart can be rendered from a single simulation.
+/// Amounts are swapped on the curve only, without interface and referral fees.
+pub fn handler(ctx: Context<SimulateMarket>, sizes: Vec<u64>) -> Result<MarketSimulation> {
+    require!(
+        sizes.len() <= MAX_SIMULATION_SIZES,
+        TokenMillError::InvalidSimulationSizes
+    );
+
+    let market = ctx.accounts.market.load()?;
+
+    let circulating_supply = market.circulating_supply();
+    let (ask_price, bid_price) = market.get_spot_prices()?;
+
+    let mut ask_depth = Vec::new();
+    let mut bid_depth = Vec::new();
+
+    // Finer curves are sampled at the default resolution, so that the depth fits in the return data
+    for i in 0..=INTERVAL_NUMBER {
+        let boundary = market.total_supply / INTERVAL_NUMBER * i;
+
+        if boundary < circulating_supply {
+            let (base_amount, quote_amount) = market.quote_sell(circulating_supply - boundary)?;
+
+            bid_depth.push(DepthLevel {
+                supply: boundary,
+                base_amount,
+                quote_amount,
+            });
+        } else if boundary > circulating_supply {
+            let (base_amount, quote_amount) = market.quote_buy(boundary - circulating_supply)?;
+
+            if base_amount == 0 {
+                break;
+            }
+
+            ask_depth.push(DepthLevel {
+                supply: circulating_supply + base_amount,
+                base_amount,
+                quote_amount,
+            });
+
+            if base_amount < boundary - circulating_supply {
+                break;
+            }
+        }
+    }
+
+    bid_depth.reverse();
+
+    let quotes = market.quote_ladder(&sizes)?;
+
+    Ok(MarketSimulation {
+        circulating_supply,
+        ask_price,
+        bid_price,
+        ask_depth,
+        bid_depth,
+        quotes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AnchorDeserialize;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, SimulateMarketAction, SwapAction, TokenMillEnv, DEFAULT_TOTAL_SUPPLY,
+        },
+        SwapAmountType, SwapType, TokenMillError,
+    };
+
+    use crate::{
+        constant::{INTERVAL_NUMBER, MAX_SIMULATION_SIZES},
+        instructions::MarketSimulation,
+    };
+
+    const BASE_AMOUNT: u64 = 150_000_000_000;
+
+    fn setup_env() -> TokenMillEnv {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            BASE_AMOUNT,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        testing_env
+    }
+
+    #[test]
+    fn simulate_market() {
+        let mut testing_env = setup_env();
+
+        let sizes = vec![1_000_000_000, 10_000_000_000, DEFAULT_TOTAL_SUPPLY];
+
+        let action = SimulateMarketAction::new(&testing_env, sizes.clone());
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let simulation = MarketSimulation::try_from_slice(&result.return_data.data).unwrap();
+
+        let interval_supply = DEFAULT_TOTAL_SUPPLY / INTERVAL_NUMBER;
+
+        assert_eq!(simulation.circulating_supply, BASE_AMOUNT);
+        assert!(simulation.ask_price > simulation.bid_price);
+
+        // 150B sits in the second interval, so the boundaries at 0 and 100B lie below and the nine others above
+        assert_eq!(simulation.bid_depth.len(), 2);
+        assert_eq!(simulation.ask_depth.len(), 9);
+        assert_eq!(simulation.bid_depth[0].supply, interval_supply);
+        assert_eq!(simulation.bid_depth[1].supply, 0);
+        assert_eq!(simulation.bid_depth[1].base_amount, BASE_AMOUNT);
+        assert_eq!(simulation.ask_depth[0].supply, 2 * interval_supply);
+        assert_eq!(
+            simulation.ask_depth.last().unwrap().supply,
+            DEFAULT_TOTAL_SUPPLY
+        );
+
+        for depth in [&simulation.ask_depth, &simulation.bid_depth] {
+            assert!(depth
+                .windows(2)
+                .all(|levels| levels[0].quote_amount < levels[1].quote_amount));
+        }
+
+        assert_eq!(simulation.quotes.len(), sizes.len());
+
+        // Sizes above the depth of the curve are capped on both sides
+        let last_quote = simulation.quotes.last().unwrap();
+
+        assert_eq!(
+            last_quote.buy_base_amount,
+            DEFAULT_TOTAL_SUPPLY - BASE_AMOUNT
+        );
+        assert_eq!(last_quote.sell_base_amount, BASE_AMOUNT);
+        assert_eq!(
+            last_quote.buy_quote_amount,
+            simulation.ask_depth.last().unwrap().quote_amount
+        );
+        assert_eq!(
+            last_quote.sell_quote_amount,
+            simulation.bid_depth.last().unwrap().quote_amount
+        );
+
+        // The quotes match the swaps
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Sell,
+            SwapAmountType::ExactInput,
+            sizes[1],
+            0,
+            None,
+        );
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+        let (_, quote_amount) = <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        assert_eq!(quote_amount, simulation.quotes[1].sell_quote_amount);
+    }
+
+    #[test]
+    fn simulate_market_with_too_many_sizes() {
+        let mut testing_env = setup_env();
+
+        let action = SimulateMarketAction::new(&testing_env, vec![1; MAX_SIMULATION_SIZES + 1]);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidSimulationSizes
+        );
+    }
+}
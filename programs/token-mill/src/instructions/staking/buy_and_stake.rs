@@ -0,0 +1,407 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::{TokenMillIncentiveEvent, TokenMillStakingDepositEvent, TokenMillSwapEvent},
+    manager::{
+        oracle_manager, price_feed_manager, staking_manager,
+        swap_manager::{self, SwapAmountType, SwapType},
+        token_manager::{transfer_from_eoa, transfer_from_pda},
+    },
+    state::{IncentiveAction, Market, MarketStaking, StakePosition},
+    TokenMillConfig, MARKET_ORACLE_PDA_SEED, MARKET_PDA_SEED, RESTRICTED_WALLET_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BuyAndStake<'info> {
+    #[account(constraint = !config.swaps_paused @ TokenMillError::SwapsPaused)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        mut,
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = user @ TokenMillError::InvalidAuthority
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = quote_token_program
+    )]
+    pub user_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = config.protocol_fee_recipient,
+        associated_token::token_program = quote_token_program
+    )]
+    pub protocol_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pyth price update of the quote token, required if the market registered one. Checked in the handler
+    pub quote_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: TWAP oracle of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [MARKET_ORACLE_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub market_oracle: UncheckedAccount<'info>,
+
+    /// CHECK: Compliance hold of the user on this market, must not exist
+    #[account(
+        seeds = [RESTRICTED_WALLET_PDA_SEED.as_bytes(), market.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = restricted_wallet.data_is_empty() @ TokenMillError::WalletRestricted
+    )]
+    pub restricted_wallet: UncheckedAccount<'info>,
+
+    pub user: Signer<'info>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Buys base tokens and deposits them in the stake position of the user.
+/// The base tokens never leave the market base token ATA, which holds both the reserve and the staked tokens.
+/// The swap has to be fully filled, and referral and interface fees are not supported.
+pub fn handler(
+    ctx: Context<BuyAndStake>,
+    swap_amount_type: SwapAmountType,
+    amount: u64,
+    other_amount_threshold: u64,
+) -> Result<(u64, u64)> {
+    if amount == 0 {
+        return Err(TokenMillError::InvalidAmount.into());
+    }
+
+    let (
+        base_amount,
+        quote_amount,
+        creator_fee,
+        staking_fee,
+        protocol_fee,
+        buy_incentive_weight,
+        stake_incentive_weight,
+        circulating_supply_before,
+        circulating_supply_after,
+        (ask_price, bid_price),
+        market_bump,
+    ) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        price_feed_manager::check_quote_peg(market, ctx.accounts.quote_price_feed.as_deref())?;
+
+        let circulating_supply_before = market.circulating_supply();
+
+        let (base_amount, quote_amount, swap_fee) = swap_manager::swap(
+            market,
+            SwapType::Buy,
+            swap_amount_type,
+            amount,
+            &Clock::get()?,
+        )?;
+
+        let circulating_supply_after = market.circulating_supply();
+        let spot_prices = market.get_spot_prices()?;
+
+        oracle_manager::record_prices(&ctx.accounts.market_oracle, market)?;
+
+        let filled_amount = match swap_amount_type {
+            SwapAmountType::ExactInput => quote_amount,
+            SwapAmountType::ExactOutput => base_amount,
+        };
+
+        require!(filled_amount == amount, TokenMillError::SwapNotFullyFilled);
+
+        let (quote_amount, swap_fee) = swap_manager::apply_fee_exemption(
+            &ctx.accounts.config,
+            &ctx.accounts.user.key(),
+            SwapType::Buy,
+            quote_amount,
+            swap_fee,
+        )?;
+
+        let (creator_fee, staking_fee, protocol_fee, _) =
+            market.fees.distribute_fee(swap_fee, None)?;
+
+        staking_manager::deposit(
+            market,
+            &mut ctx.accounts.staking,
+            &mut ctx.accounts.stake_position,
+            base_amount,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        (
+            base_amount,
+            quote_amount,
+            creator_fee,
+            staking_fee,
+            protocol_fee,
+            market.get_incentive_weight(IncentiveAction::Buy, quote_amount)?,
+            market.get_incentive_weight(IncentiveAction::Stake, base_amount)?,
+            circulating_supply_before,
+            circulating_supply_after,
+            spot_prices,
+            market.bump,
+        )
+    };
+
+    match swap_amount_type {
+        SwapAmountType::ExactInput => {
+            if base_amount < other_amount_threshold {
+                return Err(TokenMillError::AmountThresholdNotMet.into());
+            }
+        }
+        SwapAmountType::ExactOutput => {
+            if quote_amount > other_amount_threshold {
+                return Err(TokenMillError::AmountThresholdNotMet.into());
+            }
+        }
+    }
+
+    transfer_from_eoa(
+        &ctx.accounts.quote_token_mint,
+        &ctx.accounts.user,
+        &ctx.accounts.user_quote_token_ata,
+        &ctx.accounts.market_quote_token_ata,
+        &ctx.accounts.quote_token_program,
+        quote_amount,
+    )?;
+
+    if protocol_fee > 0 {
+        let base_token_mint_key = ctx.accounts.base_token_mint.key();
+        let seeds = [
+            MARKET_PDA_SEED.as_bytes(),
+            base_token_mint_key.as_ref(),
+            &[market_bump],
+        ];
+
+        transfer_from_pda(
+            &ctx.accounts.quote_token_mint,
+            ctx.accounts.market.to_account_info(),
+            &ctx.accounts.market_quote_token_ata,
+            &ctx.accounts.protocol_quote_token_ata,
+            &ctx.accounts.quote_token_program,
+            protocol_fee,
+            &seeds,
+        )?;
+    }
+
+    emit_cpi!(TokenMillSwapEvent {
+        user: ctx.accounts.user.key(),
+        market: ctx.accounts.market.key(),
+        swap_type: SwapType::Buy,
+        base_amount,
+        quote_amount,
+        referral_token_account: None,
+        creator_fee,
+        staking_fee,
+        protocol_fee,
+        referral_fee: 0,
+        interface_fee_recipient: None,
+        interface_fee: 0,
+        circulating_supply_before,
+        circulating_supply_after,
+        ask_price,
+        bid_price,
+    });
+
+    emit_cpi!(TokenMillStakingDepositEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        amount: base_amount,
+    });
+
+    for (action, amount, weight) in [
+        (IncentiveAction::Buy, quote_amount, buy_incentive_weight),
+        (IncentiveAction::Stake, base_amount, stake_incentive_weight),
+    ] {
+        if let Some(weight) = weight {
+            emit_cpi!(TokenMillIncentiveEvent {
+                wallet: ctx.accounts.user.key(),
+                market: ctx.accounts.market.key(),
+                action,
+                amount,
+                weight,
+            });
+        }
+    }
+
+    Ok((base_amount, quote_amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Market, MarketStaking, StakePosition};
+    use anchor_lang::AnchorDeserialize;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, BuyAndStakeAction, SetFeeExemptionAction, TokenMillEnv,
+        },
+        SwapAmountType, TokenMillError,
+    };
+
+    const BUY_AMOUNT: u64 = 1_000_000_000;
+
+    fn setup_env() -> (TokenMillEnv, BuyAndStakeAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(0);
+        testing_env.svm.change_payer("bob");
+
+        let action = BuyAndStakeAction::new(
+            &testing_env,
+            SwapAmountType::ExactOutput,
+            BUY_AMOUNT,
+            u64::MAX,
+        );
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn buy_and_stake() {
+        let (mut testing_env, action) = setup_env();
+
+        let base_token_mint = testing_env.base_token_mint.unwrap();
+        let market_base_balance_before = testing_env
+            .svm
+            .get_balance(&base_token_mint, &testing_env.market);
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+        let (base_amount, _) = <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        assert_eq!(base_amount, BUY_AMOUNT);
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&action.staking);
+
+        assert_eq!(stake_position.amount_staked, BUY_AMOUNT);
+        assert_eq!(staking.amount_staked, BUY_AMOUNT);
+
+        // The staked tokens never left the market
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&base_token_mint, &testing_env.market),
+            market_base_balance_before
+        );
+        assert_eq!(
+            market.base_reserve + staking.amount_staked,
+            market_base_balance_before
+        );
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&base_token_mint, &action.signer),
+            0
+        );
+    }
+
+    #[test]
+    fn buy_and_stake_with_exact_input() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.swap_amount_type = SwapAmountType::ExactInput;
+        action.other_amount_threshold = 0;
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+        let (base_amount, quote_amount) =
+            <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        assert_eq!(quote_amount, BUY_AMOUNT);
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        assert_eq!(stake_position.amount_staked, base_amount);
+    }
+
+    #[test]
+    fn buy_and_stake_with_fee_exemption() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+        let (_, quote_amount) = <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetFeeExemptionAction::new(action.signer, 10_000)])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+        let (base_amount, exempted_quote_amount) =
+            <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        assert_eq!(base_amount, BUY_AMOUNT);
+        assert!(exempted_quote_amount < quote_amount);
+    }
+
+    #[test]
+    fn buy_and_stake_above_threshold() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.other_amount_threshold = 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::AmountThresholdNotMet);
+    }
+
+    #[test]
+    fn buy_and_stake_with_invalid_stake_position() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("carol");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillStakingCheckpointEvent,
+    manager::staking_manager,
+    state::{Market, MarketStaking, StakingCheckpoint, StakingCheckpoints},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CheckpointStakingEpoch<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking_checkpoints: AccountLoader<'info, StakingCheckpoints>,
+}
+
+/// Records the staking totals of the market for the current epoch. Permissionless, once per epoch.
+/// The pending staking fees are pushed to the reward index first, so that it is current.
+pub fn handler(ctx: Context<CheckpointStakingEpoch>) -> Result<()> {
+    let clock = Clock::get()?;
+    let staking_checkpoints = &mut ctx.accounts.staking_checkpoints.load_mut()?;
+
+    if let Some(last_checkpoint) = staking_checkpoints.get_checkpoint(0) {
+        require!(
+            last_checkpoint.epoch < clock.epoch,
+            TokenMillError::StakingEpochAlreadyCheckpointed
+        );
+    }
+
+    let staking = &mut ctx.accounts.staking;
+
+    {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        staking_manager::distribute_staking_fees(market, staking)?;
+    }
+
+    let checkpoint = StakingCheckpoint {
+        acc_reward_amount_per_share: staking.acc_reward_amount_per_share,
+        epoch: clock.epoch,
+        timestamp: clock.unix_timestamp,
+        amount_staked: staking.amount_staked,
+        total_shares: staking.total_shares(),
+    };
+
+    staking_checkpoints.record(checkpoint);
+
+    emit_cpi!(TokenMillStakingCheckpointEvent {
+        market: ctx.accounts.market.key(),
+        epoch: checkpoint.epoch,
+        amount_staked: checkpoint.amount_staked,
+        total_shares: checkpoint.total_shares,
+        acc_reward_amount_per_share: checkpoint.acc_reward_amount_per_share,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::actions::token_mill::{
+        tm_parse_error, CheckpointStakingEpochAction, CreateStakingCheckpointsAction,
+        DepositAction, TokenMillEnv,
+    };
+
+    use crate::{errors::TokenMillError, StakingCheckpoints};
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    fn setup_env() -> (TokenMillEnv, CheckpointStakingEpochAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        testing_env
+            .svm
+            .execute_actions(&[&CreateStakingCheckpointsAction::new(&testing_env)])
+            .unwrap();
+
+        let action = CheckpointStakingEpochAction::new(&testing_env);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn checkpoint_staking_epoch() {
+        let (mut testing_env, action) = setup_env();
+
+        let epoch = testing_env.svm.get_clock().epoch;
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+
+        testing_env.svm.warp_epochs(2);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let staking_checkpoints = testing_env
+            .svm
+            .get_parsed_account::<StakingCheckpoints>(&action.staking_checkpoints);
+
+        assert_eq!(staking_checkpoints.count, 2);
+
+        let checkpoint = staking_checkpoints.get_checkpoint(0).unwrap();
+
+        assert_eq!(checkpoint.epoch, epoch + 2);
+        assert_eq!(checkpoint.amount_staked, STAKE_AMOUNT);
+        assert_eq!(checkpoint.total_shares, STAKE_AMOUNT);
+
+        // The stake at the skipped epoch is the one of the previous checkpoint
+        let checkpoint = staking_checkpoints.get_checkpoint_at(epoch + 1).unwrap();
+
+        assert_eq!(checkpoint.epoch, epoch);
+        assert_eq!(checkpoint.amount_staked, 0);
+    }
+
+    #[test]
+    fn checkpoint_staking_epoch_twice() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::StakingEpochAlreadyCheckpointed);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     errors::TokenMillError,
-    events::TokenMillStakingRewardsClaimEvent,
+    events::{TokenMillStakingBadgeUnlinkEvent, TokenMillStakingRewardsClaimEvent},
     manager::{staking_manager, token_manager::transfer_from_pda},
     state::{Market, MarketStaking, StakePosition},
     MARKET_PDA_SEED,
@@ -42,6 +42,13 @@ pub struct StakingRewardsClaim<'info> {
     )]
     pub user_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
 
+    /// Token account of the user holding the badge linked to the stake position, the boost is removed without it
+    #[account(
+        token::mint = stake_position.badge_mint,
+        token::authority = user
+    )]
+    pub user_badge_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
     pub user: Signer<'info>,
 
     pub quote_token_program: Interface<'info, TokenInterface>,
@@ -49,21 +56,28 @@ pub struct StakingRewardsClaim<'info> {
 
 pub fn handler(ctx: Context<StakingRewardsClaim>) -> Result<()> {
     let pending_rewards;
+    let unlinked_badge_mint;
     let base_token_mint;
     let market_bump;
 
     {
         let market = &mut ctx.accounts.market.load_mut()?;
-        let staking = &mut ctx.accounts.staking;
-        let stake_position = &mut ctx.accounts.stake_position;
-
-        staking_manager::deposit(market, staking, stake_position, 0)?;
+        let holds_badge = ctx
+            .accounts
+            .user_badge_token_account
+            .as_ref()
+            .is_some_and(|token_account| token_account.amount > 0);
+
+        (pending_rewards, unlinked_badge_mint) = staking_manager::claim_rewards(
+            market,
+            &mut ctx.accounts.staking,
+            &mut ctx.accounts.stake_position,
+            holds_badge,
+            Clock::get()?.unix_timestamp,
+        )?;
 
-        pending_rewards = stake_position.pending_rewards;
         base_token_mint = market.base_token_mint;
         market_bump = market.bump;
-
-        stake_position.pending_rewards = 0;
     };
 
     let quote_token_mint = &ctx.accounts.quote_token_mint;
@@ -83,6 +97,14 @@ pub fn handler(ctx: Context<StakingRewardsClaim>) -> Result<()> {
         &seeds,
     )?;
 
+    if let Some(badge_mint) = unlinked_badge_mint {
+        emit_cpi!(TokenMillStakingBadgeUnlinkEvent {
+            market: ctx.accounts.market.key(),
+            user: ctx.accounts.user.key(),
+            badge_mint,
+        });
+    }
+
     emit_cpi!(TokenMillStakingRewardsClaimEvent {
         market: ctx.accounts.market.key(),
         user: ctx.accounts.user.key(),
@@ -0,0 +1,249 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillVaultRewardsClaimEvent,
+    manager::{staking_manager, token_manager::transfer_from_pda},
+    state::{Market, MarketStaking, RewardVault, StakePosition, REWARD_VAULT_PDA_SEED},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimVaultRewards<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = user @ TokenMillError::InvalidAuthority
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = reward_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+
+    pub reward_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = reward_vault,
+        associated_token::token_program = reward_token_program
+    )]
+    pub reward_vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // Created here as stakers rarely hold the reward token beforehand
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = reward_token_program
+    )]
+    pub user_reward_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ClaimVaultRewards>) -> Result<()> {
+    let pending_vault_rewards = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+        let staking = &mut ctx.accounts.staking;
+        let stake_position = &mut ctx.accounts.stake_position;
+
+        staking_manager::deposit(
+            market,
+            staking,
+            stake_position,
+            0,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        let pending_vault_rewards = stake_position.pending_vault_rewards;
+
+        stake_position.pending_vault_rewards = 0;
+
+        pending_vault_rewards
+    };
+
+    let reward_vault = &ctx.accounts.reward_vault;
+    let market_key = reward_vault.market;
+    let seeds = [
+        REWARD_VAULT_PDA_SEED.as_bytes(),
+        market_key.as_ref(),
+        &[reward_vault.bump],
+    ];
+
+    transfer_from_pda(
+        &ctx.accounts.reward_token_mint,
+        reward_vault.to_account_info(),
+        &ctx.accounts.reward_vault_ata,
+        &ctx.accounts.user_reward_token_ata,
+        &ctx.accounts.reward_token_program,
+        pending_vault_rewards,
+        &seeds,
+    )?;
+
+    emit_cpi!(TokenMillVaultRewardsClaimEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        reward_token_mint: ctx.accounts.reward_token_mint.key(),
+        amount: pending_vault_rewards,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            ClaimVaultRewardsAction, CreateRewardVaultAction, CreateStakePositionAction,
+            DepositAction, FundRewardVaultAction, SwapAction, TokenMillEnv,
+        },
+        make_address, SwapAmountType, SwapType, TokenType,
+    };
+    use solana_sdk::pubkey::Pubkey;
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+    const FUNDING_AMOUNT: u64 = 1_000_000_000;
+    const EMISSION_DURATION: i64 = 86_400;
+
+    fn setup_env() -> (TokenMillEnv, Pubkey) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        let reward_token_mint = testing_env.svm.create_token(TokenType::Token, 6).unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &CreateRewardVaultAction::new(&testing_env, reward_token_mint),
+                &FundRewardVaultAction::new(
+                    &testing_env,
+                    reward_token_mint,
+                    FUNDING_AMOUNT,
+                    EMISSION_DURATION,
+                ),
+            ])
+            .unwrap();
+
+        (testing_env, reward_token_mint)
+    }
+
+    #[test]
+    fn claim_vault_rewards() {
+        let (mut testing_env, reward_token_mint) = setup_env();
+
+        testing_env.svm.warp(EMISSION_DURATION);
+
+        testing_env.svm.change_payer("bob");
+
+        let balance_before = testing_env
+            .svm
+            .get_balance(&reward_token_mint, &make_address("bob"));
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&ClaimVaultRewardsAction::new(
+                &testing_env,
+                reward_token_mint,
+            )]);
+
+        assert!(result.is_ok());
+
+        let rewards = testing_env
+            .svm
+            .get_balance(&reward_token_mint, &make_address("bob"))
+            - balance_before;
+
+        assert!(rewards <= FUNDING_AMOUNT);
+        assert!(rewards >= FUNDING_AMOUNT - 1);
+    }
+
+    #[test]
+    fn claim_shared_vault_rewards() {
+        let (mut testing_env, reward_token_mint) = setup_env();
+
+        testing_env.svm.warp(EMISSION_DURATION / 2);
+
+        // Carol stakes as much as Bob for the second half of the emission
+        let carol = testing_env.svm.change_payer("carol");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                SwapAction::new(
+                    &testing_env,
+                    SwapType::Buy,
+                    SwapAmountType::ExactOutput,
+                    STAKE_AMOUNT,
+                    u64::MAX,
+                    None,
+                )
+                .with_user(carol),
+                CreateStakePositionAction::new(&testing_env).with_user(carol),
+                DepositAction::new(&testing_env, STAKE_AMOUNT).with_user(carol),
+            ])
+            .unwrap();
+
+        testing_env.svm.warp(EMISSION_DURATION / 2);
+
+        let bob_balance_before = testing_env
+            .svm
+            .get_balance(&reward_token_mint, &make_address("bob"));
+        let carol_balance_before = testing_env.svm.get_balance(&reward_token_mint, &carol);
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                ClaimVaultRewardsAction::new(&testing_env, reward_token_mint).with_user(carol),
+            ])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&ClaimVaultRewardsAction::new(
+                &testing_env,
+                reward_token_mint,
+            )])
+            .unwrap();
+
+        let bob_rewards = testing_env
+            .svm
+            .get_balance(&reward_token_mint, &make_address("bob"))
+            - bob_balance_before;
+        let carol_rewards =
+            testing_env.svm.get_balance(&reward_token_mint, &carol) - carol_balance_before;
+
+        assert!(bob_rewards + carol_rewards <= FUNDING_AMOUNT);
+        assert!(bob_rewards + carol_rewards >= FUNDING_AMOUNT - 2);
+        assert!(bob_rewards.abs_diff(3 * carol_rewards) <= 3);
+    }
+}
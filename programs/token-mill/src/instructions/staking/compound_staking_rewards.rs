@@ -0,0 +1,350 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constant::{COMPOUNDING_CRANKER_FEE_BPS, MAX_BPS},
+    errors::TokenMillError,
+    events::{TokenMillStakingRewardsCompoundEvent, TokenMillSwapEvent},
+    manager::{
+        oracle_manager, price_feed_manager, staking_manager,
+        swap_manager::{self, SwapAmountType, SwapType},
+        token_manager::transfer_from_pda,
+    },
+    state::{CompoundingVault, Market, MarketStaking, StakePosition, STAKING_POSITION_PDA_SEED},
+    TokenMillConfig, MARKET_ORACLE_PDA_SEED, MARKET_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CompoundStakingRewards<'info> {
+    #[account(constraint = !config.swaps_paused @ TokenMillError::SwapsPaused)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        mut,
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub compounding_vault: Account<'info, CompoundingVault>,
+
+    #[account(
+        mut,
+        seeds = [STAKING_POSITION_PDA_SEED.as_bytes(), market.key().as_ref(), compounding_vault.key().as_ref()],
+        bump
+    )]
+    pub vault_stake_position: Account<'info, StakePosition>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = config.protocol_fee_recipient,
+        associated_token::token_program = quote_token_program
+    )]
+    pub protocol_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = quote_token_mint,
+        token::token_program = quote_token_program
+    )]
+    pub cranker_quote_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pyth price update of the quote token, required if the market registered one. Checked in the handler
+    pub quote_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: TWAP oracle of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [MARKET_ORACLE_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub market_oracle: UncheckedAccount<'info>,
+
+    pub cranker: Signer<'info>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Buys base tokens with the staking fees accrued by the compounding vault and restakes them. Permissionless,
+/// the cranker being paid `COMPOUNDING_CRANKER_FEE_BPS` of the fees. The quote tokens never leave the market quote
+/// token ATA, where the fees are held, and fees left unspent by a partial fill stay pending in the vault position.
+pub fn handler(ctx: Context<CompoundStakingRewards>) -> Result<()> {
+    let (
+        base_amount,
+        quote_amount,
+        cranker_fee,
+        creator_fee,
+        staking_fee,
+        protocol_fee,
+        circulating_supply_before,
+        circulating_supply_after,
+        (ask_price, bid_price),
+        market_bump,
+        base_token_mint,
+    ) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+        let staking = &mut ctx.accounts.staking;
+        let vault_stake_position = &mut ctx.accounts.vault_stake_position;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        price_feed_manager::check_quote_peg(market, ctx.accounts.quote_price_feed.as_deref())?;
+
+        staking_manager::deposit(market, staking, vault_stake_position, 0, current_time)?;
+
+        let pending_rewards = vault_stake_position.pending_rewards;
+
+        require!(pending_rewards > 0, TokenMillError::InvalidAmount);
+
+        let cranker_fee = u64::try_from(
+            u128::from(pending_rewards) * u128::from(COMPOUNDING_CRANKER_FEE_BPS)
+                / u128::from(MAX_BPS),
+        )?;
+
+        let circulating_supply_before = market.circulating_supply();
+
+        let (base_amount, quote_amount, swap_fee) = swap_manager::swap(
+            market,
+            SwapType::Buy,
+            SwapAmountType::ExactInput,
+            pending_rewards - cranker_fee,
+            &Clock::get()?,
+        )?;
+
+        require!(base_amount > 0, TokenMillError::InvalidAmount);
+
+        let (quote_amount, swap_fee) = swap_manager::apply_fee_exemption(
+            &ctx.accounts.config,
+            &ctx.accounts.compounding_vault.key(),
+            SwapType::Buy,
+            quote_amount,
+            swap_fee,
+        )?;
+
+        let circulating_supply_after = market.circulating_supply();
+        let spot_prices = market.get_spot_prices()?;
+
+        oracle_manager::record_prices(&ctx.accounts.market_oracle, market)?;
+
+        let (creator_fee, staking_fee, protocol_fee, _) =
+            market.fees.distribute_fee(swap_fee, None)?;
+
+        vault_stake_position.pending_rewards = pending_rewards - cranker_fee - quote_amount;
+        market.release_quote(cranker_fee + quote_amount)?;
+
+        staking_manager::deposit(
+            market,
+            staking,
+            vault_stake_position,
+            base_amount,
+            current_time,
+        )?;
+
+        (
+            base_amount,
+            quote_amount,
+            cranker_fee,
+            creator_fee,
+            staking_fee,
+            protocol_fee,
+            circulating_supply_before,
+            circulating_supply_after,
+            spot_prices,
+            market.bump,
+            market.base_token_mint,
+        )
+    };
+
+    ctx.accounts.compounding_vault.total_compounded += base_amount;
+
+    let seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint.as_ref(),
+        &[market_bump],
+    ];
+
+    for (destination, amount) in [
+        (&ctx.accounts.protocol_quote_token_ata, protocol_fee),
+        (&ctx.accounts.cranker_quote_token_account, cranker_fee),
+    ] {
+        if amount > 0 {
+            transfer_from_pda(
+                &ctx.accounts.quote_token_mint,
+                ctx.accounts.market.to_account_info(),
+                &ctx.accounts.market_quote_token_ata,
+                destination,
+                &ctx.accounts.quote_token_program,
+                amount,
+                &seeds,
+            )?;
+        }
+    }
+
+    emit_cpi!(TokenMillSwapEvent {
+        user: ctx.accounts.compounding_vault.key(),
+        market: ctx.accounts.market.key(),
+        swap_type: SwapType::Buy,
+        base_amount,
+        quote_amount,
+        referral_token_account: None,
+        creator_fee,
+        staking_fee,
+        protocol_fee,
+        referral_fee: 0,
+        interface_fee_recipient: None,
+        interface_fee: 0,
+        circulating_supply_before,
+        circulating_supply_after,
+        ask_price,
+        bid_price,
+    });
+
+    emit_cpi!(TokenMillStakingRewardsCompoundEvent {
+        market: ctx.accounts.market.key(),
+        cranker: ctx.accounts.cranker.key(),
+        quote_amount,
+        base_amount,
+        cranker_fee,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CompoundStakingRewardsAction, CreateCompoundingVaultAction,
+            DepositToCompoundingVaultAction, SwapAction, TokenMillEnv,
+            WithdrawFromCompoundingVaultAction,
+        },
+        make_address, SwapAmountType, SwapType,
+    };
+
+    use crate::{errors::TokenMillError, CompoundingVault, StakePosition};
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+    const SWAP_AMOUNT: u64 = 1_000_000_000_000 / 2;
+
+    fn setup_env(swap_amount: u64) -> (TokenMillEnv, CompoundStakingRewardsAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &CreateCompoundingVaultAction::new(&testing_env),
+                &DepositToCompoundingVaultAction::new(&testing_env, STAKE_AMOUNT),
+            ])
+            .unwrap();
+
+        if swap_amount > 0 {
+            let carol = testing_env.svm.change_payer("carol");
+
+            testing_env
+                .svm
+                .execute_actions(&[SwapAction::new(
+                    &testing_env,
+                    SwapType::Buy,
+                    SwapAmountType::ExactOutput,
+                    swap_amount,
+                    u64::MAX,
+                    None,
+                )
+                .with_user(carol)])
+                .unwrap();
+        }
+
+        let mallory = testing_env.svm.change_payer("mallory");
+
+        let mut action = CompoundStakingRewardsAction::new(&testing_env);
+        action.with_cranker(mallory);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn compound_staking_rewards() {
+        let (mut testing_env, action) = setup_env(SWAP_AMOUNT);
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let cranker_balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("mallory"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let cranker_balance_after = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("mallory"));
+
+        assert!(cranker_balance_after > cranker_balance_before);
+
+        let vault_stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.vault_stake_position);
+        let compounding_vault = testing_env
+            .svm
+            .get_parsed_account::<CompoundingVault>(&action.compounding_vault);
+
+        assert!(compounding_vault.total_compounded > 0);
+        assert_eq!(
+            vault_stake_position.amount_staked,
+            STAKE_AMOUNT + compounding_vault.total_compounded
+        );
+
+        // Bob's shares now redeem the compounded stake
+        testing_env.svm.change_payer("bob");
+
+        let base_token_mint = testing_env.base_token_mint.unwrap();
+        let balance_before = testing_env
+            .svm
+            .get_balance(&base_token_mint, &make_address("bob"));
+
+        testing_env
+            .svm
+            .execute_actions(&[&WithdrawFromCompoundingVaultAction::new(
+                &testing_env,
+                STAKE_AMOUNT,
+            )])
+            .unwrap();
+
+        let balance_after = testing_env
+            .svm
+            .get_balance(&base_token_mint, &make_address("bob"));
+
+        assert_eq!(
+            balance_after - balance_before,
+            STAKE_AMOUNT + compounding_vault.total_compounded
+        );
+    }
+
+    #[test]
+    fn compound_staking_rewards_without_rewards() {
+        let (mut testing_env, action) = setup_env(0);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAmount);
+    }
+}
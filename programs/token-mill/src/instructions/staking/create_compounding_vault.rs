@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    state::{
+        CompoundingVault, Market, MarketStaking, StakePosition, COMPOUNDING_VAULT_PDA_SEED,
+        STAKING_POSITION_PDA_SEED,
+    },
+};
+
+#[derive(Accounts)]
+pub struct CreateCompoundingVault<'info> {
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CompoundingVault::INIT_SPACE,
+        seeds = [COMPOUNDING_VAULT_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub compounding_vault: Account<'info, CompoundingVault>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [STAKING_POSITION_PDA_SEED.as_bytes(), market.key().as_ref(), compounding_vault.key().as_ref()],
+        bump
+    )]
+    pub vault_stake_position: Account<'info, StakePosition>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates the compounding vault of a staking market and its stake position. Anyone can create it,
+/// stakers opting in by depositing into it instead of their own stake position.
+pub fn handler(ctx: Context<CreateCompoundingVault>) -> Result<()> {
+    let market = ctx.accounts.market.key();
+    let compounding_vault = &mut ctx.accounts.compounding_vault;
+
+    compounding_vault.initialize(ctx.bumps.compounding_vault, market)?;
+
+    ctx.accounts
+        .vault_stake_position
+        .initialize(market, compounding_vault.key())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::actions::token_mill::{
+        CreateCompoundingVaultAction, TokenMillEnv,
+    };
+
+    use crate::{CompoundingVault, StakePosition};
+
+    #[test]
+    fn create_compounding_vault() {
+        let mut testing_env = TokenMillEnv::default().with_staking(0);
+
+        testing_env.svm.change_payer("mallory");
+
+        let action = CreateCompoundingVaultAction::new(&testing_env);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let compounding_vault = testing_env
+            .svm
+            .get_parsed_account::<CompoundingVault>(&action.compounding_vault);
+
+        assert_eq!(compounding_vault.market, testing_env.market);
+        assert_eq!(compounding_vault.total_shares, 0);
+
+        let vault_stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.vault_stake_position);
+
+        assert_eq!(vault_stake_position.user, action.compounding_vault);
+    }
+}
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    errors::TokenMillError,
+    manager::token_manager::check_mint_extensions,
+    state::{Market, MarketStaking, RewardVault, REWARD_VAULT_PDA_SEED},
+};
+
+#[derive(Accounts)]
+pub struct CreateRewardVault<'info> {
+    #[account(has_one = creator @ TokenMillError::InvalidAuthority)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + RewardVault::INIT_SPACE,
+        seeds = [REWARD_VAULT_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+
+    pub reward_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = reward_vault,
+        associated_token::token_program = reward_token_program
+    )]
+    pub reward_vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates the reward vault of a market, holding the `reward_token_mint` tokens emitted to its stakers.
+/// Only the creator can create it, so that the single vault of a market can't be squatted with another token.
+pub fn handler(ctx: Context<CreateRewardVault>) -> Result<()> {
+    require!(
+        check_mint_extensions(&ctx.accounts.reward_token_mint)?,
+        TokenMillError::UnsupportedTokenMint
+    );
+
+    ctx.accounts.reward_vault.initialize(
+        ctx.bumps.reward_vault,
+        ctx.accounts.market.key(),
+        ctx.accounts.reward_token_mint.key(),
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, CreateRewardVaultAction, TokenMillEnv},
+        TokenType,
+    };
+
+    use crate::{errors::TokenMillError, RewardVault};
+
+    fn setup_env() -> (TokenMillEnv, CreateRewardVaultAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(0);
+
+        let reward_token_mint = testing_env.svm.create_token(TokenType::Token, 6).unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let action = CreateRewardVaultAction::new(&testing_env, reward_token_mint);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn create_reward_vault() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let reward_vault = testing_env
+            .svm
+            .get_parsed_account::<RewardVault>(&action.reward_vault);
+
+        assert_eq!(reward_vault.market, testing_env.market);
+        assert_eq!(reward_vault.reward_token_mint, action.reward_token_mint);
+        assert_eq!(reward_vault.total_funded, 0);
+    }
+
+    #[test]
+    fn create_reward_vault_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidAuthority);
+    }
+}
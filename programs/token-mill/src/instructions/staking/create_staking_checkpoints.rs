@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    state::{Market, MarketStaking, StakingCheckpoints, STAKING_CHECKPOINTS_PDA_SEED},
+};
+
+#[derive(Accounts)]
+pub struct CreateStakingCheckpoints<'info> {
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        init,
+        seeds = [STAKING_CHECKPOINTS_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + StakingCheckpoints::INIT_SPACE
+    )]
+    pub staking_checkpoints: AccountLoader<'info, StakingCheckpoints>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Permissionless, so that airdrop and governance programs can start recording the stake of any staking market
+pub fn handler(ctx: Context<CreateStakingCheckpoints>) -> Result<()> {
+    let staking_checkpoints = &mut ctx.accounts.staking_checkpoints.load_init()?;
+
+    staking_checkpoints.initialize(ctx.accounts.market.key())?;
+
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use crate::{
+    errors::TokenMillError,
+    events::{TokenMillStakingBadgeUnlinkEvent, TokenMillStakingRewardsClaimEvent},
+    manager::{staking_manager, token_manager::transfer_from_pda},
+    state::{Market, MarketStaking, StakePosition},
+    MARKET_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DelegatedStakingRewardsClaim<'info> {
+    #[account(mut, has_one = quote_token_mint @ TokenMillError::InvalidMintAccount)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        mut,
+        has_one = user @ TokenMillError::InvalidAuthority,
+        has_one = market @ TokenMillError::InvalidMarket,
+        constraint = stake_position.claim_delegate == delegate.key() @ TokenMillError::InvalidAuthority
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = quote_token_program
+    )]
+    pub user_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token account of the user holding the badge linked to the stake position, required if it has one
+    #[account(
+        token::mint = stake_position.badge_mint,
+        token::authority = user
+    )]
+    pub user_badge_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Owner of the stake position, receiving the rewards
+    pub user: UncheckedAccount<'info>,
+
+    pub delegate: Signer<'info>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Claims the staking rewards of a position on behalf of its user, to the quote token ATA of the user.
+/// The badge account is required so that the delegate can't remove the boost of a user still holding the badge.
+pub fn handler(ctx: Context<DelegatedStakingRewardsClaim>) -> Result<()> {
+    require!(
+        ctx.accounts.stake_position.badge_mint == Pubkey::default()
+            || ctx.accounts.user_badge_token_account.is_some(),
+        TokenMillError::InvalidStakingBadge
+    );
+
+    let pending_rewards;
+    let unlinked_badge_mint;
+    let base_token_mint;
+    let market_bump;
+
+    {
+        let market = &mut ctx.accounts.market.load_mut()?;
+        let holds_badge = ctx
+            .accounts
+            .user_badge_token_account
+            .as_ref()
+            .is_some_and(|token_account| token_account.amount > 0);
+
+        (pending_rewards, unlinked_badge_mint) = staking_manager::claim_rewards(
+            market,
+            &mut ctx.accounts.staking,
+            &mut ctx.accounts.stake_position,
+            holds_badge,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        base_token_mint = market.base_token_mint;
+        market_bump = market.bump;
+    }
+
+    let seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint.as_ref(),
+        &[market_bump],
+    ];
+
+    transfer_from_pda(
+        &ctx.accounts.quote_token_mint,
+        ctx.accounts.market.to_account_info(),
+        &ctx.accounts.market_quote_token_ata,
+        &ctx.accounts.user_quote_token_ata,
+        &ctx.accounts.quote_token_program,
+        pending_rewards,
+        &seeds,
+    )?;
+
+    if let Some(badge_mint) = unlinked_badge_mint {
+        emit_cpi!(TokenMillStakingBadgeUnlinkEvent {
+            market: ctx.accounts.market.key(),
+            user: ctx.accounts.user.key(),
+            badge_mint,
+        });
+    }
+
+    emit_cpi!(TokenMillStakingRewardsClaimEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        amount_distributed: pending_rewards,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, DelegatedClaimStakingRewardsAction, DepositAction,
+            SetClaimDelegateAction, SwapAction, TokenMillEnv, WithdrawAction,
+        },
+        make_address, SwapAmountType, SwapType,
+    };
+
+    use crate::errors::TokenMillError;
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    fn setup_env() -> (TokenMillEnv, DelegatedClaimStakingRewardsAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        let swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000_000 / 2,
+            u64::MAX,
+            None,
+        );
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &DepositAction::new(&testing_env, STAKE_AMOUNT),
+                &SetClaimDelegateAction::new(&testing_env, make_address("carol")),
+                &swap_action,
+            ])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let action = DelegatedClaimStakingRewardsAction::new(&testing_env, make_address("carol"));
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn delegated_claim_staking_rewards() {
+        let (mut testing_env, action) = setup_env();
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let bob_balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("bob"));
+        let carol_balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("carol"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        assert!(
+            testing_env
+                .svm
+                .get_balance(&quote_token_mint, &make_address("bob"))
+                > bob_balance_before
+        );
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&quote_token_mint, &make_address("carol")),
+            carol_balance_before
+        );
+    }
+
+    #[test]
+    fn delegated_claim_staking_rewards_with_invalid_delegate() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.delegate = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+
+    #[test]
+    fn delegate_cannot_withdraw() {
+        let (mut testing_env, _) = setup_env();
+
+        let mut withdraw_action = WithdrawAction::new(&testing_env, STAKE_AMOUNT);
+        withdraw_action.signer = make_address("carol");
+
+        let result = testing_env.svm.execute_actions(&[&withdraw_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -1,8 +1,9 @@
 use crate::{
     errors::TokenMillError,
-    events::TokenMillStakingDepositEvent,
+    events::{TokenMillIncentiveEvent, TokenMillStakingDepositEvent},
     manager::{staking_manager, token_manager::transfer_from_eoa},
-    state::{Market, MarketStaking, StakePosition},
+    state::{IncentiveAction, Market, MarketStaking, StakePosition},
+    RESTRICTED_WALLET_PDA_SEED,
 };
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
@@ -41,6 +42,14 @@ pub struct StakeUpdate<'info> {
     )]
     pub user_base_token_ata: InterfaceAccount<'info, TokenAccount>,
 
+    /// CHECK: Compliance hold of the user on this market, must not exist
+    #[account(
+        seeds = [RESTRICTED_WALLET_PDA_SEED.as_bytes(), market.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = restricted_wallet.data_is_empty() @ TokenMillError::WalletRestricted
+    )]
+    pub restricted_wallet: UncheckedAccount<'info>,
+
     pub user: Signer<'info>,
 
     pub base_token_program: Interface<'info, TokenInterface>,
@@ -51,7 +60,15 @@ pub fn handler(ctx: Context<StakeUpdate>, amount: u64) -> Result<()> {
     let staking = &mut ctx.accounts.staking;
     let stake_position = &mut ctx.accounts.stake_position;
 
-    staking_manager::deposit(market, staking, stake_position, amount)?;
+    staking_manager::deposit(
+        market,
+        staking,
+        stake_position,
+        amount,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    let incentive_weight = market.get_incentive_weight(IncentiveAction::Stake, amount)?;
 
     transfer_from_eoa(
         &ctx.accounts.base_token_mint,
@@ -68,6 +85,16 @@ pub fn handler(ctx: Context<StakeUpdate>, amount: u64) -> Result<()> {
         amount,
     });
 
+    if let Some(weight) = incentive_weight {
+        emit_cpi!(TokenMillIncentiveEvent {
+            wallet: ctx.accounts.user.key(),
+            market: ctx.accounts.market.key(),
+            action: IncentiveAction::Stake,
+            amount,
+            weight,
+        });
+    }
+
     Ok(())
 }
 
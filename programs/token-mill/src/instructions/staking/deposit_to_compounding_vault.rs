@@ -0,0 +1,177 @@
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillCompoundingVaultDepositEvent,
+    manager::{staking_manager, token_manager::transfer_from_eoa},
+    state::{
+        CompoundingPosition, CompoundingVault, Market, MarketStaking, StakePosition,
+        COMPOUNDING_POSITION_PDA_SEED, STAKING_POSITION_PDA_SEED,
+    },
+    RESTRICTED_WALLET_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CompoundingVaultUpdate<'info> {
+    #[account(mut, has_one = base_token_mint @ TokenMillError::InvalidMintAccount)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub compounding_vault: Account<'info, CompoundingVault>,
+
+    #[account(
+        mut,
+        seeds = [STAKING_POSITION_PDA_SEED.as_bytes(), market.key().as_ref(), compounding_vault.key().as_ref()],
+        bump
+    )]
+    pub vault_stake_position: Account<'info, StakePosition>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + CompoundingPosition::INIT_SPACE,
+        seeds = [COMPOUNDING_POSITION_PDA_SEED.as_bytes(), compounding_vault.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub compounding_position: Account<'info, CompoundingPosition>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = base_token_program
+    )]
+    pub user_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Compliance hold of the user on this market, must not exist
+    #[account(
+        seeds = [RESTRICTED_WALLET_PDA_SEED.as_bytes(), market.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = restricted_wallet.data_is_empty() @ TokenMillError::WalletRestricted
+    )]
+    pub restricted_wallet: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposits `amount` base tokens into the compounding vault, minting shares at the current stake per share.
+/// Staking fees not compounded yet are shared with the new shares, so holders are better off cranking first.
+pub fn handler(ctx: Context<CompoundingVaultUpdate>, amount: u64) -> Result<()> {
+    require!(amount > 0, TokenMillError::InvalidAmount);
+
+    let compounding_vault = &mut ctx.accounts.compounding_vault;
+    let vault_stake_position = &mut ctx.accounts.vault_stake_position;
+    let compounding_position = &mut ctx.accounts.compounding_position;
+
+    if compounding_position.vault == Pubkey::default() {
+        compounding_position.initialize(compounding_vault.key(), ctx.accounts.user.key())?;
+    }
+
+    let shares = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+        let staking = &mut ctx.accounts.staking;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Restakes the pending penalties before pricing the shares
+        staking_manager::deposit(market, staking, vault_stake_position, 0, current_time)?;
+
+        let shares = compounding_vault.get_shares(amount, vault_stake_position.amount_staked)?;
+
+        require!(shares > 0, TokenMillError::InvalidAmount);
+
+        staking_manager::deposit(market, staking, vault_stake_position, amount, current_time)?;
+
+        compounding_position.min_stake_end = compounding_position
+            .min_stake_end
+            .max(current_time + staking.min_stake_duration);
+
+        shares
+    };
+
+    compounding_vault.total_shares += shares;
+    compounding_position.shares += shares;
+
+    transfer_from_eoa(
+        &ctx.accounts.base_token_mint,
+        &ctx.accounts.user,
+        &ctx.accounts.user_base_token_ata,
+        &ctx.accounts.market_base_token_ata,
+        &ctx.accounts.base_token_program,
+        amount,
+    )?;
+
+    emit_cpi!(TokenMillCompoundingVaultDepositEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        amount,
+        shares,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::actions::token_mill::{
+        CreateCompoundingVaultAction, DepositToCompoundingVaultAction, TokenMillEnv,
+    };
+
+    use crate::{CompoundingPosition, CompoundingVault, MarketStaking, StakePosition};
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    #[test]
+    fn deposit_to_compounding_vault() {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        let action = DepositToCompoundingVaultAction::new(&testing_env, STAKE_AMOUNT);
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&CreateCompoundingVaultAction::new(&testing_env), &action]);
+
+        assert!(result.is_ok());
+
+        let compounding_position = testing_env
+            .svm
+            .get_parsed_account::<CompoundingPosition>(&action.compounding_position);
+
+        assert_eq!(compounding_position.shares, STAKE_AMOUNT);
+
+        let compounding_vault = testing_env
+            .svm
+            .get_parsed_account::<CompoundingVault>(&action.compounding_vault);
+
+        assert_eq!(compounding_vault.total_shares, STAKE_AMOUNT);
+
+        let vault_stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.vault_stake_position);
+
+        assert_eq!(vault_stake_position.amount_staked, STAKE_AMOUNT);
+
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&action.market_staking);
+
+        assert_eq!(staking.amount_staked, STAKE_AMOUNT);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::{
+    constant::MAX_BPS,
+    errors::TokenMillError,
+    events::TokenMillEmergencyUnstakeEvent,
+    manager::{staking_manager, token_manager::transfer_from_pda},
+    math::{mul_div, Rounding},
+    state::{Market, MarketStaking, StakePosition},
+    TokenMillConfig, MARKET_PDA_SEED, RESTRICTED_WALLET_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct EmergencyUnstake<'info> {
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        mut,
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = user @ TokenMillError::InvalidAuthority
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = base_token_program
+    )]
+    pub user_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Compliance hold of the user on this market, must not exist
+    #[account(
+        seeds = [RESTRICTED_WALLET_PDA_SEED.as_bytes(), market.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = restricted_wallet.data_is_empty() @ TokenMillError::WalletRestricted
+    )]
+    pub restricted_wallet: UncheckedAccount<'info>,
+
+    pub user: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Withdraws `amount` from a stake position before the end of its lockup. The penalty of the config, rounded up, is
+/// kept by the market and restaked into the positions of the remaining stakers as they next accrue their rewards.
+/// Stake locked in a boost pool can't be withdrawn this way.
+pub fn handler(ctx: Context<EmergencyUnstake>, amount: u64) -> Result<()> {
+    let penalty_bps = ctx.accounts.config.emergency_unstake_penalty_bps;
+
+    require!(penalty_bps > 0, TokenMillError::EmergencyUnstakeDisabled);
+    require!(amount > 0, TokenMillError::InvalidAmount);
+
+    let staking = &mut ctx.accounts.staking;
+    let stake_position = &mut ctx.accounts.stake_position;
+
+    require_gte!(
+        stake_position.amount_staked,
+        amount,
+        TokenMillError::InsufficientStakeAmount
+    );
+
+    require_gte!(
+        stake_position.unlocked_amount(),
+        amount,
+        TokenMillError::StakeLocked
+    );
+
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(
+        stake_position.is_lockup_active(current_time),
+        TokenMillError::StakeNotLocked
+    );
+
+    require!(
+        stake_position.min_stake_end <= current_time,
+        TokenMillError::MinStakeDurationNotReached
+    );
+
+    let penalty_amount = u64::try_from(
+        mul_div(
+            u128::from(amount),
+            u128::from(penalty_bps),
+            u128::from(MAX_BPS),
+            Rounding::Up,
+        )
+        .ok_or(TokenMillError::MathError)?,
+    )?;
+
+    let market_bump = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        staking_manager::emergency_withdraw(
+            market,
+            staking,
+            stake_position,
+            amount,
+            penalty_amount,
+            current_time,
+        )?;
+
+        market.bump
+    };
+
+    let base_token_mint = &ctx.accounts.base_token_mint;
+    let base_token_mint_key = base_token_mint.key();
+    let seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint_key.as_ref(),
+        &[market_bump],
+    ];
+
+    transfer_from_pda(
+        base_token_mint,
+        ctx.accounts.market.to_account_info(),
+        &ctx.accounts.market_base_token_ata,
+        &ctx.accounts.user_base_token_ata,
+        &ctx.accounts.base_token_program,
+        amount - penalty_amount,
+        &seeds,
+    )?;
+
+    emit_cpi!(TokenMillEmergencyUnstakeEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        amount,
+        penalty_amount,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateStakePositionAction, DepositAction, EmergencyUnstakeAction,
+            LockStakePositionAction, SwapAction, TokenMillEnv, UpdateEmergencyUnstakePenaltyAction,
+            WithdrawAction,
+        },
+        make_address, SwapAmountType, SwapType,
+    };
+
+    use crate::{errors::TokenMillError, MarketStaking, StakePosition};
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+    const PENALTY_BPS: u16 = 2_000;
+    const PENALTY_AMOUNT: u64 = STAKE_AMOUNT / 5;
+
+    fn setup_env(penalty_bps: u16) -> (TokenMillEnv, EmergencyUnstakeAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&UpdateEmergencyUnstakePenaltyAction::new(penalty_bps)])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &DepositAction::new(&testing_env, STAKE_AMOUNT),
+                &LockStakePositionAction::new(&testing_env, 3),
+            ])
+            .unwrap();
+
+        let action = EmergencyUnstakeAction::new(&testing_env, STAKE_AMOUNT);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn emergency_unstake() {
+        let (mut testing_env, action) = setup_env(PENALTY_BPS);
+
+        let balance_before = testing_env
+            .svm
+            .get_balance(&action.base_token_mint, &make_address("bob"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let balance_after = testing_env
+            .svm
+            .get_balance(&action.base_token_mint, &make_address("bob"));
+
+        assert_eq!(
+            balance_after - balance_before,
+            STAKE_AMOUNT - PENALTY_AMOUNT
+        );
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        assert_eq!(stake_position.amount_staked, 0);
+        assert_eq!(stake_position.lock_tier, 0);
+        assert_eq!(stake_position.lock_end, 0);
+
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&action.market_staking);
+
+        assert_eq!(staking.amount_staked, 0);
+        assert_eq!(staking.total_lock_bonus, 0);
+        assert_eq!(staking.pending_penalty_amount, PENALTY_AMOUNT);
+    }
+
+    #[test]
+    fn emergency_unstake_penalty_restaked() {
+        let (mut testing_env, action) = setup_env(PENALTY_BPS);
+
+        // Carol stakes as much as Bob before his emergency unstake
+        let carol = testing_env.svm.change_payer("carol");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                SwapAction::new(
+                    &testing_env,
+                    SwapType::Buy,
+                    SwapAmountType::ExactOutput,
+                    STAKE_AMOUNT,
+                    u64::MAX,
+                    None,
+                )
+                .with_user(carol),
+                CreateStakePositionAction::new(&testing_env).with_user(carol),
+                DepositAction::new(&testing_env, STAKE_AMOUNT).with_user(carol),
+            ])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let mut withdraw_action = WithdrawAction::new(&testing_env, STAKE_AMOUNT);
+        withdraw_action.with_user(carol);
+
+        let result = testing_env.svm.execute_actions(&[&withdraw_action]);
+
+        assert!(result.is_ok());
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&withdraw_action.stake_position);
+
+        assert_eq!(stake_position.amount_staked, PENALTY_AMOUNT);
+
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&withdraw_action.market_staking);
+
+        assert_eq!(staking.amount_staked, PENALTY_AMOUNT);
+        assert_eq!(staking.pending_penalty_amount, 0);
+    }
+
+    #[test]
+    fn emergency_unstake_when_disabled() {
+        let (mut testing_env, action) = setup_env(0);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::EmergencyUnstakeDisabled);
+    }
+
+    #[test]
+    fn emergency_unstake_without_lockup() {
+        let (mut testing_env, mut action) = setup_env(PENALTY_BPS);
+
+        // Dave's position isn't locked
+        let dave = testing_env.svm.change_payer("dave");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                SwapAction::new(
+                    &testing_env,
+                    SwapType::Buy,
+                    SwapAmountType::ExactOutput,
+                    STAKE_AMOUNT,
+                    u64::MAX,
+                    None,
+                )
+                .with_user(dave),
+                CreateStakePositionAction::new(&testing_env).with_user(dave),
+                DepositAction::new(&testing_env, STAKE_AMOUNT).with_user(dave),
+            ])
+            .unwrap();
+
+        action.with_user(dave);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::StakeNotLocked);
+    }
+}
@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillStakingLockupEvent,
+    manager::staking_manager,
+    state::{Market, MarketStaking, StakePosition},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExpireStakePositionLock<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub stake_position: Account<'info, StakePosition>,
+}
+
+/// Removes the lock bonus of a position whose lock has ended, so that it stops earning the tier multiplier.
+/// Permissionless, the other stakers of the market getting the rewards the bonus would have taken.
+pub fn handler(ctx: Context<ExpireStakePositionLock>) -> Result<()> {
+    let stake_position = &mut ctx.accounts.stake_position;
+
+    {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        staking_manager::expire_lock(
+            market,
+            &mut ctx.accounts.staking,
+            stake_position,
+            Clock::get()?.unix_timestamp,
+        )?;
+    }
+
+    emit_cpi!(TokenMillStakingLockupEvent {
+        market: ctx.accounts.market.key(),
+        user: stake_position.user,
+        lock_tier: stake_position.lock_tier,
+        lock_end: stake_position.lock_end,
+        lock_bonus: stake_position.lock_bonus,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::actions::token_mill::{
+        tm_parse_error, DepositAction, ExpireStakePositionLockAction, LockStakePositionAction,
+        TokenMillEnv,
+    };
+
+    use crate::{
+        constant::STAKING_LOCKUP_TIERS, errors::TokenMillError, MarketStaking, StakePosition,
+    };
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    fn setup_env() -> (TokenMillEnv, ExpireStakePositionLockAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &DepositAction::new(&testing_env, STAKE_AMOUNT),
+                &LockStakePositionAction::new(&testing_env, 1),
+            ])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let action = ExpireStakePositionLockAction::new(&testing_env);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn expire_stake_position_lock() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.warp(STAKING_LOCKUP_TIERS[1].0);
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&action.market_staking);
+
+        assert_eq!(stake_position.lock_tier, 0);
+        assert_eq!(stake_position.lock_bonus, 0);
+        assert_eq!(stake_position.amount_staked, STAKE_AMOUNT);
+        assert_eq!(staking.total_lock_bonus, 0);
+        assert_eq!(staking.total_shares(), STAKE_AMOUNT);
+    }
+
+    #[test]
+    fn expire_active_stake_position_lock() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(tm_parse_error(result).unwrap(), TokenMillError::StakeLocked);
+    }
+
+    #[test]
+    fn expire_unlocked_stake_position() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.warp(STAKING_LOCKUP_TIERS[1].0);
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::StakeNotLocked
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{
+    errors::TokenMillError, events::TokenMillStakingLockupEvent, manager::staking_manager,
+};
+use anchor_lang::prelude::*;
+
+use super::StakeLockupUpdate;
+
+/// Relocks a locked position for the duration of `lock_tier` from now, which can't be lower than its current tier.
+/// The lock end never moves backwards.
+pub fn handler(ctx: Context<StakeLockupUpdate>, lock_tier: u8) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let staking = &mut ctx.accounts.staking;
+    let stake_position = &mut ctx.accounts.stake_position;
+
+    require!(
+        stake_position.is_lockup_active(current_time),
+        TokenMillError::StakeNotLocked
+    );
+    require_gte!(
+        lock_tier,
+        stake_position.lock_tier,
+        TokenMillError::InvalidLockDuration
+    );
+
+    {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        staking_manager::lock(market, staking, stake_position, lock_tier, current_time)?;
+    }
+
+    emit_cpi!(TokenMillStakingLockupEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        lock_tier,
+        lock_end: stake_position.lock_end,
+        lock_bonus: stake_position.lock_bonus,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::actions::token_mill::{
+        tm_parse_error, DepositAction, ExtendStakePositionLockAction, LockStakePositionAction,
+        TokenMillEnv,
+    };
+
+    use crate::{constant::STAKING_LOCKUP_TIERS, errors::TokenMillError, StakePosition};
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    fn setup_env() -> (TokenMillEnv, ExtendStakePositionLockAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &DepositAction::new(&testing_env, STAKE_AMOUNT),
+                &LockStakePositionAction::new(&testing_env, 1),
+            ])
+            .unwrap();
+
+        let action = ExtendStakePositionLockAction::new(&testing_env, 3);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn extend_stake_position_lock() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.warp(86_400);
+
+        let current_time = testing_env.svm.get_clock().unix_timestamp;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        assert_eq!(stake_position.lock_tier, 3);
+        assert_eq!(
+            stake_position.lock_end,
+            current_time + STAKING_LOCKUP_TIERS[3].0
+        );
+        assert_eq!(stake_position.lock_bonus, STAKE_AMOUNT);
+    }
+
+    #[test]
+    fn extend_stake_position_lock_to_lower_tier() {
+        let (mut testing_env, mut action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        action.lock_tier = 2;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidLockDuration);
+    }
+
+    #[test]
+    fn extend_unlocked_stake_position() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.warp(STAKING_LOCKUP_TIERS[1].0);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::StakeNotLocked);
+    }
+}
@@ -0,0 +1,232 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillRewardVaultFundingEvent,
+    manager::token_manager::transfer_from_eoa,
+    state::{Market, MarketStaking, RewardVault},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FundRewardVault<'info> {
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = reward_token_mint @ TokenMillError::InvalidMintAccount
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+
+    pub reward_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = reward_vault,
+        associated_token::token_program = reward_token_program
+    )]
+    pub reward_vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = reward_token_mint,
+        token::authority = funder,
+        token::token_program = reward_token_program
+    )]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub funder: Signer<'info>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Adds `amount` to the vault and restarts the emission of all the remaining rewards over `duration`.
+/// Anyone can fund the vault, but only the creator can lower the rate of an ongoing emission, e.g. by stretching it.
+pub fn handler(ctx: Context<FundRewardVault>, amount: u64, duration: i64) -> Result<()> {
+    require!(duration > 0, TokenMillError::InvalidEmissionDuration);
+
+    let is_creator = ctx.accounts.market.load()?.creator == ctx.accounts.funder.key();
+    let staking = &mut ctx.accounts.staking;
+    let reward_vault = &mut ctx.accounts.reward_vault;
+
+    require!(
+        amount > 0 || (is_creator && staking.vault_emission.reward_remaining > 0),
+        TokenMillError::InvalidAmount
+    );
+
+    let current_time = Clock::get()?.unix_timestamp;
+
+    staking.update_vault_emission(current_time)?;
+
+    let emission = &mut staking.vault_emission;
+    let previous_reward_rate = if emission.emission_end > current_time {
+        emission.reward_rate
+    } else {
+        0
+    };
+
+    emission.fund(amount, current_time, duration)?;
+
+    if !is_creator {
+        require_gte!(
+            emission.reward_rate,
+            previous_reward_rate,
+            TokenMillError::InvalidEmissionDuration
+        );
+    }
+
+    reward_vault.total_funded += amount;
+
+    transfer_from_eoa(
+        &ctx.accounts.reward_token_mint,
+        &ctx.accounts.funder,
+        &ctx.accounts.funder_token_account,
+        &ctx.accounts.reward_vault_ata,
+        &ctx.accounts.reward_token_program,
+        amount,
+    )?;
+
+    emit_cpi!(TokenMillRewardVaultFundingEvent {
+        market: ctx.accounts.market.key(),
+        funder: ctx.accounts.funder.key(),
+        amount,
+        reward_remaining: emission.reward_remaining,
+        emission_end: emission.emission_end,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateRewardVaultAction, FundRewardVaultAction, TokenMillEnv,
+        },
+        make_address, TokenType,
+    };
+
+    use crate::{errors::TokenMillError, MarketStaking, RewardVault};
+
+    const FUNDING_AMOUNT: u64 = 1_000_000_000;
+    const EMISSION_DURATION: i64 = 86_400;
+
+    fn setup_env() -> (TokenMillEnv, FundRewardVaultAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(0);
+
+        let reward_token_mint = testing_env.svm.create_token(TokenType::Token, 6).unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&CreateRewardVaultAction::new(
+                &testing_env,
+                reward_token_mint,
+            )])
+            .unwrap();
+
+        let action = FundRewardVaultAction::new(
+            &testing_env,
+            reward_token_mint,
+            FUNDING_AMOUNT,
+            EMISSION_DURATION,
+        );
+
+        (testing_env, action)
+    }
+
+    fn get_vault_balance(testing_env: &TokenMillEnv, action: &FundRewardVaultAction) -> u64 {
+        testing_env
+            .svm
+            .get_balance(&action.reward_token_mint, &action.reward_vault)
+    }
+
+    #[test]
+    fn fund_reward_vault() {
+        let (mut testing_env, action) = setup_env();
+
+        let current_time = testing_env.svm.get_clock().unix_timestamp;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&action.market_staking);
+
+        assert_eq!(staking.vault_emission.reward_remaining, FUNDING_AMOUNT);
+        assert_eq!(
+            staking.vault_emission.emission_end,
+            current_time + EMISSION_DURATION
+        );
+
+        let reward_vault = testing_env
+            .svm
+            .get_parsed_account::<RewardVault>(&action.reward_vault);
+
+        assert_eq!(reward_vault.total_funded, FUNDING_AMOUNT);
+        assert_eq!(get_vault_balance(&testing_env, &action), FUNDING_AMOUNT);
+    }
+
+    #[test]
+    fn fund_reward_vault_by_anyone() {
+        let (mut testing_env, mut action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let carol = testing_env.svm.change_payer("carol");
+        action.with_funder(carol);
+
+        // Stretching the emission would lower its rate
+        action.amount = FUNDING_AMOUNT / 2;
+        action.duration = 2 * EMISSION_DURATION;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidEmissionDuration);
+
+        action.duration = EMISSION_DURATION;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        assert_eq!(
+            get_vault_balance(&testing_env, &action),
+            FUNDING_AMOUNT + FUNDING_AMOUNT / 2
+        );
+    }
+
+    #[test]
+    fn fund_reward_vault_with_invalid_mint() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.reward_token_mint = testing_env.quote_token_mint.unwrap();
+        action.funder_token_account = testing_env
+            .svm
+            .get_ata_address(&action.reward_token_mint, &make_address("alice"));
+        action.reward_vault_ata = testing_env
+            .svm
+            .get_ata_address(&action.reward_token_mint, &testing_env.market);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidMintAccount);
+    }
+}
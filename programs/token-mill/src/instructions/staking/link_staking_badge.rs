@@ -0,0 +1,341 @@
+use crate::{
+    errors::TokenMillError,
+    events::{TokenMillStakingBadgeLinkEvent, TokenMillStakingBadgeUnlinkEvent},
+    manager::{staking_manager, token_manager::is_group_member_nft},
+    state::{Market, MarketStaking, StakePosition, StakingBadge},
+    STAKING_BADGE_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct LinkStakingBadge<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = user @ TokenMillError::InvalidAuthority
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + StakingBadge::INIT_SPACE,
+        seeds = [STAKING_BADGE_PDA_SEED.as_bytes(), market.key().as_ref(), badge_mint.key().as_ref()],
+        bump
+    )]
+    pub staking_badge: Account<'info, StakingBadge>,
+
+    /// Position the badge was last linked to, required if it isn't `stake_position`
+    #[account(
+        mut,
+        address = staking_badge.stake_position @ TokenMillError::InvalidStakePosition,
+        constraint = previous_stake_position.key() != stake_position.key() @ TokenMillError::InvalidStakePosition
+    )]
+    pub previous_stake_position: Option<Account<'info, StakePosition>>,
+
+    pub badge_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        token::mint = badge_mint,
+        token::authority = user,
+        constraint = user_badge_token_account.amount == 1 @ TokenMillError::InvalidStakingBadge
+    )]
+    pub user_badge_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Links an NFT of the badge collection of the market held by the user to their stake position, boosting its stake by
+/// the badge multiplier. A badge boosts a single position at a time, linking it again moves the boost away from the
+/// position it was linked to. The boost is removed when claiming staking rewards without holding the badge anymore.
+pub fn handler(ctx: Context<LinkStakingBadge>) -> Result<()> {
+    let staking = &mut ctx.accounts.staking;
+    let stake_position = &mut ctx.accounts.stake_position;
+    let staking_badge = &mut ctx.accounts.staking_badge;
+    let badge_mint = ctx.accounts.badge_mint.key();
+
+    require!(
+        staking.badge_collection != Pubkey::default()
+            && is_group_member_nft(&ctx.accounts.badge_mint, &staking.badge_collection)?,
+        TokenMillError::InvalidStakingBadge
+    );
+    require!(
+        stake_position.badge_mint != badge_mint,
+        TokenMillError::InvalidStakingBadge
+    );
+
+    if staking_badge.market == Pubkey::default() {
+        staking_badge.market = ctx.accounts.market.key();
+        staking_badge.badge_mint = badge_mint;
+    }
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let mut previous_user = None;
+
+    {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        if staking_badge.stake_position != Pubkey::default()
+            && staking_badge.stake_position != stake_position.key()
+        {
+            let previous_stake_position = ctx
+                .accounts
+                .previous_stake_position
+                .as_mut()
+                .ok_or(TokenMillError::InvalidStakePosition)?;
+
+            if previous_stake_position.badge_mint == badge_mint {
+                staking_manager::unlink_badge(
+                    market,
+                    staking,
+                    previous_stake_position,
+                    current_time,
+                )?;
+
+                previous_user = Some(previous_stake_position.user);
+            }
+        }
+
+        staking_manager::link_badge(market, staking, stake_position, badge_mint, current_time)?;
+    }
+
+    staking_badge.stake_position = stake_position.key();
+
+    if let Some(previous_user) = previous_user {
+        emit_cpi!(TokenMillStakingBadgeUnlinkEvent {
+            market: ctx.accounts.market.key(),
+            user: previous_user,
+            badge_mint,
+        });
+    }
+
+    emit_cpi!(TokenMillStakingBadgeLinkEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        badge_mint,
+        badge_bonus: stake_position.badge_bonus,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, ClaimStakingRewardsAction, CreateStakePositionAction, DepositAction,
+            LinkStakingBadgeAction, SetStakingBadgeCollectionAction, TokenMillEnv,
+        },
+        make_address,
+    };
+
+    use crate::{errors::TokenMillError, MarketStaking, StakePosition};
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+    const BADGE_MULTIPLIER_BPS: u16 = 12_000;
+    const BADGE_BONUS: u64 = STAKE_AMOUNT / 5;
+
+    fn setup_env() -> (TokenMillEnv, LinkStakingBadgeAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        let badge_collection = make_address("badge_collection");
+
+        testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetStakingBadgeCollectionAction::new(
+                &testing_env,
+                badge_collection,
+                BADGE_MULTIPLIER_BPS,
+            )])
+            .unwrap();
+
+        let badge_mint = testing_env
+            .svm
+            .create_group_member_nft(&badge_collection, &make_address("bob"))
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+
+        let action = LinkStakingBadgeAction::new(&testing_env, badge_mint);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn link_staking_badge() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&action.market_staking);
+
+        assert_eq!(stake_position.badge_mint, action.badge_mint);
+        assert_eq!(stake_position.badge_bonus, BADGE_BONUS);
+        assert_eq!(stake_position.total_shares(), STAKE_AMOUNT + BADGE_BONUS);
+        assert_eq!(staking.total_badge_bonus, BADGE_BONUS);
+
+        // Already linked
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidStakingBadge
+        );
+    }
+
+    #[test]
+    fn link_staking_badge_removed_at_claim() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        // Claiming while holding the badge keeps the boost
+        let mut claim_action = ClaimStakingRewardsAction::new(&testing_env);
+        claim_action.with_badge(action.badge_mint);
+
+        testing_env.svm.execute_actions(&[&claim_action]).unwrap();
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        assert_eq!(stake_position.badge_bonus, BADGE_BONUS);
+
+        // The badge leaves the wallet
+        testing_env
+            .svm
+            .transfer_nft(
+                &action.badge_mint,
+                &make_address("bob"),
+                &make_address("carol"),
+            )
+            .unwrap();
+
+        testing_env.svm.execute_actions(&[&claim_action]).unwrap();
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&action.market_staking);
+
+        assert_eq!(stake_position.badge_bonus, 0);
+        assert_eq!(staking.total_badge_bonus, 0);
+    }
+
+    #[test]
+    fn link_staking_badge_moved_to_new_holder() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env
+            .svm
+            .transfer_nft(
+                &action.badge_mint,
+                &make_address("bob"),
+                &make_address("carol"),
+            )
+            .unwrap();
+
+        let carol = testing_env.svm.change_payer("carol");
+
+        testing_env
+            .svm
+            .execute_actions(&[CreateStakePositionAction::new(&testing_env).with_user(carol)])
+            .unwrap();
+
+        let mut carol_action = LinkStakingBadgeAction::new(&testing_env, action.badge_mint);
+        carol_action.with_user(carol);
+
+        // The position boosted so far has to be passed to be unlinked
+        let result = testing_env.svm.execute_actions(&[&carol_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidStakePosition
+        );
+
+        carol_action.previous_stake_position = action.stake_position;
+
+        let result = testing_env.svm.execute_actions(&[&carol_action]);
+
+        assert!(result.is_ok());
+
+        let bob_stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+        let carol_stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&carol_action.stake_position);
+
+        assert_eq!(bob_stake_position.badge_bonus, 0);
+        assert_eq!(carol_stake_position.badge_mint, action.badge_mint);
+    }
+
+    #[test]
+    fn link_staking_badge_outside_collection() {
+        let (mut testing_env, _) = setup_env();
+
+        let badge_mint = testing_env
+            .svm
+            .create_group_member_nft(&make_address("other_collection"), &make_address("bob"))
+            .unwrap();
+
+        let action = LinkStakingBadgeAction::new(&testing_env, badge_mint);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidStakingBadge
+        );
+    }
+
+    #[test]
+    fn link_staking_badge_not_held() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env
+            .svm
+            .transfer_nft(
+                &action.badge_mint,
+                &make_address("bob"),
+                &make_address("carol"),
+            )
+            .unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidStakingBadge
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillStakingLockupEvent,
+    manager::staking_manager,
+    state::{Market, MarketStaking, StakePosition},
+};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct StakeLockupUpdate<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = user @ TokenMillError::InvalidAuthority
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    pub user: Signer<'info>,
+}
+
+/// Locks the whole stake of an unlocked position for the duration of `lock_tier`, scaling its staking rewards
+/// by the tier multiplier. The stake can't be withdrawn until the lock ends.
+pub fn handler(ctx: Context<StakeLockupUpdate>, lock_tier: u8) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let staking = &mut ctx.accounts.staking;
+    let stake_position = &mut ctx.accounts.stake_position;
+
+    require!(
+        !stake_position.is_lockup_active(current_time),
+        TokenMillError::StakeLocked
+    );
+
+    {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        staking_manager::lock(market, staking, stake_position, lock_tier, current_time)?;
+    }
+
+    emit_cpi!(TokenMillStakingLockupEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        lock_tier,
+        lock_end: stake_position.lock_end,
+        lock_bonus: stake_position.lock_bonus,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateStakePositionAction, DepositAction, LockStakePositionAction,
+            SwapAction, TokenMillEnv, WithdrawAction,
+        },
+        SwapAmountType, SwapType,
+    };
+
+    use crate::{
+        constant::STAKING_LOCKUP_TIERS, errors::TokenMillError, MarketStaking, StakePosition,
+    };
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    fn setup_env() -> (TokenMillEnv, LockStakePositionAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT * 2);
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+
+        let action = LockStakePositionAction::new(&testing_env, 2);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn lock_stake_position() {
+        let (mut testing_env, action) = setup_env();
+
+        let current_time = testing_env.svm.get_clock().unix_timestamp;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        assert_eq!(stake_position.lock_tier, 2);
+        assert_eq!(
+            stake_position.lock_end,
+            current_time + STAKING_LOCKUP_TIERS[2].0
+        );
+        assert_eq!(stake_position.lock_bonus, STAKE_AMOUNT / 2);
+
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&action.market_staking);
+
+        assert_eq!(staking.total_lock_bonus, STAKE_AMOUNT / 2);
+
+        // Deposits join the lockup
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        assert_eq!(stake_position.lock_bonus, STAKE_AMOUNT);
+    }
+
+    #[test]
+    fn locked_stake_rewards() {
+        let (mut testing_env, action) = setup_env();
+
+        // Carol stakes the same amount without locking it
+        let carol = testing_env.svm.change_payer("carol");
+        let mut carol_deposit_action = DepositAction::new(&testing_env, STAKE_AMOUNT);
+        carol_deposit_action.with_user(carol);
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                SwapAction::new(
+                    &testing_env,
+                    SwapType::Buy,
+                    SwapAmountType::ExactOutput,
+                    STAKE_AMOUNT,
+                    u64::MAX,
+                    None,
+                )
+                .with_user(carol),
+                CreateStakePositionAction::new(&testing_env).with_user(carol),
+                &carol_deposit_action,
+            ])
+            .unwrap();
+
+        // Bob locks for 180 days, doubling his rewards
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&LockStakePositionAction::new(&testing_env, 3)])
+            .unwrap();
+
+        let bob_rewards_before = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position)
+            .pending_rewards;
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                1_000_000_000_000 / 2,
+                u64::MAX,
+                None,
+            )])
+            .unwrap();
+
+        // Empty deposits accrue the rewards of the positions
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, 0)])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        testing_env
+            .svm
+            .execute_actions(&[DepositAction::new(&testing_env, 0).with_user(carol)])
+            .unwrap();
+
+        let bob_rewards = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position)
+            .pending_rewards
+            - bob_rewards_before;
+        let carol_rewards = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&carol_deposit_action.stake_position)
+            .pending_rewards;
+
+        assert!(carol_rewards > 0);
+        assert!(bob_rewards.abs_diff(2 * carol_rewards) <= 2);
+    }
+
+    #[test]
+    fn withdraw_before_unlock() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let withdraw_action = WithdrawAction::new(&testing_env, STAKE_AMOUNT);
+
+        let result = testing_env.svm.execute_actions(&[&withdraw_action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::StakeLocked);
+
+        testing_env.svm.warp(STAKING_LOCKUP_TIERS[2].0);
+
+        let result = testing_env.svm.execute_actions(&[&withdraw_action]);
+
+        assert!(result.is_ok());
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        assert_eq!(stake_position.amount_staked, 0);
+        assert_eq!(stake_position.lock_tier, 0);
+        assert_eq!(stake_position.lock_bonus, 0);
+
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&action.market_staking);
+
+        assert_eq!(staking.total_lock_bonus, 0);
+    }
+
+    #[test]
+    fn lock_stake_position_while_locked() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let result = testing_env
+            .svm
+            .execute_actions(&[&LockStakePositionAction::new(&testing_env, 3)]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::StakeLocked);
+    }
+
+    #[test]
+    fn lock_stake_position_with_invalid_tier() {
+        let (mut testing_env, mut action) = setup_env();
+
+        for lock_tier in [0, STAKING_LOCKUP_TIERS.len() as u8] {
+            action.lock_tier = lock_tier;
+
+            let result = testing_env.svm.execute_actions(&[&action]);
+
+            assert!(result.is_err());
+
+            let error = tm_parse_error(result).unwrap();
+
+            assert_eq!(error, TokenMillError::InvalidLockDuration);
+        }
+    }
+}
@@ -1,10 +1,54 @@
+pub mod buy_and_stake;
+pub mod checkpoint_staking_epoch;
 pub mod claim_staking_rewards;
+pub mod claim_vault_rewards;
+pub mod compound_staking_rewards;
+pub mod create_compounding_vault;
+pub mod create_reward_vault;
 pub mod create_stake_position;
 pub mod create_staking;
+pub mod create_staking_checkpoints;
+pub mod delegated_claim_staking_rewards;
 pub mod deposit;
+pub mod deposit_to_compounding_vault;
+pub mod emergency_unstake;
+pub mod expire_stake_position_lock;
+pub mod extend_stake_position_lock;
+pub mod fund_reward_vault;
+pub mod link_staking_badge;
+pub mod lock_stake_position;
+pub mod set_claim_delegate;
+pub mod set_min_stake_duration;
+pub mod set_staking_badge_collection;
+pub mod stake_for;
+pub mod transfer_stake_position;
+pub mod upgrade_market_staking;
+pub mod upgrade_stake_position;
 pub mod withdraw;
+pub mod withdraw_from_compounding_vault;
 
+pub use buy_and_stake::*;
+pub use checkpoint_staking_epoch::*;
 pub use claim_staking_rewards::*;
+pub use claim_vault_rewards::*;
+pub use compound_staking_rewards::*;
+pub use create_compounding_vault::*;
+pub use create_reward_vault::*;
 pub use create_stake_position::*;
 pub use create_staking::*;
+pub use create_staking_checkpoints::*;
+pub use delegated_claim_staking_rewards::*;
 pub use deposit::*;
+pub use deposit_to_compounding_vault::*;
+pub use emergency_unstake::*;
+pub use expire_stake_position_lock::*;
+pub use fund_reward_vault::*;
+pub use link_staking_badge::*;
+pub use lock_stake_position::*;
+pub use set_claim_delegate::*;
+pub use set_min_stake_duration::*;
+pub use set_staking_badge_collection::*;
+pub use stake_for::*;
+pub use transfer_stake_position::*;
+pub use upgrade_market_staking::*;
+pub use upgrade_stake_position::*;
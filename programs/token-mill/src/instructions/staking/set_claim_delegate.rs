@@ -0,0 +1,94 @@
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillClaimDelegateUpdateEvent,
+    state::{Market, StakePosition},
+};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetClaimDelegate<'info> {
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = user @ TokenMillError::InvalidAuthority
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    pub user: Signer<'info>,
+}
+
+/// Lets `claim_delegate` claim the staking rewards of the position, always sent to the user, e.g. for harvest bots.
+/// The delegate can't withdraw nor move the stake. The default pubkey revokes the current delegate.
+pub fn handler(ctx: Context<SetClaimDelegate>, claim_delegate: Pubkey) -> Result<()> {
+    require!(
+        claim_delegate != ctx.accounts.user.key(),
+        TokenMillError::InvalidAuthority
+    );
+
+    ctx.accounts.stake_position.claim_delegate = claim_delegate;
+
+    emit_cpi!(TokenMillClaimDelegateUpdateEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        claim_delegate,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, SetClaimDelegateAction, TokenMillEnv},
+        make_address,
+    };
+
+    use crate::{errors::TokenMillError, StakePosition};
+
+    #[test]
+    fn set_claim_delegate() {
+        let mut testing_env = TokenMillEnv::default().with_staking(0);
+
+        let mut action = SetClaimDelegateAction::new(&testing_env, make_address("dave"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        assert_eq!(stake_position.claim_delegate, make_address("dave"));
+
+        // Revoked
+        action.claim_delegate = Pubkey::default();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        assert_eq!(stake_position.claim_delegate, Pubkey::default());
+    }
+
+    #[test]
+    fn set_claim_delegate_with_invalid_signer() {
+        let mut testing_env = TokenMillEnv::default().with_staking(0);
+
+        let mut action = SetClaimDelegateAction::new(&testing_env, make_address("mallory"));
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
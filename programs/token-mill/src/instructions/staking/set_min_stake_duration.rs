@@ -0,0 +1,145 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::MAX_MIN_STAKE_DURATION,
+    errors::TokenMillError,
+    events::TokenMillMinStakeDurationUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_i64, ChangeLogParameter, Market, MarketStaking},
+    CHANGE_LOG_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetMinStakeDuration<'info> {
+    #[account(has_one = creator @ TokenMillError::InvalidAuthority)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    /// CHECK: Change log of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [CHANGE_LOG_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub change_log: UncheckedAccount<'info>,
+
+    pub creator: Signer<'info>,
+}
+
+/// Sets how long deposits have to stay staked before being withdrawn, up to `MAX_MIN_STAKE_DURATION`,
+/// against stakers jumping in right before large fee distributions. Stake deposited before keeps its own end.
+pub fn handler(ctx: Context<SetMinStakeDuration>, min_stake_duration: i64) -> Result<()> {
+    require!(
+        (0..=MAX_MIN_STAKE_DURATION).contains(&min_stake_duration),
+        TokenMillError::InvalidMinStakeDuration
+    );
+
+    let staking = &mut ctx.accounts.staking;
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::MinStakeDuration,
+        encode_i64(staking.min_stake_duration),
+        encode_i64(min_stake_duration),
+    )?;
+
+    staking.min_stake_duration = min_stake_duration;
+
+    emit_cpi!(TokenMillMinStakeDurationUpdateEvent {
+        market: ctx.accounts.market.key(),
+        min_stake_duration,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::actions::token_mill::{
+        tm_parse_error, DepositAction, SetMinStakeDurationAction, TokenMillEnv, WithdrawAction,
+    };
+
+    use crate::{constant::MAX_MIN_STAKE_DURATION, errors::TokenMillError, MarketStaking};
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+    const MIN_STAKE_DURATION: i64 = 86_400;
+
+    fn setup_env() -> (TokenMillEnv, SetMinStakeDurationAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        testing_env.svm.change_payer("alice");
+
+        let action = SetMinStakeDurationAction::new(&testing_env, MIN_STAKE_DURATION);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn set_min_stake_duration() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&action.market_staking);
+
+        assert_eq!(staking.min_stake_duration, MIN_STAKE_DURATION);
+
+        // Deposits can only be withdrawn once the duration has passed
+        testing_env.svm.change_payer("bob");
+
+        let withdraw_action = WithdrawAction::new(&testing_env, STAKE_AMOUNT);
+
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&withdraw_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MinStakeDurationNotReached
+        );
+
+        testing_env.svm.warp(MIN_STAKE_DURATION);
+
+        let result = testing_env.svm.execute_actions(&[&withdraw_action]);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn set_min_stake_duration_with_invalid_value() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.min_stake_duration = MAX_MIN_STAKE_DURATION + 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidMinStakeDuration
+        );
+    }
+
+    #[test]
+    fn set_min_stake_duration_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::{MAX_BPS, MAX_STAKING_BADGE_MULTIPLIER_BPS},
+    errors::TokenMillError,
+    events::TokenMillStakingBadgeCollectionUpdateEvent,
+    manager::change_log_manager::record_change,
+    state::{encode_pubkey, encode_u64, ChangeLogParameter, Market, MarketStaking},
+    CHANGE_LOG_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetStakingBadgeCollection<'info> {
+    #[account(has_one = creator @ TokenMillError::InvalidAuthority)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    /// CHECK: Change log of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [CHANGE_LOG_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub change_log: UncheckedAccount<'info>,
+
+    pub creator: Signer<'info>,
+}
+
+/// Sets the Token-2022 group whose member NFTs boost the stake of the positions they're linked to by
+/// `badge_multiplier_bps`, up to `MAX_STAKING_BADGE_MULTIPLIER_BPS`. The collection can't be changed once set,
+/// but the multiplier can, down to 1x to turn the boost off. Linked positions pick it up on their next update.
+pub fn handler(
+    ctx: Context<SetStakingBadgeCollection>,
+    badge_collection: Pubkey,
+    badge_multiplier_bps: u16,
+) -> Result<()> {
+    require!(
+        (MAX_BPS as u16..=MAX_STAKING_BADGE_MULTIPLIER_BPS).contains(&badge_multiplier_bps),
+        TokenMillError::InvalidStakingBadgeMultiplier
+    );
+    require!(
+        badge_collection != Pubkey::default(),
+        TokenMillError::InvalidStakingBadge
+    );
+
+    let staking = &mut ctx.accounts.staking;
+
+    require!(
+        staking.badge_collection == Pubkey::default()
+            || staking.badge_collection == badge_collection,
+        TokenMillError::StakingBadgeCollectionAlreadySet
+    );
+
+    record_change(
+        &ctx.accounts.change_log,
+        ChangeLogParameter::StakingBadgeCollection,
+        encode_pubkey(Some(badge_collection)),
+        encode_u64(badge_multiplier_bps.into()),
+    )?;
+
+    staking.badge_collection = badge_collection;
+    staking.badge_multiplier_bps = badge_multiplier_bps;
+
+    emit_cpi!(TokenMillStakingBadgeCollectionUpdateEvent {
+        market: ctx.accounts.market.key(),
+        badge_collection,
+        badge_multiplier_bps,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{tm_parse_error, SetStakingBadgeCollectionAction, TokenMillEnv},
+        make_address,
+    };
+
+    use crate::{
+        constant::MAX_STAKING_BADGE_MULTIPLIER_BPS, errors::TokenMillError, MarketStaking,
+    };
+
+    const BADGE_MULTIPLIER_BPS: u16 = 12_000;
+
+    fn setup_env() -> (TokenMillEnv, SetStakingBadgeCollectionAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(0);
+
+        testing_env.svm.change_payer("alice");
+
+        let action = SetStakingBadgeCollectionAction::new(
+            &testing_env,
+            make_address("badge_collection"),
+            BADGE_MULTIPLIER_BPS,
+        );
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn set_staking_badge_collection() {
+        let (mut testing_env, mut action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&action.market_staking);
+
+        assert_eq!(staking.badge_collection, make_address("badge_collection"));
+        assert_eq!(staking.badge_multiplier_bps, BADGE_MULTIPLIER_BPS);
+
+        // The multiplier stays adjustable
+        action.badge_multiplier_bps = MAX_STAKING_BADGE_MULTIPLIER_BPS;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&action.market_staking);
+
+        assert_eq!(
+            staking.badge_multiplier_bps,
+            MAX_STAKING_BADGE_MULTIPLIER_BPS
+        );
+    }
+
+    #[test]
+    fn set_staking_badge_collection_twice() {
+        let (mut testing_env, mut action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        action.badge_collection = make_address("other_badge_collection");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::StakingBadgeCollectionAlreadySet
+        );
+    }
+
+    #[test]
+    fn set_staking_badge_collection_with_invalid_multiplier() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.badge_multiplier_bps = MAX_STAKING_BADGE_MULTIPLIER_BPS + 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidStakingBadgeMultiplier
+        );
+    }
+
+    #[test]
+    fn set_staking_badge_collection_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{
+    errors::TokenMillError,
+    events::{TokenMillStakingDepositEvent, TokenMillStakingLockupEvent},
+    manager::{staking_manager, token_manager::transfer_from_eoa},
+    state::{Market, MarketStaking, StakePosition},
+    RESTRICTED_WALLET_PDA_SEED, STAKING_POSITION_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct StakeFor<'info> {
+    #[account(mut, has_one = base_token_mint @ TokenMillError::InvalidMintAccount)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [STAKING_POSITION_PDA_SEED.as_bytes(), market.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    /// CHECK: Owner of the stake position, the only one able to withdraw from it or claim its rewards
+    pub beneficiary: UncheckedAccount<'info>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = base_token_mint,
+        token::authority = payer,
+        token::token_program = base_token_program
+    )]
+    pub payer_base_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Compliance hold of the beneficiary on this market, must not exist
+    #[account(
+        seeds = [RESTRICTED_WALLET_PDA_SEED.as_bytes(), market.key().as_ref(), beneficiary.key().as_ref()],
+        bump,
+        constraint = restricted_wallet.data_is_empty() @ TokenMillError::WalletRestricted
+    )]
+    pub restricted_wallet: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposits `amount` base tokens of the payer into the stake position of `beneficiary`, created if needed.
+/// A `lock_tier` above 0 locks the deposit as with `lock_stake_position`, which is only allowed on positions without
+/// stake so that a payer can't lock the stake of others.
+pub fn handler(ctx: Context<StakeFor>, amount: u64, lock_tier: u8) -> Result<()> {
+    require!(amount > 0, TokenMillError::InvalidAmount);
+
+    let staking = &mut ctx.accounts.staking;
+    let stake_position = &mut ctx.accounts.stake_position;
+
+    if stake_position.market == Pubkey::default() {
+        stake_position.initialize(ctx.accounts.market.key(), ctx.accounts.beneficiary.key())?;
+    }
+
+    require!(
+        lock_tier == 0 || stake_position.amount_staked == 0,
+        TokenMillError::InvalidStakePosition
+    );
+
+    {
+        let market = &mut ctx.accounts.market.load_mut()?;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        staking_manager::deposit(market, staking, stake_position, amount, current_time)?;
+
+        if lock_tier > 0 {
+            staking_manager::lock(market, staking, stake_position, lock_tier, current_time)?;
+        }
+    }
+
+    transfer_from_eoa(
+        &ctx.accounts.base_token_mint,
+        &ctx.accounts.payer,
+        &ctx.accounts.payer_base_token_account,
+        &ctx.accounts.market_base_token_ata,
+        &ctx.accounts.base_token_program,
+        amount,
+    )?;
+
+    emit_cpi!(TokenMillStakingDepositEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.beneficiary.key(),
+        amount,
+    });
+
+    if lock_tier > 0 {
+        emit_cpi!(TokenMillStakingLockupEvent {
+            market: ctx.accounts.market.key(),
+            user: ctx.accounts.beneficiary.key(),
+            lock_tier,
+            lock_end: stake_position.lock_end,
+            lock_bonus: stake_position.lock_bonus,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, DepositAction, StakeForAction, TokenMillEnv, WithdrawAction,
+        },
+        make_address, TokenType,
+    };
+
+    use crate::{constant::STAKING_LOCKUP_TIERS, errors::TokenMillError, StakePosition};
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    fn setup_env(lock_tier: u8) -> (TokenMillEnv, StakeForAction) {
+        let testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        let action =
+            StakeForAction::new(&testing_env, make_address("carol"), STAKE_AMOUNT, lock_tier);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn stake_for() {
+        let (mut testing_env, action) = setup_env(1);
+
+        let current_time = testing_env.svm.get_clock().unix_timestamp;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        assert_eq!(stake_position.user, make_address("carol"));
+        assert_eq!(stake_position.amount_staked, STAKE_AMOUNT);
+        assert_eq!(stake_position.lock_tier, 1);
+        assert_eq!(
+            stake_position.lock_end,
+            current_time + STAKING_LOCKUP_TIERS[1].0
+        );
+
+        // Only the beneficiary can withdraw, once the lockup ends
+        testing_env.svm.warp(STAKING_LOCKUP_TIERS[1].0);
+
+        let mut withdraw_action = WithdrawAction::new(&testing_env, STAKE_AMOUNT);
+        withdraw_action.stake_position = action.stake_position;
+
+        let result = testing_env.svm.execute_actions(&[&withdraw_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+
+        let carol = testing_env.svm.change_payer("carol");
+
+        testing_env
+            .svm
+            .create_ata(
+                &carol,
+                &testing_env.base_token_mint.unwrap(),
+                TokenType::Token2022,
+            )
+            .unwrap();
+
+        withdraw_action.with_user(carol);
+
+        let result = testing_env.svm.execute_actions(&[&withdraw_action]);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn stake_for_locking_staked_position() {
+        let (mut testing_env, _) = setup_env(1);
+
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, STAKE_AMOUNT / 2)])
+            .unwrap();
+
+        let action = StakeForAction::new(&testing_env, make_address("bob"), STAKE_AMOUNT / 2, 1);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidStakePosition);
+    }
+}
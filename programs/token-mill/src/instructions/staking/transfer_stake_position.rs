@@ -0,0 +1,238 @@
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillStakePositionTransferEvent,
+    manager::staking_manager,
+    state::{Market, MarketStaking, StakePosition},
+    RESTRICTED_WALLET_PDA_SEED, STAKING_POSITION_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TransferStakePosition<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = user @ TokenMillError::InvalidAuthority
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [STAKING_POSITION_PDA_SEED.as_bytes(), market.key().as_ref(), new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_stake_position: Account<'info, StakePosition>,
+
+    /// CHECK: Owner of the receiving stake position
+    #[account(constraint = new_owner.key() != user.key() @ TokenMillError::InvalidStakePosition)]
+    pub new_owner: UncheckedAccount<'info>,
+
+    /// CHECK: Compliance hold of the new owner on this market, must not exist
+    #[account(
+        seeds = [RESTRICTED_WALLET_PDA_SEED.as_bytes(), market.key().as_ref(), new_owner.key().as_ref()],
+        bump,
+        constraint = restricted_wallet.data_is_empty() @ TokenMillError::WalletRestricted
+    )]
+    pub restricted_wallet: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Moves the stake of a position, lockup included, to the position of `new_owner`, so that locked stake can change
+/// hands without being withdrawn. The receiving position must hold no stake. Vesting plans and boost locks are bound to
+/// the position and have to be released first, and the pending rewards stay claimable by the previous owner.
+pub fn handler(ctx: Context<TransferStakePosition>) -> Result<()> {
+    let staking = &mut ctx.accounts.staking;
+    let stake_position = &mut ctx.accounts.stake_position;
+    let new_stake_position = &mut ctx.accounts.new_stake_position;
+
+    require!(
+        stake_position.amount_staked > 0,
+        TokenMillError::InsufficientStakeAmount
+    );
+    require!(
+        stake_position.total_amount_vested == 0 && stake_position.amount_locked == 0,
+        TokenMillError::StakePositionNotTransferable
+    );
+
+    if new_stake_position.market == Pubkey::default() {
+        new_stake_position.initialize(ctx.accounts.market.key(), ctx.accounts.new_owner.key())?;
+    }
+
+    require!(
+        new_stake_position.amount_staked == 0 && new_stake_position.total_amount_vested == 0,
+        TokenMillError::StakePositionNotTransferable
+    );
+
+    {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        staking_manager::transfer(
+            market,
+            staking,
+            stake_position,
+            new_stake_position,
+            Clock::get()?.unix_timestamp,
+        )?;
+    }
+
+    emit_cpi!(TokenMillStakePositionTransferEvent {
+        market: ctx.accounts.market.key(),
+        from: ctx.accounts.user.key(),
+        to: ctx.accounts.new_owner.key(),
+        amount: new_stake_position.amount_staked,
+        lock_tier: new_stake_position.lock_tier,
+        lock_end: new_stake_position.lock_end,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateStakePositionAction, DepositAction, LockStakePositionAction,
+            SwapAction, TokenMillEnv, TransferStakePositionAction, WithdrawAction,
+        },
+        make_address, SwapAmountType, SwapType, TokenType,
+    };
+
+    use crate::{constant::STAKING_LOCKUP_TIERS, errors::TokenMillError, StakePosition};
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    fn setup_env() -> (TokenMillEnv, TransferStakePositionAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &DepositAction::new(&testing_env, STAKE_AMOUNT),
+                &LockStakePositionAction::new(&testing_env, 1),
+            ])
+            .unwrap();
+
+        let action = TransferStakePositionAction::new(&testing_env, make_address("carol"));
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn transfer_stake_position() {
+        let (mut testing_env, action) = setup_env();
+
+        let bob_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let carol_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.new_stake_position);
+
+        assert_eq!(carol_position.user, make_address("carol"));
+        assert_eq!(carol_position.amount_staked, STAKE_AMOUNT);
+        assert_eq!(carol_position.lock_tier, bob_position.lock_tier);
+        assert_eq!(carol_position.lock_end, bob_position.lock_end);
+        assert_eq!(carol_position.lock_bonus, bob_position.lock_bonus);
+
+        let bob_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        assert_eq!(bob_position.amount_staked, 0);
+        assert_eq!(bob_position.lock_bonus, 0);
+
+        // The lockup moved with the stake
+        let carol = testing_env.svm.change_payer("carol");
+
+        testing_env
+            .svm
+            .create_ata(
+                &carol,
+                &testing_env.base_token_mint.unwrap(),
+                TokenType::Token2022,
+            )
+            .unwrap();
+
+        let mut withdraw_action = WithdrawAction::new(&testing_env, STAKE_AMOUNT);
+        withdraw_action.with_user(carol);
+
+        let result = testing_env.svm.execute_actions(&[&withdraw_action]);
+
+        assert_eq!(tm_parse_error(result).unwrap(), TokenMillError::StakeLocked);
+
+        testing_env.svm.warp(STAKING_LOCKUP_TIERS[1].0);
+
+        let result = testing_env.svm.execute_actions(&[&withdraw_action]);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn transfer_stake_position_to_staker() {
+        let (mut testing_env, action) = setup_env();
+
+        let carol = testing_env.svm.change_payer("carol");
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                SwapAction::new(
+                    &testing_env,
+                    SwapType::Buy,
+                    SwapAmountType::ExactOutput,
+                    STAKE_AMOUNT,
+                    u64::MAX,
+                    None,
+                )
+                .with_user(carol),
+                CreateStakePositionAction::new(&testing_env).with_user(carol),
+                DepositAction::new(&testing_env, STAKE_AMOUNT).with_user(carol),
+            ])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::StakePositionNotTransferable);
+    }
+
+    #[test]
+    fn transfer_stake_position_to_self() {
+        let (mut testing_env, _) = setup_env();
+
+        let action = TransferStakePositionAction::new(&testing_env, make_address("bob"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InvalidStakePosition);
+    }
+}
@@ -0,0 +1,122 @@
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::{
+    constant::MAX_BPS, errors::TokenMillError, events::TokenMillMarketStakingUpgradeEvent,
+    instructions::upgrade_config::grow_account, state::MarketStaking,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpgradeMarketStaking<'info> {
+    /// CHECK: Staking of the first deployment, checked by the handler as it doesn't deserialize yet
+    #[account(mut, owner = crate::ID @ TokenMillError::InvalidMarketStaking)]
+    pub staking: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a market staking created by the first deployment to the current layout, the payer topping up its rent.
+/// The fields added since then are appended, so the zeroed bytes deserialize to their default values, but for the
+/// badge multiplier set to 1x. Permissionless, as the stake positions of the market can't be used until it's upgraded
+pub fn handler(ctx: Context<UpgradeMarketStaking>) -> Result<()> {
+    let staking_info = ctx.accounts.staking.to_account_info();
+    let new_len = 8 + MarketStaking::INIT_SPACE;
+
+    {
+        let data = staking_info.try_borrow_data()?;
+
+        require!(
+            data.len() >= 8 && data.len() < new_len && data[..8] == MarketStaking::DISCRIMINATOR,
+            TokenMillError::InvalidMarketStaking
+        );
+    }
+
+    grow_account(
+        &staking_info,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        new_len,
+    )?;
+
+    let mut staking = MarketStaking::try_deserialize(&mut &staking_info.try_borrow_data()?[..])?;
+
+    staking.badge_multiplier_bps = MAX_BPS as u16;
+    staking.try_serialize(&mut &mut staking_info.try_borrow_mut_data()?[..])?;
+
+    emit_cpi!(TokenMillMarketStakingUpgradeEvent {
+        market: staking.market,
+        staking: ctx.accounts.staking.key(),
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, DepositAction, TokenMillEnv, UpgradeMarketStakingAction,
+        },
+        TokenMillError,
+    };
+
+    use crate::{constant::MAX_BPS, state::MarketStaking};
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    fn setup_env() -> (TokenMillEnv, UpgradeMarketStakingAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+
+        testing_env.set_legacy_market_staking();
+
+        let action = UpgradeMarketStakingAction::new(&testing_env);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn upgrade_market_staking() {
+        let (mut testing_env, action) = setup_env();
+
+        // The baseline layout doesn't deserialize, so the staking can't be used until it's upgraded
+        let result = testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, 0)]);
+
+        assert!(result.is_err());
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&action.staking);
+
+        assert_eq!(staking.market, testing_env.market);
+        assert_eq!(staking.amount_staked, STAKE_AMOUNT);
+        assert_eq!(staking.total_lock_bonus, 0);
+        assert_eq!(staking.min_stake_duration, 0);
+        assert_eq!(staking.badge_multiplier_bps, MAX_BPS as u16);
+    }
+
+    #[test]
+    fn upgrade_market_staking_twice() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidMarketStaking
+        );
+    }
+}
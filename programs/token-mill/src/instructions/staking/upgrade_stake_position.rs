@@ -0,0 +1,166 @@
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillStakePositionUpgradeEvent,
+    instructions::upgrade_config::grow_account,
+    state::{Market, StakePosition},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpgradeStakePosition<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: Stake position of the first deployment, checked by the handler as it doesn't deserialize yet
+    #[account(mut, owner = crate::ID @ TokenMillError::InvalidStakePosition)]
+    pub stake_position: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a stake position created by the first deployment to the current layout, the payer topping up its rent.
+/// The fields added since then are appended, so the zeroed bytes deserialize to their default values: an unlocked
+/// position without badge. Its vault reward and penalty indexes start at 0 like the ones of the upgraded market
+/// staking, whose shares always included the stake of the position. Its stake, vested amount and pending rewards
+/// become escrowed by the market, which has to be upgraded first. Permissionless, the position keeping its owner
+pub fn handler(ctx: Context<UpgradeStakePosition>) -> Result<()> {
+    let stake_position_info = ctx.accounts.stake_position.to_account_info();
+    let new_len = 8 + StakePosition::INIT_SPACE;
+
+    {
+        let data = stake_position_info.try_borrow_data()?;
+
+        require!(
+            data.len() >= 8 && data.len() < new_len && data[..8] == StakePosition::DISCRIMINATOR,
+            TokenMillError::InvalidStakePosition
+        );
+    }
+
+    grow_account(
+        &stake_position_info,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        new_len,
+    )?;
+
+    let stake_position =
+        StakePosition::try_deserialize(&mut &stake_position_info.try_borrow_data()?[..])?;
+
+    require_keys_eq!(
+        stake_position.market,
+        ctx.accounts.market.key(),
+        TokenMillError::InvalidMarket
+    );
+
+    {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        market.escrow_base(stake_position.amount_staked + stake_position.total_amount_vested)?;
+        market.escrow_quote(stake_position.pending_rewards)?;
+    }
+
+    emit_cpi!(TokenMillStakePositionUpgradeEvent {
+        market: stake_position.market,
+        user: stake_position.user,
+        stake_position: ctx.accounts.stake_position.key(),
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, DepositAction, TokenMillEnv, UpgradeMarketStakingAction,
+            UpgradeStakePositionAction, WithdrawAction,
+        },
+        make_address, TokenMillError,
+    };
+
+    use crate::state::{Market, StakePosition};
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    fn setup_env() -> (TokenMillEnv, UpgradeStakePositionAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+
+        testing_env.set_legacy_market_staking();
+        testing_env.set_legacy_stake_position(&make_address("bob"));
+
+        testing_env
+            .svm
+            .execute_actions(&[&UpgradeMarketStakingAction::new(&testing_env)])
+            .unwrap();
+
+        // Anyone can pay for the upgrade
+        testing_env.svm.change_payer("carol");
+
+        let action = UpgradeStakePositionAction::new(&testing_env, &make_address("bob"));
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn upgrade_stake_position() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        assert_eq!(stake_position.market, testing_env.market);
+        assert_eq!(stake_position.user, make_address("bob"));
+        assert_eq!(stake_position.amount_staked, STAKE_AMOUNT);
+        assert_eq!(stake_position.lock_tier, 0);
+        assert_eq!(stake_position.lock_end, 0);
+        assert_eq!(stake_position.lock_bonus, 0);
+        assert_eq!(stake_position.badge_mint, Default::default());
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.escrowed_base, STAKE_AMOUNT);
+
+        // The upgraded position is usable again
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&WithdrawAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+
+        let market = testing_env
+            .svm
+            .get_parsed_account::<Market>(&testing_env.market);
+
+        assert_eq!(market.escrowed_base, 0);
+    }
+
+    #[test]
+    fn upgrade_stake_position_twice() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidStakePosition
+        );
+    }
+}
@@ -18,10 +18,28 @@ pub fn handler(ctx: Context<StakeUpdate>, amount: u64) -> Result<()> {
         TokenMillError::InsufficientStakeAmount
     );
 
+    require_gte!(
+        stake_position.unlocked_amount(),
+        amount,
+        TokenMillError::StakeLocked
+    );
+
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(
+        !stake_position.is_lockup_active(current_time),
+        TokenMillError::StakeLocked
+    );
+
+    require!(
+        stake_position.min_stake_end <= current_time,
+        TokenMillError::MinStakeDurationNotReached
+    );
+
     let market_bump = {
         let market = &mut ctx.accounts.market.load_mut()?;
 
-        staking_manager::withdraw(market, staking, stake_position, amount)?;
+        staking_manager::withdraw(market, staking, stake_position, amount, current_time)?;
 
         market.bump
     };
@@ -55,13 +73,17 @@ pub fn handler(ctx: Context<StakeUpdate>, amount: u64) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use joelana_test_utils::joelana_env::actions::token_mill::{
-        DepositAction, TokenMillEnv, WithdrawAction,
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            ClaimStakingRewardsAction, DepositAction, SwapAction, TokenMillEnv, WithdrawAction,
+        },
+        make_address, SwapAmountType, SwapType,
     };
 
     use crate::{MarketStaking, StakePosition};
 
     const STAKE_AMOUNT: u64 = 100_000_000;
+    const SWAP_AMOUNT: u64 = 100_000_000_000;
 
     #[test]
     fn withdraw() {
@@ -89,4 +111,75 @@ mod tests {
             .get_parsed_account::<StakePosition>(&deposit_action.stake_position);
         assert_eq!(stake_position.amount_staked, STAKE_AMOUNT / 2);
     }
+
+    #[test]
+    fn withdraw_partially() {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        let carol = testing_env.svm.change_payer("carol");
+
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            SWAP_AMOUNT,
+            u64::MAX,
+            None,
+        );
+        swap_action.with_user(carol);
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&DepositAction::new(&testing_env, STAKE_AMOUNT)])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+        testing_env.svm.change_payer("bob");
+
+        let withdraw_action = WithdrawAction::new(&testing_env, STAKE_AMOUNT / 2);
+
+        let result = testing_env.svm.execute_actions(&[&withdraw_action]);
+
+        assert!(result.is_ok());
+
+        // The rewards of the whole stake are settled at the withdrawal
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&withdraw_action.market_staking);
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&withdraw_action.stake_position);
+
+        assert_eq!(stake_position.amount_staked, STAKE_AMOUNT / 2);
+        assert_eq!(
+            stake_position.acc_reward_amount_per_share,
+            staking.acc_reward_amount_per_share
+        );
+        assert!(stake_position.pending_rewards > 0);
+
+        // The remaining stake keeps accruing the staking fees
+        testing_env.svm.change_payer("carol");
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+        testing_env.svm.change_payer("bob");
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("bob"));
+
+        testing_env
+            .svm
+            .execute_actions(&[&ClaimStakingRewardsAction::new(&testing_env)])
+            .unwrap();
+
+        let rewards = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("bob"))
+            - balance_before;
+
+        assert!(rewards > stake_position.pending_rewards);
+    }
 }
@@ -0,0 +1,145 @@
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillCompoundingVaultWithdrawalEvent,
+    manager::{staking_manager, token_manager::transfer_from_pda},
+    MARKET_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+
+use super::CompoundingVaultUpdate;
+
+/// Burns `shares` of the compounding position and withdraws the stake they own
+pub fn handler(ctx: Context<CompoundingVaultUpdate>, shares: u64) -> Result<()> {
+    require!(shares > 0, TokenMillError::InvalidAmount);
+
+    let compounding_vault = &mut ctx.accounts.compounding_vault;
+    let vault_stake_position = &mut ctx.accounts.vault_stake_position;
+    let compounding_position = &mut ctx.accounts.compounding_position;
+
+    require_gte!(
+        compounding_position.shares,
+        shares,
+        TokenMillError::InsufficientStakeAmount
+    );
+
+    let (amount, market_bump) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+        let staking = &mut ctx.accounts.staking;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            compounding_position.min_stake_end <= current_time,
+            TokenMillError::MinStakeDurationNotReached
+        );
+
+        // Restakes the pending penalties before pricing the shares
+        staking_manager::deposit(market, staking, vault_stake_position, 0, current_time)?;
+
+        let amount = compounding_vault.get_amount(shares, vault_stake_position.amount_staked)?;
+
+        staking_manager::withdraw(market, staking, vault_stake_position, amount, current_time)?;
+
+        (amount, market.bump)
+    };
+
+    compounding_vault.total_shares -= shares;
+    compounding_position.shares -= shares;
+
+    let base_token_mint = &ctx.accounts.base_token_mint;
+    let base_token_mint_key = base_token_mint.key();
+    let seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint_key.as_ref(),
+        &[market_bump],
+    ];
+
+    transfer_from_pda(
+        base_token_mint,
+        ctx.accounts.market.to_account_info(),
+        &ctx.accounts.market_base_token_ata,
+        &ctx.accounts.user_base_token_ata,
+        &ctx.accounts.base_token_program,
+        amount,
+        &seeds,
+    )?;
+
+    emit_cpi!(TokenMillCompoundingVaultWithdrawalEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        amount,
+        shares,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateCompoundingVaultAction, DepositToCompoundingVaultAction,
+            TokenMillEnv, WithdrawFromCompoundingVaultAction,
+        },
+        make_address,
+    };
+
+    use crate::{errors::TokenMillError, CompoundingPosition};
+
+    const STAKE_AMOUNT: u64 = 100_000_000;
+
+    fn setup_env() -> (TokenMillEnv, WithdrawFromCompoundingVaultAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(STAKE_AMOUNT);
+
+        testing_env
+            .svm
+            .execute_actions(&[
+                &CreateCompoundingVaultAction::new(&testing_env),
+                &DepositToCompoundingVaultAction::new(&testing_env, STAKE_AMOUNT),
+            ])
+            .unwrap();
+
+        let action = WithdrawFromCompoundingVaultAction::new(&testing_env, STAKE_AMOUNT / 2);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn withdraw_from_compounding_vault() {
+        let (mut testing_env, action) = setup_env();
+
+        let balance_before = testing_env
+            .svm
+            .get_balance(&action.base_token_mint, &make_address("bob"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let balance_after = testing_env
+            .svm
+            .get_balance(&action.base_token_mint, &make_address("bob"));
+
+        assert_eq!(balance_after - balance_before, STAKE_AMOUNT / 2);
+
+        let compounding_position = testing_env
+            .svm
+            .get_parsed_account::<CompoundingPosition>(&action.compounding_position);
+
+        assert_eq!(compounding_position.shares, STAKE_AMOUNT / 2);
+    }
+
+    #[test]
+    fn withdraw_from_compounding_vault_with_insufficient_shares() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.shares = STAKE_AMOUNT + 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InsufficientStakeAmount);
+    }
+}
@@ -1,20 +1,35 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::spl_token::native_mint,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
 
+#[cfg(feature = "staking")]
+use crate::manager::staking_manager;
 use crate::{
     errors::TokenMillError,
-    events::TokenMillSwapEvent,
+    events::{
+        TokenMillFeeDistributionEvent, TokenMillIncentiveEvent, TokenMillProtocolFeeRebateEvent,
+        TokenMillStakerDiscountEvent, TokenMillSwapEvent, TokenMillSwapPartialFillEvent,
+    },
     manager::{
-        swap_manager::{self, SwapAmountType, SwapType},
-        token_manager::{transfer_from_eoa, transfer_from_pda},
+        oracle_manager, price_feed_manager,
+        swap_manager::{self, SwapAmountType, SwapFillType, SwapOptions, SwapType},
+        token_manager::{transfer_from_eoa, transfer_from_pda, unwrap_sol, wrap_sol},
+    },
+    state::{
+        Market, MarketStaking, ProtocolStats, ReferralCode, StakePosition, TraderProfile,
+        TraderStats,
     },
-    state::Market,
-    TokenMillConfig, MARKET_PDA_SEED,
+    TokenMillConfig, MARKET_ORACLE_PDA_SEED, MARKET_PDA_SEED, PROTOCOL_STATS_PDA_SEED,
+    RESTRICTED_WALLET_PDA_SEED,
 };
 
 #[event_cpi]
 #[derive(Accounts)]
 pub struct Swap<'info> {
+    #[account(constraint = !config.swaps_paused @ TokenMillError::SwapsPaused)]
     pub config: Account<'info, TokenMillConfig>,
 
     #[account(
@@ -53,13 +68,15 @@ pub struct Swap<'info> {
     )]
     pub user_base_token_ata: InterfaceAccount<'info, TokenAccount>,
 
+    // Created on the fly, so that swaps of markets quoted in wSOL can be made with native SOL
     #[account(
-        mut,
-        associated_token::mint = market.load()?.quote_token_mint,
+        init_if_needed,
+        payer = user,
+        associated_token::mint = quote_token_mint,
         associated_token::authority = user,
         associated_token::token_program = quote_token_program
     )]
-    pub user_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+    pub user_quote_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
@@ -74,11 +91,91 @@ pub struct Swap<'info> {
     #[account(mut)]
     pub referral_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
+    // Lets integrators refer by code, the referral token account then has to be owned by the referral account of the code
+    #[account(has_one = config @ TokenMillError::InvalidConfigAccount)]
+    pub referral_code: Option<Box<Account<'info, ReferralCode>>>,
+
+    // Interface fees are charged in quote token on top of the swap, or taken from the input of exact input buys, and
+    // capped by the config
+    #[account(
+        mut,
+        token::mint = quote_token_mint,
+        token::token_program = quote_token_program
+    )]
+    pub interface_fee_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = user @ TokenMillError::InvalidAuthority
+    )]
+    pub trader_profile: Option<Box<Account<'info, TraderProfile>>>,
+
+    // Destinations of the pending fees, only used on the swaps triggering an automatic fee distribution
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market.load()?.creator,
+        associated_token::token_program = quote_token_program
+    )]
+    pub creator_quote_token_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Option<Box<Account<'info, MarketStaking>>>,
+
+    #[account(
+        mut,
+        seeds = [
+            PROTOCOL_STATS_PDA_SEED.as_bytes(),
+            config.key().as_ref(),
+            quote_token_mint.key().as_ref(),
+        ],
+        bump = protocol_stats.bump,
+    )]
+    pub protocol_stats: Option<Box<Account<'info, ProtocolStats>>>,
+
+    // Tracks the 30-day volume of the user, which unlocks the protocol fee rebates of the config
+    #[account(
+        mut,
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = user @ TokenMillError::InvalidAuthority
+    )]
+    pub trader_stats: Option<Box<Account<'info, TraderStats>>>,
+
+    // Stake position of the user on the protocol token market, which unlocks the staker discounts of the config
+    #[account(has_one = user @ TokenMillError::InvalidAuthority)]
+    pub stake_position: Option<Box<Account<'info, StakePosition>>>,
+
+    /// CHECK: Pyth price update of the quote token, required if the market registered one. Checked in the handler
+    pub quote_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: TWAP oracle of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [MARKET_ORACLE_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub market_oracle: UncheckedAccount<'info>,
+
+    /// CHECK: Compliance hold of the user on this market, must not exist
+    #[account(
+        seeds = [RESTRICTED_WALLET_PDA_SEED.as_bytes(), market.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = restricted_wallet.data_is_empty() @ TokenMillError::WalletRestricted
+    )]
+    pub restricted_wallet: UncheckedAccount<'info>,
+
+    #[account(mut)]
     pub user: Signer<'info>,
 
     pub base_token_program: Interface<'info, TokenInterface>,
 
     pub quote_token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 pub fn handler(
@@ -87,39 +184,191 @@ pub fn handler(
     swap_amount_type: SwapAmountType,
     amount: u64,
     other_amount_threshold: u64,
+    options: SwapOptions,
 ) -> Result<(u64, u64)> {
+    let SwapOptions {
+        interface_fee_bps,
+        deadline,
+        fill_type,
+        max_price_impact_bps,
+    } = options;
+
     if amount == 0 {
         return Err(TokenMillError::InvalidAmount.into());
     }
 
+    if let Some(deadline) = deadline {
+        require!(
+            !deadline.is_expired(&Clock::get()?),
+            TokenMillError::SwapDeadlineExceeded
+        );
+    }
+
+    require!(
+        interface_fee_bps <= ctx.accounts.config.max_interface_fee_bps,
+        TokenMillError::InterfaceFeeTooHigh
+    );
+
     let referral_token_account = &ctx.accounts.referral_token_account;
 
+    if let Some(referral_code) = &ctx.accounts.referral_code {
+        require!(
+            referral_token_account
+                .as_ref()
+                .is_some_and(|account| account.owner == referral_code.referral_account),
+            TokenMillError::InvalidReferralTokenAccount
+        );
+    }
+    let interface_fee_token_account = &ctx.accounts.interface_fee_token_account;
+    let charged_interface_fee_bps = interface_fee_token_account
+        .as_ref()
+        .map(|_| interface_fee_bps);
+
+    let curve_amount = swap_manager::get_amount_without_interface_fee(
+        swap_type,
+        swap_amount_type,
+        amount,
+        charged_interface_fee_bps,
+    )?;
+
     let base_amount;
-    let quote_amount;
+    let mut quote_amount;
     let market_bump;
-    let swap_fee;
+    let mut swap_fee;
     let creator_fee;
     let staking_fee;
-    let protocol_fee;
+    let mut protocol_fee;
     let referral_fee;
+    let filled_amount;
+    let incentive_weight;
+    let circulating_supply_before;
+    let circulating_supply_after;
+    let ask_price;
+    let bid_price;
 
     {
-        let config = &ctx.accounts.config;
         let market = &mut ctx.accounts.market.load_mut()?;
 
-        (base_amount, quote_amount, swap_fee) =
-            swap_manager::swap(market, swap_type, swap_amount_type, amount)?;
+        if swap_type == SwapType::Sell && market.are_sells_paused(Clock::get()?.unix_timestamp) {
+            return Err(TokenMillError::SellsPaused.into());
+        }
+
+        price_feed_manager::check_quote_peg(market, ctx.accounts.quote_price_feed.as_deref())?;
+
+        circulating_supply_before = market.circulating_supply();
+
+        (base_amount, quote_amount, swap_fee) = swap_manager::swap(
+            market,
+            swap_type,
+            swap_amount_type,
+            curve_amount,
+            &Clock::get()?,
+        )?;
+
+        circulating_supply_after = market.circulating_supply();
+        (ask_price, bid_price) = market.get_spot_prices()?;
+
+        oracle_manager::record_prices(&ctx.accounts.market_oracle, market)?;
 
-        (creator_fee, staking_fee, protocol_fee, referral_fee) = market.fees.distribute_fee(
+        // The specified amount is only partially filled once the curve or the available supply is exhausted
+        filled_amount = match (swap_type, swap_amount_type) {
+            (SwapType::Buy, SwapAmountType::ExactInput)
+            | (SwapType::Sell, SwapAmountType::ExactOutput) => quote_amount,
+            (SwapType::Buy, SwapAmountType::ExactOutput)
+            | (SwapType::Sell, SwapAmountType::ExactInput) => base_amount,
+        };
+
+        require!(
+            fill_type == SwapFillType::ImmediateOrCancel || filled_amount == curve_amount,
+            TokenMillError::SwapNotFullyFilled
+        );
+
+        if let Some(max_price_impact_bps) = max_price_impact_bps {
+            let price_impact_bps = swap_manager::get_price_impact_bps(
+                market,
+                swap_type,
+                circulating_supply_before,
+                base_amount,
+                quote_amount,
+            )?;
+
+            require!(
+                price_impact_bps <= u64::from(max_price_impact_bps),
+                TokenMillError::PriceImpactTooHigh
+            );
+        }
+
+        (quote_amount, swap_fee) = swap_manager::apply_fee_exemption(
+            &ctx.accounts.config,
+            &ctx.accounts.user.key(),
+            swap_type,
+            quote_amount,
             swap_fee,
-            referral_token_account
-                .as_ref()
-                .map(|_| config.referral_fee_share),
         )?;
 
+        #[cfg(feature = "referrals")]
+        let referral_fee_share = referral_token_account
+            .as_ref()
+            .map(|_| market.get_referral_fee_share(&ctx.accounts.config));
+        // Without referrals, the whole protocol fee goes to the protocol fee recipient
+        #[cfg(not(feature = "referrals"))]
+        let referral_fee_share = None;
+
+        (creator_fee, staking_fee, protocol_fee, referral_fee) =
+            market.fees.distribute_fee(swap_fee, referral_fee_share)?;
+
+        incentive_weight = market.get_incentive_weight(swap_type.into(), quote_amount)?;
+
         market_bump = market.bump;
     };
 
+    let interface_fee = swap_manager::get_swap_interface_fee(
+        swap_type,
+        swap_amount_type,
+        amount,
+        quote_amount,
+        charged_interface_fee_bps,
+    )?;
+
+    if let Some(protocol_stats) = &mut ctx.accounts.protocol_stats {
+        protocol_stats.record_swap(quote_amount, swap_fee);
+    }
+
+    // The rebate tier is given by the volume before the swap
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let refunds = swap_manager::get_protocol_fee_refunds(
+        &ctx.accounts.config,
+        &ctx.accounts.quote_token_mint.key(),
+        quote_amount,
+        protocol_fee,
+        ctx.accounts
+            .trader_stats
+            .as_ref()
+            .map(|trader_stats| trader_stats.get_volume(current_time)),
+        ctx.accounts
+            .stake_position
+            .as_ref()
+            .map(|stake_position| (stake_position.market, stake_position.amount_staked)),
+    )?;
+
+    protocol_fee -= refunds.total();
+
+    if let Some(trader_stats) = &mut ctx.accounts.trader_stats {
+        trader_stats.record_swap(current_time, quote_amount);
+    }
+
+    // The cost basis follows the quote amount the user actually pays or receives, the rebate and the staker discount
+    // being sent back to the user
+    if let Some(trader_profile) = &mut ctx.accounts.trader_profile {
+        let user_quote_amount = match swap_type {
+            SwapType::Buy => quote_amount + interface_fee - refunds.total(),
+            SwapType::Sell => quote_amount - interface_fee + refunds.total(),
+        };
+
+        trader_profile.record_swap(swap_type, base_amount, user_quote_amount)?;
+    }
+
     let user = &ctx.accounts.user;
     let base_token_mint_key = ctx.accounts.base_token_mint.key();
     let seeds = [
@@ -145,7 +394,7 @@ pub fn handler(
             base_amount,
             &ctx.accounts.quote_token_mint,
             &ctx.accounts.base_token_mint,
-            &ctx.accounts.user_quote_token_ata,
+            ctx.accounts.user_quote_token_ata.as_ref(),
             &ctx.accounts.user_base_token_ata,
             &ctx.accounts.market_quote_token_ata,
             &ctx.accounts.market_base_token_ata,
@@ -158,7 +407,7 @@ pub fn handler(
             &ctx.accounts.base_token_mint,
             &ctx.accounts.quote_token_mint,
             &ctx.accounts.user_base_token_ata,
-            &ctx.accounts.user_quote_token_ata,
+            ctx.accounts.user_quote_token_ata.as_ref(),
             &ctx.accounts.market_base_token_ata,
             &ctx.accounts.market_quote_token_ata,
             &ctx.accounts.base_token_program,
@@ -166,19 +415,40 @@ pub fn handler(
         ),
     };
 
+    // The interface fee is paid by the user on the quote side, so thresholds apply to the amounts net of it
+    let (user_amount_in, user_amount_out) = match swap_type {
+        SwapType::Buy => (amount_in + interface_fee, amount_out),
+        SwapType::Sell => (amount_in, amount_out - interface_fee),
+    };
+
     match swap_amount_type {
         SwapAmountType::ExactInput => {
-            if amount_out < other_amount_threshold {
+            if user_amount_out < other_amount_threshold {
                 return Err(TokenMillError::AmountThresholdNotMet.into());
             }
         }
         SwapAmountType::ExactOutput => {
-            if amount_in > other_amount_threshold {
+            if user_amount_in > other_amount_threshold {
                 return Err(TokenMillError::AmountThresholdNotMet.into());
             }
         }
     }
 
+    // Markets quoted in wSOL can be traded with native SOL: the missing wSOL is wrapped from the user's lamports,
+    // and a wSOL account that was empty before the swap is closed after it, unwrapping what it holds
+    let is_native_quote = ctx.accounts.quote_token_mint.key() == native_mint::ID;
+    let user_quote_balance = ctx.accounts.user_quote_token_ata.amount;
+
+    if is_native_quote && swap_type == SwapType::Buy && user_amount_in > user_quote_balance {
+        wrap_sol(
+            user,
+            &ctx.accounts.user_quote_token_ata,
+            &ctx.accounts.quote_token_program,
+            &ctx.accounts.system_program,
+            user_amount_in - user_quote_balance,
+        )?;
+    }
+
     transfer_from_eoa(
         mint_in,
         user,
@@ -194,10 +464,34 @@ pub fn handler(
         market_account_out,
         user_account_out,
         token_program_out,
-        amount_out,
+        user_amount_out,
         &seeds,
     )?;
 
+    if let Some(interface_fee_token_account) = interface_fee_token_account {
+        if interface_fee > 0 {
+            match swap_type {
+                SwapType::Buy => transfer_from_eoa(
+                    &ctx.accounts.quote_token_mint,
+                    user,
+                    &ctx.accounts.user_quote_token_ata,
+                    interface_fee_token_account,
+                    &ctx.accounts.quote_token_program,
+                    interface_fee,
+                )?,
+                SwapType::Sell => transfer_from_pda(
+                    &ctx.accounts.quote_token_mint,
+                    ctx.accounts.market.to_account_info(),
+                    &ctx.accounts.market_quote_token_ata,
+                    interface_fee_token_account,
+                    &ctx.accounts.quote_token_program,
+                    interface_fee,
+                    &seeds,
+                )?,
+            }
+        }
+    }
+
     if protocol_fee > 0 {
         transfer_from_pda(
             &ctx.accounts.quote_token_mint,
@@ -210,6 +504,30 @@ pub fn handler(
         )?;
     }
 
+    if let Some((_, _, rebate_amount)) = refunds.rebate {
+        transfer_from_pda(
+            &ctx.accounts.quote_token_mint,
+            ctx.accounts.market.to_account_info(),
+            &ctx.accounts.market_quote_token_ata,
+            &ctx.accounts.user_quote_token_ata,
+            &ctx.accounts.quote_token_program,
+            rebate_amount,
+            &seeds,
+        )?;
+    }
+
+    if let Some((_, _, discount_amount)) = refunds.staker_discount {
+        transfer_from_pda(
+            &ctx.accounts.quote_token_mint,
+            ctx.accounts.market.to_account_info(),
+            &ctx.accounts.market_quote_token_ata,
+            &ctx.accounts.user_quote_token_ata,
+            &ctx.accounts.quote_token_program,
+            discount_amount,
+            &seeds,
+        )?;
+    }
+
     if let Some(referral_token_account) = referral_token_account {
         if referral_fee > 0 {
             transfer_from_pda(
@@ -235,17 +553,131 @@ pub fn handler(
         staking_fee,
         protocol_fee,
         referral_fee,
+        interface_fee_recipient: interface_fee_token_account.as_ref().map(|a| a.owner),
+        interface_fee,
+        circulating_supply_before,
+        circulating_supply_after,
+        ask_price,
+        bid_price,
     });
 
+    if let Some((volume, rebate_bps, rebate)) = refunds.rebate {
+        emit_cpi!(TokenMillProtocolFeeRebateEvent {
+            user: ctx.accounts.user.key(),
+            market: ctx.accounts.market.key(),
+            volume,
+            rebate_bps,
+            rebate,
+        });
+    }
+
+    if let Some((amount_staked, discount_bps, discount)) = refunds.staker_discount {
+        emit_cpi!(TokenMillStakerDiscountEvent {
+            user: ctx.accounts.user.key(),
+            market: ctx.accounts.market.key(),
+            amount_staked,
+            discount_bps,
+            discount,
+        });
+    }
+
+    if let Some(weight) = incentive_weight {
+        emit_cpi!(TokenMillIncentiveEvent {
+            wallet: ctx.accounts.user.key(),
+            market: ctx.accounts.market.key(),
+            action: swap_type.into(),
+            amount: quote_amount,
+            weight,
+        });
+    }
+
+    if filled_amount < curve_amount {
+        emit_cpi!(TokenMillSwapPartialFillEvent {
+            user: ctx.accounts.user.key(),
+            market: ctx.accounts.market.key(),
+            swap_type,
+            swap_amount_type,
+            filled_amount,
+            unfilled_amount: curve_amount - filled_amount,
+        });
+    }
+
+    let fee_distribution_interval = u64::from(ctx.accounts.config.fee_distribution_interval);
+
+    let (creator_fees_distributed, staking_fees_distributed) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        if fee_distribution_interval != 0 && market.swap_count % fee_distribution_interval == 0 {
+            #[cfg(feature = "staking")]
+            let staking_fees_distributed = match &mut ctx.accounts.staking {
+                Some(staking) => staking_manager::distribute_staking_fees(market, staking)?,
+                None => 0,
+            };
+            #[cfg(not(feature = "staking"))]
+            let staking_fees_distributed = 0;
+
+            // Split creator fees are only claimed to their recipients with `claim_creator_fees`
+            let creator_fees_distributed = if ctx.accounts.creator_quote_token_ata.is_some()
+                && market.creator_fee_split == 0
+            {
+                let pending_creator_fees = market.fees.pending_creator_fees;
+                market.fees.pending_creator_fees = 0;
+
+                pending_creator_fees
+            } else {
+                0
+            };
+
+            (creator_fees_distributed, staking_fees_distributed)
+        } else {
+            (0, 0)
+        }
+    };
+
+    if let Some(creator_quote_token_ata) = &ctx.accounts.creator_quote_token_ata {
+        if creator_fees_distributed > 0 {
+            transfer_from_pda(
+                &ctx.accounts.quote_token_mint,
+                ctx.accounts.market.to_account_info(),
+                &ctx.accounts.market_quote_token_ata,
+                creator_quote_token_ata,
+                &ctx.accounts.quote_token_program,
+                creator_fees_distributed,
+                &seeds,
+            )?;
+        }
+    }
+
+    if creator_fees_distributed > 0 || staking_fees_distributed > 0 {
+        emit_cpi!(TokenMillFeeDistributionEvent {
+            market: ctx.accounts.market.key(),
+            creator_fees_distributed,
+            staking_fees_distributed,
+        });
+    }
+
+    if is_native_quote && user_quote_balance == 0 {
+        unwrap_sol(
+            user,
+            &ctx.accounts.user_quote_token_ata,
+            &ctx.accounts.quote_token_program,
+        )?;
+    }
+
     Ok((base_amount, quote_amount))
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{manager::swap_manager, Market};
+    use anchor_lang::AnchorDeserialize;
     use joelana_test_utils::joelana_env::{
-        actions::token_mill::{tm_parse_error, SwapAction, TokenMillEnv},
-        make_address, SwapAmountType, SwapType, TokenMillError, TokenType,
+        actions::token_mill::{
+            tm_parse_error, CreateReferralAccountAction, RegisterReferralCodeAction, SwapAction,
+            TokenMillEnv, UpdateMaxInterfaceFeeAction,
+        },
+        make_address, SwapAmountType, SwapDeadline, SwapFillType, SwapType, TokenMillError,
+        TokenType,
     };
     use rstest::rstest;
 
@@ -489,4 +921,317 @@ mod tests {
 
         testing_env.svm.execute_actions(&[&swap_action]).unwrap();
     }
+
+    #[test]
+    fn swap_with_referral_code() {
+        let (mut testing_env, _) = setup_env();
+
+        testing_env.svm.change_payer("carol");
+
+        testing_env
+            .svm
+            .execute_actions(&[&RegisterReferralCodeAction::new("carol")])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000,
+            u64::MAX,
+            Some(make_address("carol")),
+        );
+        swap_action.with_referral_code("carol");
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let referral_account = CreateReferralAccountAction::new().referral_account;
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        assert!(
+            testing_env
+                .svm
+                .get_balance(&quote_token_mint, &referral_account)
+                > 0
+        );
+
+        // The code doesn't resolve to any other token account
+        swap_action.referral_quote_token_ata = swap_action.user_quote_token_ata;
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidReferralTokenAccount
+        );
+    }
+
+    #[test]
+    fn swap_with_interface_fee() {
+        let (mut testing_env, mut swap_action) = setup_env();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&UpdateMaxInterfaceFeeAction::new(100)])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let interface_balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("carol"));
+
+        swap_action.with_interface_fee(make_address("carol"), 100);
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let interface_balance_after = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("carol"));
+
+        assert!(interface_balance_after > interface_balance_before);
+    }
+
+    #[test]
+    fn swap_exact_input_with_interface_fee() {
+        let (mut testing_env, mut swap_action) = setup_env();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&UpdateMaxInterfaceFeeAction::new(100)])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let quote_balance_before = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &swap_action.signer);
+
+        swap_action.swap_amount_type = SwapAmountType::ExactInput;
+        swap_action.amount = 1_000_000_000;
+        swap_action.other_amount_threshold = 0;
+        swap_action.with_interface_fee(make_address("carol"), 100);
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let quote_balance_after = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &swap_action.signer);
+        let interface_fee = testing_env
+            .svm
+            .get_balance(&quote_token_mint, &make_address("carol"));
+
+        // The interface fee is taken from the exact input instead of on top of it
+        assert!(interface_fee > 0);
+        assert!(quote_balance_before - quote_balance_after <= swap_action.amount);
+        assert!(quote_balance_before - quote_balance_after >= swap_action.amount - 1);
+    }
+
+    #[test]
+    fn swap_with_interface_fee_too_high() {
+        let (mut testing_env, mut swap_action) = setup_env();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&UpdateMaxInterfaceFeeAction::new(100)])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        swap_action.with_interface_fee(make_address("carol"), 101);
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::InterfaceFeeTooHigh);
+    }
+
+    #[rstest]
+    fn swap_before_deadline(#[values(true, false)] slot_deadline: bool) {
+        let (mut testing_env, mut swap_action) = setup_env();
+
+        let clock = testing_env.svm.get_clock();
+
+        swap_action.with_deadline(if slot_deadline {
+            SwapDeadline::Slot(clock.slot)
+        } else {
+            SwapDeadline::Timestamp(clock.unix_timestamp)
+        });
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        assert!(result.is_ok());
+    }
+
+    #[rstest]
+    fn swap_after_deadline(#[values(true, false)] slot_deadline: bool) {
+        let (mut testing_env, mut swap_action) = setup_env();
+
+        let clock = testing_env.svm.get_clock();
+
+        if slot_deadline {
+            swap_action.with_deadline(SwapDeadline::Slot(clock.slot));
+            testing_env.svm.warp_to_slot(clock.slot + 1);
+        } else {
+            swap_action.with_deadline(SwapDeadline::Timestamp(clock.unix_timestamp));
+            testing_env.svm.warp(1);
+        }
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::SwapDeadlineExceeded);
+    }
+
+    #[rstest]
+    fn swap_above_available_supply(
+        #[values(SwapAmountType::ExactInput, SwapAmountType::ExactOutput)]
+        swap_amount_type: SwapAmountType,
+        #[values(SwapFillType::FillOrKill, SwapFillType::ImmediateOrCancel)]
+        fill_type: SwapFillType,
+    ) {
+        let (mut testing_env, mut swap_action) = setup_env();
+
+        let quote_balance_before = testing_env
+            .svm
+            .get_balance(&swap_action.quote_token_mint, &swap_action.signer);
+
+        swap_action.swap_amount_type = swap_amount_type;
+        swap_action.fill_type = fill_type;
+        swap_action.amount = match swap_amount_type {
+            SwapAmountType::ExactInput => quote_balance_before,
+            SwapAmountType::ExactOutput => TOTAL_SUPPLY + 1,
+        };
+        swap_action.other_amount_threshold = match swap_amount_type {
+            SwapAmountType::ExactInput => 0,
+            SwapAmountType::ExactOutput => u64::MAX,
+        };
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        if fill_type == SwapFillType::ImmediateOrCancel {
+            let (base_amount, quote_amount) =
+                <(u64, u64)>::try_from_slice(&result.unwrap().return_data.data).unwrap();
+
+            // The unfilled part of the amount is cancelled, only the filled part is paid for
+            assert_eq!(base_amount, TOTAL_SUPPLY);
+            assert_eq!(
+                testing_env
+                    .svm
+                    .get_balance(&swap_action.quote_token_mint, &swap_action.signer),
+                quote_balance_before - quote_amount
+            );
+        } else {
+            let error = tm_parse_error(result).unwrap();
+
+            assert_eq!(error, TokenMillError::SwapNotFullyFilled);
+        }
+    }
+
+    #[rstest]
+    fn swap_with_max_price_impact(
+        #[values(SwapType::Buy, SwapType::Sell)] swap_type: SwapType,
+        #[values(true, false)] above_max_price_impact: bool,
+    ) {
+        let (mut testing_env, mut swap_action) = setup_env();
+
+        // The default curve is linear, so swapping 1% of the circulating supply moves the average price by 0.5%
+        swap_action.amount = 100_000_000_000;
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        swap_action.swap_type = swap_type;
+        swap_action.amount = 1_000_000_000;
+
+        if swap_type == SwapType::Sell {
+            swap_action.swap_amount_type = SwapAmountType::ExactInput;
+            swap_action.other_amount_threshold = 0;
+        }
+
+        swap_action.max_price_impact_bps = Some(if above_max_price_impact { 40 } else { 60 });
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]);
+
+        if above_max_price_impact {
+            let error = tm_parse_error(result).unwrap();
+
+            assert_eq!(error, TokenMillError::PriceImpactTooHigh);
+        } else {
+            assert!(result.is_ok());
+        }
+    }
+
+    #[test]
+    fn swap_with_native_sol() {
+        let mut testing_env = TokenMillEnv::new()
+            .with_native_quote_token_mint()
+            .with_default_market();
+        let bob = testing_env.svm.change_payer("bob");
+
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let bob_quote_token_ata = testing_env.svm.get_ata_address(&quote_token_mint, &bob);
+
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            1_000_000_000,
+            u64::MAX,
+            None,
+        );
+
+        let lamports_before = testing_env.svm.get_lamports(&bob);
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+        let (base_amount, quote_amount) =
+            <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        let lamports_after = testing_env.svm.get_lamports(&bob);
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&testing_env.base_token_mint.unwrap(), &bob),
+            base_amount
+        );
+        // Only the transaction fee is paid on top of the quote amount, the wSOL account rent is refunded
+        assert!(lamports_before - lamports_after - quote_amount < 10_000);
+        assert!(testing_env
+            .svm
+            .try_get_account(&bob_quote_token_ata)
+            .is_none());
+
+        swap_action.swap_type = SwapType::Sell;
+        swap_action.swap_amount_type = SwapAmountType::ExactInput;
+        swap_action.amount = base_amount;
+        swap_action.other_amount_threshold = 0;
+
+        let lamports_before = testing_env.svm.get_lamports(&bob);
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+        let (_, quote_amount) = <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        let lamports_after = testing_env.svm.get_lamports(&bob);
+
+        assert!(lamports_after + 10_000 - lamports_before > quote_amount);
+        assert!(lamports_after - lamports_before <= quote_amount);
+        assert!(testing_env
+            .svm
+            .try_get_account(&bob_quote_token_ata)
+            .is_none());
+    }
 }
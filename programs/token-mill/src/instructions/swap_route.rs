@@ -0,0 +1,494 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    errors::TokenMillError,
+    events::{TokenMillIncentiveEvent, TokenMillSwapEvent},
+    manager::{
+        oracle_manager, price_feed_manager,
+        swap_manager::{self, SwapAmountType, SwapType},
+        token_manager::{transfer_from_eoa, transfer_from_pda},
+    },
+    state::Market,
+    TokenMillConfig, MARKET_ORACLE_PDA_SEED, MARKET_PDA_SEED, RESTRICTED_WALLET_PDA_SEED,
+};
+
+/// Accounts shared by both legs of the route.
+/// Each market is passed in the remaining accounts as a `RouteLeg`, the sold market first.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SwapRoute<'info> {
+    #[account(constraint = !config.swaps_paused @ TokenMillError::SwapsPaused)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = quote_token_program
+    )]
+    pub user_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = config.protocol_fee_recipient,
+        associated_token::token_program = quote_token_program
+    )]
+    pub protocol_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Market accounts of one leg of a route, in the order expected in the remaining accounts
+pub struct RouteLeg<'info> {
+    pub market: AccountLoader<'info, Market>,
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+    pub market_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+    pub market_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+    pub user_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+    /// Pyth price update of the quote token if the market registered one, any account otherwise
+    pub quote_price_feed: &'info AccountInfo<'info>,
+    /// TWAP oracle of the market, only written to once it has been created
+    pub market_oracle: &'info AccountInfo<'info>,
+    /// Compliance hold of the user on the market, must not exist
+    pub restricted_wallet: &'info AccountInfo<'info>,
+}
+
+impl<'info> RouteLeg<'info> {
+    pub const ACCOUNTS_LEN: usize = 8;
+
+    pub(super) fn try_from_accounts(
+        accounts: &'info [AccountInfo<'info>],
+        route: &SwapRoute<'info>,
+    ) -> Result<Self> {
+        let [market, base_token_mint, market_base_token_ata, market_quote_token_ata, user_base_token_ata, quote_price_feed, market_oracle, restricted_wallet] =
+            accounts
+        else {
+            return Err(TokenMillError::InvalidRouteAccounts.into());
+        };
+
+        let leg = Self {
+            market: AccountLoader::try_from(market)?,
+            base_token_mint: InterfaceAccount::try_from(base_token_mint)?,
+            market_base_token_ata: InterfaceAccount::try_from(market_base_token_ata)?,
+            market_quote_token_ata: InterfaceAccount::try_from(market_quote_token_ata)?,
+            user_base_token_ata: InterfaceAccount::try_from(user_base_token_ata)?,
+            quote_price_feed,
+            market_oracle,
+            restricted_wallet,
+        };
+
+        {
+            let market = leg.market.load()?;
+
+            require_keys_eq!(
+                market.config,
+                route.config.key(),
+                TokenMillError::InvalidConfigAccount
+            );
+            require_keys_eq!(
+                market.quote_token_mint,
+                route.quote_token_mint.key(),
+                TokenMillError::InvalidQuoteTokenMint
+            );
+            require_keys_eq!(
+                market.base_token_mint,
+                leg.base_token_mint.key(),
+                TokenMillError::InvalidMintAccount
+            );
+        }
+
+        let market = leg.market.key();
+        let base_token_mint = leg.base_token_mint.key();
+
+        require_keys_eq!(
+            leg.market_base_token_ata.key(),
+            get_associated_token_address_with_program_id(
+                &market,
+                &base_token_mint,
+                &route.base_token_program.key()
+            ),
+            TokenMillError::InvalidRouteAccounts
+        );
+        require_keys_eq!(
+            leg.market_quote_token_ata.key(),
+            get_associated_token_address_with_program_id(
+                &market,
+                &route.quote_token_mint.key(),
+                &route.quote_token_program.key()
+            ),
+            TokenMillError::InvalidRouteAccounts
+        );
+        require_keys_eq!(
+            leg.user_base_token_ata.key(),
+            get_associated_token_address_with_program_id(
+                &route.user.key(),
+                &base_token_mint,
+                &route.base_token_program.key()
+            ),
+            TokenMillError::InvalidRouteAccounts
+        );
+        require_keys_eq!(
+            leg.market_oracle.key(),
+            Pubkey::find_program_address(
+                &[MARKET_ORACLE_PDA_SEED.as_bytes(), market.as_ref()],
+                &crate::ID
+            )
+            .0,
+            TokenMillError::InvalidRouteAccounts
+        );
+        require_keys_eq!(
+            leg.restricted_wallet.key(),
+            Pubkey::find_program_address(
+                &[
+                    RESTRICTED_WALLET_PDA_SEED.as_bytes(),
+                    market.as_ref(),
+                    route.user.key().as_ref()
+                ],
+                &crate::ID
+            )
+            .0,
+            TokenMillError::InvalidRouteAccounts
+        );
+        require!(
+            leg.restricted_wallet.data_is_empty(),
+            TokenMillError::WalletRestricted
+        );
+
+        Ok(leg)
+    }
+}
+
+/// Sells `amount` base tokens of the first market and buys the base token of the second market with all the quote tokens received.
+/// Both swaps are fully filled and only the final output is checked against `min_amount_out`.
+/// Referral and interface fees are not supported on routes.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+    amount: u64,
+    min_amount_out: u64,
+) -> Result<(u64, u64)> {
+    if amount == 0 {
+        return Err(TokenMillError::InvalidAmount.into());
+    }
+
+    require!(
+        ctx.remaining_accounts.len() == 2 * RouteLeg::ACCOUNTS_LEN,
+        TokenMillError::InvalidRouteAccounts
+    );
+
+    let (sell_accounts, buy_accounts) = ctx.remaining_accounts.split_at(RouteLeg::ACCOUNTS_LEN);
+    let sell_leg = RouteLeg::try_from_accounts(sell_accounts, ctx.accounts)?;
+    let buy_leg = RouteLeg::try_from_accounts(buy_accounts, ctx.accounts)?;
+
+    require_keys_neq!(
+        sell_leg.market.key(),
+        buy_leg.market.key(),
+        TokenMillError::InvalidRouteAccounts
+    );
+
+    let (base_amount_in, quote_amount) = execute_leg(&ctx, &sell_leg, SwapType::Sell, amount)?;
+    let (base_amount_out, _) = execute_leg(&ctx, &buy_leg, SwapType::Buy, quote_amount)?;
+
+    if base_amount_out < min_amount_out {
+        return Err(TokenMillError::AmountThresholdNotMet.into());
+    }
+
+    Ok((base_amount_in, base_amount_out))
+}
+
+/// Swaps `amount` of the input token of a leg (base for sells, quote for buys), settling the tokens and the protocol fee.
+/// Returns the base and quote amounts of the swap.
+pub(super) fn execute_leg<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+    leg: &RouteLeg<'info>,
+    swap_type: SwapType,
+    amount: u64,
+) -> Result<(u64, u64)> {
+    let (
+        base_amount,
+        quote_amount,
+        creator_fee,
+        staking_fee,
+        protocol_fee,
+        incentive_weight,
+        circulating_supply_before,
+        circulating_supply_after,
+        (ask_price, bid_price),
+        market_bump,
+    ) = {
+        let market = &mut leg.market.load_mut()?;
+
+        if swap_type == SwapType::Sell && market.are_sells_paused(Clock::get()?.unix_timestamp) {
+            return Err(TokenMillError::SellsPaused.into());
+        }
+
+        price_feed_manager::check_quote_peg(market, Some(leg.quote_price_feed))?;
+
+        let circulating_supply_before = market.circulating_supply();
+
+        let (base_amount, quote_amount, swap_fee) = swap_manager::swap(
+            market,
+            swap_type,
+            SwapAmountType::ExactInput,
+            amount,
+            &Clock::get()?,
+        )?;
+
+        let filled_amount = match swap_type {
+            SwapType::Buy => quote_amount,
+            SwapType::Sell => base_amount,
+        };
+
+        require!(filled_amount == amount, TokenMillError::SwapNotFullyFilled);
+
+        let (quote_amount, swap_fee) = swap_manager::apply_fee_exemption(
+            &ctx.accounts.config,
+            &ctx.accounts.user.key(),
+            swap_type,
+            quote_amount,
+            swap_fee,
+        )?;
+
+        oracle_manager::record_prices(leg.market_oracle, market)?;
+
+        let (creator_fee, staking_fee, protocol_fee, _) =
+            market.fees.distribute_fee(swap_fee, None)?;
+
+        (
+            base_amount,
+            quote_amount,
+            creator_fee,
+            staking_fee,
+            protocol_fee,
+            market.get_incentive_weight(swap_type.into(), quote_amount)?,
+            circulating_supply_before,
+            market.circulating_supply(),
+            market.get_spot_prices()?,
+            market.bump,
+        )
+    };
+
+    let base_token_mint_key = leg.base_token_mint.key();
+    let seeds = [
+        MARKET_PDA_SEED.as_bytes(),
+        base_token_mint_key.as_ref(),
+        &[market_bump],
+    ];
+
+    let accounts = &ctx.accounts;
+
+    match swap_type {
+        SwapType::Buy => {
+            transfer_from_eoa(
+                &accounts.quote_token_mint,
+                &accounts.user,
+                &accounts.user_quote_token_ata,
+                &leg.market_quote_token_ata,
+                &accounts.quote_token_program,
+                quote_amount,
+            )?;
+
+            transfer_from_pda(
+                &leg.base_token_mint,
+                leg.market.to_account_info(),
+                &leg.market_base_token_ata,
+                &leg.user_base_token_ata,
+                &accounts.base_token_program,
+                base_amount,
+                &seeds,
+            )?;
+        }
+        SwapType::Sell => {
+            transfer_from_eoa(
+                &leg.base_token_mint,
+                &accounts.user,
+                &leg.user_base_token_ata,
+                &leg.market_base_token_ata,
+                &accounts.base_token_program,
+                base_amount,
+            )?;
+
+            transfer_from_pda(
+                &accounts.quote_token_mint,
+                leg.market.to_account_info(),
+                &leg.market_quote_token_ata,
+                &accounts.user_quote_token_ata,
+                &accounts.quote_token_program,
+                quote_amount,
+                &seeds,
+            )?;
+        }
+    }
+
+    if protocol_fee > 0 {
+        transfer_from_pda(
+            &accounts.quote_token_mint,
+            leg.market.to_account_info(),
+            &leg.market_quote_token_ata,
+            &accounts.protocol_quote_token_ata,
+            &accounts.quote_token_program,
+            protocol_fee,
+            &seeds,
+        )?;
+    }
+
+    emit_cpi!(TokenMillSwapEvent {
+        user: accounts.user.key(),
+        market: leg.market.key(),
+        swap_type,
+        base_amount,
+        quote_amount,
+        referral_token_account: None,
+        creator_fee,
+        staking_fee,
+        protocol_fee,
+        referral_fee: 0,
+        interface_fee_recipient: None,
+        interface_fee: 0,
+        circulating_supply_before,
+        circulating_supply_after,
+        ask_price,
+        bid_price,
+    });
+
+    if let Some(weight) = incentive_weight {
+        emit_cpi!(TokenMillIncentiveEvent {
+            wallet: accounts.user.key(),
+            market: leg.market.key(),
+            action: swap_type.into(),
+            amount: quote_amount,
+            weight,
+        });
+    }
+
+    Ok((base_amount, quote_amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AnchorDeserialize;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, SwapAction, SwapRouteAction, TokenMillEnv, DEFAULT_TOTAL_SUPPLY,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+    use solana_sdk::pubkey::Pubkey;
+
+    const BUY_AMOUNT: u64 = 10_000_000_000;
+
+    /// Bob holds base tokens of the default market, and a second market shares its quote token
+    fn setup_env() -> (TokenMillEnv, Pubkey, Pubkey) {
+        let mut testing_env = TokenMillEnv::default();
+        let sell_base_token_mint = testing_env.base_token_mint.unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let buy_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            BUY_AMOUNT,
+            u64::MAX,
+            None,
+        );
+
+        testing_env.svm.execute_actions(&[&buy_action]).unwrap();
+
+        let buy_base_token_mint = make_address("base_token_mint_b");
+        let mut testing_env = testing_env.with_market(buy_base_token_mint, DEFAULT_TOTAL_SUPPLY);
+
+        testing_env.svm.change_payer("bob");
+
+        (testing_env, sell_base_token_mint, buy_base_token_mint)
+    }
+
+    #[test]
+    fn swap_route() {
+        let (mut testing_env, sell_base_token_mint, buy_base_token_mint) = setup_env();
+
+        let bob = make_address("bob");
+        let quote_token_mint = testing_env.quote_token_mint.unwrap();
+        let quote_balance_before = testing_env.svm.get_balance(&quote_token_mint, &bob);
+
+        let action = SwapRouteAction::new(
+            &testing_env,
+            sell_base_token_mint,
+            buy_base_token_mint,
+            BUY_AMOUNT / 2,
+            0,
+        );
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let (base_amount_in, base_amount_out) =
+            <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        assert_eq!(base_amount_in, BUY_AMOUNT / 2);
+        assert!(base_amount_out > 0);
+
+        assert_eq!(
+            testing_env.svm.get_balance(&sell_base_token_mint, &bob),
+            BUY_AMOUNT / 2
+        );
+        assert_eq!(
+            testing_env.svm.get_balance(&buy_base_token_mint, &bob),
+            base_amount_out
+        );
+        // The intermediate quote tokens are fully spent on the second market
+        assert_eq!(
+            testing_env.svm.get_balance(&quote_token_mint, &bob),
+            quote_balance_before
+        );
+    }
+
+    #[test]
+    fn swap_route_slippage() {
+        let (mut testing_env, sell_base_token_mint, buy_base_token_mint) = setup_env();
+
+        let action = SwapRouteAction::new(
+            &testing_env,
+            sell_base_token_mint,
+            buy_base_token_mint,
+            BUY_AMOUNT / 2,
+            u64::MAX,
+        );
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::AmountThresholdNotMet
+        );
+    }
+
+    #[test]
+    fn swap_route_same_market() {
+        let (mut testing_env, sell_base_token_mint, _) = setup_env();
+
+        let action = SwapRouteAction::new(
+            &testing_env,
+            sell_base_token_mint,
+            sell_base_token_mint,
+            BUY_AMOUNT / 2,
+            0,
+        );
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidRouteAccounts
+        );
+    }
+}
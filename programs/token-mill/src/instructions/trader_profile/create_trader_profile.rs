@@ -0,0 +1,67 @@
+use crate::{
+    state::{Market, TraderProfile},
+    TRADER_PROFILE_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateTraderProfile<'info> {
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + TraderProfile::INIT_SPACE,
+        seeds = [TRADER_PROFILE_PDA_SEED.as_bytes(), market.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub trader_profile: Account<'info, TraderProfile>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateTraderProfile>) -> Result<()> {
+    let trader_profile = &mut ctx.accounts.trader_profile;
+
+    trader_profile.initialize(
+        ctx.bumps.trader_profile,
+        ctx.accounts.market.key(),
+        ctx.accounts.user.key(),
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{CreateTraderProfileAction, TokenMillEnv},
+        make_address,
+    };
+
+    use crate::TraderProfile;
+
+    #[test]
+    fn create_trader_profile() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        let action = CreateTraderProfileAction::new(&testing_env);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let trader_profile = testing_env
+            .svm
+            .get_parsed_account::<TraderProfile>(&action.trader_profile);
+
+        assert_eq!(trader_profile.market, testing_env.market);
+        assert_eq!(trader_profile.user, make_address("bob"));
+        assert_eq!(trader_profile.base_amount, 0);
+    }
+}
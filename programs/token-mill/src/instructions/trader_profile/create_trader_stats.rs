@@ -0,0 +1,195 @@
+use crate::{
+    state::{TokenMillConfig, TraderStats},
+    TRADER_STATS_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct CreateTraderStats<'info> {
+    pub config: Account<'info, TokenMillConfig>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + TraderStats::INIT_SPACE,
+        seeds = [
+            TRADER_STATS_PDA_SEED.as_bytes(),
+            config.key().as_ref(),
+            quote_token_mint.key().as_ref(),
+            user.key().as_ref(),
+        ],
+        bump
+    )]
+    pub trader_stats: Account<'info, TraderStats>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateTraderStats>) -> Result<()> {
+    let trader_stats = &mut ctx.accounts.trader_stats;
+
+    trader_stats.initialize(
+        ctx.bumps.trader_stats,
+        ctx.accounts.config.key(),
+        ctx.accounts.quote_token_mint.key(),
+        ctx.accounts.user.key(),
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AnchorDeserialize;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            CreateTraderStatsAction, SetRebateScheduleAction, SwapAction, TokenMillEnv,
+        },
+        make_address, SwapAmountType, SwapType,
+    };
+
+    use crate::{state::RebateTier, TraderStats};
+
+    const BASE_AMOUNT: u64 = 10_000_000_000;
+
+    #[test]
+    fn create_trader_stats() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        let action = CreateTraderStatsAction::new(&testing_env);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let trader_stats = testing_env
+            .svm
+            .get_parsed_account::<TraderStats>(&action.trader_stats);
+
+        assert_eq!(trader_stats.user, make_address("bob"));
+        assert_eq!(
+            trader_stats.quote_token_mint,
+            testing_env.quote_token_mint.unwrap()
+        );
+    }
+
+    #[test]
+    fn swap_with_protocol_fee_rebate() {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&CreateTraderStatsAction::new(&testing_env)])
+            .unwrap();
+
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            BASE_AMOUNT,
+            u64::MAX,
+            None,
+        );
+        swap_action.with_trader_stats();
+
+        // No rebate schedule yet, the volume is only tracked
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let trader_stats = testing_env
+            .svm
+            .get_parsed_account::<TraderStats>(&swap_action.trader_stats);
+        let volume = trader_stats.get_volume(testing_env.svm.get_clock().unix_timestamp);
+
+        assert!(volume > 0);
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetRebateScheduleAction::new(
+                swap_action.quote_token_mint,
+                [
+                    RebateTier::default(),
+                    RebateTier {
+                        min_volume: volume,
+                        rebate_bps: 10,
+                    },
+                    RebateTier::default(),
+                ],
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        let protocol_balance_before = testing_env
+            .svm
+            .get_balance(&swap_action.quote_token_mint, &make_address("dave"));
+
+        let mut untracked_swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            BASE_AMOUNT,
+            u64::MAX,
+            None,
+        );
+
+        testing_env
+            .svm
+            .execute_actions(&[&untracked_swap_action])
+            .unwrap();
+
+        let protocol_fee = testing_env
+            .svm
+            .get_balance(&swap_action.quote_token_mint, &make_address("dave"))
+            - protocol_balance_before;
+
+        // Sells the tokens back, so that the next buy is priced like the previous one
+        untracked_swap_action.swap_type = SwapType::Sell;
+        untracked_swap_action.swap_amount_type = SwapAmountType::ExactInput;
+        untracked_swap_action.other_amount_threshold = 0;
+
+        testing_env
+            .svm
+            .execute_actions(&[&untracked_swap_action])
+            .unwrap();
+
+        let protocol_balance_before = testing_env
+            .svm
+            .get_balance(&swap_action.quote_token_mint, &make_address("dave"));
+        let quote_balance_before = testing_env
+            .svm
+            .get_balance(&swap_action.quote_token_mint, &swap_action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let (_, quote_amount) = <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+        let rebate = (quote_amount * 10 / 10_000).min(protocol_fee);
+
+        assert!(rebate > 0);
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&swap_action.quote_token_mint, &make_address("dave"))
+                - protocol_balance_before,
+            protocol_fee - rebate
+        );
+        assert_eq!(
+            quote_balance_before
+                - testing_env
+                    .svm
+                    .get_balance(&swap_action.quote_token_mint, &swap_action.signer),
+            quote_amount - rebate
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{
+    errors::TokenMillError,
+    manager::swap_manager::SwapAmountType,
+    state::{Market, TraderPnl, TraderProfile},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct GetTraderPnl<'info> {
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(has_one = market @ TokenMillError::InvalidMarket)]
+    pub trader_profile: Account<'info, TraderProfile>,
+}
+
+/// View instruction, the unrealized PnL is computed against the amount the position would get by selling into the curve
+pub fn handler(ctx: Context<GetTraderPnl>) -> Result<TraderPnl> {
+    let market = ctx.accounts.market.load()?;
+    let trader_profile = &ctx.accounts.trader_profile;
+
+    let base_amount = trader_profile.base_amount.min(market.circulating_supply());
+
+    let current_value = if base_amount > 0 {
+        let (_, quote_amount) = market.get_quote_amount(base_amount, SwapAmountType::ExactInput)?;
+
+        quote_amount
+    } else {
+        0
+    };
+
+    trader_profile.pnl(current_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AnchorDeserialize;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            CreateTraderProfileAction, CreateTraderStatsAction, GetTraderPnlAction,
+            SetRebateScheduleAction, SwapAction, TokenMillEnv,
+        },
+        SwapAmountType, SwapType,
+    };
+
+    use crate::{state::RebateTier, TraderPnl, TraderProfile};
+
+    const BUY_AMOUNT: u64 = 1_000_000_000;
+
+    fn setup_env() -> (TokenMillEnv, SwapAction) {
+        let mut testing_env = TokenMillEnv::default();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&CreateTraderProfileAction::new(&testing_env)])
+            .unwrap();
+
+        let mut swap_action = SwapAction::new(
+            &testing_env,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            BUY_AMOUNT,
+            u64::MAX,
+            None,
+        );
+        swap_action.with_trader_profile();
+
+        (testing_env, swap_action)
+    }
+
+    #[test]
+    fn track_cost_basis() {
+        let (mut testing_env, mut swap_action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let trader_profile = testing_env
+            .svm
+            .get_parsed_account::<TraderProfile>(&swap_action.trader_profile);
+
+        assert_eq!(trader_profile.base_amount, BUY_AMOUNT);
+        assert!(trader_profile.cost_basis > 0);
+
+        swap_action.swap_type = SwapType::Sell;
+        swap_action.swap_amount_type = SwapAmountType::ExactInput;
+        swap_action.amount = BUY_AMOUNT / 2;
+        swap_action.other_amount_threshold = 0;
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let trader_profile_after = testing_env
+            .svm
+            .get_parsed_account::<TraderProfile>(&swap_action.trader_profile);
+
+        assert_eq!(trader_profile_after.base_amount, BUY_AMOUNT / 2);
+        assert_eq!(
+            trader_profile_after.cost_basis,
+            trader_profile.cost_basis - trader_profile.cost_basis / 2
+        );
+        // Selling into the bid curve right after buying from the ask curve always realizes a loss
+        assert!(trader_profile_after.realized_pnl < 0);
+    }
+
+    #[test]
+    fn track_cost_basis_net_of_rebate() {
+        let (mut testing_env, mut swap_action) = setup_env();
+
+        testing_env
+            .svm
+            .execute_actions(&[&CreateTraderStatsAction::new(&testing_env)])
+            .unwrap();
+
+        testing_env.svm.change_payer("admin");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SetRebateScheduleAction::new(
+                swap_action.quote_token_mint,
+                [
+                    RebateTier {
+                        min_volume: 0,
+                        rebate_bps: 10,
+                    },
+                    RebateTier::default(),
+                    RebateTier::default(),
+                ],
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("bob");
+
+        swap_action.with_trader_stats();
+
+        let quote_balance_before = testing_env
+            .svm
+            .get_balance(&swap_action.quote_token_mint, &swap_action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let (_, quote_amount) = <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+        let quote_paid = quote_balance_before
+            - testing_env
+                .svm
+                .get_balance(&swap_action.quote_token_mint, &swap_action.signer);
+
+        let trader_profile = testing_env
+            .svm
+            .get_parsed_account::<TraderProfile>(&swap_action.trader_profile);
+
+        // The rebate sent back to the trader lowers the cost basis
+        assert!(quote_paid < quote_amount);
+        assert_eq!(trader_profile.cost_basis, quote_paid);
+    }
+
+    #[test]
+    fn get_trader_pnl() {
+        let (mut testing_env, swap_action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&swap_action]).unwrap();
+
+        let action = GetTraderPnlAction::new(&testing_env);
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let pnl = TraderPnl::try_from_slice(&result.return_data.data).unwrap();
+
+        let trader_profile = testing_env
+            .svm
+            .get_parsed_account::<TraderProfile>(&swap_action.trader_profile);
+
+        assert_eq!(pnl.base_amount, BUY_AMOUNT);
+        assert_eq!(pnl.cost_basis, trader_profile.cost_basis);
+        assert_eq!(pnl.average_price, trader_profile.average_price());
+        assert_eq!(pnl.realized_pnl, 0);
+        assert!(pnl.unrealized_pnl < 0);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod create_trader_profile;
+pub mod create_trader_stats;
+pub mod get_trader_pnl;
+
+pub use create_trader_profile::*;
+pub use create_trader_stats::*;
+pub use get_trader_pnl::*;
@@ -0,0 +1,190 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use bytemuck::Zeroable;
+
+use crate::{
+    constant::MAX_BPS,
+    errors::TokenMillError,
+    manager::token_manager::check_mint_extensions,
+    state::{Market, TokenMillConfig},
+    QuoteTokenBadge, QuoteTokenBadgeStatus, QUOTE_TOKEN_BADGE_PDA_SEED,
+};
+
+#[derive(Accounts)]
+pub struct ValidateCreateMarket<'info> {
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        seeds = [
+            QUOTE_TOKEN_BADGE_PDA_SEED.as_bytes(),
+            config.key().as_ref(),
+            quote_token_mint.key().as_ref(),
+        ],
+        bump = quote_token_badge.bump,
+        constraint = quote_token_badge.status == QuoteTokenBadgeStatus::Enabled || creator.key() == config.authority @ TokenMillError::InvalidQuoteAssetBadge,
+    )]
+    pub quote_token_badge: Option<Account<'info, QuoteTokenBadge>>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    pub creator: Signer<'info>,
+}
+
+/// Dry run of `create_market` followed by `set_market_prices`, meant to be simulated by launch tooling.
+/// Mirrors their checks against the live config and returns without writing anything.
+pub fn handler(
+    ctx: Context<ValidateCreateMarket>,
+    total_supply: u64,
+    creator_fee_share: u16,
+    staking_fee_share: u16,
+    bid_prices: Vec<u64>,
+    ask_prices: Vec<u64>,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+
+    let quote_token_mint = ctx.accounts.quote_token_mint.key();
+    let protocol_fee_share = config.get_protocol_fee_share(&quote_token_mint);
+    let (creator_fee_share, staking_fee_share) =
+        config.get_market_fee_shares(&quote_token_mint, creator_fee_share, staking_fee_share);
+
+    require_eq!(
+        creator_fee_share + staking_fee_share + protocol_fee_share,
+        MAX_BPS as u16,
+        TokenMillError::InvalidFeeShare
+    );
+
+    #[cfg(not(feature = "staking"))]
+    require!(staking_fee_share == 0, TokenMillError::InvalidFeeShare);
+
+    require!(
+        check_mint_extensions(&ctx.accounts.quote_token_mint)?,
+        TokenMillError::UnsupportedTokenMint
+    );
+
+    // The market is initialized in memory only
+    let mut market = Market::zeroed();
+
+    market.initialize(
+        0,
+        config.key(),
+        ctx.accounts.creator.key(),
+        Pubkey::default(),
+        quote_token_mint,
+        ctx.accounts.quote_token_mint.decimals,
+        total_supply,
+        creator_fee_share,
+        staking_fee_share,
+    )?;
+
+    market.check_and_set_prices(&bid_prices, &ask_prices, config.max_spread_bps)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constant::{INTERVAL_NUMBER, MAX_TOTAL_SUPPLY};
+    use joelana_test_utils::{
+        joelana_env::{
+            actions::token_mill::{
+                tm_parse_error, CreateMarketAction, CreateQuoteAssetBadgeAction, TokenMillEnv,
+                UpdateQuoteAssetBadgeAction, ValidateCreateMarketAction,
+            },
+            QuoteTokenBadgeStatus, TokenMillError,
+        },
+        utils::token_mill::curve_generator::Curve,
+    };
+
+    fn setup_env() -> (TokenMillEnv, ValidateCreateMarketAction) {
+        let mut testing_env = TokenMillEnv::new().with_default_quote_token_mint();
+
+        let create_badge_action =
+            CreateQuoteAssetBadgeAction::new(testing_env.quote_token_mint.unwrap());
+
+        testing_env
+            .svm
+            .execute_actions(&[&create_badge_action])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let action = ValidateCreateMarketAction::new(&testing_env, Curve::default());
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn validate_create_market() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        // Nothing is created
+        let market = CreateMarketAction::new(&testing_env).market;
+
+        assert!(testing_env.svm.try_get_account(&market).is_none());
+    }
+
+    #[test]
+    fn validate_create_market_with_invalid_parameters() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.total_supply = MAX_TOTAL_SUPPLY + INTERVAL_NUMBER;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidTotalSupply
+        );
+
+        let (mut testing_env, mut action) = setup_env();
+
+        action.creator_fee_share += 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidFeeShare
+        );
+
+        let (mut testing_env, mut action) = setup_env();
+
+        let curve = &mut action.price_curve;
+        curve.bid_prices[2] = curve.bid_prices[1] - 1;
+        curve.ask_prices[2] = curve.ask_prices[1] - 1;
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::DecreasingPrices
+        );
+    }
+
+    #[test]
+    fn validate_create_market_with_disabled_quote_asset_badge() {
+        let (mut testing_env, mut action) = setup_env();
+
+        testing_env.svm.change_payer("admin");
+
+        let disable_action = UpdateQuoteAssetBadgeAction::new(
+            action.quote_token_mint,
+            QuoteTokenBadgeStatus::Disabled,
+        );
+
+        testing_env.svm.execute_actions(&[&disable_action]).unwrap();
+
+        action.signer = testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidQuoteAssetBadge
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillVestingPlanCreationEvent,
+    manager::{staking_manager, token_manager::transfer_from_eoa},
+    state::{
+        Market, MarketStaking, StakePosition, VestingMilestone, VestingPlan, MAX_VESTING_MILESTONES,
+    },
+    STAKING_POSITION_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateCreatorVestingPlan<'info> {
+    #[account(
+        mut,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = creator @ TokenMillError::InvalidAuthority
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [STAKING_POSITION_PDA_SEED.as_bytes(), market.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(init, payer = creator, space = 8 + VestingPlan::INIT_SPACE)]
+    pub vesting_plan: Account<'info, VestingPlan>,
+
+    /// CHECK: Wallet the tokens vest to, owner of the stake position
+    pub recipient: UncheckedAccount<'info>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = base_token_program
+    )]
+    pub creator_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Vests `vesting_amount` base tokens of the creator to `recipient` through its stake position, created if needed,
+/// e.g. for team allocations. The tokens earn staking rewards while vesting and are released to the recipient with
+/// `release`. A `revocable` plan lets the creator take back the tokens that haven't vested yet with `revoke_vesting_plan`.
+/// Part of the amount can be set aside in `milestones` tranches, unlocked once the circulating supply or the quote
+/// raised of the market reaches their threshold instead of over time.
+pub fn handler(
+    ctx: Context<CreateCreatorVestingPlan>,
+    start: i64,
+    vesting_amount: u64,
+    vesting_duration: i64,
+    cliff_duration: i64,
+    revocable: bool,
+    milestones: [VestingMilestone; MAX_VESTING_MILESTONES],
+) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let staking = &mut ctx.accounts.staking;
+    let stake_position = &mut ctx.accounts.stake_position;
+    let vesting_plan = &mut ctx.accounts.vesting_plan;
+
+    if stake_position.market == Pubkey::default() {
+        stake_position.initialize(ctx.accounts.market.key(), ctx.accounts.recipient.key())?;
+    }
+
+    vesting_plan.initialize(
+        stake_position.key(),
+        start,
+        vesting_amount,
+        vesting_duration,
+        cliff_duration,
+        ctx.accounts.creator.key(),
+        revocable,
+        milestones,
+        current_time,
+    )?;
+
+    {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        staking_manager::deposit_vested(
+            market,
+            staking,
+            stake_position,
+            vesting_amount,
+            current_time,
+        )?;
+    }
+
+    transfer_from_eoa(
+        &ctx.accounts.base_token_mint,
+        &ctx.accounts.creator,
+        &ctx.accounts.creator_base_token_ata,
+        &ctx.accounts.market_base_token_ata,
+        &ctx.accounts.base_token_program,
+        vesting_amount,
+    )?;
+
+    emit_cpi!(TokenMillVestingPlanCreationEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.recipient.key(),
+        vesting_plan: vesting_plan.key(),
+        vesting_amount,
+        start,
+        vesting_duration,
+        cliff_duration,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateCreatorVestingPlanAction, ReleaseAction, SwapAction, TokenMillEnv,
+        },
+        make_address, SwapAmountType, SwapType,
+    };
+
+    use crate::{
+        errors::TokenMillError,
+        state::{VestingMilestone, VestingMilestoneType},
+        StakePosition, VestingPlan,
+    };
+
+    const VESTING_AMOUNT: u64 = 1_000_000_000;
+    const START: i64 = 333;
+    const VESTING_DURATION: i64 = 300;
+    const CLIFF_DURATION: i64 = 60;
+
+    fn setup_env() -> (TokenMillEnv, CreateCreatorVestingPlanAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(0);
+
+        testing_env.svm.warp(START);
+
+        let alice = testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                VESTING_AMOUNT,
+                u64::MAX,
+                None,
+            )
+            .with_user(alice)])
+            .unwrap();
+
+        let action = CreateCreatorVestingPlanAction::new(
+            &testing_env,
+            make_address("carol"),
+            VESTING_AMOUNT,
+            START,
+            VESTING_DURATION,
+            CLIFF_DURATION,
+            true,
+        );
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn create_creator_vesting_plan() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let vesting_plan = testing_env
+            .svm
+            .get_parsed_account::<VestingPlan>(&action.vesting_plan);
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        assert_eq!(vesting_plan.stake_position, action.stake_position);
+        assert_eq!(vesting_plan.funder, make_address("alice"));
+        assert!(vesting_plan.revocable);
+        assert_eq!(stake_position.user, make_address("carol"));
+        assert_eq!(stake_position.total_amount_vested, VESTING_AMOUNT);
+
+        // Released to the recipient
+        testing_env.svm.warp(VESTING_DURATION);
+        testing_env.svm.change_payer("carol");
+
+        let base_token_mint = testing_env.base_token_mint.unwrap();
+        let balance_before = testing_env
+            .svm
+            .get_balance(&base_token_mint, &make_address("carol"));
+
+        let mut release_action = ReleaseAction::new();
+        release_action.vesting_plan = action.vesting_plan;
+        release_action.with_user(make_address("carol"));
+
+        testing_env.svm.execute_actions(&[&release_action]).unwrap();
+
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&base_token_mint, &make_address("carol")),
+            balance_before + VESTING_AMOUNT
+        );
+    }
+
+    #[test]
+    fn create_creator_vesting_plan_with_milestones() {
+        let (mut testing_env, mut action) = setup_env();
+
+        // Already reached by the purchase of the creator
+        action.milestones[0] = VestingMilestone {
+            milestone_type: VestingMilestoneType::QuoteRaised,
+            threshold: 1,
+            amount: VESTING_AMOUNT / 4,
+            reached: false,
+        };
+        action.milestones[1] = VestingMilestone {
+            milestone_type: VestingMilestoneType::CirculatingSupply,
+            threshold: u64::MAX,
+            amount: VESTING_AMOUNT / 4,
+            reached: false,
+        };
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let mut release_action = ReleaseAction::new();
+        release_action.vesting_plan = action.vesting_plan;
+        release_action.with_user(make_address("carol"));
+
+        // Only the reached tranche is released before the cliff
+        testing_env.svm.execute_actions(&[&release_action]).unwrap();
+
+        let vesting_plan = testing_env
+            .svm
+            .get_parsed_account::<VestingPlan>(&action.vesting_plan);
+
+        assert!(vesting_plan.milestones[0].reached);
+        assert!(!vesting_plan.milestones[1].reached);
+        assert_eq!(vesting_plan.amount_released, VESTING_AMOUNT / 4);
+
+        // The unreached tranche stays locked after the vesting duration
+        testing_env.svm.warp(VESTING_DURATION);
+
+        testing_env.svm.execute_actions(&[&release_action]).unwrap();
+
+        let vesting_plan = testing_env
+            .svm
+            .get_parsed_account::<VestingPlan>(&action.vesting_plan);
+
+        assert_eq!(vesting_plan.amount_released, VESTING_AMOUNT * 3 / 4);
+    }
+
+    #[test]
+    fn create_creator_vesting_plan_with_invalid_milestones() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.milestones[0] = VestingMilestone {
+            milestone_type: VestingMilestoneType::QuoteRaised,
+            threshold: 1,
+            amount: VESTING_AMOUNT + 1,
+            reached: false,
+        };
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidVestingMilestones
+        );
+    }
+
+    #[test]
+    fn create_creator_vesting_plan_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.signer = testing_env.svm.change_payer("mallory");
+        action.creator_base_token_ata = testing_env
+            .svm
+            .get_ata_address(&testing_env.base_token_mint.unwrap(), &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -2,8 +2,9 @@ use crate::{
     errors::TokenMillError,
     events::TokenMillVestingPlanCreationEvent,
     manager::{staking_manager, token_manager::transfer_from_eoa},
-    state::{Market, MarketStaking, StakePosition},
-    VestingPlan,
+    state::{
+        Market, MarketStaking, StakePosition, VestingMilestone, VestingPlan, MAX_VESTING_MILESTONES,
+    },
 };
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
@@ -59,20 +60,7 @@ pub fn handler(
     vesting_duration: i64,
     cliff_duration: i64,
 ) -> Result<()> {
-    require!(
-        start > 0 && vesting_duration > 0 && cliff_duration > 0,
-        TokenMillError::InvalidVestingDuration
-    );
-
-    require!(
-        vesting_duration > cliff_duration,
-        TokenMillError::InvalidVestingDuration
-    );
-
-    require!(
-        start + vesting_duration > Clock::get()?.unix_timestamp,
-        TokenMillError::InvalidVestingStartTime
-    );
+    let current_time = Clock::get()?.unix_timestamp;
 
     let market = &mut ctx.accounts.market.load_mut()?;
     let staking = &mut ctx.accounts.staking;
@@ -85,9 +73,19 @@ pub fn handler(
         vesting_amount,
         vesting_duration,
         cliff_duration,
+        ctx.accounts.user.key(),
+        false,
+        [VestingMilestone::default(); MAX_VESTING_MILESTONES],
+        current_time,
     )?;
 
-    staking_manager::deposit_vested(market, staking, stake_position, vesting_amount)?;
+    staking_manager::deposit_vested(
+        market,
+        staking,
+        stake_position,
+        vesting_amount,
+        current_time,
+    )?;
 
     transfer_from_eoa(
         &ctx.accounts.base_token_mint,
@@ -0,0 +1,430 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::TokenMillError,
+    events::{TokenMillIncentiveEvent, TokenMillSwapEvent, TokenMillVestingPlanCreationEvent},
+    manager::{
+        oracle_manager, price_feed_manager, staking_manager,
+        swap_manager::{self, SwapAmountType, SwapType},
+        token_manager::{transfer_from_eoa, transfer_from_pda},
+    },
+    state::{
+        IncentiveAction, Market, MarketStaking, StakePosition, VestingMilestone, VestingPlan,
+        MAX_VESTING_MILESTONES,
+    },
+    TokenMillConfig, MARKET_ORACLE_PDA_SEED, MARKET_PDA_SEED, RESTRICTED_WALLET_PDA_SEED,
+    STAKING_POSITION_PDA_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DevBuyAndVest<'info> {
+    #[account(constraint = !config.swaps_paused @ TokenMillError::SwapsPaused)]
+    pub config: Account<'info, TokenMillConfig>,
+
+    #[account(
+        mut,
+        has_one = config @ TokenMillError::InvalidConfigAccount,
+        has_one = base_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = quote_token_mint @ TokenMillError::InvalidMintAccount,
+        has_one = creator @ TokenMillError::InvalidAuthority
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [STAKING_POSITION_PDA_SEED.as_bytes(), market.key().as_ref(), creator.key().as_ref()],
+        bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(init, payer = creator, space = 8 + VestingPlan::INIT_SPACE)]
+    pub vesting_plan: Account<'info, VestingPlan>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    pub quote_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = quote_token_program
+    )]
+    pub market_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = quote_token_program
+    )]
+    pub creator_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_token_mint,
+        associated_token::authority = config.protocol_fee_recipient,
+        associated_token::token_program = quote_token_program
+    )]
+    pub protocol_quote_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pyth price update of the quote token, required if the market registered one. Checked in the handler
+    pub quote_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: TWAP oracle of the market, only written to once it has been created
+    #[account(
+        mut,
+        seeds = [MARKET_ORACLE_PDA_SEED.as_bytes(), market.key().as_ref()],
+        bump
+    )]
+    pub market_oracle: UncheckedAccount<'info>,
+
+    /// CHECK: Compliance hold of the creator on this market, must not exist
+    #[account(
+        seeds = [RESTRICTED_WALLET_PDA_SEED.as_bytes(), market.key().as_ref(), creator.key().as_ref()],
+        bump,
+        constraint = restricted_wallet.data_is_empty() @ TokenMillError::WalletRestricted
+    )]
+    pub restricted_wallet: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// First buy of a market, made by its creator in the market creation transaction. The base tokens bought never leave
+/// the market base token ATA and are vested to the creator through an irrevocable vesting plan starting now, so
+/// buyers can check that the dev allocation can't be dumped, e.g. with a lock certificate. The swap has to be fully
+/// filled, and referral and interface fees are not supported.
+pub fn handler(
+    ctx: Context<DevBuyAndVest>,
+    swap_amount_type: SwapAmountType,
+    amount: u64,
+    other_amount_threshold: u64,
+    vesting_duration: i64,
+    cliff_duration: i64,
+) -> Result<(u64, u64)> {
+    if amount == 0 {
+        return Err(TokenMillError::InvalidAmount.into());
+    }
+
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let staking = &mut ctx.accounts.staking;
+    let stake_position = &mut ctx.accounts.stake_position;
+    let vesting_plan = &mut ctx.accounts.vesting_plan;
+
+    if stake_position.market == Pubkey::default() {
+        stake_position.initialize(ctx.accounts.market.key(), ctx.accounts.creator.key())?;
+    }
+
+    let (
+        base_amount,
+        quote_amount,
+        creator_fee,
+        staking_fee,
+        protocol_fee,
+        buy_incentive_weight,
+        circulating_supply_before,
+        circulating_supply_after,
+        (ask_price, bid_price),
+        market_bump,
+    ) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        require!(market.swap_count == 0, TokenMillError::MarketAlreadyTraded);
+
+        price_feed_manager::check_quote_peg(market, ctx.accounts.quote_price_feed.as_deref())?;
+
+        let circulating_supply_before = market.circulating_supply();
+
+        let (base_amount, quote_amount, swap_fee) = swap_manager::swap(
+            market,
+            SwapType::Buy,
+            swap_amount_type,
+            amount,
+            &Clock::get()?,
+        )?;
+
+        let circulating_supply_after = market.circulating_supply();
+        let spot_prices = market.get_spot_prices()?;
+
+        oracle_manager::record_prices(&ctx.accounts.market_oracle, market)?;
+
+        let filled_amount = match swap_amount_type {
+            SwapAmountType::ExactInput => quote_amount,
+            SwapAmountType::ExactOutput => base_amount,
+        };
+
+        require!(filled_amount == amount, TokenMillError::SwapNotFullyFilled);
+
+        let (quote_amount, swap_fee) = swap_manager::apply_fee_exemption(
+            &ctx.accounts.config,
+            &ctx.accounts.creator.key(),
+            SwapType::Buy,
+            quote_amount,
+            swap_fee,
+        )?;
+
+        let (creator_fee, staking_fee, protocol_fee, _) =
+            market.fees.distribute_fee(swap_fee, None)?;
+
+        vesting_plan.initialize(
+            stake_position.key(),
+            current_time,
+            base_amount,
+            vesting_duration,
+            cliff_duration,
+            ctx.accounts.creator.key(),
+            false,
+            [VestingMilestone::default(); MAX_VESTING_MILESTONES],
+            current_time,
+        )?;
+
+        staking_manager::deposit_vested(
+            market,
+            staking,
+            stake_position,
+            base_amount,
+            current_time,
+        )?;
+
+        (
+            base_amount,
+            quote_amount,
+            creator_fee,
+            staking_fee,
+            protocol_fee,
+            market.get_incentive_weight(IncentiveAction::Buy, quote_amount)?,
+            circulating_supply_before,
+            circulating_supply_after,
+            spot_prices,
+            market.bump,
+        )
+    };
+
+    match swap_amount_type {
+        SwapAmountType::ExactInput => {
+            if base_amount < other_amount_threshold {
+                return Err(TokenMillError::AmountThresholdNotMet.into());
+            }
+        }
+        SwapAmountType::ExactOutput => {
+            if quote_amount > other_amount_threshold {
+                return Err(TokenMillError::AmountThresholdNotMet.into());
+            }
+        }
+    }
+
+    transfer_from_eoa(
+        &ctx.accounts.quote_token_mint,
+        &ctx.accounts.creator,
+        &ctx.accounts.creator_quote_token_ata,
+        &ctx.accounts.market_quote_token_ata,
+        &ctx.accounts.quote_token_program,
+        quote_amount,
+    )?;
+
+    if protocol_fee > 0 {
+        let base_token_mint_key = ctx.accounts.base_token_mint.key();
+        let seeds = [
+            MARKET_PDA_SEED.as_bytes(),
+            base_token_mint_key.as_ref(),
+            &[market_bump],
+        ];
+
+        transfer_from_pda(
+            &ctx.accounts.quote_token_mint,
+            ctx.accounts.market.to_account_info(),
+            &ctx.accounts.market_quote_token_ata,
+            &ctx.accounts.protocol_quote_token_ata,
+            &ctx.accounts.quote_token_program,
+            protocol_fee,
+            &seeds,
+        )?;
+    }
+
+    emit_cpi!(TokenMillSwapEvent {
+        user: ctx.accounts.creator.key(),
+        market: ctx.accounts.market.key(),
+        swap_type: SwapType::Buy,
+        base_amount,
+        quote_amount,
+        referral_token_account: None,
+        creator_fee,
+        staking_fee,
+        protocol_fee,
+        referral_fee: 0,
+        interface_fee_recipient: None,
+        interface_fee: 0,
+        circulating_supply_before,
+        circulating_supply_after,
+        ask_price,
+        bid_price,
+    });
+
+    emit_cpi!(TokenMillVestingPlanCreationEvent {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.creator.key(),
+        vesting_plan: ctx.accounts.vesting_plan.key(),
+        vesting_amount: base_amount,
+        start: current_time,
+        vesting_duration,
+        cliff_duration,
+    });
+
+    if let Some(weight) = buy_incentive_weight {
+        emit_cpi!(TokenMillIncentiveEvent {
+            wallet: ctx.accounts.creator.key(),
+            market: ctx.accounts.market.key(),
+            action: IncentiveAction::Buy,
+            amount: quote_amount,
+            weight,
+        });
+    }
+
+    Ok((base_amount, quote_amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{state::VestingPlan, MarketStaking, StakePosition, LOCK_CERTIFICATE_PDA_SEED};
+    use anchor_lang::AnchorDeserialize;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, DevBuyAndVestAction, IssueVestingLockCertificateAction, SwapAction,
+            TokenMillEnv,
+        },
+        make_address, SwapAmountType, SwapType, TokenMillError,
+    };
+    use solana_sdk::pubkey::Pubkey;
+
+    const BUY_AMOUNT: u64 = 1_000_000_000;
+    const VESTING_DURATION: i64 = 300;
+    const CLIFF_DURATION: i64 = 60;
+
+    fn setup_env() -> (TokenMillEnv, DevBuyAndVestAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(0);
+
+        testing_env.svm.warp(333);
+        testing_env.svm.change_payer("alice");
+
+        let action = DevBuyAndVestAction::new(
+            &testing_env,
+            SwapAmountType::ExactOutput,
+            BUY_AMOUNT,
+            u64::MAX,
+            VESTING_DURATION,
+            CLIFF_DURATION,
+        );
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn dev_buy_and_vest() {
+        let (mut testing_env, action) = setup_env();
+
+        let creator_base_balance_before = testing_env
+            .svm
+            .get_balance(&action.base_token_mint, &make_address("alice"));
+
+        let result = testing_env.svm.execute_actions(&[&action]).unwrap();
+        let (base_amount, _) = <(u64, u64)>::try_from_slice(&result.return_data.data).unwrap();
+
+        assert_eq!(base_amount, BUY_AMOUNT);
+
+        let vesting_plan = testing_env
+            .svm
+            .get_parsed_account::<VestingPlan>(&action.vesting_plan);
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+        let staking = testing_env
+            .svm
+            .get_parsed_account::<MarketStaking>(&action.staking);
+
+        assert_eq!(vesting_plan.amount_vested, BUY_AMOUNT);
+        assert_eq!(vesting_plan.funder, make_address("alice"));
+        assert!(!vesting_plan.revocable);
+        assert_eq!(stake_position.user, make_address("alice"));
+        assert_eq!(stake_position.total_amount_vested, BUY_AMOUNT);
+        assert_eq!(staking.total_amount_vested, BUY_AMOUNT);
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&action.base_token_mint, &make_address("alice")),
+            creator_base_balance_before
+        );
+
+        // The lock can be certified
+        let mut certificate_action = IssueVestingLockCertificateAction::new();
+        certificate_action.market = action.market;
+        certificate_action.stake_position = action.stake_position;
+        certificate_action.vesting_plan = action.vesting_plan;
+        certificate_action.lock_certificate = Pubkey::find_program_address(
+            &[
+                LOCK_CERTIFICATE_PDA_SEED.as_bytes(),
+                &action.vesting_plan.to_bytes(),
+            ],
+            &crate::ID,
+        )
+        .0;
+
+        testing_env
+            .svm
+            .execute_actions(&[&certificate_action])
+            .unwrap();
+    }
+
+    #[test]
+    fn dev_buy_and_vest_after_first_swap() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                BUY_AMOUNT,
+                u64::MAX,
+                None,
+            )])
+            .unwrap();
+
+        testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::MarketAlreadyTraded
+        );
+    }
+
+    #[test]
+    fn dev_buy_and_vest_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.with_user(testing_env.svm.change_payer("bob"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillLockCertificateEvent,
+    state::{
+        LockCertificate, LockType, Market, StakePosition, VestingPlan, LOCK_CERTIFICATE_PDA_SEED,
+    },
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct IssueVestingLockCertificate<'info> {
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(has_one = market @ TokenMillError::InvalidMarket)]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(has_one = stake_position @ TokenMillError::InvalidStakePosition)]
+    pub vesting_plan: Account<'info, VestingPlan>,
+
+    #[account(
+        init,
+        seeds = [LOCK_CERTIFICATE_PDA_SEED.as_bytes(), vesting_plan.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + LockCertificate::INIT_SPACE
+    )]
+    pub lock_certificate: Account<'info, LockCertificate>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Permissionless, certifies the tokens still locked in a vesting plan. Revocable plans can't be certified
+/// as their funder can take the tokens back, nor plans with milestone tranches as those have no unlock time.
+pub fn handler(ctx: Context<IssueVestingLockCertificate>) -> Result<()> {
+    let vesting_plan = &ctx.accounts.vesting_plan;
+
+    require!(
+        !vesting_plan.revocable,
+        TokenMillError::VestingPlanRevocable
+    );
+    require!(
+        !vesting_plan.has_milestones(),
+        TokenMillError::VestingPlanMilestoneGated
+    );
+
+    let amount = vesting_plan.amount_vested - vesting_plan.amount_released;
+    let unlock_start = vesting_plan.start + vesting_plan.cliff_duration;
+    let unlock_end = vesting_plan.start + vesting_plan.vesting_duration;
+
+    require!(
+        amount > 0 && unlock_end > Clock::get()?.unix_timestamp,
+        TokenMillError::NothingLocked
+    );
+
+    let mint = ctx.accounts.market.load()?.base_token_mint;
+
+    let lock_certificate = &mut ctx.accounts.lock_certificate;
+
+    lock_certificate.initialize(
+        ctx.bumps.lock_certificate,
+        LockType::Vesting,
+        ctx.accounts.market.key(),
+        vesting_plan.key(),
+        mint,
+        ctx.accounts.stake_position.user,
+        amount,
+        unlock_start,
+        unlock_end,
+    )?;
+
+    emit_cpi!(TokenMillLockCertificateEvent {
+        lock_certificate: lock_certificate.key(),
+        target: vesting_plan.key(),
+        owner: lock_certificate.owner,
+        amount,
+        unlock_start,
+        unlock_end,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::{LockCertificate, LockType};
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateVestingPlanAction, IssueVestingLockCertificateAction,
+            ReleaseAction, TokenMillEnv,
+        },
+        make_address, TokenMillError,
+    };
+
+    const VESTING_AMOUNT: u64 = 1_000_000_000;
+    const START: i64 = 333;
+    const VESTING_DURATION: i64 = 300;
+    const CLIFF_DURATION: i64 = 60;
+
+    fn setup_env() -> (TokenMillEnv, IssueVestingLockCertificateAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(VESTING_AMOUNT);
+
+        testing_env.svm.warp(START);
+
+        testing_env.svm.change_payer("bob");
+
+        let create_vesting_action =
+            CreateVestingPlanAction::new(VESTING_AMOUNT, START, VESTING_DURATION, CLIFF_DURATION);
+
+        testing_env
+            .svm
+            .execute_actions(&[&create_vesting_action])
+            .unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let action = IssueVestingLockCertificateAction::new();
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn issue_vesting_lock_certificate() {
+        let (mut testing_env, action) = setup_env();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let lock_certificate = testing_env
+            .svm
+            .get_parsed_account::<LockCertificate>(&action.lock_certificate);
+
+        assert_eq!(lock_certificate.lock_type, LockType::Vesting);
+        assert_eq!(lock_certificate.market, action.market);
+        assert_eq!(lock_certificate.target, action.vesting_plan);
+        assert_eq!(lock_certificate.mint, make_address("base_token_mint"));
+        assert_eq!(lock_certificate.owner, make_address("bob"));
+        assert_eq!(lock_certificate.amount, VESTING_AMOUNT);
+        assert_eq!(lock_certificate.unlock_start, START + CLIFF_DURATION);
+        assert_eq!(lock_certificate.unlock_end, START + VESTING_DURATION);
+    }
+
+    #[test]
+    fn issue_vesting_lock_certificate_after_partial_release() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.warp(VESTING_DURATION / 2);
+        testing_env.svm.change_payer("bob");
+
+        testing_env
+            .svm
+            .execute_actions(&[&ReleaseAction::new()])
+            .unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let lock_certificate = testing_env
+            .svm
+            .get_parsed_account::<LockCertificate>(&action.lock_certificate);
+
+        assert_eq!(lock_certificate.amount, VESTING_AMOUNT / 2);
+    }
+
+    #[test]
+    fn issue_vesting_lock_certificate_after_unlock() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.warp(VESTING_DURATION);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_err());
+
+        let error = tm_parse_error(result).unwrap();
+
+        assert_eq!(error, TokenMillError::NothingLocked);
+    }
+}
@@ -1,5 +1,19 @@
+pub mod create_creator_vesting_plan;
 pub mod create_vesting_plan;
+pub mod dev_buy_and_vest;
+pub mod issue_vesting_lock_certificate;
 pub mod release;
+pub mod release_all;
+pub mod revoke_vesting_plan;
+pub mod transfer_vesting_plan;
+pub mod upgrade_vesting_plan;
 
+pub use create_creator_vesting_plan::*;
 pub use create_vesting_plan::*;
+pub use dev_buy_and_vest::*;
+pub use issue_vesting_lock_certificate::*;
 pub use release::*;
+pub use release_all::*;
+pub use revoke_vesting_plan::*;
+pub use transfer_vesting_plan::*;
+pub use upgrade_vesting_plan::*;
@@ -55,16 +55,22 @@ pub fn handler(ctx: Context<Release>) -> Result<()> {
     let stake_position = &mut ctx.accounts.stake_position;
     let vesting_plan = &mut ctx.accounts.vesting_plan;
 
-    let current_time = Clock::get().unwrap().unix_timestamp;
+    let current_time = Clock::get()?.unix_timestamp;
 
-    let amount_released = vesting_plan.release(current_time)?;
-
-    let market_bump = {
+    let (amount_released, market_bump) = {
         let market = &mut ctx.accounts.market.load_mut()?;
 
-        staking_manager::withdraw_vested(market, staking, stake_position, amount_released)?;
+        let amount_released = vesting_plan.release(market, current_time)?;
+
+        staking_manager::withdraw_vested(
+            market,
+            staking,
+            stake_position,
+            amount_released,
+            current_time,
+        )?;
 
-        market.bump
+        (amount_released, market.bump)
     };
 
     if amount_released > 0 {
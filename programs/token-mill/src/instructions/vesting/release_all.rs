@@ -0,0 +1,256 @@
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillVestingPlanReleaseEvent,
+    manager::{staking_manager, token_manager::transfer_from_pda},
+    state::{Market, MarketStaking, StakePosition},
+    VestingPlan, MARKET_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReleaseAll<'info> {
+    #[account(mut, has_one = base_token_mint @ TokenMillError::InvalidMintAccount)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(
+        mut,
+        has_one = market @ TokenMillError::InvalidMarket,
+        has_one = user @ TokenMillError::InvalidAuthority
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = base_token_program
+    )]
+    pub user_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Releases the vested tokens of every vesting plan of the stake position passed in the remaining accounts,
+/// in a single transfer. Returns the total amount released.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ReleaseAll<'info>>) -> Result<u64> {
+    require!(
+        !ctx.remaining_accounts.is_empty(),
+        TokenMillError::InvalidClaimAccounts
+    );
+
+    let staking = &mut ctx.accounts.staking;
+    let stake_position = &mut ctx.accounts.stake_position;
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let mut total_released = 0;
+
+    let market_bump = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        for vesting_plan_info in ctx.remaining_accounts {
+            require!(
+                vesting_plan_info.is_writable,
+                TokenMillError::InvalidClaimAccounts
+            );
+
+            let mut vesting_plan = Account::<VestingPlan>::try_from(vesting_plan_info)?;
+
+            require_keys_eq!(
+                vesting_plan.stake_position,
+                stake_position.key(),
+                TokenMillError::InvalidStakePosition
+            );
+
+            let amount_released = vesting_plan.release(market, current_time)?;
+
+            // Written back right away so that a plan passed twice isn't released twice
+            vesting_plan.exit(&crate::ID)?;
+
+            total_released += amount_released;
+
+            emit_cpi!(TokenMillVestingPlanReleaseEvent {
+                vesting_plan: vesting_plan.key(),
+                amount_released,
+            });
+        }
+
+        staking_manager::withdraw_vested(
+            market,
+            staking,
+            stake_position,
+            total_released,
+            current_time,
+        )?;
+
+        market.bump
+    };
+
+    if total_released > 0 {
+        let base_token_mint = &ctx.accounts.base_token_mint;
+        let base_token_mint_key = base_token_mint.key();
+        let seeds = [
+            MARKET_PDA_SEED.as_bytes(),
+            base_token_mint_key.as_ref(),
+            &[market_bump],
+        ];
+
+        transfer_from_pda(
+            base_token_mint,
+            ctx.accounts.market.to_account_info(),
+            &ctx.accounts.market_base_token_ata,
+            &ctx.accounts.user_base_token_ata,
+            &ctx.accounts.base_token_program,
+            total_released,
+            &seeds,
+        )?;
+    }
+
+    Ok(total_released)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::VestingPlan;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateStakePositionAction, CreateVestingPlanAction, ReleaseAllAction,
+            TokenMillEnv,
+        },
+        make_address, TokenMillError,
+    };
+
+    const VESTING_AMOUNT: u64 = 1_000_000_000;
+    const START: i64 = 333;
+    const VESTING_DURATION: i64 = 300;
+    const CLIFF_DURATION: i64 = 60;
+
+    fn setup_env() -> (TokenMillEnv, ReleaseAllAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(2 * VESTING_AMOUNT);
+
+        testing_env.svm.warp(START);
+
+        testing_env.svm.change_payer("bob");
+
+        let create_vesting_action =
+            CreateVestingPlanAction::new(VESTING_AMOUNT, START, VESTING_DURATION, CLIFF_DURATION);
+
+        let mut create_longer_vesting_action = CreateVestingPlanAction::new(
+            VESTING_AMOUNT,
+            START,
+            2 * VESTING_DURATION,
+            CLIFF_DURATION,
+        );
+        create_longer_vesting_action.vesting_plan = make_address("longer_vesting_plan");
+
+        testing_env
+            .svm
+            .execute_actions(&[&create_vesting_action, &create_longer_vesting_action])
+            .unwrap();
+
+        let action = ReleaseAllAction::new(vec![
+            create_vesting_action.vesting_plan,
+            create_longer_vesting_action.vesting_plan,
+        ]);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn release_all() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.warp(VESTING_DURATION);
+
+        let base_token_mint = make_address("base_token_mint");
+        let balance_before = testing_env
+            .svm
+            .get_balance(&base_token_mint, &make_address("bob"));
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let vesting_plan = testing_env
+            .svm
+            .get_parsed_account::<VestingPlan>(&action.vesting_plans[0]);
+        let longer_vesting_plan = testing_env
+            .svm
+            .get_parsed_account::<VestingPlan>(&action.vesting_plans[1]);
+
+        assert_eq!(vesting_plan.amount_released, VESTING_AMOUNT);
+        assert_eq!(longer_vesting_plan.amount_released, VESTING_AMOUNT / 2);
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&base_token_mint, &make_address("bob")),
+            balance_before + VESTING_AMOUNT * 3 / 2
+        );
+    }
+
+    #[test]
+    fn release_all_with_duplicate_plan() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.vesting_plans[1] = action.vesting_plans[0];
+
+        testing_env.svm.warp(VESTING_DURATION);
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let vesting_plan = testing_env
+            .svm
+            .get_parsed_account::<VestingPlan>(&action.vesting_plans[0]);
+
+        assert_eq!(vesting_plan.amount_released, VESTING_AMOUNT);
+    }
+
+    #[test]
+    fn release_all_without_plans() {
+        let (mut testing_env, mut action) = setup_env();
+
+        action.vesting_plans.clear();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidClaimAccounts
+        );
+    }
+
+    #[test]
+    fn release_all_with_other_user_plan() {
+        let (mut testing_env, mut action) = setup_env();
+
+        let carol = testing_env.svm.change_payer("carol");
+
+        testing_env
+            .svm
+            .execute_actions(&[CreateStakePositionAction::new(&testing_env).with_user(carol)])
+            .unwrap();
+
+        action.with_user(carol);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidStakePosition
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillVestingPlanRevocationEvent,
+    manager::{staking_manager, token_manager::transfer_from_pda},
+    state::{Market, MarketStaking, StakePosition},
+    VestingPlan, MARKET_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RevokeVestingPlan<'info> {
+    #[account(mut, has_one = base_token_mint @ TokenMillError::InvalidMintAccount)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        has_one = stake_position @ TokenMillError::InvalidStakePosition,
+        has_one = funder @ TokenMillError::InvalidAuthority
+    )]
+    pub vesting_plan: Account<'info, VestingPlan>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = funder,
+        associated_token::token_program = base_token_program
+    )]
+    pub funder_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub funder: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Ends a revocable vesting plan, sending the tokens that haven't vested yet back to its funder.
+/// The tokens vested so far stay releasable by the recipient, along with the rewards they earned.
+pub fn handler(ctx: Context<RevokeVestingPlan>) -> Result<()> {
+    let staking = &mut ctx.accounts.staking;
+    let stake_position = &mut ctx.accounts.stake_position;
+    let vesting_plan = &mut ctx.accounts.vesting_plan;
+
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let (amount_revoked, market_bump) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        let amount_revoked = vesting_plan.revoke(market, current_time)?;
+
+        staking_manager::withdraw_vested(
+            market,
+            staking,
+            stake_position,
+            amount_revoked,
+            current_time,
+        )?;
+
+        (amount_revoked, market.bump)
+    };
+
+    if amount_revoked > 0 {
+        let base_token_mint = &ctx.accounts.base_token_mint;
+        let base_token_mint_key = base_token_mint.key();
+        let seeds = [
+            MARKET_PDA_SEED.as_bytes(),
+            base_token_mint_key.as_ref(),
+            &[market_bump],
+        ];
+
+        transfer_from_pda(
+            base_token_mint,
+            ctx.accounts.market.to_account_info(),
+            &ctx.accounts.market_base_token_ata,
+            &ctx.accounts.funder_base_token_ata,
+            &ctx.accounts.base_token_program,
+            amount_revoked,
+            &seeds,
+        )?;
+    }
+
+    emit_cpi!(TokenMillVestingPlanRevocationEvent {
+        vesting_plan: vesting_plan.key(),
+        funder: ctx.accounts.funder.key(),
+        amount_revoked,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateCreatorVestingPlanAction, RevokeVestingPlanAction, SwapAction,
+            TokenMillEnv,
+        },
+        make_address, SwapAmountType, SwapType,
+    };
+
+    use crate::{errors::TokenMillError, StakePosition, VestingPlan};
+
+    const VESTING_AMOUNT: u64 = 1_000_000_000;
+    const START: i64 = 333;
+    const VESTING_DURATION: i64 = 300;
+    const CLIFF_DURATION: i64 = 60;
+
+    fn setup_env(revocable: bool) -> (TokenMillEnv, RevokeVestingPlanAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(0);
+
+        testing_env.svm.warp(START);
+
+        let alice = testing_env.svm.change_payer("alice");
+
+        let create_action = CreateCreatorVestingPlanAction::new(
+            &testing_env,
+            make_address("carol"),
+            VESTING_AMOUNT,
+            START,
+            VESTING_DURATION,
+            CLIFF_DURATION,
+            revocable,
+        );
+
+        testing_env
+            .svm
+            .execute_actions(&[SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                VESTING_AMOUNT,
+                u64::MAX,
+                None,
+            )
+            .with_user(alice)])
+            .unwrap();
+
+        testing_env.svm.execute_actions(&[&create_action]).unwrap();
+
+        let action = RevokeVestingPlanAction::new(&create_action);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn revoke_vesting_plan() {
+        let (mut testing_env, action) = setup_env(true);
+
+        testing_env.svm.warp(VESTING_DURATION / 2);
+
+        let base_token_mint = testing_env.base_token_mint.unwrap();
+        let balance_before = testing_env
+            .svm
+            .get_balance(&base_token_mint, &make_address("alice"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let vesting_plan = testing_env
+            .svm
+            .get_parsed_account::<VestingPlan>(&action.vesting_plan);
+        let stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+
+        assert_eq!(vesting_plan.amount_vested, VESTING_AMOUNT / 2);
+        assert_eq!(stake_position.total_amount_vested, VESTING_AMOUNT / 2);
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&base_token_mint, &make_address("alice")),
+            balance_before + VESTING_AMOUNT / 2
+        );
+
+        // Can only be revoked once
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::VestingPlanNotRevocable
+        );
+    }
+
+    #[test]
+    fn revoke_irrevocable_vesting_plan() {
+        let (mut testing_env, action) = setup_env(false);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::VestingPlanNotRevocable
+        );
+    }
+
+    #[test]
+    fn revoke_vesting_plan_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env(true);
+
+        action.signer = testing_env.svm.change_payer("mallory");
+        action.funder_base_token_ata = testing_env
+            .svm
+            .get_ata_address(&testing_env.base_token_mint.unwrap(), &action.signer);
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use crate::{
+    errors::TokenMillError,
+    events::TokenMillVestingPlanTransferEvent,
+    manager::{staking_manager, token_manager::transfer_from_pda},
+    state::{Market, MarketStaking, StakePosition},
+    VestingPlan, LOCK_CERTIFICATE_PDA_SEED, MARKET_PDA_SEED, RESTRICTED_WALLET_PDA_SEED,
+    STAKING_POSITION_PDA_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TransferVestingPlan<'info> {
+    #[account(mut, has_one = base_token_mint @ TokenMillError::InvalidMintAccount)]
+    pub market: AccountLoader<'info, Market>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub staking: Account<'info, MarketStaking>,
+
+    #[account(mut, has_one = market @ TokenMillError::InvalidMarket)]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [STAKING_POSITION_PDA_SEED.as_bytes(), market.key().as_ref(), new_beneficiary.key().as_ref()],
+        bump
+    )]
+    pub new_stake_position: Account<'info, StakePosition>,
+
+    #[account(mut, has_one = stake_position @ TokenMillError::InvalidStakePosition)]
+    pub vesting_plan: Account<'info, VestingPlan>,
+
+    /// CHECK: Lock certificate of the vesting plan, must not exist as it names the current beneficiary
+    #[account(
+        seeds = [LOCK_CERTIFICATE_PDA_SEED.as_bytes(), vesting_plan.key().as_ref()],
+        bump,
+        constraint = lock_certificate.data_is_empty() @ TokenMillError::VestingPlanCertified
+    )]
+    pub lock_certificate: UncheckedAccount<'info>,
+
+    /// CHECK: Wallet the remaining tokens vest to from now on
+    #[account(constraint = new_beneficiary.key() != stake_position.user @ TokenMillError::InvalidStakePosition)]
+    pub new_beneficiary: UncheckedAccount<'info>,
+
+    /// CHECK: Compliance hold of the new beneficiary on this market, must not exist
+    #[account(
+        seeds = [RESTRICTED_WALLET_PDA_SEED.as_bytes(), market.key().as_ref(), new_beneficiary.key().as_ref()],
+        bump,
+        constraint = restricted_wallet.data_is_empty() @ TokenMillError::WalletRestricted
+    )]
+    pub restricted_wallet: UncheckedAccount<'info>,
+
+    pub base_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = base_token_program
+    )]
+    pub market_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = stake_position.user,
+        associated_token::token_program = base_token_program
+    )]
+    pub user_base_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Current beneficiary of the plan, or its funder if the plan is revocable
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Reassigns the tokens of a vesting plan that haven't been released yet to `new_beneficiary`, e.g. when a team
+/// member leaves or a treasury migrates. The tokens vested so far are released to the current beneficiary first, and
+/// the rest keeps vesting on the same schedule through the stake position of the new beneficiary, created if needed.
+/// Revocable plans can also be transferred by their funder. Plans with a lock certificate can't be transferred.
+pub fn handler(ctx: Context<TransferVestingPlan>) -> Result<()> {
+    let staking = &mut ctx.accounts.staking;
+    let stake_position = &mut ctx.accounts.stake_position;
+    let new_stake_position = &mut ctx.accounts.new_stake_position;
+    let vesting_plan = &mut ctx.accounts.vesting_plan;
+    let authority = ctx.accounts.authority.key();
+
+    require!(
+        authority == stake_position.user
+            || (vesting_plan.revocable && authority == vesting_plan.funder),
+        TokenMillError::InvalidAuthority
+    );
+
+    if new_stake_position.market == Pubkey::default() {
+        new_stake_position.initialize(
+            ctx.accounts.market.key(),
+            ctx.accounts.new_beneficiary.key(),
+        )?;
+    }
+
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let (amount_released, amount_transferred, market_bump) = {
+        let market = &mut ctx.accounts.market.load_mut()?;
+
+        let amount_released = vesting_plan.release(market, current_time)?;
+        let amount_transferred = vesting_plan.amount_vested - vesting_plan.amount_released;
+
+        staking_manager::withdraw_vested(
+            market,
+            staking,
+            stake_position,
+            amount_released + amount_transferred,
+            current_time,
+        )?;
+        staking_manager::deposit_vested(
+            market,
+            staking,
+            new_stake_position,
+            amount_transferred,
+            current_time,
+        )?;
+
+        (amount_released, amount_transferred, market.bump)
+    };
+
+    vesting_plan.stake_position = new_stake_position.key();
+
+    if amount_released > 0 {
+        let base_token_mint = &ctx.accounts.base_token_mint;
+        let base_token_mint_key = base_token_mint.key();
+        let seeds = [
+            MARKET_PDA_SEED.as_bytes(),
+            base_token_mint_key.as_ref(),
+            &[market_bump],
+        ];
+
+        transfer_from_pda(
+            base_token_mint,
+            ctx.accounts.market.to_account_info(),
+            &ctx.accounts.market_base_token_ata,
+            &ctx.accounts.user_base_token_ata,
+            &ctx.accounts.base_token_program,
+            amount_released,
+            &seeds,
+        )?;
+    }
+
+    emit_cpi!(TokenMillVestingPlanTransferEvent {
+        vesting_plan: vesting_plan.key(),
+        from: stake_position.user,
+        to: ctx.accounts.new_beneficiary.key(),
+        amount_released,
+        amount_transferred,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateCreatorVestingPlanAction, ReleaseAction, SwapAction,
+            TokenMillEnv, TransferVestingPlanAction,
+        },
+        make_address, SwapAmountType, SwapType,
+    };
+
+    use crate::{errors::TokenMillError, StakePosition, VestingPlan};
+
+    const VESTING_AMOUNT: u64 = 1_000_000_000;
+    const START: i64 = 333;
+    const VESTING_DURATION: i64 = 300;
+    const CLIFF_DURATION: i64 = 60;
+
+    fn setup_env(revocable: bool) -> (TokenMillEnv, TransferVestingPlanAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(0);
+
+        testing_env.svm.warp(START);
+
+        let alice = testing_env.svm.change_payer("alice");
+
+        testing_env
+            .svm
+            .execute_actions(&[SwapAction::new(
+                &testing_env,
+                SwapType::Buy,
+                SwapAmountType::ExactOutput,
+                VESTING_AMOUNT,
+                u64::MAX,
+                None,
+            )
+            .with_user(alice)])
+            .unwrap();
+
+        let create_action = CreateCreatorVestingPlanAction::new(
+            &testing_env,
+            make_address("carol"),
+            VESTING_AMOUNT,
+            START,
+            VESTING_DURATION,
+            CLIFF_DURATION,
+            revocable,
+        );
+
+        testing_env.svm.execute_actions(&[&create_action]).unwrap();
+
+        testing_env.svm.change_payer("carol");
+
+        let action = TransferVestingPlanAction::new(&create_action, make_address("dave"));
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn transfer_vesting_plan() {
+        let (mut testing_env, action) = setup_env(false);
+
+        testing_env.svm.warp(VESTING_DURATION / 2);
+
+        let base_token_mint = testing_env.base_token_mint.unwrap();
+        let carol_balance_before = testing_env
+            .svm
+            .get_balance(&base_token_mint, &make_address("carol"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let vesting_plan = testing_env
+            .svm
+            .get_parsed_account::<VestingPlan>(&action.vesting_plan);
+        let carol_stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.stake_position);
+        let dave_stake_position = testing_env
+            .svm
+            .get_parsed_account::<StakePosition>(&action.new_stake_position);
+
+        // The vested half went to carol, the rest vests to dave
+        assert_eq!(vesting_plan.stake_position, action.new_stake_position);
+        assert_eq!(carol_stake_position.total_amount_vested, 0);
+        assert_eq!(dave_stake_position.user, make_address("dave"));
+        assert_eq!(dave_stake_position.total_amount_vested, VESTING_AMOUNT / 2);
+        assert_eq!(
+            testing_env
+                .svm
+                .get_balance(&base_token_mint, &make_address("carol")),
+            carol_balance_before + VESTING_AMOUNT / 2
+        );
+
+        testing_env.svm.warp(VESTING_DURATION);
+        testing_env.svm.change_payer("dave");
+
+        let mut release_action = ReleaseAction::new();
+        release_action.vesting_plan = action.vesting_plan;
+        release_action.with_user(make_address("dave"));
+
+        testing_env.svm.execute_actions(&[&release_action]).unwrap();
+
+        let vesting_plan = testing_env
+            .svm
+            .get_parsed_account::<VestingPlan>(&action.vesting_plan);
+
+        assert!(vesting_plan.is_fully_released());
+    }
+
+    #[test]
+    fn transfer_vesting_plan_by_funder() {
+        let (mut testing_env, mut action) = setup_env(true);
+
+        action.signer = testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert!(result.is_ok());
+
+        let vesting_plan = testing_env
+            .svm
+            .get_parsed_account::<VestingPlan>(&action.vesting_plan);
+
+        assert_eq!(vesting_plan.stake_position, action.new_stake_position);
+    }
+
+    #[test]
+    fn transfer_irrevocable_vesting_plan_by_funder() {
+        let (mut testing_env, mut action) = setup_env(false);
+
+        action.signer = testing_env.svm.change_payer("alice");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+
+    #[test]
+    fn transfer_vesting_plan_with_invalid_signer() {
+        let (mut testing_env, mut action) = setup_env(true);
+
+        action.signer = testing_env.svm.change_payer("mallory");
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidAuthority
+        );
+    }
+
+    #[test]
+    fn transfer_vesting_plan_to_beneficiary() {
+        let (mut testing_env, mut action) = setup_env(false);
+
+        action.with_new_beneficiary(make_address("carol"));
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidStakePosition
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::{
+    errors::TokenMillError, events::TokenMillVestingPlanUpgradeEvent,
+    instructions::upgrade_config::grow_account, state::VestingPlan,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpgradeVestingPlan<'info> {
+    /// CHECK: Vesting plan of the first deployment, checked by the handler as it doesn't deserialize yet
+    #[account(mut, owner = crate::ID @ TokenMillError::InvalidVestingPlan)]
+    pub vesting_plan: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a vesting plan created by the first deployment to the current layout, the payer topping up its rent.
+/// The fields added since then are appended, so the zeroed bytes deserialize to their default values: an irrevocable
+/// plan without funder nor milestones, vesting linearly as before. Permissionless, the plan keeping its stake position
+pub fn handler(ctx: Context<UpgradeVestingPlan>) -> Result<()> {
+    let vesting_plan_info = ctx.accounts.vesting_plan.to_account_info();
+    let new_len = 8 + VestingPlan::INIT_SPACE;
+
+    {
+        let data = vesting_plan_info.try_borrow_data()?;
+
+        require!(
+            data.len() >= 8 && data.len() < new_len && data[..8] == VestingPlan::DISCRIMINATOR,
+            TokenMillError::InvalidVestingPlan
+        );
+    }
+
+    grow_account(
+        &vesting_plan_info,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        new_len,
+    )?;
+
+    let vesting_plan =
+        VestingPlan::try_deserialize(&mut &vesting_plan_info.try_borrow_data()?[..])?;
+
+    emit_cpi!(TokenMillVestingPlanUpgradeEvent {
+        vesting_plan: ctx.accounts.vesting_plan.key(),
+        stake_position: vesting_plan.stake_position,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+    use joelana_test_utils::joelana_env::{
+        actions::token_mill::{
+            tm_parse_error, CreateVestingPlanAction, GarbageCollectAction, ReleaseAction,
+            TokenMillEnv, UpgradeVestingPlanAction,
+        },
+        TokenMillError,
+    };
+
+    use crate::{
+        state::{VestingMilestone, MAX_VESTING_MILESTONES},
+        VestingPlan,
+    };
+
+    const VESTING_AMOUNT: u64 = 1_000_000_000;
+    const STARTING_SLOT: i64 = 333;
+    const VESTING_DURATION: i64 = 300;
+    const CLIFF_DURATION: i64 = 60;
+
+    fn setup_env() -> (TokenMillEnv, UpgradeVestingPlanAction) {
+        let mut testing_env = TokenMillEnv::default().with_staking(VESTING_AMOUNT);
+
+        testing_env.svm.warp(STARTING_SLOT);
+
+        testing_env.svm.change_payer("bob");
+
+        let create_vesting_action = CreateVestingPlanAction::new(
+            VESTING_AMOUNT,
+            STARTING_SLOT,
+            VESTING_DURATION,
+            CLIFF_DURATION,
+        );
+
+        testing_env
+            .svm
+            .execute_actions(&[&create_vesting_action])
+            .unwrap();
+
+        testing_env.set_legacy_vesting_plan(&create_vesting_action.vesting_plan);
+
+        // Anyone can pay for the upgrade
+        testing_env.svm.change_payer("carol");
+
+        let action = UpgradeVestingPlanAction::new(create_vesting_action.vesting_plan);
+
+        (testing_env, action)
+    }
+
+    #[test]
+    fn upgrade_vesting_plan() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.change_payer("bob");
+
+        // The baseline layout doesn't deserialize, so the plan can't be released until it's upgraded
+        let release_action = ReleaseAction::new();
+
+        assert!(testing_env.svm.execute_actions(&[&release_action]).is_err());
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let vesting_plan = testing_env
+            .svm
+            .get_parsed_account::<VestingPlan>(&action.vesting_plan);
+
+        assert_eq!(vesting_plan.amount_vested, VESTING_AMOUNT);
+        assert_eq!(vesting_plan.start, STARTING_SLOT);
+        assert_eq!(vesting_plan.funder, Pubkey::default());
+        assert!(!vesting_plan.revocable);
+        assert_eq!(
+            vesting_plan.milestones,
+            [VestingMilestone::default(); MAX_VESTING_MILESTONES]
+        );
+
+        testing_env.svm.warp(VESTING_DURATION);
+
+        testing_env.svm.execute_actions(&[&release_action]).unwrap();
+
+        let vesting_plan = testing_env
+            .svm
+            .get_parsed_account::<VestingPlan>(&action.vesting_plan);
+
+        assert_eq!(vesting_plan.amount_released, VESTING_AMOUNT);
+
+        // Without funder, the rent of the released plan goes back to the owner of the stake position
+        let mut garbage_collect_action = GarbageCollectAction::new(&testing_env);
+
+        garbage_collect_action.with_vesting_plan(action.vesting_plan, crate::ID);
+
+        testing_env.svm.change_payer("dave");
+
+        testing_env
+            .svm
+            .execute_actions(&[&garbage_collect_action])
+            .unwrap();
+
+        assert!(testing_env
+            .svm
+            .try_get_account(&action.vesting_plan)
+            .is_none());
+    }
+
+    #[test]
+    fn upgrade_vesting_plan_twice() {
+        let (mut testing_env, action) = setup_env();
+
+        testing_env.svm.execute_actions(&[&action]).unwrap();
+
+        let result = testing_env.svm.execute_actions(&[&action]);
+
+        assert_eq!(
+            tm_parse_error(result).unwrap(),
+            TokenMillError::InvalidVestingPlan
+        );
+    }
+}
@@ -1,19 +1,35 @@
+#![cfg_attr(not(any(feature = "program", test)), no_std)]
+
+// Without the `program` feature, only the constants and the curve math are built, for off-chain use
+#[cfg(feature = "program")]
 use anchor_lang::prelude::*;
 
+#[cfg(feature = "program")]
 declare_id!("JoeaRXgtME3jAoz5WuFXGEndfv4NPH9nBxsLq44hk9J");
 
 pub mod constant;
+#[cfg(feature = "program")]
 pub mod errors;
-mod events;
+#[cfg(feature = "program")]
+pub mod events;
+#[cfg(feature = "program")]
 mod instructions;
+#[cfg(feature = "program")]
 pub mod manager;
-mod math;
+pub mod math;
+#[cfg(feature = "program")]
 pub mod state;
 
+#[cfg(feature = "program")]
 use instructions::*;
-use manager::swap_manager::{SwapAmountType, SwapType};
+#[cfg(feature = "program")]
+use manager::curve_manager::PricePreset;
+#[cfg(feature = "program")]
+use manager::swap_manager::{SwapAmountType, SwapOptions, SwapQuote, SwapType};
+#[cfg(feature = "program")]
 use state::*;
 
+#[cfg(feature = "program")]
 #[program]
 pub mod token_mill {
     use super::*;
@@ -54,20 +70,101 @@ pub mod token_mill {
         )
     }
 
+    pub fn create_market_with_existing_mint(
+        ctx: Context<CreateMarketWithExistingMint>,
+        total_supply: u64,
+        initial_deposit: u64,
+        creator_fee_share: u16,
+        staking_fee_share: u16,
+    ) -> Result<()> {
+        instructions::create_market_with_existing_mint::handler(
+            ctx,
+            total_supply,
+            initial_deposit,
+            creator_fee_share,
+            staking_fee_share,
+        )
+    }
+
+    pub fn create_protocol_stats(ctx: Context<CreateProtocolStats>) -> Result<()> {
+        instructions::create_protocol_stats::handler(ctx)
+    }
+
+    pub fn create_market_oracle(ctx: Context<CreateMarketOracle>) -> Result<()> {
+        instructions::create_market_oracle::handler(ctx)
+    }
+
     pub fn set_market_prices(
-        ctx: Context<MarketSettingsUpdate>,
-        bid_prices: [u64; constant::PRICES_LENGTH],
-        ask_prices: [u64; constant::PRICES_LENGTH],
+        ctx: Context<SetMarketPrices>,
+        bid_prices: Vec<u64>,
+        ask_prices: Vec<u64>,
     ) -> Result<()> {
         instructions::set_market_prices::handler(ctx, bid_prices, ask_prices)
     }
 
+    pub fn set_prices_from_preset(
+        ctx: Context<SetMarketPrices>,
+        preset: PricePreset,
+        prices_length: u8,
+        min_price: u64,
+        max_price: u64,
+        spread_bps: u16,
+    ) -> Result<()> {
+        instructions::set_prices_from_preset::handler(
+            ctx,
+            preset,
+            prices_length,
+            min_price,
+            max_price,
+            spread_bps,
+        )
+    }
+
+    pub fn update_prices(
+        ctx: Context<SetMarketPrices>,
+        bid_prices: Vec<u64>,
+        ask_prices: Vec<u64>,
+    ) -> Result<()> {
+        instructions::update_prices::handler(ctx, bid_prices, ask_prices)
+    }
+
+    pub fn raise_tail_prices(ctx: Context<SetMarketPrices>, ask_prices: Vec<u64>) -> Result<()> {
+        instructions::raise_tail_prices::handler(ctx, ask_prices)
+    }
+
+    pub fn validate_create_market(
+        ctx: Context<ValidateCreateMarket>,
+        total_supply: u64,
+        creator_fee_share: u16,
+        staking_fee_share: u16,
+        bid_prices: Vec<u64>,
+        ask_prices: Vec<u64>,
+    ) -> Result<()> {
+        instructions::validate_create_market::handler(
+            ctx,
+            total_supply,
+            creator_fee_share,
+            staking_fee_share,
+            bid_prices,
+            ask_prices,
+        )
+    }
+
+    pub fn validate_market_prices(
+        ctx: Context<SetMarketPrices>,
+        bid_prices: Vec<u64>,
+        ask_prices: Vec<u64>,
+    ) -> Result<()> {
+        instructions::validate_market_prices::handler(ctx, bid_prices, ask_prices)
+    }
+
     pub fn swap(
         ctx: Context<Swap>,
         swap_type: SwapType,
         swap_amount_type: SwapAmountType,
         amount: u64,
         other_amount_threshold: u64,
+        options: SwapOptions,
     ) -> Result<(u64, u64)> {
         instructions::swap::handler(
             ctx,
@@ -75,31 +172,413 @@ pub mod token_mill {
             swap_amount_type,
             amount,
             other_amount_threshold,
+            options,
+        )
+    }
+
+    pub fn swap_route<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+        amount: u64,
+        min_amount_out: u64,
+    ) -> Result<(u64, u64)> {
+        instructions::swap_route::handler(ctx, amount, min_amount_out)
+    }
+
+    pub fn buy_basket<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+        amount: u64,
+        weights: Vec<u16>,
+        min_amount_out: u64,
+    ) -> Result<(u64, u64)> {
+        instructions::buy_basket::handler(ctx, amount, weights, min_amount_out)
+    }
+
+    pub fn get_invariants(ctx: Context<GetInvariants>) -> Result<MarketInvariants> {
+        instructions::get_invariants::handler(ctx)
+    }
+
+    pub fn quote_swap(
+        ctx: Context<QuoteSwap>,
+        swap_type: SwapType,
+        swap_amount_type: SwapAmountType,
+        amount: u64,
+        interface_fee_bps: u16,
+        with_referral: bool,
+    ) -> Result<SwapQuote> {
+        instructions::quote_swap::handler(
+            ctx,
+            swap_type,
+            swap_amount_type,
+            amount,
+            interface_fee_bps,
+            with_referral,
+        )
+    }
+
+    pub fn simulate_market(
+        ctx: Context<SimulateMarket>,
+        sizes: Vec<u64>,
+    ) -> Result<MarketSimulation> {
+        instructions::simulate_market::handler(ctx, sizes)
+    }
+
+    pub fn get_market_depth(
+        ctx: Context<GetMarketDepth>,
+        target_price: u64,
+        sizes: Vec<u64>,
+    ) -> Result<MarketDepth> {
+        instructions::get_market_depth::handler(ctx, target_price, sizes)
+    }
+
+    pub fn sell_and_burn(
+        ctx: Context<SellAndBurn>,
+        swap_amount_type: SwapAmountType,
+        amount: u64,
+        other_amount_threshold: u64,
+    ) -> Result<(u64, u64)> {
+        instructions::sell_and_burn::handler(ctx, swap_amount_type, amount, other_amount_threshold)
+    }
+
+    pub fn buyback_and_burn(ctx: Context<BuybackAndBurn>) -> Result<(u64, u64)> {
+        instructions::buyback_and_burn::handler(ctx)
+    }
+
+    // Graduation
+    pub fn graduate_market(ctx: Context<GraduateMarket>) -> Result<(u64, u64)> {
+        instructions::graduate_market::handler(ctx)
+    }
+
+    pub fn complete_graduation<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CompleteGraduation<'info>>,
+        pool_creation_lamports: u64,
+    ) -> Result<Pubkey> {
+        instructions::complete_graduation::handler(ctx, pool_creation_lamports)
+    }
+
+    pub fn buy_secondary_curve(
+        ctx: Context<BuySecondaryCurve>,
+        base_amount: u64,
+        max_quote_amount: u64,
+    ) -> Result<u64> {
+        instructions::buy_secondary_curve::handler(ctx, base_amount, max_quote_amount)
+    }
+
+    // Firm quotes
+    pub fn post_firm_quote(
+        ctx: Context<PostFirmQuote>,
+        swap_type: SwapType,
+        base_amount: u64,
+        price: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        instructions::firm_quotes::post_firm_quote::handler(
+            ctx,
+            swap_type,
+            base_amount,
+            price,
+            expiry,
+        )
+    }
+
+    pub fn cancel_firm_quote(ctx: Context<CancelFirmQuote>) -> Result<()> {
+        instructions::firm_quotes::cancel_firm_quote::handler(ctx)
+    }
+
+    pub fn fill_firm_quote(
+        ctx: Context<FillFirmQuote>,
+        base_amount: u64,
+        other_amount_threshold: u64,
+    ) -> Result<(u64, u64)> {
+        instructions::firm_quotes::fill_firm_quote::handler(
+            ctx,
+            base_amount,
+            other_amount_threshold,
+        )
+    }
+
+    // Swap delegates
+    pub fn create_swap_delegate(
+        ctx: Context<CreateSwapDelegate>,
+        quote_allowance: u64,
+        base_allowance: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        instructions::delegation::create_swap_delegate::handler(
+            ctx,
+            quote_allowance,
+            base_allowance,
+            expiry,
         )
     }
 
+    pub fn revoke_swap_delegate(ctx: Context<RevokeSwapDelegate>) -> Result<()> {
+        instructions::delegation::revoke_swap_delegate::handler(ctx)
+    }
+
+    pub fn delegated_swap(
+        ctx: Context<DelegatedSwap>,
+        swap_type: SwapType,
+        swap_amount_type: SwapAmountType,
+        amount: u64,
+        other_amount_threshold: u64,
+    ) -> Result<(u64, u64)> {
+        instructions::delegation::delegated_swap::handler(
+            ctx,
+            swap_type,
+            swap_amount_type,
+            amount,
+            other_amount_threshold,
+        )
+    }
+
+    // DCA
+    pub fn create_dca_position(
+        ctx: Context<CreateDcaPosition>,
+        amount_per_buy: u64,
+        crank_bounty: u64,
+        interval: i64,
+        max_price: u64,
+        buys: u32,
+    ) -> Result<()> {
+        instructions::dca::create_dca_position::handler(
+            ctx,
+            amount_per_buy,
+            crank_bounty,
+            interval,
+            max_price,
+            buys,
+        )
+    }
+
+    pub fn execute_dca_buy(ctx: Context<ExecuteDcaBuy>) -> Result<(u64, u64)> {
+        instructions::dca::execute_dca_buy::handler(ctx)
+    }
+
+    pub fn close_dca_position(ctx: Context<CloseDcaPosition>) -> Result<()> {
+        instructions::dca::close_dca_position::handler(ctx)
+    }
+
+    // Trader profiles
+    pub fn create_trader_profile(ctx: Context<CreateTraderProfile>) -> Result<()> {
+        instructions::trader_profile::create_trader_profile::handler(ctx)
+    }
+
+    pub fn get_trader_pnl(ctx: Context<GetTraderPnl>) -> Result<TraderPnl> {
+        instructions::trader_profile::get_trader_pnl::handler(ctx)
+    }
+
+    pub fn create_trader_stats(ctx: Context<CreateTraderStats>) -> Result<()> {
+        instructions::trader_profile::create_trader_stats::handler(ctx)
+    }
+
     // Staking
+    #[cfg(feature = "staking")]
     pub fn create_staking(ctx: Context<CreateStaking>) -> Result<()> {
         instructions::staking::create_staking::handler(ctx)
     }
 
+    #[cfg(feature = "staking")]
     pub fn create_stake_position(ctx: Context<CreateStakePosition>) -> Result<()> {
         instructions::staking::create_stake_position::handler(ctx)
     }
 
+    #[cfg(feature = "staking")]
     pub fn deposit(ctx: Context<StakeUpdate>, amount: u64) -> Result<()> {
         instructions::staking::deposit::handler(ctx, amount)
     }
 
+    #[cfg(feature = "staking")]
+    pub fn set_min_stake_duration(
+        ctx: Context<SetMinStakeDuration>,
+        min_stake_duration: i64,
+    ) -> Result<()> {
+        instructions::staking::set_min_stake_duration::handler(ctx, min_stake_duration)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn set_staking_badge_collection(
+        ctx: Context<SetStakingBadgeCollection>,
+        badge_collection: Pubkey,
+        badge_multiplier_bps: u16,
+    ) -> Result<()> {
+        instructions::staking::set_staking_badge_collection::handler(
+            ctx,
+            badge_collection,
+            badge_multiplier_bps,
+        )
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn link_staking_badge(ctx: Context<LinkStakingBadge>) -> Result<()> {
+        instructions::staking::link_staking_badge::handler(ctx)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn stake_for(ctx: Context<StakeFor>, amount: u64, lock_tier: u8) -> Result<()> {
+        instructions::staking::stake_for::handler(ctx, amount, lock_tier)
+    }
+
+    #[cfg(feature = "staking")]
     pub fn withdraw(ctx: Context<StakeUpdate>, amount: u64) -> Result<()> {
         instructions::staking::withdraw::handler(ctx, amount)
     }
 
+    #[cfg(feature = "staking")]
+    pub fn emergency_unstake(ctx: Context<EmergencyUnstake>, amount: u64) -> Result<()> {
+        instructions::staking::emergency_unstake::handler(ctx, amount)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn buy_and_stake(
+        ctx: Context<BuyAndStake>,
+        swap_amount_type: SwapAmountType,
+        amount: u64,
+        other_amount_threshold: u64,
+    ) -> Result<(u64, u64)> {
+        instructions::staking::buy_and_stake::handler(
+            ctx,
+            swap_amount_type,
+            amount,
+            other_amount_threshold,
+        )
+    }
+
+    #[cfg(feature = "staking")]
     pub fn claim_staking_rewards(ctx: Context<StakingRewardsClaim>) -> Result<()> {
         instructions::staking::claim_staking_rewards::handler(ctx)
     }
 
+    #[cfg(feature = "staking")]
+    pub fn set_claim_delegate(
+        ctx: Context<SetClaimDelegate>,
+        claim_delegate: Pubkey,
+    ) -> Result<()> {
+        instructions::staking::set_claim_delegate::handler(ctx, claim_delegate)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn delegated_claim_staking_rewards(
+        ctx: Context<DelegatedStakingRewardsClaim>,
+    ) -> Result<()> {
+        instructions::staking::delegated_claim_staking_rewards::handler(ctx)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn lock_stake_position(ctx: Context<StakeLockupUpdate>, lock_tier: u8) -> Result<()> {
+        instructions::staking::lock_stake_position::handler(ctx, lock_tier)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn extend_stake_position_lock(
+        ctx: Context<StakeLockupUpdate>,
+        lock_tier: u8,
+    ) -> Result<()> {
+        instructions::staking::extend_stake_position_lock::handler(ctx, lock_tier)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn expire_stake_position_lock(ctx: Context<ExpireStakePositionLock>) -> Result<()> {
+        instructions::staking::expire_stake_position_lock::handler(ctx)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn transfer_stake_position(ctx: Context<TransferStakePosition>) -> Result<()> {
+        instructions::staking::transfer_stake_position::handler(ctx)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn upgrade_market_staking(ctx: Context<UpgradeMarketStaking>) -> Result<()> {
+        instructions::staking::upgrade_market_staking::handler(ctx)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn upgrade_stake_position(ctx: Context<UpgradeStakePosition>) -> Result<()> {
+        instructions::staking::upgrade_stake_position::handler(ctx)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn create_compounding_vault(ctx: Context<CreateCompoundingVault>) -> Result<()> {
+        instructions::staking::create_compounding_vault::handler(ctx)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn deposit_to_compounding_vault(
+        ctx: Context<CompoundingVaultUpdate>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::staking::deposit_to_compounding_vault::handler(ctx, amount)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn withdraw_from_compounding_vault(
+        ctx: Context<CompoundingVaultUpdate>,
+        shares: u64,
+    ) -> Result<()> {
+        instructions::staking::withdraw_from_compounding_vault::handler(ctx, shares)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn compound_staking_rewards(ctx: Context<CompoundStakingRewards>) -> Result<()> {
+        instructions::staking::compound_staking_rewards::handler(ctx)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn create_staking_checkpoints(ctx: Context<CreateStakingCheckpoints>) -> Result<()> {
+        instructions::staking::create_staking_checkpoints::handler(ctx)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn checkpoint_staking_epoch(ctx: Context<CheckpointStakingEpoch>) -> Result<()> {
+        instructions::staking::checkpoint_staking_epoch::handler(ctx)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn create_reward_vault(ctx: Context<CreateRewardVault>) -> Result<()> {
+        instructions::staking::create_reward_vault::handler(ctx)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn fund_reward_vault(
+        ctx: Context<FundRewardVault>,
+        amount: u64,
+        duration: i64,
+    ) -> Result<()> {
+        instructions::staking::fund_reward_vault::handler(ctx, amount, duration)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn claim_vault_rewards(ctx: Context<ClaimVaultRewards>) -> Result<()> {
+        instructions::staking::claim_vault_rewards::handler(ctx)
+    }
+
+    // Boost
+    #[cfg(feature = "boost")]
+    pub fn create_boost_pool(ctx: Context<CreateBoostPool>) -> Result<()> {
+        instructions::boost::create_boost_pool::handler(ctx)
+    }
+
+    #[cfg(feature = "boost")]
+    pub fn fund_boost_pool(ctx: Context<FundBoostPool>, amount: u64, duration: i64) -> Result<()> {
+        instructions::boost::fund_boost_pool::handler(ctx, amount, duration)
+    }
+
+    #[cfg(feature = "boost")]
+    pub fn lock_stake(ctx: Context<LockStake>, amount: u64, duration: i64) -> Result<()> {
+        instructions::boost::lock_stake::handler(ctx, amount, duration)
+    }
+
+    #[cfg(feature = "boost")]
+    pub fn unlock_stake(ctx: Context<UnlockStake>) -> Result<()> {
+        instructions::boost::unlock_stake::handler(ctx)
+    }
+
+    #[cfg(feature = "boost")]
+    pub fn claim_boost_rewards(ctx: Context<ClaimBoostRewards>) -> Result<()> {
+        instructions::boost::claim_boost_rewards::handler(ctx)
+    }
+
     // Vesting
+    #[cfg(feature = "vesting")]
     pub fn create_vesting_plan(
         ctx: Context<CreateVestingPlan>,
         start: i64,
@@ -116,11 +595,80 @@ pub mod token_mill {
         )
     }
 
+    #[cfg(feature = "vesting")]
+    pub fn create_creator_vesting_plan(
+        ctx: Context<CreateCreatorVestingPlan>,
+        start: i64,
+        vesting_amount: u64,
+        vesting_duration: i64,
+        cliff_duration: i64,
+        revocable: bool,
+        milestones: [VestingMilestone; MAX_VESTING_MILESTONES],
+    ) -> Result<()> {
+        instructions::vesting::create_creator_vesting_plan::handler(
+            ctx,
+            start,
+            vesting_amount,
+            vesting_duration,
+            cliff_duration,
+            revocable,
+            milestones,
+        )
+    }
+
+    #[cfg(feature = "vesting")]
+    pub fn dev_buy_and_vest(
+        ctx: Context<DevBuyAndVest>,
+        swap_amount_type: SwapAmountType,
+        amount: u64,
+        other_amount_threshold: u64,
+        vesting_duration: i64,
+        cliff_duration: i64,
+    ) -> Result<(u64, u64)> {
+        instructions::vesting::dev_buy_and_vest::handler(
+            ctx,
+            swap_amount_type,
+            amount,
+            other_amount_threshold,
+            vesting_duration,
+            cliff_duration,
+        )
+    }
+
+    #[cfg(feature = "vesting")]
     pub fn release(ctx: Context<Release>) -> Result<()> {
         instructions::vesting::release::handler(ctx)
     }
 
+    #[cfg(feature = "vesting")]
+    pub fn release_all<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReleaseAll<'info>>,
+    ) -> Result<u64> {
+        instructions::vesting::release_all::handler(ctx)
+    }
+
+    #[cfg(feature = "vesting")]
+    pub fn revoke_vesting_plan(ctx: Context<RevokeVestingPlan>) -> Result<()> {
+        instructions::vesting::revoke_vesting_plan::handler(ctx)
+    }
+
+    #[cfg(feature = "vesting")]
+    pub fn transfer_vesting_plan(ctx: Context<TransferVestingPlan>) -> Result<()> {
+        instructions::vesting::transfer_vesting_plan::handler(ctx)
+    }
+
+    #[cfg(feature = "vesting")]
+    pub fn upgrade_vesting_plan(ctx: Context<UpgradeVestingPlan>) -> Result<()> {
+        instructions::vesting::upgrade_vesting_plan::handler(ctx)
+    }
+
+    #[cfg(feature = "vesting")]
+    pub fn issue_vesting_lock_certificate(ctx: Context<IssueVestingLockCertificate>) -> Result<()> {
+        instructions::vesting::issue_vesting_lock_certificate::handler(ctx)
+    }
+
     // Referrals
+    #[cfg(feature = "referrals")]
     pub fn create_referral_account(
         ctx: Context<CreateReferralAccount>,
         referrer: Pubkey,
@@ -128,6 +676,12 @@ pub mod token_mill {
         instructions::referrals::create_referral_account::handler(ctx, referrer)
     }
 
+    #[cfg(feature = "referrals")]
+    pub fn register_referral_code(ctx: Context<RegisterReferralCode>, code: String) -> Result<()> {
+        instructions::referrals::register_referral_code::handler(ctx, code)
+    }
+
+    #[cfg(feature = "referrals")]
     pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>) -> Result<()> {
         instructions::referrals::claim_referral_fees::handler(ctx)
     }
@@ -137,8 +691,19 @@ pub mod token_mill {
         instructions::update_creator::handler(ctx, new_creator)
     }
 
-    pub fn update_market_fee_shares(
+    pub fn propose_creator(
         ctx: Context<MarketSettingsUpdate>,
+        pending_creator: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::propose_creator::handler(ctx, pending_creator)
+    }
+
+    pub fn accept_creator(ctx: Context<AcceptCreator>) -> Result<()> {
+        instructions::accept_creator::handler(ctx)
+    }
+
+    pub fn update_market_fee_shares(
+        ctx: Context<MarketFeeSharesUpdate>,
         new_creator_fee_share: u16,
         new_staking_fee_share: u16,
     ) -> Result<()> {
@@ -149,10 +714,154 @@ pub mod token_mill {
         )
     }
 
-    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+    pub fn activate_market_fee_shares(ctx: Context<ActivateMarketFeeShares>) -> Result<()> {
+        instructions::activate_market_fee_shares::handler(ctx)
+    }
+
+    pub fn set_max_raise(ctx: Context<MarketSettingsUpdate>, max_raise: u64) -> Result<()> {
+        instructions::set_max_raise::handler(ctx, max_raise)
+    }
+
+    pub fn set_virtual_supply(
+        ctx: Context<MarketSettingsUpdate>,
+        virtual_supply: u64,
+    ) -> Result<()> {
+        instructions::set_virtual_supply::handler(ctx, virtual_supply)
+    }
+
+    pub fn set_graduation_supply(
+        ctx: Context<MarketSettingsUpdate>,
+        graduation_supply: u64,
+    ) -> Result<()> {
+        instructions::set_graduation_supply::handler(ctx, graduation_supply)
+    }
+
+    pub fn set_secondary_curve(ctx: Context<MarketSettingsUpdate>, price: u64) -> Result<()> {
+        instructions::set_secondary_curve::handler(ctx, price)
+    }
+
+    pub fn set_impact_fee(
+        ctx: Context<MarketSettingsUpdate>,
+        max_impact_fee_bps: u16,
+        impact_fee_threshold_bps: u16,
+        impact_fee_rate_bps: u16,
+    ) -> Result<()> {
+        instructions::set_impact_fee::handler(
+            ctx,
+            max_impact_fee_bps,
+            impact_fee_threshold_bps,
+            impact_fee_rate_bps,
+        )
+    }
+
+    pub fn set_launch_fee(
+        ctx: Context<MarketSettingsUpdate>,
+        launch_fee_bps: u16,
+        duration: u32,
+        recipient: LaunchFeeRecipient,
+    ) -> Result<()> {
+        instructions::set_launch_fee::handler(ctx, launch_fee_bps, duration, recipient)
+    }
+
+    pub fn pause_sells(ctx: Context<MarketSettingsUpdate>, duration: i64) -> Result<()> {
+        instructions::pause_sells::handler(ctx, duration)
+    }
+
+    pub fn set_incentive_weights(
+        ctx: Context<MarketSettingsUpdate>,
+        buy_weight: u16,
+        sell_weight: u16,
+        stake_weight: u16,
+    ) -> Result<()> {
+        instructions::set_incentive_weights::handler(ctx, buy_weight, sell_weight, stake_weight)
+    }
+
+    pub fn set_max_slot_price_move(
+        ctx: Context<MarketSettingsUpdate>,
+        max_slot_price_move_bps: u16,
+    ) -> Result<()> {
+        instructions::set_max_slot_price_move::handler(ctx, max_slot_price_move_bps)
+    }
+
+    pub fn claim_creator_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimCreatorFees<'info>>,
+    ) -> Result<()> {
         instructions::claim_creator_fees::handler(ctx)
     }
 
+    pub fn claim_all_creator_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimAllCreatorFees<'info>>,
+    ) -> Result<u64> {
+        instructions::claim_all_creator_fees::handler(ctx)
+    }
+
+    pub fn propose_close_market(ctx: Context<ProposeCloseMarket>) -> Result<()> {
+        instructions::propose_close_market::handler(ctx)
+    }
+
+    pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
+        instructions::close_market::handler(ctx)
+    }
+
+    pub fn set_burn_fee_share(
+        ctx: Context<MarketSettingsUpdate>,
+        burn_fee_share: u16,
+    ) -> Result<()> {
+        instructions::set_burn_fee_share::handler(ctx, burn_fee_share)
+    }
+
+    pub fn set_creator_fee_split(
+        ctx: Context<SetCreatorFeeSplit>,
+        recipients: [CreatorFeeRecipient; CREATOR_FEE_RECIPIENTS_LENGTH],
+    ) -> Result<()> {
+        instructions::set_creator_fee_split::handler(ctx, recipients)
+    }
+
+    pub fn deposit_additional_supply(
+        ctx: Context<DepositAdditionalSupply>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::deposit_additional_supply::handler(ctx, amount)
+    }
+
+    pub fn import_migration(
+        ctx: Context<ImportMigration>,
+        source: Pubkey,
+        snapshot_slot: u64,
+        holders_root: [u8; 32],
+        claim_supply: u64,
+    ) -> Result<()> {
+        instructions::migration::import_migration::handler(
+            ctx,
+            source,
+            snapshot_slot,
+            holders_root,
+            claim_supply,
+        )
+    }
+
+    pub fn claim_migration(
+        ctx: Context<ClaimMigration>,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::migration::claim_migration::handler(ctx, amount, proof)
+    }
+
+    // Maintenance
+    #[cfg(feature = "staking")]
+    pub fn garbage_collect(ctx: Context<GarbageCollect>) -> Result<()> {
+        instructions::garbage_collect::handler(ctx)
+    }
+
+    pub fn garbage_collect_firm_quote(ctx: Context<GarbageCollectFirmQuote>) -> Result<()> {
+        instructions::firm_quotes::garbage_collect_firm_quote::handler(ctx)
+    }
+
+    pub fn create_change_log(ctx: Context<CreateChangeLog>) -> Result<()> {
+        instructions::create_change_log::handler(ctx)
+    }
+
     // Admin instructions
     pub fn create_quote_asset_badge(ctx: Context<CreateQuoteAssetBadge>) -> Result<()> {
         instructions::create_quote_asset_badge::handler(ctx)
@@ -165,6 +874,14 @@ pub mod token_mill {
         instructions::update_quote_asset_badge::handler(ctx, status)
     }
 
+    pub fn create_market_maker_badge(ctx: Context<CreateMarketMakerBadge>) -> Result<()> {
+        instructions::create_market_maker_badge::handler(ctx)
+    }
+
+    pub fn revoke_market_maker_badge(ctx: Context<RevokeMarketMakerBadge>) -> Result<()> {
+        instructions::revoke_market_maker_badge::handler(ctx)
+    }
+
     pub fn update_default_fee_shares(
         ctx: Context<ConfigUpdate>,
         new_default_protocol_fee_share: u16,
@@ -177,11 +894,191 @@ pub mod token_mill {
         )
     }
 
-    pub fn update_protocol_fee_recipient(
+    pub fn set_quote_fee_override(
+        ctx: Context<ConfigUpdate>,
+        quote_token_mint: Pubkey,
+        protocol_fee_share: u16,
+        referral_fee_share: u16,
+        creator_fee_share: u16,
+        staking_fee_share: u16,
+    ) -> Result<()> {
+        instructions::set_quote_fee_override::handler(
+            ctx,
+            quote_token_mint,
+            protocol_fee_share,
+            referral_fee_share,
+            creator_fee_share,
+            staking_fee_share,
+        )
+    }
+
+    pub fn remove_quote_fee_override(
+        ctx: Context<ConfigUpdate>,
+        quote_token_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::remove_quote_fee_override::handler(ctx, quote_token_mint)
+    }
+
+    pub fn set_rebate_schedule(
         ctx: Context<ConfigUpdate>,
-        new_protocol_fee_recipient: Pubkey,
+        quote_token_mint: Pubkey,
+        tiers: [RebateTier; REBATE_TIERS_LENGTH],
     ) -> Result<()> {
-        instructions::update_protocol_fee_recipient::handler(ctx, new_protocol_fee_recipient)
+        instructions::set_rebate_schedule::handler(ctx, quote_token_mint, tiers)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn set_staker_discounts(
+        ctx: Context<ConfigUpdate>,
+        protocol_token_market: Pubkey,
+        tiers: [StakerDiscountTier; STAKER_DISCOUNT_TIERS_LENGTH],
+    ) -> Result<()> {
+        instructions::set_staker_discounts::handler(ctx, protocol_token_market, tiers)
+    }
+
+    pub fn set_fee_exemption(
+        ctx: Context<ConfigUpdate>,
+        wallet: Pubkey,
+        fee_discount_bps: u16,
+    ) -> Result<()> {
+        instructions::set_fee_exemption::handler(ctx, wallet, fee_discount_bps)
+    }
+
+    pub fn update_max_interface_fee(
+        ctx: Context<ConfigUpdate>,
+        new_max_interface_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::update_max_interface_fee::handler(ctx, new_max_interface_fee_bps)
+    }
+
+    pub fn update_max_spread(ctx: Context<ConfigUpdate>, new_max_spread_bps: u16) -> Result<()> {
+        instructions::update_max_spread::handler(ctx, new_max_spread_bps)
+    }
+
+    pub fn set_graduation_config(
+        ctx: Context<ConfigUpdate>,
+        graduation_adapter: GraduationAdapter,
+        graduation_program: Pubkey,
+        graduation_pool_config: Pubkey,
+    ) -> Result<()> {
+        instructions::set_graduation_config::handler(
+            ctx,
+            graduation_adapter,
+            graduation_program,
+            graduation_pool_config,
+        )
+    }
+
+    pub fn set_emergency_authority(
+        ctx: Context<ConfigUpdate>,
+        emergency_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::set_emergency_authority::handler(ctx, emergency_authority)
+    }
+
+    pub fn pause_swaps(ctx: Context<PauseSwaps>) -> Result<()> {
+        instructions::pause_swaps::handler(ctx)
+    }
+
+    pub fn unpause_swaps(ctx: Context<ConfigUpdate>) -> Result<()> {
+        instructions::unpause_swaps::handler(ctx)
+    }
+
+    #[cfg(feature = "staking")]
+    pub fn update_emergency_unstake_penalty(
+        ctx: Context<ConfigUpdate>,
+        new_emergency_unstake_penalty_bps: u16,
+    ) -> Result<()> {
+        instructions::update_emergency_unstake_penalty::handler(
+            ctx,
+            new_emergency_unstake_penalty_bps,
+        )
+    }
+
+    pub fn update_fee_distribution_interval(
+        ctx: Context<ConfigUpdate>,
+        new_fee_distribution_interval: u16,
+    ) -> Result<()> {
+        instructions::update_fee_distribution_interval::handler(ctx, new_fee_distribution_interval)
+    }
+
+    pub fn update_fee_shares_update_delay(
+        ctx: Context<ConfigUpdate>,
+        new_fee_shares_update_delay: u32,
+    ) -> Result<()> {
+        instructions::update_fee_shares_update_delay::handler(ctx, new_fee_shares_update_delay)
+    }
+
+    pub fn update_market_close_delay(
+        ctx: Context<ConfigUpdate>,
+        new_market_close_delay: u32,
+    ) -> Result<()> {
+        instructions::update_market_close_delay::handler(ctx, new_market_close_delay)
+    }
+
+    pub fn update_min_market_age(
+        ctx: Context<ConfigUpdate>,
+        new_min_market_age: u32,
+    ) -> Result<()> {
+        instructions::update_min_market_age::handler(ctx, new_min_market_age)
+    }
+
+    pub fn upgrade_config(ctx: Context<UpgradeConfig>) -> Result<()> {
+        instructions::upgrade_config::handler(ctx)
+    }
+
+    pub fn upgrade_market(ctx: Context<UpgradeMarket>) -> Result<()> {
+        instructions::upgrade_market::handler(ctx)
+    }
+
+    pub fn update_firm_quote_fee(
+        ctx: Context<ConfigUpdate>,
+        new_firm_quote_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::update_firm_quote_fee::handler(ctx, new_firm_quote_fee_bps)
+    }
+
+    pub fn propose_fee_recipient(
+        ctx: Context<ConfigUpdate>,
+        pending_protocol_fee_recipient: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::propose_fee_recipient::handler(ctx, pending_protocol_fee_recipient)
+    }
+
+    pub fn accept_fee_recipient(ctx: Context<AcceptFeeRecipient>) -> Result<()> {
+        instructions::accept_fee_recipient::handler(ctx)
+    }
+
+    pub fn override_sell_pause(ctx: Context<MarketAdminUpdate>) -> Result<()> {
+        instructions::override_sell_pause::handler(ctx)
+    }
+
+    pub fn set_market_paused(ctx: Context<MarketAdminUpdate>, paused: bool) -> Result<()> {
+        instructions::set_market_paused::handler(ctx, paused)
+    }
+
+    pub fn set_quote_price_feed(
+        ctx: Context<MarketAdminUpdate>,
+        quote_price_feed: Pubkey,
+        max_quote_depeg_bps: u16,
+    ) -> Result<()> {
+        instructions::set_quote_price_feed::handler(ctx, quote_price_feed, max_quote_depeg_bps)
+    }
+
+    #[cfg(feature = "referrals")]
+    pub fn set_market_referral_fee_share(
+        ctx: Context<MarketAdminUpdate>,
+        referral_fee_share: Option<u16>,
+    ) -> Result<()> {
+        instructions::set_market_referral_fee_share::handler(ctx, referral_fee_share)
+    }
+
+    pub fn restrict_wallet(ctx: Context<RestrictWallet>) -> Result<()> {
+        instructions::restrict_wallet::handler(ctx)
+    }
+
+    pub fn lift_wallet_restriction(ctx: Context<LiftWalletRestriction>) -> Result<()> {
+        instructions::lift_wallet_restriction::handler(ctx)
     }
 
     pub fn transfer_config_ownership(
@@ -0,0 +1,35 @@
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::state::{ChangeLog, ChangeLogParameter};
+
+/// Records a parameter change in the change log of its target.
+/// Change logs are opt-in, nothing is recorded until the change log of the target has been created.
+pub fn record_change(
+    change_log: &AccountInfo,
+    parameter: ChangeLogParameter,
+    old_value: [u8; 32],
+    new_value: [u8; 32],
+) -> Result<()> {
+    if change_log.owner != &crate::ID || change_log.data_is_empty() {
+        return Ok(());
+    }
+
+    let mut data = change_log.try_borrow_mut_data()?;
+
+    require!(
+        data[..8] == ChangeLog::DISCRIMINATOR,
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    let change_log: &mut ChangeLog =
+        bytemuck::from_bytes_mut(&mut data[8..8 + std::mem::size_of::<ChangeLog>()]);
+
+    change_log.record(
+        Clock::get()?.unix_timestamp,
+        parameter,
+        old_value,
+        new_value,
+    );
+
+    Ok(())
+}
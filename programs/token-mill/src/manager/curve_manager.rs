@@ -0,0 +1,273 @@
+use anchor_lang::prelude::*;
+use ruint::aliases::U256;
+
+use crate::{
+    constant::{CURVE_RESOLUTIONS, MAX_BPS, SCALE},
+    errors::TokenMillError,
+    math::{mul_div, Rounding},
+};
+
+/// Shape of the ask prices between the min and max price of a preset curve, `t` being the share of the supply sold
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq)]
+pub enum PricePreset {
+    /// `min + (max - min) * t`
+    Linear,
+    /// `min * (max / min)^t`, every interval multiplies the price by the same ratio
+    Exponential,
+    /// S-curve `min + (max - min) * (3t^2 - 2t^3)`, the smoothstep approximation of a logistic curve
+    Logistic,
+    /// `min / (1 - c * t)^2`, the price of a constant product pool with `c = 1 - sqrt(min / max)`
+    ConstantProduct,
+}
+
+/// Computes the bid and ask prices of a preset curve of `prices_length` prices going from `min_price` to `max_price`.
+/// Bid prices are `spread_bps` below the ask prices.
+pub fn generate_prices(
+    preset: PricePreset,
+    prices_length: usize,
+    min_price: u64,
+    max_price: u64,
+    spread_bps: u16,
+) -> Result<(Vec<u64>, Vec<u64>)> {
+    require!(
+        CURVE_RESOLUTIONS.contains(&prices_length),
+        TokenMillError::InvalidCurveResolution
+    );
+
+    let requires_min_price = matches!(
+        preset,
+        PricePreset::Exponential | PricePreset::ConstantProduct
+    );
+
+    require!(
+        min_price <= max_price
+            && max_price > 0
+            && (min_price > 0 || !requires_min_price)
+            && u64::from(spread_bps) < MAX_BPS,
+        TokenMillError::InvalidPricePreset
+    );
+
+    let interval_number = prices_length as u128 - 1;
+    let min = u128::from(min_price);
+    let max = u128::from(max_price);
+
+    let ask_prices = match preset {
+        PricePreset::Linear => (0..prices_length as u128)
+            .map(|i| {
+                Ok(min
+                    + mul_div(max - min, i, interval_number, Rounding::Down)
+                        .ok_or(TokenMillError::MathError)?)
+            })
+            .collect::<Result<Vec<_>>>()?,
+        PricePreset::Exponential => {
+            let ratio = get_interval_ratio(min, max, interval_number)?;
+            let mut factor = SCALE;
+            let mut prices = Vec::with_capacity(prices_length);
+
+            for i in 0..prices_length {
+                if i > 0 {
+                    factor = mul_div(factor, ratio, SCALE, Rounding::Down)
+                        .ok_or(TokenMillError::MathError)?;
+                }
+
+                prices.push(
+                    mul_div(min, factor, SCALE, Rounding::Down).ok_or(TokenMillError::MathError)?,
+                );
+            }
+
+            prices
+        }
+        PricePreset::Logistic => {
+            let denominator = interval_number.pow(3);
+
+            (0..prices_length as u128)
+                .map(|i| {
+                    let numerator = i * i * (3 * interval_number - 2 * i);
+
+                    Ok(min
+                        + mul_div(max - min, numerator, denominator, Rounding::Down)
+                            .ok_or(TokenMillError::MathError)?)
+                })
+                .collect::<Result<Vec<_>>>()?
+        }
+        PricePreset::ConstantProduct => {
+            let min_max_ratio = mul_div(min, SCALE * SCALE, max, Rounding::Down)
+                .ok_or(TokenMillError::MathError)?;
+            let c = SCALE
+                - u128::try_from(U256::from(min_max_ratio).root(2))
+                    .map_err(|_| TokenMillError::MathError)?;
+            let mut prices = Vec::with_capacity(prices_length);
+
+            for i in 0..prices_length as u128 {
+                let d = SCALE
+                    - mul_div(c, i, interval_number, Rounding::Down)
+                        .ok_or(TokenMillError::MathError)?;
+
+                prices.push(
+                    mul_div(min, SCALE * SCALE, d * d, Rounding::Down)
+                        .ok_or(TokenMillError::MathError)?,
+                );
+            }
+
+            prices
+        }
+    };
+
+    // Rounding can't overshoot the max price, and the curve always ends on it
+    let ask_prices = ask_prices
+        .into_iter()
+        .enumerate()
+        .map(|(i, price)| {
+            if i == prices_length - 1 {
+                max_price
+            } else {
+                price.min(max) as u64
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let bid_prices = ask_prices
+        .iter()
+        .map(|&price| {
+            let bid_price = mul_div(
+                u128::from(price),
+                u128::from(MAX_BPS - u64::from(spread_bps)),
+                u128::from(MAX_BPS),
+                Rounding::Down,
+            )
+            .ok_or(TokenMillError::MathError)?;
+
+            Ok(bid_price as u64)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((bid_prices, ask_prices))
+}
+
+/// Largest ratio, scaled by `SCALE`, whose `interval_number`th power is at most `max / min`
+fn get_interval_ratio(min: u128, max: u128, interval_number: u128) -> Result<u128> {
+    let target = mul_div(max, SCALE, min, Rounding::Down).ok_or(TokenMillError::MathError)?;
+
+    // `max / min <= MAX_PRICE`, so the ratio is below 64 for the 10 intervals of the coarsest curve
+    let mut low = SCALE;
+    let mut high = 64 * SCALE;
+
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+
+        if pow_scaled(mid, interval_number).is_some_and(|power| power <= target) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    Ok(low)
+}
+
+/// `base^exponent` scaled by `SCALE`, `None` if it overflows. `base` has to be at least `SCALE`
+fn pow_scaled(mut base: u128, mut exponent: u128) -> Option<u128> {
+    let mut result = SCALE;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result.checked_mul(base)? / SCALE;
+        }
+
+        exponent >>= 1;
+
+        if exponent > 0 {
+            base = base.checked_mul(base)? / SCALE;
+        }
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constant::{MAX_PRICE, MAX_PRICES_LENGTH, PRICES_LENGTH};
+    use rstest::rstest;
+
+    #[rstest]
+    fn generate_prices(
+        #[values(
+            PricePreset::Linear,
+            PricePreset::Exponential,
+            PricePreset::Logistic,
+            PricePreset::ConstantProduct
+        )]
+        preset: PricePreset,
+        #[values(PRICES_LENGTH, 21, MAX_PRICES_LENGTH)] prices_length: usize,
+        #[values((1, 100), (1_000, 1_000_000), (1, MAX_PRICE), (500, 500))] prices: (u64, u64),
+    ) {
+        let (min_price, max_price) = prices;
+
+        let (bid_prices, ask_prices) =
+            super::generate_prices(preset, prices_length, min_price, max_price, 100).unwrap();
+
+        assert_eq!(ask_prices.len(), prices_length);
+        assert_eq!(bid_prices.len(), prices_length);
+        assert_eq!(ask_prices[0], min_price);
+        assert_eq!(ask_prices[prices_length - 1], max_price);
+
+        for i in 0..prices_length {
+            assert!(bid_prices[i] <= ask_prices[i]);
+            assert_eq!(
+                u128::from(bid_prices[i]),
+                u128::from(ask_prices[i]) * 99 / 100
+            );
+
+            if i > 0 {
+                assert!(ask_prices[i] >= ask_prices[i - 1]);
+                assert!(bid_prices[i] >= bid_prices[i - 1]);
+            }
+        }
+    }
+
+    #[test]
+    fn generate_prices_shapes() {
+        let mid = PRICES_LENGTH / 2;
+
+        let (_, linear) =
+            super::generate_prices(PricePreset::Linear, PRICES_LENGTH, 100, 1_100, 0).unwrap();
+        let (_, exponential) =
+            super::generate_prices(PricePreset::Exponential, PRICES_LENGTH, 100, 10_000, 0)
+                .unwrap();
+        let (_, logistic) =
+            super::generate_prices(PricePreset::Logistic, PRICES_LENGTH, 100, 1_100, 0).unwrap();
+        let (_, constant_product) =
+            super::generate_prices(PricePreset::ConstantProduct, PRICES_LENGTH, 100, 400, 0)
+                .unwrap();
+
+        assert_eq!(linear[mid], 600);
+        // Geometric mean of the min and max price
+        assert!(exponential[mid].abs_diff(1_000) <= 1);
+        // Symmetric around the middle of the curve
+        assert_eq!(logistic[mid], 600);
+        assert_eq!(logistic[1] - logistic[0], logistic[10] - logistic[9]);
+        // `c = 1/2`, so the price is `min / (3/4)^2` halfway through
+        assert!(constant_product[mid].abs_diff(177) <= 1);
+    }
+
+    #[rstest]
+    #[case(PricePreset::Linear, 12, 1, 10, 0)]
+    #[case(PricePreset::Linear, PRICES_LENGTH, 10, 1, 0)]
+    #[case(PricePreset::Linear, PRICES_LENGTH, 0, 0, 0)]
+    #[case(PricePreset::Linear, PRICES_LENGTH, 1, 10, 10_000)]
+    #[case(PricePreset::Exponential, PRICES_LENGTH, 0, 10, 0)]
+    #[case(PricePreset::ConstantProduct, PRICES_LENGTH, 0, 10, 0)]
+    fn generate_prices_with_invalid_parameters(
+        #[case] preset: PricePreset,
+        #[case] prices_length: usize,
+        #[case] min_price: u64,
+        #[case] max_price: u64,
+        #[case] spread_bps: u16,
+    ) {
+        assert!(
+            super::generate_prices(preset, prices_length, min_price, max_price, spread_bps)
+                .is_err()
+        );
+    }
+}
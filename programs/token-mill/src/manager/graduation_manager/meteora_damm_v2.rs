@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use ruint::aliases::U256;
+
+use crate::{errors::TokenMillError, state::GRADUATION_POSITION_PDA_SEED};
+
+use super::{check_account, invoke_pool_program, GraduationLiquidity};
+
+/// Discriminator of the `initialize_pool` instruction of the Meteora DAMM v2 program
+pub const INITIALIZE_POOL_DISCRIMINATOR: [u8; 8] = [95, 180, 10, 172, 84, 174, 232, 40];
+/// Accounts of the `initialize_pool` instruction: creator, position nft mint, position nft account, payer, config,
+/// pool authority, pool, position, token a mint, token b mint, token a vault, token b vault, payer token a,
+/// payer token b, token a program, token b program, token 2022 program, system program, event authority, program
+pub const ACCOUNTS_LEN: usize = 20;
+/// Price range of the pool program, in Q64.64 square root prices. Pools are created on the full range
+pub const MIN_SQRT_PRICE: u128 = 4_295_048_016;
+pub const MAX_SQRT_PRICE: u128 = 79_226_673_521_066_979_257_578_248_091;
+
+#[derive(AnchorSerialize)]
+struct InitializePoolArgs {
+    liquidity: u128,
+    sqrt_price: u128,
+    activation_point: Option<u64>,
+}
+
+/// Creates a Meteora DAMM v2 pool with the liquidity, the base token being token a.
+/// The graduation authority pays for the pool and owns its position, whose NFT mint is a PDA of the market.
+/// The config must be a full range one, and the rounding of the liquidity can leave dust in the token accounts
+pub fn create_pool<'info>(
+    pool_program: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    liquidity: &GraduationLiquidity,
+) -> Result<Pubkey> {
+    require!(
+        accounts.len() == ACCOUNTS_LEN,
+        TokenMillError::InvalidGraduationAccounts
+    );
+
+    let (position_nft_mint, position_nft_mint_bump) = Pubkey::find_program_address(
+        &[
+            GRADUATION_POSITION_PDA_SEED.as_bytes(),
+            liquidity.market.as_ref(),
+        ],
+        &crate::ID,
+    );
+
+    check_account(accounts, 0, &liquidity.graduation_authority)?;
+    check_account(accounts, 1, &position_nft_mint)?;
+    check_account(accounts, 3, &liquidity.graduation_authority)?;
+    check_account(accounts, 4, &liquidity.pool_config)?;
+    check_account(accounts, 8, &liquidity.base_token_mint)?;
+    check_account(accounts, 9, &liquidity.quote_token_mint)?;
+    check_account(accounts, 12, &liquidity.base_token_account)?;
+    check_account(accounts, 13, &liquidity.quote_token_account)?;
+
+    let (pool_liquidity, sqrt_price) =
+        get_liquidity(liquidity.base_amount, liquidity.quote_amount)?;
+
+    let mut data = INITIALIZE_POOL_DISCRIMINATOR.to_vec();
+
+    InitializePoolArgs {
+        liquidity: pool_liquidity,
+        sqrt_price,
+        activation_point: None,
+    }
+    .serialize(&mut data)?;
+
+    invoke_pool_program(
+        pool_program,
+        accounts,
+        data,
+        &[liquidity.graduation_authority, position_nft_mint],
+        &[
+            liquidity.graduation_authority_seeds,
+            &[
+                GRADUATION_POSITION_PDA_SEED.as_bytes(),
+                liquidity.market.as_ref(),
+                &[position_nft_mint_bump],
+            ],
+        ],
+    )?;
+
+    Ok(accounts[6].key())
+}
+
+/// Full range liquidity and square root price of a pool holding `base_amount` and `quote_amount`, in Q64.64.
+/// The liquidity is rounded down so that the pool never pulls more than these amounts
+pub fn get_liquidity(base_amount: u64, quote_amount: u64) -> Result<(u128, u128)> {
+    require!(
+        base_amount > 0 && quote_amount > 0,
+        TokenMillError::InvalidGraduationPrice
+    );
+
+    let base_amount = U256::from(base_amount);
+    let quote_amount = U256::from(quote_amount);
+
+    let sqrt_price = ((quote_amount << 128usize) / base_amount).root(2);
+
+    require!(
+        sqrt_price > U256::from(MIN_SQRT_PRICE) && sqrt_price < U256::from(MAX_SQRT_PRICE),
+        TokenMillError::InvalidGraduationPrice
+    );
+
+    // Ignoring the upper bound of the range requires a bit less base
+    let base_liquidity = base_amount * sqrt_price;
+    let quote_liquidity = (quote_amount << 128) / (sqrt_price - U256::from(MIN_SQRT_PRICE));
+
+    let liquidity = base_liquidity.min(quote_liquidity);
+
+    Ok((
+        liquidity
+            .try_into()
+            .map_err(|_| TokenMillError::InvalidGraduationPrice)?,
+        sqrt_price
+            .try_into()
+            .map_err(|_| TokenMillError::InvalidGraduationPrice)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_liquidity_at_price_one() {
+        let (liquidity, sqrt_price) = get_liquidity(1_000_000, 1_000_000).unwrap();
+
+        assert_eq!(sqrt_price, 1 << 64);
+        assert_eq!(liquidity, 1_000_000 << 64);
+    }
+
+    #[test]
+    fn get_liquidity_rounds_down() {
+        let base_amount = 200_000_000_000_000;
+        let quote_amount = 3_141_592_653;
+
+        let (liquidity, sqrt_price) = get_liquidity(base_amount, quote_amount).unwrap();
+
+        let liquidity = U256::from(liquidity);
+        let sqrt_price = U256::from(sqrt_price);
+        let max_sqrt_price = U256::from(MAX_SQRT_PRICE);
+        let min_sqrt_price = U256::from(MIN_SQRT_PRICE);
+
+        // Amounts pulled by the pool program, rounded up
+        let base_delta =
+            (liquidity * (max_sqrt_price - sqrt_price)).div_ceil(sqrt_price * max_sqrt_price);
+        let quote_delta =
+            (liquidity * (sqrt_price - min_sqrt_price)).div_ceil(U256::from(1) << 128);
+
+        assert!(base_delta <= U256::from(base_amount));
+        assert!(quote_delta <= U256::from(quote_amount));
+    }
+
+    #[test]
+    fn get_liquidity_with_invalid_amounts() {
+        assert!(get_liquidity(0, 1_000).is_err());
+        assert!(get_liquidity(1_000, 0).is_err());
+        assert!(get_liquidity(u64::MAX, 1).is_err());
+    }
+}
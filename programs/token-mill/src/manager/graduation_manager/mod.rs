@@ -0,0 +1,89 @@
+//! Adapters creating the pool a market graduates to. Each AMM takes the accounts of its pool creation instruction
+//! as remaining accounts, in the order of the instruction, and checks the ones holding the liquidity.
+//! The pool is created by the graduation authority, paying for the pool accounts and holding the liquidity for good.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+};
+
+use crate::{errors::TokenMillError, state::GraduationAdapter};
+
+pub mod meteora_damm_v2;
+pub mod raydium_cpmm;
+
+/// Liquidity of a graduation, held by the graduation authority
+pub struct GraduationLiquidity<'a> {
+    pub market: Pubkey,
+    pub graduation_authority: Pubkey,
+    pub graduation_authority_seeds: &'a [&'a [u8]],
+    pub pool_config: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+    /// Token accounts of the graduation authority
+    pub base_token_account: Pubkey,
+    pub quote_token_account: Pubkey,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+}
+
+/// Creates the pool of a graduation with the adapter of its AMM, returns the address of the pool
+pub fn create_pool<'info>(
+    adapter: GraduationAdapter,
+    pool_program: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    liquidity: &GraduationLiquidity,
+) -> Result<Pubkey> {
+    match adapter {
+        GraduationAdapter::RaydiumCpmm => {
+            raydium_cpmm::create_pool(pool_program, accounts, liquidity)
+        }
+        GraduationAdapter::MeteoraDammV2 => {
+            meteora_damm_v2::create_pool(pool_program, accounts, liquidity)
+        }
+    }
+}
+
+/// Checks that the account at `index` of the pool creation is `expected`
+fn check_account(accounts: &[AccountInfo], index: usize, expected: &Pubkey) -> Result<()> {
+    require_keys_eq!(
+        accounts[index].key(),
+        *expected,
+        TokenMillError::InvalidGraduationAccounts
+    );
+
+    Ok(())
+}
+
+/// Invokes the pool program with `accounts`, in order, `signers` signing with `signer_seeds`.
+/// Other accounts keep the signer and writable flags of the transaction
+fn invoke_pool_program<'info>(
+    pool_program: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    data: Vec<u8>,
+    signers: &[Pubkey],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let instruction = Instruction {
+        program_id: pool_program.key(),
+        accounts: accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer || signers.contains(account.key),
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data,
+    };
+
+    let mut account_infos = accounts.to_vec();
+    account_infos.push(pool_program.clone());
+
+    invoke_signed(&instruction, &account_infos, signer_seeds)?;
+
+    Ok(())
+}
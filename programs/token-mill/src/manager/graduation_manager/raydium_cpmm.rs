@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::TokenMillError;
+
+use super::{check_account, invoke_pool_program, GraduationLiquidity};
+
+/// Discriminator of the `initialize` instruction of the Raydium CPMM program
+pub const INITIALIZE_DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+/// Accounts of the `initialize` instruction: creator, amm config, authority, pool state, token 0 mint,
+/// token 1 mint, lp mint, creator token 0, creator token 1, creator lp token, token 0 vault, token 1 vault,
+/// create pool fee, observation state, token program, token 0 program, token 1 program,
+/// associated token program, system program, rent
+pub const ACCOUNTS_LEN: usize = 20;
+
+/// Arguments of the `initialize` instruction, `open_time` 0 opening the pool at once
+#[derive(AnchorSerialize)]
+struct InitializeArgs {
+    init_amount_0: u64,
+    init_amount_1: u64,
+    open_time: u64,
+}
+
+/// Creates a Raydium CPMM pool with the liquidity, the LP tokens going to the graduation authority.
+/// Token 0 is the mint with the lowest address, as required by the pool program
+pub fn create_pool<'info>(
+    pool_program: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    liquidity: &GraduationLiquidity,
+) -> Result<Pubkey> {
+    require!(
+        accounts.len() == ACCOUNTS_LEN,
+        TokenMillError::InvalidGraduationAccounts
+    );
+
+    let base = (
+        liquidity.base_token_mint,
+        liquidity.base_token_account,
+        liquidity.base_amount,
+    );
+    let quote = (
+        liquidity.quote_token_mint,
+        liquidity.quote_token_account,
+        liquidity.quote_amount,
+    );
+
+    let (token_0, token_1) = if base.0 < quote.0 {
+        (base, quote)
+    } else {
+        (quote, base)
+    };
+
+    check_account(accounts, 0, &liquidity.graduation_authority)?;
+    check_account(accounts, 1, &liquidity.pool_config)?;
+    check_account(accounts, 4, &token_0.0)?;
+    check_account(accounts, 5, &token_1.0)?;
+    check_account(accounts, 7, &token_0.1)?;
+    check_account(accounts, 8, &token_1.1)?;
+
+    let mut data = INITIALIZE_DISCRIMINATOR.to_vec();
+
+    InitializeArgs {
+        init_amount_0: token_0.2,
+        init_amount_1: token_1.2,
+        open_time: 0,
+    }
+    .serialize(&mut data)?;
+
+    invoke_pool_program(
+        pool_program,
+        accounts,
+        data,
+        &[liquidity.graduation_authority],
+        &[liquidity.graduation_authority_seeds],
+    )?;
+
+    Ok(accounts[3].key())
+}
@@ -0,0 +1,121 @@
+//! Metaplex Token Metadata accounts of the base mints, for the wallets and indexers that don't read the Token-2022
+//! metadata extension. Built by hand, like the graduation adapters, to keep the Metaplex crates out of the program.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+};
+
+use crate::errors::TokenMillError;
+
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+pub const METADATA_PDA_SEED: &str = "metadata";
+
+/// Discriminator of the `CreateMetadataAccountV3` instruction of the Token Metadata program
+pub const CREATE_METADATA_ACCOUNT_V3_DISCRIMINATOR: u8 = 33;
+
+/// `DataV2` of the Token Metadata program. Creators, collection and uses are never set, only their `None` tag is
+/// serialized
+#[derive(AnchorSerialize)]
+struct DataV2 {
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    creators: Option<()>,
+    collection: Option<()>,
+    uses: Option<()>,
+}
+
+#[derive(AnchorSerialize)]
+struct CreateMetadataAccountArgsV3 {
+    data: DataV2,
+    is_mutable: bool,
+    collection_details: Option<()>,
+}
+
+/// Address of the Metaplex metadata account of `mint`
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            METADATA_PDA_SEED.as_bytes(),
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Creates the Metaplex metadata account of a base mint. The market signs as mint authority, so this has to happen
+/// before its mint authority is removed, and is set as update authority
+#[allow(clippy::too_many_arguments)]
+pub fn create_metadata_account<'info>(
+    token_metadata_program: &AccountInfo<'info>,
+    metadata: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    market: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    name: String,
+    symbol: String,
+    uri: String,
+    market_seeds: &[&[u8]],
+) -> Result<()> {
+    require_keys_eq!(
+        metadata.key(),
+        metadata_address(mint.key),
+        TokenMillError::InvalidMetadataAccount
+    );
+
+    let mut data = vec![CREATE_METADATA_ACCOUNT_V3_DISCRIMINATOR];
+
+    CreateMetadataAccountArgsV3 {
+        data: DataV2 {
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        },
+        is_mutable: true,
+        collection_details: None,
+    }
+    .serialize(&mut data)?;
+
+    // Metadata, mint, mint authority, payer, update authority, system program
+    let instruction = Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(metadata.key(), false),
+            AccountMeta::new_readonly(mint.key(), false),
+            AccountMeta::new_readonly(market.key(), true),
+            AccountMeta::new(payer.key(), true),
+            AccountMeta::new_readonly(market.key(), true),
+            AccountMeta::new_readonly(system_program.key(), false),
+        ],
+        data,
+    };
+
+    invoke_signed(
+        &instruction,
+        &[
+            metadata.clone(),
+            mint.clone(),
+            market.clone(),
+            payer.clone(),
+            system_program.clone(),
+            token_metadata_program.clone(),
+        ],
+        &[market_seeds],
+    )?;
+
+    Ok(())
+}
@@ -1,3 +1,10 @@
+pub mod change_log_manager;
+pub mod curve_manager;
+pub mod graduation_manager;
+pub mod metadata_manager;
+pub mod oracle_manager;
+pub mod price_feed_manager;
+#[cfg(feature = "staking")]
 pub mod staking_manager;
 pub mod swap_manager;
 pub mod token_manager;
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Market, MarketOracle};
+
+/// Records the spot prices of a market in its oracle.
+/// Oracles are opt-in, nothing is recorded until the oracle of the market has been created.
+pub fn record_prices(market_oracle: &AccountInfo, market: &Market) -> Result<()> {
+    if market_oracle.owner != &crate::ID || market_oracle.data_is_empty() {
+        return Ok(());
+    }
+
+    let mut data = market_oracle.try_borrow_mut_data()?;
+
+    let mut oracle = MarketOracle::try_deserialize(&mut &data[..])?;
+
+    let (ask_price, bid_price) = market.get_spot_prices()?;
+
+    oracle.record(Clock::get()?.unix_timestamp, ask_price, bid_price)?;
+
+    oracle.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::{MAX_BPS, MAX_QUOTE_PRICE_AGE},
+    errors::TokenMillError,
+    state::Market,
+};
+
+/// Owner of the Pyth `PriceUpdateV2` accounts
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+pub const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum VerificationLevel {
+    Partial { num_signatures: u8 },
+    Full,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub struct PriceFeedMessage {
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub prev_publish_time: i64,
+    pub ema_price: i64,
+    pub ema_conf: u64,
+}
+
+/// Layout of the Pyth receiver `PriceUpdateV2` account, without its discriminator
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub struct PriceUpdateV2 {
+    pub write_authority: Pubkey,
+    pub verification_level: VerificationLevel,
+    pub price_message: PriceFeedMessage,
+    pub posted_slot: u64,
+}
+
+/// Circuit breaker of markets quoted in a pegged asset.
+/// Swaps are rejected while the quote price deviates from 1 by more than `max_quote_depeg_bps`.
+/// Prices older than `MAX_QUOTE_PRICE_AGE` are ignored, so that a stalled feed doesn't freeze the market.
+pub fn check_quote_peg(market: &Market, price_feed: Option<&AccountInfo>) -> Result<()> {
+    if market.quote_price_feed == Pubkey::default() {
+        return Ok(());
+    }
+
+    let price_feed = price_feed
+        .filter(|price_feed| price_feed.key() == market.quote_price_feed)
+        .ok_or(TokenMillError::InvalidQuotePriceFeed)?;
+
+    let price_message = parse_price_update(price_feed)?.price_message;
+
+    if Clock::get()?.unix_timestamp - price_message.publish_time > MAX_QUOTE_PRICE_AGE {
+        return Ok(());
+    }
+
+    require!(
+        get_depeg_bps(price_message.price, price_message.exponent)?
+            <= u64::from(market.max_quote_depeg_bps),
+        TokenMillError::QuoteDepegged
+    );
+
+    Ok(())
+}
+
+fn parse_price_update(price_feed: &AccountInfo) -> Result<PriceUpdateV2> {
+    require_keys_eq!(
+        *price_feed.owner,
+        PYTH_RECEIVER_PROGRAM_ID,
+        TokenMillError::InvalidQuotePriceFeed
+    );
+
+    let data = price_feed.try_borrow_data()?;
+
+    require!(
+        data.len() > 8 && data[..8] == PRICE_UPDATE_V2_DISCRIMINATOR,
+        TokenMillError::InvalidQuotePriceFeed
+    );
+
+    let price_update = PriceUpdateV2::deserialize(&mut &data[8..])?;
+
+    require!(
+        price_update.verification_level == VerificationLevel::Full,
+        TokenMillError::InvalidQuotePriceFeed
+    );
+
+    Ok(price_update)
+}
+
+/// Deviation of `price * 10^exponent` from 1, in bps
+pub fn get_depeg_bps(price: i64, exponent: i32) -> Result<u64> {
+    require!(
+        (-18..=0).contains(&exponent),
+        TokenMillError::InvalidQuotePriceFeed
+    );
+
+    let one = 10i128.pow(exponent.unsigned_abs());
+    let deviation = (i128::from(price) - one).unsigned_abs();
+
+    Ok(u64::try_from(deviation * u128::from(MAX_BPS) / one.unsigned_abs()).unwrap_or(u64::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get_depeg_bps;
+
+    #[test]
+    fn depeg_bps() {
+        assert_eq!(get_depeg_bps(100_000_000, -8).unwrap(), 0);
+        assert_eq!(get_depeg_bps(99_000_000, -8).unwrap(), 100);
+        assert_eq!(get_depeg_bps(101_500_000, -8).unwrap(), 150);
+        assert_eq!(get_depeg_bps(0, -8).unwrap(), 10_000);
+        assert_eq!(get_depeg_bps(1, 0).unwrap(), 0);
+        assert!(get_depeg_bps(100_000_000, 1).is_err());
+    }
+}
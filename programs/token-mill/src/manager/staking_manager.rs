@@ -1,16 +1,40 @@
-use crate::state::{Market, MarketStaking, StakePosition};
+use crate::{
+    constant::STAKING_LOCKUP_TIERS,
+    errors::TokenMillError,
+    state::{Market, MarketStaking, StakePosition},
+};
 use anchor_lang::prelude::*;
 
+/// Deposits `amount` into the stake position, the deposit joining its lockup if active.
+/// The position can't be withdrawn from until the min stake duration of the market has passed since the deposit.
+/// The stakes and vested amounts of the positions are escrowed by the market, whose vault holds them.
 pub fn deposit(
     market: &mut Market,
     staking: &mut MarketStaking,
     stake_position: &mut StakePosition,
     amount: u64,
+    current_time: i64,
 ) -> Result<u64> {
-    let pending_staking_fees = accrue_rewards(market, staking, stake_position)?;
+    // Deposits of nothing only accrue the rewards, which claims rely on
+    require!(
+        amount == 0 || market.paused == 0,
+        TokenMillError::MarketPaused
+    );
+
+    let pending_staking_fees = accrue_rewards(market, staking, stake_position, current_time)?;
 
     staking.amount_staked += amount;
     stake_position.amount_staked += amount;
+    market.escrow_base(amount)?;
+
+    if amount > 0 {
+        stake_position.min_stake_end = stake_position
+            .min_stake_end
+            .max(current_time + staking.min_stake_duration);
+    }
+
+    expire_lockup(stake_position, current_time);
+    update_bonuses(staking, stake_position)?;
 
     Ok(pending_staking_fees)
 }
@@ -20,11 +44,13 @@ pub fn deposit_vested(
     staking: &mut MarketStaking,
     stake_position: &mut StakePosition,
     amount: u64,
+    current_time: i64,
 ) -> Result<u64> {
-    let pending_staking_fees = accrue_rewards(market, staking, stake_position)?;
+    let pending_staking_fees = accrue_rewards(market, staking, stake_position, current_time)?;
 
     staking.total_amount_vested += amount;
     stake_position.total_amount_vested += amount;
+    market.escrow_base(amount)?;
 
     Ok(pending_staking_fees)
 }
@@ -34,11 +60,69 @@ pub fn withdraw(
     staking: &mut MarketStaking,
     stake_position: &mut StakePosition,
     amount: u64,
+    current_time: i64,
 ) -> Result<u64> {
-    let pending_staking_fees = accrue_rewards(market, staking, stake_position)?;
+    let pending_staking_fees = accrue_rewards(market, staking, stake_position, current_time)?;
 
     staking.amount_staked -= amount;
     stake_position.amount_staked -= amount;
+    market.release_base(amount)?;
+
+    expire_lockup(stake_position, current_time);
+    update_bonuses(staking, stake_position)?;
+
+    Ok(pending_staking_fees)
+}
+
+/// Locks the stake position in `lock_tier` from `current_time`, or until the current lock end if later
+pub fn lock(
+    market: &mut Market,
+    staking: &mut MarketStaking,
+    stake_position: &mut StakePosition,
+    lock_tier: u8,
+    current_time: i64,
+) -> Result<u64> {
+    let (duration, _) = STAKING_LOCKUP_TIERS
+        .get(usize::from(lock_tier))
+        .ok_or(TokenMillError::InvalidLockDuration)?;
+
+    require!(lock_tier > 0, TokenMillError::InvalidLockDuration);
+    require!(
+        stake_position.amount_staked > 0,
+        TokenMillError::InsufficientStakeAmount
+    );
+
+    let pending_staking_fees = accrue_rewards(market, staking, stake_position, current_time)?;
+
+    stake_position.lock_tier = lock_tier;
+    stake_position.lock_end = stake_position.lock_end.max(current_time + duration);
+
+    update_bonuses(staking, stake_position)?;
+
+    Ok(pending_staking_fees)
+}
+
+/// Ends the lockup of a stake position past its lock end, removing its lock bonus from the staking shares.
+/// The rewards accrued until now keep the multiplier
+pub fn expire_lock(
+    market: &mut Market,
+    staking: &mut MarketStaking,
+    stake_position: &mut StakePosition,
+    current_time: i64,
+) -> Result<u64> {
+    require!(
+        !stake_position.is_lockup_active(current_time),
+        TokenMillError::StakeLocked
+    );
+    require!(
+        stake_position.lock_tier != 0,
+        TokenMillError::StakeNotLocked
+    );
+
+    let pending_staking_fees = accrue_rewards(market, staking, stake_position, current_time)?;
+
+    expire_lockup(stake_position, current_time);
+    update_bonuses(staking, stake_position)?;
 
     Ok(pending_staking_fees)
 }
@@ -48,11 +132,140 @@ pub fn withdraw_vested(
     staking: &mut MarketStaking,
     stake_position: &mut StakePosition,
     amount: u64,
+    current_time: i64,
 ) -> Result<u64> {
-    let pending_staking_fees = accrue_rewards(market, staking, stake_position)?;
+    let pending_staking_fees = accrue_rewards(market, staking, stake_position, current_time)?;
 
     staking.total_amount_vested -= amount;
     stake_position.total_amount_vested -= amount;
+    market.release_base(amount)?;
+
+    Ok(pending_staking_fees)
+}
+
+/// Withdraws `amount` from a locked stake position, `penalty_amount` of it being left to the other stakers.
+/// Emptying the position ends its lockup. The penalty is escrowed again once restaked by the positions.
+pub fn emergency_withdraw(
+    market: &mut Market,
+    staking: &mut MarketStaking,
+    stake_position: &mut StakePosition,
+    amount: u64,
+    penalty_amount: u64,
+    current_time: i64,
+) -> Result<u64> {
+    let pending_staking_fees = withdraw(market, staking, stake_position, amount, current_time)?;
+
+    if stake_position.amount_staked == 0 {
+        stake_position.lock_tier = 0;
+        stake_position.lock_end = 0;
+    }
+
+    staking.pending_penalty_amount += penalty_amount;
+
+    Ok(pending_staking_fees)
+}
+
+/// Moves the stake and the lockup of `stake_position` to the empty `new_stake_position`.
+/// The rewards accrued so far are left to the previous owner.
+pub fn transfer(
+    market: &mut Market,
+    staking: &mut MarketStaking,
+    stake_position: &mut StakePosition,
+    new_stake_position: &mut StakePosition,
+    current_time: i64,
+) -> Result<u64> {
+    let pending_staking_fees = accrue_rewards(market, staking, stake_position, current_time)?;
+    accrue_rewards(market, staking, new_stake_position, current_time)?;
+
+    expire_lockup(stake_position, current_time);
+    update_bonuses(staking, stake_position)?;
+
+    new_stake_position.amount_staked = stake_position.amount_staked;
+    new_stake_position.lock_tier = stake_position.lock_tier;
+    new_stake_position.lock_end = stake_position.lock_end;
+    new_stake_position.lock_bonus = stake_position.lock_bonus;
+    new_stake_position.min_stake_end = stake_position.min_stake_end;
+
+    stake_position.amount_staked = 0;
+    stake_position.lock_tier = 0;
+    stake_position.lock_end = 0;
+    stake_position.lock_bonus = 0;
+    stake_position.min_stake_end = 0;
+
+    // Badges stay with their holder, the moved stake being boosted by the badge of the new owner if any
+    update_badge_bonus(staking, stake_position)?;
+    update_badge_bonus(staking, new_stake_position)?;
+
+    Ok(pending_staking_fees)
+}
+
+/// Links `badge_mint` to the stake position, boosting its stake by the badge multiplier of the market
+pub fn link_badge(
+    market: &mut Market,
+    staking: &mut MarketStaking,
+    stake_position: &mut StakePosition,
+    badge_mint: Pubkey,
+    current_time: i64,
+) -> Result<u64> {
+    let pending_staking_fees = accrue_rewards(market, staking, stake_position, current_time)?;
+
+    stake_position.badge_mint = badge_mint;
+    update_badge_bonus(staking, stake_position)?;
+
+    Ok(pending_staking_fees)
+}
+
+/// Removes the badge of the stake position, the rewards accrued so far keeping the boost
+pub fn unlink_badge(
+    market: &mut Market,
+    staking: &mut MarketStaking,
+    stake_position: &mut StakePosition,
+    current_time: i64,
+) -> Result<u64> {
+    let pending_staking_fees = accrue_rewards(market, staking, stake_position, current_time)?;
+
+    stake_position.badge_mint = Pubkey::default();
+    update_badge_bonus(staking, stake_position)?;
+
+    Ok(pending_staking_fees)
+}
+
+/// Settles the rewards of the stake position and returns them with the badge unlinked if the user doesn't hold it anymore.
+/// The caller is responsible for sending the rewards.
+pub fn claim_rewards(
+    market: &mut Market,
+    staking: &mut MarketStaking,
+    stake_position: &mut StakePosition,
+    holds_badge: bool,
+    current_time: i64,
+) -> Result<(u64, Option<Pubkey>)> {
+    deposit(market, staking, stake_position, 0, current_time)?;
+
+    let mut unlinked_badge_mint = None;
+
+    if stake_position.badge_mint != Pubkey::default() && !holds_badge {
+        unlinked_badge_mint = Some(stake_position.badge_mint);
+
+        unlink_badge(market, staking, stake_position, current_time)?;
+    }
+
+    let pending_rewards = stake_position.pending_rewards;
+    stake_position.pending_rewards = 0;
+    market.release_quote(pending_rewards)?;
+
+    Ok((pending_rewards, unlinked_badge_mint))
+}
+
+/// Pushes the pending staking fees of the market to the staking account without touching any position
+pub fn distribute_staking_fees(market: &mut Market, staking: &mut MarketStaking) -> Result<u64> {
+    let pending_staking_fees = market.fees.pending_staking_fees;
+
+    if staking.total_shares() == 0 {
+        return Ok(0);
+    }
+
+    staking.accrue_rewards(pending_staking_fees)?;
+    market.fees.pending_staking_fees = 0;
 
     Ok(pending_staking_fees)
 }
@@ -61,7 +274,11 @@ fn accrue_rewards(
     market: &mut Market,
     staking: &mut MarketStaking,
     stake_position: &mut StakePosition,
+    current_time: i64,
 ) -> Result<u64> {
+    let acc_vault_reward_amount_per_share = staking.update_vault_emission(current_time)?;
+    stake_position.accrue_vault_rewards(acc_vault_reward_amount_per_share)?;
+
     let pending_staking_fees = market.fees.pending_staking_fees;
     let acc_reward_amount_per_share = staking.accrue_rewards(pending_staking_fees)?;
 
@@ -69,7 +286,52 @@ fn accrue_rewards(
         market.fees.pending_staking_fees = 0;
     }
 
-    stake_position.accrue_rewards(acc_reward_amount_per_share)?;
+    // The fees accrued to the position are escrowed until claimed, the rounding dust staying with the market
+    let rewards = stake_position.accrue_rewards(acc_reward_amount_per_share)?;
+    market.escrow_quote(rewards)?;
+
+    // Penalties are restaked, the tokens already being held by the market
+    let acc_penalty_amount_per_share = staking.accrue_penalties()?;
+    let penalty_amount = stake_position.accrue_penalties(acc_penalty_amount_per_share)?;
+
+    if penalty_amount > 0 {
+        staking.amount_staked += penalty_amount;
+        stake_position.amount_staked += penalty_amount;
+        market.escrow_base(penalty_amount)?;
+
+        update_bonuses(staking, stake_position)?;
+    }
 
     Ok(pending_staking_fees)
 }
+
+/// Rewards accrued since the lock end keep the multiplier until the position is next updated, which anyone can do
+/// with `expire_stake_position_lock`
+fn expire_lockup(stake_position: &mut StakePosition, current_time: i64) {
+    if !stake_position.is_lockup_active(current_time) {
+        stake_position.lock_tier = 0;
+    }
+}
+
+/// Recomputes the lockup and badge bonuses of the position from its current stake
+fn update_bonuses(staking: &mut MarketStaking, stake_position: &mut StakePosition) -> Result<()> {
+    let lock_bonus = stake_position.get_lock_bonus()?;
+
+    staking.total_lock_bonus = staking.total_lock_bonus - stake_position.lock_bonus + lock_bonus;
+    stake_position.lock_bonus = lock_bonus;
+
+    update_badge_bonus(staking, stake_position)
+}
+
+fn update_badge_bonus(
+    staking: &mut MarketStaking,
+    stake_position: &mut StakePosition,
+) -> Result<()> {
+    let badge_bonus = stake_position.get_badge_bonus(staking.badge_multiplier_bps)?;
+
+    staking.total_badge_bonus =
+        staking.total_badge_bonus - stake_position.badge_bonus + badge_bonus;
+    stake_position.badge_bonus = badge_bonus;
+
+    Ok(())
+}
@@ -1,8 +1,15 @@
+use std::cmp::min;
+
 use anchor_lang::prelude::*;
 
-use crate::{math::Rounding, state::Market};
+use crate::{
+    constant::MAX_BPS,
+    errors::TokenMillError,
+    math::{mul_div, normalization::get_execution_price, Rounding},
+    state::{Market, TokenMillConfig},
+};
 
-#[derive(Debug, AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq)]
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Copy, Clone, InitSpace, PartialEq)]
 pub enum SwapType {
     Buy,  // Buy base token
     Sell, // Sell base token
@@ -14,29 +21,165 @@ pub enum SwapAmountType {
     ExactOutput,
 }
 
+/// Behavior of a swap whose specified amount can't be fully filled, once the curve or the available supply is exhausted
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq)]
+pub enum SwapFillType {
+    /// Fill-or-kill (FOK), the swap fails unless the whole amount is filled
+    FillOrKill,
+    /// Immediate-or-cancel (IOC), the swap fills what it can and the rest of the amount is cancelled
+    ImmediateOrCancel,
+}
+
+/// Amounts and fees of a swap, `amount_in` and `amount_out` being the amounts paid and received by the user, net of
+/// the protocol fee rebate and the staker discount sent back to them
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq)]
+pub struct SwapQuote {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+    pub creator_fee: u64,
+    pub staking_fee: u64,
+    /// Protocol fee net of the rebate and the staker discount
+    pub protocol_fee: u64,
+    pub referral_fee: u64,
+    pub interface_fee: u64,
+    pub protocol_fee_rebate: u64,
+    pub staker_discount: u64,
+}
+
+/// Parts of the protocol fee of a swap sent back to the user
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct ProtocolFeeRefunds {
+    /// 30-day volume of the user before the swap, rebate bps of its tier and rebated amount
+    pub rebate: Option<(u64, u16, u64)>,
+    /// Amount staked by the user on the protocol token market, discount bps and discounted amount
+    pub staker_discount: Option<(u64, u16, u64)>,
+}
+
+impl ProtocolFeeRefunds {
+    pub fn rebate_amount(&self) -> u64 {
+        self.rebate.map_or(0, |(_, _, rebate_amount)| rebate_amount)
+    }
+
+    pub fn discount_amount(&self) -> u64 {
+        self.staker_discount
+            .map_or(0, |(_, _, discount_amount)| discount_amount)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.rebate_amount() + self.discount_amount()
+    }
+}
+
+/// Last unix timestamp or slot at which a swap can be executed
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq)]
+pub enum SwapDeadline {
+    Timestamp(i64),
+    Slot(u64),
+}
+
+impl SwapDeadline {
+    pub fn is_expired(&self, clock: &Clock) -> bool {
+        match *self {
+            SwapDeadline::Timestamp(timestamp) => clock.unix_timestamp > timestamp,
+            SwapDeadline::Slot(slot) => clock.slot > slot,
+        }
+    }
+}
+
+/// Optional parameters of a swap
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq)]
+pub struct SwapOptions {
+    /// Charged on the quote amount when an interface fee token account is passed
+    pub interface_fee_bps: u16,
+    pub deadline: Option<SwapDeadline>,
+    pub fill_type: SwapFillType,
+    /// Max price impact of the swap, in bps of the spot price before the swap
+    pub max_price_impact_bps: Option<u16>,
+}
+
+impl Default for SwapOptions {
+    fn default() -> Self {
+        Self {
+            interface_fee_bps: 0,
+            deadline: None,
+            fill_type: SwapFillType::FillOrKill,
+            max_price_impact_bps: None,
+        }
+    }
+}
+
+/// Swaps on the curve of `market`. The clock is passed by the caller, so that quotes can be computed off-chain
 pub fn swap(
     market: &mut Market,
     swap_type: SwapType,
     swap_amount_type: SwapAmountType,
     amount: u64,
+    clock: &Clock,
 ) -> Result<(u64, u64, u64)> {
-    let (base_amount, quote_amount) = match (swap_type, swap_amount_type) {
-        (SwapType::Buy, SwapAmountType::ExactInput) => market.get_base_amount_out(amount)?,
-        (SwapType::Buy, SwapAmountType::ExactOutput) => {
-            market.get_quote_amount(amount, swap_amount_type)?
-        }
-        (SwapType::Sell, SwapAmountType::ExactInput) => {
-            market.get_quote_amount(amount, swap_amount_type)?
-        }
-        (SwapType::Sell, SwapAmountType::ExactOutput) => market.get_base_amount_in(amount)?,
+    require!(market.graduated == 0, TokenMillError::MarketGraduated);
+    require!(market.paused == 0, TokenMillError::MarketPaused);
+
+    if swap_type == SwapType::Buy && market.is_max_raise_reached() {
+        return Err(TokenMillError::MaxRaiseReached.into());
+    }
+
+    if market.max_slot_price_move_bps != 0 {
+        market.check_slot_price_move(clock.slot)?;
+    }
+
+    let launch_fee_bps = if market.launch_fee_bps != 0 {
+        market.update_launch_fee(clock.unix_timestamp)
+    } else {
+        0
+    };
+
+    let mut curve_amount =
+        get_curve_amount(market, swap_type, swap_amount_type, amount, launch_fee_bps)?;
+    let (mut base_amount, mut quote_amount) =
+        get_curve_amounts(market, swap_type, swap_amount_type, curve_amount)?;
+
+    let impact_fee_bps = if market.max_impact_fee_bps != 0 {
+        let price_impact_bps = get_price_impact_bps(
+            market,
+            swap_type,
+            market.circulating_supply(),
+            base_amount,
+            quote_amount,
+        )?;
+
+        market.get_impact_fee_bps(price_impact_bps)
+    } else {
+        0
     };
 
+    let surcharge_bps = launch_fee_bps + impact_fee_bps;
+
+    // The impact fee of swaps whose quote amount is given is set by the impact of the amount before the fee
+    if impact_fee_bps != 0
+        && matches!(
+            (swap_type, swap_amount_type),
+            (SwapType::Buy, SwapAmountType::ExactInput)
+                | (SwapType::Sell, SwapAmountType::ExactOutput)
+        )
+    {
+        curve_amount =
+            get_curve_amount(market, swap_type, swap_amount_type, amount, surcharge_bps)?;
+        (base_amount, quote_amount) =
+            get_curve_amounts(market, swap_type, swap_amount_type, curve_amount)?;
+    }
+
     let mut swap_fee = 0;
 
+    market.swap_count += 1;
+    // A pending close proposal lapses with any trade, the creator has to propose it again
+    market.close_time = 0;
+
     match swap_type {
         SwapType::Buy => {
             let (_, buyback_amount) = market.get_quote_amount_with_parameters(
-                market.circulating_supply(),
+                market.curve_supply(),
                 base_amount,
                 SwapAmountType::ExactInput,
                 Rounding::Up,
@@ -46,12 +189,392 @@ pub fn swap(
                 swap_fee = quote_amount - buyback_amount;
             }
 
+            require!(
+                quote_amount <= market.remaining_raise(),
+                TokenMillError::MaxRaiseReached
+            );
+
             market.base_reserve -= base_amount;
+            market.quote_raised += quote_amount;
         }
         SwapType::Sell => {
             market.base_reserve += base_amount;
+            market.quote_raised = market.quote_raised.saturating_sub(quote_amount);
         }
     }
 
+    if surcharge_bps == 0 {
+        return Ok((base_amount, quote_amount, swap_fee));
+    }
+
+    // The rounding of fully filled swaps goes to the surcharge, so that the user gets exactly the amount asked for
+    let surcharge = match (swap_type, swap_amount_type) {
+        (SwapType::Buy, SwapAmountType::ExactInput)
+            if quote_amount == get_amount_without_surcharge(amount, surcharge_bps)? =>
+        {
+            amount - quote_amount
+        }
+        (SwapType::Sell, SwapAmountType::ExactOutput) if quote_amount == curve_amount => {
+            quote_amount - amount
+        }
+        _ => get_surcharge(swap_type, quote_amount, surcharge_bps)?,
+    };
+
+    // The launch fee goes to its recipient, the impact fee is shared like the swap fee
+    let launch_fee = u64::try_from(
+        u128::from(surcharge) * u128::from(launch_fee_bps) / u128::from(surcharge_bps),
+    )?;
+
+    market.credit_launch_fee(launch_fee);
+    swap_fee += surcharge - launch_fee;
+
+    let quote_amount = match swap_type {
+        SwapType::Buy => quote_amount + surcharge,
+        SwapType::Sell => quote_amount - surcharge,
+    };
+
     Ok((base_amount, quote_amount, swap_fee))
 }
+
+/// Amount swapped on the curve once a surcharge of `surcharge_bps` is taken from the quote amounts given by the user.
+/// Exact input buys are capped to the quote amount that can still be raised
+fn get_curve_amount(
+    market: &Market,
+    swap_type: SwapType,
+    swap_amount_type: SwapAmountType,
+    amount: u64,
+    surcharge_bps: u16,
+) -> Result<u64> {
+    match (swap_type, swap_amount_type) {
+        (SwapType::Buy, SwapAmountType::ExactInput) => Ok(min(
+            get_amount_without_surcharge(amount, surcharge_bps)?,
+            market.remaining_raise(),
+        )),
+        (SwapType::Sell, SwapAmountType::ExactOutput) => {
+            get_amount_with_surcharge(amount, surcharge_bps)
+        }
+        _ => Ok(amount),
+    }
+}
+
+/// Base and quote amounts of a swap of `amount` on the curve, without changing the market
+fn get_curve_amounts(
+    market: &Market,
+    swap_type: SwapType,
+    swap_amount_type: SwapAmountType,
+    amount: u64,
+) -> Result<(u64, u64)> {
+    match (swap_type, swap_amount_type) {
+        (SwapType::Buy, SwapAmountType::ExactInput) => {
+            let (base_amount, quote_amount) = market.get_base_amount_out(amount)?;
+
+            // Buys can't reach the supply not deposited yet
+            if base_amount > market.available_supply() {
+                market.get_quote_amount(market.available_supply(), SwapAmountType::ExactOutput)
+            } else {
+                Ok((base_amount, quote_amount))
+            }
+        }
+        (SwapType::Buy, SwapAmountType::ExactOutput) => {
+            market.get_quote_amount(min(amount, market.available_supply()), swap_amount_type)
+        }
+        (SwapType::Sell, SwapAmountType::ExactInput) => {
+            market.get_quote_amount(amount, swap_amount_type)
+        }
+        (SwapType::Sell, SwapAmountType::ExactOutput) => market.get_base_amount_in(amount),
+    }
+}
+
+/// Surcharge of a swap exchanging `quote_amount` on the curve, `surcharge_bps` of the quote amount given by the
+/// user on buys and of the quote amount of the curve on sells, rounded up
+pub fn get_surcharge(swap_type: SwapType, quote_amount: u64, surcharge_bps: u16) -> Result<u64> {
+    if surcharge_bps == 0 {
+        return Ok(0);
+    }
+
+    let denominator = match swap_type {
+        SwapType::Buy => MAX_BPS - u64::from(surcharge_bps),
+        SwapType::Sell => MAX_BPS,
+    };
+
+    let surcharge = mul_div(
+        u128::from(quote_amount),
+        u128::from(surcharge_bps),
+        u128::from(denominator),
+        Rounding::Up,
+    )
+    .ok_or(TokenMillError::MathError)?;
+
+    Ok(u64::try_from(surcharge)?)
+}
+
+/// Quote amount left to the curve out of `amount` once the surcharge is taken, rounded down
+fn get_amount_without_surcharge(amount: u64, surcharge_bps: u16) -> Result<u64> {
+    if surcharge_bps == 0 {
+        return Ok(amount);
+    }
+
+    let amount = mul_div(
+        u128::from(amount),
+        u128::from(MAX_BPS - u64::from(surcharge_bps)),
+        u128::from(MAX_BPS),
+        Rounding::Down,
+    )
+    .ok_or(TokenMillError::MathError)?;
+
+    Ok(u64::try_from(amount)?)
+}
+
+/// Quote amount the curve has to give for `amount` to be left once the surcharge is taken, rounded up.
+/// Saturates, as such an amount can't be filled anyway
+fn get_amount_with_surcharge(amount: u64, surcharge_bps: u16) -> Result<u64> {
+    if surcharge_bps == 0 {
+        return Ok(amount);
+    }
+
+    let amount = mul_div(
+        u128::from(amount),
+        u128::from(MAX_BPS),
+        u128::from(MAX_BPS - u64::from(surcharge_bps)),
+        Rounding::Up,
+    )
+    .ok_or(TokenMillError::MathError)?;
+
+    Ok(u64::try_from(amount).unwrap_or(u64::MAX))
+}
+
+/// Sells base tokens into the curve without returning them to the reserve.
+/// The tokens are burned by the caller, so the total supply shrinks by the amount sold.
+pub fn sell_and_burn(
+    market: &mut Market,
+    swap_amount_type: SwapAmountType,
+    amount: u64,
+    clock: &Clock,
+) -> Result<(u64, u64)> {
+    let (base_amount, quote_amount, _) =
+        swap(market, SwapType::Sell, swap_amount_type, amount, clock)?;
+
+    market.base_reserve -= base_amount;
+    market.total_supply -= base_amount;
+
+    Ok((base_amount, quote_amount))
+}
+
+/// Buys base tokens from the curve with the pending burn fees and moves them from the total supply to the burned supply,
+/// the caller burning them. The fee of the buyback and the quote amount left unfilled go to the next buyback.
+pub fn buyback_and_burn(market: &mut Market, clock: &Clock) -> Result<(u64, u64)> {
+    let pending_burn_fees = market.fees.pending_burn_fees;
+
+    require!(pending_burn_fees > 0, TokenMillError::InvalidAmount);
+
+    market.fees.pending_burn_fees = 0;
+
+    let (base_amount, quote_amount, swap_fee) = swap(
+        market,
+        SwapType::Buy,
+        SwapAmountType::ExactInput,
+        pending_burn_fees,
+        clock,
+    )?;
+
+    market.fees.pending_burn_fees = pending_burn_fees - quote_amount + swap_fee;
+    market.total_supply -= base_amount;
+    market.burned_supply += base_amount;
+
+    Ok((base_amount, quote_amount))
+}
+
+/// Amount swapped on the curve out of the `amount` specified by the user, exact input buys paying the interface fee
+/// out of their input. `interface_fee_bps` is `None` when no interface fee recipient is passed
+pub fn get_amount_without_interface_fee(
+    swap_type: SwapType,
+    swap_amount_type: SwapAmountType,
+    amount: u64,
+    interface_fee_bps: Option<u16>,
+) -> Result<u64> {
+    match (swap_type, swap_amount_type, interface_fee_bps) {
+        (SwapType::Buy, SwapAmountType::ExactInput, Some(interface_fee_bps)) => {
+            Ok(amount - get_interface_fee(amount, interface_fee_bps)?)
+        }
+        _ => Ok(amount),
+    }
+}
+
+/// Interface fee of a swap of `amount` exchanging `quote_amount` on the curve, taken from the input of exact input
+/// buys and charged on top of the quote amount otherwise
+pub fn get_swap_interface_fee(
+    swap_type: SwapType,
+    swap_amount_type: SwapAmountType,
+    amount: u64,
+    quote_amount: u64,
+    interface_fee_bps: Option<u16>,
+) -> Result<u64> {
+    match (swap_type, swap_amount_type, interface_fee_bps) {
+        (_, _, None) => Ok(0),
+        (SwapType::Buy, SwapAmountType::ExactInput, Some(interface_fee_bps)) => {
+            get_included_interface_fee(quote_amount, amount, interface_fee_bps)
+        }
+        (_, _, Some(interface_fee_bps)) => get_interface_fee(quote_amount, interface_fee_bps),
+    }
+}
+
+/// Computes the interface fee charged on top of the quote amount of a swap.
+/// The fee is rounded down so that the user is never charged more than the displayed rate.
+pub fn get_interface_fee(quote_amount: u64, interface_fee_bps: u16) -> Result<u64> {
+    Ok(u64::try_from(
+        u128::from(quote_amount) * u128::from(interface_fee_bps) / u128::from(MAX_BPS),
+    )?)
+}
+
+/// Computes the interface fee included in `amount_in`, the input of an exact input buy, so that the quote amount of
+/// the buy and the fee add up to at most `amount_in`
+pub fn get_included_interface_fee(
+    quote_amount: u64,
+    amount_in: u64,
+    interface_fee_bps: u16,
+) -> Result<u64> {
+    let interface_fee = u64::try_from(
+        u128::from(quote_amount) * u128::from(interface_fee_bps)
+            / u128::from(MAX_BPS - u64::from(interface_fee_bps)),
+    )?;
+
+    Ok(min(interface_fee, amount_in - quote_amount))
+}
+
+/// Part of the protocol fee of a swap rebated to the trader, `rebate_bps` of the quote amount capped at the protocol fee
+pub fn get_protocol_fee_rebate(
+    quote_amount: u64,
+    protocol_fee: u64,
+    rebate_bps: u16,
+) -> Result<u64> {
+    let rebate =
+        u64::try_from(u128::from(quote_amount) * u128::from(rebate_bps) / u128::from(MAX_BPS))?;
+
+    Ok(min(rebate, protocol_fee))
+}
+
+/// Part of the protocol fee given back to stakers of the protocol token
+pub fn get_staker_discount(protocol_fee: u64, discount_bps: u16) -> Result<u64> {
+    Ok(u64::try_from(
+        u128::from(protocol_fee) * u128::from(discount_bps) / u128::from(MAX_BPS),
+    )?)
+}
+
+/// Parts of `protocol_fee` sent back to the user: the rebate of the tier of `volume`, the 30-day volume of the user
+/// before the swap, then the staker discount of `stake`, the market and amount of the stake position of the user,
+/// applied to the protocol fee left after the rebate
+pub fn get_protocol_fee_refunds(
+    config: &TokenMillConfig,
+    quote_token_mint: &Pubkey,
+    quote_amount: u64,
+    protocol_fee: u64,
+    volume: Option<u64>,
+    stake: Option<(Pubkey, u64)>,
+) -> Result<ProtocolFeeRefunds> {
+    let mut refunds = ProtocolFeeRefunds::default();
+
+    if let Some(volume) = volume {
+        let rebate_bps = config.get_rebate_bps(quote_token_mint, volume);
+        let rebate_amount = get_protocol_fee_rebate(quote_amount, protocol_fee, rebate_bps)?;
+
+        if rebate_amount > 0 {
+            refunds.rebate = Some((volume, rebate_bps, rebate_amount));
+        }
+    }
+
+    if let Some((market, amount_staked)) = stake {
+        let discount_bps = config.get_staker_discount_bps(&market, amount_staked);
+        let discount_amount =
+            get_staker_discount(protocol_fee - refunds.rebate_amount(), discount_bps)?;
+
+        if discount_amount > 0 {
+            refunds.staker_discount = Some((amount_staked, discount_bps, discount_amount));
+        }
+    }
+
+    Ok(refunds)
+}
+
+/// Waives `fee_discount_bps` of the swap fee for fee exempted wallets, returns the quote amount and swap fee net of it.
+/// Buyers pay the discount less and sellers receive it on top of the quote amount
+pub fn apply_fee_discount(
+    swap_type: SwapType,
+    quote_amount: u64,
+    swap_fee: u64,
+    fee_discount_bps: u16,
+) -> Result<(u64, u64)> {
+    let fee_discount =
+        u64::try_from(u128::from(swap_fee) * u128::from(fee_discount_bps) / u128::from(MAX_BPS))?;
+
+    let quote_amount = match swap_type {
+        SwapType::Buy => quote_amount - fee_discount,
+        SwapType::Sell => quote_amount + fee_discount,
+    };
+
+    Ok((quote_amount, swap_fee - fee_discount))
+}
+
+/// Applies the fee exemption of `wallet` to a swap on the curve, the wallet the swap is made for.
+/// Every swap path calls it once the fill of the swap is checked, so that exempted wallets get the same discount
+/// whichever instruction they trade through. Returns the quote amount and swap fee net of the discount
+pub fn apply_fee_exemption(
+    config: &TokenMillConfig,
+    wallet: &Pubkey,
+    swap_type: SwapType,
+    quote_amount: u64,
+    swap_fee: u64,
+) -> Result<(u64, u64)> {
+    apply_fee_discount(
+        swap_type,
+        quote_amount,
+        swap_fee,
+        config.get_fee_discount_bps(wallet),
+    )
+}
+
+/// Computes the deviation of the average execution price of a swap from the marginal price of the curve at
+/// `supply_before`, the supply before the swap. Buys are compared to the ask price and sells to the bid price.
+/// Executions better than the marginal price have no impact.
+pub fn get_price_impact_bps(
+    market: &Market,
+    swap_type: SwapType,
+    supply_before: u64,
+    base_amount: u64,
+    quote_amount: u64,
+) -> Result<u64> {
+    if base_amount == 0 {
+        return Ok(0);
+    }
+
+    let execution_price = get_execution_price(
+        base_amount,
+        quote_amount,
+        market.quote_token_decimals,
+        Rounding::Down,
+    )?;
+
+    let (spot_price, price_delta) = match swap_type {
+        SwapType::Buy => {
+            let spot_price = u128::from(market.get_ask_price(supply_before)?);
+
+            (spot_price, execution_price.saturating_sub(spot_price))
+        }
+        SwapType::Sell => {
+            let spot_price = u128::from(market.get_bid_price(supply_before)?);
+
+            (spot_price, spot_price.saturating_sub(execution_price))
+        }
+    };
+
+    if price_delta == 0 {
+        return Ok(0);
+    }
+
+    if spot_price == 0 {
+        return Ok(u64::MAX);
+    }
+
+    Ok(u64::try_from(
+        price_delta * u128::from(MAX_BPS) / spot_price,
+    )?)
+}
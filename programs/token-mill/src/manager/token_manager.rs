@@ -1,12 +1,24 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
 use anchor_spl::{
     token::Token,
-    token_2022::spl_token_2022::{
-        self,
-        extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    token_2022::{
+        spl_token_2022::{
+            self,
+            extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+        },
+        Token2022,
+    },
+    token_interface::{
+        approve, burn, close_account, revoke, sync_native, transfer_checked, Approve, Burn,
+        CloseAccount, Mint, Revoke, SyncNative, TokenAccount, TokenInterface, TransferChecked,
     },
-    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
+use spl_token_group_interface::state::TokenGroupMember;
+
+use crate::constant::{GARBAGE_COLLECTION_REWARD_SHARE, MAX_BPS};
 
 /// Checks that the mint account only has allowed extensions.
 /// Tax-transfer quote tokens for example would cause some issues with the current implementation.
@@ -29,6 +41,25 @@ pub fn check_mint_extensions(mint_account: &InterfaceAccount<Mint>) -> Result<bo
     Ok(true)
 }
 
+/// Checks that the mint is an NFT registered as a member of the Token-2022 `group`.
+/// Members can only be added with the signature of the group update authority.
+pub fn is_group_member_nft(mint_account: &InterfaceAccount<Mint>, group: &Pubkey) -> Result<bool> {
+    let mint_account_info = mint_account.to_account_info();
+    if *mint_account_info.owner != Token2022::id()
+        || mint_account.decimals != 0
+        || mint_account.supply != 1
+    {
+        return Ok(false);
+    }
+
+    let mint_data = mint_account_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+
+    Ok(mint
+        .get_extension::<TokenGroupMember>()
+        .is_ok_and(|member| member.group == *group && member.mint == mint_account.key()))
+}
+
 pub fn transfer_from_pda<'info>(
     mint: &InterfaceAccount<'info, Mint>,
     pda: AccountInfo<'info>,
@@ -76,3 +107,168 @@ pub fn transfer_from_eoa<'info>(
         mint.decimals,
     )
 }
+
+pub fn burn_from_eoa<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+    eoa: &Signer<'info>,
+    eoa_token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    burn(
+        CpiContext::new(
+            token_program.to_account_info(),
+            Burn {
+                mint: mint.to_account_info(),
+                from: eoa_token_account.to_account_info(),
+                authority: eoa.to_account_info(),
+            },
+        ),
+        amount,
+    )
+}
+
+pub fn burn_from_pda<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+    pda: AccountInfo<'info>,
+    pda_token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+    pda_seeds: &[&[u8]],
+) -> Result<()> {
+    burn(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Burn {
+                mint: mint.to_account_info(),
+                from: pda_token_account.to_account_info(),
+                authority: pda,
+            },
+            &[pda_seeds],
+        ),
+        amount,
+    )
+}
+
+/// Closes an empty token account of the PDA, sending its rent to `destination`
+pub fn close_pda_token_account<'info>(
+    pda: AccountInfo<'info>,
+    pda_token_account: &InterfaceAccount<'info, TokenAccount>,
+    destination: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    pda_seeds: &[&[u8]],
+) -> Result<()> {
+    close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: pda_token_account.to_account_info(),
+            destination,
+            authority: pda,
+        },
+        &[pda_seeds],
+    ))
+}
+
+/// Allows `delegate` to transfer up to `amount` tokens out of the EOA token account
+pub fn approve_from_eoa<'info>(
+    eoa: &Signer<'info>,
+    eoa_token_account: &InterfaceAccount<'info, TokenAccount>,
+    delegate: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    approve(
+        CpiContext::new(
+            token_program.to_account_info(),
+            Approve {
+                to: eoa_token_account.to_account_info(),
+                delegate,
+                authority: eoa.to_account_info(),
+            },
+        ),
+        amount,
+    )
+}
+
+pub fn revoke_from_eoa<'info>(
+    eoa: &Signer<'info>,
+    eoa_token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    revoke(CpiContext::new(
+        token_program.to_account_info(),
+        Revoke {
+            source: eoa_token_account.to_account_info(),
+            authority: eoa.to_account_info(),
+        },
+    ))
+}
+
+/// Wraps `amount` lamports of the EOA into its wSOL token account
+pub fn wrap_sol<'info>(
+    eoa: &Signer<'info>,
+    eoa_token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            Transfer {
+                from: eoa.to_account_info(),
+                to: eoa_token_account.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    sync_native(CpiContext::new(
+        token_program.to_account_info(),
+        SyncNative {
+            account: eoa_token_account.to_account_info(),
+        },
+    ))
+}
+
+/// Closes the wSOL token account of the EOA, returning its whole balance and rent as lamports
+pub fn unwrap_sol<'info>(
+    eoa: &Signer<'info>,
+    eoa_token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    close_account(CpiContext::new(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: eoa_token_account.to_account_info(),
+            destination: eoa.to_account_info(),
+            authority: eoa.to_account_info(),
+        },
+    ))
+}
+
+/// Closes an account that no longer holds any value, rewarding the caller with a share of its rent.
+/// Returns the rent reclaimed and the caller reward.
+pub fn collect_rent<'info, T>(
+    account: &Account<'info, T>,
+    caller: &AccountInfo<'info>,
+    rent_recipient: &AccountInfo<'info>,
+) -> Result<(u64, u64)>
+where
+    T: AccountSerialize + AccountDeserialize + Owner + Clone,
+{
+    let account_info = account.to_account_info();
+    let rent_reclaimed = account_info.lamports();
+
+    let caller_reward = u64::try_from(
+        u128::from(rent_reclaimed) * u128::from(GARBAGE_COLLECTION_REWARD_SHARE)
+            / u128::from(MAX_BPS),
+    )?;
+
+    account_info.sub_lamports(caller_reward)?;
+    caller.add_lamports(caller_reward)?;
+
+    account.close(rent_recipient.clone())?;
+
+    Ok((rent_reclaimed, caller_reward))
+}
@@ -0,0 +1,612 @@
+use core::cmp::min;
+
+use ruint::aliases::U256;
+
+use crate::constant::{MAX_PRICES_LENGTH, SCALE};
+
+use super::{
+    get_delta_base_in, get_delta_base_out, get_delta_quote, mul_div,
+    normalization::{
+        denormalize_base_amount, denormalize_quote_amount, normalize_base_amount,
+        normalize_quote_amount,
+    },
+    to_u128, MathError, Result, Rounding,
+};
+
+/// One side of a market curve, with everything needed to quote swaps against it.
+/// Free of Anchor and Solana types so that quoting servers, adapters and fuzzers can use it off-chain.
+#[derive(Debug, Clone, Copy)]
+pub struct PriceCurve<'a> {
+    /// Only the first `interval_number + 1` prices are used
+    pub prices: &'a [u64; MAX_PRICES_LENGTH],
+    /// See `get_cumulative_quotes`
+    pub cumulative_quotes: &'a [u128; MAX_PRICES_LENGTH],
+    pub rounded_intervals: u64,
+    pub width_scaled: u64,
+    pub interval_number: u64,
+    pub quote_token_decimals: u8,
+}
+
+/// Normalized quote amount of the intervals below each price, every interval rounded down.
+/// Bit `i` of the returned mask is set if the quote amount of interval `i` was rounded, to round the sums up.
+pub fn get_cumulative_quotes(
+    prices: &[u64; MAX_PRICES_LENGTH],
+    interval_number: u64,
+    width_scaled: u64,
+) -> Result<([u128; MAX_PRICES_LENGTH], u64)> {
+    let width_scaled = U256::from(width_scaled);
+    let denominator = U256::from(2 * SCALE);
+
+    let mut cumulative_quotes: [u128; MAX_PRICES_LENGTH] = [0; MAX_PRICES_LENGTH];
+    let mut rounded_intervals = 0;
+
+    for i in 0..interval_number as usize {
+        // Same as `get_delta_quote` over the whole interval
+        let (quote, remainder) = (width_scaled
+            * (U256::from(prices[i]) + U256::from(prices[i + 1])))
+        .div_rem(denominator);
+
+        cumulative_quotes[i + 1] = cumulative_quotes[i]
+            .checked_add(to_u128(quote)?)
+            .ok_or(MathError)?;
+
+        if remainder != U256::ZERO {
+            rounded_intervals |= 1 << i;
+        }
+    }
+
+    Ok((cumulative_quotes, rounded_intervals))
+}
+
+impl PriceCurve<'_> {
+    /// Price of the curve at `supply`, linearly interpolated within its interval
+    pub fn get_price(&self, supply: u64) -> Result<u64> {
+        let normalized_supply = normalize_base_amount(supply);
+
+        let i = usize::try_from(normalized_supply / u128::from(self.width_scaled))?;
+        let interval_number = self.interval_number as usize;
+
+        if i >= interval_number {
+            return Ok(self.prices[interval_number]);
+        }
+
+        let price_0 = self.prices[i];
+        let price_1 = self.prices[i + 1];
+
+        let price_delta = mul_div(
+            u128::from(price_1 - price_0),
+            normalized_supply % u128::from(self.width_scaled),
+            u128::from(self.width_scaled),
+            Rounding::Down,
+        )
+        .ok_or(MathError)?;
+
+        Ok(price_0 + u64::try_from(price_delta)?)
+    }
+
+    /// Lowest supply at which the price of the curve is at least `price`, the end of the curve if it never is
+    pub fn get_supply_at_price(&self, price: u64) -> Result<u64> {
+        let width_scaled = u128::from(self.width_scaled);
+        let interval_number = self.interval_number as usize;
+
+        // Prices don't decrease, `i` is the first one at or above `price`
+        let i = self.prices[..=interval_number].partition_point(|&curve_price| curve_price < price);
+
+        let normalized_supply = if i == 0 {
+            0
+        } else if i > interval_number {
+            interval_number as u128 * width_scaled
+        } else {
+            let price_0 = self.prices[i - 1];
+            let price_1 = self.prices[i];
+
+            // Inverse of `get_price`, `price_0 < price <= price_1`
+            (i as u128 - 1) * width_scaled
+                + mul_div(
+                    u128::from(price - price_0),
+                    width_scaled,
+                    u128::from(price_1 - price_0),
+                    Rounding::Up,
+                )
+                .ok_or(MathError)?
+        };
+
+        denormalize_base_amount(normalized_supply, Rounding::Up)
+    }
+
+    /// Normalized quote amount of the full intervals between the prices `start` and `end`
+    pub fn get_intervals_quote(&self, start: usize, end: usize, rounding: Rounding) -> u128 {
+        let quote = self.cumulative_quotes[end] - self.cumulative_quotes[start];
+
+        match rounding {
+            Rounding::Down => quote,
+            Rounding::Up => {
+                let intervals = ((1u64 << (end - start)) - 1) << start;
+
+                quote + u128::from((self.rounded_intervals & intervals).count_ones())
+            }
+        }
+    }
+
+    /// Base amount swapped and quote amount of `base_amount` taken from the curve starting at `supply`.
+    /// Less than `base_amount` is swapped if the curve ends before.
+    pub fn get_quote_amount(
+        &self,
+        supply: u64,
+        base_amount: u64,
+        rounding: Rounding,
+    ) -> Result<(u64, u64)> {
+        let width_scaled = u128::from(self.width_scaled);
+        let interval_number = self.interval_number as usize;
+
+        let normalized_supply = normalize_base_amount(supply);
+
+        let mut normalized_base_amount_left = normalize_base_amount(base_amount);
+
+        let mut i = usize::try_from(normalized_supply / width_scaled)?;
+        let interval_supply_already_used = normalized_supply % width_scaled;
+
+        let mut normalized_quote_amount: u128 = 0;
+
+        // Interval of the current supply, the only one that can be partially used already
+        if normalized_base_amount_left > 0 && i < interval_number {
+            let delta_base = min(
+                normalized_base_amount_left,
+                width_scaled - interval_supply_already_used,
+            );
+
+            normalized_quote_amount = get_delta_quote(
+                self.prices[i].into(),
+                self.prices[i + 1].into(),
+                width_scaled,
+                interval_supply_already_used,
+                delta_base,
+                rounding,
+            )?;
+
+            normalized_base_amount_left -= delta_base;
+            i += 1;
+        }
+
+        // Full intervals, their quote amounts are precomputed
+        let full_intervals = min(
+            usize::try_from(normalized_base_amount_left / width_scaled)?,
+            interval_number - i,
+        );
+
+        if full_intervals > 0 {
+            normalized_quote_amount = normalized_quote_amount
+                .checked_add(self.get_intervals_quote(i, i + full_intervals, rounding))
+                .ok_or(MathError)?;
+
+            normalized_base_amount_left -= full_intervals as u128 * width_scaled;
+            i += full_intervals;
+        }
+
+        // Interval the swap ends in
+        if normalized_base_amount_left > 0 && i < interval_number {
+            let delta_quote = get_delta_quote(
+                self.prices[i].into(),
+                self.prices[i + 1].into(),
+                width_scaled,
+                0,
+                normalized_base_amount_left,
+                rounding,
+            )?;
+
+            normalized_quote_amount = normalized_quote_amount
+                .checked_add(delta_quote)
+                .ok_or(MathError)?;
+
+            normalized_base_amount_left = 0;
+        }
+
+        let base_amount_swapped =
+            base_amount - denormalize_base_amount(normalized_base_amount_left, rounding)?;
+
+        let quote_amount_swapped =
+            denormalize_quote_amount(normalized_quote_amount, self.quote_token_decimals, rounding)?;
+
+        Ok((base_amount_swapped, quote_amount_swapped))
+    }
+
+    /// Base amount to sell from `supply` down to get `quote_amount`, and the quote amount actually received.
+    /// Rounded against the seller.
+    pub fn get_base_amount_in(&self, supply: u64, quote_amount: u64) -> Result<(u64, u64)> {
+        let normalized_supply = normalize_base_amount(supply);
+
+        let mut normalized_quote_amount_left =
+            normalize_quote_amount(quote_amount, self.quote_token_decimals)?;
+        let mut normalized_base_amount = 0;
+
+        let width_scaled = u128::from(self.width_scaled);
+
+        let mut i = usize::try_from(normalized_supply / width_scaled)?;
+        let mut interval_supply_available = normalized_supply % width_scaled;
+
+        if interval_supply_available == 0 {
+            interval_supply_available = width_scaled;
+        } else {
+            i += 1;
+        }
+
+        // Interval of the current supply, the only one that can be partially sold already
+        if normalized_quote_amount_left > 0 && i > 0 {
+            let (delta_base, delta_quote) = get_delta_base_in(
+                self.prices[i - 1].into(),
+                self.prices[i].into(),
+                width_scaled,
+                interval_supply_available,
+                normalized_quote_amount_left,
+            )?;
+
+            normalized_base_amount += delta_base;
+            normalized_quote_amount_left -= delta_quote;
+
+            i -= 1;
+        }
+
+        if normalized_quote_amount_left > 0 && i > 0 {
+            let intervals_quote = |start: usize| self.get_intervals_quote(start, i, Rounding::Down);
+
+            // Lowest price such that the intervals up to the current price cost less than the quote amount left
+            let mut low = 0;
+            let mut high = i;
+
+            while low < high {
+                let mid = (low + high) / 2;
+
+                if intervals_quote(mid) < normalized_quote_amount_left {
+                    high = mid;
+                } else {
+                    low = mid + 1;
+                }
+            }
+
+            normalized_base_amount += (i - low) as u128 * width_scaled;
+            normalized_quote_amount_left -= intervals_quote(low);
+
+            i = low;
+
+            // Interval the swap ends in, its quote amount is at least the quote amount left
+            if i > 0 {
+                let (delta_base, delta_quote) = get_delta_base_in(
+                    self.prices[i - 1].into(),
+                    self.prices[i].into(),
+                    width_scaled,
+                    width_scaled,
+                    normalized_quote_amount_left,
+                )?;
+
+                normalized_base_amount += delta_base;
+                normalized_quote_amount_left -= delta_quote;
+            }
+        }
+
+        let base_amount_swapped = denormalize_base_amount(normalized_base_amount, Rounding::Up)?;
+
+        let quote_amount_swapped = quote_amount
+            - denormalize_quote_amount(
+                normalized_quote_amount_left,
+                self.quote_token_decimals,
+                Rounding::Up,
+            )?;
+
+        Ok((base_amount_swapped, quote_amount_swapped))
+    }
+
+    /// Base amount bought from `supply` up with `quote_amount`, and the quote amount actually spent.
+    /// Rounded against the buyer.
+    pub fn get_base_amount_out(&self, supply: u64, quote_amount: u64) -> Result<(u64, u64)> {
+        let normalized_supply = normalize_base_amount(supply);
+
+        let mut normalized_quote_amount_left =
+            normalize_quote_amount(quote_amount, self.quote_token_decimals)?;
+        let mut normalized_base_amount = 0;
+
+        let width_scaled = u128::from(self.width_scaled);
+        let interval_number = self.interval_number as usize;
+
+        let mut i = usize::try_from(normalized_supply / width_scaled)?;
+        let interval_supply_already_used = normalized_supply % width_scaled;
+
+        // Interval of the current supply, the only one that can be partially bought already
+        if normalized_quote_amount_left > 0 && i < interval_number {
+            let (delta_base, delta_quote) = get_delta_base_out(
+                self.prices[i].into(),
+                self.prices[i + 1].into(),
+                width_scaled,
+                interval_supply_already_used,
+                normalized_quote_amount_left,
+            )?;
+
+            normalized_base_amount += delta_base;
+            normalized_quote_amount_left -= delta_quote;
+
+            i += 1;
+        }
+
+        if normalized_quote_amount_left > 0 && i < interval_number {
+            let intervals_quote = |end: usize| self.get_intervals_quote(i, end, Rounding::Up);
+
+            // Highest price such that the intervals from the current price cost less than the quote amount left
+            let mut low = i;
+            let mut high = interval_number;
+
+            while low < high {
+                let mid = (low + high).div_ceil(2);
+
+                if intervals_quote(mid) < normalized_quote_amount_left {
+                    low = mid;
+                } else {
+                    high = mid - 1;
+                }
+            }
+
+            normalized_base_amount += (low - i) as u128 * width_scaled;
+            normalized_quote_amount_left -= intervals_quote(low);
+
+            i = low;
+
+            // Interval the swap ends in, its quote amount is at least the quote amount left
+            if i < interval_number {
+                let (delta_base, delta_quote) = get_delta_base_out(
+                    self.prices[i].into(),
+                    self.prices[i + 1].into(),
+                    width_scaled,
+                    0,
+                    normalized_quote_amount_left,
+                )?;
+
+                normalized_base_amount += delta_base;
+                normalized_quote_amount_left -= delta_quote;
+            }
+        }
+
+        let base_amount_swapped = denormalize_base_amount(normalized_base_amount, Rounding::Down)?;
+
+        let quote_amount_swapped = quote_amount
+            - denormalize_quote_amount(
+                normalized_quote_amount_left,
+                self.quote_token_decimals,
+                Rounding::Down,
+            )?;
+
+        Ok((base_amount_swapped, quote_amount_swapped))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constant::{BASE_PRECISION, INTERVAL_NUMBER, PRICES_LENGTH},
+        math::div,
+    };
+
+    const TOTAL_SUPPLY: u64 = 12_345_670_000;
+    const QUOTE_TOKEN_DECIMALS: u8 = 6;
+
+    /// Curve whose intervals don't hold whole quote amounts, over a supply that isn't a round number
+    fn irregular_prices() -> ([u64; MAX_PRICES_LENGTH], [u64; MAX_PRICES_LENGTH]) {
+        let mut bid_prices = [0; MAX_PRICES_LENGTH];
+        let mut ask_prices = [0; MAX_PRICES_LENGTH];
+
+        for i in 0..PRICES_LENGTH {
+            ask_prices[i] = (i * i) as u64 * 1_000_003 + 7;
+            bid_prices[i] = ask_prices[i] * 9 / 10;
+        }
+
+        (bid_prices, ask_prices)
+    }
+
+    fn width_scaled() -> u64 {
+        (u128::from(TOTAL_SUPPLY / INTERVAL_NUMBER) * SCALE / u128::from(BASE_PRECISION)) as u64
+    }
+
+    fn with_curve<T>(prices: &[u64; MAX_PRICES_LENGTH], f: impl FnOnce(PriceCurve) -> T) -> T {
+        let (cumulative_quotes, rounded_intervals) =
+            get_cumulative_quotes(prices, INTERVAL_NUMBER, width_scaled()).unwrap();
+
+        f(PriceCurve {
+            prices,
+            cumulative_quotes: &cumulative_quotes,
+            rounded_intervals,
+            width_scaled: width_scaled(),
+            interval_number: INTERVAL_NUMBER,
+            quote_token_decimals: QUOTE_TOKEN_DECIMALS,
+        })
+    }
+
+    /// Interval by interval walk of `get_base_amount_out`, returning the normalized amounts
+    fn walk_base_amount_out(curve: &PriceCurve, supply: u64, quote_amount: u128) -> (u128, u128) {
+        let width_scaled = u128::from(curve.width_scaled);
+        let normalized_supply = u128::from(supply) * SCALE / u128::from(BASE_PRECISION);
+
+        let mut i = (normalized_supply / width_scaled) as usize;
+        let mut interval_supply_already_used = normalized_supply % width_scaled;
+        let (mut base_amount, mut quote_amount_left) = (0, quote_amount);
+
+        while quote_amount_left > 0 && i < curve.interval_number as usize {
+            let (delta_base, delta_quote) = get_delta_base_out(
+                curve.prices[i].into(),
+                curve.prices[i + 1].into(),
+                width_scaled,
+                interval_supply_already_used,
+                quote_amount_left,
+            )
+            .unwrap();
+
+            base_amount += delta_base;
+            quote_amount_left -= delta_quote;
+            interval_supply_already_used = 0;
+            i += 1;
+        }
+
+        (base_amount, quote_amount_left)
+    }
+
+    /// Interval by interval walk of `get_base_amount_in`, returning the normalized amounts
+    fn walk_base_amount_in(curve: &PriceCurve, supply: u64, quote_amount: u128) -> (u128, u128) {
+        let width_scaled = u128::from(curve.width_scaled);
+        let normalized_supply = u128::from(supply) * SCALE / u128::from(BASE_PRECISION);
+
+        let mut i = normalized_supply.div_ceil(width_scaled) as usize;
+        let mut interval_supply_available = match normalized_supply % width_scaled {
+            0 => width_scaled,
+            available => available,
+        };
+        let (mut base_amount, mut quote_amount_left) = (0, quote_amount);
+
+        while quote_amount_left > 0 && i > 0 {
+            let (delta_base, delta_quote) = get_delta_base_in(
+                curve.prices[i - 1].into(),
+                curve.prices[i].into(),
+                width_scaled,
+                interval_supply_available,
+                quote_amount_left,
+            )
+            .unwrap();
+
+            base_amount += delta_base;
+            quote_amount_left -= delta_quote;
+            interval_supply_available = width_scaled;
+            i -= 1;
+        }
+
+        (base_amount, quote_amount_left)
+    }
+
+    #[test]
+    fn get_intervals_quote() {
+        let (bid_prices, ask_prices) = irregular_prices();
+        let width_scaled = u128::from(width_scaled());
+
+        for prices in [&bid_prices, &ask_prices] {
+            with_curve(prices, |curve| {
+                assert_ne!(curve.rounded_intervals, 0);
+
+                for rounding in [Rounding::Down, Rounding::Up] {
+                    for start in 0..PRICES_LENGTH {
+                        for end in start..PRICES_LENGTH {
+                            let walked_quote = (start..end)
+                                .map(|i| {
+                                    get_delta_quote(
+                                        prices[i].into(),
+                                        prices[i + 1].into(),
+                                        width_scaled,
+                                        0,
+                                        width_scaled,
+                                        rounding,
+                                    )
+                                    .unwrap()
+                                })
+                                .sum::<u128>();
+
+                            assert_eq!(
+                                curve.get_intervals_quote(start, end, rounding),
+                                walked_quote
+                            );
+                        }
+                    }
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn get_base_amount_matches_interval_walk() {
+        let (bid_prices, ask_prices) = irregular_prices();
+        let quote_precision = 10u128.pow(u32::from(QUOTE_TOKEN_DECIMALS));
+
+        with_curve(&bid_prices, |bid_curve| {
+            with_curve(&ask_prices, |ask_curve| {
+                let (_, full_quote_amount) = ask_curve
+                    .get_quote_amount(0, TOTAL_SUPPLY, Rounding::Up)
+                    .unwrap();
+
+                for supply in [0, 1, TOTAL_SUPPLY / 10, TOTAL_SUPPLY / 3, TOTAL_SUPPLY - 1] {
+                    for quote_amount in [
+                        1,
+                        full_quote_amount / 7,
+                        full_quote_amount / 2,
+                        full_quote_amount,
+                        full_quote_amount + 1,
+                    ] {
+                        let normalized_quote_amount =
+                            u128::from(quote_amount) * SCALE / quote_precision;
+
+                        let (base_amount, quote_amount_left) =
+                            walk_base_amount_out(&ask_curve, supply, normalized_quote_amount);
+
+                        assert_eq!(
+                            ask_curve.get_base_amount_out(supply, quote_amount).unwrap(),
+                            (
+                                div(
+                                    base_amount * u128::from(BASE_PRECISION),
+                                    SCALE,
+                                    Rounding::Down
+                                )
+                                .unwrap(),
+                                quote_amount
+                                    - div(
+                                        quote_amount_left * quote_precision,
+                                        SCALE,
+                                        Rounding::Down
+                                    )
+                                    .unwrap()
+                            )
+                        );
+
+                        let (base_amount, quote_amount_left) =
+                            walk_base_amount_in(&bid_curve, supply, normalized_quote_amount);
+
+                        assert_eq!(
+                            bid_curve.get_base_amount_in(supply, quote_amount).unwrap(),
+                            (
+                                div(
+                                    base_amount * u128::from(BASE_PRECISION),
+                                    SCALE,
+                                    Rounding::Up
+                                )
+                                .unwrap(),
+                                quote_amount
+                                    - div(quote_amount_left * quote_precision, SCALE, Rounding::Up)
+                                        .unwrap()
+                            )
+                        );
+                    }
+                }
+            });
+        });
+    }
+
+    #[test]
+    fn get_supply_at_price() {
+        let (_, ask_prices) = irregular_prices();
+
+        with_curve(&ask_prices, |curve| {
+            for price in [
+                0,
+                7,
+                8,
+                ask_prices[3],
+                ask_prices[3] + 1,
+                ask_prices[9] + 12_345,
+            ] {
+                let supply = curve.get_supply_at_price(price).unwrap();
+
+                assert!(curve.get_price(supply).unwrap() >= price);
+
+                if supply > 0 {
+                    assert!(curve.get_price(supply - 1).unwrap() < price);
+                }
+            }
+
+            assert_eq!(
+                curve.get_supply_at_price(ask_prices[10] + 1).unwrap(),
+                TOTAL_SUPPLY
+            );
+        });
+    }
+}
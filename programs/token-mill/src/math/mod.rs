@@ -1,14 +1,67 @@
-use anchor_lang::prelude::*;
+use core::num::TryFromIntError;
+
 use ruint::aliases::U256;
 
 use crate::constant::SCALE;
 
+pub mod curve;
+pub mod normalization;
+
+/// Overflow or out of range result of the curve math, `TokenMillError::MathError` on-chain.
+/// The math doesn't depend on Anchor, so that it can be built off-chain with `default-features = false`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct MathError;
+
+pub type Result<T> = core::result::Result<T, MathError>;
+
+impl From<TryFromIntError> for MathError {
+    fn from(_: TryFromIntError) -> Self {
+        MathError
+    }
+}
+
+#[cfg(feature = "program")]
+impl From<MathError> for anchor_lang::error::Error {
+    fn from(_: MathError) -> Self {
+        crate::errors::TokenMillError::MathError.into()
+    }
+}
+
 #[derive(PartialEq, Clone, Copy)]
 pub enum Rounding {
     Up,
     Down,
 }
 
+/// Normalized quote amount of `delta_base` taken from an interval going linearly from `price_0` to `price_1`,
+/// starting `interval_supply_already_used` into it.
+/// Intermediate products are computed on 256 bits so only a result above `u128::MAX` fails.
+pub fn get_delta_quote(
+    price_0: u128,
+    price_1: u128,
+    width_scaled: u128,
+    interval_supply_already_used: u128,
+    delta_base: u128,
+    rounding: Rounding,
+) -> Result<u128> {
+    let numerator = U256::from(delta_base)
+        .checked_mul(
+            U256::from(price_1 - price_0)
+                * (U256::from(delta_base)
+                    + U256::from(interval_supply_already_used) * U256::from(2))
+                + U256::from(price_0) * U256::from(width_scaled) * U256::from(2),
+        )
+        .ok_or(MathError)?;
+    let denominator = U256::from(SCALE) * U256::from(width_scaled) * U256::from(2);
+
+    let delta_quote = match rounding {
+        Rounding::Up => numerator.div_ceil(denominator),
+        Rounding::Down => numerator / denominator,
+    };
+
+    to_u128(delta_quote)
+}
+
 pub fn get_delta_base_in(
     price_0: u128,
     price_1: u128,
@@ -18,16 +71,22 @@ pub fn get_delta_base_in(
 ) -> Result<(u128, u128)> {
     let price_diff = price_1 - price_0;
 
-    let current_quote = mul_div(
+    let current_quote = get_delta_quote(
+        price_0,
+        price_1,
+        width_scaled,
+        0,
         interval_supply_available,
-        price_diff * interval_supply_available + 2 * price_0 * width_scaled,
-        2 * SCALE * width_scaled,
         Rounding::Down,
-    )
-    .unwrap();
+    )?;
 
     if remaining_quote >= current_quote {
         Ok((interval_supply_available, current_quote))
+    } else if price_diff == 0 {
+        // Constant price, `price_0 > 0` as `current_quote > 0`
+        let delta_base = mul_div(remaining_quote, SCALE, price_0, Rounding::Up).ok_or(MathError)?;
+
+        Ok((delta_base, remaining_quote))
     } else {
         let sqrt_discriminant = get_sqrt_discriminant(
             price_diff,
@@ -36,10 +95,11 @@ pub fn get_delta_base_in(
             current_quote - remaining_quote,
         )?;
 
-        let rl = price_0 * width_scaled + price_diff * interval_supply_available;
-        let delta_base = div(rl - sqrt_discriminant, price_diff, Rounding::Up)?;
+        let rl = U256::from(price_0) * U256::from(width_scaled)
+            + U256::from(price_diff) * U256::from(interval_supply_available);
+        let delta_base = (rl - sqrt_discriminant).div_ceil(U256::from(price_diff));
 
-        Ok((delta_base.into(), remaining_quote))
+        Ok((to_u128(delta_base)?, remaining_quote))
     }
 }
 
@@ -52,32 +112,45 @@ pub fn get_delta_base_out(
 ) -> Result<(u128, u128)> {
     let price_diff = price_1 - price_0;
 
-    let current_quote = mul_div(
+    let current_quote = get_delta_quote(
+        price_0,
+        price_1,
+        width_scaled,
+        0,
         interval_supply_already_used,
-        price_diff * interval_supply_already_used + 2 * price_0 * width_scaled,
-        2 * SCALE * width_scaled,
         Rounding::Down,
-    )
-    .unwrap();
+    )?;
 
-    let next_quote = div((price_0 + price_1) * width_scaled, 2 * SCALE, Rounding::Up)?;
+    let next_quote = to_u128(
+        ((U256::from(price_0) + U256::from(price_1)) * U256::from(width_scaled))
+            .div_ceil(U256::from(SCALE) * U256::from(2)),
+    )?;
 
-    let max_quote = u128::from(next_quote) - current_quote;
+    let max_quote = next_quote - current_quote;
 
     if remaining_quote >= max_quote {
         Ok((width_scaled - interval_supply_already_used, max_quote))
+    } else if price_diff == 0 {
+        // Constant price, `price_0 > 0` as `max_quote > 0`
+        let delta_base =
+            mul_div(remaining_quote, SCALE, price_0, Rounding::Down).ok_or(MathError)?;
+
+        Ok((delta_base, remaining_quote))
     } else {
         let sqrt_discriminant = get_sqrt_discriminant(
             price_diff,
             price_0,
             width_scaled,
-            current_quote + remaining_quote,
+            current_quote
+                .checked_add(remaining_quote)
+                .ok_or(MathError)?,
         )?;
 
-        let rr = price_0 * width_scaled + price_diff * interval_supply_already_used;
-        let delta_base = div(sqrt_discriminant - rr, price_diff, Rounding::Down)?;
+        let rr = U256::from(price_0) * U256::from(width_scaled)
+            + U256::from(price_diff) * U256::from(interval_supply_already_used);
+        let delta_base = (sqrt_discriminant - rr) / U256::from(price_diff);
 
-        Ok((delta_base.into(), remaining_quote))
+        Ok((to_u128(delta_base)?, remaining_quote))
     }
 }
 
@@ -86,12 +159,17 @@ pub fn get_sqrt_discriminant(
     price_0: u128,
     width_scaled: u128,
     current_quote: u128,
-) -> Result<u128> {
-    let dl = U256::from(width_scaled * price_diff) * U256::from(current_quote * 2 * SCALE);
-    let dr = U256::from(price_0 * width_scaled) * U256::from(price_0 * width_scaled);
-    let d = dl + dr;
-    let sqrt_discriminant = d.root(2);
-    Ok(sqrt_discriminant.try_into().unwrap())
+) -> Result<U256> {
+    let dl = (U256::from(width_scaled) * U256::from(price_diff))
+        .checked_mul(U256::from(current_quote) * U256::from(SCALE) * U256::from(2))
+        .ok_or(MathError)?;
+    let dr = U256::from(price_0) * U256::from(width_scaled);
+    let d = dr
+        .checked_mul(dr)
+        .and_then(|dr| dl.checked_add(dr))
+        .ok_or(MathError)?;
+
+    Ok(d.root(2))
 }
 
 pub fn mul_div(x: u128, y: u128, denominator: u128, rounding: Rounding) -> Option<u128> {
@@ -115,7 +193,7 @@ pub fn mul_div(x: u128, y: u128, denominator: u128, rounding: Rounding) -> Optio
 }
 
 pub fn div(a: u128, b: u128, rounding: Rounding) -> Result<u64> {
-    let rounding = if rounding == Rounding::Up && a % b != 0 {
+    let rounding = if rounding == Rounding::Up && !a.is_multiple_of(b) {
         1
     } else {
         0
@@ -123,3 +201,126 @@ pub fn div(a: u128, b: u128, rounding: Rounding) -> Result<u64> {
 
     Ok(u64::try_from(a / b)? + rounding)
 }
+
+/// Narrows a 256 bits intermediate result, failing with `MathError` if it doesn't fit
+pub fn to_u128(x: U256) -> Result<u128> {
+    x.try_into().map_err(|_| MathError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constant::{BASE_PRECISION, MAX_PRICE, MAX_TOTAL_SUPPLY};
+
+    const WIDTH_SCALED: u128 = 1_000_000_000_000;
+    const PRICE: u128 = 1_000_000_000;
+
+    #[test]
+    fn get_delta_base_out_with_constant_price() {
+        let remaining_quote = 123_456_789;
+
+        let (delta_base, delta_quote) =
+            get_delta_base_out(PRICE, PRICE, WIDTH_SCALED, 0, remaining_quote).unwrap();
+
+        assert_eq!(delta_quote, remaining_quote);
+        assert_eq!(delta_base, remaining_quote * SCALE / PRICE);
+
+        // The generic path on an almost flat interval can't be more accurate
+        let (generic_delta_base, _) =
+            get_delta_base_out(PRICE, PRICE + 1, WIDTH_SCALED, 0, remaining_quote).unwrap();
+
+        assert!(generic_delta_base <= delta_base);
+        assert!(delta_base - generic_delta_base <= 1);
+    }
+
+    #[test]
+    fn get_delta_base_in_with_constant_price() {
+        let remaining_quote = 123_456_789;
+
+        let (delta_base, delta_quote) =
+            get_delta_base_in(PRICE, PRICE, WIDTH_SCALED, WIDTH_SCALED, remaining_quote).unwrap();
+
+        assert_eq!(delta_quote, remaining_quote);
+        assert_eq!(delta_base, (remaining_quote * SCALE).div_ceil(PRICE));
+
+        let (generic_delta_base, _) = get_delta_base_in(
+            PRICE - 1,
+            PRICE,
+            WIDTH_SCALED,
+            WIDTH_SCALED,
+            remaining_quote,
+        )
+        .unwrap();
+
+        assert!(generic_delta_base >= delta_base);
+        assert!(generic_delta_base - delta_base <= 1);
+    }
+
+    #[test]
+    fn get_delta_base_with_full_constant_interval() {
+        let interval_quote = WIDTH_SCALED * PRICE / SCALE;
+
+        assert_eq!(
+            get_delta_base_out(PRICE, PRICE, WIDTH_SCALED, 0, u128::MAX / 2).unwrap(),
+            (WIDTH_SCALED, interval_quote)
+        );
+        assert_eq!(
+            get_delta_base_in(PRICE, PRICE, WIDTH_SCALED, WIDTH_SCALED, u128::MAX / 2).unwrap(),
+            (WIDTH_SCALED, interval_quote)
+        );
+    }
+
+    #[test]
+    fn get_delta_base_with_max_price() {
+        // Single interval spanning the largest supply, up to the highest price
+        let width_scaled = u128::from(MAX_TOTAL_SUPPLY) * SCALE / u128::from(BASE_PRECISION);
+        let price_0 = u128::from(MAX_PRICE) / 2;
+        let price_1 = u128::from(MAX_PRICE);
+
+        let interval_quote = get_delta_quote(
+            price_0,
+            price_1,
+            width_scaled,
+            0,
+            width_scaled,
+            Rounding::Down,
+        )
+        .unwrap();
+
+        assert_eq!(
+            interval_quote,
+            (price_0 + price_1) * width_scaled / (2 * SCALE)
+        );
+
+        let remaining_quote = interval_quote / 3;
+
+        let (delta_base, delta_quote) =
+            get_delta_base_out(price_0, price_1, width_scaled, 0, remaining_quote).unwrap();
+
+        assert_eq!(delta_quote, remaining_quote);
+        assert!(
+            get_delta_quote(
+                price_0,
+                price_1,
+                width_scaled,
+                0,
+                delta_base,
+                Rounding::Down
+            )
+            .unwrap()
+                <= remaining_quote
+        );
+
+        let (delta_base, delta_quote) = get_delta_base_in(
+            price_0,
+            price_1,
+            width_scaled,
+            width_scaled,
+            remaining_quote,
+        )
+        .unwrap();
+
+        assert_eq!(delta_quote, remaining_quote);
+        assert!(delta_base < width_scaled);
+    }
+}
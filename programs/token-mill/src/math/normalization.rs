@@ -0,0 +1,181 @@
+use crate::constant::{BASE_PRECISION, MAX_QUOTE_TOKEN_DECIMALS, SCALE};
+
+use super::{div, mul_div, MathError, Result, Rounding};
+
+// The curve math works on amounts scaled to `SCALE` units per token, whatever the decimals of the mints.
+// Base tokens always have `BASE_PRECISION` units, quote tokens have `10^quote_token_decimals` units.
+// Above 10 decimals a quote token unit is smaller than a normalized unit, normalizing rounds down to a multiple
+// of `10^(quote_token_decimals - 10)` units, which stays in favor of the market as long as `MAX_QUOTE_TOKEN_DECIMALS`
+// keeps that loss negligible.
+
+/// Number of units of a quote token with `quote_token_decimals` decimals, fails on unsupported decimals
+pub fn quote_precision(quote_token_decimals: u8) -> Result<u128> {
+    if quote_token_decimals > MAX_QUOTE_TOKEN_DECIMALS {
+        return Err(MathError);
+    }
+
+    Ok(10u128.pow(u32::from(quote_token_decimals)))
+}
+
+/// Base amount in normalized units, rounded down
+pub fn normalize_base_amount(base_amount: u64) -> u128 {
+    u128::from(base_amount) * SCALE / u128::from(BASE_PRECISION)
+}
+
+/// Base amount in token units of a normalized base amount
+pub fn denormalize_base_amount(normalized_base_amount: u128, rounding: Rounding) -> Result<u64> {
+    div(
+        normalized_base_amount * u128::from(BASE_PRECISION),
+        SCALE,
+        rounding,
+    )
+}
+
+/// Quote amount in normalized units, rounded down
+pub fn normalize_quote_amount(quote_amount: u64, quote_token_decimals: u8) -> Result<u128> {
+    mul_div(
+        u128::from(quote_amount),
+        SCALE,
+        quote_precision(quote_token_decimals)?,
+        Rounding::Down,
+    )
+    .ok_or(MathError)
+}
+
+/// Quote amount in token units of a normalized quote amount, fails if it doesn't fit in a `u64`
+pub fn denormalize_quote_amount(
+    normalized_quote_amount: u128,
+    quote_token_decimals: u8,
+    rounding: Rounding,
+) -> Result<u64> {
+    let quote_amount = mul_div(
+        normalized_quote_amount,
+        quote_precision(quote_token_decimals)?,
+        SCALE,
+        rounding,
+    )
+    .ok_or(MathError)?;
+
+    Ok(u64::try_from(quote_amount)?)
+}
+
+/// Price, in the unit of the curve prices, of `base_amount` base tokens exchanged for `quote_amount` quote tokens
+pub fn get_execution_price(
+    base_amount: u64,
+    quote_amount: u64,
+    quote_token_decimals: u8,
+    rounding: Rounding,
+) -> Result<u128> {
+    mul_div(
+        u128::from(quote_amount),
+        SCALE * u128::from(BASE_PRECISION),
+        u128::from(base_amount) * quote_precision(quote_token_decimals)?,
+        rounding,
+    )
+    .ok_or(MathError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const SCALE_DECIMALS: u8 = 10;
+
+    #[rstest]
+    fn normalize_quote_amount_round_trip(
+        #[values(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12)] decimals: u8,
+    ) {
+        // Quote amounts lost when normalizing, below one normalized unit
+        let max_loss = 10u64.pow(u32::from(decimals.saturating_sub(SCALE_DECIMALS)));
+
+        for quote_amount in [0, 1, 99, 100, 123_456_789, u64::MAX / 1_000, u64::MAX] {
+            let normalized_quote_amount = normalize_quote_amount(quote_amount, decimals).unwrap();
+
+            let quote_amount_down =
+                denormalize_quote_amount(normalized_quote_amount, decimals, Rounding::Down)
+                    .unwrap();
+            let quote_amount_up =
+                denormalize_quote_amount(normalized_quote_amount, decimals, Rounding::Up).unwrap();
+
+            assert!(quote_amount_down <= quote_amount);
+            assert!(quote_amount - quote_amount_down < max_loss);
+            assert!(quote_amount_up >= quote_amount_down);
+            assert!(quote_amount_up - quote_amount_down <= 1);
+
+            if decimals <= SCALE_DECIMALS {
+                assert_eq!(quote_amount_down, quote_amount);
+                assert_eq!(quote_amount_up, quote_amount);
+            }
+        }
+    }
+
+    #[rstest]
+    fn denormalize_quote_amount_rounding(
+        #[values(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12)] decimals: u8,
+    ) {
+        // One and a half quote token
+        let normalized_quote_amount = 3 * SCALE / 2;
+        let quote_amount = 3 * 10u64.pow(u32::from(decimals)) / 2;
+
+        let quote_amount_down =
+            denormalize_quote_amount(normalized_quote_amount, decimals, Rounding::Down).unwrap();
+        let quote_amount_up =
+            denormalize_quote_amount(normalized_quote_amount, decimals, Rounding::Up).unwrap();
+
+        assert_eq!(quote_amount_down, quote_amount);
+        // A whole token doesn't split in half without decimals
+        assert_eq!(quote_amount_up, quote_amount + u64::from(decimals == 0));
+
+        // A single normalized unit is worth less than a quote token unit up to 9 decimals
+        let quote_amount_down = denormalize_quote_amount(1, decimals, Rounding::Down).unwrap();
+        let quote_amount_up = denormalize_quote_amount(1, decimals, Rounding::Up).unwrap();
+
+        assert_eq!(
+            quote_amount_down,
+            10u64.pow(u32::from(decimals)) / 10u64.pow(u32::from(SCALE_DECIMALS))
+        );
+        assert_eq!(quote_amount_up, quote_amount_down.max(1));
+    }
+
+    #[rstest]
+    fn get_execution_price(#[values(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12)] decimals: u8) {
+        // 2.5 quote tokens for 10 base tokens
+        let base_amount = 10 * BASE_PRECISION;
+        let quote_amount = 25 * 10u64.pow(u32::from(decimals)) / 10;
+
+        let price = super::get_execution_price(base_amount, quote_amount, decimals, Rounding::Down)
+            .unwrap();
+
+        // Rounding the quote amount to whole tokens without decimals
+        let expected_price = if decimals == 0 { SCALE / 5 } else { SCALE / 4 };
+
+        assert_eq!(price, expected_price);
+    }
+
+    #[test]
+    fn normalize_base_amount_round_trip() {
+        for base_amount in [0, 1, BASE_PRECISION, 123_456_789, u64::MAX] {
+            let normalized_base_amount = normalize_base_amount(base_amount);
+
+            assert_eq!(
+                denormalize_base_amount(normalized_base_amount, Rounding::Down).unwrap(),
+                base_amount
+            );
+            assert_eq!(
+                denormalize_base_amount(normalized_base_amount, Rounding::Up).unwrap(),
+                base_amount
+            );
+        }
+    }
+
+    #[rstest]
+    fn normalize_with_unsupported_decimals(
+        #[values(MAX_QUOTE_TOKEN_DECIMALS + 1, 18, 39, u8::MAX)] decimals: u8,
+    ) {
+        assert!(quote_precision(decimals).is_err());
+        assert!(normalize_quote_amount(1, decimals).is_err());
+        assert!(denormalize_quote_amount(SCALE, decimals, Rounding::Down).is_err());
+        assert!(super::get_execution_price(1, 1, decimals, Rounding::Down).is_err());
+    }
+}
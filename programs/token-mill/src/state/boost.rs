@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::constant::STAKING_SCALE;
+
+pub const BOOST_POOL_PDA_SEED: &str = "boost_pool";
+pub const BOOST_POSITION_PDA_SEED: &str = "boost_position";
+
+/// Creator funded rewards, emitted linearly to the stake locked in the top lock tier.
+/// The rewards are held in the market quote token ATA alongside the pending fees, escrowed once accrued to a position.
+#[account]
+#[derive(InitSpace)]
+pub struct BoostPool {
+    pub market: Pubkey,
+    /// Sum of the lock weights of the active boost positions
+    pub total_weight: u64,
+    /// Funded rewards not emitted yet
+    pub reward_remaining: u64,
+    /// Rewards emitted per second, scaled by `STAKING_SCALE`
+    pub reward_rate: u128,
+    pub emission_end: i64,
+    pub last_update: i64,
+    pub acc_reward_amount_per_weight: u128,
+}
+
+impl BoostPool {
+    pub fn initialize(&mut self, market: Pubkey, current_time: i64) -> Result<()> {
+        self.market = market;
+        self.last_update = current_time;
+
+        Ok(())
+    }
+
+    /// Emits the rewards since the last update. Nothing is emitted while no stake is locked,
+    /// the rewards left at the end of the emission can be emitted again by the next funding.
+    pub fn update(&mut self, current_time: i64) -> Result<u128> {
+        let emission_time = current_time.min(self.emission_end);
+
+        if emission_time > self.last_update && self.total_weight > 0 {
+            let emitted_amount = if emission_time == self.emission_end {
+                self.reward_remaining
+            } else {
+                u64::try_from(
+                    self.reward_rate * u128::try_from(emission_time - self.last_update)?
+                        / STAKING_SCALE,
+                )?
+                .min(self.reward_remaining)
+            };
+
+            self.acc_reward_amount_per_weight +=
+                u128::from(emitted_amount) * STAKING_SCALE / u128::from(self.total_weight);
+            self.reward_remaining -= emitted_amount;
+        }
+
+        self.last_update = self.last_update.max(current_time);
+
+        Ok(self.acc_reward_amount_per_weight)
+    }
+
+    /// Adds `amount` to the remaining rewards and spreads them over `duration` from now
+    pub fn fund(&mut self, amount: u64, current_time: i64, duration: i64) -> Result<()> {
+        self.reward_remaining += amount;
+        self.reward_rate =
+            u128::from(self.reward_remaining) * STAKING_SCALE / u128::try_from(duration)?;
+        self.emission_end = current_time + duration;
+
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct BoostPosition {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount_locked: u64,
+    pub lock_end: i64,
+    pub weight: u64,
+    pub pending_rewards: u64,
+    pub acc_reward_amount_per_weight: u128,
+}
+
+impl BoostPosition {
+    pub fn initialize(&mut self, market: Pubkey, user: Pubkey) -> Result<()> {
+        self.market = market;
+        self.user = user;
+
+        Ok(())
+    }
+
+    /// Accrues the rewards since the last update, returning the amount accrued
+    pub fn accrue_rewards(&mut self, acc_reward_amount_per_weight: u128) -> Result<u64> {
+        let reward_amount = if self.weight > 0 {
+            u64::try_from(
+                u128::from(self.weight)
+                    * (acc_reward_amount_per_weight - self.acc_reward_amount_per_weight)
+                    / STAKING_SCALE,
+            )?
+        } else {
+            0
+        };
+
+        self.pending_rewards += reward_amount;
+        self.acc_reward_amount_per_weight = acc_reward_amount_per_weight;
+
+        Ok(reward_amount)
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.amount_locked > 0
+    }
+}
@@ -0,0 +1,214 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    IncentiveWeights, QuoteFeeOverride, RebateTier, StakerDiscountTier, REBATE_TIERS_LENGTH,
+    STAKER_DISCOUNT_TIERS_LENGTH,
+};
+
+pub const CHANGE_LOG_PDA_SEED: &str = "change_log";
+pub const CHANGE_LOG_LENGTH: usize = 32;
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq)]
+#[repr(u8)]
+pub enum ChangeLogParameter {
+    PendingAuthority,
+    DefaultFeeShares,
+    MaxInterfaceFee,
+    FeeDistributionInterval,
+    FirmQuoteFee,
+    ProtocolFeeRecipient,
+    MarketPrices,
+    Creator,
+    MarketFeeShares,
+    MaxRaise,
+    SellPause,
+    SellPauseOverride,
+    /// Old value holds the quote token mint, new value the protocol, referral, creator and staking fee shares
+    QuoteFeeOverride,
+    /// Old value holds the quote token mint, new value the removed fee shares
+    QuoteFeeOverrideRemoval,
+    /// Values hold the buy, sell and stake weights
+    IncentiveWeights,
+    /// Old value holds the wallet of a lifted restriction, new value the wallet of a placed one
+    WalletRestriction,
+    /// Values hold the quote price feeds
+    QuotePriceFeed,
+    SlotPriceMoveLimit,
+    /// Old value holds the quote token mint, new value the tiers of its rebate schedule
+    RebateSchedule,
+    MaxSpread,
+    VirtualSupply,
+    /// Values hold whether the referral fee share is overridden, then the override
+    MarketReferralFeeShare,
+    /// Values hold the launch fee in bps, its recipient and its duration
+    LaunchFee,
+    /// Values hold the max impact fee, the impact threshold and the impact fee rate, in bps
+    ImpactFee,
+    /// Old value holds the wallet, new value its fee discount in bps
+    FeeExemption,
+    /// Values hold the creator and burn fee shares
+    BurnFeeShare,
+    FeeSharesUpdateDelay,
+    /// Old value holds the protocol token market, new value the staker discount tiers
+    StakerDiscounts,
+    PendingProtocolFeeRecipient,
+    EmergencyUnstakePenalty,
+    MinStakeDuration,
+    /// Old value holds the badge collection, new value the badge multiplier in bps
+    StakingBadgeCollection,
+    /// Values hold the graduation programs, the adapter and pool config being in the event
+    GraduationConfig,
+    GraduationSupply,
+    MarketPause,
+    EmergencyAuthority,
+    SwapsPause,
+    PendingCreator,
+    MarketCloseDelay,
+    SecondaryCurvePrice,
+    MinMarketAge,
+}
+
+/// Values are little-endian encoded integers, or raw pubkeys, right-padded with zeros
+#[zero_copy]
+#[derive(Debug, InitSpace)]
+pub struct ChangeLogEntry {
+    pub timestamp: i64,
+    pub parameter: u8,
+    _space: [u8; 7],
+    pub old_value: [u8; 32],
+    pub new_value: [u8; 32],
+}
+
+/// Append-only ring buffer of the parameter changes of a config or a market.
+/// The oldest entries are overwritten once `CHANGE_LOG_LENGTH` changes have been recorded.
+#[account(zero_copy)]
+#[derive(Debug, InitSpace)]
+pub struct ChangeLog {
+    /// Config or market whose changes are recorded
+    pub target: Pubkey,
+    /// Number of changes recorded since creation, the next entry is written at `count % CHANGE_LOG_LENGTH`
+    pub count: u64,
+    pub entries: [ChangeLogEntry; CHANGE_LOG_LENGTH],
+}
+
+impl ChangeLog {
+    pub fn initialize(&mut self, target: Pubkey) -> Result<()> {
+        self.target = target;
+
+        Ok(())
+    }
+
+    pub fn record(
+        &mut self,
+        timestamp: i64,
+        parameter: ChangeLogParameter,
+        old_value: [u8; 32],
+        new_value: [u8; 32],
+    ) {
+        let entry = &mut self.entries[(self.count % CHANGE_LOG_LENGTH as u64) as usize];
+
+        entry.timestamp = timestamp;
+        entry.parameter = parameter as u8;
+        entry.old_value = old_value;
+        entry.new_value = new_value;
+
+        self.count += 1;
+    }
+
+    /// Entry recorded `index` changes ago, 0 being the latest
+    pub fn get_entry(&self, index: u64) -> Option<&ChangeLogEntry> {
+        if index >= self.count.min(CHANGE_LOG_LENGTH as u64) {
+            return None;
+        }
+
+        let position = (self.count - 1 - index) % CHANGE_LOG_LENGTH as u64;
+
+        Some(&self.entries[position as usize])
+    }
+}
+
+pub fn encode_u64(value: u64) -> [u8; 32] {
+    let mut encoded = [0; 32];
+    encoded[..8].copy_from_slice(&value.to_le_bytes());
+
+    encoded
+}
+
+pub fn encode_i64(value: i64) -> [u8; 32] {
+    let mut encoded = [0; 32];
+    encoded[..8].copy_from_slice(&value.to_le_bytes());
+
+    encoded
+}
+
+pub fn encode_fee_shares(first_fee_share: u16, second_fee_share: u16) -> [u8; 32] {
+    let mut encoded = [0; 32];
+    encoded[..2].copy_from_slice(&first_fee_share.to_le_bytes());
+    encoded[2..4].copy_from_slice(&second_fee_share.to_le_bytes());
+
+    encoded
+}
+
+pub fn encode_quote_fee_override(fee_override: &QuoteFeeOverride) -> [u8; 32] {
+    let mut encoded = encode_fee_shares(
+        fee_override.protocol_fee_share,
+        fee_override.referral_fee_share,
+    );
+    encoded[4..6].copy_from_slice(&fee_override.creator_fee_share.to_le_bytes());
+    encoded[6..8].copy_from_slice(&fee_override.staking_fee_share.to_le_bytes());
+
+    encoded
+}
+
+pub fn encode_incentive_weights(weights: &IncentiveWeights) -> [u8; 32] {
+    let mut encoded = encode_fee_shares(weights.buy_weight, weights.sell_weight);
+    encoded[4..6].copy_from_slice(&weights.stake_weight.to_le_bytes());
+
+    encoded
+}
+
+pub fn encode_impact_fee(
+    max_impact_fee_bps: u16,
+    impact_fee_threshold_bps: u16,
+    impact_fee_rate_bps: u16,
+) -> [u8; 32] {
+    let mut encoded = encode_fee_shares(max_impact_fee_bps, impact_fee_threshold_bps);
+    encoded[4..6].copy_from_slice(&impact_fee_rate_bps.to_le_bytes());
+
+    encoded
+}
+
+pub fn encode_launch_fee(launch_fee_bps: u16, recipient: u8, duration: u32) -> [u8; 32] {
+    let mut encoded = encode_fee_shares(launch_fee_bps, recipient.into());
+    encoded[4..8].copy_from_slice(&duration.to_le_bytes());
+
+    encoded
+}
+
+pub fn encode_rebate_tiers(tiers: &[RebateTier; REBATE_TIERS_LENGTH]) -> [u8; 32] {
+    let mut encoded = [0; 32];
+
+    for (i, tier) in tiers.iter().enumerate() {
+        encoded[i * 10..i * 10 + 8].copy_from_slice(&tier.min_volume.to_le_bytes());
+        encoded[i * 10 + 8..i * 10 + 10].copy_from_slice(&tier.rebate_bps.to_le_bytes());
+    }
+
+    encoded
+}
+
+pub fn encode_staker_discount_tiers(
+    tiers: &[StakerDiscountTier; STAKER_DISCOUNT_TIERS_LENGTH],
+) -> [u8; 32] {
+    let mut encoded = [0; 32];
+
+    for (i, tier) in tiers.iter().enumerate() {
+        encoded[i * 10..i * 10 + 8].copy_from_slice(&tier.min_stake.to_le_bytes());
+        encoded[i * 10 + 8..i * 10 + 10].copy_from_slice(&tier.discount_bps.to_le_bytes());
+    }
+
+    encoded
+}
+
+pub fn encode_pubkey(value: Option<Pubkey>) -> [u8; 32] {
+    value.map(|value| value.to_bytes()).unwrap_or_default()
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+pub const COMPOUNDING_VAULT_PDA_SEED: &str = "compounding_vault";
+pub const COMPOUNDING_POSITION_PDA_SEED: &str = "compounding_position";
+
+/// Pools the stake of its depositors in its own stake position, whose staking fees are bought back into
+/// base tokens and restaked by `compound_staking_rewards`. Depositors own shares of the pooled stake.
+#[account]
+#[derive(InitSpace)]
+pub struct CompoundingVault {
+    pub bump: u8,
+    pub market: Pubkey,
+    /// Sum of the shares of the compounding positions
+    pub total_shares: u64,
+    /// Base tokens bought with the staking fees so far
+    pub total_compounded: u64,
+}
+
+impl CompoundingVault {
+    pub fn initialize(&mut self, bump: u8, market: Pubkey) -> Result<()> {
+        self.bump = bump;
+        self.market = market;
+        self.total_shares = 0;
+        self.total_compounded = 0;
+
+        Ok(())
+    }
+
+    /// Shares minted for a deposit of `amount` into a vault staking `amount_staked`
+    pub fn get_shares(&self, amount: u64, amount_staked: u64) -> Result<u64> {
+        if self.total_shares == 0 || amount_staked == 0 {
+            return Ok(amount);
+        }
+
+        Ok(u64::try_from(
+            u128::from(amount) * u128::from(self.total_shares) / u128::from(amount_staked),
+        )?)
+    }
+
+    /// Stake redeemed by `shares` out of a vault staking `amount_staked`
+    pub fn get_amount(&self, shares: u64, amount_staked: u64) -> Result<u64> {
+        if self.total_shares == 0 {
+            return Ok(0);
+        }
+
+        Ok(u64::try_from(
+            u128::from(shares) * u128::from(amount_staked) / u128::from(self.total_shares),
+        )?)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CompoundingPosition {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub shares: u64,
+    /// Until then the shares can't be burned, pushed back by every deposit
+    pub min_stake_end: i64,
+}
+
+impl CompoundingPosition {
+    pub fn initialize(&mut self, vault: Pubkey, user: Pubkey) -> Result<()> {
+        self.vault = vault;
+        self.user = user;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,55 @@
 use anchor_lang::prelude::*;
 
+use crate::{constant::MAX_BPS, errors::TokenMillError, state::GraduationAdapter};
+
+pub const QUOTE_FEE_OVERRIDES_LENGTH: usize = 8;
+pub const REBATE_SCHEDULES_LENGTH: usize = 4;
+pub const REBATE_TIERS_LENGTH: usize = 3;
+pub const FEE_EXEMPTIONS_LENGTH: usize = 8;
+pub const STAKER_DISCOUNT_TIERS_LENGTH: usize = 3;
+
+/// Fee shares applied to markets quoted in `quote_token_mint` instead of the config defaults
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq)]
+pub struct QuoteFeeOverride {
+    pub quote_token_mint: Pubkey,
+    pub protocol_fee_share: u16,
+    pub referral_fee_share: u16,
+    /// Default fee shares of the markets created without creator and staking fee shares, unused if both are 0
+    pub creator_fee_share: u16,
+    pub staking_fee_share: u16,
+}
+
+/// Rebate of the protocol fee, in bps of the quote amount, granted to traders whose 30-day volume reaches `min_volume`.
+/// Tiers with a rebate of 0 are unused
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq)]
+pub struct RebateTier {
+    pub min_volume: u64,
+    pub rebate_bps: u16,
+}
+
+/// Protocol fee rebate tiers of the swaps quoted in `quote_token_mint`, volumes being in quote token units
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq)]
+pub struct RebateSchedule {
+    pub quote_token_mint: Pubkey,
+    pub tiers: [RebateTier; REBATE_TIERS_LENGTH],
+}
+
+/// Discount of the protocol fee, in bps of it, granted to swappers staking at least `min_stake` protocol tokens.
+/// Tiers with a discount of 0 are unused
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq)]
+pub struct StakerDiscountTier {
+    pub min_stake: u64,
+    pub discount_bps: u16,
+}
+
+/// Part of the swap fee waived on the swaps signed by `wallet`, in bps of the fee.
+/// Programs are exempted through the PDA signing their swaps
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq)]
+pub struct FeeExemption {
+    pub wallet: Pubkey,
+    pub fee_discount_bps: u16,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct TokenMillConfig {
@@ -8,6 +58,44 @@ pub struct TokenMillConfig {
     pub protocol_fee_recipient: Pubkey,
     pub default_protocol_fee_share: u16,
     pub referral_fee_share: u16,
+    /// Upper bound on the interface fee a frontend can charge on a swap, in bps of the quote amount. At most
+    /// `MAX_INTERFACE_FEE_BPS`
+    pub max_interface_fee_bps: u16,
+    /// Pending fees are pushed to their destinations every `fee_distribution_interval` swaps, 0 to disable
+    pub fee_distribution_interval: u16,
+    /// Protocol fee charged on firm quote fills, in bps of the quote amount
+    pub firm_quote_fee_bps: u16,
+    /// Unused entries have a default `quote_token_mint`
+    pub quote_fee_overrides: [QuoteFeeOverride; QUOTE_FEE_OVERRIDES_LENGTH],
+    /// Unused entries have a default `quote_token_mint`
+    pub rebate_schedules: [RebateSchedule; REBATE_SCHEDULES_LENGTH],
+    /// Upper bound on the spread between the ask and bid prices of market curves, in bps of the ask price, 0 if unbounded
+    pub max_spread_bps: u16,
+    /// Unused entries have a default `wallet`
+    pub fee_exemptions: [FeeExemption; FEE_EXEMPTIONS_LENGTH],
+    /// Delay before the fee shares proposed by a market creator can be activated, in seconds, 0 to apply them at once
+    pub fee_shares_update_delay: u32,
+    /// Market of the protocol token, whose stakers get the discounts of `staker_discount_tiers`. Default if none
+    pub protocol_token_market: Pubkey,
+    pub staker_discount_tiers: [StakerDiscountTier; STAKER_DISCOUNT_TIERS_LENGTH],
+    /// Recipient proposed by the authority, becoming `protocol_fee_recipient` once it accepts
+    pub pending_protocol_fee_recipient: Option<Pubkey>,
+    /// Part of the stake left to the other stakers by an emergency unstake, in bps, 0 to disable emergency unstakes
+    pub emergency_unstake_penalty_bps: u16,
+    /// AMM markets graduate to, through the adapter of `graduation_program`
+    pub graduation_adapter: GraduationAdapter,
+    /// Pool program markets graduate to, default to disable graduations
+    pub graduation_program: Pubkey,
+    /// Config of the graduation program, e.g. setting the fee tier of the graduation pools
+    pub graduation_pool_config: Pubkey,
+    /// Signer allowed to halt the swaps of every market alongside the authority, default if none
+    pub emergency_authority: Pubkey,
+    /// Set by the emergency authority to halt the swaps of every market, only lifted by the authority
+    pub swaps_paused: bool,
+    /// Delay between the proposal to close a market and its closing, giving its holders time to react, in seconds
+    pub market_close_delay: u32,
+    /// Age a market has to reach before its creator can propose to close it, in seconds
+    pub min_market_age: u32,
 }
 
 impl TokenMillConfig {
@@ -23,6 +111,265 @@ impl TokenMillConfig {
         self.protocol_fee_recipient = protocol_fee_recipient;
         self.default_protocol_fee_share = protocol_fee_share;
         self.referral_fee_share = referral_fee_share;
+        self.max_interface_fee_bps = 0;
+        self.fee_distribution_interval = 0;
+        self.firm_quote_fee_bps = 0;
+        self.quote_fee_overrides = Default::default();
+        self.rebate_schedules = Default::default();
+        self.max_spread_bps = 0;
+        self.fee_exemptions = Default::default();
+        self.fee_shares_update_delay = 0;
+        self.protocol_token_market = Pubkey::default();
+        self.staker_discount_tiers = Default::default();
+        self.pending_protocol_fee_recipient = None;
+        self.emergency_unstake_penalty_bps = 0;
+        self.graduation_adapter = GraduationAdapter::RaydiumCpmm;
+        self.graduation_program = Pubkey::default();
+        self.graduation_pool_config = Pubkey::default();
+        self.emergency_authority = Pubkey::default();
+        self.swaps_paused = false;
+        self.market_close_delay = 0;
+        self.min_market_age = 0;
+
+        Ok(())
+    }
+
+    pub fn get_quote_fee_override(&self, quote_token_mint: &Pubkey) -> Option<&QuoteFeeOverride> {
+        self.quote_fee_overrides
+            .iter()
+            .find(|fee_override| fee_override.quote_token_mint == *quote_token_mint)
+    }
+
+    /// Protocol fee share of new markets quoted in `quote_token_mint`
+    pub fn get_protocol_fee_share(&self, quote_token_mint: &Pubkey) -> u16 {
+        self.get_quote_fee_override(quote_token_mint)
+            .map_or(self.default_protocol_fee_share, |fee_override| {
+                fee_override.protocol_fee_share
+            })
+    }
+
+    /// Share of the protocol fee sent to referrers on swaps quoted in `quote_token_mint`
+    pub fn get_referral_fee_share(&self, quote_token_mint: &Pubkey) -> u16 {
+        self.get_quote_fee_override(quote_token_mint)
+            .map_or(self.referral_fee_share, |fee_override| {
+                fee_override.referral_fee_share
+            })
+    }
+
+    /// Creator and staking fee shares of a new market quoted in `quote_token_mint`.
+    /// Markets created without any take the defaults of the quote token override, if it has some
+    pub fn get_market_fee_shares(
+        &self,
+        quote_token_mint: &Pubkey,
+        creator_fee_share: u16,
+        staking_fee_share: u16,
+    ) -> (u16, u16) {
+        if creator_fee_share != 0 || staking_fee_share != 0 {
+            return (creator_fee_share, staking_fee_share);
+        }
+
+        self.get_quote_fee_override(quote_token_mint)
+            .map_or((0, 0), |fee_override| {
+                (
+                    fee_override.creator_fee_share,
+                    fee_override.staking_fee_share,
+                )
+            })
+    }
+
+    /// Inserts or replaces the override of `quote_token_mint`
+    pub fn set_quote_fee_override(
+        &mut self,
+        quote_token_mint: Pubkey,
+        protocol_fee_share: u16,
+        referral_fee_share: u16,
+        creator_fee_share: u16,
+        staking_fee_share: u16,
+    ) -> Result<()> {
+        let slot = self
+            .quote_fee_overrides
+            .iter()
+            .position(|fee_override| fee_override.quote_token_mint == quote_token_mint)
+            .or_else(|| {
+                self.quote_fee_overrides
+                    .iter()
+                    .position(|fee_override| fee_override.quote_token_mint == Pubkey::default())
+            })
+            .ok_or(TokenMillError::QuoteFeeOverridesFull)?;
+
+        self.quote_fee_overrides[slot] = QuoteFeeOverride {
+            quote_token_mint,
+            protocol_fee_share,
+            referral_fee_share,
+            creator_fee_share,
+            staking_fee_share,
+        };
+
+        Ok(())
+    }
+
+    /// Removes the override of `quote_token_mint`, returns the removed one
+    pub fn remove_quote_fee_override(
+        &mut self,
+        quote_token_mint: &Pubkey,
+    ) -> Result<QuoteFeeOverride> {
+        let slot = self
+            .quote_fee_overrides
+            .iter()
+            .position(|fee_override| fee_override.quote_token_mint == *quote_token_mint)
+            .ok_or(TokenMillError::QuoteFeeOverrideNotFound)?;
+
+        let removed_override = self.quote_fee_overrides[slot];
+        self.quote_fee_overrides[slot] = QuoteFeeOverride::default();
+
+        Ok(removed_override)
+    }
+
+    /// Protocol fee discount of a swapper staking `amount_staked` on `market`, in bps of the protocol fee
+    pub fn get_staker_discount_bps(&self, market: &Pubkey, amount_staked: u64) -> u16 {
+        if *market != self.protocol_token_market {
+            return 0;
+        }
+
+        self.staker_discount_tiers
+            .iter()
+            .rev()
+            .find(|tier| tier.discount_bps > 0 && amount_staked >= tier.min_stake)
+            .map_or(0, |tier| tier.discount_bps)
+    }
+
+    /// Sets the protocol token market and the discount tiers of its stakers.
+    /// Used tiers must have increasing stakes and discounts.
+    pub fn set_staker_discounts(
+        &mut self,
+        protocol_token_market: Pubkey,
+        tiers: [StakerDiscountTier; STAKER_DISCOUNT_TIERS_LENGTH],
+    ) -> Result<()> {
+        let mut previous_tier = StakerDiscountTier::default();
+
+        for tier in tiers.iter().filter(|tier| tier.discount_bps > 0) {
+            require!(
+                u64::from(tier.discount_bps) <= MAX_BPS
+                    && tier.discount_bps > previous_tier.discount_bps
+                    && (previous_tier.discount_bps == 0
+                        || tier.min_stake > previous_tier.min_stake),
+                TokenMillError::InvalidStakerDiscountTiers
+            );
+
+            previous_tier = *tier;
+        }
+
+        self.protocol_token_market = protocol_token_market;
+        self.staker_discount_tiers = tiers;
+
+        Ok(())
+    }
+
+    /// Protocol fee rebate of a trader with a 30-day `volume` on swaps quoted in `quote_token_mint`, in bps
+    pub fn get_rebate_bps(&self, quote_token_mint: &Pubkey, volume: u64) -> u16 {
+        self.rebate_schedules
+            .iter()
+            .find(|schedule| schedule.quote_token_mint == *quote_token_mint)
+            .and_then(|schedule| {
+                schedule
+                    .tiers
+                    .iter()
+                    .rev()
+                    .find(|tier| tier.rebate_bps > 0 && volume >= tier.min_volume)
+            })
+            .map_or(0, |tier| tier.rebate_bps)
+    }
+
+    /// Inserts or replaces the rebate schedule of `quote_token_mint`, removes it if all its tiers are unused.
+    /// Used tiers must have increasing volumes and rebates.
+    pub fn set_rebate_schedule(
+        &mut self,
+        quote_token_mint: Pubkey,
+        tiers: [RebateTier; REBATE_TIERS_LENGTH],
+    ) -> Result<()> {
+        let mut previous_tier = RebateTier::default();
+
+        for tier in tiers.iter().filter(|tier| tier.rebate_bps > 0) {
+            require!(
+                u64::from(tier.rebate_bps) <= MAX_BPS
+                    && tier.rebate_bps > previous_tier.rebate_bps
+                    && (previous_tier.rebate_bps == 0
+                        || tier.min_volume > previous_tier.min_volume),
+                TokenMillError::InvalidRebateTiers
+            );
+
+            previous_tier = *tier;
+        }
+
+        let position = self
+            .rebate_schedules
+            .iter()
+            .position(|schedule| schedule.quote_token_mint == quote_token_mint);
+
+        if previous_tier.rebate_bps == 0 {
+            if let Some(position) = position {
+                self.rebate_schedules[position] = RebateSchedule::default();
+            }
+
+            return Ok(());
+        }
+
+        let slot = position
+            .or_else(|| {
+                self.rebate_schedules
+                    .iter()
+                    .position(|schedule| schedule.quote_token_mint == Pubkey::default())
+            })
+            .ok_or(TokenMillError::RebateSchedulesFull)?;
+
+        self.rebate_schedules[slot] = RebateSchedule {
+            quote_token_mint,
+            tiers,
+        };
+
+        Ok(())
+    }
+
+    /// Part of the swap fee waived on the swaps of `wallet`, in bps of the fee
+    pub fn get_fee_discount_bps(&self, wallet: &Pubkey) -> u16 {
+        self.fee_exemptions
+            .iter()
+            .find(|exemption| exemption.wallet == *wallet)
+            .map_or(0, |exemption| exemption.fee_discount_bps)
+    }
+
+    /// Inserts or replaces the fee exemption of `wallet`, removes it if the discount is 0
+    pub fn set_fee_exemption(&mut self, wallet: Pubkey, fee_discount_bps: u16) -> Result<()> {
+        require!(
+            u64::from(fee_discount_bps) <= MAX_BPS,
+            TokenMillError::InvalidFeeShare
+        );
+
+        let position = self
+            .fee_exemptions
+            .iter()
+            .position(|exemption| exemption.wallet == wallet);
+
+        if fee_discount_bps == 0 {
+            if let Some(position) = position {
+                self.fee_exemptions[position] = FeeExemption::default();
+            }
+
+            return Ok(());
+        }
+
+        let slot = position
+            .or_else(|| {
+                self.fee_exemptions
+                    .iter()
+                    .position(|exemption| exemption.wallet == Pubkey::default())
+            })
+            .ok_or(TokenMillError::FeeExemptionsFull)?;
+
+        self.fee_exemptions[slot] = FeeExemption {
+            wallet,
+            fee_discount_bps,
+        };
 
         Ok(())
     }
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::{constant::MAX_BPS, errors::TokenMillError};
+
+pub const CREATOR_FEE_SPLIT_PDA_SEED: &str = "creator_fee_split";
+pub const CREATOR_FEE_RECIPIENTS_LENGTH: usize = 4;
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq)]
+pub struct CreatorFeeRecipient {
+    pub recipient: Pubkey,
+    pub share_bps: u16,
+}
+
+/// Split of the creator fees of a market among several recipients, replacing the creator as sole recipient
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct CreatorFeeSplit {
+    pub bump: u8,
+    pub market: Pubkey,
+    /// Used entries come first and their shares add up to 100%. Unused entries have a default `recipient`
+    pub recipients: [CreatorFeeRecipient; CREATOR_FEE_RECIPIENTS_LENGTH],
+}
+
+impl CreatorFeeSplit {
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        market: Pubkey,
+        recipients: [CreatorFeeRecipient; CREATOR_FEE_RECIPIENTS_LENGTH],
+    ) -> Result<()> {
+        let recipient_count = recipients
+            .iter()
+            .take_while(|entry| entry.recipient != Pubkey::default())
+            .count();
+        let (used, unused) = recipients.split_at(recipient_count);
+
+        require!(
+            used.iter().all(|entry| entry.share_bps > 0)
+                && used
+                    .iter()
+                    .map(|entry| u64::from(entry.share_bps))
+                    .sum::<u64>()
+                    == MAX_BPS
+                && unused
+                    .iter()
+                    .all(|entry| *entry == CreatorFeeRecipient::default())
+                && used.iter().enumerate().all(|(i, entry)| {
+                    used[..i]
+                        .iter()
+                        .all(|other| other.recipient != entry.recipient)
+                }),
+            TokenMillError::InvalidCreatorFeeSplit
+        );
+
+        self.bump = bump;
+        self.market = market;
+        self.recipients = recipients;
+
+        Ok(())
+    }
+
+    pub fn recipients(&self) -> impl Iterator<Item = &CreatorFeeRecipient> {
+        self.recipients
+            .iter()
+            .take_while(|entry| entry.recipient != Pubkey::default())
+    }
+
+    /// Shares of `fees` of the used recipients, the rounding going to the first one
+    pub fn split(&self, fees: u64) -> Result<Vec<u64>> {
+        let mut amounts = self
+            .recipients()
+            .map(|entry| {
+                u64::try_from(u128::from(fees) * u128::from(entry.share_bps) / u128::from(MAX_BPS))
+            })
+            .collect::<std::result::Result<Vec<u64>, _>>()?;
+
+        amounts[0] += fees - amounts.iter().sum::<u64>();
+
+        Ok(amounts)
+    }
+}
@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::BASE_PRECISION,
+    errors::TokenMillError,
+    math::{mul_div, Rounding},
+};
+
+pub const DCA_POSITION_PDA_SEED: &str = "dca_position";
+
+/// Recurring buy schedule of a wallet on a market, executed by permissionless cranks.
+/// The quote tokens of the remaining buys and their crank bounties are escrowed in the market quote token ATA.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct DcaPosition {
+    pub bump: u8,
+    pub market: Pubkey,
+    pub owner: Pubkey,
+    /// Quote amount spent on each buy
+    pub amount_per_buy: u64,
+    /// Quote amount paid to the cranker of each buy
+    pub crank_bounty: u64,
+    /// Minimum number of seconds between two buys
+    pub interval: i64,
+    /// Highest average price accepted for a buy, in quote token units per `BASE_PRECISION` base token units
+    pub max_price: u64,
+    pub buys_remaining: u32,
+    pub next_buy_time: i64,
+    /// Quote tokens held by the market on behalf of the owner
+    pub escrow_amount: u64,
+}
+
+impl DcaPosition {
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        market: Pubkey,
+        owner: Pubkey,
+        amount_per_buy: u64,
+        crank_bounty: u64,
+        interval: i64,
+        max_price: u64,
+        buys_remaining: u32,
+        current_time: i64,
+    ) -> Result<()> {
+        require!(
+            amount_per_buy > 0 && interval > 0 && max_price > 0 && buys_remaining > 0,
+            TokenMillError::InvalidDcaPosition
+        );
+
+        self.bump = bump;
+        self.market = market;
+        self.owner = owner;
+        self.amount_per_buy = amount_per_buy;
+        self.crank_bounty = crank_bounty;
+        self.interval = interval;
+        self.max_price = max_price;
+        self.buys_remaining = buys_remaining;
+        // The first buy can be cranked right away
+        self.next_buy_time = current_time;
+
+        self.escrow_amount = amount_per_buy
+            .checked_add(crank_bounty)
+            .and_then(|amount| amount.checked_mul(u64::from(buys_remaining)))
+            .ok_or(TokenMillError::MathError)?;
+
+        Ok(())
+    }
+
+    /// Schedules the next buy one interval after `current_time`, and releases the escrow of the executed one
+    pub fn execute_buy(&mut self, current_time: i64) -> Result<()> {
+        require!(
+            self.buys_remaining > 0 && current_time >= self.next_buy_time,
+            TokenMillError::DcaBuyNotDue
+        );
+
+        self.buys_remaining -= 1;
+        self.next_buy_time = current_time
+            .checked_add(self.interval)
+            .ok_or(TokenMillError::MathError)?;
+        self.escrow_amount -= self.amount_per_buy + self.crank_bounty;
+
+        Ok(())
+    }
+
+    pub fn check_price(&self, base_amount: u64, quote_amount: u64) -> Result<()> {
+        require!(base_amount > 0, TokenMillError::DcaPriceLimitExceeded);
+
+        let price = mul_div(
+            u128::from(quote_amount),
+            u128::from(BASE_PRECISION),
+            u128::from(base_amount),
+            Rounding::Up,
+        )
+        .ok_or(TokenMillError::MathError)?;
+
+        require!(
+            price <= u128::from(self.max_price),
+            TokenMillError::DcaPriceLimitExceeded
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execute_buy() {
+        let mut dca_position = DcaPosition {
+            bump: 0,
+            market: Pubkey::default(),
+            owner: Pubkey::default(),
+            amount_per_buy: 0,
+            crank_bounty: 0,
+            interval: 0,
+            max_price: 0,
+            buys_remaining: 0,
+            next_buy_time: 0,
+            escrow_amount: 0,
+        };
+
+        dca_position
+            .initialize(
+                0,
+                Pubkey::default(),
+                Pubkey::default(),
+                100,
+                1,
+                60,
+                1_000,
+                2,
+                10,
+            )
+            .unwrap();
+
+        assert_eq!(dca_position.escrow_amount, 202);
+
+        dca_position.execute_buy(10).unwrap();
+
+        assert_eq!(dca_position.buys_remaining, 1);
+        assert_eq!(dca_position.next_buy_time, 70);
+        assert_eq!(dca_position.escrow_amount, 101);
+
+        assert!(dca_position.execute_buy(69).is_err());
+
+        dca_position.execute_buy(100).unwrap();
+
+        assert_eq!(dca_position.escrow_amount, 0);
+        assert!(dca_position.execute_buy(1_000).is_err());
+    }
+
+    #[test]
+    fn check_price() {
+        let mut dca_position = DcaPosition {
+            bump: 0,
+            market: Pubkey::default(),
+            owner: Pubkey::default(),
+            amount_per_buy: 0,
+            crank_bounty: 0,
+            interval: 0,
+            max_price: 1_000,
+            buys_remaining: 0,
+            next_buy_time: 0,
+            escrow_amount: 0,
+        };
+
+        assert!(dca_position.check_price(1_000_000, 1_000).is_ok());
+        assert!(dca_position.check_price(999_999, 1_000).is_err());
+
+        dca_position.max_price = 1_001;
+
+        assert!(dca_position.check_price(999_999, 1_000).is_ok());
+        assert!(dca_position.check_price(0, 1_000).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::BASE_PRECISION,
+    errors::TokenMillError,
+    manager::swap_manager::SwapType,
+    math::{mul_div, Rounding},
+};
+
+pub const MARKET_MAKER_BADGE_PDA_SEED: &str = "market_maker_badge";
+pub const FIRM_QUOTE_ESCROW_PDA_SEED: &str = "firm_quote_escrow";
+
+/// Approval of a market maker by the config authority, required to post and fill firm quotes
+#[account]
+#[derive(InitSpace)]
+pub struct MarketMakerBadge {
+    pub bump: u8,
+    pub config: Pubkey,
+    pub market_maker: Pubkey,
+}
+
+/// Firm quote posted by an approved market maker, fully backed by the tokens escrowed in its own token account,
+/// held by the data-less PDA `[FIRM_QUOTE_ESCROW_PDA_SEED, firm_quote]`.
+/// Takers can fill it as an alternative to the curve, in part or in full, until it expires.
+#[account]
+#[derive(InitSpace)]
+pub struct FirmQuote {
+    pub market: Pubkey,
+    pub market_maker: Pubkey,
+    /// Swap performed by the taker, `Buy` if the market maker sells base tokens
+    pub swap_type: SwapType,
+    /// Base amount that can still be filled
+    pub base_amount: u64,
+    /// Quote token units per `BASE_PRECISION` base token units
+    pub price: u64,
+    pub expiry: i64,
+    /// Tokens held in escrow on behalf of the market maker, base tokens for asks and quote tokens for bids
+    pub escrow_amount: u64,
+}
+
+impl MarketMakerBadge {
+    pub fn initialize(&mut self, bump: u8, config: Pubkey, market_maker: Pubkey) -> Result<()> {
+        self.bump = bump;
+        self.config = config;
+        self.market_maker = market_maker;
+
+        Ok(())
+    }
+}
+
+impl FirmQuote {
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        market: Pubkey,
+        market_maker: Pubkey,
+        swap_type: SwapType,
+        base_amount: u64,
+        price: u64,
+        expiry: i64,
+        current_time: i64,
+    ) -> Result<()> {
+        require!(base_amount > 0, TokenMillError::InvalidAmount);
+        require!(
+            price > 0 && expiry > current_time,
+            TokenMillError::InvalidFirmQuote
+        );
+
+        self.market = market;
+        self.market_maker = market_maker;
+        self.swap_type = swap_type;
+        self.base_amount = base_amount;
+        self.price = price;
+        self.expiry = expiry;
+
+        // Bids escrow enough quote tokens to buy the full size
+        self.escrow_amount = match swap_type {
+            SwapType::Buy => base_amount,
+            SwapType::Sell => self.get_quote_amount(base_amount, Rounding::Up)?,
+        };
+
+        require!(self.escrow_amount > 0, TokenMillError::InvalidAmount);
+
+        Ok(())
+    }
+
+    pub fn is_expired(&self, current_time: i64) -> bool {
+        current_time >= self.expiry
+    }
+
+    pub fn get_quote_amount(&self, base_amount: u64, rounding: Rounding) -> Result<u64> {
+        let quote_amount = mul_div(
+            u128::from(base_amount),
+            u128::from(self.price),
+            u128::from(BASE_PRECISION),
+            rounding,
+        )
+        .ok_or(TokenMillError::MathError)?;
+
+        Ok(u64::try_from(quote_amount)?)
+    }
+
+    /// Fills `base_amount` of the quote, rounding in favor of the market maker.
+    /// Returns the quote amount exchanged.
+    pub fn fill(&mut self, base_amount: u64, current_time: i64) -> Result<u64> {
+        require!(base_amount > 0, TokenMillError::InvalidAmount);
+        require!(
+            !self.is_expired(current_time),
+            TokenMillError::FirmQuoteExpired
+        );
+        require!(
+            base_amount <= self.base_amount,
+            TokenMillError::FirmQuoteAmountExceeded
+        );
+
+        let quote_amount = match self.swap_type {
+            SwapType::Buy => {
+                let quote_amount = self.get_quote_amount(base_amount, Rounding::Up)?;
+
+                self.escrow_amount -= base_amount;
+
+                quote_amount
+            }
+            SwapType::Sell => {
+                let quote_amount = self.get_quote_amount(base_amount, Rounding::Down)?;
+
+                self.escrow_amount -= quote_amount;
+
+                quote_amount
+            }
+        };
+
+        self.base_amount -= base_amount;
+
+        Ok(quote_amount)
+    }
+}
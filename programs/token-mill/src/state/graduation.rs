@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::TokenMillError;
+
+pub const GRADUATION_PDA_SEED: &str = "graduation";
+/// Data-less PDA creating the pool a market graduates to, and holding its liquidity
+pub const GRADUATION_AUTHORITY_PDA_SEED: &str = "graduation_authority";
+/// Mint of the position NFT of the pools whose liquidity is a position, e.g. Meteora DAMM v2
+pub const GRADUATION_POSITION_PDA_SEED: &str = "graduation_position";
+
+/// AMMs markets can graduate to, each adapter building the pool creation from the remaining accounts
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Copy, Clone, InitSpace, PartialEq)]
+pub enum GraduationAdapter {
+    RaydiumCpmm,
+    MeteoraDammV2,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Copy, Clone, InitSpace, PartialEq)]
+pub enum GraduationStatus {
+    /// The curve is disabled and the liquidity of the pool is held by the graduation authority
+    Pending,
+    /// The pool is created with the liquidity
+    Completed,
+}
+
+/// Graduation of a market, started by `graduate_market` and completed once `complete_graduation` creates the pool.
+/// The AMM is set when the graduation starts, config updates don't affect pending graduations
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct Graduation {
+    pub bump: u8,
+    pub market: Pubkey,
+    pub status: GraduationStatus,
+    pub adapter: GraduationAdapter,
+    pub pool_program: Pubkey,
+    /// Config of the pool program, e.g. setting the fee tier of the pool
+    pub pool_config: Pubkey,
+    /// Default until the graduation is completed
+    pub pool: Pubkey,
+    /// Base and quote amounts seeding the pool
+    pub base_amount: u64,
+    pub quote_amount: u64,
+    /// Unsold base amount burned
+    pub burned_amount: u64,
+}
+
+impl Graduation {
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        market: Pubkey,
+        adapter: GraduationAdapter,
+        pool_program: Pubkey,
+        pool_config: Pubkey,
+        base_amount: u64,
+        quote_amount: u64,
+        burned_amount: u64,
+    ) -> Result<()> {
+        self.bump = bump;
+        self.market = market;
+        self.status = GraduationStatus::Pending;
+        self.adapter = adapter;
+        self.pool_program = pool_program;
+        self.pool_config = pool_config;
+        self.pool = Pubkey::default();
+        self.base_amount = base_amount;
+        self.quote_amount = quote_amount;
+        self.burned_amount = burned_amount;
+
+        Ok(())
+    }
+
+    pub fn complete(&mut self, pool: Pubkey) -> Result<()> {
+        require!(
+            self.status == GraduationStatus::Pending,
+            TokenMillError::GraduationCompleted
+        );
+
+        self.status = GraduationStatus::Completed;
+        self.pool = pool;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete() {
+        let mut graduation = Graduation {
+            bump: 0,
+            market: Pubkey::default(),
+            status: GraduationStatus::Pending,
+            adapter: GraduationAdapter::RaydiumCpmm,
+            pool_program: Pubkey::default(),
+            pool_config: Pubkey::default(),
+            pool: Pubkey::default(),
+            base_amount: 100,
+            quote_amount: 50,
+            burned_amount: 0,
+        };
+
+        let pool = Pubkey::new_unique();
+
+        graduation.complete(pool).unwrap();
+
+        assert_eq!(graduation.status, GraduationStatus::Completed);
+        assert_eq!(graduation.pool, pool);
+
+        assert!(graduation.complete(Pubkey::new_unique()).is_err());
+    }
+}
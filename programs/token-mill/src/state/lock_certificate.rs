@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+pub const LOCK_CERTIFICATE_PDA_SEED: &str = "lock_certificate";
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq)]
+pub enum LockType {
+    Vesting,
+}
+
+/// Non-transferable record proving that tokens are locked by the program.
+/// Derived from the account holding the lock, so each lock has at most one certificate.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct LockCertificate {
+    pub bump: u8,
+    pub lock_type: LockType,
+    pub market: Pubkey,
+    /// Account enforcing the lock, e.g. the vesting plan
+    pub target: Pubkey,
+    pub mint: Pubkey,
+    /// Beneficiary of the locked tokens once unlocked
+    pub owner: Pubkey,
+    /// Amount locked when the certificate was issued
+    pub amount: u64,
+    /// Time at which the first tokens unlock
+    pub unlock_start: i64,
+    /// Time at which all the tokens are unlocked
+    pub unlock_end: i64,
+}
+
+impl LockCertificate {
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        lock_type: LockType,
+        market: Pubkey,
+        target: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+        unlock_start: i64,
+        unlock_end: i64,
+    ) -> Result<()> {
+        self.bump = bump;
+        self.lock_type = lock_type;
+        self.market = market;
+        self.target = target;
+        self.mint = mint;
+        self.owner = owner;
+        self.amount = amount;
+        self.unlock_start = unlock_start;
+        self.unlock_end = unlock_end;
+
+        Ok(())
+    }
+}
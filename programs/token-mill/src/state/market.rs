@@ -1,26 +1,81 @@
 use std::cmp::min;
 
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+use bytemuck::Zeroable;
 
 use crate::{
     constant::*,
     errors::TokenMillError,
-    manager::swap_manager::SwapAmountType,
-    math::{div, get_delta_base_in, get_delta_base_out, mul_div, Rounding},
+    manager::swap_manager::{SwapAmountType, SwapType},
+    math::{
+        curve::{get_cumulative_quotes, PriceCurve},
+        mul_div,
+        normalization::{denormalize_quote_amount, normalize_base_amount},
+        Rounding,
+    },
+    state::TokenMillConfig,
 };
 
 pub const MARKET_PDA_SEED: &str = "market";
 
+/// SHA-256 of the little-endian bid prices followed by the little-endian ask prices, unused prices excluded
+pub fn hash_prices(bid_prices: &[u64], ask_prices: &[u64]) -> [u8; 32] {
+    let bytes = bid_prices
+        .iter()
+        .chain(ask_prices.iter())
+        .flat_map(|price| price.to_le_bytes())
+        .collect::<Vec<u8>>();
+
+    hashv(&[&bytes]).to_bytes()
+}
+
 #[zero_copy]
 #[derive(Debug, InitSpace)]
 pub struct MarketFees {
-    /// staking_fee_share + creator_fee_share + protocol_fee_share = 100%
+    /// staking_fee_share + creator_fee_share + burn_fee_share + protocol_fee_share = 100%
     pub staking_fee_share: u16,
     pub creator_fee_share: u16,
-    _space: u32,
+    /// Share of the swap fee bought back from the curve and burned, carved out of the creator fee share
+    pub burn_fee_share: u16,
+    _space: u16,
 
     pub pending_staking_fees: u64,
     pub pending_creator_fees: u64,
+    /// Quote amount earmarked for the next `buyback_and_burn`
+    pub pending_burn_fees: u64,
+}
+
+/// Creator-set weights of the incentive events emitted on user activity, in bps of the activity amount.
+/// A weight of 0 disables the events of that action
+#[zero_copy]
+#[derive(Debug, InitSpace)]
+pub struct IncentiveWeights {
+    pub buy_weight: u16,
+    pub sell_weight: u16,
+    pub stake_weight: u16,
+    _space: u16,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq)]
+pub enum LaunchFeeRecipient {
+    Creator,
+    Staking,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq)]
+pub enum IncentiveAction {
+    Buy,
+    Sell,
+    Stake,
+}
+
+impl From<SwapType> for IncentiveAction {
+    fn from(swap_type: SwapType) -> Self {
+        match swap_type {
+            SwapType::Buy => IncentiveAction::Buy,
+            SwapType::Sell => IncentiveAction::Sell,
+        }
+    }
 }
 
 #[account(zero_copy)]
@@ -34,13 +89,143 @@ pub struct Market {
 
     pub base_reserve: u64,
 
+    /// Only the first `interval_number + 1` prices are used
+    pub bid_prices: [u64; MAX_PRICES_LENGTH],
+    pub ask_prices: [u64; MAX_PRICES_LENGTH],
+
+    pub width_scaled: u64,
+    /// Normalized quote amount of the intervals below each bid price, every interval rounded down
+    pub bid_cumulative_quotes: [u128; MAX_PRICES_LENGTH],
+    pub ask_cumulative_quotes: [u128; MAX_PRICES_LENGTH],
+    /// Bit `i` is set if the quote amount of interval `i` was rounded down, to round the cumulative quotes up
+    pub bid_rounded_intervals: u64,
+    pub ask_rounded_intervals: u64,
+    pub total_supply: u64,
+    /// Number of intervals of the curve, set by the length of the prices
+    pub interval_number: u64,
+    /// Supply the curve starts at, the intervals are spread over `total_supply + virtual_supply`
+    pub virtual_supply: u64,
+
+    pub fees: MarketFees,
+
+    /// Cap on the net quote amount raised by the market, 0 if uncapped
+    pub max_raise: u64,
+    /// Quote amount paid by buyers minus the quote amount paid to sellers
+    pub quote_raised: u64,
+    /// Timestamp until which sells are disabled by the creator, kept once the pause is over for the cooldown
+    pub sells_paused_until: i64,
+    pub swap_count: u64,
+    /// Part of the total supply of a wrap-mode market not deposited yet, at the tail of the curve
+    pub pending_supply: u64,
+    /// Launchpad that co-signed the market creation, required to co-sign the price curve. Default if none
+    pub launchpad: Pubkey,
+    pub incentive_weights: IncentiveWeights,
+    /// Commitment to the price curve, see `hash_prices`. Zero until the prices are set
+    pub prices_hash: [u8; 32],
+    /// Pyth price update of the quote token checked by swaps, default if none
+    pub quote_price_feed: Pubkey,
+
+    /// Slot of the first swap tracked by the per-slot price move guard
+    pub guarded_slot: u64,
+    /// Ask price of the curve before the first swap of `guarded_slot`
+    pub guarded_slot_open_price: u64,
+
+    /// Deviation of the quote price from 1 above which swaps are rejected
+    pub max_quote_depeg_bps: u16,
+    /// Move of the ask price within a slot above which further swaps of the slot are rejected, 0 if unguarded
+    pub max_slot_price_move_bps: u16,
+    pub quote_token_decimals: u8,
+    pub bump: u8,
+    /// Set by the guardian to prevent the creator from pausing sells again
+    pub sell_pause_locked: u8,
+    /// Set if `referral_fee_share_override` replaces the referral fee share of the config
+    pub referral_fee_share_overridden: u8,
+    /// Share of the protocol fee sent to referrers on swaps of this market, if overridden
+    pub referral_fee_share_override: u16,
+    /// Surcharge on the quote amount of the first swap, decaying linearly to 0 over `launch_fee_duration` seconds.
+    /// Cleared once the decay is over
+    pub launch_fee_bps: u16,
+    pub launch_fee_duration: u32,
+    /// Timestamp of the first swap charged with the launch fee, 0 before it
+    pub launch_fee_start: i64,
+    /// Timestamp from which the pending fee shares can be activated, 0 if none are pending
+    pub fee_shares_activation_time: i64,
+    /// Timestamp from which `close_market` can close the market, 0 until the creator proposed to close it
+    pub close_time: i64,
+    /// Base amount at the tail of the curve set aside to seed the pool of the graduation, 0 if none
+    pub graduation_supply: u64,
+    /// Flat price, in the unit of the curve prices, at which the supply left unsold by the graduation keeps being sold.
+    /// 0 if that supply is burned
+    pub secondary_curve_price: u64,
+    /// Part of `base_reserve` sold on the secondary curve after the graduation
+    pub secondary_curve_supply: u64,
+    /// Cap of the impact fee, 0 if the market doesn't charge one
+    pub max_impact_fee_bps: u16,
+    /// Price impact up to which swaps don't pay the impact fee
+    pub impact_fee_threshold_bps: u16,
+    /// Impact fee per bps of price impact above the threshold, in bps
+    pub impact_fee_rate_bps: u16,
+    /// `LaunchFeeRecipient` of the pending fees the launch fee is added to
+    pub launch_fee_recipient: u8,
+    /// Set while the creator fees are split by the `CreatorFeeSplit` of the market
+    pub creator_fee_split: u8,
+    /// Creator and staking fee shares proposed by the creator, applied by `activate_market_fee_shares`
+    pub pending_creator_fee_share: u16,
+    pub pending_staking_fee_share: u16,
+    /// Set once the market graduated, curve swaps being disabled from then on
+    pub graduated: u8,
+    /// Set by the guardian to halt the swaps and stake deposits of the market
+    pub paused: u8,
+    /// Creator proposed by the current one, taking over once it accepts with `accept_creator`. Default if none
+    pub pending_creator: Pubkey,
+
+    _padding: [u8; 2],
+
+    /// Timestamp at which the market was created, 0 for the markets created before it was recorded
+    pub creation_time: i64,
+    /// Supply bought back and burned by `buyback_and_burn`. It stays at the bottom of the curve with the virtual supply,
+    /// so the quote amount paid for it remains in the curve reserve below the circulating supply
+    pub burned_supply: u64,
+    /// Base tokens held by the vault for others: stakes, vesting plans and unclaimed migration allocations
+    pub escrowed_base: u64,
+    /// Quote tokens held by the vault for others: DCA positions and the staking and boost rewards accrued to positions
+    pub escrowed_quote: u64,
+
+    _space: [u8; 8],
+}
+
+/// Fees of a `LegacyMarket`
+#[zero_copy]
+#[derive(Debug)]
+pub struct LegacyMarketFees {
+    pub staking_fee_share: u16,
+    pub creator_fee_share: u16,
+    _space: u32,
+
+    pub pending_staking_fees: u64,
+    pub pending_creator_fees: u64,
+}
+
+/// Layout of the markets created by the first deployment, before the curve resolutions grew the price arrays.
+/// These accounts don't deserialize as a `Market` until `upgrade_market` converts them
+#[zero_copy]
+#[derive(Debug)]
+pub struct LegacyMarket {
+    pub config: Pubkey,
+    pub creator: Pubkey,
+
+    pub base_token_mint: Pubkey,
+    pub quote_token_mint: Pubkey,
+
+    pub base_reserve: u64,
+
     pub bid_prices: [u64; PRICES_LENGTH],
     pub ask_prices: [u64; PRICES_LENGTH],
 
     pub width_scaled: u64,
     pub total_supply: u64,
 
-    pub fees: MarketFees,
+    pub fees: LegacyMarketFees,
 
     pub quote_token_decimals: u8,
     pub bump: u8,
@@ -48,6 +233,46 @@ pub struct Market {
     _space: [u8; 6],
 }
 
+/// Amounts swapped to move the circulating supply from its current value to `supply`
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq)]
+pub struct DepthLevel {
+    pub supply: u64,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+}
+
+/// Buy and sell of a ladder size, the filled base amounts being lower than the size once the curve is exhausted
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq)]
+pub struct LadderQuote {
+    pub buy_base_amount: u64,
+    /// Quote amount paid for `buy_base_amount`
+    pub buy_quote_amount: u64,
+    pub sell_base_amount: u64,
+    /// Quote amount received for `sell_base_amount`
+    pub sell_quote_amount: u64,
+}
+
+/// Derived quantities of a market, exposed so that integrators can check its invariants without recomputing them
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub struct MarketInvariants {
+    pub total_supply: u64,
+    pub base_reserve: u64,
+    pub circulating_supply: u64,
+    /// `total_supply / interval_number` normalized by `SCALE / BASE_PRECISION`
+    pub width_scaled: u64,
+    pub max_ask_price: u64,
+    /// Quote amount owed if the whole circulating supply were sold back into the curve
+    pub worst_case_quote: u64,
+    pub pending_fees: u64,
+    /// creator_fee_share + staking_fee_share + burn_fee_share, the protocol takes the remainder of `MAX_BPS`
+    pub fee_share_sum: u16,
+    pub max_bps: u64,
+    pub scale: u128,
+    pub base_precision: u64,
+    pub interval_number: u64,
+    pub quote_token_decimals: u8,
+}
+
 impl MarketFees {
     pub fn distribute_fee(
         &mut self,
@@ -60,7 +285,10 @@ impl MarketFees {
         let staking_fee = u64::try_from(
             u128::from(swap_fee) * u128::from(self.staking_fee_share) / MAX_BPS as u128,
         )?;
-        let remaining_fee = swap_fee - creator_fee - staking_fee;
+        let burn_fee = u64::try_from(
+            u128::from(swap_fee) * u128::from(self.burn_fee_share) / MAX_BPS as u128,
+        )?;
+        let remaining_fee = swap_fee - creator_fee - staking_fee - burn_fee;
 
         let referral_fee = if let Some(referral_fee_share) = referral_fee_share {
             u64::try_from(
@@ -74,9 +302,40 @@ impl MarketFees {
 
         self.pending_creator_fees += creator_fee;
         self.pending_staking_fees += staking_fee;
+        self.pending_burn_fees += burn_fee;
 
         Ok((creator_fee, staking_fee, protocol_fee, referral_fee))
     }
+
+    /// Sets the creator and staking fee shares, their sum being constant as the protocol and burn fee shares are set apart
+    pub fn update_fee_shares(
+        &mut self,
+        creator_fee_share: u16,
+        staking_fee_share: u16,
+    ) -> Result<()> {
+        require_eq!(
+            creator_fee_share + staking_fee_share,
+            self.creator_fee_share + self.staking_fee_share,
+            TokenMillError::InvalidFeeShare
+        );
+
+        self.creator_fee_share = creator_fee_share;
+        self.staking_fee_share = staking_fee_share;
+
+        Ok(())
+    }
+
+    /// Moves fee share between the creator and the burn, their sum being constant
+    pub fn set_burn_fee_share(&mut self, burn_fee_share: u16) -> Result<()> {
+        let share_sum = self.creator_fee_share + self.burn_fee_share;
+
+        require!(burn_fee_share <= share_sum, TokenMillError::InvalidFeeShare);
+
+        self.creator_fee_share = share_sum - burn_fee_share;
+        self.burn_fee_share = burn_fee_share;
+
+        Ok(())
+    }
 }
 
 impl Market {
@@ -100,6 +359,11 @@ impl Market {
             return Err(TokenMillError::InvalidTotalSupply.into());
         }
 
+        // Quote amounts of mints with more decimals can't be normalized without losing value
+        if quote_token_decimals > MAX_QUOTE_TOKEN_DECIMALS {
+            return Err(TokenMillError::UnsupportedQuoteTokenDecimals.into());
+        }
+
         self.bump = bump;
         self.config = config;
         self.creator = creator;
@@ -108,25 +372,133 @@ impl Market {
         self.quote_token_decimals = quote_token_decimals;
         self.total_supply = total_supply;
         self.base_reserve = total_supply;
-        self.width_scaled = u64::try_from(
-            u128::from(total_supply / INTERVAL_NUMBER) * SCALE / u128::from(BASE_PRECISION),
-        )?;
+        self.set_interval_number(INTERVAL_NUMBER)?;
 
         self.fees.creator_fee_share = creator_fee_share;
         self.fees.staking_fee_share = staking_fee_share;
         Ok(())
     }
 
+    /// Converts a market of the legacy layout. The settings added since then keep their default values, and the quote
+    /// amount raised, which wasn't tracked, is recovered from `quote_reserve`, the quote tokens held for the curve
+    pub fn from_legacy(legacy_market: &LegacyMarket, quote_reserve: u64) -> Result<Self> {
+        if legacy_market.quote_token_decimals > MAX_QUOTE_TOKEN_DECIMALS {
+            return Err(TokenMillError::UnsupportedQuoteTokenDecimals.into());
+        }
+
+        let mut market = Self::zeroed();
+
+        market.config = legacy_market.config;
+        market.creator = legacy_market.creator;
+        market.base_token_mint = legacy_market.base_token_mint;
+        market.quote_token_mint = legacy_market.quote_token_mint;
+        market.quote_token_decimals = legacy_market.quote_token_decimals;
+        market.bump = legacy_market.bump;
+        market.total_supply = legacy_market.total_supply;
+        market.base_reserve = legacy_market.base_reserve;
+        market.quote_raised = quote_reserve;
+        market.set_interval_number(INTERVAL_NUMBER)?;
+
+        market.fees.staking_fee_share = legacy_market.fees.staking_fee_share;
+        market.fees.creator_fee_share = legacy_market.fees.creator_fee_share;
+        market.fees.pending_staking_fees = legacy_market.fees.pending_staking_fees;
+        market.fees.pending_creator_fees = legacy_market.fees.pending_creator_fees;
+
+        // The prices were checked when they were set, the derived values are recomputed
+        if legacy_market.ask_prices[PRICES_LENGTH - 1] != 0 {
+            market.bid_prices[..PRICES_LENGTH].copy_from_slice(&legacy_market.bid_prices);
+            market.ask_prices[..PRICES_LENGTH].copy_from_slice(&legacy_market.ask_prices);
+            market.prices_hash = hash_prices(&legacy_market.bid_prices, &legacy_market.ask_prices);
+            market.set_cumulative_quotes()?;
+        }
+
+        Ok(market)
+    }
+
+    fn set_interval_number(&mut self, interval_number: u64) -> Result<()> {
+        self.interval_number = interval_number;
+        self.width_scaled =
+            u64::try_from(normalize_base_amount(self.curve_length() / interval_number))?;
+
+        Ok(())
+    }
+
+    /// Sets the curve, its resolution being given by the number of prices (see `CURVE_RESOLUTIONS`).
+    /// Every interval has to hold at least `BASE_PRECISION` base tokens.
+    /// Bid prices can't be more than `max_spread_bps` below the ask prices, unless it's 0.
     pub fn check_and_set_prices(
         &mut self,
-        bid_prices: [u64; PRICES_LENGTH],
-        ask_prices: [u64; PRICES_LENGTH],
+        bid_prices: &[u64],
+        ask_prices: &[u64],
+        max_spread_bps: u16,
     ) -> Result<()> {
         if self.are_prices_set() {
             return Err(TokenMillError::PricesAlreadySet.into());
         }
 
-        for i in 0..PRICES_LENGTH {
+        let prices_length = ask_prices.len();
+
+        if bid_prices.len() != prices_length || !CURVE_RESOLUTIONS.contains(&prices_length) {
+            return Err(TokenMillError::InvalidCurveResolution.into());
+        }
+
+        let interval_number = prices_length as u64 - 1;
+
+        if self.curve_length() / interval_number < BASE_PRECISION
+            || !self.curve_length().is_multiple_of(interval_number)
+        {
+            return Err(TokenMillError::InvalidCurveResolution.into());
+        }
+
+        Self::check_prices(bid_prices, ask_prices, max_spread_bps)?;
+
+        self.set_interval_number(interval_number)?;
+        self.bid_prices[..prices_length].copy_from_slice(bid_prices);
+        self.ask_prices[..prices_length].copy_from_slice(ask_prices);
+        self.prices_hash = hash_prices(bid_prices, ask_prices);
+        self.set_cumulative_quotes()?;
+
+        Ok(())
+    }
+
+    /// Precomputes the quote amount of the full intervals of both curves, so that swaps don't walk the intervals
+    fn set_cumulative_quotes(&mut self) -> Result<()> {
+        (self.bid_cumulative_quotes, self.bid_rounded_intervals) =
+            get_cumulative_quotes(&self.bid_prices, self.interval_number, self.width_scaled)?;
+        (self.ask_cumulative_quotes, self.ask_rounded_intervals) =
+            get_cumulative_quotes(&self.ask_prices, self.interval_number, self.width_scaled)?;
+
+        Ok(())
+    }
+
+    /// Bid side of the curve, sells are quoted against it
+    pub fn bid_curve(&self) -> PriceCurve<'_> {
+        PriceCurve {
+            prices: &self.bid_prices,
+            cumulative_quotes: &self.bid_cumulative_quotes,
+            rounded_intervals: self.bid_rounded_intervals,
+            width_scaled: self.width_scaled,
+            interval_number: self.interval_number,
+            quote_token_decimals: self.quote_token_decimals,
+        }
+    }
+
+    /// Ask side of the curve, buys are quoted against it
+    pub fn ask_curve(&self) -> PriceCurve<'_> {
+        PriceCurve {
+            prices: &self.ask_prices,
+            cumulative_quotes: &self.ask_cumulative_quotes,
+            rounded_intervals: self.ask_rounded_intervals,
+            width_scaled: self.width_scaled,
+            interval_number: self.interval_number,
+            quote_token_decimals: self.quote_token_decimals,
+        }
+    }
+
+    fn check_prices(bid_prices: &[u64], ask_prices: &[u64], max_spread_bps: u16) -> Result<()> {
+        let prices_length = ask_prices.len();
+
+        for i in 0..prices_length {
             let bid_price = bid_prices[i];
             let ask_price = ask_prices[i];
 
@@ -134,225 +506,1702 @@ impl Market {
                 return Err(TokenMillError::BidAskMismatch.into());
             }
 
-            if i > 0 && (ask_price <= ask_prices[i - 1] || bid_price <= bid_prices[i - 1]) {
+            if max_spread_bps > 0
+                && u128::from(ask_price - bid_price) * u128::from(MAX_BPS)
+                    > u128::from(ask_price) * u128::from(max_spread_bps)
+            {
+                return Err(TokenMillError::SpreadTooWide.into());
+            }
+
+            // Flat segments are allowed, as long as they don't give the interval away for free
+            if i > 0
+                && (ask_price < ask_prices[i - 1]
+                    || bid_price < bid_prices[i - 1]
+                    || bid_price == 0)
+            {
                 return Err(TokenMillError::DecreasingPrices.into());
             }
         }
 
-        if ask_prices[INTERVAL_NUMBER as usize] > MAX_PRICE {
+        if ask_prices[prices_length - 1] > MAX_PRICE {
             return Err(TokenMillError::PriceTooHigh.into());
         }
 
-        self.bid_prices = bid_prices;
-        self.ask_prices = ask_prices;
+        Ok(())
+    }
+
+    /// Raises the ask prices of the intervals lying entirely above the circulating supply, their bid prices being
+    /// scaled by the same ratio. The intervals holders can sell into keep their prices, and no price can be lowered.
+    pub fn raise_tail_prices(&mut self, ask_prices: &[u64], max_spread_bps: u16) -> Result<()> {
+        if !self.are_prices_set() {
+            return Err(TokenMillError::PricesNotSet.into());
+        }
+
+        let prices_length = self.prices_length();
+
+        if ask_prices.len() != prices_length {
+            return Err(TokenMillError::InvalidCurveResolution.into());
+        }
+
+        // First price whose neighbouring intervals both start at or above the circulating supply
+        let normalized_supply = normalize_base_amount(self.curve_supply());
+        let first_tail_index =
+            usize::try_from(normalized_supply.div_ceil(u128::from(self.width_scaled)))? + 1;
+
+        let mut bid_prices = self.bid_prices[..prices_length].to_vec();
+
+        for i in 0..prices_length {
+            let ask_price = ask_prices[i];
+            let previous_ask_price = self.ask_prices[i];
+
+            if ask_price == previous_ask_price {
+                continue;
+            }
+
+            if i < first_tail_index || ask_price < previous_ask_price {
+                return Err(TokenMillError::InvalidTailPrices.into());
+            }
+
+            // `previous_ask_price > 0` as every price after the first is non-zero
+            bid_prices[i] = u64::try_from(
+                mul_div(
+                    u128::from(bid_prices[i]),
+                    u128::from(ask_price),
+                    u128::from(previous_ask_price),
+                    Rounding::Down,
+                )
+                .ok_or(TokenMillError::MathError)?,
+            )?;
+        }
+
+        Self::check_prices(&bid_prices, ask_prices, max_spread_bps)?;
+
+        self.bid_prices[..prices_length].copy_from_slice(&bid_prices);
+        self.ask_prices[..prices_length].copy_from_slice(ask_prices);
+        self.prices_hash = hash_prices(&bid_prices, ask_prices);
+        self.set_cumulative_quotes()?;
 
         Ok(())
     }
 
+    /// Replaces the curve of a market none of the supply has been bought from yet, the new curve going through the same checks
+    pub fn update_prices(
+        &mut self,
+        bid_prices: &[u64],
+        ask_prices: &[u64],
+        max_spread_bps: u16,
+    ) -> Result<()> {
+        if !self.are_prices_set() {
+            return Err(TokenMillError::PricesNotSet.into());
+        }
+
+        if self.base_reserve != self.total_supply {
+            return Err(TokenMillError::MarketAlreadyTraded.into());
+        }
+
+        // Unused prices of a finer previous curve are cleared as well
+        self.bid_prices = [0; MAX_PRICES_LENGTH];
+        self.ask_prices = [0; MAX_PRICES_LENGTH];
+
+        self.check_and_set_prices(bid_prices, ask_prices, max_spread_bps)
+    }
+
     pub fn are_prices_set(&self) -> bool {
-        self.ask_prices[INTERVAL_NUMBER as usize] != 0
+        self.ask_prices[self.interval_number as usize] != 0
+    }
+
+    pub fn prices_length(&self) -> usize {
+        self.interval_number as usize + 1
+    }
+
+    pub fn max_ask_price(&self) -> u64 {
+        self.ask_prices[self.interval_number as usize]
     }
 
     pub fn circulating_supply(&self) -> u64 {
         self.total_supply - self.base_reserve
     }
 
-    pub fn get_quote_amount(
-        &self,
-        base_amount: u64,
-        swap_amount_type: SwapAmountType,
-    ) -> Result<(u64, u64)> {
-        let circulating_supply = self.circulating_supply();
+    /// Position of the market on its curve, the circulating supply offset by the curve floor
+    pub fn curve_supply(&self) -> u64 {
+        self.circulating_supply() + self.curve_floor()
+    }
 
-        let (supply, rounding) = match swap_amount_type {
-            SwapAmountType::ExactInput => (circulating_supply - base_amount, Rounding::Down),
-            SwapAmountType::ExactOutput => (circulating_supply, Rounding::Up),
-        };
+    /// Position below which the circulating supply can't be sold, the virtual supply and the burned supply
+    pub fn curve_floor(&self) -> u64 {
+        self.virtual_supply + self.burned_supply
+    }
 
-        self.get_quote_amount_with_parameters(supply, base_amount, swap_amount_type, rounding)
+    /// Supply covered by the curve, its intervals being `curve_length() / interval_number` wide
+    pub fn curve_length(&self) -> u64 {
+        self.total_supply + self.curve_floor()
     }
 
-    pub fn get_quote_amount_with_parameters(
-        &self,
-        supply: u64,
-        base_amount: u64,
-        swap_amount_type: SwapAmountType,
-        rounding: Rounding,
-    ) -> Result<(u64, u64)> {
-        let price_curve = match swap_amount_type {
-            SwapAmountType::ExactInput => &self.bid_prices,
-            SwapAmountType::ExactOutput => &self.ask_prices,
-        };
+    /// Starts the curve at `virtual_supply` rather than at its first price, like the virtual liquidity of other
+    /// launchpads. Only possible before the prices are set, as it changes the width of the intervals
+    pub fn set_virtual_supply(&mut self, virtual_supply: u64) -> Result<()> {
+        if self.are_prices_set() {
+            return Err(TokenMillError::PricesAlreadySet.into());
+        }
 
-        let normalized_supply = u128::from(supply) * SCALE / u128::from(BASE_PRECISION);
+        require!(
+            self.total_supply
+                .checked_add(virtual_supply)
+                .is_some_and(|curve_length| curve_length <= MAX_TOTAL_SUPPLY),
+            TokenMillError::InvalidVirtualSupply
+        );
 
-        let mut normalized_base_amount_left =
-            u128::from(base_amount) * SCALE / u128::from(BASE_PRECISION);
+        self.virtual_supply = virtual_supply;
 
-        let mut normalized_quote_amount = 0;
+        self.set_interval_number(self.interval_number)
+    }
 
-        let mut i = usize::try_from(normalized_supply / u128::from(self.width_scaled))?;
-        let mut interval_supply_already_used = normalized_supply % u128::from(self.width_scaled);
+    pub fn set_max_raise(&mut self, max_raise: u64) -> Result<()> {
+        require!(
+            max_raise == 0 || max_raise >= self.quote_raised,
+            TokenMillError::InvalidMaxRaise
+        );
 
-        let mut price_0 = price_curve[i];
-        i += 1;
+        self.max_raise = max_raise;
 
-        while normalized_base_amount_left > 0 && i < PRICES_LENGTH {
-            let price_1 = price_curve[i];
+        Ok(())
+    }
 
-            let delta_base = min(
-                normalized_base_amount_left,
-                u128::from(self.width_scaled) - interval_supply_already_used,
-            );
+    /// Pauses sells for `duration` seconds, or lifts the current pause with a duration of 0.
+    /// A pause can't be extended, and a new one can only start `MIN_SELL_PAUSE_COOLDOWN` after the end of the last
+    /// one, so that sells can't be frozen indefinitely
+    pub fn pause_sells(&mut self, current_time: i64, duration: i64) -> Result<()> {
+        require!(self.sell_pause_locked == 0, TokenMillError::SellPauseLocked);
+        require!(
+            (0..=MAX_SELL_PAUSE_DURATION).contains(&duration),
+            TokenMillError::InvalidSellPauseDuration
+        );
+
+        // The end of a lifted pause is kept for the cooldown
+        if duration == 0 {
+            self.sells_paused_until = min(self.sells_paused_until, current_time);
+
+            return Ok(());
+        }
 
-            let delta_quote = mul_div(
-                delta_base,
-                u128::from(price_1 - price_0) * (delta_base + 2 * interval_supply_already_used)
-                    + 2 * u128::from(price_0) * u128::from(self.width_scaled),
-                2 * SCALE * u128::from(self.width_scaled),
-                rounding,
-            )
-            .ok_or(TokenMillError::MathError)?;
+        require!(
+            self.sells_paused_until == 0
+                || current_time >= self.sells_paused_until + MIN_SELL_PAUSE_COOLDOWN,
+            TokenMillError::SellPauseCooldown
+        );
 
-            normalized_base_amount_left -= delta_base;
-            normalized_quote_amount += delta_quote;
+        self.sells_paused_until = current_time + duration;
 
-            interval_supply_already_used = 0;
-            price_0 = price_1;
+        Ok(())
+    }
 
-            i += 1;
-        }
+    pub fn are_sells_paused(&self, current_time: i64) -> bool {
+        current_time < self.sells_paused_until
+    }
 
-        let base_amount_swapped = base_amount
-            - div(
-                normalized_base_amount_left * u128::from(BASE_PRECISION),
-                SCALE,
-                rounding,
-            )?;
+    pub fn set_incentive_weights(&mut self, buy_weight: u16, sell_weight: u16, stake_weight: u16) {
+        self.incentive_weights.buy_weight = buy_weight;
+        self.incentive_weights.sell_weight = sell_weight;
+        self.incentive_weights.stake_weight = stake_weight;
+    }
 
-        let quote_amount_swapped = div(
-            normalized_quote_amount * u128::pow(10, u32::from(self.quote_token_decimals)),
-            SCALE,
-            rounding,
-        )?;
+    /// Schedules a launch fee against block-zero snipers, only possible before the first swap. 0 disables it
+    pub fn set_launch_fee(
+        &mut self,
+        launch_fee_bps: u16,
+        duration: u32,
+        recipient: LaunchFeeRecipient,
+    ) -> Result<()> {
+        require!(self.swap_count == 0, TokenMillError::MarketAlreadyTraded);
 
-        Ok((base_amount_swapped, quote_amount_swapped))
+        require!(
+            launch_fee_bps <= MAX_LAUNCH_FEE_BPS
+                && duration <= MAX_LAUNCH_FEE_DURATION
+                && (launch_fee_bps == 0) == (duration == 0),
+            TokenMillError::InvalidLaunchFee
+        );
+
+        self.launch_fee_bps = launch_fee_bps;
+        self.launch_fee_duration = duration;
+        self.launch_fee_recipient = recipient as u8;
+
+        Ok(())
     }
 
-    pub fn get_base_amount_in(&self, quote_amount: u64) -> Result<(u64, u64)> {
-        let price_curve = &self.bid_prices;
-        let circulating_supply = self.circulating_supply();
+    /// Launch fee of a swap at `current_time`, in bps. The decay starts on the first swap charged
+    pub fn update_launch_fee(&mut self, current_time: i64) -> u16 {
+        if self.launch_fee_bps == 0 {
+            return 0;
+        }
+
+        if self.launch_fee_start == 0 {
+            self.launch_fee_start = current_time;
+        }
+
+        let duration = u64::from(self.launch_fee_duration);
+        let elapsed = u64::try_from(current_time - self.launch_fee_start).unwrap_or_default();
 
-        let normalized_supply = u128::from(circulating_supply) * SCALE / u128::from(BASE_PRECISION);
+        if elapsed >= duration {
+            self.launch_fee_bps = 0;
 
-        let quote_precision = u128::pow(10, u32::from(self.quote_token_decimals));
-        let mut normalized_quote_amount_left = u128::from(quote_amount) * SCALE / quote_precision;
-        let mut normalized_base_amount = 0;
+            return 0;
+        }
 
-        let mut i = usize::try_from(normalized_supply / u128::from(self.width_scaled))?;
-        let mut interval_supply_available = normalized_supply % u128::from(self.width_scaled);
+        // Rounded up, so that the fee only vanishes at the end of the decay
+        (u64::from(self.launch_fee_bps) * (duration - elapsed)).div_ceil(duration) as u16
+    }
 
-        if interval_supply_available == 0 {
-            interval_supply_available = u128::from(self.width_scaled);
+    /// Adds a launch fee kept by the market to the pending fees of its recipient
+    pub fn credit_launch_fee(&mut self, launch_fee: u64) {
+        if self.launch_fee_recipient == LaunchFeeRecipient::Staking as u8 {
+            self.fees.pending_staking_fees += launch_fee;
         } else {
-            i += 1;
+            self.fees.pending_creator_fees += launch_fee;
         }
+    }
 
-        let mut price_1 = price_curve[i];
+    /// Schedules new creator and staking fee shares, replacing the pending ones
+    pub fn propose_fee_shares(
+        &mut self,
+        creator_fee_share: u16,
+        staking_fee_share: u16,
+        activation_time: i64,
+    ) -> Result<()> {
+        require_eq!(
+            creator_fee_share + staking_fee_share,
+            self.fees.creator_fee_share + self.fees.staking_fee_share,
+            TokenMillError::InvalidFeeShare
+        );
 
-        while normalized_quote_amount_left > 0 && i > 0 {
-            let price_0 = price_curve[i - 1];
+        self.pending_creator_fee_share = creator_fee_share;
+        self.pending_staking_fee_share = staking_fee_share;
+        self.fee_shares_activation_time = activation_time;
 
-            let (delta_base, delta_quote) = get_delta_base_in(
-                price_0.into(),
-                price_1.into(),
-                self.width_scaled.into(),
-                interval_supply_available,
-                normalized_quote_amount_left,
-            )?;
+        Ok(())
+    }
 
-            normalized_base_amount += delta_base;
-            normalized_quote_amount_left -= delta_quote;
+    /// Applies the pending fee shares once their activation time is reached, returns them
+    pub fn activate_fee_shares(&mut self, current_time: i64) -> Result<(u16, u16)> {
+        require!(
+            self.fee_shares_activation_time != 0 && current_time >= self.fee_shares_activation_time,
+            TokenMillError::FeeSharesUpdateNotDue
+        );
 
-            interval_supply_available = u128::from(self.width_scaled);
-            price_1 = price_0;
+        let fee_shares = (
+            self.pending_creator_fee_share,
+            self.pending_staking_fee_share,
+        );
 
-            i -= 1;
-        }
+        self.fees.update_fee_shares(fee_shares.0, fee_shares.1)?;
+        self.cancel_fee_shares_proposal();
 
-        let base_amount_swapped = div(
-            normalized_base_amount * u128::from(BASE_PRECISION),
-            SCALE,
-            Rounding::Up,
-        )?;
+        Ok(fee_shares)
+    }
 
-        let quote_amount_swapped = quote_amount
-            - div(
-                normalized_quote_amount_left * quote_precision,
-                SCALE,
-                Rounding::Up,
-            )?;
+    pub fn cancel_fee_shares_proposal(&mut self) {
+        self.pending_creator_fee_share = 0;
+        self.pending_staking_fee_share = 0;
+        self.fee_shares_activation_time = 0;
+    }
 
-        Ok((base_amount_swapped, quote_amount_swapped))
+    /// Schedules the closing of the market `delay` seconds from now, a new proposal restarting the delay. The market
+    /// has to be closable already and at least `min_age` seconds old. Returns the close time
+    pub fn propose_close(&mut self, current_time: i64, delay: u32, min_age: u32) -> Result<i64> {
+        require!(self.is_closable(), TokenMillError::MarketNotClosable);
+        require!(
+            current_time >= self.creation_time + i64::from(min_age),
+            TokenMillError::MarketNotClosable
+        );
+
+        self.close_time = current_time + i64::from(delay);
+
+        Ok(self.close_time)
     }
 
-    pub fn get_base_amount_out(&self, quote_amount: u64) -> Result<(u64, u64)> {
-        let price_curve = &self.ask_prices;
-        let circulating_supply = self.circulating_supply();
+    /// Whether the circulating supply was sold back or the curve graduated
+    pub fn is_closable(&self) -> bool {
+        self.circulating_supply() == 0 || self.graduated == 1
+    }
 
-        let normalized_supply = u128::from(circulating_supply) * SCALE / u128::from(BASE_PRECISION);
+    pub fn is_close_due(&self, current_time: i64) -> bool {
+        self.close_time != 0 && current_time >= self.close_time
+    }
 
-        let quote_precision = u128::pow(10, u32::from(self.quote_token_decimals));
-        let mut normalized_quote_amount_left = u128::from(quote_amount) * SCALE / quote_precision;
-        let mut normalized_base_amount = 0;
+    /// Records `amount` base tokens held by the vault on behalf of someone else
+    pub fn escrow_base(&mut self, amount: u64) -> Result<()> {
+        self.escrowed_base = self
+            .escrowed_base
+            .checked_add(amount)
+            .ok_or(TokenMillError::MathError)?;
 
-        let mut i = usize::try_from(normalized_supply / u128::from(self.width_scaled))?;
-        let mut interval_supply_already_used = normalized_supply % u128::from(self.width_scaled);
+        Ok(())
+    }
 
-        let mut price_0 = price_curve[i];
+    /// Records `amount` escrowed base tokens leaving the vault, or going back to the market
+    pub fn release_base(&mut self, amount: u64) -> Result<()> {
+        self.escrowed_base = self
+            .escrowed_base
+            .checked_sub(amount)
+            .ok_or(TokenMillError::MathError)?;
 
-        while normalized_quote_amount_left > 0 && i < PRICES_LENGTH - 1 {
-            let price_1 = price_curve[i + 1];
+        Ok(())
+    }
 
-            let (delta_base, delta_quote) = get_delta_base_out(
-                price_0.into(),
-                price_1.into(),
-                self.width_scaled.into(),
-                interval_supply_already_used,
-                normalized_quote_amount_left,
-            )?;
+    /// Records `amount` quote tokens held by the vault on behalf of someone else
+    pub fn escrow_quote(&mut self, amount: u64) -> Result<()> {
+        self.escrowed_quote = self
+            .escrowed_quote
+            .checked_add(amount)
+            .ok_or(TokenMillError::MathError)?;
 
-            normalized_base_amount += delta_base;
-            normalized_quote_amount_left -= delta_quote;
+        Ok(())
+    }
 
-            interval_supply_already_used = 0;
-            price_0 = price_1;
+    /// Records `amount` escrowed quote tokens leaving the vault, or going back to the market
+    pub fn release_quote(&mut self, amount: u64) -> Result<()> {
+        self.escrowed_quote = self
+            .escrowed_quote
+            .checked_sub(amount)
+            .ok_or(TokenMillError::MathError)?;
 
-            i += 1;
-        }
+        Ok(())
+    }
 
-        let base_amount_swapped = div(
-            normalized_base_amount * u128::from(BASE_PRECISION),
-            SCALE,
-            Rounding::Down,
-        )?;
+    /// Charges swaps a fee growing with their price impact, to discourage grabbing the supply in a single swap.
+    /// A `max_impact_fee_bps` of 0 disables it
+    pub fn set_impact_fee(
+        &mut self,
+        max_impact_fee_bps: u16,
+        impact_fee_threshold_bps: u16,
+        impact_fee_rate_bps: u16,
+    ) -> Result<()> {
+        require!(
+            max_impact_fee_bps <= MAX_IMPACT_FEE_BPS
+                && (max_impact_fee_bps == 0 || impact_fee_rate_bps != 0),
+            TokenMillError::InvalidImpactFee
+        );
 
-        let quote_amount_swapped = quote_amount
-            - div(
-                normalized_quote_amount_left * quote_precision,
-                SCALE,
-                Rounding::Down,
-            )?;
+        self.max_impact_fee_bps = max_impact_fee_bps;
+        self.impact_fee_threshold_bps = impact_fee_threshold_bps;
+        self.impact_fee_rate_bps = impact_fee_rate_bps;
 
-        Ok((base_amount_swapped, quote_amount_swapped))
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use anchor_lang::Space;
+    /// Impact fee of a swap moving the price by `price_impact_bps`, in bps
+    pub fn get_impact_fee_bps(&self, price_impact_bps: u64) -> u16 {
+        let excess_impact_bps =
+            price_impact_bps.saturating_sub(u64::from(self.impact_fee_threshold_bps));
+
+        let impact_fee_bps = u128::from(excess_impact_bps) * u128::from(self.impact_fee_rate_bps)
+            / u128::from(MAX_BPS);
+
+        min(impact_fee_bps, u128::from(self.max_impact_fee_bps)) as u16
+    }
+
+    /// Replaces the referral fee share of the config for this market, `None` falls back to the config
+    pub fn set_referral_fee_share_override(
+        &mut self,
+        referral_fee_share: Option<u16>,
+    ) -> Result<()> {
+        require!(
+            referral_fee_share.is_none_or(|fee_share| fee_share <= MAX_BPS as u16),
+            TokenMillError::InvalidFeeShare
+        );
+
+        self.referral_fee_share_overridden = u8::from(referral_fee_share.is_some());
+        self.referral_fee_share_override = referral_fee_share.unwrap_or_default();
+
+        Ok(())
+    }
 
-    use crate::state::Market;
+    /// Share of the protocol fee sent to referrers on swaps of this market
+    pub fn get_referral_fee_share(&self, config: &TokenMillConfig) -> u16 {
+        if self.referral_fee_share_overridden == 1 {
+            self.referral_fee_share_override
+        } else {
+            config.get_referral_fee_share(&self.quote_token_mint)
+        }
+    }
+
+    /// Enables the per-slot price move guard, tracking the current slot from the current ask price. 0 disables it
+    pub fn set_max_slot_price_move(
+        &mut self,
+        max_slot_price_move_bps: u16,
+        slot: u64,
+    ) -> Result<()> {
+        self.max_slot_price_move_bps = max_slot_price_move_bps;
+        self.guarded_slot = slot;
+        self.guarded_slot_open_price = self.get_ask_price(self.curve_supply())?;
+
+        Ok(())
+    }
+
+    /// Rejects swaps once the ask price moved by more than `max_slot_price_move_bps` since the first swap of `slot`.
+    /// The swap crossing the limit goes through, only the following swaps of the slot are rejected.
+    pub fn check_slot_price_move(&mut self, slot: u64) -> Result<()> {
+        let ask_price = self.get_ask_price(self.curve_supply())?;
+
+        if slot != self.guarded_slot {
+            self.guarded_slot = slot;
+            self.guarded_slot_open_price = ask_price;
+
+            return Ok(());
+        }
+
+        require!(
+            u128::from(ask_price.abs_diff(self.guarded_slot_open_price)) * MAX_BPS as u128
+                <= u128::from(self.guarded_slot_open_price)
+                    * u128::from(self.max_slot_price_move_bps),
+            TokenMillError::SlotPriceMoveExceeded
+        );
+
+        Ok(())
+    }
+
+    /// Weight accrued by `amount` of `action`, None if the creator didn't set a weight for it
+    pub fn get_incentive_weight(
+        &self,
+        action: IncentiveAction,
+        amount: u64,
+    ) -> Result<Option<u64>> {
+        let weight = match action {
+            IncentiveAction::Buy => self.incentive_weights.buy_weight,
+            IncentiveAction::Sell => self.incentive_weights.sell_weight,
+            IncentiveAction::Stake => self.incentive_weights.stake_weight,
+        };
+
+        if weight == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(u64::try_from(
+            u128::from(amount) * u128::from(weight) / MAX_BPS as u128,
+        )?))
+    }
+
+    pub fn invariants(&self) -> Result<MarketInvariants> {
+        let circulating_supply = self.circulating_supply();
+
+        let (_, worst_case_quote) = self.get_quote_amount_with_parameters(
+            self.curve_floor(),
+            circulating_supply,
+            SwapAmountType::ExactInput,
+            Rounding::Up,
+        )?;
+
+        Ok(MarketInvariants {
+            total_supply: self.total_supply,
+            base_reserve: self.base_reserve,
+            circulating_supply,
+            width_scaled: self.width_scaled,
+            max_ask_price: self.max_ask_price(),
+            worst_case_quote,
+            pending_fees: self.fees.pending_creator_fees
+                + self.fees.pending_staking_fees
+                + self.fees.pending_burn_fees,
+            fee_share_sum: self.fees.creator_fee_share
+                + self.fees.staking_fee_share
+                + self.fees.burn_fee_share,
+            max_bps: MAX_BPS,
+            scale: SCALE,
+            base_precision: BASE_PRECISION,
+            interval_number: self.interval_number,
+            quote_token_decimals: self.quote_token_decimals,
+        })
+    }
+
+    /// Base amount that can be bought, excluding the supply not deposited yet and the graduation supply
+    pub fn available_supply(&self) -> u64 {
+        self.base_reserve
+            - self.pending_supply
+            - self.graduation_supply
+            - self.secondary_curve_supply
+    }
+
+    /// Extends the sellable reserve of a wrap-mode market along the tail of the curve
+    pub fn deposit_supply(&mut self, amount: u64) -> Result<()> {
+        require!(
+            amount > 0 && amount <= self.pending_supply,
+            TokenMillError::InvalidAmount
+        );
+
+        self.pending_supply -= amount;
+
+        Ok(())
+    }
+
+    /// Quote amount that can still be raised before buys are disabled
+    pub fn remaining_raise(&self) -> u64 {
+        if self.max_raise == 0 {
+            return u64::MAX;
+        }
+
+        self.max_raise.saturating_sub(self.quote_raised)
+    }
+
+    /// Once the cap is reached, the market only accepts sells and becomes eligible for graduation
+    pub fn is_max_raise_reached(&self) -> bool {
+        self.max_raise != 0 && self.quote_raised >= self.max_raise
+    }
+
+    /// Sets aside `graduation_supply` at the tail of the curve to seed the pool of the graduation, buys stopping
+    /// below it. Only possible before the first swap, some supply having to remain buyable
+    pub fn set_graduation_supply(&mut self, graduation_supply: u64) -> Result<()> {
+        require!(self.swap_count == 0, TokenMillError::MarketAlreadyTraded);
+        require!(
+            graduation_supply < self.base_reserve - self.pending_supply,
+            TokenMillError::InvalidGraduationSupply
+        );
+
+        self.graduation_supply = graduation_supply;
+
+        Ok(())
+    }
+
+    /// The market can graduate once its curve is sold out, or once its max raise is reached
+    pub fn is_graduation_reached(&self) -> bool {
+        self.available_supply() == 0 || self.is_max_raise_reached()
+    }
+
+    /// Disables the curve, returning the base and quote amounts seeding the pool and the unsold base amount to burn,
+    /// 0 if it moves to the secondary curve.
+    /// The pool gets the graduation supply and the quote amount backing the circulating supply on the bid curve
+    pub fn graduate(&mut self) -> Result<(u64, u64, u64)> {
+        require!(self.graduated == 0, TokenMillError::MarketGraduated);
+        require!(
+            self.graduation_supply > 0,
+            TokenMillError::GraduationDisabled
+        );
+        require!(
+            self.is_graduation_reached(),
+            TokenMillError::GraduationNotReached
+        );
+
+        let (_, quote_amount) = self.get_quote_amount_with_parameters(
+            self.curve_floor(),
+            self.circulating_supply(),
+            SwapAmountType::ExactInput,
+            Rounding::Down,
+        )?;
+
+        let base_amount = self.graduation_supply;
+        let unsold_amount = self.available_supply();
+
+        // The unsold supply moves to the secondary curve if the creator set one, otherwise it's burned by the caller
+        let burned_amount = if self.secondary_curve_price > 0 {
+            self.secondary_curve_supply = unsold_amount;
+            0
+        } else {
+            unsold_amount
+        };
+
+        // The pool supply joins the circulating supply
+        self.base_reserve -= base_amount + burned_amount;
+        self.total_supply -= burned_amount;
+        self.graduation_supply = 0;
+        self.graduated = 1;
+
+        Ok((base_amount, quote_amount, burned_amount))
+    }
+
+    /// Sets the flat price of the secondary curve, 0 to burn the unsold supply at the graduation instead.
+    /// The secondary curve continues the curve, so its price can't be below the last ask price
+    pub fn set_secondary_curve_price(&mut self, price: u64) -> Result<()> {
+        require!(self.graduated == 0, TokenMillError::MarketGraduated);
+        require!(self.are_prices_set(), TokenMillError::PricesNotSet);
+        require!(
+            price == 0 || price >= self.max_ask_price(),
+            TokenMillError::InvalidSecondaryCurvePrice
+        );
+
+        self.secondary_curve_price = price;
+
+        Ok(())
+    }
+
+    /// Sells `base_amount` of the secondary curve, the proceeds going to the stakers of the market.
+    /// Returns the quote amount paid, rounded up
+    pub fn buy_secondary_curve(&mut self, base_amount: u64) -> Result<u64> {
+        require!(
+            self.graduated == 1 && self.secondary_curve_supply > 0,
+            TokenMillError::SecondaryCurveInactive
+        );
+        require!(
+            base_amount > 0 && base_amount <= self.secondary_curve_supply,
+            TokenMillError::InvalidAmount
+        );
+
+        let normalized_quote_amount = mul_div(
+            normalize_base_amount(base_amount),
+            u128::from(self.secondary_curve_price),
+            SCALE,
+            Rounding::Up,
+        )
+        .ok_or(TokenMillError::MathError)?;
+        let quote_amount = denormalize_quote_amount(
+            normalized_quote_amount,
+            self.quote_token_decimals,
+            Rounding::Up,
+        )?;
+
+        self.secondary_curve_supply -= base_amount;
+        self.base_reserve -= base_amount;
+        self.fees.pending_staking_fees += quote_amount;
+
+        Ok(quote_amount)
+    }
+
+    /// Checks that the market can be closed, its circulating supply being sold back or its curve graduated, and that
+    /// its vaults hold nothing escrowed for others. Everything else in the vaults belongs to the market, whatever their
+    /// balances: the base tokens are burned and the quote tokens swept to the creator, be they pending fees, the quote
+    /// amount paid for the burned supply, rounding dust of the swaps or donations.
+    /// Returns the base amount burned and the quote amount swept
+    pub fn close(&self, base_balance: u64, quote_balance: u64) -> Result<(u64, u64)> {
+        require!(self.is_closable(), TokenMillError::MarketNotClosable);
+        // Pending creator fees of a split market go to the recipients of the split, they must be claimed first
+        require!(
+            self.creator_fee_split == 0 || self.fees.pending_creator_fees == 0,
+            TokenMillError::MarketNotClosable
+        );
+        require!(
+            self.escrowed_base == 0 && self.escrowed_quote == 0,
+            TokenMillError::MarketVaultsNotEmpty
+        );
+
+        Ok((base_balance, quote_balance))
+    }
+
+    pub fn get_quote_amount(
+        &self,
+        base_amount: u64,
+        swap_amount_type: SwapAmountType,
+    ) -> Result<(u64, u64)> {
+        let curve_supply = self.curve_supply();
+
+        let (supply, rounding) = match swap_amount_type {
+            SwapAmountType::ExactInput => (curve_supply - base_amount, Rounding::Down),
+            SwapAmountType::ExactOutput => (curve_supply, Rounding::Up),
+        };
+
+        self.get_quote_amount_with_parameters(supply, base_amount, swap_amount_type, rounding)
+    }
+
+    pub fn get_quote_amount_with_parameters(
+        &self,
+        supply: u64,
+        base_amount: u64,
+        swap_amount_type: SwapAmountType,
+        rounding: Rounding,
+    ) -> Result<(u64, u64)> {
+        let price_curve = match swap_amount_type {
+            SwapAmountType::ExactInput => self.bid_curve(),
+            SwapAmountType::ExactOutput => self.ask_curve(),
+        };
+
+        Ok(price_curve.get_quote_amount(supply, base_amount, rounding)?)
+    }
+
+    /// Bid price of the curve at `supply`, linearly interpolated within its interval
+    pub fn get_bid_price(&self, supply: u64) -> Result<u64> {
+        Ok(self.bid_curve().get_price(supply)?)
+    }
+
+    /// Ask price of the curve at `supply`, linearly interpolated within its interval
+    pub fn get_ask_price(&self, supply: u64) -> Result<u64> {
+        Ok(self.ask_curve().get_price(supply)?)
+    }
+
+    /// Ask and bid prices of the curve at the current circulating supply
+    pub fn get_spot_prices(&self) -> Result<(u64, u64)> {
+        let curve_supply = self.curve_supply();
+
+        Ok((
+            self.get_ask_price(curve_supply)?,
+            self.get_bid_price(curve_supply)?,
+        ))
+    }
+
+    /// Ask price for buys, bid price for sells, at the current position on the curve
+    pub fn spot_price(&self, side: SwapType) -> Result<u64> {
+        let curve_supply = self.curve_supply();
+
+        match side {
+            SwapType::Buy => self.get_ask_price(curve_supply),
+            SwapType::Sell => self.get_bid_price(curve_supply),
+        }
+    }
+
+    /// Swap moving the spot price to `target_price`: a buy up to where the ask price reaches it if it's above the ask
+    /// price, a sell down to where the bid price reaches it if it's below the bid price, nothing otherwise.
+    /// Buys stop at the available supply and the remaining raise, sells at the circulating supply
+    pub fn depth_to_price(&self, target_price: u64) -> Result<DepthLevel> {
+        let curve_supply = self.curve_supply();
+        let circulating_supply = self.circulating_supply();
+
+        if target_price > self.spot_price(SwapType::Buy)? {
+            let supply = self.ask_curve().get_supply_at_price(target_price)?;
+            let (base_amount, quote_amount) = self.quote_buy(supply - curve_supply)?;
+
+            return Ok(DepthLevel {
+                supply: circulating_supply + base_amount,
+                base_amount,
+                quote_amount,
+            });
+        }
+
+        if target_price < self.spot_price(SwapType::Sell)? {
+            let supply = self.bid_curve().get_supply_at_price(target_price)?;
+            let (base_amount, quote_amount) = self.quote_sell(curve_supply - supply)?;
+
+            return Ok(DepthLevel {
+                supply: circulating_supply - base_amount,
+                base_amount,
+                quote_amount,
+            });
+        }
+
+        Ok(DepthLevel {
+            supply: circulating_supply,
+            base_amount: 0,
+            quote_amount: 0,
+        })
+    }
+
+    /// Buy and sell quotes of each of the base amounts of `sizes`, in the same order.
+    /// Amounts are swapped on the curve only, without interface and referral fees
+    pub fn quote_ladder(&self, sizes: &[u64]) -> Result<Vec<LadderQuote>> {
+        sizes
+            .iter()
+            .map(|&size| {
+                let (buy_base_amount, buy_quote_amount) = self.quote_buy(size)?;
+                let (sell_base_amount, sell_quote_amount) = self.quote_sell(size)?;
+
+                Ok(LadderQuote {
+                    buy_base_amount,
+                    buy_quote_amount,
+                    sell_base_amount,
+                    sell_quote_amount,
+                })
+            })
+            .collect()
+    }
+
+    /// Buys `base_amount` within the available supply and the remaining raise, like `swap_manager::swap`
+    pub fn quote_buy(&self, base_amount: u64) -> Result<(u64, u64)> {
+        if self.is_max_raise_reached() {
+            return Ok((0, 0));
+        }
+
+        let (base_amount, quote_amount) = self.get_quote_amount(
+            min(base_amount, self.available_supply()),
+            SwapAmountType::ExactOutput,
+        )?;
+
+        if quote_amount > self.remaining_raise() {
+            return self.get_base_amount_out(self.remaining_raise());
+        }
+
+        Ok((base_amount, quote_amount))
+    }
+
+    /// Sells `base_amount` within the circulating supply
+    pub fn quote_sell(&self, base_amount: u64) -> Result<(u64, u64)> {
+        self.get_quote_amount(
+            min(base_amount, self.circulating_supply()),
+            SwapAmountType::ExactInput,
+        )
+    }
+
+    /// Lowest circulating supply at which selling `base_amount` yields at least `quote_amount`, with the bid price
+    /// at that supply. Positions are liquidatable once the supply falls below it.
+    /// Returns `None` if the target can't be reached even with the whole supply in circulation.
+    pub fn get_liquidation_supply(
+        &self,
+        base_amount: u64,
+        quote_amount: u64,
+    ) -> Result<Option<(u64, u64)>> {
+        if base_amount > self.total_supply {
+            return Ok(None);
+        }
+
+        let sell_quote_amount = |supply: u64| -> Result<u64> {
+            let (_, quote_amount) = self.get_quote_amount_with_parameters(
+                supply + self.curve_floor() - base_amount,
+                base_amount,
+                SwapAmountType::ExactInput,
+                Rounding::Down,
+            )?;
+
+            Ok(quote_amount)
+        };
+
+        // Bid prices are increasing, so the sell proceeds only grow with the supply
+        let mut low = base_amount;
+        let mut high = self.total_supply;
+
+        if sell_quote_amount(high)? < quote_amount {
+            return Ok(None);
+        }
+
+        while low < high {
+            let mid = low + (high - low) / 2;
+
+            if sell_quote_amount(mid)? >= quote_amount {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        Ok(Some((low, self.get_bid_price(low + self.curve_floor())?)))
+    }
+
+    pub fn get_base_amount_in(&self, quote_amount: u64) -> Result<(u64, u64)> {
+        let (base_amount_swapped, quote_amount_swapped) = self
+            .bid_curve()
+            .get_base_amount_in(self.curve_supply(), quote_amount)?;
+
+        // Sells can't reach below the virtual supply, at most the whole circulating supply is sold
+        if base_amount_swapped > self.circulating_supply() {
+            return self.get_quote_amount(self.circulating_supply(), SwapAmountType::ExactInput);
+        }
+
+        Ok((base_amount_swapped, quote_amount_swapped))
+    }
+
+    pub fn get_base_amount_out(&self, quote_amount: u64) -> Result<(u64, u64)> {
+        Ok(self
+            .ask_curve()
+            .get_base_amount_out(self.curve_supply(), quote_amount)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::Space;
+    use bytemuck::Zeroable;
+    use joelana_test_utils::utils::token_mill::curve_generator::Curve;
+    use rstest::rstest;
+
+    use crate::{
+        constant::MAX_QUOTE_TOKEN_DECIMALS,
+        manager::swap_manager::{SwapAmountType, SwapType},
+        math::Rounding,
+        state::{hash_prices, IncentiveAction, LaunchFeeRecipient, Market},
+    };
+
+    const TOTAL_SUPPLY: u64 = 1_000_000_000_000;
+    const BASE_AMOUNT: u64 = 10_000_000_000;
+
+    fn market() -> Market {
+        market_with_quote_token_decimals(9)
+    }
+
+    fn market_with_quote_token_decimals(quote_token_decimals: u8) -> Market {
+        let mut market = Market::zeroed();
+        let curve = Curve::default();
+
+        market
+            .initialize(
+                0,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                quote_token_decimals,
+                TOTAL_SUPPLY,
+                0,
+                0,
+            )
+            .unwrap();
+        market
+            .check_and_set_prices(&curve.bid_prices, &curve.ask_prices, 0)
+            .unwrap();
+
+        market
+    }
+
+    fn sell_quote_amount(market: &Market, supply: u64) -> u64 {
+        market
+            .get_quote_amount_with_parameters(
+                supply - BASE_AMOUNT,
+                BASE_AMOUNT,
+                SwapAmountType::ExactInput,
+                Rounding::Down,
+            )
+            .unwrap()
+            .1
+    }
+
+    #[rstest]
+    fn swap_with_quote_token_decimals(
+        #[values(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12)] quote_token_decimals: u8,
+    ) {
+        let market = market_with_quote_token_decimals(quote_token_decimals);
+        // With 10 decimals a quote token unit is a normalized unit
+        let reference_market = market_with_quote_token_decimals(10);
+
+        let supply = TOTAL_SUPPLY / 3;
+
+        let quote_amount = |market: &Market, swap_type: SwapType| {
+            let (curve, rounding) = match swap_type {
+                SwapType::Buy => (market.ask_curve(), Rounding::Up),
+                SwapType::Sell => (market.bid_curve(), Rounding::Down),
+            };
+
+            curve
+                .get_quote_amount(supply, BASE_AMOUNT, rounding)
+                .unwrap()
+                .1
+        };
+
+        let to_decimals = |reference_quote_amount: u64| {
+            u128::from(reference_quote_amount) * 10u128.pow(u32::from(quote_token_decimals))
+                / 10u128.pow(10)
+        };
+
+        let buy_quote_amount = quote_amount(&market, SwapType::Buy);
+        let sell_quote_amount = quote_amount(&market, SwapType::Sell);
+
+        assert!(sell_quote_amount < buy_quote_amount);
+        assert!(
+            u128::from(buy_quote_amount)
+                .abs_diff(to_decimals(quote_amount(&reference_market, SwapType::Buy)))
+                <= 1
+        );
+        assert!(
+            u128::from(sell_quote_amount)
+                .abs_diff(to_decimals(quote_amount(&reference_market, SwapType::Sell)))
+                <= 1
+        );
+
+        // Buying with the quote amount of `BASE_AMOUNT` never spends more than it
+        let (base_amount, quote_amount_spent) = market
+            .ask_curve()
+            .get_base_amount_out(supply, buy_quote_amount)
+            .unwrap();
+
+        assert!(base_amount >= BASE_AMOUNT - 1);
+        assert!(quote_amount_spent <= buy_quote_amount);
+
+        // Selling for the quote amount of `BASE_AMOUNT` never receives more than it
+        let (base_amount, quote_amount_received) = market
+            .bid_curve()
+            .get_base_amount_in(supply + BASE_AMOUNT, sell_quote_amount)
+            .unwrap();
+
+        assert!(base_amount <= BASE_AMOUNT + 1);
+        assert!(quote_amount_received <= sell_quote_amount);
+    }
+
+    #[rstest]
+    fn initialize_with_unsupported_quote_token_decimals(
+        #[values(MAX_QUOTE_TOKEN_DECIMALS + 1, 18, u8::MAX)] quote_token_decimals: u8,
+    ) {
+        let mut market = Market::zeroed();
+
+        assert!(market
+            .initialize(
+                0,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                quote_token_decimals,
+                TOTAL_SUPPLY,
+                0,
+                0,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn get_liquidation_supply() {
+        let market = market();
+
+        let target = sell_quote_amount(&market, TOTAL_SUPPLY / 2);
+
+        let (supply, price) = market
+            .get_liquidation_supply(BASE_AMOUNT, target)
+            .unwrap()
+            .unwrap();
+
+        assert!(supply <= TOTAL_SUPPLY / 2);
+        assert!(sell_quote_amount(&market, supply) >= target);
+        assert!(sell_quote_amount(&market, supply - 1) < target);
+        assert_eq!(price, market.get_bid_price(supply).unwrap());
+    }
+
+    #[test]
+    fn get_liquidation_supply_unreachable() {
+        let market = market();
+
+        let target = sell_quote_amount(&market, TOTAL_SUPPLY) + 1;
+
+        assert_eq!(
+            market.get_liquidation_supply(BASE_AMOUNT, target).unwrap(),
+            None
+        );
+        assert_eq!(
+            market.get_liquidation_supply(TOTAL_SUPPLY + 1, 0).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn swap_on_flat_curve() {
+        let mut market = Market::zeroed();
+
+        market
+            .initialize(
+                0,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                9,
+                TOTAL_SUPPLY,
+                0,
+                0,
+            )
+            .unwrap();
+
+        // One quote unit per base unit on the ask side, 0.9 on the bid side
+        let ask_price = crate::constant::SCALE as u64 / 1_000;
+        let bid_price = ask_price * 9 / 10;
+
+        market
+            .check_and_set_prices(
+                &[bid_price; crate::constant::PRICES_LENGTH],
+                &[ask_price; crate::constant::PRICES_LENGTH],
+                0,
+            )
+            .unwrap();
+
+        assert_eq!(
+            market.get_base_amount_out(BASE_AMOUNT).unwrap(),
+            (BASE_AMOUNT, BASE_AMOUNT)
+        );
+        assert_eq!(
+            market
+                .get_quote_amount(BASE_AMOUNT, SwapAmountType::ExactOutput)
+                .unwrap(),
+            (BASE_AMOUNT, BASE_AMOUNT)
+        );
+
+        market.base_reserve -= BASE_AMOUNT;
+
+        assert_eq!(
+            market.get_base_amount_in(BASE_AMOUNT * 9 / 10).unwrap(),
+            (BASE_AMOUNT, BASE_AMOUNT * 9 / 10)
+        );
+    }
+
+    #[test]
+    fn swap_on_finer_curve() {
+        let market = market();
+        let mut fine_market = Market::zeroed();
+        let curve = Curve::linear(crate::constant::MAX_PRICES_LENGTH);
+
+        fine_market
+            .initialize(
+                0,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                9,
+                TOTAL_SUPPLY,
+                0,
+                0,
+            )
+            .unwrap();
+        fine_market
+            .check_and_set_prices(&curve.bid_prices, &curve.ask_prices, 0)
+            .unwrap();
+
+        assert_eq!(fine_market.interval_number, 50);
+        assert_eq!(fine_market.width_scaled, market.width_scaled / 5);
+
+        // Both curves sample the same lines, so they only differ by rounding
+        for supply in [BASE_AMOUNT, TOTAL_SUPPLY / 3, TOTAL_SUPPLY - BASE_AMOUNT] {
+            assert_eq!(
+                fine_market.get_ask_price(supply).unwrap(),
+                market.get_ask_price(supply).unwrap()
+            );
+            assert_eq!(
+                fine_market.get_bid_price(supply).unwrap(),
+                market.get_bid_price(supply).unwrap()
+            );
+
+            for (swap_amount_type, rounding) in [
+                (SwapAmountType::ExactOutput, Rounding::Up),
+                (SwapAmountType::ExactInput, Rounding::Down),
+            ] {
+                let (_, quote_amount) = market
+                    .get_quote_amount_with_parameters(
+                        supply,
+                        BASE_AMOUNT,
+                        swap_amount_type,
+                        rounding,
+                    )
+                    .unwrap();
+                let (_, fine_quote_amount) = fine_market
+                    .get_quote_amount_with_parameters(
+                        supply,
+                        BASE_AMOUNT,
+                        swap_amount_type,
+                        rounding,
+                    )
+                    .unwrap();
+
+                assert!(quote_amount.abs_diff(fine_quote_amount) <= 1);
+            }
+        }
+    }
+
+    #[test]
+    fn swap_with_virtual_supply() {
+        let mut market = Market::zeroed();
+        let curve = Curve::default();
+
+        market
+            .initialize(
+                0,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                9,
+                TOTAL_SUPPLY,
+                0,
+                0,
+            )
+            .unwrap();
+
+        // The curve spans 125% of the total supply, so the market starts at its third price
+        market.set_virtual_supply(TOTAL_SUPPLY / 4).unwrap();
+        market
+            .check_and_set_prices(&curve.bid_prices, &curve.ask_prices, 0)
+            .unwrap();
+
+        assert_eq!(market.curve_length(), TOTAL_SUPPLY + TOTAL_SUPPLY / 4);
+        assert_eq!(market.circulating_supply(), 0);
+        assert_eq!(
+            market.get_spot_prices().unwrap(),
+            (curve.ask_prices[2], curve.bid_prices[2])
+        );
+        assert!(market.set_virtual_supply(0).is_err());
+
+        let (base_amount, quote_amount) = market
+            .get_quote_amount(BASE_AMOUNT, SwapAmountType::ExactOutput)
+            .unwrap();
+
+        assert!(quote_amount > 0);
+
+        market.base_reserve -= base_amount;
+
+        // Sells are capped to the circulating supply
+        let (base_amount_in, quote_amount_out) = market.get_base_amount_in(u64::MAX).unwrap();
+
+        assert_eq!(base_amount_in, BASE_AMOUNT);
+        assert!(quote_amount_out <= quote_amount);
+
+        let invariants = market.invariants().unwrap();
+
+        assert_eq!(invariants.circulating_supply, BASE_AMOUNT);
+        assert!(invariants.worst_case_quote <= quote_amount);
+    }
+
+    #[test]
+    fn set_virtual_supply_too_large() {
+        let mut market = Market::zeroed();
+
+        market
+            .initialize(
+                0,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                9,
+                TOTAL_SUPPLY,
+                0,
+                0,
+            )
+            .unwrap();
+
+        assert!(market
+            .set_virtual_supply(crate::constant::MAX_TOTAL_SUPPLY - TOTAL_SUPPLY + 1)
+            .is_err());
+        assert!(market.set_virtual_supply(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn check_and_set_prices_with_invalid_resolution() {
+        let mut market = Market::zeroed();
+        let curve = Curve::linear(21);
+
+        market
+            .initialize(
+                0,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                9,
+                // Divisible into 10 intervals but not into 20
+                10 * crate::constant::BASE_PRECISION + 10,
+                0,
+                0,
+            )
+            .unwrap();
+
+        assert!(market
+            .check_and_set_prices(&curve.bid_prices, &curve.ask_prices, 0)
+            .is_err());
+        assert!(market
+            .check_and_set_prices(&curve.bid_prices, &curve.ask_prices[1..], 0)
+            .is_err());
+    }
+
+    #[test]
+    fn check_and_set_prices_with_max_spread() {
+        let mut market = market();
+        let curve = Curve::default();
+
+        market.ask_prices = [0; crate::constant::MAX_PRICES_LENGTH];
+
+        // The default curve bids 10% below the asks
+        assert!(market
+            .check_and_set_prices(&curve.bid_prices, &curve.ask_prices, 999)
+            .is_err());
+        assert!(market
+            .check_and_set_prices(&curve.bid_prices, &curve.ask_prices, 1_000)
+            .is_ok());
+    }
+
+    #[test]
+    fn update_prices() {
+        let mut market = market();
+        let default_curve = Curve::default();
+        let finer_curve = Curve::linear(51);
+
+        market
+            .update_prices(&finer_curve.bid_prices, &finer_curve.ask_prices, 0)
+            .unwrap();
+
+        assert_eq!(market.prices_length(), 51);
+        assert_eq!(
+            market.prices_hash,
+            hash_prices(&finer_curve.bid_prices, &finer_curve.ask_prices)
+        );
+
+        market
+            .update_prices(&default_curve.bid_prices, &default_curve.ask_prices, 0)
+            .unwrap();
+
+        assert_eq!(market.prices_length(), crate::constant::PRICES_LENGTH);
+        assert_eq!(market.ask_prices[crate::constant::PRICES_LENGTH..], [0; 40]);
+
+        market.base_reserve -= BASE_AMOUNT;
+
+        assert!(market
+            .update_prices(&finer_curve.bid_prices, &finer_curve.ask_prices, 0)
+            .is_err());
+        assert!(Market::zeroed()
+            .update_prices(&default_curve.bid_prices, &default_curve.ask_prices, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn raise_tail_prices() {
+        let mut market = market();
+        let width = TOTAL_SUPPLY / crate::constant::INTERVAL_NUMBER;
+
+        // Halfway through the third interval, prices from the fifth one can be raised
+        market.base_reserve -= 2 * width + width / 2;
+
+        let mut ask_prices = market.ask_prices[..market.prices_length()].to_vec();
+
+        for ask_price in ask_prices.iter_mut().skip(4) {
+            *ask_price *= 2;
+        }
+
+        let previous_bid_prices = market.bid_prices;
+
+        market.raise_tail_prices(&ask_prices, 0).unwrap();
+
+        assert_eq!(market.ask_prices[..market.prices_length()], ask_prices);
+        assert_eq!(market.bid_prices[..4], previous_bid_prices[..4]);
+        assert_eq!(market.bid_prices[4], previous_bid_prices[4] * 2);
+        assert_eq!(market.bid_prices[10], previous_bid_prices[10] * 2);
+        assert_eq!(
+            market.prices_hash,
+            hash_prices(&market.bid_prices[..market.prices_length()], &ask_prices)
+        );
+
+        // Prices of the interval holding the circulating supply are locked
+        let mut locked_ask_prices = ask_prices.clone();
+        locked_ask_prices[3] += 1;
+
+        assert!(market.raise_tail_prices(&locked_ask_prices, 0).is_err());
+
+        // Prices can't be lowered
+        let mut lowered_ask_prices = ask_prices.clone();
+        lowered_ask_prices[8] -= 1;
+
+        assert!(market.raise_tail_prices(&lowered_ask_prices, 0).is_err());
+        assert!(market.raise_tail_prices(&ask_prices[1..], 0).is_err());
+    }
+
+    #[test]
+    fn prices_hash() {
+        let market = market();
+        let curve = Curve::default();
+
+        assert_eq!(
+            market.prices_hash,
+            hash_prices(&curve.bid_prices, &curve.ask_prices)
+        );
+        assert_ne!(
+            market.prices_hash,
+            hash_prices(&curve.ask_prices, &curve.bid_prices)
+        );
+    }
+
+    #[test]
+    fn get_bid_price() {
+        let market = market();
+        let width = TOTAL_SUPPLY / crate::constant::INTERVAL_NUMBER;
+
+        assert_eq!(market.get_bid_price(0).unwrap(), market.bid_prices[0]);
+        assert_eq!(market.get_bid_price(width).unwrap(), market.bid_prices[1]);
+        assert_eq!(
+            market.get_bid_price(TOTAL_SUPPLY).unwrap(),
+            market.bid_prices[crate::constant::INTERVAL_NUMBER as usize]
+        );
+
+        let mid_price = market.get_bid_price(width / 2).unwrap();
+
+        assert!(mid_price > market.bid_prices[0] && mid_price < market.bid_prices[1]);
+    }
+
+    #[test]
+    fn get_ask_price() {
+        let market = market();
+        let width = TOTAL_SUPPLY / crate::constant::INTERVAL_NUMBER;
+
+        assert_eq!(market.get_ask_price(0).unwrap(), market.ask_prices[0]);
+        assert_eq!(market.get_ask_price(width).unwrap(), market.ask_prices[1]);
+        assert_eq!(
+            market.get_ask_price(TOTAL_SUPPLY).unwrap(),
+            market.ask_prices[crate::constant::INTERVAL_NUMBER as usize]
+        );
+        assert!(
+            market.get_ask_price(width / 2).unwrap() >= market.get_bid_price(width / 2).unwrap()
+        );
+    }
+
+    #[test]
+    fn get_incentive_weight() {
+        let mut market = market();
+
+        assert_eq!(
+            market
+                .get_incentive_weight(IncentiveAction::Buy, BASE_AMOUNT)
+                .unwrap(),
+            None
+        );
+
+        market.set_incentive_weights(10_000, 0, 25_000);
+
+        assert_eq!(
+            market
+                .get_incentive_weight(IncentiveAction::Buy, BASE_AMOUNT)
+                .unwrap(),
+            Some(BASE_AMOUNT)
+        );
+        assert_eq!(
+            market
+                .get_incentive_weight(IncentiveAction::Sell, BASE_AMOUNT)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            market
+                .get_incentive_weight(IncentiveAction::Stake, BASE_AMOUNT)
+                .unwrap(),
+            Some(BASE_AMOUNT * 5 / 2)
+        );
+    }
+
+    #[test]
+    fn check_slot_price_move() {
+        let mut market = market();
+        market.base_reserve -= BASE_AMOUNT;
+
+        market.set_max_slot_price_move(100, 1).unwrap();
+
+        // The ask price of the default curve is proportional to the supply
+        market.base_reserve -= BASE_AMOUNT / 200;
+        market.check_slot_price_move(1).unwrap();
+
+        market.base_reserve -= BASE_AMOUNT / 50;
+        assert!(market.check_slot_price_move(1).is_err());
+
+        market.check_slot_price_move(2).unwrap();
+
+        assert_eq!(market.guarded_slot, 2);
+        assert_eq!(
+            market.guarded_slot_open_price,
+            market.get_ask_price(market.circulating_supply()).unwrap()
+        );
+    }
+
+    #[test]
+    fn launch_fee_decay() {
+        let mut market = market();
+
+        market
+            .set_launch_fee(3_000, 100, LaunchFeeRecipient::Staking)
+            .unwrap();
+
+        // The decay starts on the first swap
+        assert_eq!(market.update_launch_fee(1_000), 3_000);
+        assert_eq!(market.update_launch_fee(1_025), 2_250);
+        assert_eq!(market.update_launch_fee(1_099), 30);
+        assert_eq!(market.update_launch_fee(1_100), 0);
+
+        assert_eq!(market.launch_fee_bps, 0);
+        assert_eq!(market.update_launch_fee(1_050), 0);
+
+        market.credit_launch_fee(42);
+
+        assert_eq!(market.fees.pending_staking_fees, 42);
+        assert_eq!(market.fees.pending_creator_fees, 0);
+    }
+
+    #[test]
+    fn graduate() {
+        let mut market = market();
+
+        market.set_graduation_supply(TOTAL_SUPPLY / 5).unwrap();
+
+        assert_eq!(market.available_supply(), TOTAL_SUPPLY * 4 / 5);
+        assert!(market.graduate().is_err());
+
+        // The curve sells out below the graduation supply
+        market.base_reserve -= market.available_supply();
+
+        let circulating_supply = market.circulating_supply();
+        let (_, backing_quote_amount) = market
+            .get_quote_amount_with_parameters(
+                0,
+                circulating_supply,
+                SwapAmountType::ExactInput,
+                Rounding::Down,
+            )
+            .unwrap();
+
+        assert!(market.is_graduation_reached());
+        assert_eq!(
+            market.graduate().unwrap(),
+            (TOTAL_SUPPLY / 5, backing_quote_amount, 0)
+        );
+        assert_eq!(market.graduated, 1);
+        assert_eq!(market.base_reserve, 0);
+        assert_eq!(market.total_supply, TOTAL_SUPPLY);
+        assert!(market.graduate().is_err());
+    }
+
+    #[test]
+    fn graduate_at_max_raise() {
+        let mut market = market();
+
+        market.set_graduation_supply(TOTAL_SUPPLY / 5).unwrap();
+        market.set_max_raise(1).unwrap();
+
+        market.base_reserve -= BASE_AMOUNT;
+        market.quote_raised = 1;
+
+        let (base_amount, _, unsold_amount) = market.graduate().unwrap();
+
+        // The supply left unsold is burned
+        assert_eq!(base_amount, TOTAL_SUPPLY / 5);
+        assert_eq!(unsold_amount, TOTAL_SUPPLY * 4 / 5 - BASE_AMOUNT);
+        assert_eq!(market.total_supply, TOTAL_SUPPLY / 5 + BASE_AMOUNT);
+        assert_eq!(market.circulating_supply(), TOTAL_SUPPLY / 5 + BASE_AMOUNT);
+    }
+
+    #[test]
+    fn graduate_with_secondary_curve() {
+        let mut market = market();
+
+        market.set_graduation_supply(TOTAL_SUPPLY / 5).unwrap();
+        market.set_max_raise(1).unwrap();
+
+        let max_ask_price = market.max_ask_price();
+
+        assert!(market.set_secondary_curve_price(max_ask_price - 1).is_err());
+
+        market.set_secondary_curve_price(max_ask_price).unwrap();
+
+        market.base_reserve -= BASE_AMOUNT;
+        market.quote_raised = 1;
+
+        assert!(market.buy_secondary_curve(BASE_AMOUNT).is_err());
+
+        let (_, _, burned_amount) = market.graduate().unwrap();
+
+        // The supply left unsold stays in the reserve for the secondary curve
+        let secondary_curve_supply = TOTAL_SUPPLY * 4 / 5 - BASE_AMOUNT;
+
+        assert_eq!(burned_amount, 0);
+        assert_eq!(market.secondary_curve_supply, secondary_curve_supply);
+        assert_eq!(market.base_reserve, secondary_curve_supply);
+        assert_eq!(market.total_supply, TOTAL_SUPPLY);
+        assert_eq!(market.available_supply(), 0);
+        assert!(market.set_secondary_curve_price(0).is_err());
+
+        let quote_amount = market.buy_secondary_curve(BASE_AMOUNT).unwrap();
+
+        assert!(quote_amount > 0);
+        assert_eq!(market.fees.pending_staking_fees, quote_amount);
+        assert_eq!(
+            market.secondary_curve_supply,
+            secondary_curve_supply - BASE_AMOUNT
+        );
+        assert!(market
+            .buy_secondary_curve(secondary_curve_supply - BASE_AMOUNT + 1)
+            .is_err());
+    }
+
+    #[test]
+    fn graduate_without_graduation_supply() {
+        let mut market = market();
+
+        market.base_reserve = 0;
+
+        assert!(market.graduate().is_err());
+    }
+
+    #[test]
+    fn set_graduation_supply() {
+        let mut market = market();
+
+        assert!(market.set_graduation_supply(TOTAL_SUPPLY).is_err());
+
+        market.swap_count = 1;
+
+        assert!(market.set_graduation_supply(TOTAL_SUPPLY / 5).is_err());
+    }
+
+    #[test]
+    fn propose_close() {
+        let mut market = market();
+
+        market.creation_time = 1_000;
+
+        assert_eq!(market.propose_close(1_100, 50, 100).unwrap(), 1_150);
+
+        // Too young
+        assert!(market.propose_close(1_099, 50, 100).is_err());
+
+        market.base_reserve -= BASE_AMOUNT;
+
+        assert!(market.propose_close(1_100, 50, 100).is_err());
+
+        market.graduated = 1;
+
+        assert_eq!(market.propose_close(1_200, 0, 100).unwrap(), 1_200);
+    }
+
+    #[test]
+    fn close() {
+        let mut market = market();
+
+        market.swap_count = 2;
+        market.fees.pending_creator_fees = 10;
+        market.fees.pending_staking_fees = 20;
+        market.fees.pending_burn_fees = 30;
+
+        assert_eq!(market.close(TOTAL_SUPPLY, 63).unwrap(), (TOTAL_SUPPLY, 63));
+
+        // Donations are burned or swept with the rest
+        assert_eq!(
+            market.close(TOTAL_SUPPLY + 1, 1_000).unwrap(),
+            (TOTAL_SUPPLY + 1, 1_000)
+        );
+
+        market.base_reserve -= BASE_AMOUNT;
+
+        assert!(market.close(TOTAL_SUPPLY - BASE_AMOUNT, 60).is_err());
+
+        market.graduated = 1;
+
+        assert_eq!(
+            market.close(TOTAL_SUPPLY - BASE_AMOUNT, 60).unwrap(),
+            (TOTAL_SUPPLY - BASE_AMOUNT, 60)
+        );
+
+        market.creator_fee_split = 1;
+
+        assert!(market.close(TOTAL_SUPPLY - BASE_AMOUNT, 60).is_err());
+    }
+
+    #[test]
+    fn close_with_escrow() {
+        let mut market = market();
+
+        market.escrow_base(BASE_AMOUNT).unwrap();
+        market.escrow_quote(42).unwrap();
+
+        assert!(market.close(TOTAL_SUPPLY + BASE_AMOUNT, 42).is_err());
+
+        market.release_base(BASE_AMOUNT).unwrap();
+
+        assert!(market.close(TOTAL_SUPPLY, 42).is_err());
+        assert!(market.release_quote(43).is_err());
+
+        market.release_quote(42).unwrap();
+
+        assert_eq!(market.close(TOTAL_SUPPLY, 0).unwrap(), (TOTAL_SUPPLY, 0));
+    }
+
+    #[test]
+    fn close_with_burned_supply() {
+        let mut market = market();
+
+        let (_, spot_bid_price) = market.get_spot_prices().unwrap();
+
+        market.total_supply -= BASE_AMOUNT;
+        market.base_reserve -= BASE_AMOUNT;
+        market.burned_supply = BASE_AMOUNT;
+
+        // The burned supply lifts the curve position without being circulating
+        assert_eq!(market.circulating_supply(), 0);
+        assert!(market.get_spot_prices().unwrap().1 > spot_bid_price);
+
+        let (_, burned_supply_quote) = market
+            .get_quote_amount_with_parameters(
+                0,
+                BASE_AMOUNT,
+                SwapAmountType::ExactInput,
+                Rounding::Down,
+            )
+            .unwrap();
+
+        // The quote amount paid for the burned supply is swept to the creator
+        assert!(burned_supply_quote > 0);
+        assert_eq!(
+            market
+                .close(TOTAL_SUPPLY - BASE_AMOUNT, burned_supply_quote)
+                .unwrap(),
+            (TOTAL_SUPPLY - BASE_AMOUNT, burned_supply_quote)
+        );
+    }
 
     #[test]
     fn size() {
@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+
+pub const MARKET_ORACLE_PDA_SEED: &str = "market_oracle";
+
+/// Time-weighted spot prices of a market, updated by every swap once created.
+/// The TWAP between two observations is the difference of their cumulative prices divided by the elapsed time
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct MarketOracle {
+    pub bump: u8,
+    pub market: Pubkey,
+    pub last_update_timestamp: i64,
+    /// Ask and bid prices of the curve after the last swap
+    pub last_ask_price: u64,
+    pub last_bid_price: u64,
+    /// Sums of the spot prices weighted by the number of seconds they were in effect
+    pub cumulative_ask_price: u128,
+    pub cumulative_bid_price: u128,
+}
+
+impl MarketOracle {
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        market: Pubkey,
+        timestamp: i64,
+        ask_price: u64,
+        bid_price: u64,
+    ) -> Result<()> {
+        self.bump = bump;
+        self.market = market;
+        self.last_update_timestamp = timestamp;
+        self.last_ask_price = ask_price;
+        self.last_bid_price = bid_price;
+
+        Ok(())
+    }
+
+    /// Cumulative ask and bid prices at `timestamp`, the last spot prices being in effect since the last update
+    pub fn get_cumulative_prices(&self, timestamp: i64) -> Result<(u128, u128)> {
+        let elapsed = u128::try_from(timestamp.saturating_sub(self.last_update_timestamp).max(0))?;
+
+        Ok((
+            self.cumulative_ask_price + u128::from(self.last_ask_price) * elapsed,
+            self.cumulative_bid_price + u128::from(self.last_bid_price) * elapsed,
+        ))
+    }
+
+    pub fn record(&mut self, timestamp: i64, ask_price: u64, bid_price: u64) -> Result<()> {
+        (self.cumulative_ask_price, self.cumulative_bid_price) =
+            self.get_cumulative_prices(timestamp)?;
+
+        self.last_update_timestamp = timestamp;
+        self.last_ask_price = ask_price;
+        self.last_bid_price = bid_price;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MarketOracle;
+
+    #[test]
+    fn record() {
+        let mut oracle = MarketOracle {
+            bump: 0,
+            market: Default::default(),
+            last_update_timestamp: 0,
+            last_ask_price: 0,
+            last_bid_price: 0,
+            cumulative_ask_price: 0,
+            cumulative_bid_price: 0,
+        };
+
+        oracle
+            .initialize(0, Default::default(), 100, 10, 9)
+            .unwrap();
+
+        // Several swaps in the same second only keep the last prices
+        oracle.record(110, 1_000, 900).unwrap();
+        oracle.record(110, 20, 18).unwrap();
+
+        assert_eq!(oracle.cumulative_ask_price, 100);
+        assert_eq!(oracle.cumulative_bid_price, 90);
+
+        assert_eq!(oracle.get_cumulative_prices(115).unwrap(), (200, 180));
+
+        oracle.record(120, 30, 27).unwrap();
+
+        assert_eq!(oracle.cumulative_ask_price, 300);
+        assert_eq!(oracle.cumulative_bid_price, 270);
+        assert_eq!(oracle.last_ask_price, 30);
+        assert_eq!(oracle.last_update_timestamp, 120);
+    }
+}
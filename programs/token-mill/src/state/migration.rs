@@ -0,0 +1,170 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+use crate::errors::TokenMillError;
+
+pub const MIGRATION_PDA_SEED: &str = "migration";
+pub const MIGRATION_CLAIM_PDA_SEED: &str = "migration_claim";
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Leaf of the holder snapshot merkle tree
+pub fn hash_holder(holder: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, holder.as_ref(), &amount.to_le_bytes()]).to_bytes()
+}
+
+/// Parent of two nodes of the holder snapshot merkle tree, hashed in sorted order so that proofs don't need directions
+pub fn hash_nodes(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if left <= right {
+        (left, right)
+    } else {
+        (right, left)
+    };
+
+    hashv(&[NODE_PREFIX, first, second]).to_bytes()
+}
+
+/// Import of a project from another launchpad into a wrap-mode market.
+/// The unsold supply of the project is the supply escrowed by the market, the holder snapshot is committed as a merkle root.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct Migration {
+    pub bump: u8,
+    pub market: Pubkey,
+    /// Program or market of the launchpad the project comes from, informative only
+    pub source: Pubkey,
+    /// Slot at which the holder snapshot was taken
+    pub snapshot_slot: u64,
+    /// Root of the merkle tree of `hash_holder(holder, amount)` leaves
+    pub holders_root: [u8; 32],
+    /// Base tokens escrowed by the market for the holders to claim, 0 if the snapshot is only recorded
+    pub claim_supply: u64,
+    pub claimed_amount: u64,
+}
+
+/// Marks the claim of a holder, so that each leaf of the snapshot can only be claimed once
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct MigrationClaim {
+    pub bump: u8,
+    pub migration: Pubkey,
+    pub holder: Pubkey,
+    pub amount: u64,
+}
+
+impl Migration {
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        market: Pubkey,
+        source: Pubkey,
+        snapshot_slot: u64,
+        holders_root: [u8; 32],
+        claim_supply: u64,
+    ) -> Result<()> {
+        self.bump = bump;
+        self.market = market;
+        self.source = source;
+        self.snapshot_slot = snapshot_slot;
+        self.holders_root = holders_root;
+        self.claim_supply = claim_supply;
+
+        Ok(())
+    }
+
+    pub fn verify_holder(&self, holder: &Pubkey, amount: u64, proof: &[[u8; 32]]) -> bool {
+        let root = proof
+            .iter()
+            .fold(hash_holder(holder, amount), |node, sibling| {
+                hash_nodes(&node, sibling)
+            });
+
+        root == self.holders_root
+    }
+
+    pub fn claim(&mut self, holder: &Pubkey, amount: u64, proof: &[[u8; 32]]) -> Result<()> {
+        require!(
+            self.verify_holder(holder, amount, proof),
+            TokenMillError::InvalidMerkleProof
+        );
+
+        let claimed_amount = self.claimed_amount + amount;
+
+        require!(
+            amount > 0 && claimed_amount <= self.claim_supply,
+            TokenMillError::InvalidAmount
+        );
+
+        self.claimed_amount = claimed_amount;
+
+        Ok(())
+    }
+}
+
+impl MigrationClaim {
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        migration: Pubkey,
+        holder: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        self.bump = bump;
+        self.migration = migration;
+        self.holder = holder;
+        self.amount = amount;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migration(holders_root: [u8; 32]) -> Migration {
+        Migration {
+            bump: 0,
+            market: Pubkey::default(),
+            source: Pubkey::default(),
+            snapshot_slot: 0,
+            holders_root,
+            claim_supply: 1_000,
+            claimed_amount: 0,
+        }
+    }
+
+    #[test]
+    fn verify_holder() {
+        let holders = [
+            (Pubkey::new_unique(), 100),
+            (Pubkey::new_unique(), 200),
+            (Pubkey::new_unique(), 300),
+        ];
+        let leaves = holders.map(|(holder, amount)| hash_holder(&holder, amount));
+
+        // The odd leaf is promoted as is
+        let root = hash_nodes(&hash_nodes(&leaves[0], &leaves[1]), &leaves[2]);
+        let mut migration = migration(root);
+
+        assert!(migration.verify_holder(&holders[0].0, 100, &[leaves[1], leaves[2]]));
+        assert!(migration.verify_holder(&holders[2].0, 300, &[hash_nodes(&leaves[0], &leaves[1])]));
+        assert!(!migration.verify_holder(&holders[0].0, 101, &[leaves[1], leaves[2]]));
+        assert!(!migration.verify_holder(&holders[1].0, 200, &[leaves[1], leaves[2]]));
+
+        migration
+            .claim(&holders[1].0, 200, &[leaves[0], leaves[2]])
+            .unwrap();
+
+        assert_eq!(migration.claimed_amount, 200);
+
+        migration.claim_supply = 300;
+
+        assert!(migration
+            .claim(&holders[0].0, 100, &[leaves[1], leaves[2]])
+            .is_ok());
+        assert!(migration
+            .claim(&holders[2].0, 300, &[hash_nodes(&leaves[0], &leaves[1])])
+            .is_err());
+    }
+}
@@ -1,13 +1,47 @@
+pub mod boost;
+pub mod change_log;
+pub mod compounding_vault;
 pub mod config;
+pub mod creator_fee_split;
+pub mod dca_position;
+pub mod firm_quote;
+pub mod graduation;
+pub mod lock_certificate;
 pub mod market;
+pub mod market_oracle;
+pub mod migration;
+pub mod protocol_stats;
 pub mod quote_token_badge;
 pub mod referral;
+pub mod restricted_wallet;
+pub mod reward_vault;
 pub mod staking;
+pub mod staking_checkpoints;
+pub mod swap_delegate;
+pub mod trader_profile;
+pub mod trader_stats;
 pub mod vesting;
 
+pub use boost::*;
+pub use change_log::*;
+pub use compounding_vault::*;
 pub use config::*;
+pub use creator_fee_split::*;
+pub use dca_position::*;
+pub use firm_quote::*;
+pub use graduation::*;
+pub use lock_certificate::*;
 pub use market::*;
+pub use market_oracle::*;
+pub use migration::*;
+pub use protocol_stats::*;
 pub use quote_token_badge::*;
 pub use referral::*;
+pub use restricted_wallet::*;
+pub use reward_vault::*;
 pub use staking::*;
+pub use staking_checkpoints::*;
+pub use swap_delegate::*;
+pub use trader_profile::*;
+pub use trader_stats::*;
 pub use vesting::*;
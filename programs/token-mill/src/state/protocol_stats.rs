@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+pub const PROTOCOL_STATS_PDA_SEED: &str = "protocol_stats";
+
+/// Protocol-wide statistics of a config for a given quote token.
+/// Instructions update it only when the account is provided, to avoid write-locking it on every transaction.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct ProtocolStats {
+    pub bump: u8,
+    pub config: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub markets_created: u64,
+    pub markets_graduated: u64,
+    pub cumulative_volume: u128,
+    pub cumulative_fees: u128,
+}
+
+impl ProtocolStats {
+    pub fn initialize(&mut self, bump: u8, config: Pubkey, quote_token_mint: Pubkey) -> Result<()> {
+        self.bump = bump;
+        self.config = config;
+        self.quote_token_mint = quote_token_mint;
+
+        Ok(())
+    }
+
+    pub fn record_swap(&mut self, quote_amount: u64, swap_fee: u64) {
+        self.cumulative_volume += u128::from(quote_amount);
+        self.cumulative_fees += u128::from(swap_fee);
+    }
+}
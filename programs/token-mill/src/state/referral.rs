@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::TokenMillError;
+
 pub const REFERRAL_ACCOUNT_PDA_SEED: &str = "referral";
 
 #[account]
@@ -18,3 +20,53 @@ impl ReferralAccount {
         Ok(())
     }
 }
+
+pub const REFERRAL_CODE_PDA_SEED: &str = "referral_code";
+pub const MAX_REFERRAL_CODE_LENGTH: usize = 16;
+
+/// Short code standing for a referral account in links, see `is_valid_referral_code`
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct ReferralCode {
+    pub bump: u8,
+    pub config: Pubkey,
+    pub referrer: Pubkey,
+    pub referral_account: Pubkey,
+    /// UTF-8 bytes of the code, right-padded with zeros
+    pub code: [u8; MAX_REFERRAL_CODE_LENGTH],
+}
+
+impl ReferralCode {
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        config: Pubkey,
+        referrer: Pubkey,
+        referral_account: Pubkey,
+        code: &str,
+    ) -> Result<()> {
+        require!(
+            is_valid_referral_code(code),
+            TokenMillError::InvalidReferralCode
+        );
+
+        self.bump = bump;
+        self.config = config;
+        self.referrer = referrer;
+        self.referral_account = referral_account;
+        self.code = [0; MAX_REFERRAL_CODE_LENGTH];
+        self.code[..code.len()].copy_from_slice(code.as_bytes());
+
+        Ok(())
+    }
+}
+
+/// Codes are up to `MAX_REFERRAL_CODE_LENGTH` lowercase letters, digits, `-` or `_`,
+/// so that the same code can't be registered twice with a different case
+pub fn is_valid_referral_code(code: &str) -> bool {
+    !code.is_empty()
+        && code.len() <= MAX_REFERRAL_CODE_LENGTH
+        && code
+            .bytes()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == b'-' || c == b'_')
+}
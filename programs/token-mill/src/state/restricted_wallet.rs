@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+pub const RESTRICTED_WALLET_PDA_SEED: &str = "restricted_wallet";
+
+/// Compliance hold placed by the config authority on the interactions of a wallet with a market.
+/// Swaps and stake updates of the wallet on the market fail for as long as this account exists.
+#[account]
+#[derive(InitSpace)]
+pub struct RestrictedWallet {
+    pub bump: u8,
+    pub market: Pubkey,
+    pub wallet: Pubkey,
+    pub restricted_at: i64,
+    /// Base token balance of the wallet ATA when the hold was placed
+    pub base_balance_snapshot: u64,
+}
+
+impl RestrictedWallet {
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        market: Pubkey,
+        wallet: Pubkey,
+        restricted_at: i64,
+        base_balance_snapshot: u64,
+    ) -> Result<()> {
+        self.bump = bump;
+        self.market = market;
+        self.wallet = wallet;
+        self.restricted_at = restricted_at;
+        self.base_balance_snapshot = base_balance_snapshot;
+
+        Ok(())
+    }
+}
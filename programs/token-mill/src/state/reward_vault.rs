@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+use crate::constant::STAKING_SCALE;
+
+pub const REWARD_VAULT_PDA_SEED: &str = "reward_vault";
+
+/// Holds the external rewards of a market in its `reward_token_mint` ATA.
+/// Their emission to the stakers is tracked by the `vault_emission` of the market staking account.
+#[account]
+#[derive(InitSpace)]
+pub struct RewardVault {
+    pub bump: u8,
+    pub market: Pubkey,
+    pub reward_token_mint: Pubkey,
+    /// Total amount deposited in the vault
+    pub total_funded: u64,
+}
+
+impl RewardVault {
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        market: Pubkey,
+        reward_token_mint: Pubkey,
+    ) -> Result<()> {
+        self.bump = bump;
+        self.market = market;
+        self.reward_token_mint = reward_token_mint;
+        self.total_funded = 0;
+
+        Ok(())
+    }
+}
+
+/// Linear emission of the reward vault to the staking shares, indexed alongside the staking fees
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq)]
+pub struct RewardEmission {
+    /// Funded rewards not emitted yet
+    pub reward_remaining: u64,
+    /// Rewards emitted per second, scaled by `STAKING_SCALE`
+    pub reward_rate: u128,
+    pub emission_end: i64,
+    pub last_update: i64,
+    pub acc_reward_amount_per_share: u128,
+}
+
+impl RewardEmission {
+    /// Emits the rewards since the last update to `total_shares`. Nothing is emitted while nothing is staked,
+    /// the rewards left at the end of the emission can be emitted again by the next funding.
+    pub fn update(&mut self, current_time: i64, total_shares: u64) -> Result<u128> {
+        let emission_time = current_time.min(self.emission_end);
+
+        if emission_time > self.last_update && total_shares > 0 {
+            let emitted_amount = if emission_time == self.emission_end {
+                self.reward_remaining
+            } else {
+                u64::try_from(
+                    self.reward_rate * u128::try_from(emission_time - self.last_update)?
+                        / STAKING_SCALE,
+                )?
+                .min(self.reward_remaining)
+            };
+
+            self.acc_reward_amount_per_share +=
+                u128::from(emitted_amount) * STAKING_SCALE / u128::from(total_shares);
+            self.reward_remaining -= emitted_amount;
+        }
+
+        self.last_update = self.last_update.max(current_time);
+
+        Ok(self.acc_reward_amount_per_share)
+    }
+
+    /// Adds `amount` to the remaining rewards and spreads them over `duration` from now
+    pub fn fund(&mut self, amount: u64, current_time: i64, duration: i64) -> Result<()> {
+        self.reward_remaining += amount;
+        self.reward_rate =
+            u128::from(self.reward_remaining) * STAKING_SCALE / u128::try_from(duration)?;
+        self.emission_end = current_time + duration;
+
+        Ok(())
+    }
+}
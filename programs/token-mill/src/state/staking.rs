@@ -1,9 +1,14 @@
 use anchor_lang::prelude::*;
 
-use crate::constant::STAKING_SCALE;
+use crate::{
+    constant::{MAX_BPS, STAKING_LOCKUP_TIERS, STAKING_SCALE},
+    errors::TokenMillError,
+    state::RewardEmission,
+};
 
 pub const MARKET_STAKING_PDA_SEED: &str = "market_staking";
 pub const STAKING_POSITION_PDA_SEED: &str = "stake_position";
+pub const STAKING_BADGE_PDA_SEED: &str = "staking_badge";
 
 #[account]
 #[derive(InitSpace)]
@@ -12,6 +17,21 @@ pub struct MarketStaking {
     pub amount_staked: u64,
     pub total_amount_vested: u64,
     pub acc_reward_amount_per_share: u128,
+    /// Sum of the `lock_bonus` of the stake positions
+    pub total_lock_bonus: u64,
+    /// Emission of the reward vault of the market, default if it has none
+    pub vault_emission: RewardEmission,
+    /// Emergency unstake penalties waiting for stakers to be spread over
+    pub pending_penalty_amount: u64,
+    pub acc_penalty_amount_per_share: u128,
+    /// Time deposits have to stay staked before they can be withdrawn, in seconds
+    pub min_stake_duration: i64,
+    /// Token-2022 group whose member NFTs boost the positions they're linked to, default if none
+    pub badge_collection: Pubkey,
+    /// Multiplier applied to the stake of positions linked to a badge, in bps
+    pub badge_multiplier_bps: u16,
+    /// Sum of the `badge_bonus` of the stake positions
+    pub total_badge_bonus: u64,
 }
 
 impl MarketStaking {
@@ -19,12 +39,34 @@ impl MarketStaking {
         self.market = market;
         self.amount_staked = 0;
         self.acc_reward_amount_per_share = 0;
+        self.total_lock_bonus = 0;
+        self.vault_emission = RewardEmission::default();
+        self.pending_penalty_amount = 0;
+        self.acc_penalty_amount_per_share = 0;
+        self.min_stake_duration = 0;
+        self.badge_collection = Pubkey::default();
+        self.badge_multiplier_bps = MAX_BPS as u16;
+        self.total_badge_bonus = 0;
 
         Ok(())
     }
 
+    pub fn total_shares(&self) -> u64 {
+        self.amount_staked
+            + self.total_amount_vested
+            + self.total_lock_bonus
+            + self.total_badge_bonus
+    }
+
+    /// Emits the vault rewards since the last update, to be called before any change of the staking shares
+    pub fn update_vault_emission(&mut self, current_time: i64) -> Result<u128> {
+        let total_shares = self.total_shares();
+
+        self.vault_emission.update(current_time, total_shares)
+    }
+
     pub fn accrue_rewards(&mut self, pending_rewards: u64) -> Result<u128> {
-        let total_shares = self.amount_staked + self.total_amount_vested;
+        let total_shares = self.total_shares();
 
         if total_shares > 0 && pending_rewards > 0 {
             self.acc_reward_amount_per_share +=
@@ -33,6 +75,19 @@ impl MarketStaking {
 
         Ok(self.acc_reward_amount_per_share)
     }
+
+    /// Spreads the pending penalties over the staking shares, kept pending while nothing is staked
+    pub fn accrue_penalties(&mut self) -> Result<u128> {
+        let total_shares = self.total_shares();
+
+        if total_shares > 0 && self.pending_penalty_amount > 0 {
+            self.acc_penalty_amount_per_share +=
+                u128::from(self.pending_penalty_amount) * STAKING_SCALE / u128::from(total_shares);
+            self.pending_penalty_amount = 0;
+        }
+
+        Ok(self.acc_penalty_amount_per_share)
+    }
 }
 
 #[account]
@@ -44,6 +99,26 @@ pub struct StakePosition {
     pub total_amount_vested: u64,
     pub pending_rewards: u64,
     pub acc_reward_amount_per_share: u128,
+    /// Part of `amount_staked` locked by the boost position of the user
+    pub amount_locked: u64,
+    /// Index of the lockup tier in `STAKING_LOCKUP_TIERS`, 0 if unlocked
+    pub lock_tier: u8,
+    /// Until then `amount_staked` can't be withdrawn
+    pub lock_end: i64,
+    /// Reward shares added on top of `amount_staked` by the multiplier of the lockup tier
+    pub lock_bonus: u64,
+    /// Reward vault tokens accrued and not claimed yet
+    pub pending_vault_rewards: u64,
+    pub acc_vault_reward_amount_per_share: u128,
+    pub acc_penalty_amount_per_share: u128,
+    /// Until then `amount_staked` can't be withdrawn, pushed back by every deposit
+    pub min_stake_end: i64,
+    /// NFT of the badge collection of the market linked to the position, default if none
+    pub badge_mint: Pubkey,
+    /// Reward shares added on top of `amount_staked` by the badge multiplier of the market
+    pub badge_bonus: u64,
+    /// Wallet allowed to claim the staking rewards to the user, default if none
+    pub claim_delegate: Pubkey,
 }
 
 impl StakePosition {
@@ -54,19 +129,109 @@ impl StakePosition {
         Ok(())
     }
 
-    pub fn accrue_rewards(&mut self, acc_reward_amount_per_share: u128) -> Result<()> {
-        let total_shares = self.amount_staked + self.total_amount_vested;
+    pub fn total_shares(&self) -> u64 {
+        self.amount_staked + self.total_amount_vested + self.lock_bonus + self.badge_bonus
+    }
 
-        if total_shares > 0 {
-            self.pending_rewards += u64::try_from(
+    /// Accrues the rewards since the last update, returning the amount accrued
+    pub fn accrue_rewards(&mut self, acc_reward_amount_per_share: u128) -> Result<u64> {
+        let total_shares = self.total_shares();
+
+        let reward_amount = if total_shares > 0 {
+            u64::try_from(
                 u128::from(total_shares)
                     * (acc_reward_amount_per_share - self.acc_reward_amount_per_share)
                     / STAKING_SCALE,
+            )?
+        } else {
+            0
+        };
+
+        self.pending_rewards += reward_amount;
+        self.acc_reward_amount_per_share = acc_reward_amount_per_share;
+
+        Ok(reward_amount)
+    }
+
+    pub fn accrue_vault_rewards(&mut self, acc_vault_reward_amount_per_share: u128) -> Result<()> {
+        let total_shares = self.total_shares();
+
+        if total_shares > 0 {
+            self.pending_vault_rewards += u64::try_from(
+                u128::from(total_shares)
+                    * (acc_vault_reward_amount_per_share - self.acc_vault_reward_amount_per_share)
+                    / STAKING_SCALE,
             )?;
         }
 
-        self.acc_reward_amount_per_share = acc_reward_amount_per_share;
+        self.acc_vault_reward_amount_per_share = acc_vault_reward_amount_per_share;
 
         Ok(())
     }
+
+    /// Share of the emergency unstake penalties accrued since the last update, to be restaked by the caller
+    pub fn accrue_penalties(&mut self, acc_penalty_amount_per_share: u128) -> Result<u64> {
+        let total_shares = self.total_shares();
+
+        let penalty_amount = u64::try_from(
+            u128::from(total_shares)
+                * (acc_penalty_amount_per_share - self.acc_penalty_amount_per_share)
+                / STAKING_SCALE,
+        )?;
+
+        self.acc_penalty_amount_per_share = acc_penalty_amount_per_share;
+
+        Ok(penalty_amount)
+    }
+
+    pub fn unlocked_amount(&self) -> u64 {
+        self.amount_staked - self.amount_locked
+    }
+
+    pub fn is_lockup_active(&self, current_time: i64) -> bool {
+        self.lock_end > current_time
+    }
+
+    /// Reward shares the lockup tier adds to the current `amount_staked`
+    pub fn get_lock_bonus(&self) -> Result<u64> {
+        let (_, multiplier_bps) = STAKING_LOCKUP_TIERS
+            .get(usize::from(self.lock_tier))
+            .ok_or(TokenMillError::InvalidLockDuration)?;
+
+        Ok(u64::try_from(
+            u128::from(self.amount_staked) * u128::from(multiplier_bps - MAX_BPS)
+                / u128::from(MAX_BPS),
+        )?)
+    }
+
+    /// Reward shares the linked badge adds to the current `amount_staked`
+    pub fn get_badge_bonus(&self, badge_multiplier_bps: u16) -> Result<u64> {
+        if self.badge_mint == Pubkey::default() {
+            return Ok(0);
+        }
+
+        Ok(u64::try_from(
+            u128::from(self.amount_staked) * u128::from(u64::from(badge_multiplier_bps) - MAX_BPS)
+                / u128::from(MAX_BPS),
+        )?)
+    }
+
+    /// Positions linked to a badge are never empty, the badge pointing to them until it's unlinked
+    pub fn is_empty(&self) -> bool {
+        self.amount_staked == 0
+            && self.total_amount_vested == 0
+            && self.pending_rewards == 0
+            && self.pending_vault_rewards == 0
+            && self.badge_mint == Pubkey::default()
+    }
+}
+
+/// Keeps a badge linked to a single stake position of the market at a time
+#[account]
+#[derive(InitSpace)]
+pub struct StakingBadge {
+    pub market: Pubkey,
+    pub badge_mint: Pubkey,
+    /// Stake position the badge was last linked to
+    pub stake_position: Pubkey,
 }
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+pub const STAKING_CHECKPOINTS_PDA_SEED: &str = "staking_checkpoints";
+pub const STAKING_CHECKPOINTS_LENGTH: usize = 64;
+
+/// Staking totals of a market at the first checkpoint of an epoch
+#[zero_copy]
+#[derive(Debug, InitSpace)]
+pub struct StakingCheckpoint {
+    pub acc_reward_amount_per_share: u128,
+    pub epoch: u64,
+    pub timestamp: i64,
+    pub amount_staked: u64,
+    /// Staked and vested amounts plus the lockup bonuses
+    pub total_shares: u64,
+}
+
+/// Ring buffer of the per-epoch staking checkpoints of a market, filled by `checkpoint_staking_epoch`.
+/// The oldest checkpoints are overwritten once `STAKING_CHECKPOINTS_LENGTH` epochs have been recorded.
+#[account(zero_copy)]
+#[derive(Debug, InitSpace)]
+pub struct StakingCheckpoints {
+    pub market: Pubkey,
+    /// Number of checkpoints recorded since creation, the next one is written at `count % STAKING_CHECKPOINTS_LENGTH`
+    pub count: u64,
+    _space: [u8; 8],
+    pub checkpoints: [StakingCheckpoint; STAKING_CHECKPOINTS_LENGTH],
+}
+
+impl StakingCheckpoints {
+    pub fn initialize(&mut self, market: Pubkey) -> Result<()> {
+        self.market = market;
+
+        Ok(())
+    }
+
+    pub fn record(&mut self, checkpoint: StakingCheckpoint) {
+        self.checkpoints[(self.count % STAKING_CHECKPOINTS_LENGTH as u64) as usize] = checkpoint;
+
+        self.count += 1;
+    }
+
+    /// Checkpoint recorded `index` epochs ago, 0 being the latest
+    pub fn get_checkpoint(&self, index: u64) -> Option<&StakingCheckpoint> {
+        if index >= self.count.min(STAKING_CHECKPOINTS_LENGTH as u64) {
+            return None;
+        }
+
+        let position = (self.count - 1 - index) % STAKING_CHECKPOINTS_LENGTH as u64;
+
+        Some(&self.checkpoints[position as usize])
+    }
+
+    /// Latest checkpoint recorded at or before `epoch`, if still in the buffer
+    pub fn get_checkpoint_at(&self, epoch: u64) -> Option<&StakingCheckpoint> {
+        (0..self.count.min(STAKING_CHECKPOINTS_LENGTH as u64))
+            .filter_map(|index| self.get_checkpoint(index))
+            .find(|checkpoint| checkpoint.epoch <= epoch)
+    }
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::TokenMillError, manager::swap_manager::SwapType};
+
+pub const SWAP_DELEGATE_PDA_SEED: &str = "swap_delegate";
+
+/// Session key authorized by a wallet to swap on a market on its behalf, until `expiry` and within its allowances.
+/// The wallet approves this PDA as delegate of its token accounts, and the swapped tokens always go back to the wallet.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct SwapDelegate {
+    pub bump: u8,
+    pub market: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    /// Quote amount the delegate can still spend on buys
+    pub quote_allowance: u64,
+    /// Base amount the delegate can still sell
+    pub base_allowance: u64,
+    pub expiry: i64,
+}
+
+impl SwapDelegate {
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        market: Pubkey,
+        owner: Pubkey,
+        delegate: Pubkey,
+        quote_allowance: u64,
+        base_allowance: u64,
+        expiry: i64,
+        current_time: i64,
+    ) -> Result<()> {
+        require!(
+            expiry > current_time && delegate != owner,
+            TokenMillError::InvalidSwapDelegate
+        );
+
+        self.bump = bump;
+        self.market = market;
+        self.owner = owner;
+        self.delegate = delegate;
+        self.quote_allowance = quote_allowance;
+        self.base_allowance = base_allowance;
+        self.expiry = expiry;
+
+        Ok(())
+    }
+
+    /// Deducts the input amount of a swap from the matching allowance
+    pub fn spend(
+        &mut self,
+        swap_type: SwapType,
+        base_amount: u64,
+        quote_amount: u64,
+        current_time: i64,
+    ) -> Result<()> {
+        require!(
+            current_time < self.expiry,
+            TokenMillError::SwapDelegateExpired
+        );
+
+        let (allowance, amount) = match swap_type {
+            SwapType::Buy => (&mut self.quote_allowance, quote_amount),
+            SwapType::Sell => (&mut self.base_allowance, base_amount),
+        };
+
+        *allowance = allowance
+            .checked_sub(amount)
+            .ok_or(TokenMillError::SwapDelegateAllowanceExceeded)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spend() {
+        let mut swap_delegate = SwapDelegate {
+            bump: 0,
+            market: Pubkey::default(),
+            owner: Pubkey::default(),
+            delegate: Pubkey::default(),
+            quote_allowance: 100,
+            base_allowance: 50,
+            expiry: 10,
+        };
+
+        swap_delegate.spend(SwapType::Buy, 1_000, 60, 0).unwrap();
+        swap_delegate.spend(SwapType::Sell, 50, 1_000, 9).unwrap();
+
+        assert_eq!(swap_delegate.quote_allowance, 40);
+        assert_eq!(swap_delegate.base_allowance, 0);
+
+        assert!(swap_delegate.spend(SwapType::Buy, 0, 41, 0).is_err());
+        assert!(swap_delegate.spend(SwapType::Buy, 0, 40, 10).is_err());
+    }
+}
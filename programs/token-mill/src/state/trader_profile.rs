@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+
+use crate::{constant::SCALE, manager::swap_manager::SwapType};
+
+pub const TRADER_PROFILE_PDA_SEED: &str = "trader_profile";
+
+/// Opt-in account tracking the cost basis of a trader's position on a market.
+/// Only swaps routed with the profile are accounted for.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct TraderProfile {
+    pub bump: u8,
+    pub market: Pubkey,
+    pub user: Pubkey,
+    /// Base amount bought through tracked swaps and not sold yet
+    pub base_amount: u64,
+    /// Quote amount paid for `base_amount`, fees included and rebates and staker discounts deducted
+    pub cost_basis: u64,
+    pub realized_pnl: i64,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub struct TraderPnl {
+    pub base_amount: u64,
+    pub cost_basis: u64,
+    pub average_price: u128,
+    pub realized_pnl: i64,
+    pub unrealized_pnl: i64,
+}
+
+impl TraderProfile {
+    pub fn initialize(&mut self, bump: u8, market: Pubkey, user: Pubkey) -> Result<()> {
+        self.bump = bump;
+        self.market = market;
+        self.user = user;
+
+        Ok(())
+    }
+
+    pub fn record_swap(
+        &mut self,
+        swap_type: SwapType,
+        base_amount: u64,
+        quote_amount: u64,
+    ) -> Result<()> {
+        match swap_type {
+            SwapType::Buy => {
+                self.base_amount += base_amount;
+                self.cost_basis += quote_amount;
+            }
+            SwapType::Sell => {
+                // Tokens acquired outside of tracked swaps have no known cost basis and are ignored
+                let tracked_amount = base_amount.min(self.base_amount);
+
+                if tracked_amount == 0 {
+                    return Ok(());
+                }
+
+                let cost_removed = u64::try_from(
+                    u128::from(self.cost_basis) * u128::from(tracked_amount)
+                        / u128::from(self.base_amount),
+                )?;
+                let proceeds = u64::try_from(
+                    u128::from(quote_amount) * u128::from(tracked_amount) / u128::from(base_amount),
+                )?;
+
+                self.base_amount -= tracked_amount;
+                self.cost_basis -= cost_removed;
+                self.realized_pnl += i64::try_from(proceeds)? - i64::try_from(cost_removed)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Weighted average price paid per base token unit, in quote token units scaled by `SCALE`
+    pub fn average_price(&self) -> u128 {
+        if self.base_amount == 0 {
+            return 0;
+        }
+
+        u128::from(self.cost_basis) * SCALE / u128::from(self.base_amount)
+    }
+
+    pub fn pnl(&self, current_value: u64) -> Result<TraderPnl> {
+        Ok(TraderPnl {
+            base_amount: self.base_amount,
+            cost_basis: self.cost_basis,
+            average_price: self.average_price(),
+            realized_pnl: self.realized_pnl,
+            unrealized_pnl: i64::try_from(current_value)? - i64::try_from(self.cost_basis)?,
+        })
+    }
+}
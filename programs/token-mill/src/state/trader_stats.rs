@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+
+pub const TRADER_STATS_PDA_SEED: &str = "trader_stats";
+pub const VOLUME_WINDOW_DAYS: usize = 30;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Opt-in account tracking the daily swap volume of a trader in a quote token over a rolling 30-day window.
+/// Only swaps routed with the account are accounted for, its volume unlocks the protocol fee rebates of the config.
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct TraderStats {
+    pub bump: u8,
+    pub config: Pubkey,
+    pub quote_token_mint: Pubkey,
+    pub user: Pubkey,
+    /// Day of the last recorded swap, counted from the unix epoch
+    pub last_day: i64,
+    /// Quote volume of the days of the window, indexed by day modulo `VOLUME_WINDOW_DAYS`
+    pub daily_volumes: [u64; VOLUME_WINDOW_DAYS],
+}
+
+impl TraderStats {
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        config: Pubkey,
+        quote_token_mint: Pubkey,
+        user: Pubkey,
+    ) -> Result<()> {
+        self.bump = bump;
+        self.config = config;
+        self.quote_token_mint = quote_token_mint;
+        self.user = user;
+
+        Ok(())
+    }
+
+    /// Quote volume of the 30 days ending at `current_time`, current day included
+    pub fn get_volume(&self, current_time: i64) -> u64 {
+        let day = current_time / SECONDS_PER_DAY;
+        let window = VOLUME_WINDOW_DAYS as i64;
+
+        // Buckets only hold the days of the window ending at the last recorded swap
+        let first_day = (day - window + 1).max(self.last_day - window + 1);
+
+        (first_day..=day.min(self.last_day)).fold(0, |volume: u64, day| {
+            volume.saturating_add(self.daily_volumes[day.rem_euclid(window) as usize])
+        })
+    }
+
+    pub fn record_swap(&mut self, current_time: i64, quote_amount: u64) {
+        let day = current_time / SECONDS_PER_DAY;
+        let window = VOLUME_WINDOW_DAYS as i64;
+
+        if day > self.last_day {
+            // Clears the buckets of the days that left the window
+            for elapsed_day in (self.last_day + 1).max(day - window + 1)..=day {
+                self.daily_volumes[elapsed_day.rem_euclid(window) as usize] = 0;
+            }
+
+            self.last_day = day;
+        }
+
+        let bucket = &mut self.daily_volumes[day.rem_euclid(window) as usize];
+        *bucket = bucket.saturating_add(quote_amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolling_volume() {
+        let mut trader_stats = TraderStats {
+            bump: 0,
+            config: Pubkey::default(),
+            quote_token_mint: Pubkey::default(),
+            user: Pubkey::default(),
+            last_day: 0,
+            daily_volumes: [0; VOLUME_WINDOW_DAYS],
+        };
+
+        let start = 1_000 * SECONDS_PER_DAY;
+
+        trader_stats.record_swap(start, 100);
+        trader_stats.record_swap(start + 10, 50);
+        trader_stats.record_swap(start + 10 * SECONDS_PER_DAY, 200);
+
+        assert_eq!(trader_stats.get_volume(start + 10 * SECONDS_PER_DAY), 350);
+
+        // The first day leaves the window after 30 days
+        assert_eq!(trader_stats.get_volume(start + 29 * SECONDS_PER_DAY), 350);
+        assert_eq!(trader_stats.get_volume(start + 30 * SECONDS_PER_DAY), 200);
+
+        // Its bucket is reused once a swap is recorded 30 days later
+        trader_stats.record_swap(start + 30 * SECONDS_PER_DAY, 1);
+
+        assert_eq!(trader_stats.get_volume(start + 30 * SECONDS_PER_DAY), 201);
+        assert_eq!(trader_stats.get_volume(start + 40 * SECONDS_PER_DAY), 1);
+        assert_eq!(trader_stats.get_volume(start + 100 * SECONDS_PER_DAY), 0);
+
+        trader_stats.record_swap(start + 100 * SECONDS_PER_DAY, 5);
+
+        assert_eq!(trader_stats.get_volume(start + 100 * SECONDS_PER_DAY), 5);
+    }
+}
@@ -1,5 +1,39 @@
 use anchor_lang::prelude::*;
 
+use crate::{errors::TokenMillError, state::Market};
+
+pub const MAX_VESTING_MILESTONES: usize = 4;
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq)]
+pub enum VestingMilestoneType {
+    /// Circulating supply of the market, in base token units
+    #[default]
+    CirculatingSupply,
+    /// Net quote amount raised by the market, in quote token units
+    QuoteRaised,
+}
+
+/// Tranche of a vesting plan unlocked once the market reaches `threshold`, instead of vesting over time.
+/// Tranches with an amount of 0 are unused
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq)]
+pub struct VestingMilestone {
+    pub milestone_type: VestingMilestoneType,
+    pub threshold: u64,
+    pub amount: u64,
+    pub reached: bool,
+}
+
+impl VestingMilestone {
+    fn is_reached(&self, market: &Market) -> bool {
+        match self.milestone_type {
+            VestingMilestoneType::CirculatingSupply => {
+                market.circulating_supply() >= self.threshold
+            }
+            VestingMilestoneType::QuoteRaised => market.quote_raised >= self.threshold,
+        }
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct VestingPlan {
@@ -9,9 +43,16 @@ pub struct VestingPlan {
     pub start: i64,
     pub cliff_duration: i64,
     pub vesting_duration: i64,
+    /// Wallet that funded the plan, the only one able to revoke it
+    pub funder: Pubkey,
+    /// Whether the funder can take back the tokens that haven't vested yet
+    pub revocable: bool,
+    /// Part of `amount_vested` unlocked by market milestones, the rest vesting linearly after the cliff
+    pub milestones: [VestingMilestone; MAX_VESTING_MILESTONES],
 }
 
 impl VestingPlan {
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize(
         &mut self,
         stake_position: Pubkey,
@@ -19,35 +60,304 @@ impl VestingPlan {
         amount_vested: u64,
         vesting_duration: i64,
         cliff_duration: i64,
+        funder: Pubkey,
+        revocable: bool,
+        milestones: [VestingMilestone; MAX_VESTING_MILESTONES],
+        current_time: i64,
     ) -> Result<()> {
+        require!(
+            start > 0 && vesting_duration > 0 && cliff_duration > 0,
+            TokenMillError::InvalidVestingDuration
+        );
+
+        require!(
+            vesting_duration > cliff_duration,
+            TokenMillError::InvalidVestingDuration
+        );
+
+        require!(
+            start + vesting_duration > current_time,
+            TokenMillError::InvalidVestingStartTime
+        );
+
         self.stake_position = stake_position;
         self.start = start;
         self.amount_vested = amount_vested;
         self.vesting_duration = vesting_duration;
         self.cliff_duration = cliff_duration;
+        self.funder = funder;
+        self.revocable = revocable;
+        self.milestones = milestones;
+
+        let mut milestone_amount: u64 = 0;
+
+        for milestone in self.milestones.iter_mut() {
+            require!(
+                milestone.amount == 0 || milestone.threshold > 0,
+                TokenMillError::InvalidVestingMilestones
+            );
+
+            milestone.reached = false;
+            milestone_amount = milestone_amount
+                .checked_add(milestone.amount)
+                .ok_or(TokenMillError::InvalidVestingMilestones)?;
+        }
+
+        require!(
+            milestone_amount <= amount_vested,
+            TokenMillError::InvalidVestingMilestones
+        );
 
         Ok(())
     }
 
-    pub fn release(&mut self, current_time: i64) -> Result<u64> {
+    pub fn has_milestones(&self) -> bool {
+        self.milestones.iter().any(|milestone| milestone.amount > 0)
+    }
+
+    /// Marks the milestones the market has reached, once the plan has started. A reached milestone stays reached
+    /// even if the market falls back below its threshold.
+    pub fn update_milestones(&mut self, market: &Market, current_time: i64) {
+        if current_time < self.start {
+            return;
+        }
+
+        for milestone in self.milestones.iter_mut() {
+            if milestone.amount > 0 && !milestone.reached && milestone.is_reached(market) {
+                milestone.reached = true;
+            }
+        }
+    }
+
+    fn milestone_amount(&self, reached_only: bool) -> u64 {
+        self.milestones
+            .iter()
+            .filter(|milestone| milestone.reached || !reached_only)
+            .map(|milestone| milestone.amount)
+            .sum()
+    }
+
+    /// Amount vested at `current_time`, released or not
+    pub fn vested_amount(&self, current_time: i64) -> u64 {
+        let reached_amount = self.milestone_amount(true);
+        let time_amount = self.amount_vested - self.milestone_amount(false);
         let elapsed_time = current_time - self.start;
 
         if elapsed_time < self.cliff_duration {
-            return Ok(0);
+            return reached_amount;
         }
 
         if elapsed_time >= self.vesting_duration {
-            let amount_to_release = self.amount_vested - self.amount_released;
-            self.amount_released += amount_to_release;
-
-            return Ok(amount_to_release);
+            return reached_amount + time_amount;
         }
 
-        let amount_free =
-            self.amount_vested * (elapsed_time as u64) / (self.vesting_duration as u64);
-        let amount_to_release = amount_free - self.amount_released;
+        reached_amount + time_amount * (elapsed_time as u64) / (self.vesting_duration as u64)
+    }
+
+    pub fn release(&mut self, market: &Market, current_time: i64) -> Result<u64> {
+        self.update_milestones(market, current_time);
+
+        let amount_to_release = self.vested_amount(current_time) - self.amount_released;
         self.amount_released += amount_to_release;
 
         Ok(amount_to_release)
     }
+
+    /// Stops the vesting at `current_time`, the amount vested so far staying releasable right away.
+    /// Returns the amount that won't vest anymore.
+    pub fn revoke(&mut self, market: &Market, current_time: i64) -> Result<u64> {
+        require!(self.revocable, TokenMillError::VestingPlanNotRevocable);
+
+        self.update_milestones(market, current_time);
+
+        let amount_vested = self.vested_amount(current_time);
+        let amount_revoked = self.amount_vested - amount_vested;
+
+        for milestone in self.milestones.iter_mut() {
+            if !milestone.reached {
+                milestone.amount = 0;
+            }
+        }
+
+        self.amount_vested = amount_vested;
+        self.vesting_duration = self.vesting_duration.min(current_time - self.start);
+        self.revocable = false;
+
+        Ok(amount_revoked)
+    }
+
+    pub fn is_fully_released(&self) -> bool {
+        self.amount_released == self.amount_vested
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::Zeroable;
+
+    use super::*;
+
+    fn vesting_plan(revocable: bool) -> VestingPlan {
+        VestingPlan {
+            stake_position: Pubkey::default(),
+            amount_vested: 1_000,
+            amount_released: 0,
+            start: 100,
+            cliff_duration: 10,
+            vesting_duration: 100,
+            funder: Pubkey::default(),
+            revocable,
+            milestones: [VestingMilestone::default(); MAX_VESTING_MILESTONES],
+        }
+    }
+
+    fn milestone_vesting_plan(revocable: bool) -> VestingPlan {
+        let mut plan = vesting_plan(revocable);
+
+        plan.milestones[0] = VestingMilestone {
+            milestone_type: VestingMilestoneType::CirculatingSupply,
+            threshold: 500,
+            amount: 200,
+            reached: false,
+        };
+        plan.milestones[1] = VestingMilestone {
+            milestone_type: VestingMilestoneType::QuoteRaised,
+            threshold: 10_000,
+            amount: 300,
+            reached: false,
+        };
+
+        plan
+    }
+
+    #[test]
+    fn revoke() {
+        let market = Market::zeroed();
+        let mut plan = vesting_plan(true);
+
+        assert_eq!(plan.release(&market, 150).unwrap(), 500);
+        assert_eq!(plan.revoke(&market, 160).unwrap(), 400);
+
+        assert_eq!(plan.amount_vested, 600);
+        assert_eq!(plan.release(&market, 160).unwrap(), 100);
+        assert_eq!(plan.release(&market, 1_000).unwrap(), 0);
+        assert!(plan.is_fully_released());
+
+        assert!(plan.revoke(&market, 170).is_err());
+    }
+
+    #[test]
+    fn revoke_before_cliff() {
+        let market = Market::zeroed();
+        let mut plan = vesting_plan(true);
+
+        assert_eq!(plan.revoke(&market, 105).unwrap(), 1_000);
+        assert_eq!(plan.release(&market, 1_000).unwrap(), 0);
+        assert!(plan.is_fully_released());
+    }
+
+    #[test]
+    fn revoke_irrevocable() {
+        let market = Market::zeroed();
+        let mut plan = vesting_plan(false);
+
+        assert!(plan.revoke(&market, 150).is_err());
+    }
+
+    #[test]
+    fn release_milestones() {
+        let mut market = Market::zeroed();
+        let mut plan = milestone_vesting_plan(false);
+
+        // Only the time vested part is released while no milestone is reached
+        assert_eq!(plan.release(&market, 150).unwrap(), 250);
+
+        market.total_supply = 500;
+        assert_eq!(plan.release(&market, 150).unwrap(), 200);
+
+        market.quote_raised = 10_000;
+        assert_eq!(plan.release(&market, 150).unwrap(), 300);
+
+        // Milestones stay reached
+        market.total_supply = 0;
+        market.quote_raised = 0;
+        assert_eq!(plan.release(&market, 1_000).unwrap(), 250);
+        assert!(plan.is_fully_released());
+    }
+
+    #[test]
+    fn release_milestones_before_start() {
+        let mut market = Market::zeroed();
+        let mut plan = milestone_vesting_plan(false);
+
+        market.total_supply = 500;
+        assert_eq!(plan.release(&market, 50).unwrap(), 0);
+        assert!(!plan.milestones[0].reached);
+
+        market.total_supply = 0;
+        assert_eq!(plan.release(&market, 105).unwrap(), 0);
+    }
+
+    #[test]
+    fn revoke_milestones() {
+        let mut market = Market::zeroed();
+        let mut plan = milestone_vesting_plan(true);
+
+        market.total_supply = 500;
+
+        assert_eq!(plan.revoke(&market, 150).unwrap(), 550);
+        assert_eq!(plan.amount_vested, 450);
+
+        market.quote_raised = 10_000;
+        assert_eq!(plan.release(&market, 1_000).unwrap(), 450);
+        assert!(plan.is_fully_released());
+    }
+
+    #[test]
+    fn initialize_milestones() {
+        let mut plan = vesting_plan(false);
+        let mut milestones = milestone_vesting_plan(false).milestones;
+
+        assert!(plan
+            .initialize(
+                Pubkey::default(),
+                100,
+                500,
+                100,
+                10,
+                Pubkey::default(),
+                false,
+                milestones,
+                0
+            )
+            .is_ok());
+        assert!(plan
+            .initialize(
+                Pubkey::default(),
+                100,
+                499,
+                100,
+                10,
+                Pubkey::default(),
+                false,
+                milestones,
+                0
+            )
+            .is_err());
+
+        milestones[0].threshold = 0;
+        assert!(plan
+            .initialize(
+                Pubkey::default(),
+                100,
+                500,
+                100,
+                10,
+                Pubkey::default(),
+                false,
+                milestones,
+                0
+            )
+            .is_err());
+    }
 }
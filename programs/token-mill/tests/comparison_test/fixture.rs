@@ -26,7 +26,8 @@ impl Fixture {
     }
 
     pub fn create_markets(&mut self, total_supply: u64, price_curve: Curve) {
-        self.evm_engine.create_market(total_supply, price_curve);
+        self.evm_engine
+            .create_market(total_supply, price_curve.clone());
         self.svm_engine.create_market(total_supply, price_curve);
     }
 
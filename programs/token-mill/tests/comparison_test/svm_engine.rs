@@ -41,8 +41,6 @@ impl SvmEngine {
 
         self.testing_env.svm.change_payer("alice");
 
-        let quote_token_mint = self.testing_env.quote_token_mint.unwrap();
-
         let mut create_market_action = CreateMarketAction::new(&self.testing_env);
         create_market_action.total_supply = total_supply;
 
@@ -54,23 +52,14 @@ impl SvmEngine {
             .execute_actions(&[create_market_action.no_badge(), &set_prices_action])
             .unwrap();
 
-        // Create ATAs
-        for actor in ACTORS {
+        // Create ATAs, the creator's one is created with the market
+        for actor in ACTORS.iter().filter(|actor| **actor != "alice") {
             self.testing_env
                 .svm
                 .create_ata(&make_address(actor), &base_token_mint, TokenType::Token2022)
                 .unwrap();
         }
 
-        self.testing_env
-            .svm
-            .create_ata(
-                &create_market_action.market,
-                &quote_token_mint,
-                self.testing_env.quote_token_type,
-            )
-            .unwrap();
-
         self.testing_env.market = create_market_action.market;
         self.testing_env.base_token_mint = Some(base_token_mint);
 
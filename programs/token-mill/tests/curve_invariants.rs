@@ -0,0 +1,363 @@
+//! Property tests of the curve math invariants, over random curves, supplies and quote token decimals.
+//!
+//! The market can never be drained by trading against it: buying then selling back never pays off,
+//! splitting a swap never pays off, and the exact input and exact output quotes of a swap agree within rounding.
+//! Run with `PROPTEST_CASES=<n>` to explore more cases.
+
+use anchor_lang::prelude::Clock;
+use bytemuck::Zeroable;
+use proptest::prelude::*;
+use token_mill::{
+    constant::*,
+    manager::swap_manager::{self, SwapAmountType, SwapType},
+    math::{normalization::denormalize_quote_amount, Rounding},
+    state::Market,
+};
+
+#[derive(Debug, Clone)]
+struct MarketParameters {
+    total_supply: u64,
+    quote_token_decimals: u8,
+    bid_prices: Vec<u64>,
+    ask_prices: Vec<u64>,
+}
+
+/// Increasing curves of every resolution, with prices anywhere from a few units to `MAX_PRICE` and any spread
+fn prices() -> impl Strategy<Value = (Vec<u64>, Vec<u64>)> {
+    (prop::sample::select(CURVE_RESOLUTIONS.to_vec()), 0u32..=18)
+        .prop_flat_map(|(prices_length, max_price_exponent)| {
+            let max_step = (10u64.pow(max_price_exponent) / prices_length as u64).max(1);
+
+            // Steps of at least 1, only the first bid price can be 0
+            (
+                prop::collection::vec(1..=max_step, prices_length - 1),
+                0..=max_step,
+                0..MAX_BPS,
+            )
+        })
+        .prop_map(|(steps, first_price, spread_bps)| {
+            let ask_prices = std::iter::once(first_price)
+                .chain(steps.iter().scan(first_price, |price, step| {
+                    *price += step;
+                    Some(*price)
+                }))
+                .collect::<Vec<_>>();
+
+            let bid_prices = ask_prices
+                .iter()
+                .enumerate()
+                .map(|(i, price)| {
+                    let bid_price = (u128::from(*price) * u128::from(MAX_BPS - spread_bps)
+                        / u128::from(MAX_BPS)) as u64;
+
+                    if i > 0 {
+                        bid_price.max(1)
+                    } else {
+                        bid_price
+                    }
+                })
+                .collect();
+
+            (bid_prices, ask_prices)
+        })
+}
+
+/// Total supplies from the smallest to the largest allowed, splitting evenly in intervals at every resolution
+fn total_supply() -> impl Strategy<Value = u64> {
+    (6u32..=12)
+        .prop_flat_map(|exponent| 10u64.pow(exponent)..=10u64.pow(exponent + 1))
+        .prop_map(|unit| unit * 100)
+}
+
+fn market_parameters() -> impl Strategy<Value = MarketParameters> {
+    (total_supply(), 0..=MAX_QUOTE_TOKEN_DECIMALS, prices()).prop_map(
+        |(total_supply, quote_token_decimals, (bid_prices, ask_prices))| MarketParameters {
+            total_supply,
+            quote_token_decimals,
+            bid_prices,
+            ask_prices,
+        },
+    )
+}
+
+/// Market with `circulating_supply` already bought, `None` if buying the whole curve would overflow the quote amounts
+fn create_market(parameters: &MarketParameters, circulating_supply: u64) -> Option<Market> {
+    let mut market = Market::zeroed();
+
+    market
+        .initialize(
+            0,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            parameters.quote_token_decimals,
+            parameters.total_supply,
+            0,
+            0,
+        )
+        .unwrap();
+
+    market
+        .check_and_set_prices(&parameters.bid_prices, &parameters.ask_prices, 0)
+        .unwrap();
+
+    // Every quote amount stays far from `u64::MAX`, so `quote_raised` can't overflow
+    let (_, full_quote_amount) = market
+        .get_quote_amount_with_parameters(
+            0,
+            parameters.total_supply,
+            SwapAmountType::ExactOutput,
+            Rounding::Up,
+        )
+        .ok()?;
+
+    if full_quote_amount > u64::MAX / 4 {
+        return None;
+    }
+
+    if circulating_supply > 0 {
+        swap_manager::swap(
+            &mut market,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            circulating_supply,
+            &Clock::default(),
+        )
+        .unwrap();
+    }
+
+    Some(market)
+}
+
+/// `fraction` of `amount`, in millionths
+fn fraction_of(amount: u64, fraction: u64) -> u64 {
+    (u128::from(amount) * u128::from(fraction) / 1_000_000) as u64
+}
+
+#[test]
+fn buy_then_sell_never_profits() {
+    proptest!(|(
+        parameters in market_parameters(),
+        supply_fraction in 0..1_000_000u64,
+        amount_fraction in 1..=1_000_000u64,
+        buy_amount_type in prop_oneof![Just(SwapAmountType::ExactInput), Just(SwapAmountType::ExactOutput)],
+        interface_fee_bps in 0..=1_000u16
+    )| {
+        let market = create_market(&parameters, fraction_of(parameters.total_supply, supply_fraction));
+        prop_assume!(market.is_some());
+        let mut market = market.unwrap();
+
+        let base_amount = fraction_of(market.available_supply(), amount_fraction).max(1);
+
+        let amount = match buy_amount_type {
+            SwapAmountType::ExactOutput => base_amount,
+            SwapAmountType::ExactInput => {
+                market.get_quote_amount(base_amount, SwapAmountType::ExactOutput).unwrap().1
+            }
+        };
+
+        prop_assume!(amount > 0);
+
+        let quote_raised = market.quote_raised;
+
+        let (base_amount_bought, quote_amount_paid, swap_fee) = swap_manager::swap(
+            &mut market,
+            SwapType::Buy,
+            buy_amount_type,
+            amount,
+            &Clock::default(),
+        )
+        .unwrap();
+
+        prop_assert!(swap_fee <= quote_amount_paid);
+
+        let (base_amount_sold, quote_amount_received, _) = swap_manager::swap(
+            &mut market,
+            SwapType::Sell,
+            SwapAmountType::ExactInput,
+            base_amount_bought,
+            &Clock::default(),
+        )
+        .unwrap();
+
+        prop_assert_eq!(base_amount_sold, base_amount_bought);
+        prop_assert!(quote_amount_received <= quote_amount_paid);
+        prop_assert!(market.quote_raised >= quote_raised);
+
+        // Interface fees are charged on top of the buy and taken out of the sell
+        let buy_interface_fee =
+            swap_manager::get_interface_fee(quote_amount_paid, interface_fee_bps).unwrap();
+        let sell_interface_fee =
+            swap_manager::get_interface_fee(quote_amount_received, interface_fee_bps).unwrap();
+
+        prop_assert!(
+            quote_amount_received - sell_interface_fee <= quote_amount_paid + buy_interface_fee
+        );
+    });
+}
+
+#[test]
+fn split_swaps_never_pay_off() {
+    proptest!(|(
+        parameters in market_parameters(),
+        supply_fraction in 0..1_000_000u64,
+        first_fraction in 0..=1_000_000u64,
+        second_fraction in 0..=1_000_000u64
+    )| {
+        let market = create_market(&parameters, 0);
+        prop_assume!(market.is_some());
+        let market = market.unwrap();
+
+        let supply = fraction_of(parameters.total_supply, supply_fraction);
+        let first_amount = fraction_of(parameters.total_supply - supply, first_fraction);
+        let second_amount = fraction_of(parameters.total_supply - supply - first_amount, second_fraction);
+
+        for (swap_amount_type, rounding) in [
+            (SwapAmountType::ExactOutput, Rounding::Up),
+            (SwapAmountType::ExactInput, Rounding::Down),
+        ] {
+            let quote_amount = |supply: u64, base_amount: u64| {
+                market
+                    .get_quote_amount_with_parameters(supply, base_amount, swap_amount_type, rounding)
+                    .unwrap()
+                    .1
+            };
+
+            let split_quote_amount =
+                quote_amount(supply, first_amount) + quote_amount(supply + first_amount, second_amount);
+            let single_quote_amount = quote_amount(supply, first_amount + second_amount);
+
+            match swap_amount_type {
+                // Two buys cost at least as much as one, two sells yield at most as much as one
+                SwapAmountType::ExactOutput => prop_assert!(split_quote_amount >= single_quote_amount),
+                SwapAmountType::ExactInput => prop_assert!(split_quote_amount <= single_quote_amount),
+            }
+        }
+    });
+}
+
+/// Quote amount the exact input and exact output quotes of a swap can differ by: the value of two base token units
+/// at the highest price, base amounts being rounded to whole units, and two normalized quote units per interval
+fn rounding_tolerance(market: &Market) -> u64 {
+    let normalized_quote_amount = (2 * u128::from(market.max_ask_price()))
+        .div_ceil(u128::from(BASE_PRECISION))
+        + 2 * MAX_PRICES_LENGTH as u128
+        + 2;
+
+    denormalize_quote_amount(
+        normalized_quote_amount,
+        market.quote_token_decimals,
+        Rounding::Up,
+    )
+    .unwrap()
+        + 1
+}
+
+#[test]
+fn exact_input_matches_exact_output() {
+    proptest!(|(
+        parameters in market_parameters(),
+        supply_fraction in 0..1_000_000u64,
+        amount_fraction in 1..=1_000_000u64
+    )| {
+        let market = create_market(&parameters, fraction_of(parameters.total_supply, supply_fraction));
+        prop_assume!(market.is_some());
+        let market = market.unwrap();
+
+        let tolerance = rounding_tolerance(&market);
+
+        // Buying with exact input at the exact output cost of `base_amount`, the base amount bought costs what was paid
+        let base_amount = fraction_of(market.available_supply(), amount_fraction).max(1);
+        let (_, quote_amount) = market.get_quote_amount(base_amount, SwapAmountType::ExactOutput).unwrap();
+
+        let (base_amount_out, quote_amount_in) = market.get_base_amount_out(quote_amount).unwrap();
+        let (_, cost) = market.get_quote_amount(base_amount_out, SwapAmountType::ExactOutput).unwrap();
+
+        prop_assert!(quote_amount_in <= quote_amount);
+        prop_assert!(cost.abs_diff(quote_amount_in) <= tolerance);
+
+        // Selling with exact output for the exact input proceeds of `base_amount`, the base amount sold yields what
+        // was received
+        let base_amount = fraction_of(market.circulating_supply(), amount_fraction);
+        let (_, quote_amount) = market.get_quote_amount(base_amount, SwapAmountType::ExactInput).unwrap();
+
+        let (base_amount_in, quote_amount_out) = market.get_base_amount_in(quote_amount).unwrap();
+        let (_, proceeds) = market.get_quote_amount(base_amount_in, SwapAmountType::ExactInput).unwrap();
+
+        prop_assert!(base_amount_in <= market.circulating_supply());
+        prop_assert!(proceeds.abs_diff(quote_amount_out) <= tolerance);
+    });
+}
+
+#[test]
+fn quotes_are_monotone_in_size() {
+    proptest!(|(
+        parameters in market_parameters(),
+        supply_fraction in 0..1_000_000u64,
+        smaller_fraction in 0..=1_000_000u64,
+        larger_fraction in 0..=1_000_000u64
+    )| {
+        let market = create_market(&parameters, fraction_of(parameters.total_supply, supply_fraction));
+        prop_assume!(market.is_some());
+        let market = market.unwrap();
+
+        let (smaller_fraction, larger_fraction) =
+            (smaller_fraction.min(larger_fraction), smaller_fraction.max(larger_fraction));
+
+        // Base amounts, buys within the available supply and sells within the circulating supply
+        for (swap_amount_type, base_available) in [
+            (SwapAmountType::ExactOutput, market.available_supply()),
+            (SwapAmountType::ExactInput, market.circulating_supply()),
+        ] {
+            let (_, smaller_quote_amount) = market
+                .get_quote_amount(fraction_of(base_available, smaller_fraction), swap_amount_type)
+                .unwrap();
+            let (_, larger_quote_amount) = market
+                .get_quote_amount(fraction_of(base_available, larger_fraction), swap_amount_type)
+                .unwrap();
+
+            prop_assert!(smaller_quote_amount <= larger_quote_amount);
+        }
+
+        // Buying the next base tokens always costs at least as much as selling them back
+        let base_amount = fraction_of(market.available_supply(), larger_fraction);
+        let supply = market.curve_supply();
+
+        let (_, ask_quote_amount) = market
+            .get_quote_amount_with_parameters(supply, base_amount, SwapAmountType::ExactOutput, Rounding::Up)
+            .unwrap();
+        let (_, bid_quote_amount) = market
+            .get_quote_amount_with_parameters(supply, base_amount, SwapAmountType::ExactInput, Rounding::Down)
+            .unwrap();
+
+        prop_assert!(bid_quote_amount <= ask_quote_amount);
+
+        // Quote amounts, the base amounts swapped only grow with them
+        let (_, max_quote_amount) = market
+            .get_quote_amount(market.available_supply(), SwapAmountType::ExactOutput)
+            .unwrap();
+
+        let (smaller_base_amount, _) = market
+            .get_base_amount_out(fraction_of(max_quote_amount, smaller_fraction))
+            .unwrap();
+        let (larger_base_amount, _) = market
+            .get_base_amount_out(fraction_of(max_quote_amount, larger_fraction))
+            .unwrap();
+
+        prop_assert!(smaller_base_amount <= larger_base_amount);
+
+        let (_, max_quote_amount) = market
+            .get_quote_amount(market.circulating_supply(), SwapAmountType::ExactInput)
+            .unwrap();
+
+        let (smaller_base_amount, _) = market
+            .get_base_amount_in(fraction_of(max_quote_amount, smaller_fraction))
+            .unwrap();
+        let (larger_base_amount, _) = market
+            .get_base_amount_in(fraction_of(max_quote_amount, larger_fraction))
+            .unwrap();
+
+        prop_assert!(smaller_base_amount <= larger_base_amount);
+    });
+}
@@ -0,0 +1,281 @@
+//! Canonical quote vectors shared with the client SDKs.
+//!
+//! Every vector is computed with the on-chain swap math and checked against
+//! `tests/vectors/quote_vectors.json`, which the TS and Python SDK test suites consume.
+//! Integers are serialized as strings to stay exact in languages without native u64/u128.
+//!
+//! Run with `UPDATE_QUOTE_VECTORS=1` to regenerate the file after an intended change of the math.
+
+use std::{fs, path::PathBuf};
+
+use anchor_lang::prelude::Clock;
+use bytemuck::Zeroable;
+use joelana_test_utils::utils::token_mill::curve_generator::Curve;
+use serde_json::{json, Value};
+use token_mill::{
+    constant::*,
+    manager::swap_manager::{self, SwapAmountType, SwapType},
+    state::Market,
+};
+
+const VECTORS_VERSION: u64 = 1;
+const VECTORS_PATH: &str = "tests/vectors/quote_vectors.json";
+
+struct MarketParameters {
+    name: &'static str,
+    total_supply: u64,
+    quote_token_decimals: u8,
+    curve: Curve,
+}
+
+fn steep_curve() -> Curve {
+    let ask_prices = (0..PRICES_LENGTH as u64)
+        .map(|i| (i + 1).pow(3) * SCALE as u64 / 100)
+        .collect::<Vec<_>>();
+    let bid_prices = ask_prices.iter().map(|price| price * 95 / 100).collect();
+
+    Curve {
+        bid_prices,
+        ask_prices,
+    }
+}
+
+fn market_parameters() -> Vec<MarketParameters> {
+    vec![
+        MarketParameters {
+            name: "default_curve_6_decimals",
+            total_supply: 1_000_000_000_000,
+            quote_token_decimals: 6,
+            curve: Curve::default(),
+        },
+        MarketParameters {
+            name: "default_curve_9_decimals",
+            total_supply: 1_000_000_000_000,
+            quote_token_decimals: 9,
+            curve: Curve::default(),
+        },
+        MarketParameters {
+            name: "steep_curve_max_supply",
+            total_supply: MAX_TOTAL_SUPPLY,
+            quote_token_decimals: 9,
+            curve: steep_curve(),
+        },
+        MarketParameters {
+            name: "steep_curve_min_supply",
+            total_supply: BASE_PRECISION * INTERVAL_NUMBER,
+            quote_token_decimals: 6,
+            curve: steep_curve(),
+        },
+    ]
+}
+
+fn create_market(parameters: &MarketParameters, circulating_supply: u64) -> Market {
+    let mut market = Market::zeroed();
+
+    market
+        .initialize(
+            0,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            parameters.quote_token_decimals,
+            parameters.total_supply,
+            0,
+            0,
+        )
+        .unwrap();
+
+    market
+        .check_and_set_prices(
+            &parameters.curve.bid_prices,
+            &parameters.curve.ask_prices,
+            0,
+        )
+        .unwrap();
+
+    if circulating_supply > 0 {
+        swap_manager::swap(
+            &mut market,
+            SwapType::Buy,
+            SwapAmountType::ExactOutput,
+            circulating_supply,
+            &Clock::default(),
+        )
+        .unwrap();
+    }
+
+    market
+}
+
+/// Rounding applied to the computed side of the swap, always in favor of the market
+fn rounding(swap_amount_type: SwapAmountType) -> &'static str {
+    match swap_amount_type {
+        SwapAmountType::ExactInput => "down",
+        SwapAmountType::ExactOutput => "up",
+    }
+}
+
+fn swap_type_name(swap_type: SwapType) -> &'static str {
+    match swap_type {
+        SwapType::Buy => "buy",
+        SwapType::Sell => "sell",
+    }
+}
+
+fn swap_amount_type_name(swap_amount_type: SwapAmountType) -> &'static str {
+    match swap_amount_type {
+        SwapAmountType::ExactInput => "exact_input",
+        SwapAmountType::ExactOutput => "exact_output",
+    }
+}
+
+/// Amounts spanning dust, partial intervals, interval boundaries and the whole available range
+fn swap_amounts(
+    market: &Market,
+    swap_type: SwapType,
+    swap_amount_type: SwapAmountType,
+) -> Vec<u64> {
+    let base_available = match swap_type {
+        SwapType::Buy => market.base_reserve,
+        SwapType::Sell => market.circulating_supply(),
+    };
+
+    let base_amounts = [
+        1,
+        BASE_PRECISION,
+        market.total_supply / INTERVAL_NUMBER / 3,
+        market.total_supply / INTERVAL_NUMBER,
+        base_available / 2,
+        base_available,
+    ];
+
+    let mut amounts: Vec<u64> = match (swap_type, swap_amount_type) {
+        (SwapType::Buy, SwapAmountType::ExactOutput)
+        | (SwapType::Sell, SwapAmountType::ExactInput) => base_amounts
+            .iter()
+            .map(|amount| (*amount).min(base_available))
+            .collect(),
+        // Quote amounts are derived from the base amounts, plus one unit above the full range to cover partial fills
+        (SwapType::Buy, SwapAmountType::ExactInput) => base_amounts
+            .iter()
+            .map(|amount| {
+                market
+                    .get_quote_amount((*amount).min(base_available), SwapAmountType::ExactOutput)
+                    .unwrap()
+                    .1
+            })
+            .chain([1, u64::from(u32::MAX)])
+            .collect(),
+        (SwapType::Sell, SwapAmountType::ExactOutput) => base_amounts
+            .iter()
+            .map(|amount| {
+                market
+                    .get_quote_amount((*amount).min(base_available), SwapAmountType::ExactInput)
+                    .unwrap()
+                    .1
+            })
+            .chain([1, u64::from(u32::MAX)])
+            .collect(),
+    };
+
+    amounts.retain(|amount| *amount > 0);
+    amounts.sort_unstable();
+    amounts.dedup();
+
+    amounts
+}
+
+fn generate_vectors() -> Value {
+    let mut markets = Vec::new();
+    let mut vectors = Vec::new();
+
+    for parameters in market_parameters() {
+        markets.push(json!({
+            "name": parameters.name,
+            "total_supply": parameters.total_supply.to_string(),
+            "quote_token_decimals": parameters.quote_token_decimals,
+            "bid_prices": parameters.curve.bid_prices.iter().map(|price| price.to_string()).collect::<Vec<_>>(),
+            "ask_prices": parameters.curve.ask_prices.iter().map(|price| price.to_string()).collect::<Vec<_>>(),
+        }));
+
+        let interval_width = parameters.total_supply / INTERVAL_NUMBER;
+
+        let circulating_supplies = [
+            0,
+            interval_width / 2,
+            interval_width * 5,
+            parameters.total_supply - interval_width / 3,
+            parameters.total_supply,
+        ];
+
+        for circulating_supply in circulating_supplies {
+            let market = create_market(&parameters, circulating_supply);
+
+            for swap_type in [SwapType::Buy, SwapType::Sell] {
+                for swap_amount_type in [SwapAmountType::ExactInput, SwapAmountType::ExactOutput] {
+                    for amount in swap_amounts(&market, swap_type, swap_amount_type) {
+                        let mut market = create_market(&parameters, circulating_supply);
+
+                        let (base_amount, quote_amount, swap_fee) = swap_manager::swap(
+                            &mut market,
+                            swap_type,
+                            swap_amount_type,
+                            amount,
+                            &Clock::default(),
+                        )
+                        .unwrap();
+
+                        vectors.push(json!({
+                            "market": parameters.name,
+                            "circulating_supply": circulating_supply.to_string(),
+                            "swap_type": swap_type_name(swap_type),
+                            "swap_amount_type": swap_amount_type_name(swap_amount_type),
+                            "amount": amount.to_string(),
+                            "rounding": rounding(swap_amount_type),
+                            "expected": {
+                                "base_amount": base_amount.to_string(),
+                                "quote_amount": quote_amount.to_string(),
+                                "swap_fee": swap_fee.to_string(),
+                            },
+                        }));
+                    }
+                }
+            }
+        }
+    }
+
+    json!({
+        "version": VECTORS_VERSION,
+        "constants": {
+            "scale": SCALE.to_string(),
+            "base_precision": BASE_PRECISION.to_string(),
+            "interval_number": INTERVAL_NUMBER.to_string(),
+            "max_bps": MAX_BPS.to_string(),
+        },
+        "markets": markets,
+        "vectors": vectors,
+    })
+}
+
+#[test]
+fn quote_vectors() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(VECTORS_PATH);
+
+    let vectors = generate_vectors();
+    let serialized = serde_json::to_string_pretty(&vectors).unwrap() + "\n";
+
+    if std::env::var("UPDATE_QUOTE_VECTORS").is_ok() || !path.exists() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, serialized).unwrap();
+
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap();
+
+    assert!(
+        serialized == expected,
+        "Quote vectors changed, run with UPDATE_QUOTE_VECTORS=1 if the change is intended"
+    );
+}
@@ -146,6 +146,7 @@ console.log("User base token ATA created");
   const transaction = await program.methods
     .setMarketPrices(bidPrices, askPrices)
     .accountsPartial({
+      config,
       market,
       creator: wallet.publicKey,
     })
@@ -49,13 +49,19 @@ const userQuoteTokenAta = spl.getAssociatedTokenAddressSync(
 );
 
 const u64Max = new BN(2).pow(new BN(64)).sub(new BN(1));
+const swapOptions = {
+  interfaceFeeBps: 0,
+  deadline: null,
+  fillType: { fillOrKill: {} },
+  maxPriceImpactBps: null,
+};
 
 const swapActions = [];
-swapActions.push([{ buy: {} }, { exactOutput: {} }, new BN(100e6), u64Max]);
-swapActions.push([{ sell: {} }, { exactInput: {} }, new BN(50e6), new BN(0)]);
-swapActions.push([{ buy: {} }, { exactOutput: {} }, new BN(300e6), u64Max]);
-swapActions.push([{ buy: {} }, { exactOutput: {} }, new BN(432e6), u64Max]);
-swapActions.push([{ sell: {} }, { exactInput: {} }, new BN(100e6), new BN(0)]);
+swapActions.push([{ buy: {} }, { exactOutput: {} }, new BN(100e6), u64Max, swapOptions]);
+swapActions.push([{ sell: {} }, { exactInput: {} }, new BN(50e6), new BN(0), swapOptions]);
+swapActions.push([{ buy: {} }, { exactOutput: {} }, new BN(300e6), u64Max, swapOptions]);
+swapActions.push([{ buy: {} }, { exactOutput: {} }, new BN(432e6), u64Max, swapOptions]);
+swapActions.push([{ sell: {} }, { exactInput: {} }, new BN(100e6), new BN(0), swapOptions]);
 
 for (const action of swapActions) {
   const transaction = await program.methods
@@ -71,6 +77,7 @@ for (const action of swapActions) {
       userQuoteTokenAta,
       protocolQuoteTokenAta: userQuoteTokenAta, // Here protocol fee recipient is the user
       referralTokenAccount: program.programId,
+      interfaceFeeTokenAccount: program.programId,
       user: wallet.publicKey,
       baseTokenProgram: spl.TOKEN_2022_PROGRAM_ID,
       quoteTokenProgram: spl.TOKEN_PROGRAM_ID,
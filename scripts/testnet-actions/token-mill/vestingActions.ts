@@ -116,9 +116,15 @@ if (!stakePositionAccountInfo) {
 }
 
 const u64Max = new BN(2).pow(new BN(64)).sub(new BN(1));
+const swapOptions = {
+  interfaceFeeBps: 0,
+  deadline: null,
+  fillType: { fillOrKill: {} },
+  maxPriceImpactBps: null,
+};
 
 const swapActions = [];
-swapActions.push([{ buy: {} }, { exactOutput: {} }, new BN(100e6), u64Max]);
+swapActions.push([{ buy: {} }, { exactOutput: {} }, new BN(100e6), u64Max, swapOptions]);
 
 for (const action of swapActions) {
   const transaction = await program.methods